@@ -1,9 +1,10 @@
-use flow_canvas::input::{InputState, Key, ModifiersState, MouseButtons};
+use flow_canvas::input::{InputState, Key, ModifiersState, MouseButtons, TouchPoint};
 use flow_canvas::model::GraphState;
 use flow_canvas::render::DrawCommand;
+use flow_canvas::theme::{Theme, ThemePreset};
 use flow_canvas::{Canvas, CanvasConfig};
 use macroquad::prelude as mq;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::mpsc::{Receiver, Sender, channel};
 use std::thread;
 use uuid::Uuid;
 
@@ -106,6 +107,7 @@ async fn main() {
     // 3. Initialize UI State
     let config = CanvasConfig::default();
     let mut canvas = Canvas::new(config);
+    let mut theme = ThemePreset::default();
     let mut graph = GraphState::<NodeData>::default();
     let mut sdk_ready = false;
     let mut error_screen: Option<String> = None;
@@ -133,10 +135,14 @@ async fn main() {
         let input_port = graph.ports.insert_with_key(|key| flow_canvas::model::Port {
             id: key,
             node: node_id,
+            label: None,
+            data_type: None,
         });
         let output_port = graph.ports.insert_with_key(|key| flow_canvas::model::Port {
             id: key,
             node: node_id,
+            label: None,
+            data_type: None,
         });
 
         if let Some(node) = graph.nodes.get_mut(node_id) {
@@ -206,12 +212,45 @@ async fn main() {
                 if mq::is_key_pressed(mq::KeyCode::A) {
                     keys.push(Key::A);
                 }
+                // Navigation shortcuts (see CanvasConfig::keymap)
+                for (code, key) in [
+                    (mq::KeyCode::Escape, Key::Escape),
+                    (mq::KeyCode::Up, Key::ArrowUp),
+                    (mq::KeyCode::Down, Key::ArrowDown),
+                    (mq::KeyCode::Left, Key::ArrowLeft),
+                    (mq::KeyCode::Right, Key::ArrowRight),
+                    (mq::KeyCode::F, Key::F),
+                ] {
+                    if mq::is_key_pressed(code) {
+                        keys.push(key);
+                    }
+                }
                 keys
             },
             screen_size: glam::Vec2::new(screen_w, screen_h),
             event_consumed_by_content: false,
+            touches: mq::touches()
+                .into_iter()
+                .filter(|t| !matches!(t.phase, mq::TouchPhase::Ended | mq::TouchPhase::Cancelled))
+                .map(|t| TouchPoint {
+                    id: t.id,
+                    pos: glam::Vec2::new(t.position.x, t.position.y),
+                })
+                .collect(),
+            pan_delta: glam::Vec2::ZERO,
+            pinch_delta: 0.0,
         };
 
+        // Cycle through the built-in themes (T)
+        if mq::is_key_pressed(mq::KeyCode::T) {
+            theme = match theme {
+                ThemePreset::Dark => ThemePreset::Light,
+                ThemePreset::Light => ThemePreset::HighContrast,
+                ThemePreset::HighContrast => ThemePreset::Dark,
+            };
+            canvas.set_theme(&Theme::preset(theme));
+        }
+
         // Handle "Add Node" shortcut (A) manually for this playground
         if mq::is_key_pressed(mq::KeyCode::A) && !input.modifiers.ctrl && !input.modifiers.meta {
             // 0. Convert Mouse Screen -> World
@@ -257,10 +296,14 @@ async fn main() {
             let input_port = graph.ports.insert_with_key(|key| flow_canvas::model::Port {
                 id: key,
                 node: node_id,
+                label: None,
+                data_type: None,
             });
             let output_port = graph.ports.insert_with_key(|key| flow_canvas::model::Port {
                 id: key,
                 node: node_id,
+                label: None,
+                data_type: None,
             });
 
             // 3. Update Node with Ports
@@ -269,6 +312,7 @@ async fn main() {
                 node.outputs.push(output_port);
             }
 
+            graph.mark_changed();
             println!("[Playground] Added Node: {} at World {:?}", name, pos);
         }
 
@@ -292,6 +336,8 @@ async fn main() {
                             to,
                             style: flow_canvas::model::WireStyle::Cubic,
                             visual_style: None,
+                            waypoints: Vec::new(),
+                            label: None,
                         });
 
                         // 2. Send to Backend
@@ -300,6 +346,11 @@ async fn main() {
                         }
                     }
                 }
+                flow_canvas::interaction::LogicEvent::Disconnect { id }
+                    if graph.connections.remove(id).is_some() && sdk_ready =>
+                {
+                    let _ = to_backend.send(BackendMsg::Deploy(graph.clone()));
+                }
                 flow_canvas::interaction::LogicEvent::DeleteSelection => {
                     // Identify valid selected nodes (collect to avoid borrow checker)
                     let selected_ids: Vec<flow_canvas::model::NodeId> = graph
@@ -360,7 +411,8 @@ async fn main() {
         }
 
         // 7. Render
-        mq::clear_background(mq::DARKGRAY);
+        let bg = canvas.config.style.background_color;
+        mq::clear_background(mq::Color::new(bg.x, bg.y, bg.z, bg.w));
         for cmd in draw_list {
             match cmd {
                 DrawCommand::Rect {
@@ -440,7 +492,7 @@ async fn main() {
         // DEBUG: Draw Mouse Cursor alignment check
         mq::draw_circle(mx, my, 5.0, mq::RED);
         mq::draw_text(
-            &format!("Mouse: {:.1}, {:.1}", mx, my),
+            format!("Mouse: {:.1}, {:.1}", mx, my),
             mx + 10.0,
             my,
             20.0,
@@ -452,7 +504,7 @@ async fn main() {
             mq::draw_text("Connecting to Engine...", 10.0, 30.0, 20.0, mq::YELLOW);
         } else {
             mq::draw_text(
-                &format!("Nodes: {}", graph.nodes.len()),
+                format!("Nodes: {}", graph.nodes.len()),
                 10.0,
                 30.0,
                 20.0,
@@ -62,6 +62,7 @@ pub async fn get_graph(state: tauri::State<'_, AppState>) -> Result<Serializable
                 inputs: node.inputs.clone(),
                 outputs: node.outputs.clone(),
                 data: node.data.clone(),
+                collapsed: node.is_collapsed(),
             },
         );
     }
@@ -69,6 +70,11 @@ pub async fn get_graph(state: tauri::State<'_, AppState>) -> Result<Serializable
     let mut serializable_edges = HashMap::new();
     for (id, conn) in &graph.connections {
         let edge_id = format!("{:?}", id);
+        let data_type = graph.connection_data_type(id);
+        let color = match &conn.visual_style {
+            Some(style) => style.color,
+            None => state.canvas_style.edge_color_for(data_type),
+        };
 
         let start_pos = graph
             .find_port_position(conn.from)
@@ -77,29 +83,34 @@ pub async fn get_graph(state: tauri::State<'_, AppState>) -> Result<Serializable
             .find_port_position(conn.to)
             .unwrap_or(glam::Vec2::ZERO);
 
-        let obstacles: Vec<flow_canvas::math::Rect> = graph
-            .nodes
-            .iter()
-            .map(|(_, node)| flow_canvas::math::Rect::new(node.position, node.size))
-            .collect();
+        let obstacles: Vec<flow_canvas::math::Rect> =
+            graph.nodes.iter().map(|(_, node)| node.rect()).collect();
 
         let mut bezier_cp = None;
         let path = match conn.style {
-            WireStyle::Cubic => {
+            WireStyle::Cubic if conn.waypoints.is_empty() => {
                 let (cp1, cp2) = flow_canvas::math::calculate_bezier_points(start_pos, end_pos);
                 bezier_cp = Some(((cp1.x, cp1.y), (cp2.x, cp2.y)));
                 vec![(start_pos.x, start_pos.y), (end_pos.x, end_pos.y)]
             }
-            WireStyle::Linear => flow_canvas::math::calculate_linear_points(start_pos, end_pos)
-                .into_iter()
-                .map(|v| (v.x, v.y))
-                .collect(),
-            WireStyle::Orthogonal => {
-                flow_canvas::math::calculate_smart_orthogonal(start_pos, end_pos, &obstacles, 20.0)
+            // The frontend draws a single curve per edge, so a curved wire with waypoints
+            // is sent as the polyline through them
+            WireStyle::Cubic | WireStyle::Linear => {
+                flow_canvas::math::calculate_linear_path(start_pos, &conn.waypoints, end_pos)
                     .into_iter()
                     .map(|v| (v.x, v.y))
                     .collect()
             }
+            WireStyle::Orthogonal => flow_canvas::math::calculate_smart_orthogonal_through(
+                start_pos,
+                &conn.waypoints,
+                end_pos,
+                &obstacles,
+                20.0,
+            )
+            .into_iter()
+            .map(|v| (v.x, v.y))
+            .collect(),
         };
 
         serializable_edges.insert(
@@ -111,6 +122,9 @@ pub async fn get_graph(state: tauri::State<'_, AppState>) -> Result<Serializable
                 style: conn.style.clone(),
                 path,
                 bezier_control_points: bezier_cp,
+                label: conn.label.clone(),
+                data_type: data_type.map(str::to_string),
+                color: Some(css_color(color)),
             },
         );
     }
@@ -130,6 +144,23 @@ pub async fn bring_to_front(state: tauri::State<'_, AppState>, id: NodeId) -> Re
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_node_collapsed(
+    state: tauri::State<'_, AppState>,
+    id: NodeId,
+    collapsed: bool,
+) -> Result<(), String> {
+    let mut graph = state.graph.lock().await;
+    let mut history = state.history.lock().await;
+
+    if !graph.nodes.contains_key(id) {
+        return Err("Node not found".to_string());
+    }
+    history.commit(&graph);
+    graph.set_collapsed(id, collapsed);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn undo(state: tauri::State<'_, AppState>) -> Result<(), String> {
     let mut graph = state.graph.lock().await;
@@ -178,6 +209,27 @@ pub async fn set_connection_wire_style(
     }
 }
 
+#[tauri::command]
+pub async fn set_connection_label(
+    state: tauri::State<'_, AppState>,
+    id: String,
+    label: Option<String>,
+) -> Result<(), String> {
+    let mut graph = state.graph.lock().await;
+    let mut history = state.history.lock().await;
+
+    let conn_id = graph
+        .connections
+        .keys()
+        .find(|conn_id| format!("{:?}", conn_id) == id)
+        .ok_or_else(|| "Connection not found".to_string())?;
+
+    history.commit(&graph);
+    // An empty label clears it
+    graph.set_connection_label(conn_id, label.filter(|text| !text.trim().is_empty()));
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn set_all_connection_wire_styles(
     state: tauri::State<'_, AppState>,
@@ -247,11 +299,11 @@ pub async fn add_node(
     graph.draw_order.push(node_id);
 
     // Create ports from template
-    for _input in &template.inputs {
-        graph.add_port(node_id, true);
+    for input in &template.inputs {
+        graph.add_labeled_port(node_id, true, &input.name, Some(input.data_type.clone()));
     }
-    for _output in &template.outputs {
-        graph.add_port(node_id, false);
+    for output in &template.outputs {
+        graph.add_labeled_port(node_id, false, &output.name, Some(output.data_type.clone()));
     }
 
     Ok(format!("{:?}", node_id))
@@ -377,6 +429,9 @@ pub async fn copy_items(
                 style: conn.style.clone(),
                 path: vec![],
                 bezier_control_points: None,
+                label: conn.label.clone(),
+                data_type: None,
+                color: None,
             });
         }
     }
@@ -445,7 +500,8 @@ pub async fn paste_items(
     for edge in data.edges {
         if let (Some(&new_from), Some(&new_to)) = (port_map.get(&edge.from), port_map.get(&edge.to))
         {
-            graph.connect_with_style(new_from, new_to, edge.style);
+            let conn = graph.connect_with_style(new_from, new_to, edge.style);
+            graph.set_connection_label(conn, edge.label);
         }
     }
 
@@ -493,3 +549,14 @@ pub async fn reload_definitions(state: tauri::State<'_, AppState>) -> Result<(),
         .map_err(|e| e.to_string())?;
     rx.await.map_err(|e| e.to_string())?
 }
+
+/// `color` as a CSS `rgba()` string.
+fn css_color(color: glam::Vec4) -> String {
+    format!(
+        "rgba({}, {}, {}, {})",
+        (color.x * 255.0).round() as u8,
+        (color.y * 255.0).round() as u8,
+        (color.z * 255.0).round() as u8,
+        color.w
+    )
+}
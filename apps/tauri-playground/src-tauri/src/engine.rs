@@ -41,7 +41,7 @@ pub fn spawn_engine_thread(mut engine_rx: mpsc::Receiver<EngineCommand>) {
                     }
                     EngineCommand::Deploy(graph, tx) => {
                         if let Some(c) = client.as_mut() {
-                            let res = c.compile_and_deploy(&graph).await;
+                            let res = c.compile_and_deploy(&graph).await.map(|_| ());
                             if res.is_ok() {
                                 let _ = c.tick().await;
                             }
@@ -24,6 +24,7 @@ pub fn run() {
             history: Arc::new(Mutex::new(HistoryManager::default())),
             default_wire_style: Arc::new(Mutex::new(WireStyle::Cubic)),
             registry_cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            canvas_style: state::playground_style(),
         })
         .invoke_handler(tauri::generate_handler![
             commands::init_sdk,
@@ -32,8 +33,10 @@ pub fn run() {
             commands::add_node,
             commands::add_edge,
             commands::bring_to_front,
+            commands::set_node_collapsed,
             commands::set_connection_wire_style,
             commands::set_all_connection_wire_styles,
+            commands::set_connection_label,
             commands::update_node_position,
             commands::delete_items,
             commands::undo,
@@ -1,5 +1,6 @@
 use crate::engine::EngineCommand;
 use crate::types::PlaygroundNodeData;
+use flow_canvas::config::CanvasStyle;
 use flow_canvas::history::HistoryManager;
 use flow_canvas::model::{GraphState, WireStyle};
 use std::collections::HashMap;
@@ -12,4 +13,21 @@ pub struct AppState {
     pub history: Arc<Mutex<HistoryManager<PlaygroundNodeData>>>,
     pub default_wire_style: Arc<Mutex<WireStyle>>,
     pub registry_cache: Arc<Mutex<HashMap<String, crate::types::NodeTemplate>>>,
+    /// Colors wires by the type of data they carry.
+    pub canvas_style: CanvasStyle,
+}
+
+/// The canvas style with a wire color for each port type used by the node definitions.
+pub fn playground_style() -> CanvasStyle {
+    let mut style = CanvasStyle::default();
+    for (data_type, color) in [
+        ("flow", glam::Vec4::new(0.9, 0.9, 0.9, 1.0)),
+        ("any", glam::Vec4::new(0.6, 0.6, 0.6, 1.0)),
+        ("string", glam::Vec4::new(0.95, 0.6, 0.3, 1.0)),
+        ("number", glam::Vec4::new(0.35, 0.7, 1.0, 1.0)),
+        ("object", glam::Vec4::new(0.7, 0.5, 0.95, 1.0)),
+    ] {
+        style.edge_type_colors.insert(data_type.to_string(), color);
+    }
+    style
 }
@@ -25,6 +25,8 @@ pub struct SerializableNode {
     pub inputs: Vec<PortId>,
     pub outputs: Vec<PortId>,
     pub data: PlaygroundNodeData,
+    /// Folded down to its header bar; every wire meets it at one port per side.
+    pub collapsed: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -35,6 +37,14 @@ pub struct SerializableEdge {
     pub style: WireStyle,
     pub path: Vec<(f32, f32)>,
     pub bezier_control_points: Option<((f32, f32), (f32, f32))>,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Type of the data flowing over the wire, from its source port.
+    #[serde(default)]
+    pub data_type: Option<String>,
+    /// CSS color to stroke the wire with.
+    #[serde(default)]
+    pub color: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -7,6 +7,7 @@ export interface SerializableNode {
     inputs: string[];
     outputs: string[];
     data: { name: string; template_id: string; settings: Record<string, any> };
+    collapsed: boolean;
 }
 
 export type WireStyle = "Cubic" | "Linear" | "Orthogonal";
@@ -18,6 +19,9 @@ export interface SerializableEdge {
     style: WireStyle;
     path: [number, number][];
     bezier_control_points?: [[number, number], [number, number]];
+    label?: string | null;
+    data_type?: string | null;
+    color?: string | null;
 }
 
 export interface GraphState {
@@ -0,0 +1,137 @@
+//! Criterion benchmarks for the engine's hot paths: moving tickets between nodes, the
+//! BlobStore round trip every node does per ticket, and template rendering.
+//!
+//! Run with `cargo bench --bench core_paths`; `tests/performance_budget_test.rs` keeps coarse
+//! budgets for the same paths under `cargo test`.
+
+use bevy_ecs::prelude::*;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::templates::TemplateEngine;
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::templating::apply_template;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use serde_json::json;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const TICKETS: usize = 1_000;
+const FAN_OUT: usize = 4;
+
+fn node(i: usize) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: format!("Node {}", i),
+        node_type: "Transform".to_string(),
+        workflow_id: Some("bench".to_string()),
+        tenant_id: None,
+        version: None,
+    }
+}
+
+/// One source fanning out to `FAN_OUT` targets, with the topology already cached.
+fn transport_world() -> (World, Schedule, Entity, Vec<Entity>) {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(16);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(GraphTopology::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(BlobStore::default());
+
+    let source = world.spawn((node(0), Outbox::default())).id();
+    let targets: Vec<Entity> = (1..=FAN_OUT)
+        .map(|i| world.spawn((node(i), Inbox::default())).id())
+        .collect();
+    for target in &targets {
+        world.spawn(Edge {
+            source,
+            source_handle: None,
+            target: *target,
+            target_handle: None,
+        });
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((update_graph_topology, transport_worker).chain());
+    schedule.run(&mut world);
+    (world, schedule, source, targets)
+}
+
+fn bench_transport(c: &mut Criterion) {
+    let (mut world, mut schedule, source, targets) = transport_world();
+    let store = world.resource::<BlobStore>().clone();
+    let tickets: Vec<_> = (0..TICKETS)
+        .map(|i| {
+            store
+                .check_in(json!({ "n": i }).to_string().as_bytes())
+                .unwrap()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("transport_worker");
+    group.throughput(Throughput::Elements((TICKETS * FAN_OUT) as u64));
+    group.bench_function(BenchmarkId::new("fan_out", FAN_OUT), |b| {
+        b.iter_custom(|iterations| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iterations {
+                for target in &targets {
+                    world.get_mut::<Inbox>(*target).unwrap().queue.clear();
+                }
+                world.get_mut::<Outbox>(source).unwrap().queue =
+                    tickets.iter().map(|t| (None, t.clone())).collect();
+                let start = Instant::now();
+                schedule.run(&mut world);
+                total += start.elapsed();
+            }
+            total
+        })
+    });
+    group.finish();
+}
+
+fn bench_blob_store(c: &mut Criterion) {
+    let store = BlobStore::default();
+    let mut group = c.benchmark_group("blob_store");
+    for size in [1024, 64 * 1024] {
+        let payload = vec![b'x'; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("check_in", size),
+            &payload,
+            |b, payload| {
+                b.iter(|| {
+                    let ticket = store.check_in(payload).unwrap();
+                    let _ = store.release(&ticket);
+                })
+            },
+        );
+        let ticket = store.check_in(&payload).unwrap();
+        group.bench_with_input(BenchmarkId::new("claim", size), &ticket, |b, ticket| {
+            b.iter(|| black_box(store.claim(ticket).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_templates(c: &mut Criterion) {
+    let data = json!({
+        "user": { "name": "Ada", "email": "ada@example.com" },
+        "items": (0..20).map(|i| json!({ "sku": format!("SKU-{}", i), "qty": i })).collect::<Vec<_>>(),
+    });
+    let template = "Hi {{user.name}}, your order: {{#each items}}{{sku}} x{{qty}}, {{/each}}";
+    let engine = TemplateEngine::default();
+
+    let mut group = c.benchmark_group("templates");
+    group.bench_function("apply_template", |b| {
+        b.iter(|| black_box(apply_template(template, &data)))
+    });
+    group.bench_function("template_engine", |b| {
+        b.iter(|| black_box(engine.render(template, &data).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_transport, bench_blob_store, bench_templates);
+criterion_main!(benches);
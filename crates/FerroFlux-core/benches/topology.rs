@@ -0,0 +1,131 @@
+//! Criterion benchmarks for topology cache upkeep on large graphs.
+//!
+//! `cargo bench --bench topology` times `update_graph_topology` on a graph with more than
+//! 10k edges when nothing changed and when single edges come and go, next to a full rebuild
+//! of the cache, which is what every edge change used to cost.
+
+use bevy_ecs::prelude::*;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use ferroflux_core::components::{Edge, NodeConfig};
+use ferroflux_core::resources::GraphTopology;
+use ferroflux_core::systems::transport::update_graph_topology;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const NODES: usize = 2_000;
+const FAN_OUT: usize = 6;
+
+fn graph() -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    world.insert_resource(GraphTopology::default());
+    let nodes: Vec<Entity> = (0..NODES)
+        .map(|i| {
+            world
+                .spawn(NodeConfig {
+                    id: uuid::Uuid::new_v4(),
+                    name: format!("Node {}", i),
+                    node_type: "Transform".to_string(),
+                    workflow_id: Some(format!("wf-{}", i % 20)),
+                    tenant_id: None,
+                    version: None,
+                })
+                .id()
+        })
+        .collect();
+    for (i, source) in nodes.iter().enumerate() {
+        for hop in 1..=FAN_OUT {
+            world.spawn(edge(*source, nodes[(i + hop) % NODES]));
+        }
+    }
+    (world, nodes)
+}
+
+fn edge(source: Entity, target: Entity) -> Edge {
+    Edge {
+        source,
+        source_handle: Some("out".to_string()),
+        target,
+        target_handle: Some("in".to_string()),
+    }
+}
+
+/// The graph with its topology already cached.
+fn cached_graph() -> (World, Schedule, Vec<Entity>) {
+    let (mut world, nodes) = graph();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(update_graph_topology);
+    schedule.run(&mut world);
+    (world, schedule, nodes)
+}
+
+fn bench_topology(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_graph_topology");
+    group.sample_size(20);
+
+    group.bench_function("initial_build", |b| {
+        b.iter_batched(
+            || {
+                let (world, _) = graph();
+                let mut schedule = Schedule::default();
+                schedule.add_systems(update_graph_topology);
+                (world, schedule)
+            },
+            |(mut world, mut schedule)| schedule.run(&mut world),
+            BatchSize::LargeInput,
+        )
+    });
+
+    let (mut world, mut schedule, nodes) = cached_graph();
+    group.bench_function("idle_tick", |b| b.iter(|| schedule.run(&mut world)));
+
+    group.bench_function("add_edge", |b| {
+        b.iter_custom(|iterations| {
+            let mut total = Duration::ZERO;
+            for i in 0..iterations as usize {
+                let source = nodes[i % NODES];
+                let target = nodes[(i * 7 + 3) % NODES];
+                let start = Instant::now();
+                let added = world.spawn(edge(source, target)).id();
+                schedule.run(&mut world);
+                total += start.elapsed();
+                world.despawn(added);
+                schedule.run(&mut world);
+            }
+            total
+        })
+    });
+
+    group.bench_function("remove_edge", |b| {
+        b.iter_custom(|iterations| {
+            let mut total = Duration::ZERO;
+            for i in 0..iterations as usize {
+                let source = nodes[i % NODES];
+                let target = nodes[(i * 7 + 3) % NODES];
+                let added = world.spawn(edge(source, target)).id();
+                schedule.run(&mut world);
+                let start = Instant::now();
+                world.despawn(added);
+                schedule.run(&mut world);
+                total += start.elapsed();
+            }
+            total
+        })
+    });
+
+    let all: Vec<(Entity, Edge)> = world
+        .query::<(Entity, &Edge)>()
+        .iter(&world)
+        .map(|(entity, edge)| (entity, edge.clone()))
+        .collect();
+    let mut topology = GraphTopology::default();
+    group.bench_function("full_rebuild", |b| {
+        b.iter(|| {
+            topology.rebuild(all.iter().map(|(entity, edge)| (*entity, edge)));
+            black_box(&topology);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_topology);
+criterion_main!(benches);
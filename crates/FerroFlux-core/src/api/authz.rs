@@ -0,0 +1,189 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::api::{ApiCommand, ApiRequest, RunError};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::{AuthzError, Permission, Principal, Role};
+
+/// The tenant permission a command needs.
+///
+/// Returns `None` for engine-global and operator commands, which only system principals may
+/// issue.
+pub fn required_permission(cmd: &ApiCommand) -> Option<Permission> {
+    match cmd {
+        ApiCommand::LoadGraph(..)
+        | ApiCommand::TriggerNode(..)
+        | ApiCommand::TriggerWorkflow(..)
+        | ApiCommand::TriggerAndWait { .. }
+        | ApiCommand::PinNode(..)
+        | ApiCommand::PinNodeOutput(..)
+        | ApiCommand::UnpinNode(..)
+        | ApiCommand::SimulateNode { .. }
+        | ApiCommand::ShadowRun { .. }
+        | ApiCommand::ReplayRun { .. }
+        | ApiCommand::PauseWorkflow(..)
+        | ApiCommand::ResumeWorkflow(..)
+        | ApiCommand::CancelRun(..)
+        | ApiCommand::SavePromptTemplate { .. }
+        | ApiCommand::DeletePromptTemplate { .. } => Some(Permission::Deploy),
+        ApiCommand::ListTemplates { .. } | ApiCommand::ListNodeTypes { .. } => {
+            Some(Permission::Read)
+        }
+        ApiCommand::InstantiateTemplate { .. } | ApiCommand::InstallPlugin { .. } => {
+            Some(Permission::Deploy)
+        }
+        ApiCommand::ConnectionVerified(..)
+        | ApiCommand::VerifyConnection { .. }
+        | ApiCommand::RotateTenantKey(_) => Some(Permission::ManageConnections),
+        ApiCommand::ReloadDefinitions
+        | ApiCommand::UpdateSettings(_)
+        | ApiCommand::SetTenantLimits { .. } => None,
+    }
+}
+
+/// Minimum tenant role required to issue a command.
+pub fn required_role(cmd: &ApiCommand) -> Option<Role> {
+    required_permission(cmd).map(|permission| permission.required_role())
+}
+
+/// Checks that the principal is a member of the command's tenant with a sufficient role.
+pub fn authorize(principal: &Principal, cmd: &ApiCommand) -> Result<(), AuthzError> {
+    match (cmd.tenant(), required_permission(cmd)) {
+        (Some(tenant), Some(permission)) => principal.require(tenant, permission),
+        _ if principal.is_system => Ok(()),
+        _ => Err(AuthzError::SystemOnly {
+            user_id: principal.user_id.clone(),
+        }),
+    }
+}
+
+/// Logs a rejected request and broadcasts an `AccessDenied` audit event. A caller waiting on
+/// a `TriggerAndWait` reply receives the error as `RunError::Forbidden`; one waiting on any
+/// other reply (`ListTemplates`, `ListNodeTypes`, `InstantiateTemplate`, `InstallPlugin`,
+/// `VerifyConnection`) receives its message.
+pub fn audit_denial(world: &World, request: &ApiRequest, error: &AuthzError) {
+    let tenant_id = request.command.tenant().map(|t| t.0.clone());
+
+    tracing::warn!(
+        user_id = %request.principal.user_id,
+        tenant_id = ?tenant_id,
+        command = request.command.name(),
+        reason = %error,
+        "API command rejected by authorization"
+    );
+
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::AccessDenied {
+            user_id: request.principal.user_id.clone(),
+            tenant_id,
+            command: request.command.name().to_string(),
+            permission: required_permission(&request.command).map(|p| p.to_string()),
+            reason: error.to_string(),
+            error: error.clone(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+
+    match &request.command {
+        ApiCommand::TriggerAndWait { reply, .. } => {
+            reply.send(Err(RunError::Forbidden(error.clone())));
+        }
+        ApiCommand::ListTemplates { reply, .. } => reply.send(Err(error.to_string())),
+        ApiCommand::ListNodeTypes { reply, .. } => reply.send(Err(error.to_string())),
+        ApiCommand::InstantiateTemplate { reply, .. } => reply.send(Err(error.to_string())),
+        ApiCommand::InstallPlugin { reply, .. } => reply.send(Err(error.to_string())),
+        ApiCommand::VerifyConnection { reply, .. } => reply.send(Err(error.to_string())),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ferroflux_iam::TenantId;
+
+    fn trigger(tenant: &str) -> ApiCommand {
+        ApiCommand::TriggerWorkflow(
+            TenantId::from(tenant),
+            "wf".to_string(),
+            serde_json::json!({}),
+        )
+    }
+
+    #[test]
+    fn test_member_with_role_is_allowed() {
+        let principal =
+            Principal::new("alice").with_membership(TenantId::from("acme"), Role::Editor);
+        assert!(authorize(&principal, &trigger("acme")).is_ok());
+    }
+
+    #[test]
+    fn test_foreign_tenant_is_rejected() {
+        let principal =
+            Principal::new("alice").with_membership(TenantId::from("acme"), Role::Owner);
+        assert!(matches!(
+            authorize(&principal, &trigger("globex")),
+            Err(AuthzError::NotAMember { .. })
+        ));
+    }
+
+    #[test]
+    fn test_viewer_cannot_trigger() {
+        let principal = Principal::new("bob").with_membership(TenantId::from("acme"), Role::Viewer);
+        assert!(matches!(
+            authorize(&principal, &trigger("acme")),
+            Err(AuthzError::InsufficientRole { .. })
+        ));
+    }
+
+    #[test]
+    fn test_connections_need_admin() {
+        let acme = TenantId::from("acme");
+        let rotate = ApiCommand::RotateTenantKey(acme.clone());
+        let verified = ApiCommand::ConnectionVerified(acme.clone(), "crm".to_string());
+        let editor = Principal::new("carol").with_membership(acme.clone(), Role::Editor);
+        let admin = Principal::new("dave").with_membership(acme, Role::Admin);
+        for cmd in [&rotate, &verified] {
+            assert!(matches!(
+                authorize(&editor, cmd),
+                Err(AuthzError::InsufficientRole {
+                    required: Role::Admin,
+                    ..
+                })
+            ));
+            assert!(authorize(&admin, cmd).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_tenants_cannot_raise_their_own_limits() {
+        let acme = TenantId::from("acme");
+        let cmd = ApiCommand::SetTenantLimits {
+            tenant_id: acme.clone(),
+            limits: Default::default(),
+        };
+        let owner = Principal::new("erin").with_membership(acme, Role::Owner);
+        assert!(matches!(
+            authorize(&owner, &cmd),
+            Err(AuthzError::SystemOnly { .. })
+        ));
+        assert!(authorize(&Principal::system(), &cmd).is_ok());
+    }
+
+    #[test]
+    fn test_denial_serializes_with_a_kind() {
+        let error = authorize(&Principal::new("bob"), &trigger("acme")).unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "not_a_member", "user_id": "bob", "tenant": "acme" })
+        );
+    }
+
+    #[test]
+    fn test_global_commands_are_system_only() {
+        let principal =
+            Principal::new("alice").with_membership(TenantId::from("acme"), Role::Owner);
+        assert!(authorize(&principal, &ApiCommand::ReloadDefinitions).is_err());
+        assert!(authorize(&Principal::system(), &ApiCommand::ReloadDefinitions).is_ok());
+        let update = ApiCommand::UpdateSettings(serde_json::json!({}));
+        assert!(authorize(&principal, &update).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::resources::redaction::SecretRedactor;
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
@@ -30,6 +31,15 @@ pub enum SystemEvent {
         /// The content or payload of the activity
         content: String,
     },
+    /// A piece of an agent's completion, sent as the provider streams it.
+    AgentToken {
+        /// The UUID of the agent node
+        node_id: Uuid,
+        /// Correlation ID of the run
+        trace_id: String,
+        /// Text appended to the completion
+        delta: String,
+    },
     /// Telemetry data for node execution performance and status.
     NodeTelemetry {
         /// correlation ID for the execution flow
@@ -74,18 +84,371 @@ pub enum SystemEvent {
     },
     /// Represents the movement of data between two nodes in the graph.
     EdgeTraversal {
+        /// Correlation ID of the ticket that moved
+        trace_id: String,
         /// The UUID of the upstream source node
         source_id: Uuid,
+        /// The output port the ticket left through
+        source_handle: Option<String>,
         /// The UUID of the downstream target node
         target_id: Uuid,
+        /// Payload size in bytes
+        bytes: u64,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// Audit record for an API command rejected by the authorization layer.
+    AccessDenied {
+        /// The user ID of the rejected principal
+        user_id: String,
+        /// The tenant named by the command, if any
+        tenant_id: Option<String>,
+        /// The command name (e.g., "TriggerNode")
+        command: String,
+        /// The permission the command needs (e.g., "deploy"); `None` for system-only commands
+        permission: Option<String>,
+        /// Human-readable rejection reason
+        reason: String,
+        /// The rejection in structured form
+        error: ferroflux_iam::AuthzError,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// Work refused (or a cron tick skipped) because a tenant reached one of its quotas.
+    QuotaExceeded {
+        /// The tenant whose limit was reached
+        tenant_id: String,
+        /// The limit that was reached
+        quota: crate::resources::quotas::Quota,
+        /// The configured limit
+        limit: u64,
+        /// Usage when the work was refused
+        used: u64,
+        /// The workflow the refused work belonged to, if known
+        workflow_id: Option<String>,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// Periodic engine health summary from the `EngineProfiler`.
+    EngineStats {
+        /// Schedule runs in the sampling window
+        ticks: u64,
+        /// Mean tick duration in milliseconds
+        avg_tick_ms: f64,
+        /// Longest tick in milliseconds
+        max_tick_ms: f64,
+        /// Fraction of the window spent running the schedule (0.0 - 1.0)
+        busy_ratio: f64,
+        /// Systems with the most total run time, slowest first
+        slowest_systems: Vec<SystemTiming>,
+        /// Live ECS entities
+        entity_count: u32,
+        /// Entities that are workflow nodes
+        node_count: u32,
+        /// Resident memory of the process, where the platform reports it
+        memory_bytes: Option<u64>,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A payload rejected by a Data Contract node, or an agent answer that failed its output
+    /// schema after every repair attempt.
+    ContractViolation {
+        /// Correlation ID
+        trace_id: String,
+        /// The UUID of the contract or agent node
+        node_id: Uuid,
+        /// The first violations found, in document order
+        violations: Vec<SchemaViolation>,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A node was pinned to a fixed output or unpinned.
+    NodePinChanged {
+        /// The tenant owning the node
+        tenant_id: String,
+        /// The UUID of the node
+        node_id: Uuid,
+        /// The pinned output's ticket, or `None` once unpinned
+        ticket_id: Option<Uuid>,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A connection kept failing authentication and was marked `error`.
+    ConnectionUnhealthy {
+        /// The tenant owning the connection
+        tenant_id: String,
+        /// The connection slug
+        slug: String,
+        /// Authentication failures in a row
+        auth_failures: u32,
+        /// Share of recent calls through the connection that failed (0.0 - 1.0)
+        error_rate: f64,
+        /// Workflows held until the connection is re-verified
+        paused_workflows: Vec<String>,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A bounded inbox filled up and its backpressure policy took over.
+    BackpressureEngaged {
+        /// The UUID of the node whose inbox is full
+        node_id: Uuid,
+        /// The policy applied: "block", "drop_oldest" or "spill"
+        policy: String,
+        /// Tickets waiting in the inbox
+        inbox_len: usize,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A run was cancelled and its in-flight tickets discarded.
+    RunCancelled {
+        /// The tenant that cancelled the run
+        tenant_id: String,
+        /// Correlation ID of the cancelled run
+        trace_id: String,
+        /// Tickets removed from inboxes and outboxes
+        purged: usize,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A shadow run was started; its node telemetry carries `trace_id`.
+    ShadowRunStarted {
+        /// The tenant that started the run
+        tenant_id: String,
+        /// The workflow being dry-run
+        workflow_id: String,
+        /// Correlation ID of the run
+        trace_id: String,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A recorded run is being replayed under a new trace id.
+    ReplayStarted {
+        /// The tenant that started the replay
+        tenant_id: String,
+        /// Correlation ID of the replay
+        trace_id: String,
+        /// Correlation ID of the recorded run
+        replay_of: String,
+        /// Recorded results loaded for the replay
+        recorded: usize,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// Outbound HTTP calls to a host kept failing; calls to it fail fast for a while.
+    HostCircuitOpened {
+        /// The host name (e.g., "api.example.com")
+        host: String,
+        /// Failed calls in a row
+        consecutive_failures: u32,
+        /// How long calls fail fast before a probe is let through, in milliseconds
+        open_ms: u64,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// A tenant's connection encryption key was replaced.
+    TenantKeyRotated {
+        /// The tenant whose key was rotated
+        tenant_id: String,
+        /// Version of the new key
+        version: u32,
+        /// Unix timestamp in milliseconds
+        timestamp: i64,
+    },
+    /// Node types were added, changed or removed at runtime, so palettes should be refreshed.
+    RegistryChanged {
+        /// Node types registered for the first time
+        added: Vec<String>,
+        /// Node types whose definition was replaced
+        updated: Vec<String>,
+        /// Node types no longer available
+        removed: Vec<String>,
+        /// The tenant whose own node types changed, or `None` for engine-wide node types
+        #[serde(default)]
+        tenant_id: Option<String>,
         /// Unix timestamp in milliseconds
         timestamp: i64,
     },
 }
 
+impl SystemEvent {
+    /// Masks known secrets in the free text of an event: `Log` messages, `NodeTelemetry`
+    /// details, `NodeError` errors, agent activity and token deltas, and contract violation
+    /// messages. [`SystemEventBus::send`] applies this to every event.
+    ///
+    /// The match lists every variant, so a new event has to say whether it carries text that
+    /// could hold a secret.
+    pub fn redact(&mut self, redactor: &SecretRedactor) {
+        if redactor.is_empty() {
+            return;
+        }
+        match self {
+            SystemEvent::Log { message, .. } => *message = redactor.redact(message),
+            SystemEvent::NodeTelemetry { details, .. } => redactor.redact_value(details),
+            SystemEvent::NodeError { error, .. } => *error = redactor.redact(error),
+            SystemEvent::AgentActivity { content, .. } => *content = redactor.redact(content),
+            SystemEvent::AgentToken { delta, .. } => *delta = redactor.redact(delta),
+            SystemEvent::ContractViolation { violations, .. } => {
+                for violation in violations {
+                    violation.message = redactor.redact(&violation.message);
+                }
+            }
+            // Ids, counts and engine-generated text only.
+            SystemEvent::WorkflowUpdate { .. }
+            | SystemEvent::CheckpointCreated { .. }
+            | SystemEvent::EdgeTraversal { .. }
+            | SystemEvent::AccessDenied { .. }
+            | SystemEvent::QuotaExceeded { .. }
+            | SystemEvent::EngineStats { .. }
+            | SystemEvent::NodePinChanged { .. }
+            | SystemEvent::ConnectionUnhealthy { .. }
+            | SystemEvent::BackpressureEngaged { .. }
+            | SystemEvent::RunCancelled { .. }
+            | SystemEvent::ShadowRunStarted { .. }
+            | SystemEvent::ReplayStarted { .. }
+            | SystemEvent::HostCircuitOpened { .. }
+            | SystemEvent::TenantKeyRotated { .. }
+            | SystemEvent::RegistryChanged { .. } => {}
+        }
+    }
+
+    /// The variant name, which is also the `type` tag of the serialized event.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SystemEvent::Log { .. } => "Log",
+            SystemEvent::AgentActivity { .. } => "AgentActivity",
+            SystemEvent::AgentToken { .. } => "AgentToken",
+            SystemEvent::NodeTelemetry { .. } => "NodeTelemetry",
+            SystemEvent::WorkflowUpdate { .. } => "WorkflowUpdate",
+            SystemEvent::CheckpointCreated { .. } => "CheckpointCreated",
+            SystemEvent::NodeError { .. } => "NodeError",
+            SystemEvent::EdgeTraversal { .. } => "EdgeTraversal",
+            SystemEvent::AccessDenied { .. } => "AccessDenied",
+            SystemEvent::QuotaExceeded { .. } => "QuotaExceeded",
+            SystemEvent::EngineStats { .. } => "EngineStats",
+            SystemEvent::ContractViolation { .. } => "ContractViolation",
+            SystemEvent::NodePinChanged { .. } => "NodePinChanged",
+            SystemEvent::ConnectionUnhealthy { .. } => "ConnectionUnhealthy",
+            SystemEvent::BackpressureEngaged { .. } => "BackpressureEngaged",
+            SystemEvent::RunCancelled { .. } => "RunCancelled",
+            SystemEvent::ShadowRunStarted { .. } => "ShadowRunStarted",
+            SystemEvent::ReplayStarted { .. } => "ReplayStarted",
+            SystemEvent::HostCircuitOpened { .. } => "HostCircuitOpened",
+            SystemEvent::TenantKeyRotated { .. } => "TenantKeyRotated",
+            SystemEvent::RegistryChanged { .. } => "RegistryChanged",
+        }
+    }
+
+    /// The nodes the event is about: both ends of an `EdgeTraversal`, otherwise at most one.
+    pub fn node_ids(&self) -> Vec<Uuid> {
+        match self {
+            SystemEvent::AgentActivity { node_id, .. }
+            | SystemEvent::AgentToken { node_id, .. }
+            | SystemEvent::NodeTelemetry { node_id, .. }
+            | SystemEvent::CheckpointCreated { node_id, .. }
+            | SystemEvent::NodeError { node_id, .. }
+            | SystemEvent::ContractViolation { node_id, .. }
+            | SystemEvent::NodePinChanged { node_id, .. }
+            | SystemEvent::BackpressureEngaged { node_id, .. } => vec![*node_id],
+            SystemEvent::EdgeTraversal {
+                source_id,
+                target_id,
+                ..
+            } => vec![*source_id, *target_id],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn trace_id(&self) -> Option<&str> {
+        match self {
+            SystemEvent::Log { trace_id, .. }
+            | SystemEvent::AgentToken { trace_id, .. }
+            | SystemEvent::NodeTelemetry { trace_id, .. }
+            | SystemEvent::CheckpointCreated { trace_id, .. }
+            | SystemEvent::NodeError { trace_id, .. }
+            | SystemEvent::EdgeTraversal { trace_id, .. }
+            | SystemEvent::ContractViolation { trace_id, .. }
+            | SystemEvent::RunCancelled { trace_id, .. }
+            | SystemEvent::ShadowRunStarted { trace_id, .. }
+            | SystemEvent::ReplayStarted { trace_id, .. } => Some(trace_id),
+            _ => None,
+        }
+    }
+
+    /// The tenant named by the event. Node events don't carry one; their tenant is the one
+    /// owning the node.
+    pub fn tenant_id(&self) -> Option<&str> {
+        match self {
+            SystemEvent::QuotaExceeded { tenant_id, .. }
+            | SystemEvent::NodePinChanged { tenant_id, .. }
+            | SystemEvent::ConnectionUnhealthy { tenant_id, .. }
+            | SystemEvent::RunCancelled { tenant_id, .. }
+            | SystemEvent::ShadowRunStarted { tenant_id, .. }
+            | SystemEvent::ReplayStarted { tenant_id, .. }
+            | SystemEvent::TenantKeyRotated { tenant_id, .. } => Some(tenant_id),
+            SystemEvent::AccessDenied { tenant_id, .. }
+            | SystemEvent::RegistryChanged { tenant_id, .. } => tenant_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// One JSON Schema validation failure.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the offending value (e.g., "/items/1"); empty for the root
+    pub path: String,
+    /// JSON Pointer to the schema keyword that failed (e.g., "/properties/id/type")
+    pub schema_path: String,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// Run time of one system over an `EngineStats` window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SystemTiming {
+    /// System function name (e.g., "http_worker")
+    pub name: String,
+    /// Number of runs in the window
+    pub runs: u64,
+    /// Total run time in milliseconds
+    pub total_ms: f64,
+    /// Longest single run in milliseconds
+    pub max_ms: f64,
+}
+
 /// A Bevy Resource wrapper around a broadcast sender for system events.
 ///
 /// This serves as the central nervous system for real-time feedback, allowing systems
 /// to emit events that are propagated to the API layer (SSE) and other listeners.
+/// Every event is passed through the bus's [`SecretRedactor`] on [`send`](Self::send), so
+/// subscribers (SSE, remote clients, analytics) never see a known secret.
 #[derive(Resource, Clone)]
-pub struct SystemEventBus(pub broadcast::Sender<SystemEvent>);
+pub struct SystemEventBus {
+    sender: broadcast::Sender<SystemEvent>,
+    redactor: SecretRedactor,
+}
+
+impl SystemEventBus {
+    pub fn new(sender: broadcast::Sender<SystemEvent>) -> Self {
+        Self {
+            sender,
+            redactor: SecretRedactor::default(),
+        }
+    }
+
+    /// Masks the secrets known to `redactor` in every event sent from now on.
+    pub fn with_redactor(mut self, redactor: SecretRedactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Redacts `event` and broadcasts it, returning how many subscribers received it.
+    pub fn send(&self, mut event: SystemEvent) -> usize {
+        event.redact(&self.redactor);
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SystemEvent> {
+        self.sender.subscribe()
+    }
+}
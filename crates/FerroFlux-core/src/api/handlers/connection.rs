@@ -0,0 +1,175 @@
+use crate::api::Reply;
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::integrations::{ConnectionVerification, IntegrationRegistry};
+use crate::resources::connection_health::{ConnectionHealth, PausedWorkflows};
+use crate::resources::{GlobalHttpClient, TokioRuntime};
+use crate::secrets::{DatabaseSecretStore, SecretStore};
+use crate::store::database::PersistentStore;
+use anyhow::Context;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+
+pub fn handle_connection_verified(
+    world: &mut World,
+    tenant: TenantId,
+    slug: String,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %slug, "Processing ConnectionVerified command");
+
+    if let Some(health) = world.get_resource::<ConnectionHealth>() {
+        health.reset(&tenant, &slug);
+    }
+    if let Some(mut paused) = world.get_resource_mut::<PausedWorkflows>() {
+        for workflow_id in paused.release(&tenant, &slug) {
+            tracing::info!(%tenant, %workflow_id, "Workflow resumed");
+        }
+    }
+
+    mark_connection_status(world, tenant, slug, "active");
+    Ok(())
+}
+
+/// Calls the connection's `verify_endpoint` on the `TokioRuntime`, so the tick doesn't wait on
+/// the provider, and replies from there. The task records the connection's status; a verified
+/// connection is also reported to `ConnectionHealth`, whose worker resets it and resumes its
+/// workflows.
+pub fn handle_verify_connection(
+    world: &mut World,
+    tenant: TenantId,
+    slug: String,
+    reply: Reply<Result<ConnectionVerification, String>>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %slug, "Processing VerifyConnection command");
+
+    let verifier = match Verifier::from_world(world) {
+        Ok(verifier) => verifier,
+        Err(e) => {
+            reply.send(Err(e.to_string()));
+            return Err(e);
+        }
+    };
+    let runtime = verifier.runtime.clone();
+    let health = world.get_resource::<ConnectionHealth>().cloned();
+    runtime.0.spawn(async move {
+        match verifier.verify(&tenant, &slug).await {
+            Ok(outcome) => {
+                let status = if outcome.verified {
+                    if let Some(health) = health {
+                        health.report_verified(&tenant, &slug);
+                    }
+                    "active"
+                } else {
+                    tracing::warn!(%tenant, %slug, error = ?outcome.error, "Connection failed verification");
+                    "error"
+                };
+                if let Err(e) = verifier.store.mark_connection_status(&tenant, &slug, status).await {
+                    tracing::error!(%slug, error = %e, "Failed to mark connection status");
+                }
+                reply.send(Ok(outcome));
+            }
+            Err(e) => {
+                tracing::error!(%tenant, %slug, error = %e, "Connection verification failed");
+                reply.send(Err(e.to_string()));
+            }
+        }
+    });
+    Ok(())
+}
+
+/// What a verification task needs, taken from the world.
+struct Verifier {
+    store: PersistentStore,
+    secrets: DatabaseSecretStore,
+    runtime: TokioRuntime,
+    registry: IntegrationRegistry,
+    client: reqwest::Client,
+}
+
+impl Verifier {
+    fn from_world(world: &World) -> anyhow::Result<Self> {
+        Ok(Self {
+            store: world
+                .get_resource::<PersistentStore>()
+                .cloned()
+                .context("Connection verification requires a PersistentStore")?,
+            secrets: world
+                .get_resource::<DatabaseSecretStore>()
+                .cloned()
+                .context("Connection verification requires a DatabaseSecretStore")?,
+            runtime: world
+                .get_resource::<TokioRuntime>()
+                .cloned()
+                .context("TokioRuntime resource not found")?,
+            registry: world
+                .get_resource::<IntegrationRegistry>()
+                .cloned()
+                .context("IntegrationRegistry resource not found")?,
+            client: world
+                .get_resource::<GlobalHttpClient>()
+                .cloned()
+                .unwrap_or_default()
+                .client,
+        })
+    }
+
+    async fn verify(
+        &self,
+        tenant: &TenantId,
+        slug: &str,
+    ) -> anyhow::Result<ConnectionVerification> {
+        let (provider, _) = self
+            .store
+            .get_connection_secret(tenant, slug)
+            .await?
+            .with_context(|| format!("Connection '{}' not found", slug))?;
+        let def = self
+            .registry
+            .definitions
+            .get(&provider)
+            .with_context(|| format!("Integration '{}' not found", provider))?;
+        let credentials = self.secrets.resolve_connection(tenant, slug).await?;
+        def.verify(&self.client, &credentials).await
+    }
+}
+
+/// Records a connection's status in the background; the store is optional in tests.
+fn mark_connection_status(world: &World, tenant: TenantId, slug: String, status: &'static str) {
+    if let (Some(store), Some(runtime)) = (
+        world.get_resource::<PersistentStore>().cloned(),
+        world.get_resource::<TokioRuntime>().cloned(),
+    ) {
+        runtime.0.spawn(async move {
+            if let Err(e) = store.mark_connection_status(&tenant, &slug, status).await {
+                tracing::error!(%slug, error = %e, "Failed to mark connection status");
+            }
+        });
+    }
+}
+
+/// Rotates inline, so that the next command already sees the new key.
+pub fn handle_rotate_tenant_key(world: &mut World, tenant: TenantId) -> anyhow::Result<()> {
+    tracing::info!(%tenant, "Processing RotateTenantKey command");
+
+    let secrets = world
+        .get_resource::<DatabaseSecretStore>()
+        .cloned()
+        .context("Key rotation requires a DatabaseSecretStore")?;
+    let runtime = world
+        .get_resource::<TokioRuntime>()
+        .cloned()
+        .context("TokioRuntime resource not found")?;
+    let version = tokio::task::block_in_place(|| {
+        runtime
+            .0
+            .block_on(secrets.keyring().rotate_tenant_key(&tenant))
+    })?;
+
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::TenantKeyRotated {
+            tenant_id: tenant.0.clone(),
+            version,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+    Ok(())
+}
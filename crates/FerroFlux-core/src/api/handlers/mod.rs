@@ -1,5 +1,12 @@
+pub mod connection;
 pub mod graph;
 pub mod pin;
+pub mod prompt;
+pub mod quota;
 pub mod registry;
+pub mod replay;
+pub mod run;
+pub mod settings;
 pub mod simulation;
+pub mod template;
 pub mod trigger;
@@ -1,13 +1,16 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
 use crate::components::{NodeConfig, PinnedOutput};
-use crate::store::BlobStore;
+use crate::store::{BlobStore, SecureTicket};
 use bevy_ecs::prelude::*;
-use ferroflux_iam::TenantId;
+use ferroflux_iam::{Principal, TenantId};
+use serde_json::Value;
 use std::collections::HashMap;
 use uuid::Uuid;
 
 pub fn handle_pin_node(
     world: &mut World,
-    _tenant: TenantId,
+    principal: &Principal,
+    tenant: TenantId,
     node_id: Uuid,
     ticket_id_str: String,
 ) -> anyhow::Result<()> {
@@ -15,38 +18,103 @@ pub fn handle_pin_node(
 
     let ticket_uuid = Uuid::parse_str(&ticket_id_str)?;
 
-    let mut target_entity = None;
-    {
-        let mut query = world.query::<(Entity, &NodeConfig)>();
-        for (e, conf) in query.iter(world) {
-            if conf.id == node_id {
-                target_entity = Some(e);
-                break;
-            }
-        }
+    let entity = find_node(world, principal, &tenant, node_id)
+        .ok_or_else(|| anyhow::anyhow!("Node not found for pinning"))?;
+    let store = world
+        .get_resource::<BlobStore>()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("BlobStore not found"))?;
+    let mut ticket = store
+        .recover_ticket(&ticket_uuid)
+        .ok_or_else(|| anyhow::anyhow!("Ticket not found in store for pinning"))?;
+    ticket
+        .metadata
+        .insert("pinned".to_string(), "true".to_string());
+    let mut meta_update = HashMap::new();
+    meta_update.insert("pinned".to_string(), "true".to_string());
+    let _ = store.update_metadata(&ticket.id, meta_update);
+
+    pin(world, entity, &tenant, node_id, ticket);
+    Ok(())
+}
+
+/// Pins `output` as the node's result. The JSON is checked into the `BlobStore` and read back
+/// before the node is pinned, so a store that can't hold it fails here rather than mid-run.
+pub fn handle_pin_node_output(
+    world: &mut World,
+    principal: &Principal,
+    tenant: TenantId,
+    node_id: Uuid,
+    output: Value,
+) -> anyhow::Result<()> {
+    tracing::info!(node_id = %node_id, "Processing PinNodeOutput command");
+
+    let entity = find_node(world, principal, &tenant, node_id)
+        .ok_or_else(|| anyhow::anyhow!("Node not found for pinning"))?;
+    let store = world
+        .get_resource::<BlobStore>()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("BlobStore not found"))?;
+
+    let bytes = serde_json::to_vec(&output)?;
+    let metadata = HashMap::from([("pinned".to_string(), "true".to_string())]);
+    let ticket = store.check_in_with_metadata(&bytes, metadata)?;
+    let stored: Value = serde_json::from_slice(&store.claim(&ticket)?)?;
+    anyhow::ensure!(
+        stored == output,
+        "Pinned output did not survive a BlobStore round trip"
+    );
+
+    pin(world, entity, &tenant, node_id, ticket);
+    Ok(())
+}
+
+/// Lets the node execute again. Unpinning a node that isn't pinned is not an error.
+pub fn handle_unpin_node(
+    world: &mut World,
+    principal: &Principal,
+    tenant: TenantId,
+    node_id: Uuid,
+) -> anyhow::Result<()> {
+    tracing::info!(node_id = %node_id, "Processing UnpinNode command");
+
+    let entity = find_node(world, principal, &tenant, node_id)
+        .ok_or_else(|| anyhow::anyhow!("Node not found for unpinning"))?;
+    // The pinned blob is left in place: copies of the ticket may still be in flight.
+    if world.entity_mut(entity).take::<PinnedOutput>().is_some() {
+        tracing::info!(entity = ?entity, "Node unpinned");
+        emit(world, &tenant, node_id, None);
     }
+    Ok(())
+}
+
+fn find_node(
+    world: &mut World,
+    principal: &Principal,
+    tenant: &TenantId,
+    node_id: Uuid,
+) -> Option<Entity> {
+    let mut query = world.query::<(Entity, &NodeConfig)>();
+    query
+        .iter(world)
+        .find(|(_, conf)| conf.id == node_id && super::trigger::belongs_to(conf, principal, tenant))
+        .map(|(e, _)| e)
+}
+
+fn pin(world: &mut World, entity: Entity, tenant: &TenantId, node_id: Uuid, ticket: SecureTicket) {
+    let ticket_id = ticket.id;
+    world.entity_mut(entity).insert(PinnedOutput(ticket));
+    tracing::info!(entity = ?entity, "Node pinned successfully");
+    emit(world, tenant, node_id, Some(ticket_id));
+}
 
-    if let Some(entity) = target_entity {
-        if let Some(store) = world.get_resource::<BlobStore>() {
-            match store.recover_ticket(&ticket_uuid) {
-                Some(mut ticket) => {
-                    ticket
-                        .metadata
-                        .insert("pinned".to_string(), "true".to_string());
-                    let mut meta_update = HashMap::new();
-                    meta_update.insert("pinned".to_string(), "true".to_string());
-                    let _ = store.update_metadata(&ticket.id, meta_update);
-
-                    world.entity_mut(entity).insert(PinnedOutput(ticket));
-                    tracing::info!(entity = ?entity, "Node pinned successfully");
-                    Ok(())
-                }
-                None => Err(anyhow::anyhow!("Ticket not found in store for pinning")),
-            }
-        } else {
-            Err(anyhow::anyhow!("BlobStore not found"))
-        }
-    } else {
-        Err(anyhow::anyhow!("Node not found for pinning"))
+fn emit(world: &World, tenant: &TenantId, node_id: Uuid, ticket_id: Option<Uuid>) {
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::NodePinChanged {
+            tenant_id: tenant.0.clone(),
+            node_id,
+            ticket_id,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
     }
 }
@@ -0,0 +1,64 @@
+use crate::resources::TokioRuntime;
+use crate::resources::prompts::{PromptLibrary, validate_name};
+use crate::store::database::PersistentStore;
+use anyhow::Context;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+
+/// Stores the template as its next version and adds it to the `PromptLibrary`.
+pub fn handle_save_prompt_template(
+    world: &mut World,
+    tenant: TenantId,
+    name: String,
+    template: String,
+    variables: Vec<String>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %name, "Processing SavePromptTemplate command");
+    validate_name(&name)?;
+
+    let (store, runtime) = store_and_runtime(world)?;
+    let saved = tokio::task::block_in_place(|| {
+        runtime
+            .0
+            .block_on(store.save_prompt_template(&tenant, &name, &template, &variables))
+    })?;
+    tracing::info!(%tenant, %name, version = saved.version, "Prompt template saved");
+
+    world
+        .get_resource_or_insert_with(PromptLibrary::default)
+        .insert(&tenant, saved);
+    Ok(())
+}
+
+pub fn handle_delete_prompt_template(
+    world: &mut World,
+    tenant: TenantId,
+    name: String,
+    version: Option<u32>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %name, ?version, "Processing DeletePromptTemplate command");
+
+    let (store, runtime) = store_and_runtime(world)?;
+    tokio::task::block_in_place(|| {
+        runtime
+            .0
+            .block_on(store.delete_prompt_template(&tenant, &name, version))
+    })?;
+
+    if let Some(mut library) = world.get_resource_mut::<PromptLibrary>() {
+        library.remove(&tenant, &name, version);
+    }
+    Ok(())
+}
+
+fn store_and_runtime(world: &World) -> anyhow::Result<(PersistentStore, TokioRuntime)> {
+    let store = world
+        .get_resource::<PersistentStore>()
+        .cloned()
+        .context("Prompt templates require a PersistentStore")?;
+    let runtime = world
+        .get_resource::<TokioRuntime>()
+        .cloned()
+        .context("TokioRuntime resource not found")?;
+    Ok((store, runtime))
+}
@@ -0,0 +1,30 @@
+use crate::resources::TokioRuntime;
+use crate::resources::quotas::TenantQuotas;
+use crate::store::database::{PersistentStore, TenantLimits};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+
+/// Replaces the tenant's limits in `TenantQuotas`, and in the database when one is attached.
+/// Usage already counted is kept.
+pub fn handle_set_tenant_limits(
+    world: &mut World,
+    tenant: TenantId,
+    limits: TenantLimits,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, ?limits, "Processing SetTenantLimits command");
+
+    let store = world.get_resource::<PersistentStore>().cloned();
+    let runtime = world.get_resource::<TokioRuntime>().cloned();
+    if let (Some(store), Some(runtime)) = (store, runtime) {
+        tokio::task::block_in_place(|| {
+            runtime
+                .0
+                .block_on(store.save_tenant_limits(&tenant, &limits))
+        })?;
+    }
+
+    world
+        .get_resource_or_insert_with(TenantQuotas::default)
+        .set_limits(&tenant, limits);
+    Ok(())
+}
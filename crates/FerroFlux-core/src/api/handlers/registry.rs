@@ -1,8 +1,13 @@
-use crate::api::PlatformPath;
+use crate::api::{PlatformPath, Reply};
+use crate::nodes::loader::NodeDefinitionLoader;
+use crate::nodes::plugin::{self, PluginRegistry};
 use crate::nodes::register_core_nodes;
 use crate::nodes::yaml_factory::YamlNodeFactory;
 use crate::resources::registry::{DefinitionRegistry, NodeRegistry};
+use crate::store::BlobStore;
+use crate::traits::node_factory::NodeMetadata;
 use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
 
 pub fn handle_reload_definitions(world: &mut World) -> anyhow::Result<()> {
     tracing::info!("Processing ReloadDefinitions command");
@@ -32,8 +37,76 @@ pub fn handle_reload_definitions(world: &mut World) -> anyhow::Result<()> {
             }
             tracing::info!(count = defs.definitions.len(), "Node factories reloaded");
         }
+        let plugins = world.get_resource::<PluginRegistry>().cloned();
+        if let Some(plugins) = plugins
+            && let Some(mut registry) = world.get_resource_mut::<NodeRegistry>()
+        {
+            plugins.register_node_types(&mut registry);
+        }
+
+        // 3. Custom nodes were dropped with the rest; register them again now
+        if let Some(mut loader) = world.remove_resource::<NodeDefinitionLoader>() {
+            loader.reset();
+            let mut nodes = world.remove_resource::<NodeRegistry>();
+            let mut defs = world.remove_resource::<DefinitionRegistry>();
+            if let (Some(nodes), Some(defs)) = (nodes.as_mut(), defs.as_mut()) {
+                loader.scan(nodes, defs);
+            }
+            if let Some(nodes) = nodes {
+                world.insert_resource(nodes);
+            }
+            if let Some(defs) = defs {
+                world.insert_resource(defs);
+            }
+            world.insert_resource(loader);
+        }
         Ok(())
     } else {
         Err(anyhow::anyhow!("PlatformPath resource not found"))
     }
 }
+
+pub fn handle_list_node_types(
+    world: &mut World,
+    tenant: TenantId,
+    query: String,
+    category: Option<String>,
+    reply: Reply<Result<Vec<NodeMetadata>, String>>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %query, ?category, "Processing ListNodeTypes command");
+    let node_types = world
+        .get_resource::<NodeRegistry>()
+        .map(|registry| registry.search_for(Some(&tenant), &query, category.as_deref()))
+        .unwrap_or_default();
+    reply.send(Ok(node_types));
+    Ok(())
+}
+
+pub fn handle_install_plugin(
+    world: &mut World,
+    tenant: TenantId,
+    ticket: uuid::Uuid,
+    reply: Reply<Result<NodeMetadata, String>>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %ticket, "Processing InstallPlugin command");
+    let result = (|| {
+        let store = world
+            .get_resource::<BlobStore>()
+            .ok_or_else(|| anyhow::anyhow!("BlobStore resource not found"))?;
+        let ticket = store
+            .recover_ticket(&ticket)
+            .ok_or_else(|| anyhow::anyhow!("Ticket {} not found", ticket))?;
+        let bytes = store.claim(&ticket)?;
+        plugin::install(world, Some(tenant), &bytes)
+    })();
+    match result {
+        Ok(metadata) => {
+            reply.send(Ok(metadata));
+            Ok(())
+        }
+        Err(e) => {
+            reply.send(Err(e.to_string()));
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::trigger::belongs_to;
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::{NodeConfig, Outbox};
+use crate::resources::{ReplayRuns, TokioRuntime, WorkDone};
+use crate::store::BlobStore;
+use crate::store::database::PersistentStore;
+use crate::systems::replay::REPLAY_OF_KEY;
+use crate::systems::shadow::SideEffecting;
+use anyhow::Context;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::{Principal, TenantId};
+use std::collections::HashMap;
+
+/// Replays a recorded run under a fresh trace id: source nodes re-emit their recorded output,
+/// and side-effecting nodes answer with theirs as the run reaches them (see `replay_worker`).
+pub fn handle_replay_run(
+    world: &mut World,
+    principal: &Principal,
+    tenant: TenantId,
+    trace_id: String,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %trace_id, "Processing ReplayRun command");
+
+    let db = world
+        .get_resource::<PersistentStore>()
+        .cloned()
+        .context("Replaying a run requires a PersistentStore")?;
+    let runtime = world
+        .get_resource::<TokioRuntime>()
+        .cloned()
+        .context("TokioRuntime resource not found")?;
+    let recordings = tokio::task::block_in_place(|| {
+        runtime
+            .0
+            .block_on(db.load_io_recordings(&tenant, &trace_id))
+    })?;
+    anyhow::ensure!(
+        !recordings.is_empty(),
+        "No recorded results for trace {}",
+        trace_id
+    );
+
+    let mut query = world.query::<(Entity, &NodeConfig)>();
+    let nodes: HashMap<uuid::Uuid, Entity> = query
+        .iter(world)
+        .filter(|(_, conf)| belongs_to(conf, principal, &tenant))
+        .map(|(e, conf)| (conf.id, e))
+        .collect();
+    let mut side_effecting = world.query_filtered::<(), SideEffecting>();
+    let (fed_back, sources): (Vec<_>, Vec<_>) = recordings.into_iter().partition(|recording| {
+        nodes
+            .get(&recording.node_id)
+            .is_some_and(|e| side_effecting.get(world, *e).is_ok())
+    });
+
+    let replay_id = uuid::Uuid::new_v4().to_string();
+    let recorded = fed_back.len() + sources.len();
+    world
+        .get_resource_or_insert_with(ReplayRuns::default)
+        .start(&replay_id, fed_back);
+
+    let store = world
+        .get_resource::<BlobStore>()
+        .cloned()
+        .context("BlobStore resource not found")?;
+    for recording in sources {
+        let Some(&entity) = nodes.get(&recording.node_id) else {
+            tracing::warn!(node_id = %recording.node_id, "Recorded node is not deployed");
+            continue;
+        };
+        let mut metadata = recording.metadata;
+        metadata.insert("trace_id".to_string(), replay_id.clone());
+        metadata.insert(REPLAY_OF_KEY.to_string(), trace_id.clone());
+        let ticket = store.check_in_with_metadata(&recording.output, metadata)?;
+        if let Some(mut outbox) = world.get_mut::<Outbox>(entity) {
+            outbox.queue.push_back((recording.port, ticket));
+        }
+    }
+    if let Some(wd) = world.get_resource::<WorkDone>() {
+        wd.mark();
+    }
+
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::ReplayStarted {
+            tenant_id: tenant.0.clone(),
+            trace_id: replay_id,
+            replay_of: trace_id,
+            recorded,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::observability::{Trace, TraceCancelled};
+use crate::components::{Inbox, NodeConfig, Outbox};
+use crate::resources::CancelledRuns;
+use crate::resources::connection_health::{OPERATOR_PAUSE, PausedWorkflows};
+use crate::store::{BlobStore, SecureTicket};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::{Principal, TenantId};
+
+pub fn handle_pause_workflow(
+    world: &mut World,
+    tenant: TenantId,
+    workflow_id: String,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %workflow_id, "Processing PauseWorkflow command");
+
+    world
+        .get_resource_or_insert_with(PausedWorkflows::default)
+        .pause(&tenant, &workflow_id, OPERATOR_PAUSE);
+    Ok(())
+}
+
+pub fn handle_resume_workflow(
+    world: &mut World,
+    tenant: TenantId,
+    workflow_id: String,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %workflow_id, "Processing ResumeWorkflow command");
+
+    let resumed = world
+        .get_resource_mut::<PausedWorkflows>()
+        .is_some_and(|mut paused| paused.resume(&tenant, &workflow_id));
+    if !resumed {
+        tracing::warn!(%tenant, %workflow_id, "Workflow was not paused");
+    }
+    Ok(())
+}
+
+/// Removes the run's tickets from the tenant's inboxes and outboxes, releasing their blobs,
+/// and records the trace as cancelled so late results are dropped by the transport.
+pub fn handle_cancel_run(
+    world: &mut World,
+    principal: &Principal,
+    tenant: TenantId,
+    trace_id: String,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %trace_id, "Processing CancelRun command");
+
+    world
+        .get_resource_or_insert_with(CancelledRuns::default)
+        .cancel(&tenant, &trace_id);
+
+    let store = world.get_resource::<BlobStore>().cloned();
+    let in_run = |ticket: &SecureTicket| ticket.metadata.get("trace_id") == Some(&trace_id);
+    let mut purged = Vec::new();
+    let mut query = world.query::<(&NodeConfig, Option<&mut Inbox>, Option<&mut Outbox>)>();
+    for (config, inbox, outbox) in query.iter_mut(world) {
+        if !super::trigger::belongs_to(config, principal, &tenant) {
+            continue;
+        }
+        if let Some(mut inbox) = inbox {
+            inbox.queue.retain(|ticket| {
+                let keep = !in_run(ticket);
+                if !keep {
+                    purged.push(ticket.clone());
+                }
+                keep
+            });
+        }
+        if let Some(mut outbox) = outbox {
+            outbox.queue.retain(|(_, ticket)| {
+                let keep = !in_run(ticket);
+                if !keep {
+                    purged.push(ticket.clone());
+                }
+                keep
+            });
+        }
+    }
+    if let Some(store) = &store {
+        for ticket in &purged {
+            let _ = store.release(ticket);
+        }
+    }
+
+    if let Ok(trace_uuid) = uuid::Uuid::parse_str(&trace_id) {
+        let traces: Vec<Entity> = world
+            .query::<(Entity, &Trace)>()
+            .iter(world)
+            .filter(|(_, trace)| trace.0 == trace_uuid)
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in traces {
+            world
+                .entity_mut(entity)
+                .insert(TraceCancelled(chrono::Utc::now()));
+        }
+    }
+
+    tracing::info!(%tenant, %trace_id, purged = purged.len(), "Run cancelled");
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::RunCancelled {
+            tenant_id: tenant.0.clone(),
+            trace_id,
+            purged: purged.len(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+    Ok(())
+}
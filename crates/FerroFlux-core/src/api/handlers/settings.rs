@@ -0,0 +1,40 @@
+use crate::resources::TokioRuntime;
+use crate::resources::settings::{RuntimeSettings, merge};
+use crate::store::database::PersistentStore;
+use bevy_ecs::prelude::*;
+use serde_json::Value;
+
+/// Applies a partial `EngineSettings` document and persists it on top of earlier updates.
+///
+/// Only the accumulated patches are stored, so values that came from the settings file or
+/// the environment are not frozen into the database.
+pub fn handle_update_settings(world: &mut World, patch: Value) -> anyhow::Result<()> {
+    tracing::info!("Processing UpdateSettings command");
+
+    let settings = world
+        .get_resource::<RuntimeSettings>()
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("RuntimeSettings resource not found"))?;
+    let updated = settings.current().patched(patch.clone())?;
+    settings.replace(updated);
+
+    // Persisted inline so that back-to-back updates can't interleave their read-merge-write.
+    if let (Some(store), Some(runtime)) = (
+        world.get_resource::<PersistentStore>().cloned(),
+        world.get_resource::<TokioRuntime>().cloned(),
+    ) {
+        tokio::task::block_in_place(move || {
+            runtime.0.block_on(async move {
+                let mut stored = match store.load_engine_settings().await? {
+                    Some(json) => serde_json::from_str(&json)?,
+                    None => Value::Object(Default::default()),
+                };
+                merge(&mut stored, patch);
+                store
+                    .save_engine_settings(&serde_json::to_string(&stored)?)
+                    .await
+            })
+        })?;
+    }
+    Ok(())
+}
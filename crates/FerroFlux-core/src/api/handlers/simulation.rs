@@ -1,11 +1,16 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
 use crate::components::pipeline::PipelineNode;
+use crate::components::shadow::{MockConfig, SHADOW_KEY};
+use crate::resources::ShadowRuns;
+use crate::resources::settings::RuntimeSettings;
 use anyhow::{Context, Result};
 use bevy_ecs::prelude::*;
 use std::collections::HashMap;
 
 pub fn handle_simulate_node(
     world: &mut World,
-    _tenant: ferroflux_iam::TenantId,
+    principal: &ferroflux_iam::Principal,
+    tenant: ferroflux_iam::TenantId,
     node_id: uuid::Uuid,
     input_ticket: uuid::Uuid,
     trace_id: String,
@@ -33,7 +38,7 @@ pub fn handle_simulate_node(
     // DIFFERENTIATION: SimulateNode creates a NEW ephemeral entity to avoid state corruption.
     // So we *must* find the config of the source node.
 
-    let source_entity = find_entity_by_uuid(world, node_id).context("Node not found")?;
+    let source_entity = find_entity_by_uuid(world, principal, &tenant, node_id).context("Node not found")?;
 
     // Extract config
     let (definition_id, config) = {
@@ -58,7 +63,7 @@ pub fn handle_simulate_node(
         metadata: {
             let mut m = HashMap::new();
             m.insert("trace_id".to_string(), trace_id);
-            m.insert("shadow".to_string(), "true".to_string());
+            m.insert(SHADOW_KEY.to_string(), "true".to_string());
             m
         },
     };
@@ -66,14 +71,19 @@ pub fn handle_simulate_node(
     let mut inbox = crate::components::Inbox::default();
     inbox.queue.push_back(ticket);
 
+    // Engine-wide default mocks fill in tools the request didn't mock itself
+    let mut mocked_tools = RuntimeSettings::effective(world.get_resource::<RuntimeSettings>())
+        .shadow
+        .default_mocks
+        .clone();
+    mocked_tools.extend(mock_config);
+
     // 4. Spawn
     world.spawn((
         shadow_node,
         inbox,
         crate::components::Outbox::default(),
-        crate::components::shadow::ShadowExecution {
-            mocked_tools: mock_config,
-        },
+        crate::components::shadow::ShadowExecution { mocked_tools },
         // We might want a cleanup component or TTL so these don't pile up?
         // For now, rely on "Janitor"? Janitor cleans traces, not entities.
         // We should add `Ephemeral` component.
@@ -84,11 +94,61 @@ pub fn handle_simulate_node(
     Ok(())
 }
 
-fn find_entity_by_uuid(world: &mut World, target: uuid::Uuid) -> Option<Entity> {
+/// Triggers a workflow under a fresh trace id registered in `ShadowRuns`, so side-effecting
+/// nodes along the run answer with mocks (see `shadow_worker`).
+pub fn handle_shadow_run(
+    world: &mut World,
+    principal: &ferroflux_iam::Principal,
+    tenant: ferroflux_iam::TenantId,
+    workflow_id: String,
+    payload: serde_json::Value,
+    mock_config: HashMap<String, MockConfig>,
+) -> Result<()> {
+    let start = super::trigger::workflow_start(world, principal, &tenant, &workflow_id)
+        .context("No suitable start node found for workflow")?;
+
+    let mut mocks = RuntimeSettings::effective(world.get_resource::<RuntimeSettings>())
+        .shadow
+        .default_mocks
+        .clone();
+    mocks.extend(mock_config);
+
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    world
+        .get_resource_or_insert_with(ShadowRuns::default)
+        .start(&trace_id, mocks);
+    super::trigger::inject(
+        world,
+        start,
+        &payload,
+        HashMap::from([
+            ("trace_id".to_string(), trace_id.clone()),
+            (SHADOW_KEY.to_string(), "true".to_string()),
+        ]),
+    );
+
+    tracing::info!(%workflow_id, %trace_id, "Shadow run started");
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(SystemEvent::ShadowRunStarted {
+            tenant_id: tenant.0.clone(),
+            workflow_id,
+            trace_id,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+    Ok(())
+}
+
+fn find_entity_by_uuid(
+    world: &mut World,
+    principal: &ferroflux_iam::Principal,
+    tenant: &ferroflux_iam::TenantId,
+    target: uuid::Uuid,
+) -> Option<Entity> {
     let mut target_entity = None;
     let mut query = world.query::<(Entity, &crate::components::NodeConfig)>();
     for (e, conf) in query.iter(world) {
-        if conf.id == target {
+        if conf.id == target && super::trigger::belongs_to(conf, principal, tenant) {
             target_entity = Some(e);
             break;
         }
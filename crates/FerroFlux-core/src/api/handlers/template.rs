@@ -0,0 +1,41 @@
+use crate::api::Reply;
+use crate::templates::{TemplateLibrary, TemplatesByCategory, instantiate_template};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use std::collections::HashMap;
+
+pub fn handle_list_templates(
+    world: &mut World,
+    tenant: TenantId,
+    reply: Reply<Result<TemplatesByCategory, String>>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, "Processing ListTemplates command");
+    let templates = world
+        .get_resource::<TemplateLibrary>()
+        .map(TemplateLibrary::by_category)
+        .unwrap_or_default();
+    reply.send(Ok(templates));
+    Ok(())
+}
+
+pub fn handle_instantiate_template(
+    world: &mut World,
+    tenant: TenantId,
+    template_id: String,
+    workflow_id: Option<String>,
+    connections: HashMap<String, String>,
+    reply: Reply<Result<String, String>>,
+) -> anyhow::Result<()> {
+    tracing::info!(%tenant, %template_id, "Processing InstantiateTemplate command");
+    match instantiate_template(world, tenant, &template_id, workflow_id, connections) {
+        Ok(imported) => {
+            tracing::info!(%template_id, workflow_id = %imported.workflow_id, "Template instantiated");
+            reply.send(Ok(imported.workflow_id));
+            Ok(())
+        }
+        Err(e) => {
+            reply.send(Err(e.to_string()));
+            Err(e)
+        }
+    }
+}
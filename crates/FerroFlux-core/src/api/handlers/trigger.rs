@@ -1,13 +1,20 @@
+use crate::api::events::SystemEventBus;
+use crate::api::{RunError, RunReply};
 use crate::components::{Inbox, NodeConfig, Outbox, WorkDone};
+use crate::resources::quotas::{self, QuotaError, TenantQuotas};
+use crate::resources::{PendingRun, PendingRuns};
 use crate::store::BlobStore;
 use bevy_ecs::prelude::*;
-use ferroflux_iam::TenantId;
+use ferroflux_iam::{Principal, TenantId};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 pub fn handle_trigger_node(
     world: &mut World,
-    _tenant: TenantId,
+    principal: &Principal,
+    tenant: TenantId,
     uuid: Uuid,
     payload: Value,
 ) -> anyhow::Result<()> {
@@ -17,7 +24,7 @@ pub fn handle_trigger_node(
     {
         let mut query = world.query::<(Entity, &NodeConfig)>();
         for (e, conf) in query.iter(world) {
-            if conf.id == uuid {
+            if conf.id == uuid && belongs_to(conf, principal, &tenant) {
                 target_entity = Some(e);
                 break;
             }
@@ -25,6 +32,10 @@ pub fn handle_trigger_node(
     }
 
     if let Some(e) = target_entity {
+        let workflow_id = world
+            .get::<NodeConfig>(e)
+            .and_then(|c| c.workflow_id.clone());
+        admit_run(world, &tenant, workflow_id, &payload)?;
         if let Some(store) = world.get_resource::<BlobStore>().cloned() {
             let payload_bytes = serde_json::to_vec(&payload).unwrap_or_else(|_| b"{}".to_vec());
             if let Ok(ticket) = store.check_in(&payload_bytes) {
@@ -38,15 +49,15 @@ pub fn handle_trigger_node(
                     if let Some(mut outbox) = world.get_mut::<Outbox>(e) {
                         outbox.queue.push_back((None, ticket));
                         tracing::info!(entity = ?e, "Trigger sent to OUTBOX (Source Node)");
-                        if let Some(mut wd) = world.get_resource_mut::<WorkDone>() {
-                            wd.0 = true;
+                        if let Some(wd) = world.get_resource::<WorkDone>() {
+                            wd.mark();
                         }
                     }
                 } else if let Some(mut inbox) = world.get_mut::<Inbox>(e) {
                     inbox.queue.push_back(ticket);
                     tracing::info!(entity = ?e, "Trigger sent to INBOX");
-                    if let Some(mut wd) = world.get_resource_mut::<WorkDone>() {
-                        wd.0 = true;
+                    if let Some(wd) = world.get_resource::<WorkDone>() {
+                        wd.mark();
                     }
                 }
             }
@@ -59,55 +70,135 @@ pub fn handle_trigger_node(
 
 pub fn handle_trigger_workflow(
     world: &mut World,
-    _tenant: TenantId,
+    principal: &Principal,
+    tenant: TenantId,
     workflow_id: String,
     payload: Value,
 ) -> anyhow::Result<()> {
     tracing::info!(workflow_id = %workflow_id, "Processing TriggerWorkflow command");
 
-    let mut target_entity = None;
-    {
-        let mut query = world.query::<(Entity, &NodeConfig)>();
-        for (e, conf) in query.iter(world) {
-            if let Some(wf_id) = &conf.workflow_id
-                && wf_id == &workflow_id
-                && conf.node_type == "Webhook"
-            {
-                target_entity = Some(e);
-                break;
-            }
-        }
+    let Some(e) = workflow_start(world, principal, &tenant, &workflow_id) else {
+        return Err(anyhow::anyhow!("No suitable start node found for workflow"));
+    };
+    admit_run(world, &tenant, Some(workflow_id), &payload)?;
+    inject(world, e, &payload, HashMap::new());
+    Ok(())
+}
+
+/// Triggers the workflow under a fresh trace id and registers the run with `PendingRuns`,
+/// where `run_waiter_worker` answers `reply` once it completes, times out or is cancelled.
+pub fn handle_trigger_and_wait(
+    world: &mut World,
+    principal: &Principal,
+    tenant: TenantId,
+    workflow_id: String,
+    payload: Value,
+    timeout: Duration,
+    reply: RunReply,
+) -> anyhow::Result<()> {
+    tracing::info!(workflow_id = %workflow_id, "Processing TriggerAndWait command");
+
+    let Some(e) = workflow_start(world, principal, &tenant, &workflow_id) else {
+        reply.send(Err(RunError::NotFound(workflow_id)));
+        return Err(anyhow::anyhow!("No suitable start node found for workflow"));
+    };
+    if let Err(e) = admit_run(world, &tenant, Some(workflow_id), &payload) {
+        reply.send(Err(RunError::QuotaExceeded(e.clone())));
+        return Err(e.into());
     }
+    let trace_id = Uuid::new_v4().to_string();
+    world
+        .get_resource_or_insert_with(PendingRuns::default)
+        .0
+        .insert(
+            trace_id.clone(),
+            PendingRun::new(tenant, reply, Instant::now() + timeout),
+        );
+    inject(
+        world,
+        e,
+        &payload,
+        HashMap::from([("trace_id".to_string(), trace_id)]),
+    );
+    Ok(())
+}
 
-    if let Some(e) = target_entity {
-        if let Some(store) = world.get_resource::<BlobStore>().cloned() {
-            let payload_bytes = serde_json::to_vec(&payload).unwrap_or_else(|_| b"{}".to_vec());
-            if let Ok(ticket) = store.check_in(&payload_bytes) {
-                let is_source = if let Some(conf) = world.get::<NodeConfig>(e) {
-                    conf.node_type == "Webhook" || conf.node_type == "Cron"
-                } else {
-                    false
-                };
+/// Counts a run against the tenant's quotas, or reports why it may not start.
+fn admit_run(
+    world: &World,
+    tenant: &TenantId,
+    workflow_id: Option<String>,
+    payload: &Value,
+) -> Result<(), QuotaError> {
+    let size = serde_json::to_vec(payload).map_or(0, |bytes| bytes.len());
+    quotas::admit_run(
+        world.get_resource::<TenantQuotas>(),
+        world.get_resource::<SystemEventBus>(),
+        tenant,
+        workflow_id,
+        size,
+    )
+}
 
-                if is_source {
-                    if let Some(mut outbox) = world.get_mut::<Outbox>(e) {
-                        outbox.queue.push_back((None, ticket));
-                        tracing::info!(entity = ?e, "Workflow trigger sent to OUTBOX (Source)");
-                        if let Some(mut wd) = world.get_resource_mut::<WorkDone>() {
-                            wd.0 = true;
-                        }
-                    }
-                } else if let Some(mut inbox) = world.get_mut::<Inbox>(e) {
-                    inbox.queue.push_back(ticket);
-                    tracing::info!(entity = ?e, "Workflow trigger sent to INBOX");
-                    if let Some(mut wd) = world.get_resource_mut::<WorkDone>() {
-                        wd.0 = true;
-                    }
-                }
+/// The Webhook node that starts the workflow.
+pub(crate) fn workflow_start(
+    world: &mut World,
+    principal: &Principal,
+    tenant: &TenantId,
+    workflow_id: &str,
+) -> Option<Entity> {
+    let mut query = world.query::<(Entity, &NodeConfig)>();
+    query
+        .iter(world)
+        .find(|(_, conf)| {
+            conf.workflow_id.as_deref() == Some(workflow_id)
+                && conf.node_type == "Webhook"
+                && belongs_to(conf, principal, tenant)
+        })
+        .map(|(e, _)| e)
+}
+
+pub(crate) fn inject(
+    world: &mut World,
+    e: Entity,
+    payload: &Value,
+    metadata: HashMap<String, String>,
+) {
+    let Some(store) = world.get_resource::<BlobStore>().cloned() else {
+        return;
+    };
+    let payload_bytes = serde_json::to_vec(payload).unwrap_or_else(|_| b"{}".to_vec());
+    let Ok(ticket) = store.check_in_with_metadata(&payload_bytes, metadata) else {
+        return;
+    };
+    let is_source = if let Some(conf) = world.get::<NodeConfig>(e) {
+        conf.node_type == "Webhook" || conf.node_type == "Cron"
+    } else {
+        false
+    };
+
+    if is_source {
+        if let Some(mut outbox) = world.get_mut::<Outbox>(e) {
+            outbox.queue.push_back((None, ticket));
+            tracing::info!(entity = ?e, "Workflow trigger sent to OUTBOX (Source)");
+            if let Some(wd) = world.get_resource::<WorkDone>() {
+                wd.mark();
             }
         }
-        Ok(())
-    } else {
-        Err(anyhow::anyhow!("No suitable start node found for workflow"))
+    } else if let Some(mut inbox) = world.get_mut::<Inbox>(e) {
+        inbox.queue.push_back(ticket);
+        tracing::info!(entity = ?e, "Workflow trigger sent to INBOX");
+        if let Some(wd) = world.get_resource::<WorkDone>() {
+            wd.mark();
+        }
+    }
+}
+
+/// Whether a command for `tenant` may act on the node. Nodes without a tenant (e.g. deployed
+/// by the embedded SDK) are only reachable by system principals.
+pub(crate) fn belongs_to(conf: &NodeConfig, principal: &Principal, tenant: &TenantId) -> bool {
+    match &conf.tenant_id {
+        Some(owner) => owner == tenant,
+        None => principal.is_system,
     }
 }
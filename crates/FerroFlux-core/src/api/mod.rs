@@ -1,5 +1,7 @@
+pub mod authz;
 pub mod events;
 pub mod handlers;
+pub mod remote;
 
 use serde::{Deserialize, Serialize};
 
@@ -8,7 +10,20 @@ pub enum ApiCommand {
     LoadGraph(ferroflux_iam::TenantId, String),
     TriggerNode(ferroflux_iam::TenantId, uuid::Uuid, serde_json::Value),
     TriggerWorkflow(ferroflux_iam::TenantId, String, serde_json::Value),
+    /// Triggers a workflow and replies with the payloads that reach its terminal nodes, or an
+    /// error once `timeout_ms` passes.
+    TriggerAndWait {
+        tenant_id: ferroflux_iam::TenantId,
+        workflow_id: String,
+        payload: serde_json::Value,
+        timeout_ms: u64,
+        #[serde(skip)]
+        reply: RunReply,
+    },
     PinNode(ferroflux_iam::TenantId, uuid::Uuid, String),
+    /// Pins a JSON value as a node's output; the node emits it instead of executing.
+    PinNodeOutput(ferroflux_iam::TenantId, uuid::Uuid, serde_json::Value),
+    UnpinNode(ferroflux_iam::TenantId, uuid::Uuid),
     ReloadDefinitions,
     SimulateNode {
         tenant_id: ferroflux_iam::TenantId,
@@ -17,10 +32,270 @@ pub enum ApiCommand {
         trace_id: String,
         mock_config: std::collections::HashMap<String, crate::components::shadow::MockConfig>,
     },
+    /// Triggers a workflow as a dry run: side-effecting nodes answer with `mock_config`
+    /// (keyed by node id or node type) instead of executing.
+    ShadowRun {
+        tenant_id: ferroflux_iam::TenantId,
+        workflow_id: String,
+        payload: serde_json::Value,
+        mock_config: std::collections::HashMap<String, crate::components::shadow::MockConfig>,
+    },
+    /// Re-runs a recorded trace, feeding back the results captured under `capture.io_results`
+    /// instead of calling out.
+    ReplayRun {
+        tenant_id: ferroflux_iam::TenantId,
+        trace_id: String,
+    },
+    /// Merges a partial `EngineSettings` document into the live settings.
+    UpdateSettings(serde_json::Value),
+    /// A connection passed verification again: clears its health history, marks it
+    /// `active` and resumes workflows it had paused.
+    ConnectionVerified(ferroflux_iam::TenantId, String),
+    /// Tests a connection's credentials against its integration's `verify_endpoint` and
+    /// records the outcome as the connection's status; a passing connection is then handled
+    /// like `ConnectionVerified`. Replies with the outcome.
+    VerifyConnection {
+        tenant_id: ferroflux_iam::TenantId,
+        slug: String,
+        #[serde(skip)]
+        reply: Reply<Result<crate::integrations::ConnectionVerification, String>>,
+    },
+    /// Holds a workflow's tickets in their outboxes until `ResumeWorkflow`.
+    PauseWorkflow(ferroflux_iam::TenantId, String),
+    /// Lifts every pause on a workflow, including ones from failing connections.
+    ResumeWorkflow(ferroflux_iam::TenantId, String),
+    /// Purges a run's in-flight tickets by trace id and marks it cancelled.
+    CancelRun(ferroflux_iam::TenantId, String),
+    /// Stores a new version of a prompt library template; agents referencing it without a
+    /// version pick it up on their next ticket.
+    SavePromptTemplate {
+        tenant_id: ferroflux_iam::TenantId,
+        name: String,
+        template: String,
+        variables: Vec<String>,
+    },
+    /// Deletes one version of a prompt library template, or every version when `version` is
+    /// `None`.
+    DeletePromptTemplate {
+        tenant_id: ferroflux_iam::TenantId,
+        name: String,
+        version: Option<u32>,
+    },
+    /// Replaces the tenant's connection encryption key and re-encrypts its connections.
+    RotateTenantKey(ferroflux_iam::TenantId),
+    /// Replaces a tenant's quotas. Operators only: the command needs a system principal.
+    SetTenantLimits {
+        tenant_id: ferroflux_iam::TenantId,
+        limits: crate::store::database::TenantLimits,
+    },
+    /// Replies with the starter workflow templates, by category.
+    ListTemplates {
+        tenant_id: ferroflux_iam::TenantId,
+        #[serde(skip)]
+        reply: Reply<Result<crate::templates::TemplatesByCategory, String>>,
+    },
+    /// Replies with the registered node types matching `query` (fuzzy, best first; all of
+    /// them when empty), optionally restricted to one `category`. See `NodeRegistry::search`.
+    ListNodeTypes {
+        tenant_id: ferroflux_iam::TenantId,
+        #[serde(default)]
+        query: String,
+        #[serde(default)]
+        category: Option<String>,
+        #[serde(skip)]
+        reply: Reply<Result<Vec<crate::traits::node_factory::NodeMetadata>, String>>,
+    },
+    /// Loads a starter template as a new workflow of the tenant, binding its connection
+    /// placeholders to the tenant's connection slugs. Replies with the new workflow id.
+    InstantiateTemplate {
+        tenant_id: ferroflux_iam::TenantId,
+        template_id: String,
+        /// Defaults to the template id with a random suffix.
+        workflow_id: Option<String>,
+        connections: std::collections::HashMap<String, String>,
+        #[serde(skip)]
+        reply: Reply<Result<String, String>>,
+    },
+    /// Installs the WASM plugin checked into the blob store under `ticket` as a node type of
+    /// the tenant. Replies with the node type's metadata. See `nodes::plugin`.
+    InstallPlugin {
+        tenant_id: ferroflux_iam::TenantId,
+        ticket: uuid::Uuid,
+        #[serde(skip)]
+        reply: Reply<Result<crate::traits::node_factory::NodeMetadata, String>>,
+    },
+}
+
+impl ApiCommand {
+    /// The tenant this command targets, or `None` for engine-global commands.
+    pub fn tenant(&self) -> Option<&ferroflux_iam::TenantId> {
+        match self {
+            ApiCommand::LoadGraph(tenant, _)
+            | ApiCommand::TriggerNode(tenant, _, _)
+            | ApiCommand::TriggerWorkflow(tenant, _, _)
+            | ApiCommand::PinNode(tenant, _, _)
+            | ApiCommand::PinNodeOutput(tenant, _, _)
+            | ApiCommand::UnpinNode(tenant, _)
+            | ApiCommand::ConnectionVerified(tenant, _)
+            | ApiCommand::PauseWorkflow(tenant, _)
+            | ApiCommand::ResumeWorkflow(tenant, _)
+            | ApiCommand::CancelRun(tenant, _)
+            | ApiCommand::RotateTenantKey(tenant) => Some(tenant),
+            ApiCommand::SimulateNode { tenant_id, .. }
+            | ApiCommand::ShadowRun { tenant_id, .. }
+            | ApiCommand::ReplayRun { tenant_id, .. }
+            | ApiCommand::TriggerAndWait { tenant_id, .. }
+            | ApiCommand::SavePromptTemplate { tenant_id, .. }
+            | ApiCommand::DeletePromptTemplate { tenant_id, .. }
+            | ApiCommand::SetTenantLimits { tenant_id, .. }
+            | ApiCommand::ListTemplates { tenant_id, .. }
+            | ApiCommand::ListNodeTypes { tenant_id, .. }
+            | ApiCommand::InstantiateTemplate { tenant_id, .. }
+            | ApiCommand::InstallPlugin { tenant_id, .. }
+            | ApiCommand::VerifyConnection { tenant_id, .. } => Some(tenant_id),
+            ApiCommand::ReloadDefinitions | ApiCommand::UpdateSettings(_) => None,
+        }
+    }
+
+    /// Short name used in logs and audit events (never includes payloads).
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApiCommand::LoadGraph(..) => "LoadGraph",
+            ApiCommand::TriggerNode(..) => "TriggerNode",
+            ApiCommand::TriggerWorkflow(..) => "TriggerWorkflow",
+            ApiCommand::TriggerAndWait { .. } => "TriggerAndWait",
+            ApiCommand::PinNode(..) => "PinNode",
+            ApiCommand::PinNodeOutput(..) => "PinNodeOutput",
+            ApiCommand::UnpinNode(..) => "UnpinNode",
+            ApiCommand::ReloadDefinitions => "ReloadDefinitions",
+            ApiCommand::SimulateNode { .. } => "SimulateNode",
+            ApiCommand::ShadowRun { .. } => "ShadowRun",
+            ApiCommand::ReplayRun { .. } => "ReplayRun",
+            ApiCommand::UpdateSettings(_) => "UpdateSettings",
+            ApiCommand::ConnectionVerified(..) => "ConnectionVerified",
+            ApiCommand::VerifyConnection { .. } => "VerifyConnection",
+            ApiCommand::PauseWorkflow(..) => "PauseWorkflow",
+            ApiCommand::ResumeWorkflow(..) => "ResumeWorkflow",
+            ApiCommand::CancelRun(..) => "CancelRun",
+            ApiCommand::SavePromptTemplate { .. } => "SavePromptTemplate",
+            ApiCommand::DeletePromptTemplate { .. } => "DeletePromptTemplate",
+            ApiCommand::RotateTenantKey(_) => "RotateTenantKey",
+            ApiCommand::SetTenantLimits { .. } => "SetTenantLimits",
+            ApiCommand::ListTemplates { .. } => "ListTemplates",
+            ApiCommand::ListNodeTypes { .. } => "ListNodeTypes",
+            ApiCommand::InstantiateTemplate { .. } => "InstantiateTemplate",
+            ApiCommand::InstallPlugin { .. } => "InstallPlugin",
+        }
+    }
+}
+
+/// Why a `TriggerAndWait` run produced no result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RunError {
+    /// The workflow has no start node visible to the tenant.
+    NotFound(String),
+    /// A node routed the run to an `error` port with nothing connected to it.
+    Failed(serde_json::Value),
+    /// No terminal node was reached before the deadline.
+    TimedOut,
+    /// The run was cancelled through `ApiCommand::CancelRun` before it completed.
+    Cancelled,
+    /// The caller may not trigger the workflow.
+    Forbidden(ferroflux_iam::AuthzError),
+    /// The tenant is over its daily run or payload size quota.
+    QuotaExceeded(crate::resources::quotas::QuotaError),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::NotFound(workflow_id) => {
+                write!(f, "No start node found for workflow '{}'", workflow_id)
+            }
+            RunError::Failed(payload) => write!(f, "Run failed: {}", payload),
+            RunError::TimedOut => write!(f, "Run timed out"),
+            RunError::Cancelled => write!(f, "Run was cancelled"),
+            RunError::Forbidden(error) => write!(f, "Forbidden: {}", error),
+            RunError::QuotaExceeded(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+/// Payloads that reached terminal nodes, in arrival order.
+pub type RunOutcome = Result<Vec<serde_json::Value>, RunError>;
+
+/// Reply slot of a command that answers its caller. It is not serialized: a command that went
+/// over the wire arrives with an empty slot, which `remote::RemoteServer` replaces with one
+/// whose outcome it sends back to the client.
+pub struct Reply<T>(std::sync::Arc<std::sync::Mutex<Option<tokio::sync::oneshot::Sender<T>>>>);
+
+/// Reply slot of a `TriggerAndWait`.
+pub type RunReply = Reply<RunOutcome>;
+
+impl<T> Reply<T> {
+    pub fn channel() -> (Self, tokio::sync::oneshot::Receiver<T>) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        (
+            Self(std::sync::Arc::new(std::sync::Mutex::new(Some(tx)))),
+            rx,
+        )
+    }
+
+    /// Delivers the outcome; only the first call has an effect.
+    pub fn send(&self, outcome: T) {
+        if let Some(tx) = self.0.lock().ok().and_then(|mut slot| slot.take()) {
+            let _ = tx.send(outcome);
+        }
+    }
+}
+
+// Not derived, which would require `T: Clone + Default`.
+impl<T> Clone for Reply<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Default for Reply<T> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<T> std::fmt::Debug for Reply<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Reply")
+    }
+}
+
+/// An `ApiCommand` together with the principal that issued it.
+///
+/// The API server resolves the principal (via `IamStore::verify_session`,
+/// `IamStore::authenticate_api_key` or `IamStore::resolve_principal`) when it authenticates a
+/// request; in-process callers use `ApiRequest::system`. The principal is the request's
+/// authorization context: `api_command_worker` checks its role in the command's tenant
+/// against `authz::required_permission` before dispatching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiRequest {
+    pub principal: ferroflux_iam::Principal,
+    pub command: ApiCommand,
+}
+
+impl ApiRequest {
+    pub fn new(principal: ferroflux_iam::Principal, command: ApiCommand) -> Self {
+        Self { principal, command }
+    }
+
+    /// Wraps a command issued by the engine itself (bootstrap, embedded SDK).
+    pub fn system(command: ApiCommand) -> Self {
+        Self::new(ferroflux_iam::Principal::system(), command)
+    }
 }
 
 #[derive(bevy_ecs::prelude::Resource)]
-pub struct ApiReceiver(pub async_channel::Receiver<ApiCommand>);
+pub struct ApiReceiver(pub async_channel::Receiver<ApiRequest>);
 
 #[derive(bevy_ecs::prelude::Resource, Clone, Debug)]
 pub struct PlatformPath(pub std::path::PathBuf);
@@ -0,0 +1,404 @@
+//! WebSocket transport for driving an engine running in another process.
+//!
+//! [`RemoteServer`] accepts WebSocket connections that authenticate with an
+//! `Authorization: Bearer <token>` header, where the token is an API key or a session access
+//! token. Once authenticated the server sends [`ServerFrame::Ready`]. Each text frame from the
+//! client is a [`CommandFrame`], which is dispatched as the authenticated principal; the
+//! server streams back the events the principal may see and, for commands with a reply slot,
+//! a [`ServerFrame::Reply`] carrying the serialized outcome.
+//!
+//! [`connect`] is the client end: it bridges the socket to the same channels an embedded
+//! engine hands out, so code written against `ApiRequest`s and `SystemEvent`s works
+//! unchanged.
+
+use super::events::SystemEvent;
+use super::{ApiCommand, ApiRequest, Reply};
+use crate::components::core::NodeConfig;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::{IamStore, Principal, TenantId};
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use uuid::Uuid;
+
+/// Events buffered for a remote client; matches the engine's own event bus.
+const EVENT_CAPACITY: usize = 100;
+
+/// A command sent by a remote client. `id` is echoed in the command's reply, if it has one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommandFrame {
+    pub id: u64,
+    pub command: ApiCommand,
+}
+
+/// A frame sent by [`RemoteServer`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "frame", rename_all = "snake_case")]
+pub enum ServerFrame {
+    /// The token was accepted; sent once, before anything else.
+    Ready { user_id: String },
+    /// An engine event visible to the connection's principal.
+    Event { event: SystemEvent },
+    /// The outcome of a command with a reply slot, or `None` if the engine dropped it
+    /// unanswered.
+    Reply {
+        id: u64,
+        outcome: Option<serde_json::Value>,
+    },
+}
+
+/// The tenant owning each deployed node, readable outside the ECS.
+///
+/// Node events don't name their tenant; the remote server looks it up here to decide which
+/// connections may see them. Kept current by [`track_node_owners`].
+#[derive(Resource, Clone, Default)]
+pub struct NodeOwners(Arc<RwLock<HashMap<Uuid, TenantId>>>);
+
+impl NodeOwners {
+    pub fn get(&self, node_id: &Uuid) -> Option<TenantId> {
+        self.0.read().ok()?.get(node_id).cloned()
+    }
+}
+
+/// System: rebuilds [`NodeOwners`] whenever nodes are deployed, changed or removed.
+pub fn track_node_owners(
+    owners: Res<NodeOwners>,
+    nodes: Query<&NodeConfig>,
+    changed: Query<(), Changed<NodeConfig>>,
+    mut removed: RemovedComponents<NodeConfig>,
+) {
+    if changed.is_empty() && removed.read().count() == 0 {
+        return;
+    }
+    let index = nodes
+        .iter()
+        .filter_map(|config| Some((config.id, config.tenant_id.clone()?)))
+        .collect();
+    if let Ok(mut owners) = owners.0.write() {
+        *owners = index;
+    }
+}
+
+/// Serves the API over WebSocket for clients created with [`connect`].
+pub struct RemoteServer {
+    iam: IamStore,
+    api_tx: async_channel::Sender<ApiRequest>,
+    events: broadcast::Sender<SystemEvent>,
+    owners: NodeOwners,
+}
+
+impl RemoteServer {
+    /// `owners` is the engine's [`NodeOwners`] resource.
+    pub fn new(
+        iam: IamStore,
+        api_tx: async_channel::Sender<ApiRequest>,
+        events: broadcast::Sender<SystemEvent>,
+        owners: NodeOwners,
+    ) -> Self {
+        Self {
+            iam,
+            api_tx,
+            events,
+            owners,
+        }
+    }
+
+    /// Accepts connections until the listener fails.
+    pub async fn serve(self, listener: TcpListener) -> anyhow::Result<()> {
+        tracing::info!(addr = ?listener.local_addr()?, "Remote API listening");
+        let server = Arc::new(self);
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(stream).await {
+                    tracing::warn!(%peer, error = %e, "Remote API connection failed");
+                }
+            });
+        }
+    }
+
+    // The handshake callback's error type is tungstenite's, large or not.
+    #[allow(clippy::result_large_err)]
+    async fn handle_connection(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut token = None;
+        let mut ws = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+            token = request
+                .headers()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(str::to_string);
+            Ok::<Response, _>(response)
+        })
+        .await?;
+
+        let principal = match &token {
+            Some(token) => self.authenticate(token).await,
+            None => None,
+        };
+        let Some(principal) = principal else {
+            ws.close(Some(CloseFrame {
+                code: CloseCode::Policy,
+                reason: "invalid or missing bearer token".into(),
+            }))
+            .await?;
+            return Ok(());
+        };
+        tracing::info!(user_id = %principal.user_id, "Remote API client connected");
+
+        // Subscribe before `Ready`, so the client sees every event from then on.
+        let mut events = self.events.subscribe();
+        let (mut sink, mut stream) = ws.split();
+        send_frame(
+            &mut sink,
+            &ServerFrame::Ready {
+                user_id: principal.user_id.clone(),
+            },
+        )
+        .await?;
+
+        let (reply_tx, mut reply_rx) = mpsc::unbounded_channel();
+        loop {
+            tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        self.dispatch(&principal, &text, &reply_tx).await?;
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
+                event = events.recv() => match event {
+                    Ok(event) if self.visible_to(&principal, &event) => {
+                        send_frame(&mut sink, &ServerFrame::Event { event }).await?;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Remote API client lagged behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                Some(frame) = reply_rx.recv() => send_frame(&mut sink, &frame).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves an API key or a session access token.
+    async fn authenticate(&self, token: &str) -> Option<Principal> {
+        if let Ok(Some(principal)) = self.iam.authenticate_api_key(token).await {
+            return Some(principal);
+        }
+        self.iam.verify_session(token).await.ok()
+    }
+
+    /// Forwards a command as `principal`, whatever principal the client had in mind.
+    async fn dispatch(
+        &self,
+        principal: &Principal,
+        text: &str,
+        replies: &mpsc::UnboundedSender<ServerFrame>,
+    ) -> anyhow::Result<()> {
+        let CommandFrame { id, mut command } = match serde_json::from_str(text) {
+            Ok(frame) => frame,
+            Err(e) => {
+                tracing::warn!(error = %e, "Ignoring malformed remote command");
+                return Ok(());
+            }
+        };
+        if let Some(outcome) = take_reply(&mut command) {
+            let replies = replies.clone();
+            tokio::spawn(async move {
+                let outcome = outcome.await;
+                let _ = replies.send(ServerFrame::Reply { id, outcome });
+            });
+        }
+        self.api_tx
+            .send(ApiRequest::new(principal.clone(), command))
+            .await?;
+        Ok(())
+    }
+
+    /// System principals see everything; others see the events of their tenants, including
+    /// those about nodes their tenants own. Engine-wide events are not tied to a tenant and
+    /// are only sent to system principals.
+    fn visible_to(&self, principal: &Principal, event: &SystemEvent) -> bool {
+        if principal.is_system {
+            return true;
+        }
+        let tenant = match event.tenant_id() {
+            Some(tenant) => Some(TenantId::from(tenant)),
+            None => event
+                .node_ids()
+                .first()
+                .and_then(|node_id| self.owners.get(node_id)),
+        };
+        tenant.is_some_and(|tenant| principal.role_in(&tenant).is_some())
+    }
+}
+
+async fn send_frame<S>(sink: &mut S, frame: &ServerFrame) -> anyhow::Result<()>
+where
+    S: futures::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    sink.send(Message::Text(serde_json::to_string(frame)?))
+        .await?;
+    Ok(())
+}
+
+/// Connects to a [`RemoteServer`] at `url` (`ws://` or `wss://`) with an API key or session
+/// access token.
+///
+/// Requests sent on the returned channel are forwarded to the server and the server's events
+/// are re-broadcast on the returned sender. A request's principal is ignored: the server acts
+/// as the principal the token authenticates. Reply slots are answered when the server replies,
+/// and closed if the connection drops first.
+pub async fn connect(
+    url: &str,
+    token: &str,
+) -> anyhow::Result<(
+    async_channel::Sender<ApiRequest>,
+    broadcast::Sender<SystemEvent>,
+)> {
+    let mut request = url.into_client_request()?;
+    request
+        .headers_mut()
+        .insert("authorization", format!("Bearer {}", token).parse()?);
+    let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+    let (sink, mut stream) = ws.split();
+
+    match stream.next().await {
+        Some(Ok(Message::Text(text))) => match serde_json::from_str(&text)? {
+            ServerFrame::Ready { user_id } => {
+                tracing::info!(url, %user_id, "Connected to remote engine");
+            }
+            frame => anyhow::bail!("Unexpected first frame from remote engine: {:?}", frame),
+        },
+        Some(Ok(Message::Close(Some(frame)))) => {
+            anyhow::bail!("Remote engine refused the connection: {}", frame.reason)
+        }
+        Some(Err(e)) => return Err(e.into()),
+        _ => anyhow::bail!("Remote engine closed the connection"),
+    }
+
+    let (api_tx, api_rx) = async_channel::unbounded();
+    let (event_tx, _) = broadcast::channel(EVENT_CAPACITY);
+    tokio::spawn(bridge(sink, stream, api_rx, event_tx.clone()));
+    Ok((api_tx, event_tx))
+}
+
+/// Pumps requests out and events and replies in until either side closes.
+async fn bridge<S, R>(
+    mut sink: S,
+    mut stream: R,
+    api_rx: async_channel::Receiver<ApiRequest>,
+    events: broadcast::Sender<SystemEvent>,
+) where
+    S: futures::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+    R: futures::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    // Commands awaiting a reply, by frame id. Holding the command keeps its reply slot.
+    let mut pending: HashMap<u64, ApiCommand> = HashMap::new();
+    let mut next_id = 0;
+    loop {
+        tokio::select! {
+            request = api_rx.recv() => {
+                let Ok(request) = request else { break };
+                next_id += 1;
+                if has_reply(&request.command) {
+                    pending.insert(next_id, request.command.clone());
+                }
+                let frame = CommandFrame { id: next_id, command: request.command };
+                let Ok(text) = serde_json::to_string(&frame) else { continue };
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                    Ok(ServerFrame::Event { event }) => {
+                        let _ = events.send(event);
+                    }
+                    Ok(ServerFrame::Reply { id, outcome }) => {
+                        if let (Some(command), Some(outcome)) = (pending.remove(&id), outcome)
+                            && let Err(e) = deliver_reply(&command, outcome)
+                        {
+                            tracing::warn!(command = command.name(), error = %e, "Unreadable remote reply");
+                        }
+                    }
+                    Ok(ServerFrame::Ready { .. }) => {}
+                    Err(e) => tracing::warn!(error = %e, "Ignoring malformed frame from remote engine"),
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    tracing::warn!("Disconnected from remote engine");
+}
+
+fn has_reply(command: &ApiCommand) -> bool {
+    matches!(
+        command,
+        ApiCommand::TriggerAndWait { .. }
+            | ApiCommand::ListTemplates { .. }
+            | ApiCommand::ListNodeTypes { .. }
+            | ApiCommand::InstantiateTemplate { .. }
+            | ApiCommand::InstallPlugin { .. }
+            | ApiCommand::VerifyConnection { .. }
+    )
+}
+
+/// Gives a command with a reply slot a fresh slot, returning the serialized outcome it will
+/// receive. Replies are not serialized, so a command decoded off the wire has an empty one.
+fn take_reply(command: &mut ApiCommand) -> Option<BoxFuture<'static, Option<serde_json::Value>>> {
+    fn swap<T: Serialize + Send + 'static>(
+        slot: &mut Reply<T>,
+    ) -> BoxFuture<'static, Option<serde_json::Value>> {
+        let (reply, rx) = Reply::channel();
+        *slot = reply;
+        Box::pin(async move { serde_json::to_value(rx.await.ok()?).ok() })
+    }
+
+    match command {
+        ApiCommand::TriggerAndWait { reply, .. } => Some(swap(reply)),
+        ApiCommand::ListTemplates { reply, .. } => Some(swap(reply)),
+        ApiCommand::ListNodeTypes { reply, .. } => Some(swap(reply)),
+        ApiCommand::InstantiateTemplate { reply, .. } => Some(swap(reply)),
+        ApiCommand::InstallPlugin { reply, .. } => Some(swap(reply)),
+        ApiCommand::VerifyConnection { reply, .. } => Some(swap(reply)),
+        _ => None,
+    }
+}
+
+/// Delivers a serialized outcome received from the server to the command's reply slot.
+fn deliver_reply(command: &ApiCommand, outcome: serde_json::Value) -> serde_json::Result<()> {
+    fn deliver<T: DeserializeOwned>(
+        slot: &Reply<T>,
+        outcome: serde_json::Value,
+    ) -> serde_json::Result<()> {
+        slot.send(serde_json::from_value(outcome)?);
+        Ok(())
+    }
+
+    match command {
+        ApiCommand::TriggerAndWait { reply, .. } => deliver(reply, outcome),
+        ApiCommand::ListTemplates { reply, .. } => deliver(reply, outcome),
+        ApiCommand::ListNodeTypes { reply, .. } => deliver(reply, outcome),
+        ApiCommand::InstantiateTemplate { reply, .. } => deliver(reply, outcome),
+        ApiCommand::InstallPlugin { reply, .. } => deliver(reply, outcome),
+        ApiCommand::VerifyConnection { reply, .. } => deliver(reply, outcome),
+        _ => Ok(()),
+    }
+}
@@ -1,17 +1,21 @@
-use crate::api::{ApiCommand, ApiReceiver};
+use crate::api::{ApiCommand, ApiReceiver, ApiRequest};
 use crate::components::{AgentConcurrency, WorkDone};
 use crate::nodes::register_core_nodes;
 use crate::resources::GlobalHttpClient;
+use crate::resources::settings::{EngineSettings, ExecutionMode, RuntimeSettings};
+use crate::resources::sharding::Sharding;
 use crate::store::BlobStore;
 use crate::store::analytics::{AnalyticsBackend, NoopStore};
 use crate::store::batcher::AnalyticsBatcher;
 use crate::store::database::PersistentStore;
+use crate::store::vector::VectorStore;
 use crate::systems::api_worker::api_command_worker;
 use crate::systems::compute::WasmRuntime;
 use crate::systems::gateway;
 use crate::systems::janitor::JanitorTimer;
 use crate::systems::register_core_systems;
 use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::ExecutorKind;
 use bevy_ecs::system::SystemState;
 use rhai::Engine;
 use std::sync::Arc;
@@ -22,6 +26,8 @@ pub struct AppBuilder {
     master_key: Option<Vec<u8>>,
     import_flows: bool,
     analytics_backend: Option<Arc<dyn AnalyticsBackend>>,
+    vector_store: Option<Arc<dyn VectorStore>>,
+    settings: Option<EngineSettings>,
 }
 
 impl Default for AppBuilder {
@@ -38,6 +44,8 @@ impl AppBuilder {
             master_key: None,
             import_flows: true,
             analytics_backend: None,
+            vector_store: None,
+            settings: None,
         }
     }
 
@@ -75,12 +83,25 @@ impl AppBuilder {
         self
     }
 
+    /// Backs Embedding and VectorSearch nodes with `store` instead of the engine database (or
+    /// Qdrant, when the `qdrant` feature is on and `QDRANT_URL` is set).
+    pub fn with_vector_store(mut self, store: Arc<dyn VectorStore>) -> Self {
+        self.vector_store = Some(store);
+        self
+    }
+
+    /// Uses these settings instead of loading them from file, database and environment.
+    pub fn with_settings(mut self, settings: EngineSettings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
     /// Builds the App and returns the App instance along with channels for external communication.
     pub async fn build(
         self,
     ) -> anyhow::Result<(
         App,
-        async_channel::Sender<ApiCommand>,
+        async_channel::Sender<ApiRequest>,
         tokio::sync::broadcast::Sender<crate::api::events::SystemEvent>,
         PersistentStore,
         BlobStore,
@@ -90,7 +111,7 @@ impl AppBuilder {
         Arc<AnalyticsBatcher>,
     )> {
         // 1. Channel for API -> ECS
-        let (api_tx, api_rx) = async_channel::unbounded::<ApiCommand>();
+        let (api_tx, api_rx) = async_channel::unbounded::<ApiRequest>();
 
         // 2. Event Bus
         let (event_tx, _) = tokio::sync::broadcast::channel::<crate::api::events::SystemEvent>(100);
@@ -113,7 +134,10 @@ impl AppBuilder {
 
             for (id, json, _status) in workflows {
                 match api_tx
-                    .send(ApiCommand::LoadGraph(default_tenant.clone(), json))
+                    .send(ApiRequest::system(ApiCommand::LoadGraph(
+                        default_tenant.clone(),
+                        json,
+                    )))
                     .await
                 {
                     Ok(_) => {}
@@ -126,6 +150,15 @@ impl AppBuilder {
 
         let store_server = store.clone();
 
+        // 3.6 Runtime settings
+        let settings = match self.settings {
+            Some(settings) => settings,
+            None => EngineSettings::load(Some(&store)).await?,
+        };
+        let max_agent_requests = settings.concurrency.max_agent_requests;
+        let execution = settings.execution.clone();
+        let runtime_settings = RuntimeSettings::new(settings);
+
         // 4. BlobStore
         let blob_store = BlobStore::default();
         let blob_store_server = blob_store.clone();
@@ -142,11 +175,19 @@ impl AppBuilder {
         });
         let master_key_clone = master_key.clone();
 
+        // Secrets; their redactor masks known secrets in events and analytics
+        let secret_store =
+            crate::secrets::DatabaseSecretStore::new(store.clone(), master_key_clone.clone());
+        let redactor = secret_store.redactor().clone();
+
         // 6.5 Analytics Setup
         let backend = self
             .analytics_backend
             .unwrap_or_else(|| Arc::new(NoopStore));
-        let analytics = Arc::new(AnalyticsBatcher::new(backend));
+        let analytics = Arc::new(
+            AnalyticsBatcher::with_settings(backend, runtime_settings.subscribe())
+                .with_redactor(redactor.clone()),
+        );
 
         // 7. API Server components (returned, not spawned)
         let action_cache = crate::store::cache::IntegrationCache::default();
@@ -155,12 +196,28 @@ impl AppBuilder {
         let mut world = World::new();
         let mut schedule = Schedule::default();
 
+        // 8.1 Execution mode
+        match execution.mode {
+            ExecutionMode::Sequential => {
+                schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+            }
+            ExecutionMode::Sharded => {
+                let sharding = Sharding::new(&execution);
+                tracing::info!(shards = sharding.shards, shard_by = ?sharding.key, "Sharded execution enabled");
+                schedule.set_executor_kind(ExecutorKind::MultiThreaded);
+                world.insert_resource(sharding);
+            }
+        }
+
         world.insert_resource(blob_store.clone());
         world.insert_resource(ApiReceiver(api_rx));
         world.insert_resource(GlobalHttpClient::default());
         world.insert_resource(crate::resources::AgentResultChannel::default());
         world.insert_resource(crate::resources::HttpResultChannel::default());
-        world.insert_resource(crate::api::events::SystemEventBus(event_tx.clone()));
+        world.insert_resource(
+            crate::api::events::SystemEventBus::new(event_tx.clone())
+                .with_redactor(redactor.clone()),
+        );
         world.insert_resource(store.clone());
 
         // Heavy resources
@@ -169,7 +226,7 @@ impl AppBuilder {
 
         // Use current runtime handle
         let runtime_handle = tokio::runtime::Handle::current();
-        world.insert_resource(crate::resources::TokioRuntime(runtime_handle));
+        world.insert_resource(crate::resources::TokioRuntime(runtime_handle.clone()));
 
         // Registry
         world.insert_resource(int_registry.clone());
@@ -177,13 +234,60 @@ impl AppBuilder {
         world.insert_resource(JanitorTimer::default());
         world.insert_resource(WorkDone::default());
         world.insert_resource(crate::resources::NodeRouter::default());
-        world.insert_resource(AgentConcurrency(Arc::new(tokio::sync::Semaphore::new(50))));
+        world.insert_resource(AgentConcurrency(Arc::new(tokio::sync::Semaphore::new(
+            max_agent_requests,
+        ))));
+        world.insert_resource(crate::resources::AgentConcurrencyLimit {
+            limit: max_agent_requests,
+            owed: 0,
+        });
+        world.insert_resource(runtime_settings);
+        world.insert_resource(crate::resources::connection_health::ConnectionHealth::default());
+        world.insert_resource(crate::resources::connection_health::PausedWorkflows::default());
+        world.insert_resource(crate::resources::CancelledRuns::default());
+        world.insert_resource(crate::resources::PendingRuns::default());
+        world.insert_resource(crate::resources::ShadowRuns::default());
+        world.insert_resource(crate::resources::ReplayRuns::default());
+        world.insert_resource(crate::resources::http_policy::HttpClientPolicy::default());
+        world.insert_resource(crate::resources::http_policy::HttpSettings::default());
         world.insert_resource(crate::resources::GraphTopology::default());
         world.insert_resource(crate::resources::templates::TemplateEngine::default());
         world.insert_resource(crate::resources::PipelineResultChannel::default());
+        world.insert_resource(crate::resources::AmqpPendingAcks::default());
+        world.insert_resource(crate::resources::mailer::EmailSender::from_env());
+        world.insert_resource(crate::resources::FileSandbox::default());
+        world.insert_resource(crate::resources::browser::HeadlessBrowser::default());
+        world.insert_resource(crate::resources::SpeechResultChannel::default());
+        world.insert_resource(crate::resources::EmbeddingResultChannel::default());
+        world.insert_resource(crate::resources::VectorSearchResultChannel::default());
+        world.insert_resource(crate::resources::SqlResultChannel::default());
+        world.insert_resource(crate::resources::RedisResultChannel::default());
+        world.insert_resource(crate::resources::EmailResultChannel::default());
+        world.insert_resource(crate::resources::ImapResultChannel::default());
+        world.insert_resource(crate::resources::GeoIpResultChannel::default());
+        let vector_store = self.vector_store.unwrap_or_else(|| {
+            #[cfg(feature = "qdrant")]
+            if let Some(qdrant) = crate::store::vector::qdrant::QdrantVectorStore::from_env() {
+                return Arc::new(qdrant);
+            }
+            Arc::new(store.vector_store())
+        });
+        world.insert_resource(crate::resources::VectorIndex(vector_store));
+        world.insert_resource(crate::resources::prompts::PromptLibrary::load(&store).await?);
+        world.insert_resource(crate::resources::quotas::TenantQuotas::load(&store).await?);
+        if let Some(recorder) = crate::resources::recorder::HttpRecorder::from_env() {
+            world.insert_resource(recorder);
+        }
+        if let Some(profiler) = crate::resources::profiler::EngineProfiler::from_env() {
+            world.insert_resource(profiler);
+        }
         // Create and register ToolRegistry
         let mut tool_registry = crate::tools::registry::ToolRegistry::default();
         crate::tools::register_core_tools(&mut tool_registry);
+        tool_registry.register(crate::tools::primitives::CounterTool::new(
+            store.clone(),
+            runtime_handle.clone(),
+        ));
         world.insert_resource(tool_registry);
 
         world.insert_resource(crate::resources::registry::NodeRegistry::default());
@@ -220,6 +324,31 @@ impl AppBuilder {
         world.insert_resource(crate::api::PlatformPath(platform_path.clone()));
         world.insert_resource(def_registry.clone());
 
+        // Shared sub-graphs referenced by workflow `subgraphs`
+        let mut graph_library = crate::graph_loader::GraphLibrary::default();
+        let library_path = std::path::PathBuf::from(
+            std::env::var("FERROFLUX_GRAPH_LIBRARY").unwrap_or_else(|_| "library".to_string()),
+        );
+        if library_path.exists()
+            && let Err(e) = graph_library.load_from_directory(&library_path)
+        {
+            tracing::error!(path = ?library_path, error = %e, "Failed to load graph library");
+        }
+        world.insert_resource(graph_library);
+
+        // Starter workflow templates
+        let mut templates = crate::templates::TemplateLibrary::default();
+        let templates_path = std::path::PathBuf::from(
+            std::env::var("FERROFLUX_TEMPLATES").unwrap_or_else(|_| "templates".to_string()),
+        );
+        if templates_path.exists()
+            && let Err(e) = templates.load_from_directory(&templates_path)
+        {
+            tracing::error!(path = ?templates_path, error = %e, "Failed to load workflow templates");
+        }
+        world.insert_resource(templates);
+        world.insert_resource(crate::api::remote::NodeOwners::default());
+
         // 10. Bridge YAML to NodeRegistry
         {
             let mut system_state =
@@ -239,12 +368,19 @@ impl AppBuilder {
                 );
             }
         }
+        // Custom nodes from nodes.d/, picked up by the definition watcher and kept in sync
+        world.insert_resource(crate::nodes::loader::NodeDefinitionLoader::from_env());
+        // Global WASM plugin nodes
+        world.insert_resource(crate::nodes::plugin::PluginRegistry::default());
+        let plugins_dir = std::env::var("FERROFLUX_PLUGINS_DIR")
+            .unwrap_or_else(|_| crate::nodes::plugin::DEFAULT_PLUGINS_DIR.to_string());
+        let plugins_dir = std::path::PathBuf::from(plugins_dir);
+        crate::nodes::plugin::load_dir(&mut world, &plugins_dir);
+        world.insert_resource(crate::nodes::plugin::PluginDir(plugins_dir));
 
         // Secrets
-        world.insert_resource(crate::secrets::DatabaseSecretStore::new(
-            store.clone(),
-            master_key_clone.clone(),
-        ));
+        world.insert_resource(redactor);
+        world.insert_resource(secret_store);
 
         // Webhook Queue Initialization (Manual for now, since server is external)
         // But the ingest_worker is registered below.
@@ -265,7 +401,10 @@ impl AppBuilder {
 
         // Register Core Systems
         register_core_systems(&mut schedule);
-        schedule.add_systems(api_command_worker);
+        schedule.add_systems(crate::resources::profiler::profiled(api_command_worker));
+        schedule.add_systems(crate::resources::profiler::profiled(
+            crate::api::remote::track_node_owners,
+        ));
 
         Ok((
             App { world, schedule },
@@ -288,8 +427,10 @@ pub struct App {
 
 impl App {
     pub fn update(&mut self) {
-        self.world.resource_mut::<WorkDone>().0 = false;
+        self.world.resource_mut::<WorkDone>().reset();
+        let start = std::time::Instant::now();
         self.schedule.run(&mut self.world);
+        crate::resources::profiler::record_tick(&mut self.world, start.elapsed());
     }
 
     pub async fn run(mut self) {
@@ -300,7 +441,7 @@ impl App {
             // Always yield to async runtime to allow IO to progress
             tokio::task::yield_now().await;
 
-            if !self.world.resource::<WorkDone>().0 {
+            if !self.world.resource::<WorkDone>().is_set() {
                 tokio::time::sleep(std::time::Duration::from_millis(10)).await;
             }
         }
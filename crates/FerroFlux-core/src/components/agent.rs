@@ -16,6 +16,35 @@ pub enum OutputMode {
     JsonSchema(Value),
 }
 
+impl OutputMode {
+    /// The schema responses are validated against: the node's `ExpectedOutput` for
+    /// `JsonStrict`, the given schema for `JsonSchema`, none for `Text`.
+    pub fn schema(&self, expected: Option<&crate::components::ExpectedOutput>) -> Option<Value> {
+        match self {
+            Self::Text => None,
+            Self::JsonStrict => Some(
+                expected
+                    .map(|expected| expected.json_schema())
+                    .unwrap_or_else(|| serde_json::json!({ "type": "object" })),
+            ),
+            Self::JsonSchema(schema) => Some(schema.clone()),
+        }
+    }
+}
+
+/// Ticket metadata key carrying an [`OutputRepair`] request back to the agent.
+pub const OUTPUT_REPAIR_KEY: &str = "output_repair";
+
+/// A re-prompt after a response failed structured output validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputRepair {
+    /// 1 for the first repair.
+    pub attempt: u32,
+    /// The rejected response.
+    pub output: String,
+    pub violations: Vec<String>,
+}
+
 /// Defines a tool that the agent can invoke.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ToolDefinition {
@@ -65,6 +94,31 @@ pub struct GenerationSettings {
     pub frequency_penalty: Option<f32>,
 }
 
+/// An alternate provider/model an agent can fall back to.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FallbackTarget {
+    pub provider: String,
+    pub model: String,
+    /// Connection for this provider; without one its API key comes from the secret store.
+    #[serde(default)]
+    pub connection_slug: Option<String>,
+}
+
+/// Providers tried in order when the agent's primary provider fails.
+///
+/// A call fails when the request errors, the provider answers with a non-2xx status, or it
+/// runs past `timeout_ms`. The first provider to succeed serves the request; if every entry
+/// fails, the last failure is the result.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct FallbackChain {
+    #[serde(default)]
+    pub targets: Vec<FallbackTarget>,
+    /// Limit for each attempt, the primary included. A streamed response only has to start
+    /// within it.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
 /// Configuration for an AI Agent Node.
 ///
 /// Agents are the autonomous "workers" in a flow, capable of reasoning,
@@ -81,12 +135,23 @@ pub struct AgentConfig {
     /// Template for the user message, supporting variable interpolation.
     #[serde(default = "default_user_prompt_template")]
     pub user_prompt_template: String,
+    /// Prompt library template (`name` or `name@version`) used instead of
+    /// `system_instruction`.
+    #[serde(default)]
+    pub system_prompt_ref: Option<String>,
+    /// Prompt library template used instead of `user_prompt_template`.
+    #[serde(default)]
+    pub user_prompt_ref: Option<String>,
     /// Tunable parameters for the generation stochasticity.
     #[serde(default)]
     pub generation_settings: GenerationSettings,
     /// Format constraints for the response.
     #[serde(default)]
     pub output_mode: OutputMode,
+    /// Times a response that fails `output_mode` validation is sent back to the model with
+    /// the errors before it goes to the `error` port.
+    #[serde(default)]
+    pub max_repair_attempts: u32,
     /// Memory management settings.
     #[serde(default)]
     pub history_config: HistoryConfig,
@@ -103,6 +168,15 @@ pub struct AgentConfig {
     /// If present, this takes precedence over `provider.env_var` lookups.
     #[serde(default)]
     pub connection_slug: Option<String>,
+    /// Syntax of `system_instruction` and `user_prompt_template`.
+    #[serde(default)]
+    pub template_syntax: crate::resources::templates::TemplateSyntax,
+    /// Locale for ICU templates. Falls back to the payload's `locale` field, then `en`.
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Alternate providers for when `provider` errors or times out.
+    #[serde(default)]
+    pub fallback: Option<FallbackChain>,
 }
 
 fn default_system_instruction() -> String {
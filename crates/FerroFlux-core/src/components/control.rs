@@ -10,3 +10,77 @@ pub struct CheckpointConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<u64>,
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CounterOperation {
+    #[default]
+    Increment,
+    Get,
+    Reset,
+}
+
+fn default_counter_amount() -> i64 {
+    1
+}
+
+/// Configuration for a Shared Counter.
+///
+/// Counters are persisted per tenant and updated atomically, so limits such as
+/// "max 100 emails/day" hold across concurrent runs and workflows.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct CounterConfig {
+    /// Counter name. Rendered as a template against the payload (e.g. `emails:{{user.id}}`).
+    pub name: String,
+    #[serde(default)]
+    pub operation: CounterOperation,
+    #[serde(default = "default_counter_amount")]
+    pub amount: i64,
+    /// Restart the counter from zero every `window_seconds` (e.g. 86400 for a daily quota).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<u64>,
+    /// When the counter exceeds this value, the ticket leaves via `limit_exceeded`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    /// Field to write `{ value, exceeded }` to. If None, the payload passes through unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_key: Option<String>,
+}
+
+/// What a Rate Limiter does with a ticket when its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// Leave tickets in the inbox until the queue has room.
+    #[default]
+    Backpressure,
+    /// Discard the ticket.
+    Drop,
+    /// Send the ticket out of the `dead_letter` port.
+    DeadLetter,
+}
+
+fn default_rate_limit_queue() -> usize {
+    1000
+}
+
+/// Configuration for a Rate Limiter (token bucket).
+///
+/// Releases at most `max_per_interval` tickets every `interval_ms`, allowing bursts of up to
+/// `max_per_interval` after a quiet period. Tickets waiting for a token are held in a queue of
+/// `queue_capacity`; `overflow` decides what happens beyond that.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub max_per_interval: u32,
+    pub interval_ms: u64,
+    #[serde(default = "default_rate_limit_queue")]
+    pub queue_capacity: usize,
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+}
+
+/// Token bucket and queued tickets of a Rate Limiter.
+#[derive(Component, Debug, Default)]
+pub struct RateLimitState {
+    pub tokens: f64,
+    pub last_refill: Option<std::time::Instant>,
+    pub queue: std::collections::VecDeque<crate::store::SecureTicket>,
+}
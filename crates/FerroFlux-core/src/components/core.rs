@@ -3,6 +3,7 @@ use ferroflux_iam::TenantId;
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 /// Basic identity for a node in the graph.
@@ -25,6 +26,9 @@ pub struct NodeConfig {
     /// The tenant this node belongs to.
     #[serde(default)]
     pub tenant_id: Option<TenantId>,
+    /// The node type version this node was built with (see `NodeMetadata::version`).
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 /// A component that acts as a "mock" or "override" for a node's output.
@@ -57,6 +61,55 @@ pub struct Inbox {
     pub queue: VecDeque<SecureTicket>,
 }
 
+/// What the transport does with a ticket for an `Inbox` that is already full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Keep the ticket in the upstream node's `Outbox` until there is room.
+    #[default]
+    Block,
+    /// Make room by discarding the oldest ticket waiting in the inbox.
+    DropOldest,
+    /// Write the ticket to disk and feed it back as the inbox drains.
+    Spill,
+}
+
+impl BackpressurePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::DropOldest => "drop_oldest",
+            Self::Spill => "spill",
+        }
+    }
+}
+
+/// Caps a node's `Inbox`. Nodes without it accept tickets without limit.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct InboxCapacity {
+    /// Tickets the inbox holds before the policy applies (at least 1).
+    pub capacity: usize,
+    #[serde(default)]
+    pub policy: BackpressurePolicy,
+}
+
+impl InboxCapacity {
+    pub fn is_full(&self, queued: usize) -> bool {
+        queued >= self.capacity.max(1)
+    }
+}
+
+/// Runtime state behind an `InboxCapacity`.
+#[derive(Component, Debug, Clone, Default)]
+pub struct BackpressureState {
+    /// Spilled ticket files, oldest first.
+    pub spilled: VecDeque<PathBuf>,
+    /// Whether the inbox was full on the last tick; telemetry is sent when this flips on.
+    pub engaged: bool,
+    /// Tickets discarded under `DropOldest`.
+    pub dropped: u64,
+}
+
 /// Holds outgoing data packets waiting to be routed to the next node.
 #[derive(Component, Debug, Clone, Default)]
 pub struct Outbox {
@@ -70,3 +123,17 @@ pub struct Outbox {
 /// based on their evaluation result.
 #[derive(Component, Debug, Clone)]
 pub struct EdgeLabel(pub String);
+
+/// A documentation note placed on the canvas.
+///
+/// Notes have no ports and are never executed; the engine only keeps them so that a
+/// workflow's annotations survive being deployed and loaded back.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct NoteConfig {
+    /// Markdown text.
+    #[serde(default)]
+    pub content: String,
+    /// Background color hint for the canvas (e.g. `#fde68a`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
@@ -30,6 +30,79 @@ pub struct HttpConfig {
     /// Optional slug reference to a secure connection.
     #[serde(default)]
     pub connection_slug: Option<String>,
+    /// Follows the pages of a list endpoint instead of making a single request.
+    #[serde(default)]
+    pub pagination: Option<PaginationConfig>,
+    /// Emits `{status, headers, body, duration_ms}` instead of the body alone, for error
+    /// statuses too. The body is parsed as JSON when it is JSON. Ignored when paginating.
+    #[serde(default)]
+    pub full_response: bool,
+    /// Overrides `HttpSettings::timeout_ms`; 0 for no timeout.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Overrides `HttpSettings::max_redirects`.
+    #[serde(default)]
+    pub max_redirects: Option<usize>,
+    /// Overrides `HttpSettings::proxy`.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Overrides `HttpSettings::no_proxy`.
+    #[serde(default)]
+    pub no_proxy: Option<Vec<String>>,
+}
+
+fn default_cursor_param() -> String {
+    "cursor".to_string()
+}
+
+fn default_start_page() -> u64 {
+    1
+}
+
+fn default_max_pages() -> u32 {
+    10
+}
+
+/// What a paginated HTTP node emits.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PaginationOutput {
+    /// One ticket whose result is an array of every page's items.
+    #[default]
+    Combined,
+    /// One ticket per page, each with that page's items.
+    PerPage,
+}
+
+/// Declarative pagination for REST list endpoints.
+///
+/// The next page comes from the first of these that yields one: the URL at `next_url_field`,
+/// the cursor at `cursor_field` (sent back as the `cursor_param` query parameter), or the
+/// `page_param` query parameter counting up from `start_page`. Paging stops when none does,
+/// when a page has no items, or after `max_pages`. Fields are top-level keys of the response,
+/// or JSON pointers when they start with `/`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PaginationConfig {
+    /// Field holding the page's items. The whole response if unset.
+    #[serde(default)]
+    pub items_field: Option<String>,
+    /// Field holding the cursor of the next page.
+    #[serde(default)]
+    pub cursor_field: Option<String>,
+    #[serde(default = "default_cursor_param")]
+    pub cursor_param: String,
+    /// Field holding the full URL of the next page.
+    #[serde(default)]
+    pub next_url_field: Option<String>,
+    /// Query parameter carrying the page number.
+    #[serde(default)]
+    pub page_param: Option<String>,
+    #[serde(default = "default_start_page")]
+    pub start_page: u64,
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+    #[serde(default)]
+    pub output: PaginationOutput,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
@@ -42,6 +115,19 @@ pub enum Frequency {
     Weekly,
 }
 
+impl Frequency {
+    /// Time between runs; `None` for a one-off.
+    pub fn interval(&self) -> Option<chrono::Duration> {
+        match self {
+            Frequency::Once => None,
+            Frequency::Minutes => Some(chrono::Duration::minutes(1)),
+            Frequency::Hourly => Some(chrono::Duration::hours(1)),
+            Frequency::Daily => Some(chrono::Duration::days(1)),
+            Frequency::Weekly => Some(chrono::Duration::days(7)),
+        }
+    }
+}
+
 /// Configuration for a Cron Node (Time Trigger).
 ///
 /// Initiates workflow execution based on a time schedule.
@@ -57,3 +143,263 @@ pub struct CronConfig {
 fn default_start_at() -> DateTime<Utc> {
     Utc::now()
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+pub enum FileOperation {
+    #[default]
+    Read,
+    /// Creates or truncates the file.
+    Write,
+    Append,
+    /// Lists a directory's entries under `result_key`; an empty path lists the tenant root.
+    List,
+    /// Removes a file or an empty directory.
+    Delete,
+}
+
+/// How file contents map to JSON.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+pub enum FileMode {
+    /// UTF-8 text as a JSON string.
+    #[default]
+    Text,
+    /// Raw bytes as a base64 string.
+    Bytes,
+    /// A JSON document, parsed on read and pretty-printed on write.
+    Json,
+}
+
+fn default_file_result_key() -> String {
+    "content".to_string()
+}
+
+/// Configuration for a File Node.
+///
+/// Reads, writes, lists or deletes files inside the tenant's sandbox directory (see
+/// `FileSandbox`). Writes are subject to the `files` quotas in `EngineSettings`.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileIoConfig {
+    #[serde(default)]
+    pub operation: FileOperation,
+    /// Path relative to the sandbox. Handlebars template rendered against the payload.
+    pub path: String,
+    #[serde(default)]
+    pub mode: FileMode,
+    /// Field holding the data to write. The whole payload is written if unset.
+    #[serde(default)]
+    pub content_field: Option<String>,
+    /// Where a read places the file contents, or a list the directory entries.
+    #[serde(default = "default_file_result_key")]
+    pub result_key: String,
+}
+
+fn default_browser_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_browser_result_key() -> String {
+    "page".to_string()
+}
+
+/// One action of a Browser node's script.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BrowserStep {
+    /// Loads another page. Handlebars template rendered against the payload.
+    Navigate { url: String },
+    /// Waits until a CSS selector matches, failing after `timeout_ms` (the script's remaining
+    /// time if unset).
+    WaitForSelector {
+        selector: String,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// Stores the text of the first match, or of every match when `all` is set, under `key`.
+    ExtractText {
+        selector: String,
+        key: String,
+        #[serde(default)]
+        all: bool,
+    },
+    /// Like `ExtractText`, for an attribute such as `href`.
+    ExtractAttribute {
+        selector: String,
+        attribute: String,
+        key: String,
+        #[serde(default)]
+        all: bool,
+    },
+    /// Captures a PNG into the `BlobStore` and stores its blob id under `key`.
+    Screenshot {
+        key: String,
+        #[serde(default)]
+        full_page: bool,
+    },
+}
+
+/// Configuration for a Browser Node.
+///
+/// Drives a headless browser (the `browser` feature) through `steps`, starting at `url`.
+/// Every page loaded is checked against the network policy, like HTTP requests are.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BrowserConfig {
+    /// The first page to load. Handlebars template rendered against the payload.
+    pub url: String,
+    #[serde(default)]
+    pub steps: Vec<BrowserStep>,
+    /// Limit for the whole script, page loads included.
+    #[serde(default = "default_browser_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Where the extracted values are placed in the payload.
+    #[serde(default = "default_browser_result_key")]
+    pub result_key: String,
+}
+
+fn default_speech_timeout_ms() -> u64 {
+    60_000
+}
+
+fn default_speech_result_key() -> String {
+    "speech".to_string()
+}
+
+/// Which way a Speech node converts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeechDirection {
+    /// Transcribes an audio blob (Whisper-compatible multipart upload).
+    SpeechToText,
+    /// Synthesizes audio from text and checks it into the BlobStore.
+    TextToSpeech,
+}
+
+impl SpeechDirection {
+    /// The integration action a provider must define for this direction.
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::SpeechToText => "speech_to_text",
+            Self::TextToSpeech => "text_to_speech",
+        }
+    }
+
+    fn default_input_field(&self) -> &'static str {
+        match self {
+            Self::SpeechToText => "audio",
+            Self::TextToSpeech => "text",
+        }
+    }
+}
+
+/// Configuration for a Speech Node.
+///
+/// Calls the `speech_to_text` or `text_to_speech` action of `provider` in the integrations
+/// registry. The action's path, headers and body template are rendered with the payload plus
+/// `model`, `voice`, `language` and the resolved credentials (`api_key`, or every field of the
+/// connection).
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SpeechConfig {
+    pub direction: SpeechDirection,
+    /// Integration name, e.g. "openai" or "elevenlabs".
+    pub provider: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Voice id for text-to-speech.
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Language hint for speech-to-text (ISO-639-1).
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Payload field with the audio (`{ "blob_id": .. }` or a blob id) or the text to speak.
+    /// Defaults to `audio` or `text`.
+    #[serde(default)]
+    pub input_field: Option<String>,
+    #[serde(default)]
+    pub connection_slug: Option<String>,
+    #[serde(default = "default_speech_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Where the transcript or the audio reference is placed in the payload.
+    #[serde(default = "default_speech_result_key")]
+    pub result_key: String,
+}
+
+impl SpeechConfig {
+    pub fn input_field(&self) -> &str {
+        self.input_field
+            .as_deref()
+            .unwrap_or_else(|| self.direction.default_input_field())
+    }
+}
+
+fn default_embedding_input_field() -> String {
+    "text".to_string()
+}
+
+fn default_embedding_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_embedding_pointer() -> String {
+    "/data/0/embedding".to_string()
+}
+
+fn default_embedding_result_key() -> String {
+    "embedding".to_string()
+}
+
+/// Configuration for an Embedding Node.
+///
+/// Calls the `embeddings` action of `provider` with the payload plus `model` and `text`, and
+/// places the vector found at `vector_pointer` in the response under `result_key`. With a
+/// `collection` the vector is also upserted into the tenant's vector store, keyed by
+/// `id_field` (or a fresh UUID) and carrying the payload as metadata.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EmbeddingConfig {
+    /// Integration name, e.g. "openai".
+    pub provider: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Payload field holding the text to embed.
+    #[serde(default = "default_embedding_input_field")]
+    pub input_field: String,
+    /// JSON pointer to the vector in the provider's response.
+    #[serde(default = "default_embedding_pointer")]
+    pub vector_pointer: String,
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Payload field used as the record id when indexing.
+    #[serde(default)]
+    pub id_field: Option<String>,
+    #[serde(default)]
+    pub connection_slug: Option<String>,
+    #[serde(default = "default_embedding_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_embedding_result_key")]
+    pub result_key: String,
+}
+
+fn default_top_k() -> usize {
+    5
+}
+
+fn default_vector_search_result_key() -> String {
+    "matches".to_string()
+}
+
+/// Configuration for a VectorSearch Node.
+///
+/// Looks up the `top_k` records of `collection` closest to the vector in `vector_field`
+/// (typically an Embedding node's output) and places them, best first, under `result_key` as
+/// `[{ id, score, metadata }]`.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VectorSearchConfig {
+    pub collection: String,
+    #[serde(default = "default_embedding_result_key")]
+    pub vector_field: String,
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    /// Drops matches scoring below this cosine similarity.
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    #[serde(default = "default_vector_search_result_key")]
+    pub result_key: String,
+}
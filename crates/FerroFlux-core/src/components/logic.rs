@@ -27,3 +27,84 @@ pub struct ScriptConfig {
     #[serde(default)]
     pub result_key: Option<String>,
 }
+
+/// JSON Schema dialect used by a data contract.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+pub enum SchemaDraft {
+    /// Taken from the schema's `$schema` keyword, falling back to 2020-12.
+    #[default]
+    Auto,
+    Draft4,
+    Draft6,
+    Draft7,
+    Draft201909,
+    Draft202012,
+}
+
+fn default_max_violations() -> usize {
+    20
+}
+
+/// Configuration for a Data Contract Node (Logic).
+///
+/// Asserts that payloads match a JSON Schema before they reach downstream nodes.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContractConfig {
+    /// The JSON Schema payloads must satisfy.
+    pub schema: serde_json::Value,
+    #[serde(default)]
+    pub draft: SchemaDraft,
+    /// Field to validate. The whole payload if unset.
+    #[serde(default)]
+    pub target_field: Option<String>,
+    /// Violations reported per payload, to keep error tickets small.
+    #[serde(default = "default_max_violations")]
+    pub max_violations: usize,
+}
+
+/// Compiled form of `ContractConfig::schema`, built on first use.
+#[derive(Component, Default)]
+pub struct ContractState {
+    pub validator: Option<std::sync::Arc<jsonschema::Validator>>,
+}
+
+/// Expression language of a Router node.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+pub enum RouterLanguage {
+    #[default]
+    Jmespath,
+    /// Rhai script with the payload bound to `input`.
+    Rhai,
+}
+
+/// One output of a Router node.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RouteCase {
+    /// Output port the ticket leaves through.
+    pub port: String,
+    /// Results that select this port. Scalars compare loosely, so `"200"` matches `200`.
+    pub values: Vec<serde_json::Value>,
+}
+
+fn default_router_port() -> String {
+    "default".to_string()
+}
+
+/// Configuration for a Router Node (Logic).
+///
+/// Evaluates an expression against each payload and forwards the ticket unchanged to the
+/// port of the first case listing the result, or to `default_port` when none does.
+#[derive(Component, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RouterConfig {
+    /// The expression whose result is matched against the cases (e.g. `order.status`).
+    pub expression: String,
+    #[serde(default)]
+    pub language: RouterLanguage,
+    pub cases: Vec<RouteCase>,
+    /// Fallthrough port for results no case lists.
+    #[serde(default = "default_router_port")]
+    pub default_port: String,
+    /// Forward a copy to every matching case instead of only the first.
+    #[serde(default)]
+    pub match_all: bool,
+}
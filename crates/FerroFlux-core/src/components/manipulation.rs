@@ -80,3 +80,210 @@ pub struct BatchState {
     pub items: Vec<serde_json::Value>,
     pub last_update: Option<std::time::Instant>,
 }
+
+/// Baseline model used by the anomaly detector.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum AnomalyMethod {
+    /// Exponentially weighted moving average and variance.
+    Ewma,
+    /// Expects the value seen one season ago, e.g. the same hour yesterday.
+    SeasonalNaive,
+}
+
+/// Flags values that deviate from a rolling baseline.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// The numeric field to monitor.
+    pub target_field: String,
+    /// Field whose value selects a separate baseline (e.g. "host"). One baseline if unset.
+    #[serde(default)]
+    pub key_field: Option<String>,
+    pub method: AnomalyMethod,
+    /// Smoothing factor (0-1]; higher values adapt faster.
+    #[serde(default = "default_anomaly_alpha")]
+    pub alpha: f64,
+    /// Samples per season for `SeasonalNaive`.
+    #[serde(default = "default_season_length")]
+    pub season_length: usize,
+    /// Score (deviations from the baseline) above which a value is anomalous.
+    pub threshold: f64,
+    /// Samples a baseline needs before it can flag anything.
+    #[serde(default = "default_anomaly_warmup")]
+    pub warmup: usize,
+    /// Where to write the result.
+    pub result_key: String,
+}
+
+fn default_anomaly_alpha() -> f64 {
+    0.3
+}
+
+fn default_season_length() -> usize {
+    24
+}
+
+fn default_anomaly_warmup() -> usize {
+    10
+}
+
+/// Rolling baseline for one key.
+#[derive(Debug, Clone, Default)]
+pub struct AnomalyBaseline {
+    pub count: usize,
+    pub mean: f64,
+    /// Weighted variance of the deviations the score is measured against.
+    pub variance: f64,
+    /// Last `season_length` values, oldest first.
+    pub history: std::collections::VecDeque<f64>,
+}
+
+#[derive(Component, Debug, Default)]
+pub struct AnomalyState {
+    pub baselines: std::collections::HashMap<String, AnomalyBaseline>,
+}
+
+/// Timestamp representations understood by the date/time node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum TimeFormat {
+    /// On input, accepts any of the formats below (numbers as epoch seconds or millis).
+    /// On output, same as `Iso8601`.
+    #[default]
+    Auto,
+    /// RFC 3339 / ISO-8601, e.g. `2024-03-01T12:00:00+01:00`.
+    Iso8601,
+    /// RFC 2822, e.g. `Fri, 01 Mar 2024 12:00:00 +0100`.
+    Rfc2822,
+    EpochSeconds,
+    EpochMillis,
+    /// A `strftime` pattern, e.g. `%d/%m/%Y %H:%M`.
+    Custom(String),
+}
+
+/// Calendar unit a timestamp can be truncated to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TimeUnit {
+    Minute,
+    Hour,
+    Day,
+    /// Monday 00:00.
+    Week,
+    Month,
+}
+
+fn default_datetime_result_key() -> String {
+    "datetime".to_string()
+}
+
+/// Parses, shifts, truncates and formats a timestamp.
+///
+/// Steps run in a fixed order: parse `source_field` (or take the current time), convert to
+/// `timezone`, apply `shift`, apply `truncate`, then format.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct DateTimeConfig {
+    /// Field holding the input timestamp. Uses the current time if unset.
+    #[serde(default)]
+    pub source_field: Option<String>,
+    #[serde(default)]
+    pub input_format: TimeFormat,
+    /// IANA zone for inputs without an offset (e.g. `2024-03-01 09:00`). Defaults to UTC.
+    #[serde(default)]
+    pub input_timezone: Option<String>,
+    /// IANA zone to convert to, e.g. `Europe/Berlin`. Defaults to UTC.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Signed duration such as `+1h 30m` or `-7days`.
+    #[serde(default)]
+    pub shift: Option<String>,
+    /// Truncation happens in `timezone`, so `Day` means local midnight.
+    #[serde(default)]
+    pub truncate: Option<TimeUnit>,
+    #[serde(default)]
+    pub output_format: TimeFormat,
+    #[serde(default = "default_datetime_result_key")]
+    pub result_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum CompressionOperation {
+    #[default]
+    Compress,
+    /// Also extracts zip archives into a `{ entry name: content }` object.
+    Decompress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum CompressionFormat {
+    /// Detected from magic bytes on decompression. Compresses as gzip.
+    #[default]
+    Auto,
+    Gzip,
+    Zstd,
+    /// Extraction only.
+    Zip,
+}
+
+fn default_max_output_bytes() -> u64 {
+    50 * 1024 * 1024
+}
+
+/// Compresses or decompresses payloads.
+///
+/// Compressed data is always carried as base64 inside JSON. Without `source_field` the
+/// whole ticket is the input; without `result_key` the output replaces the ticket.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    #[serde(default)]
+    pub operation: CompressionOperation,
+    #[serde(default)]
+    pub format: CompressionFormat,
+    /// Field holding the input (base64 when decompressing).
+    #[serde(default)]
+    pub source_field: Option<String>,
+    /// How decompressed content is represented.
+    #[serde(default)]
+    pub mode: crate::components::io::FileMode,
+    #[serde(default)]
+    pub result_key: Option<String>,
+    /// Decompression stops with an error past this many bytes, guarding against
+    /// compression bombs. Counted across all entries of a zip archive.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: u64,
+}
+
+fn default_join_timeout_ms() -> u64 {
+    30_000
+}
+
+/// Waits for one ticket on each input port, then emits them as a single object (Fan-In Rejoin).
+///
+/// The counterpart to `SplitConfig` for parallel branches: tickets are grouped by `trace_id`
+/// (or `correlation_field`) and merged into `{ "<port>": payload, ... }` once every port in
+/// `inputs` has delivered. Groups still incomplete after `timeout_ms` leave through the
+/// `error` port as `{ "partial": {...}, "missing": [...] }`, or through the default port as
+/// the partial object when `emit_partial` is set.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct JoinConfig {
+    /// Input ports (edge `target_handle`s) that must each deliver before the join fires.
+    pub inputs: Vec<String>,
+    /// Payload field whose value pairs up the branches. The ticket's `trace_id` if unset.
+    #[serde(default)]
+    pub correlation_field: Option<String>,
+    #[serde(default = "default_join_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub emit_partial: bool,
+}
+
+/// Branches received so far for one correlation key.
+#[derive(Debug, Clone)]
+pub struct PendingJoin {
+    pub started: std::time::Instant,
+    /// Metadata of the first ticket to arrive, carried onto the merged ticket.
+    pub metadata: std::collections::HashMap<String, String>,
+    pub parts: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Component, Debug, Clone, Default)]
+pub struct JoinState {
+    pub pending: std::collections::HashMap<String, PendingJoin>,
+}
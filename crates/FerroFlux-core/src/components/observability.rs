@@ -19,6 +19,10 @@ pub struct TraceStart(pub DateTime<Utc>);
 #[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct TraceInput(pub serde_json::Value);
 
+/// Marks a trace whose run was cancelled, and when.
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct TraceCancelled(pub DateTime<Utc>);
+
 /// Metadata for redaction or sensitivity.
 #[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct Sensitive(pub bool);
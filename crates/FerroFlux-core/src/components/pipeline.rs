@@ -37,6 +37,12 @@ pub struct ExecutionContext {
     pub output_transform: Option<String>,
     pub input_json: Value,
     pub start_time: u64,
+    /// `provider/model` of every attempt that failed before this one, in order.
+    #[serde(default)]
+    pub fallback_from: Vec<String>,
+    /// Structured output repairs made before this request; 0 for the first answer.
+    #[serde(default)]
+    pub repair_attempt: u32,
     // Legacy fields I thought were there but actually arent used or are handled differently?
     // workflow_id and tenant_id were in my previous truncated version.
     // Let's keep them if they are useful or remove them if they break compilation initialization?
@@ -52,6 +58,15 @@ pub struct ReadyToExecute {
     pub body: String,
     pub trace_id: String,
     pub context: ExecutionContext,
+    /// Read the response as a stream of chunks instead of waiting for the full body.
+    #[serde(default)]
+    pub stream: Option<crate::integrations::registry::StreamDef>,
+    /// Limit for the request, streaming included.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Requests to the agent's fallback providers, tried in order if this one fails.
+    #[serde(default)]
+    pub fallbacks: Vec<ReadyToExecute>,
 }
 
 #[derive(Component, Debug, Clone)]
@@ -1,4 +1,5 @@
 use bevy_ecs::prelude::*;
+use serde_json::{Value, json};
 use std::collections::HashSet;
 
 /// Schema Negotiation: What fields this node *needs* from upstream.
@@ -18,3 +19,31 @@ pub struct ExpectedOutput {
     /// Set of fields guaranteed to be present in the output.
     pub aggregated_schema: HashSet<String>,
 }
+
+impl ExpectedOutput {
+    /// A JSON Schema requiring every promised field. Dotted names (`user.email`) become
+    /// required fields of nested objects.
+    pub fn json_schema(&self) -> Value {
+        let mut fields: Vec<&String> = self.aggregated_schema.iter().collect();
+        fields.sort();
+        let mut schema = json!({ "type": "object" });
+        for field in fields {
+            let mut node = &mut schema;
+            for part in field.split('.').filter(|part| !part.is_empty()) {
+                if node["type"].is_null() {
+                    node["type"] = json!("object");
+                }
+                match node["required"].as_array_mut() {
+                    Some(required) if required.contains(&json!(part)) => {}
+                    Some(required) => required.push(json!(part)),
+                    None => node["required"] = json!([part]),
+                }
+                node = &mut node["properties"][part];
+                if node.is_null() {
+                    *node = json!({});
+                }
+            }
+        }
+        schema
+    }
+}
@@ -2,6 +2,9 @@ use bevy_ecs::prelude::Component;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Ticket metadata key set to `"true"` on tickets of a shadow run.
+pub const SHADOW_KEY: &str = "shadow";
+
 /// Configuration for a mock response in Shadow Mode.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MockConfig {
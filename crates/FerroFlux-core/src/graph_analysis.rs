@@ -0,0 +1,289 @@
+//! Static analysis of workflow graphs, run before deploy.
+//!
+//! Flags graph clutter that will never execute:
+//! - **Unreachable nodes**: no path from any trigger node.
+//! - **Unused outputs**: a branching node with some outputs wired and others left dangling.
+//!   Nodes with no wired outputs at all are treated as terminal and not reported. An edge
+//!   without a source handle leaves through the node's default output, its first declared one.
+//! - **Outdated nodes**: saved with an older version of their node type, so their config is
+//!   migrated on load.
+//! - **Deprecated nodes**: of a node type marked deprecated.
+//!
+//! Annotation nodes (notes) are left out of the analysis entirely.
+
+use crate::graph_loader::WorkflowBlueprint;
+use crate::resources::registry::NodeRegistry;
+use crate::traits::node_factory::{NodeMetadata, UNVERSIONED, is_older_version};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GraphWarningKind {
+    UnreachableNode,
+    UnusedOutput { port: String },
+    OutdatedNode { saved: String, current: String },
+    DeprecatedNode { reason: String },
+}
+
+/// A non-fatal finding about a node in the graph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphWarning {
+    /// The UUID of the offending node (maps to `Node::uuid` on the canvas).
+    pub node_id: Uuid,
+    #[serde(flatten)]
+    pub kind: GraphWarningKind,
+    pub message: String,
+}
+
+/// Minimal node view used by the analysis, independent of YAML blueprints or canvas state.
+#[derive(Clone, Debug)]
+pub struct AnalysisNode {
+    pub id: Uuid,
+    pub name: String,
+    pub is_trigger: bool,
+    /// Declared output port names.
+    pub outputs: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalysisEdge {
+    pub source: Uuid,
+    pub source_handle: Option<String>,
+    pub target: Uuid,
+}
+
+/// Whether a node type starts executions (and so roots reachability).
+pub fn is_trigger_type(node_type: &str, metadata: Option<&NodeMetadata>) -> bool {
+    matches!(node_type, "Webhook" | "Cron")
+        || metadata.is_some_and(|m| m.category.eq_ignore_ascii_case("Triggers"))
+}
+
+pub fn analyze(nodes: &[AnalysisNode], edges: &[AnalysisEdge]) -> Vec<GraphWarning> {
+    let mut adjacency: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    let mut wired_ports: HashMap<Uuid, HashSet<Option<&str>>> = HashMap::new();
+    for edge in edges {
+        adjacency.entry(edge.source).or_default().push(edge.target);
+        wired_ports
+            .entry(edge.source)
+            .or_default()
+            .insert(edge.source_handle.as_deref());
+    }
+
+    // BFS from every trigger
+    let mut reachable: HashSet<Uuid> = HashSet::new();
+    let mut queue: VecDeque<Uuid> = nodes
+        .iter()
+        .filter(|n| n.is_trigger)
+        .map(|n| n.id)
+        .collect();
+    while let Some(id) = queue.pop_front() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        if let Some(targets) = adjacency.get(&id) {
+            queue.extend(targets.iter().filter(|t| !reachable.contains(t)));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for node in nodes {
+        if !reachable.contains(&node.id) {
+            warnings.push(GraphWarning {
+                node_id: node.id,
+                kind: GraphWarningKind::UnreachableNode,
+                message: format!("Node '{}' is not reachable from any trigger", node.name),
+            });
+            continue;
+        }
+
+        let Some(wired) = wired_ports.get(&node.id) else {
+            continue;
+        };
+        for (index, port) in node.outputs.iter().enumerate() {
+            let default_wired = index == 0 && wired.contains(&None);
+            if !default_wired && !wired.contains(&Some(port.as_str())) {
+                warnings.push(GraphWarning {
+                    node_id: node.id,
+                    kind: GraphWarningKind::UnusedOutput { port: port.clone() },
+                    message: format!("Output '{}' of node '{}' is not connected", port, node.name),
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// Analyzes a workflow blueprint, resolving triggers and outputs via the node registry.
+pub fn analyze_blueprint(
+    blueprint: &WorkflowBlueprint,
+    registry: &NodeRegistry,
+) -> Vec<GraphWarning> {
+    let nodes: Vec<AnalysisNode> = blueprint
+        .nodes
+        .iter()
+        .filter(|bp| !crate::nodes::is_annotation_type(&bp.node_type))
+        .map(|bp| {
+            let metadata = registry.get(&bp.node_type).map(|f| f.metadata());
+            AnalysisNode {
+                id: bp.id,
+                name: bp.name.clone(),
+                is_trigger: is_trigger_type(&bp.node_type, metadata.as_ref()),
+                outputs: metadata
+                    .map(|m| m.outputs.into_iter().map(|p| p.name).collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+    let edges: Vec<AnalysisEdge> = blueprint
+        .edges
+        .iter()
+        .map(|e| AnalysisEdge {
+            source: e.source_id,
+            source_handle: e.source_handle.clone(),
+            target: e.target_id,
+        })
+        .collect();
+
+    let mut warnings = analyze(&nodes, &edges);
+    warnings.extend(version_warnings(blueprint, registry));
+    warnings
+}
+
+/// Nodes saved with an older version of their node type, and nodes of deprecated types.
+fn version_warnings(blueprint: &WorkflowBlueprint, registry: &NodeRegistry) -> Vec<GraphWarning> {
+    let mut warnings = Vec::new();
+    for bp in &blueprint.nodes {
+        let Some(metadata) = registry.get(&bp.node_type).map(|f| f.metadata()) else {
+            continue;
+        };
+        let saved = bp.version.as_deref().unwrap_or(UNVERSIONED);
+        if let Some(current) = &metadata.version
+            && is_older_version(saved, current)
+        {
+            let message = match &bp.version {
+                Some(saved) => format!(
+                    "Node '{}' was saved with {} v{} and is upgraded to v{}",
+                    bp.name, bp.node_type, saved, current
+                ),
+                None => format!(
+                    "Node '{}' was saved before {} was versioned and is upgraded to v{}",
+                    bp.name, bp.node_type, current
+                ),
+            };
+            warnings.push(GraphWarning {
+                node_id: bp.id,
+                kind: GraphWarningKind::OutdatedNode {
+                    saved: saved.to_string(),
+                    current: current.clone(),
+                },
+                message,
+            });
+        }
+        if let Some(reason) = metadata.deprecated {
+            warnings.push(GraphWarning {
+                node_id: bp.id,
+                message: format!(
+                    "Node '{}' uses deprecated node type {}: {}",
+                    bp.name, bp.node_type, reason
+                ),
+                kind: GraphWarningKind::DeprecatedNode { reason },
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, is_trigger: bool, outputs: &[&str]) -> AnalysisNode {
+        AnalysisNode {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            is_trigger,
+            outputs: outputs.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn edge(source: &AnalysisNode, port: &str, target: &AnalysisNode) -> AnalysisEdge {
+        AnalysisEdge {
+            source: source.id,
+            source_handle: Some(port.to_string()),
+            target: target.id,
+        }
+    }
+
+    #[test]
+    fn test_unreachable_nodes_are_flagged() {
+        let trigger = node("Webhook", true, &["Exec"]);
+        let log = node("Log", false, &[]);
+        let orphan = node("Orphan", false, &["Exec"]);
+        let orphan_child = node("OrphanChild", false, &[]);
+        let edges = vec![
+            edge(&trigger, "Exec", &log),
+            edge(&orphan, "Exec", &orphan_child),
+        ];
+
+        let warnings = analyze(
+            &[trigger, log, orphan.clone(), orphan_child.clone()],
+            &edges,
+        );
+        let unreachable: Vec<Uuid> = warnings
+            .iter()
+            .filter(|w| w.kind == GraphWarningKind::UnreachableNode)
+            .map(|w| w.node_id)
+            .collect();
+        assert_eq!(unreachable, vec![orphan.id, orphan_child.id]);
+    }
+
+    #[test]
+    fn test_unnamed_edges_wire_the_default_output() {
+        let trigger = node("Webhook", true, &["Exec"]);
+        let switch = node("Switch", false, &["true", "false"]);
+        let log = node("Log", false, &[]);
+        let unnamed = |source: &AnalysisNode, target: &AnalysisNode| AnalysisEdge {
+            source: source.id,
+            source_handle: None,
+            target: target.id,
+        };
+        let edges = vec![unnamed(&trigger, &switch), unnamed(&switch, &log)];
+
+        let warnings = analyze(&[trigger, switch.clone(), log], &edges);
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|w| (w.node_id, &w.kind))
+                .collect::<Vec<_>>(),
+            vec![(
+                switch.id,
+                &GraphWarningKind::UnusedOutput {
+                    port: "false".to_string()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_dangling_branch_is_flagged() {
+        let trigger = node("Webhook", true, &["Exec"]);
+        let switch = node("Switch", false, &["true", "false"]);
+        let log = node("Log", false, &["Exec"]);
+        let edges = vec![edge(&trigger, "Exec", &switch), edge(&switch, "true", &log)];
+
+        let warnings = analyze(&[trigger, switch.clone(), log], &edges);
+        // The terminal Log node is not reported; only the dangling "false" branch is.
+        assert_eq!(
+            warnings,
+            vec![GraphWarning {
+                node_id: switch.id,
+                kind: GraphWarningKind::UnusedOutput {
+                    port: "false".to_string()
+                },
+                message: "Output 'false' of node 'Switch' is not connected".to_string(),
+            }]
+        );
+    }
+}
@@ -1,11 +1,21 @@
-use crate::components::{Edge, EdgeLabel, Inbox, NodeConfig, Outbox, SecretConfig};
+use crate::components::{
+    BackpressureState, Edge, EdgeLabel, Inbox, InboxCapacity, NodeConfig, Outbox, SecretConfig,
+};
+use crate::resources::TokioRuntime;
+use crate::resources::prompts::{PromptLibrary, PromptRef, validate_name};
+use crate::store::database::{PersistentStore, PromptTemplate};
+use crate::traits::node_factory::{UNVERSIONED, is_older_version};
 use ferroflux_iam::TenantId;
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Nesting limit for sub-graphs, which also stops include cycles.
+const MAX_SUBGRAPH_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeBlueprint {
     pub source_id: Uuid,
     pub target_id: Uuid,
@@ -15,15 +25,199 @@ pub struct EdgeBlueprint {
 }
 
 /// The structure of the YAML file.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowBlueprint {
     /// List of nodes to spawn.
     pub nodes: Vec<NodeBlueprint>,
     /// List of connections between nodes.
     pub edges: Vec<EdgeBlueprint>,
+    /// Shared library graphs instantiated in this workflow.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subgraphs: Vec<SubGraphBlueprint>,
+}
+
+/// An instance of a [`LibraryGraph`] inside a workflow.
+///
+/// Edges connect to the instance by using its `id` as `source_id`/`target_id` and the
+/// library port name as `source_handle`/`target_handle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubGraphBlueprint {
+    /// Instance id. Also namespaces the UUIDs of the library's nodes, so the same
+    /// library can be used several times and by several workflows.
+    pub id: Uuid,
+    /// Library graph id.
+    pub graph: String,
+    /// Pinned version. The latest version is used if unset.
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+/// A node port exposed by a library graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryPort {
+    pub node: Uuid,
+    /// Handle on that node, e.g. an output port name.
+    #[serde(default)]
+    pub handle: Option<String>,
+}
+
+/// A reusable graph (auth refresh, error notification, ...) maintained in one place.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryGraph {
+    pub id: String,
+    pub version: u32,
+    /// Entry and exit points that including workflows can connect to, by name.
+    #[serde(default)]
+    pub ports: HashMap<String, LibraryPort>,
+    #[serde(flatten)]
+    pub graph: WorkflowBlueprint,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Library graphs available to `subgraphs` references, by id and version.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct GraphLibrary {
+    graphs: HashMap<String, BTreeMap<u32, LibraryGraph>>,
+}
+
+impl GraphLibrary {
+    pub fn register(&mut self, graph: LibraryGraph) {
+        self.graphs
+            .entry(graph.id.clone())
+            .or_default()
+            .insert(graph.version, graph);
+    }
+
+    /// Returns the pinned version, or the latest one.
+    pub fn get(&self, id: &str, version: Option<u32>) -> Option<&LibraryGraph> {
+        let versions = self.graphs.get(id)?;
+        match version {
+            Some(version) => versions.get(&version),
+            None => versions.values().next_back(),
+        }
+    }
+
+    /// Registers every `.yaml`/`.yml` library graph in `path`.
+    pub fn load_from_directory(&mut self, path: &Path) -> anyhow::Result<usize> {
+        let mut count = 0;
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("yaml" | "yml")
+            ) {
+                let graph: LibraryGraph = serde_yaml::from_str(&std::fs::read_to_string(&path)?)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse {:?}: {}", path, e))?;
+                tracing::info!(graph = %graph.id, version = graph.version, "Loaded library graph");
+                self.register(graph);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Replaces every sub-graph instance with the library's nodes and edges.
+///
+/// Library node UUIDs are rewritten to `Uuid::new_v5(instance id, node id)`, and edges that
+/// point at an instance are redirected to the node behind the named port.
+pub fn expand_subgraphs(
+    blueprint: WorkflowBlueprint,
+    library: Option<&GraphLibrary>,
+) -> anyhow::Result<WorkflowBlueprint> {
+    expand(blueprint, library, 0)
+}
+
+fn expand(
+    blueprint: WorkflowBlueprint,
+    library: Option<&GraphLibrary>,
+    depth: usize,
+) -> anyhow::Result<WorkflowBlueprint> {
+    if blueprint.subgraphs.is_empty() {
+        return Ok(blueprint);
+    }
+    if depth >= MAX_SUBGRAPH_DEPTH {
+        anyhow::bail!(
+            "Sub-graphs nested more than {} levels deep (is there a cycle?)",
+            MAX_SUBGRAPH_DEPTH
+        );
+    }
+    let library = library.ok_or_else(|| {
+        anyhow::anyhow!("Workflow uses sub-graphs but no graph library is loaded")
+    })?;
+
+    let WorkflowBlueprint {
+        mut nodes,
+        edges,
+        subgraphs,
+    } = blueprint;
+    let mut expanded_edges = Vec::new();
+    let mut ports: HashMap<Uuid, HashMap<String, LibraryPort>> = HashMap::new();
+
+    for instance in subgraphs {
+        let lib = library
+            .get(&instance.graph, instance.version)
+            .ok_or_else(|| match instance.version {
+                Some(v) => anyhow::anyhow!("Library graph '{}' v{} not found", instance.graph, v),
+                None => anyhow::anyhow!("Library graph '{}' not found", instance.graph),
+            })?;
+        let inner = expand(lib.graph.clone(), Some(library), depth + 1)?;
+        let namespace = |id: Uuid| Uuid::new_v5(&instance.id, id.as_bytes());
+
+        nodes.extend(inner.nodes.into_iter().map(|node| NodeBlueprint {
+            id: namespace(node.id),
+            ..node
+        }));
+        expanded_edges.extend(inner.edges.into_iter().map(|edge| EdgeBlueprint {
+            source_id: namespace(edge.source_id),
+            target_id: namespace(edge.target_id),
+            ..edge
+        }));
+        ports.insert(
+            instance.id,
+            lib.ports
+                .iter()
+                .map(|(name, port)| {
+                    let port = LibraryPort {
+                        node: namespace(port.node),
+                        handle: port.handle.clone(),
+                    };
+                    (name.clone(), port)
+                })
+                .collect(),
+        );
+    }
+
+    let resolve = |id: Uuid, handle: Option<String>| -> anyhow::Result<(Uuid, Option<String>)> {
+        let Some(instance_ports) = ports.get(&id) else {
+            return Ok((id, handle));
+        };
+        let name =
+            handle.ok_or_else(|| anyhow::anyhow!("Edge to sub-graph {} must name a port", id))?;
+        let port = instance_ports
+            .get(&name)
+            .ok_or_else(|| anyhow::anyhow!("Sub-graph {} has no port '{}'", id, name))?;
+        Ok((port.node, port.handle.clone()))
+    };
+    for edge in edges {
+        let (source_id, source_handle) = resolve(edge.source_id, edge.source_handle)?;
+        let (target_id, target_handle) = resolve(edge.target_id, edge.target_handle)?;
+        expanded_edges.push(EdgeBlueprint {
+            source_id,
+            target_id,
+            label: edge.label,
+            source_handle,
+            target_handle,
+        });
+    }
+
+    Ok(WorkflowBlueprint {
+        nodes,
+        edges: expanded_edges,
+        subgraphs: Vec::new(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeBlueprint {
     pub id: Uuid,
     pub name: String,
@@ -32,6 +226,43 @@ pub struct NodeBlueprint {
     pub config: serde_json::Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secret: Option<SecretConfig>,
+    /// Bounds the node's inbox; unbounded when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbox: Option<InboxCapacity>,
+    /// Version of the node type the node was saved with. Older versions are migrated by the
+    /// node type's factory on load, or reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Runs the node type's migration when the node was saved with an older version, updating
+/// its config in place. Returns the version the node is built with: the node type's current
+/// one, or the saved one when the node type is unversioned.
+fn migrate_node(world: &World, tenant: &TenantId, node_bp: &mut NodeBlueprint) -> Option<String> {
+    let factory = world
+        .get_resource::<crate::resources::registry::NodeRegistry>()?
+        .get_for(Some(tenant), &node_bp.node_type)?;
+    let Some(current) = factory.metadata().version else {
+        return node_bp.version.clone();
+    };
+    let saved = node_bp.version.as_deref().unwrap_or(UNVERSIONED);
+    if !is_older_version(saved, &current) {
+        return Some(current);
+    }
+
+    match factory.migrate(saved, &node_bp.config) {
+        Some(Ok(config)) => {
+            tracing::info!(node_id = %node_bp.id, node_type = %node_bp.node_type, from = %saved, to = %current, "Migrated node config");
+            node_bp.config = config;
+        }
+        Some(Err(e)) => {
+            tracing::warn!(node_id = %node_bp.id, node_type = %node_bp.node_type, from = %saved, to = %current, error = %e, "Node config migration failed; using the saved config");
+            // Keep the old version so saving doesn't hide that the config was never upgraded
+            return node_bp.version.clone();
+        }
+        None => {}
+    }
+    Some(current)
 }
 
 pub fn load_graph(world: &mut World, tenant: TenantId, path: &str) -> anyhow::Result<()> {
@@ -43,13 +274,51 @@ pub fn load_graph(world: &mut World, tenant: TenantId, path: &str) -> anyhow::Re
 #[tracing::instrument(skip(world, tenant, yaml))]
 pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> anyhow::Result<()> {
     let blueprint: WorkflowBlueprint = serde_yaml::from_str(yaml)?;
-
-    let mut uuid_map: HashMap<Uuid, Entity> = HashMap::new();
-
     let parsed_id: Option<String> = serde_yaml::from_str::<serde_json::Value>(yaml)
         .ok()
         .and_then(|v| v.get("id").and_then(|s| s.as_str()).map(|s| s.to_string()));
-    let workflow_id_ref = parsed_id.clone();
+    load_blueprint(world, tenant, parsed_id, blueprint)
+}
+
+/// Spawns a workflow, replacing the entities of a loaded workflow with the same id.
+pub fn load_blueprint(
+    world: &mut World,
+    tenant: TenantId,
+    workflow_id: Option<String>,
+    blueprint: WorkflowBlueprint,
+) -> anyhow::Result<()> {
+    let blueprint = expand_subgraphs(blueprint, world.get_resource::<GraphLibrary>())?;
+
+    if let Some(registry) = world.get_resource::<crate::resources::registry::NodeRegistry>() {
+        for warning in crate::graph_analysis::analyze_blueprint(&blueprint, registry) {
+            tracing::warn!(node_id = %warning.node_id, "{}", warning.message);
+        }
+    }
+
+    let mut uuid_map: HashMap<Uuid, Entity> = HashMap::new();
+
+    let workflow_id_ref = workflow_id;
+
+    if let Some(quotas) = world
+        .get_resource::<crate::resources::quotas::TenantQuotas>()
+        .cloned()
+    {
+        // Reloading a workflow replaces it, so it doesn't count against the limit.
+        let loaded: std::collections::HashSet<String> = world
+            .query::<&NodeConfig>()
+            .iter(world)
+            .filter(|conf| conf.tenant_id.as_ref() == Some(&tenant))
+            .filter_map(|conf| conf.workflow_id.clone())
+            .filter(|wf_id| Some(wf_id) != workflow_id_ref.as_ref())
+            .collect();
+        if let Err(e) = quotas.check_workflows(&tenant, loaded.len()) {
+            e.notify(
+                world.get_resource::<crate::api::events::SystemEventBus>(),
+                workflow_id_ref.clone(),
+            );
+            return Err(e.into());
+        }
+    }
 
     // 0. CLEANUP: Despawn existing entities for this workflow
     if let Some(wf_id) = &workflow_id_ref {
@@ -92,11 +361,12 @@ pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> a
     }
 
     // 1. Spawn Nodes
-    for node_bp in blueprint.nodes {
+    for mut node_bp in blueprint.nodes {
         let node_id = node_bp.id;
         let node_name = node_bp.name.clone();
         let node_type = node_bp.node_type.clone();
         let workflow_id = workflow_id_ref.clone();
+        let version = migrate_node(world, &tenant, &mut node_bp);
 
         let entity = world
             .spawn((
@@ -106,6 +376,7 @@ pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> a
                     node_type: node_type.clone(),
                     workflow_id,
                     tenant_id: Some(tenant.clone()),
+                    version,
                 },
                 Inbox::default(),
                 Outbox::default(),
@@ -115,7 +386,7 @@ pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> a
         // Use Registry to add variant-specific components
         world.resource_scope(
             |world, registry: Mut<crate::resources::registry::NodeRegistry>| {
-                if let Some(factory) = registry.get(&node_type) {
+                if let Some(factory) = registry.get_for(Some(&tenant), &node_type) {
                     let mut entity_mut = world.entity_mut(entity);
                     if let Err(e) = factory.build(&mut entity_mut, &node_bp.config) {
                         tracing::error!(node_name = %node_name, error = %e, "Error building node");
@@ -131,6 +402,12 @@ pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> a
             world.entity_mut(entity).insert(secret);
         }
 
+        if let Some(capacity) = node_bp.inbox {
+            world
+                .entity_mut(entity)
+                .insert((capacity, BackpressureState::default()));
+        }
+
         uuid_map.insert(node_id, entity);
         tracing::info!(entity = ?entity, node_name = %node_name, node_type = %node_type, "Spawned Node");
     }
@@ -174,6 +451,18 @@ pub fn load_graph_from_str(world: &mut World, tenant: TenantId, yaml: &str) -> a
 // Step 3 says "Save Logic... Iterate all entities...". I'll implement it for completeness.
 
 pub fn save_graph(world: &mut World, path: &str) -> anyhow::Result<()> {
+    let blueprint = collect_blueprint(world, |_| true);
+    let file = std::fs::File::create(path)?;
+    serde_yaml::to_writer(file, &blueprint)?;
+
+    Ok(())
+}
+
+/// Serializes the spawned nodes accepted by `include`, and the edges between them.
+fn collect_blueprint(
+    world: &mut World,
+    include: impl Fn(&NodeConfig) -> bool,
+) -> WorkflowBlueprint {
     // Basic implementation for now, might need further refactoring for full registry support
     let mut nodes: Vec<NodeBlueprint> = Vec::new();
     let mut edges: Vec<EdgeBlueprint> = Vec::new();
@@ -181,14 +470,17 @@ pub fn save_graph(world: &mut World, path: &str) -> anyhow::Result<()> {
     let node_entities: Vec<(Entity, NodeConfig)> = world
         .query::<(Entity, &NodeConfig)>()
         .iter(world)
+        .filter(|(_, c)| include(c))
         .map(|(e, c)| (e, c.clone()))
         .collect();
+    let included: HashMap<Entity, Uuid> = node_entities.iter().map(|(e, c)| (*e, c.id)).collect();
 
     for (e, node_config) in node_entities {
         let mut config_json = serde_json::json!({});
         world.resource_scope(
             |world, registry: Mut<crate::resources::registry::NodeRegistry>| {
-                if let Some(factory) = registry.get(&node_config.node_type)
+                if let Some(factory) =
+                    registry.get_for(node_config.tenant_id.as_ref(), &node_config.node_type)
                     && let Some(c) = factory.serialize(world, e)
                 {
                     config_json = c;
@@ -202,14 +494,16 @@ pub fn save_graph(world: &mut World, path: &str) -> anyhow::Result<()> {
             node_type: node_config.node_type,
             config: config_json,
             secret: world.get::<SecretConfig>(e).cloned(),
+            inbox: world.get::<InboxCapacity>(e).cloned(),
+            version: node_config.version,
         });
     }
 
     // 2. Query Edges
     let mut edge_query = world.query::<(Entity, &Edge, Option<&EdgeLabel>)>();
     for (_, edge, label) in edge_query.iter(world) {
-        // Resolve Source/Target Entity -> UUID using Config
-        let get_uuid = |e: Entity| -> Option<Uuid> { world.get::<NodeConfig>(e).map(|c| c.id) };
+        // Resolve Source/Target Entity -> UUID, keeping edges between included nodes only
+        let get_uuid = |e: Entity| -> Option<Uuid> { included.get(&e).copied() };
 
         if let (Some(source_id), Some(target_id)) = (get_uuid(edge.source), get_uuid(edge.target)) {
             edges.push(EdgeBlueprint {
@@ -222,9 +516,271 @@ pub fn save_graph(world: &mut World, path: &str) -> anyhow::Result<()> {
         }
     }
 
-    let blueprint = WorkflowBlueprint { nodes, edges };
-    let file = std::fs::File::create(path)?;
-    serde_yaml::to_writer(file, &blueprint)?;
+    WorkflowBlueprint {
+        nodes,
+        edges,
+        subgraphs: Vec::new(),
+    }
+}
 
-    Ok(())
+/// Version of the [`WorkflowBundle`] format written by [`export_workflow`].
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Config keys holding a connection slug, at any depth of a node's config.
+const CONNECTION_SLUG_KEY: &str = "connection_slug";
+/// Config keys holding a `PromptRef`.
+const PROMPT_REF_KEYS: [&str; 2] = ["system_prompt_ref", "user_prompt_ref"];
+
+/// A workflow packaged to be shared between tenants or engines: the graph, the prompt
+/// templates it references and, optionally, its canvas layout. Bundles are plain serde
+/// values, so they can be written as JSON or YAML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowBundle {
+    pub format_version: u32,
+    /// Workflow id at export time.
+    pub id: String,
+    pub workflow: WorkflowBlueprint,
+    /// The prompt template versions the nodes reference.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<PromptTemplate>,
+    /// Slugs of the connections the nodes use. The importing tenant usually names its
+    /// connections differently; see [`ImportOptions::connections`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<String>,
+    /// Canvas layout. Opaque to the engine; the SDK stores a `SavedGraph` here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Id to load the workflow under, instead of the bundle's.
+    pub workflow_id: Option<String>,
+    /// Connection slugs of the bundle, mapped to the importing tenant's. Unmapped slugs are
+    /// kept as they are.
+    pub connections: HashMap<String, String>,
+}
+
+/// An imported workflow.
+#[derive(Debug, Clone)]
+pub struct ImportedWorkflow {
+    pub workflow_id: String,
+    /// New UUID of every node and sub-graph instance, by its UUID in the bundle.
+    pub node_ids: HashMap<Uuid, Uuid>,
+}
+
+/// Packages a loaded workflow of `tenant` with the prompt templates it references.
+pub fn export_workflow(
+    world: &mut World,
+    tenant: &TenantId,
+    workflow_id: &str,
+) -> anyhow::Result<WorkflowBundle> {
+    let mut workflow = collect_blueprint(world, |conf| {
+        conf.tenant_id.as_ref() == Some(tenant) && conf.workflow_id.as_deref() == Some(workflow_id)
+    });
+    if workflow.nodes.is_empty() {
+        anyhow::bail!(
+            "Workflow '{}' is not loaded for tenant '{}'",
+            workflow_id,
+            tenant
+        );
+    }
+    workflow.nodes.sort_by_key(|node| node.id);
+
+    let mut connections = BTreeSet::new();
+    let mut prompt_refs = BTreeSet::new();
+    for node in &mut workflow.nodes {
+        visit_strings(&mut node.config, &mut |key, value| {
+            if key == CONNECTION_SLUG_KEY {
+                connections.insert(value.clone());
+            } else if PROMPT_REF_KEYS.contains(&key) {
+                prompt_refs.insert(value.clone());
+            }
+        });
+    }
+
+    let library = world.get_resource::<PromptLibrary>();
+    let mut prompts: Vec<PromptTemplate> = Vec::new();
+    for prompt_ref in prompt_refs {
+        let prompt: PromptRef = prompt_ref.parse()?;
+        let template = library
+            .and_then(|library| library.resolve(tenant, &prompt))
+            .ok_or_else(|| anyhow::anyhow!("Prompt template '{}' not found", prompt))?;
+        if !prompts
+            .iter()
+            .any(|t| t.name == template.name && t.version == template.version)
+        {
+            prompts.push(template.clone());
+        }
+    }
+    prompts.sort_by(|a, b| (&a.name, a.version).cmp(&(&b.name, b.version)));
+
+    Ok(WorkflowBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        id: workflow_id.to_string(),
+        workflow,
+        prompts,
+        connections: connections.into_iter().collect(),
+        layout: None,
+    })
+}
+
+/// Loads a bundle into `tenant`.
+///
+/// Node UUIDs are replaced with ones derived from the tenant and workflow id, so the same
+/// bundle can be imported by several tenants, and importing it again replaces the workflow.
+/// Prompt templates are added to the tenant's library and version-pinned references follow
+/// them.
+pub fn import_workflow(
+    world: &mut World,
+    tenant: TenantId,
+    bundle: WorkflowBundle,
+    options: &ImportOptions,
+) -> anyhow::Result<ImportedWorkflow> {
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        anyhow::bail!(
+            "Bundle format version {} is newer than the supported version {}",
+            bundle.format_version,
+            BUNDLE_FORMAT_VERSION
+        );
+    }
+    let workflow_id = options.workflow_id.clone().unwrap_or(bundle.id);
+    let mut workflow = bundle.workflow;
+
+    let namespace = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!("{}/{}", tenant, workflow_id).as_bytes(),
+    );
+    let node_ids: HashMap<Uuid, Uuid> = workflow
+        .nodes
+        .iter()
+        .map(|node| node.id)
+        .chain(workflow.subgraphs.iter().map(|instance| instance.id))
+        .map(|id| (id, Uuid::new_v5(&namespace, id.as_bytes())))
+        .collect();
+    let remap = |id: Uuid| node_ids.get(&id).copied().unwrap_or(id);
+
+    let prompt_versions = import_prompts(world, &tenant, bundle.prompts)?;
+
+    for node in &mut workflow.nodes {
+        node.id = remap(node.id);
+        visit_strings(&mut node.config, &mut |key, value| {
+            if key == CONNECTION_SLUG_KEY {
+                if let Some(slug) = options.connections.get(value.as_str()) {
+                    *value = slug.clone();
+                }
+            } else if PROMPT_REF_KEYS.contains(&key)
+                && let Ok(prompt) = value.parse::<PromptRef>()
+                && let Some(version) = prompt.version
+                && let Some(imported) = prompt_versions.get(&(prompt.name.clone(), version))
+            {
+                *value = PromptRef {
+                    name: prompt.name,
+                    version: Some(*imported),
+                }
+                .to_string();
+            }
+        });
+    }
+    for instance in &mut workflow.subgraphs {
+        instance.id = remap(instance.id);
+    }
+    for edge in &mut workflow.edges {
+        edge.source_id = remap(edge.source_id);
+        edge.target_id = remap(edge.target_id);
+    }
+
+    load_blueprint(world, tenant, Some(workflow_id.clone()), workflow)?;
+    Ok(ImportedWorkflow {
+        workflow_id,
+        node_ids,
+    })
+}
+
+/// Adds the templates to the tenant's library, returning the version each got there by its
+/// `(name, version)` in the bundle. Identical versions already in the library are reused,
+/// except that the bundle's latest version of a template must also be the library's latest.
+fn import_prompts(
+    world: &mut World,
+    tenant: &TenantId,
+    mut prompts: Vec<PromptTemplate>,
+) -> anyhow::Result<HashMap<(String, u32), u32>> {
+    // Oldest first, so the bundle's latest version is also the latest after the import.
+    prompts.sort_by(|a, b| (&a.name, a.version).cmp(&(&b.name, b.version)));
+    let store = world.get_resource::<PersistentStore>().cloned();
+    let runtime = world.get_resource::<TokioRuntime>().cloned();
+
+    let mut versions = HashMap::new();
+    for (i, prompt) in prompts.iter().enumerate() {
+        validate_name(&prompt.name)?;
+        let latest_in_bundle = prompts
+            .get(i + 1)
+            .is_none_or(|next| next.name != prompt.name);
+
+        let mut library = world.get_resource_or_insert_with(PromptLibrary::default);
+        let existing: Vec<&PromptTemplate> = library
+            .list(tenant)
+            .into_iter()
+            .filter(|t| t.name == prompt.name)
+            .collect();
+        let latest = existing.last().map(|t| t.version);
+        let identical = existing
+            .iter()
+            .rev()
+            .find(|t| t.template == prompt.template && t.variables == prompt.variables)
+            .map(|t| t.version)
+            .filter(|version| !latest_in_bundle || Some(*version) == latest);
+
+        let version = match (identical, &store, &runtime) {
+            (Some(version), ..) => version,
+            (None, Some(store), Some(runtime)) => {
+                let saved = tokio::task::block_in_place(|| {
+                    runtime.0.block_on(store.save_prompt_template(
+                        tenant,
+                        &prompt.name,
+                        &prompt.template,
+                        &prompt.variables,
+                    ))
+                })?;
+                let version = saved.version;
+                library.insert(tenant, saved);
+                version
+            }
+            // Without a store the template only lives in memory.
+            (None, ..) => {
+                let version = latest.map_or(1, |latest| latest + 1);
+                library.insert(
+                    tenant,
+                    PromptTemplate {
+                        version,
+                        created_at: chrono::Utc::now().to_rfc3339(),
+                        ..prompt.clone()
+                    },
+                );
+                version
+            }
+        };
+        versions.insert((prompt.name.clone(), prompt.version), version);
+    }
+    Ok(versions)
+}
+
+/// Calls `f` with every string value of a JSON object field, and that field's key.
+fn visit_strings(value: &mut serde_json::Value, f: &mut impl FnMut(&str, &mut String)) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    serde_json::Value::String(s) => f(key, s),
+                    other => visit_strings(other, f),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                visit_strings(item, f);
+            }
+        }
+        _ => {}
+    }
 }
@@ -0,0 +1,394 @@
+//! Structural validation of workflow graphs, run before deploy.
+//!
+//! Unlike [`graph_analysis`](crate::graph_analysis), which points out clutter, the
+//! [`GraphValidator`] reports graphs that can't run as drawn:
+//! - **Cycles** through flow edges, which would re-trigger nodes forever.
+//! - **Dangling edges** whose source or target node is missing.
+//! - **Missing ports**: edges naming a port the node type doesn't declare.
+//! - **Type mismatches** between the data types of connected ports.
+//! - **No trigger**: nothing starts the graph on its own. This is only a warning, since nodes
+//!   can still be triggered through the API.
+//!
+//! Port checks need the node's metadata; nodes of unregistered types only get the structural
+//! checks.
+
+use crate::graph_analysis::is_trigger_type;
+use crate::graph_loader::WorkflowBlueprint;
+use crate::resources::registry::NodeRegistry;
+use crate::traits::node_factory::PortMetadata;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Port type of control-flow ports, which only connect to each other.
+pub const FLOW_PORT_TYPE: &str = "flow";
+
+/// Port type that accepts, or can feed, any data port.
+pub const ANY_PORT_TYPE: &str = "any";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The graph should not be deployed.
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    Cycle,
+    DanglingEdge,
+    MissingPort {
+        port: String,
+        /// `true` for an output port, `false` for an input.
+        output: bool,
+    },
+    TypeMismatch {
+        source_type: String,
+        target_type: String,
+    },
+    NoTrigger,
+}
+
+/// A validation finding, with the nodes the canvas should highlight.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphDiagnostic {
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub kind: DiagnosticKind,
+    /// UUIDs of the offending nodes (map to `Node::uuid` on the canvas). Empty for findings
+    /// about the graph as a whole.
+    pub node_ids: Vec<Uuid>,
+    pub message: String,
+}
+
+/// A node as the validator sees it.
+#[derive(Clone, Debug)]
+pub struct ValidationNode {
+    pub id: Uuid,
+    pub name: String,
+    pub is_trigger: bool,
+    /// Declared ports, or `None` when the node type is unknown and its ports can't be checked.
+    pub inputs: Option<Vec<PortMetadata>>,
+    pub outputs: Option<Vec<PortMetadata>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidationEdge {
+    pub source: Uuid,
+    /// Output port name; `None` for the node's default output.
+    pub source_port: Option<String>,
+    pub target: Uuid,
+    /// Input port name; `None` for the node's default input.
+    pub target_port: Option<String>,
+}
+
+/// Validates graphs against the node types known to a [`NodeRegistry`].
+#[derive(Clone, Copy, Default)]
+pub struct GraphValidator<'a> {
+    registry: Option<&'a NodeRegistry>,
+}
+
+impl<'a> GraphValidator<'a> {
+    pub fn new(registry: Option<&'a NodeRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// Describes a node of `node_type`, with its triggers and ports taken from the registry.
+    pub fn node(&self, id: Uuid, name: &str, node_type: &str) -> ValidationNode {
+        let metadata = self
+            .registry
+            .and_then(|registry| registry.get(node_type))
+            .map(|factory| factory.metadata());
+        ValidationNode {
+            id,
+            name: name.to_string(),
+            is_trigger: is_trigger_type(node_type, metadata.as_ref()),
+            inputs: metadata.as_ref().map(|m| m.inputs.clone()),
+            outputs: metadata.map(|m| m.outputs),
+        }
+    }
+
+    /// Validates a workflow blueprint. Subgraphs are expected to be expanded already.
+    pub fn validate_blueprint(&self, blueprint: &WorkflowBlueprint) -> Vec<GraphDiagnostic> {
+        let nodes: Vec<ValidationNode> = blueprint
+            .nodes
+            .iter()
+            .filter(|bp| !crate::nodes::is_annotation_type(&bp.node_type))
+            .map(|bp| self.node(bp.id, &bp.name, &bp.node_type))
+            .collect();
+        let edges: Vec<ValidationEdge> = blueprint
+            .edges
+            .iter()
+            .map(|e| ValidationEdge {
+                source: e.source_id,
+                source_port: e.source_handle.clone(),
+                target: e.target_id,
+                target_port: e.target_handle.clone(),
+            })
+            .collect();
+        self.validate(&nodes, &edges)
+    }
+
+    pub fn validate(
+        &self,
+        nodes: &[ValidationNode],
+        edges: &[ValidationEdge],
+    ) -> Vec<GraphDiagnostic> {
+        let by_id: HashMap<Uuid, &ValidationNode> = nodes.iter().map(|n| (n.id, n)).collect();
+        let mut diagnostics = Vec::new();
+
+        let mut graph = DiGraph::<Uuid, ()>::new();
+        let indices: HashMap<Uuid, _> =
+            nodes.iter().map(|n| (n.id, graph.add_node(n.id))).collect();
+
+        for edge in edges {
+            let (Some(source), Some(target)) = (by_id.get(&edge.source), by_id.get(&edge.target))
+            else {
+                let node_ids: Vec<Uuid> = [edge.source, edge.target]
+                    .into_iter()
+                    .filter(|id| by_id.contains_key(id))
+                    .collect();
+                diagnostics.push(GraphDiagnostic {
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::DanglingEdge,
+                    message: format!(
+                        "Connection {} -> {} references a node that doesn't exist",
+                        edge.source, edge.target
+                    ),
+                    node_ids,
+                });
+                continue;
+            };
+
+            let source_port =
+                resolve_port(source, source.outputs.as_deref(), &edge.source_port, true);
+            let target_port =
+                resolve_port(target, target.inputs.as_deref(), &edge.target_port, false);
+            let (source_port, target_port) = match (source_port, target_port) {
+                (Err(missing), _) | (_, Err(missing)) => {
+                    diagnostics.push(missing);
+                    continue;
+                }
+                (Ok(source_port), Ok(target_port)) => (source_port, target_port),
+            };
+
+            if let (Some(from), Some(to)) = (source_port, target_port)
+                && !compatible(&from.data_type, &to.data_type)
+            {
+                diagnostics.push(GraphDiagnostic {
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::TypeMismatch {
+                        source_type: from.data_type.clone(),
+                        target_type: to.data_type.clone(),
+                    },
+                    node_ids: vec![source.id, target.id],
+                    message: format!(
+                        "Output '{}' of '{}' ({}) can't feed input '{}' of '{}' ({})",
+                        from.name, source.name, from.data_type, to.name, target.name, to.data_type
+                    ),
+                });
+            }
+
+            // Data edges hand over values; only flow edges schedule their target.
+            if source_port.is_none_or(|port| is_flow(&port.data_type)) {
+                graph.add_edge(indices[&source.id], indices[&target.id], ());
+            }
+        }
+
+        for component in tarjan_scc(&graph) {
+            let looped = component.len() > 1 || graph.contains_edge(component[0], component[0]);
+            if !looped {
+                continue;
+            }
+            let mut node_ids: Vec<Uuid> = component.iter().map(|&i| graph[i]).collect();
+            node_ids.sort_by_key(|id| nodes.iter().position(|n| n.id == *id));
+            let names: Vec<&str> = node_ids.iter().map(|id| by_id[id].name.as_str()).collect();
+            diagnostics.push(GraphDiagnostic {
+                severity: Severity::Error,
+                kind: DiagnosticKind::Cycle,
+                message: format!("Nodes form a cycle: {}", names.join(", ")),
+                node_ids,
+            });
+        }
+
+        if !nodes.is_empty() && !nodes.iter().any(|n| n.is_trigger) {
+            diagnostics.push(GraphDiagnostic {
+                severity: Severity::Warning,
+                kind: DiagnosticKind::NoTrigger,
+                node_ids: Vec::new(),
+                message:
+                    "The graph has no trigger node, so it only runs when triggered through the API"
+                        .to_string(),
+            });
+        }
+
+        diagnostics
+    }
+}
+
+/// Whether any diagnostic should block a deploy.
+pub fn has_errors(diagnostics: &[GraphDiagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Finds the port an edge names. `Ok(None)` means the port can't be checked: the node type is
+/// unknown, or the edge uses the default port.
+fn resolve_port<'p>(
+    node: &ValidationNode,
+    ports: Option<&'p [PortMetadata]>,
+    name: &Option<String>,
+    output: bool,
+) -> Result<Option<&'p PortMetadata>, GraphDiagnostic> {
+    let (Some(ports), Some(name)) = (ports, name) else {
+        return Ok(None);
+    };
+    match ports.iter().find(|port| &port.name == name) {
+        Some(port) => Ok(Some(port)),
+        None => Err(GraphDiagnostic {
+            severity: Severity::Error,
+            kind: DiagnosticKind::MissingPort {
+                port: name.clone(),
+                output,
+            },
+            node_ids: vec![node.id],
+            message: format!(
+                "Node '{}' has no {} port '{}'",
+                node.name,
+                if output { "output" } else { "input" },
+                name
+            ),
+        }),
+    }
+}
+
+fn is_flow(data_type: &str) -> bool {
+    data_type.eq_ignore_ascii_case(FLOW_PORT_TYPE)
+}
+
+/// Flow ports only connect to flow ports; `any` matches every data type.
+fn compatible(source: &str, target: &str) -> bool {
+    if is_flow(source) || is_flow(target) {
+        return is_flow(source) && is_flow(target);
+    }
+    source.eq_ignore_ascii_case(ANY_PORT_TYPE)
+        || target.eq_ignore_ascii_case(ANY_PORT_TYPE)
+        || source.eq_ignore_ascii_case(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(name: &str, data_type: &str) -> PortMetadata {
+        PortMetadata {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+        }
+    }
+
+    fn node(name: &str, is_trigger: bool) -> ValidationNode {
+        ValidationNode {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            is_trigger,
+            inputs: Some(vec![port("Exec", "flow"), port("body", "object")]),
+            outputs: Some(vec![port("Exec", "flow"), port("status", "number")]),
+        }
+    }
+
+    fn edge(
+        source: &ValidationNode,
+        from: &str,
+        target: &ValidationNode,
+        to: &str,
+    ) -> ValidationEdge {
+        ValidationEdge {
+            source: source.id,
+            source_port: Some(from.to_string()),
+            target: target.id,
+            target_port: Some(to.to_string()),
+        }
+    }
+
+    fn kinds(diagnostics: &[GraphDiagnostic]) -> Vec<(&DiagnosticKind, &[Uuid])> {
+        diagnostics
+            .iter()
+            .map(|d| (&d.kind, d.node_ids.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn test_flow_cycles_are_errors_but_data_loops_are_not() {
+        let trigger = node("Webhook", true);
+        let a = node("A", false);
+        let b = node("B", false);
+        let edges = vec![
+            edge(&trigger, "Exec", &a, "Exec"),
+            edge(&a, "Exec", &b, "Exec"),
+            edge(&b, "Exec", &a, "Exec"),
+            // Feeding a value back upstream doesn't re-trigger anything.
+            edge(&b, "status", &trigger, "body"),
+        ];
+
+        let diagnostics =
+            GraphValidator::default().validate(&[trigger.clone(), a.clone(), b.clone()], &edges);
+        assert_eq!(
+            kinds(&diagnostics),
+            [
+                (
+                    &DiagnosticKind::TypeMismatch {
+                        source_type: "number".to_string(),
+                        target_type: "object".to_string()
+                    },
+                    [b.id, trigger.id].as_slice()
+                ),
+                (&DiagnosticKind::Cycle, [a.id, b.id].as_slice()),
+            ]
+        );
+        assert!(has_errors(&diagnostics));
+    }
+
+    #[test]
+    fn test_missing_nodes_ports_and_triggers_are_reported() {
+        let a = node("A", false);
+        let b = node("B", false);
+        let unknown = ValidationNode {
+            inputs: None,
+            outputs: None,
+            ..node("Custom", false)
+        };
+        let ghost = Uuid::new_v4();
+        let edges = vec![
+            edge(&a, "Done", &b, "Exec"),
+            ValidationEdge {
+                source: a.id,
+                source_port: Some("Exec".to_string()),
+                target: ghost,
+                target_port: None,
+            },
+            // Ports of unknown node types aren't checked.
+            edge(&a, "Exec", &unknown, "anything"),
+        ];
+
+        let diagnostics = GraphValidator::default().validate(&[a.clone(), b, unknown], &edges);
+        assert_eq!(
+            kinds(&diagnostics),
+            [
+                (
+                    &DiagnosticKind::MissingPort {
+                        port: "Done".to_string(),
+                        output: true
+                    },
+                    [a.id].as_slice()
+                ),
+                (&DiagnosticKind::DanglingEdge, [a.id].as_slice()),
+                (&DiagnosticKind::NoTrigger, [].as_slice()),
+            ]
+        );
+        assert_eq!(diagnostics[2].severity, Severity::Warning);
+    }
+}
@@ -1,3 +1,7 @@
 pub mod registry;
+pub mod validation;
+pub mod verify;
 
 pub use registry::*;
+pub use validation::*;
+pub use verify::*;
@@ -1,3 +1,4 @@
+use super::validation::DefinitionError;
 use anyhow::{Context, Result};
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -50,6 +51,21 @@ pub struct OutputTransform {
     pub tool_calls: Option<String>,
 }
 
+/// How an action streams its completion: each SSE `data:` line (or, without the prefix, each
+/// line of newline-delimited JSON) is a chunk carrying the next piece of text.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct StreamDef {
+    /// JSON pointer to the text delta in a chunk, e.g. `/choices/0/delta/content`.
+    pub delta_pointer: String,
+    /// Chunk that ends the stream.
+    #[serde(default = "default_done_marker")]
+    pub done_marker: String,
+}
+
+fn default_done_marker() -> String {
+    "[DONE]".to_string()
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IntegrationCapabilities {
     #[serde(default)]
@@ -74,6 +90,9 @@ pub struct IntegrationConfig {
     pub headers: HashMap<String, String>,
     /// Template for the request body.
     pub body_template: Option<String>,
+    /// How to follow the pages of a list endpoint.
+    #[serde(default)]
+    pub pagination: Option<crate::components::io::PaginationConfig>,
 }
 
 /// Helper struct for the YAML schema to define implementation details.
@@ -99,6 +118,9 @@ pub struct IntegrationAction {
     pub documentation: Option<String>,
     pub message_transform: Option<String>,
     pub output_transform: Option<OutputTransform>,
+    /// Set for providers that can stream; body templates see `stream: true` when it is.
+    #[serde(default)]
+    pub stream: Option<StreamDef>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -177,9 +199,25 @@ pub struct IntegrationDef {
 #[derive(Resource, Default, Clone)]
 pub struct IntegrationRegistry {
     pub definitions: HashMap<String, IntegrationDef>,
+    /// Definitions rejected at load time, keyed by name (or file name when the YAML doesn't
+    /// parse), with everything found wrong with them.
+    pub invalid: HashMap<String, Vec<DefinitionError>>,
 }
 
 impl IntegrationRegistry {
+    /// Adds a definition if it passes [`IntegrationDef::validate`]; otherwise records it as
+    /// invalid and returns the problems.
+    pub fn register(&mut self, def: IntegrationDef) -> Result<(), Vec<DefinitionError>> {
+        let errors = def.validate();
+        if !errors.is_empty() {
+            self.invalid.insert(def.name.clone(), errors.clone());
+            return Err(errors);
+        }
+        self.invalid.remove(&def.name);
+        self.definitions.insert(def.name.clone(), def);
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn load_from_directory(&mut self, path: &str) -> Result<usize> {
         let dir_path = Path::new(path);
@@ -198,12 +236,34 @@ impl IntegrationRegistry {
                 let content = fs::read_to_string(&path)
                     .with_context(|| format!("Failed to read integration file: {:?}", path))?;
 
-                let def: IntegrationDef = serde_yaml::from_str(&content)
-                    .with_context(|| format!("Failed to parse YAML: {:?}", path))?;
+                let def: IntegrationDef = match serde_yaml::from_str(&content) {
+                    Ok(def) => def,
+                    Err(e) => {
+                        tracing::warn!(path = ?path, error = %e, "Failed to parse integration");
+                        let file = path.file_name().unwrap_or_default().to_string_lossy();
+                        self.invalid.insert(
+                            file.to_string(),
+                            vec![DefinitionError {
+                                location: file.to_string(),
+                                message: format!("invalid YAML: {}", e),
+                            }],
+                        );
+                        continue;
+                    }
+                };
 
-                tracing::info!(integration = %def.name, path = ?path, "Loaded integration");
-                self.definitions.insert(def.name.clone(), def);
-                count += 1;
+                let name = def.name.clone();
+                match self.register(def) {
+                    Ok(()) => {
+                        tracing::info!(integration = %name, path = ?path, "Loaded integration");
+                        count += 1;
+                    }
+                    Err(errors) => {
+                        for error in &errors {
+                            tracing::warn!(integration = %name, path = ?path, %error, "Invalid integration definition");
+                        }
+                    }
+                }
             }
         }
         Ok(count)
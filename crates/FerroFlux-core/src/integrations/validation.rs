@@ -0,0 +1,316 @@
+//! Load-time checks for integration definitions.
+//!
+//! A definition can parse fine and still break every workflow that uses it: a body template
+//! that doesn't compile, a path that references an input nobody provides, an OAuth2 block
+//! without a token URL. [`IntegrationDef::validate`] finds those problems up front so the
+//! registry can reject the definition with a readable report instead.
+
+use super::registry::{AuthDef, AuthType, IntegrationAction, IntegrationDef};
+use handlebars::template::{Parameter, Template, TemplateElement};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Variables the engine adds to the template context of every call, besides the action's
+/// inputs and the connection's fields.
+const CONTEXT_VARIABLES: &[&str] = &[
+    "api_key",
+    "history",
+    "json_mode",
+    "language",
+    "messages",
+    "model",
+    "stream",
+    "system_instruction",
+    "text",
+    "tool_choice",
+    "tools",
+    "user_prompt",
+    "voice",
+];
+
+/// Helpers templates may call: the handlebars built-ins plus the ones the engine registers.
+const HELPERS: &[&str] = &[
+    "and",
+    "each",
+    "eq",
+    "gt",
+    "gte",
+    "if",
+    "is_string",
+    "json",
+    "len",
+    "log",
+    "lookup",
+    "lt",
+    "lte",
+    "ne",
+    "not",
+    "or",
+    "unless",
+    "with",
+];
+
+/// Block helpers whose body is rendered against a different context than the action's.
+const SCOPED_HELPERS: &[&str] = &["each", "with"];
+
+/// A problem found in an integration definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DefinitionError {
+    /// Where the problem is, e.g. `actions.chat.body_template` or `auth`.
+    pub location: String,
+    pub message: String,
+}
+
+impl DefinitionError {
+    fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl IntegrationDef {
+    /// Checks templates, input references, auth and output transforms; empty when the
+    /// definition is usable.
+    pub fn validate(&self) -> Vec<DefinitionError> {
+        let mut errors = Vec::new();
+        self.validate_auth(&mut errors);
+
+        for (group, actions) in [
+            ("actions", &self.actions),
+            ("utilities", &self.utilities),
+            ("resources", &self.resources),
+        ] {
+            let mut names: Vec<_> = actions.keys().collect();
+            names.sort();
+            for name in names {
+                self.validate_action(&format!("{}.{}", group, name), &actions[name], &mut errors);
+            }
+        }
+        errors
+    }
+
+    fn validate_auth(&self, errors: &mut Vec<DefinitionError>) {
+        match &self.auth {
+            Some(AuthDef::ApiKey { key_name, .. }) if key_name.trim().is_empty() => {
+                errors.push(DefinitionError::new(
+                    "auth",
+                    "api_key auth needs a key_name",
+                ));
+            }
+            Some(AuthDef::OAuth2 {
+                grant_type,
+                auth_url,
+                token_url,
+                ..
+            }) => {
+                let missing = |url: &Option<String>| url.as_deref().is_none_or(str::is_empty);
+                match grant_type.as_str() {
+                    "authorization_code" | "client_credentials" => {}
+                    other => errors.push(DefinitionError::new(
+                        "auth.grant_type",
+                        format!("unsupported OAuth2 grant type '{}'", other),
+                    )),
+                }
+                if missing(token_url) {
+                    errors.push(DefinitionError::new(
+                        "auth.token_url",
+                        "OAuth2 auth needs a token_url",
+                    ));
+                }
+                if grant_type == "authorization_code" && missing(auth_url) {
+                    errors.push(DefinitionError::new(
+                        "auth.auth_url",
+                        "the authorization_code grant needs an auth_url",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        let matches = match self.auth_type {
+            AuthType::OAuth2 => matches!(self.auth, Some(AuthDef::OAuth2 { .. })),
+            AuthType::Basic => matches!(self.auth, Some(AuthDef::Basic)),
+            AuthType::ApiKey | AuthType::None => true,
+        };
+        if !matches {
+            errors.push(DefinitionError::new(
+                "auth_type",
+                format!(
+                    "auth_type is {:?} but no matching auth is defined",
+                    self.auth_type
+                ),
+            ));
+        }
+    }
+
+    fn validate_action(
+        &self,
+        location: &str,
+        action: &IntegrationAction,
+        errors: &mut Vec<DefinitionError>,
+    ) {
+        let mut known: HashSet<&str> = CONTEXT_VARIABLES.iter().copied().collect();
+        known.extend(action.inputs.iter().map(|input| input.name.as_str()));
+        known.extend(
+            self.connection_schema
+                .iter()
+                .flatten()
+                .map(|field| field.name.as_str()),
+        );
+
+        let config = &action.implementation.config;
+        let mut templates = vec![("path".to_string(), &config.path)];
+        let mut headers: Vec<_> = config.headers.iter().collect();
+        headers.sort();
+        templates.extend(
+            headers
+                .into_iter()
+                .map(|(name, value)| (format!("headers.{}", name), value)),
+        );
+        templates.extend(
+            config
+                .body_template
+                .iter()
+                .map(|body| ("body_template".to_string(), body)),
+        );
+        templates.extend(
+            action
+                .message_transform
+                .iter()
+                .map(|transform| ("message_transform".to_string(), transform)),
+        );
+        for (field, source) in templates {
+            let location = format!("{}.{}", location, field);
+            match Template::compile(source) {
+                Ok(template) => check_template(&template, &location, &known, errors),
+                Err(e) => errors.push(DefinitionError::new(
+                    location,
+                    format!("template does not compile: {}", e),
+                )),
+            }
+        }
+
+        for input in &action.inputs {
+            if let Some(source) = &input.dynamic_source
+                && !self.actions.contains_key(source)
+                && !self.utilities.contains_key(source)
+            {
+                errors.push(DefinitionError::new(
+                    format!("{}.inputs.{}.dynamic_source", location, input.name),
+                    format!("no action or utility named '{}'", source),
+                ));
+            }
+        }
+
+        if let Some(pagination) = &config.pagination
+            && pagination.cursor_field.is_none()
+            && pagination.next_url_field.is_none()
+            && pagination.page_param.is_none()
+        {
+            errors.push(DefinitionError::new(
+                format!("{}.pagination", location),
+                "needs one of cursor_field, next_url_field or page_param",
+            ));
+        }
+
+        if let Some(transform) = &action.output_transform {
+            for (field, expression) in [
+                ("text", Some(&transform.text)),
+                ("tool_calls", transform.tool_calls.as_ref()),
+            ] {
+                if let Some(expression) = expression
+                    && let Err(e) = jmespath::compile(expression)
+                {
+                    errors.push(DefinitionError::new(
+                        format!("{}.output_transform.{}", location, field),
+                        format!("invalid JMESPath expression: {}", e),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Reports unknown helpers and variables that aren't in `known`.
+fn check_template(
+    template: &Template,
+    location: &str,
+    known: &HashSet<&str>,
+    errors: &mut Vec<DefinitionError>,
+) {
+    for element in &template.elements {
+        check_element(element, location, known, errors);
+    }
+}
+
+fn check_element(
+    element: &TemplateElement,
+    location: &str,
+    known: &HashSet<&str>,
+    errors: &mut Vec<DefinitionError>,
+) {
+    let helper = match element {
+        TemplateElement::Expression(helper)
+        | TemplateElement::HtmlExpression(helper)
+        | TemplateElement::HelperBlock(helper) => helper,
+        _ => return,
+    };
+    let name = helper.name.as_name().unwrap_or_default();
+    let is_call = helper.block || !helper.params.is_empty() || !helper.hash.is_empty();
+
+    if !is_call && !HELPERS.contains(&name) {
+        check_variable(&helper.name, location, known, errors);
+        return;
+    }
+    if !HELPERS.contains(&name) {
+        errors.push(DefinitionError::new(
+            location,
+            format!("unknown helper '{}'", name),
+        ));
+    }
+    for param in helper.params.iter().chain(helper.hash.values()) {
+        check_variable(param, location, known, errors);
+    }
+    if let Some(body) = &helper.template
+        && !SCOPED_HELPERS.contains(&name)
+    {
+        check_template(body, location, known, errors);
+    }
+    if let Some(inverse) = &helper.inverse {
+        check_template(inverse, location, known, errors);
+    }
+}
+
+fn check_variable(
+    param: &Parameter,
+    location: &str,
+    known: &HashSet<&str>,
+    errors: &mut Vec<DefinitionError>,
+) {
+    match param {
+        Parameter::Subexpression(sub) => check_element(sub.as_element(), location, known, errors),
+        Parameter::Literal(_) => {}
+        _ => {
+            let raw = param.as_name().unwrap_or_default();
+            if raw.is_empty() || raw.starts_with(['@', '.']) || raw.starts_with("this") {
+                return;
+            }
+            let root = raw.split(['.', '/', '[']).next().unwrap_or(raw);
+            if !known.contains(root) {
+                errors.push(DefinitionError::new(
+                    location,
+                    format!("references undefined input '{}'", root),
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Credential checks for stored connections.
+//!
+//! An integration names a cheap authenticated call in `verify_endpoint` (e.g. `/models`).
+//! [`IntegrationDef::verify`] makes that call with a connection's credentials so a "Test
+//! connection" button can tell whether they work before a workflow depends on them.
+
+use super::registry::{AuthDef, IntegrationDef};
+use crate::systems::io::speech::authorize;
+use crate::systems::io::templating::apply_template;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// How long a verification call may take before the connection counts as failing.
+pub const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of checking a connection against its integration's `verify_endpoint`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionVerification {
+    /// Whether the provider accepted the credentials.
+    pub verified: bool,
+    /// The status recorded for the connection: `active` or `error`.
+    pub status: String,
+    /// HTTP status of the verification call, when there was a response.
+    pub http_status: Option<u16>,
+    /// Why verification failed.
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl ConnectionVerification {
+    fn new(http_status: Option<u16>, error: Option<String>, start: Instant) -> Self {
+        let verified = error.is_none();
+        Self {
+            verified,
+            status: if verified { "active" } else { "error" }.to_string(),
+            http_status,
+            error,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+impl IntegrationDef {
+    /// Calls `verify_endpoint` with a connection's `credentials` (its decrypted fields).
+    ///
+    /// The API key is the field named by `verify_params.api_key`, falling back to `api_key`,
+    /// and is applied the way the integration's `auth` says. Fails only when the integration
+    /// has no `verify_endpoint`; a rejected or unreachable call is an unverified result.
+    pub async fn verify(
+        &self,
+        client: &reqwest::Client,
+        credentials: &Value,
+    ) -> anyhow::Result<ConnectionVerification> {
+        let endpoint = self
+            .verify_endpoint
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Integration '{}' has no verify_endpoint", self.name))?;
+
+        let mut context = credentials.as_object().cloned().unwrap_or_default();
+        let key_field = self
+            .verify_params
+            .get("api_key")
+            .map(String::as_str)
+            .unwrap_or("api_key");
+        if let Some(key) = credentials.get(key_field) {
+            context.insert("api_key".to_string(), key.clone());
+        }
+        let context = Value::Object(context);
+
+        let url = format!("{}{}", self.base_url, apply_template(endpoint, &context));
+        let request = client.get(&url).timeout(VERIFY_TIMEOUT);
+        let request = match &self.auth {
+            Some(AuthDef::Basic) => request.basic_auth(
+                field(&context, "username"),
+                context.get("password").and_then(Value::as_str),
+            ),
+            Some(AuthDef::OAuth2 { .. }) => request.bearer_auth(field(&context, "access_token")),
+            auth => authorize(request, auth.as_ref(), &Default::default(), &context),
+        };
+
+        let start = Instant::now();
+        Ok(match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                ConnectionVerification::new(Some(response.status().as_u16()), None, start)
+            }
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                let detail: String = body.chars().take(200).collect();
+                ConnectionVerification::new(
+                    Some(status.as_u16()),
+                    Some(format!(
+                        "{} returned {}: {}",
+                        endpoint,
+                        status,
+                        detail.trim()
+                    )),
+                    start,
+                )
+            }
+            Err(e) => ConnectionVerification::new(None, Some(request_error(&e).to_string()), start),
+        })
+    }
+}
+
+/// Describes a failed call by its kind only: reqwest's message quotes the URL, and with it
+/// any credential templated into the endpoint.
+fn request_error(e: &reqwest::Error) -> &'static str {
+    if e.is_timeout() {
+        "Verification request timed out"
+    } else if e.is_connect() {
+        "Could not connect to the provider"
+    } else if e.is_redirect() {
+        "Verification request was redirected too often"
+    } else if e.is_builder() {
+        "Invalid verification request"
+    } else {
+        "Verification request failed"
+    }
+}
+
+fn field<'a>(context: &'a Value, name: &str) -> &'a str {
+    context
+        .get(name)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
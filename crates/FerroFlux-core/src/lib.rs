@@ -6,14 +6,18 @@
 pub mod api;
 pub mod app;
 pub mod components;
+pub mod graph_analysis;
 pub mod graph_loader;
+pub mod graph_validation;
 pub mod integrations;
 pub mod nodes;
 pub mod resources;
+pub mod schedule_calendar;
 pub mod schema;
 pub mod secrets;
 pub mod store;
 pub mod systems;
+pub mod templates;
 pub mod traits;
 
 pub mod tools;
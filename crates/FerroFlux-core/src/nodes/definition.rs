@@ -30,6 +30,9 @@ pub struct NodeMeta {
     pub node_type: String, // Action, Trigger, Utility, etc.
     pub description: Option<String>,
     pub version: Option<String>,
+    /// Marks the node type as deprecated, with the reason or its replacement.
+    #[serde(default)]
+    pub deprecated: Option<String>,
     pub platform: Option<String>,
     pub data_strategy: Option<String>, // enrich, replace, split, aggregate
 }
@@ -0,0 +1,250 @@
+//! Custom nodes from a directory of YAML definitions, reloaded while the engine runs.
+//!
+//! The [`NodeDefinitionLoader`] resource points at a directory (`nodes.d/` by default) of
+//! `NodeDefinition` files: metadata, ports and settings, with an `execution` pipeline built
+//! from tools such as `http_client` or `rhai`. [`node_definition_watcher`] rescans it every
+//! interval and registers a [`YamlNodeFactory`] for each definition that appeared or changed,
+//! unregisters the ones whose file was deleted, and broadcasts
+//! [`SystemEvent::RegistryChanged`] so editors can refresh their node palettes.
+//!
+//! A file that fails to parse is reported and skipped; the node it defined before, if any,
+//! stays registered until the file is fixed or removed.
+
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::nodes::definition::NodeDefinition;
+use crate::nodes::yaml_factory::YamlNodeFactory;
+use crate::resources::registry::{DefinitionRegistry, NodeRegistry};
+use bevy_ecs::prelude::*;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Directory scanned when `FERROFLUX_NODES_DIR` is not set.
+pub const DEFAULT_NODES_DIR: &str = "nodes.d";
+/// Default time between scans.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What one scan changed in the registry. Node type ids are sorted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegistryChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// Files that could not be read or parsed, with the reason.
+    pub errors: Vec<String>,
+}
+
+impl RegistryChanges {
+    /// Whether the registry is unchanged. Errors alone don't count.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A definition file as of the last scan.
+#[derive(Clone, Debug)]
+struct LoadedFile {
+    content_hash: u64,
+    /// The node type it registered; `None` while it has never parsed.
+    node_type: Option<String>,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct NodeDefinitionLoader {
+    dir: PathBuf,
+    interval: Duration,
+    last_scan: Option<Instant>,
+    files: HashMap<PathBuf, LoadedFile>,
+}
+
+impl NodeDefinitionLoader {
+    /// Watches `dir`, which may not exist yet.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            interval: DEFAULT_INTERVAL,
+            last_scan: None,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Rescans every `interval`. `Duration::ZERO` rescans on every tick.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Watches `FERROFLUX_NODES_DIR`, or `nodes.d` in the working directory.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("FERROFLUX_NODES_DIR").unwrap_or_else(|_| DEFAULT_NODES_DIR.to_string()),
+        )
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn is_due(&self) -> bool {
+        self.last_scan
+            .is_none_or(|last| last.elapsed() >= self.interval)
+    }
+
+    /// Forgets what was loaded, so the next scan registers every definition again. Used after
+    /// the registry was rebuilt from scratch.
+    pub fn reset(&mut self) {
+        self.files.clear();
+        self.last_scan = None;
+    }
+
+    /// Brings `nodes` and `definitions` in line with the directory.
+    pub fn scan(
+        &mut self,
+        nodes: &mut NodeRegistry,
+        definitions: &mut DefinitionRegistry,
+    ) -> RegistryChanges {
+        self.last_scan = Some(Instant::now());
+        let mut changes = RegistryChanges::default();
+
+        let mut paths = Vec::new();
+        if let Err(e) = collect_yaml_files(&self.dir, &mut paths)
+            && self.dir.exists()
+        {
+            changes
+                .errors
+                .push(format!("{}: {}", self.dir.display(), e));
+            // Don't treat an unreadable directory as every file being deleted
+            return changes;
+        }
+
+        let mut previous = std::mem::take(&mut self.files);
+        for path in paths {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    changes.errors.push(format!("{}: {}", path.display(), e));
+                    if let Some(file) = previous.remove(&path) {
+                        self.files.insert(path, file);
+                    }
+                    continue;
+                }
+            };
+            let content_hash = hash(&content);
+            let old = previous.remove(&path);
+            if let Some(old) = old.as_ref().filter(|old| old.content_hash == content_hash) {
+                self.files.insert(path, old.clone());
+                continue;
+            }
+
+            let old_type = old.and_then(|old| old.node_type);
+            let node_type = match serde_yaml::from_str::<NodeDefinition>(&content) {
+                Ok(definition) => {
+                    let id = definition.meta.id.clone();
+                    if let Some(old_type) = old_type.as_ref().filter(|old| **old != id) {
+                        unregister(nodes, definitions, old_type);
+                        changes.removed.push(old_type.clone());
+                    }
+                    if nodes.get(&id).is_some() {
+                        changes.updated.push(id.clone());
+                    } else {
+                        changes.added.push(id.clone());
+                    }
+                    nodes.register(&id, Box::new(YamlNodeFactory::new(definition.clone())));
+                    definitions.definitions.insert(id.clone(), definition);
+                    Some(id)
+                }
+                Err(e) => {
+                    changes.errors.push(format!("{}: {}", path.display(), e));
+                    old_type
+                }
+            };
+            self.files.insert(
+                path,
+                LoadedFile {
+                    content_hash,
+                    node_type,
+                },
+            );
+        }
+
+        // Files that are gone
+        for file in previous.into_values() {
+            if let Some(node_type) = file.node_type {
+                unregister(nodes, definitions, &node_type);
+                changes.removed.push(node_type);
+            }
+        }
+
+        changes.added.sort();
+        changes.updated.sort();
+        changes.removed.sort();
+        changes
+    }
+}
+
+fn unregister(nodes: &mut NodeRegistry, definitions: &mut DefinitionRegistry, node_type: &str) {
+    nodes.unregister(node_type);
+    definitions.definitions.remove(node_type);
+}
+
+fn hash(content: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Appends the `.yaml`/`.yml` files under `dir`, recursively.
+fn collect_yaml_files(dir: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_yaml_files(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Rescans the node definition directory when due and announces what changed.
+pub fn node_definition_watcher(
+    loader: Option<ResMut<NodeDefinitionLoader>>,
+    nodes: Option<ResMut<NodeRegistry>>,
+    definitions: Option<ResMut<DefinitionRegistry>>,
+    bus: Option<Res<SystemEventBus>>,
+) {
+    let (Some(mut loader), Some(mut nodes), Some(mut definitions)) = (loader, nodes, definitions)
+    else {
+        return;
+    };
+    if !loader.is_due() {
+        return;
+    }
+
+    let changes = loader.scan(&mut nodes, &mut definitions);
+    for error in &changes.errors {
+        tracing::warn!(%error, "Skipping invalid node definition");
+    }
+    if changes.is_empty() {
+        return;
+    }
+    tracing::info!(
+        added = changes.added.len(),
+        updated = changes.updated.len(),
+        removed = changes.removed.len(),
+        "Node definitions reloaded"
+    );
+    if let Some(bus) = bus {
+        let _ = bus.send(SystemEvent::RegistryChanged {
+            added: changes.added,
+            updated: changes.updated,
+            removed: changes.removed,
+            tenant_id: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+}
@@ -3,8 +3,18 @@ use bevy_ecs::prelude::*;
 use serde_json::Value;
 
 pub mod definition;
+pub mod loader;
+pub mod plugin;
 pub mod yaml_factory;
 
+/// Node type of canvas notes. See [`NoteNodeFactory`].
+pub const NOTE_NODE_TYPE: &str = "note";
+
+/// Whether a node type is documentation only (never executed or analyzed).
+pub fn is_annotation_type(node_type: &str) -> bool {
+    node_type.eq_ignore_ascii_case(NOTE_NODE_TYPE)
+}
+
 pub struct IntegrationNodeFactory;
 impl NodeFactory for IntegrationNodeFactory {
     fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
@@ -37,6 +47,12 @@ impl NodeFactory for IntegrationNodeFactory {
                 method: action_config.method.clone(),
                 result_key: None,
                 connection_slug: None,
+                pagination: action_config.pagination.clone(),
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
             };
 
             let requirements = crate::components::schema::Requirements {
@@ -118,6 +134,40 @@ impl NodeFactory for IntegrationNodeFactory {
             inputs: vec![],
             outputs: vec![],
             settings: vec![],
+            version: None,
+            deprecated: None,
+        }
+    }
+}
+
+/// Markdown note. Does nothing at runtime, but round-trips its content and color.
+pub struct NoteNodeFactory;
+impl NodeFactory for NoteNodeFactory {
+    fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
+        let c: crate::components::NoteConfig = serde_json::from_value(config.clone())?;
+        entity.insert(c);
+        Ok(())
+    }
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Value> {
+        world
+            .get::<crate::components::NoteConfig>(entity)
+            .map(|c| serde_json::to_value(c).unwrap_or(Value::Null))
+    }
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            id: NOTE_NODE_TYPE.to_string(),
+            name: "Note".to_string(),
+            category: "Documentation".to_string(),
+            platform: None,
+            description: Some("Markdown note for documenting a workflow.".to_string()),
+            inputs: vec![],
+            outputs: vec![],
+            settings: vec![
+                serde_json::json!({ "name": "content", "label": "Content", "type": "markdown" }),
+                serde_json::json!({ "name": "color", "label": "Color", "type": "color" }),
+            ],
+            version: None,
+            deprecated: None,
         }
     }
 }
@@ -125,7 +175,8 @@ impl NodeFactory for IntegrationNodeFactory {
 // System to register nodes (can also be called manually)
 pub fn register_core_nodes(registry: &mut crate::resources::registry::NodeRegistry) {
     println!("DEBUG: registering core nodes");
-    // We only register the Integration bridge for now.
+    // Only the Integration bridge and notes are hardcoded.
     // All other core nodes are loaded via YAML from the platforms/ directory.
     registry.register("integration", Box::new(IntegrationNodeFactory));
+    registry.register(NOTE_NODE_TYPE, Box::new(NoteNodeFactory));
 }
@@ -0,0 +1,476 @@
+//! Node types implemented by WebAssembly plugins.
+//!
+//! A plugin is a compiled WASM module that brings its own node type, so third parties can ship
+//! nodes without rebuilding the engine. Plugins are compiled with the engine of the shared
+//! [`WasmRuntime`] and installed either globally, from the `plugins.d/` directory at startup,
+//! or for one tenant through `ApiCommand::InstallPlugin`. A tenant's plugins are registered
+//! under its own namespace of the `NodeRegistry`: they are only listed for and built in its
+//! own workflows, and take precedence over a global plugin of the same node type there.
+//! Tenant installs are written to `tenants/<tenant>/` below the [`PluginDir`], and loaded
+//! back with the global plugins at startup.
+//!
+//! # ABI
+//!
+//! Values cross the boundary as UTF-8 JSON in the module's linear memory. A function returning
+//! JSON returns an `i64` packing the pointer in its high 32 bits and the length in its low 32.
+//! The module exports:
+//!
+//! - `memory`: its linear memory.
+//! - `alloc(len: i32) -> i32`: reserves `len` bytes for the engine to write an argument into.
+//! - `metadata() -> i64`: the node type's `NodeMetadata`. `id` names the node type.
+//! - `build(ptr: i32, len: i32) -> i64` (optional): validates a node's settings when a
+//!   workflow is deployed. Returning an object with an `error` string rejects the node.
+//! - `execute(ptr: i32, len: i32) -> i64`: runs the node on `{"settings": .., "input": ..}`
+//!   and returns the output payload.
+//!
+//! WASI is linked so modules built for `wasm32-wasi` instantiate, but without arguments,
+//! environment, stdio or preopened directories. Every call runs in a fresh instance with a
+//! fuel budget and a memory cap, so nothing carries over between calls, nodes or tenants.
+
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::NodeConfig;
+use crate::resources::registry::NodeRegistry;
+use crate::systems::compute::WasmRuntime;
+use crate::traits::node_factory::{NodeFactory, NodeMetadata};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use wasi_common::WasiCtx;
+use wasi_common::sync::WasiCtxBuilder;
+use wasmtime::{Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Directory of global plugins when `FERROFLUX_PLUGINS_DIR` is not set.
+pub const DEFAULT_PLUGINS_DIR: &str = "plugins.d";
+/// Fuel for one call, roughly the 2 seconds the compute worker allows.
+const PLUGIN_FUEL: u64 = 100_000_000;
+/// Largest linear memory a plugin instance may grow to.
+const PLUGIN_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+/// Largest JSON value a plugin may return.
+const PLUGIN_OUTPUT_LIMIT: usize = 16 * 1024 * 1024;
+
+/// Directory plugins are loaded from at startup, and tenant installs are saved to.
+#[derive(Resource, Clone, Debug)]
+pub struct PluginDir(pub PathBuf);
+
+/// A compiled plugin and the node type it provides.
+#[derive(Clone)]
+pub struct WasmPlugin {
+    module: Module,
+    metadata: NodeMetadata,
+    /// The tenant that installed it, or `None` for a global plugin.
+    owner: Option<TenantId>,
+}
+
+struct PluginState {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+}
+
+impl WasmPlugin {
+    /// Compiles `bytes` (binary or text format) and reads the node type's metadata from it.
+    pub fn compile(engine: &Engine, owner: Option<TenantId>, bytes: &[u8]) -> anyhow::Result<Self> {
+        let module = Module::new(engine, bytes)?;
+        let mut plugin = Self {
+            module,
+            metadata: NodeMetadata {
+                id: String::new(),
+                name: String::new(),
+                category: String::new(),
+                platform: None,
+                description: None,
+                inputs: vec![],
+                outputs: vec![],
+                settings: vec![],
+                version: None,
+                deprecated: None,
+            },
+            owner,
+        };
+        let metadata: NodeMetadata = serde_json::from_value(plugin.call(engine, "metadata", None)?)
+            .map_err(|e| anyhow::anyhow!("Invalid plugin metadata: {}", e))?;
+        if metadata.id.trim().is_empty() {
+            anyhow::bail!("Plugin metadata has no node type id");
+        }
+        plugin.metadata = metadata;
+        Ok(plugin)
+    }
+
+    pub fn metadata(&self) -> &NodeMetadata {
+        &self.metadata
+    }
+
+    pub fn node_type(&self) -> &str {
+        &self.metadata.id
+    }
+
+    pub fn owner(&self) -> Option<&TenantId> {
+        self.owner.as_ref()
+    }
+
+    fn exports(&self, name: &str) -> bool {
+        self.module.get_export(name).is_some()
+    }
+
+    /// Validates a node's settings with the plugin's `build` export, if it has one.
+    pub fn validate(&self, engine: &Engine, settings: &Value) -> anyhow::Result<()> {
+        if !self.exports("build") {
+            return Ok(());
+        }
+        let verdict = self.call(engine, "build", Some(settings))?;
+        match verdict.get("error").and_then(Value::as_str) {
+            Some(error) => Err(anyhow::anyhow!("{}", error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the node on one input payload.
+    pub fn execute(
+        &self,
+        engine: &Engine,
+        settings: &Value,
+        input: &Value,
+    ) -> anyhow::Result<Value> {
+        self.call(
+            engine,
+            "execute",
+            Some(&serde_json::json!({ "settings": settings, "input": input })),
+        )
+    }
+
+    /// Calls `export` in a fresh, limited instance, passing `argument` if given.
+    fn call(
+        &self,
+        engine: &Engine,
+        export: &str,
+        argument: Option<&Value>,
+    ) -> anyhow::Result<Value> {
+        let state = PluginState {
+            wasi: WasiCtxBuilder::new().build(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(PLUGIN_MEMORY_LIMIT)
+                .build(),
+        };
+        let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(PLUGIN_FUEL)?;
+
+        let mut linker = Linker::new(engine);
+        wasi_common::sync::add_to_linker(&mut linker, |state: &mut PluginState| &mut state.wasi)?;
+        let instance = linker.instantiate(&mut store, &self.module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow::anyhow!("Plugin does not export its memory"))?;
+
+        let packed = match argument {
+            None => instance
+                .get_typed_func::<(), i64>(&mut store, export)?
+                .call(&mut store, ())?,
+            Some(argument) => {
+                let bytes = serde_json::to_vec(argument)?;
+                let len = i32::try_from(bytes.len())?;
+                let ptr = instance
+                    .get_typed_func::<i32, i32>(&mut store, "alloc")?
+                    .call(&mut store, len)?;
+                memory.write(&mut store, ptr as u32 as usize, &bytes)?;
+                instance
+                    .get_typed_func::<(i32, i32), i64>(&mut store, export)?
+                    .call(&mut store, (ptr, len))?
+            }
+        };
+
+        // The plugin chooses the length, so bound it before allocating the copy.
+        let (ptr, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+        if len > PLUGIN_OUTPUT_LIMIT {
+            anyhow::bail!(
+                "Plugin '{}' returned {} bytes, more than the {} allowed",
+                export,
+                len,
+                PLUGIN_OUTPUT_LIMIT
+            );
+        }
+        if ptr
+            .checked_add(len)
+            .is_none_or(|end| end > memory.data_size(&store))
+        {
+            anyhow::bail!("Plugin '{}' returned a value outside its memory", export);
+        }
+        let mut output = vec![0; len];
+        memory.read(&store, ptr, &mut output)?;
+        serde_json::from_slice(&output)
+            .map_err(|e| anyhow::anyhow!("Plugin '{}' returned invalid JSON: {}", export, e))
+    }
+}
+
+/// Installed plugins, by owner and node type.
+#[derive(Resource, Default, Clone)]
+pub struct PluginRegistry {
+    plugins: HashMap<(Option<TenantId>, String), WasmPlugin>,
+}
+
+impl PluginRegistry {
+    /// Adds or replaces a plugin, returning whether it replaced one with the same owner and
+    /// node type.
+    pub fn insert(&mut self, plugin: WasmPlugin) -> bool {
+        let key = (plugin.owner.clone(), plugin.metadata.id.to_lowercase());
+        self.plugins.insert(key, plugin).is_some()
+    }
+
+    /// The plugin implementing `node_type` for `tenant`: its own, else a global one.
+    pub fn resolve(&self, tenant: Option<&TenantId>, node_type: &str) -> Option<&WasmPlugin> {
+        let node_type = node_type.to_lowercase();
+        tenant
+            .and_then(|tenant| self.plugins.get(&(Some(tenant.clone()), node_type.clone())))
+            .or_else(|| self.plugins.get(&(None, node_type)))
+    }
+
+    /// Whether any plugin, of any owner, implements `node_type`.
+    pub fn provides(&self, node_type: &str) -> bool {
+        let node_type = node_type.to_lowercase();
+        self.plugins.keys().any(|(_, id)| *id == node_type)
+    }
+
+    /// Registers a factory for every installed plugin's node type, after the node registry was
+    /// rebuilt. Tenant plugins go to their tenant's namespace.
+    pub fn register_node_types(&self, nodes: &mut NodeRegistry) {
+        for plugin in self.plugins.values() {
+            register_factory(nodes, plugin);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+/// Compiles a plugin, stores it and registers its node type, announcing the change on the
+/// event bus. A tenant's plugin is also saved below the [`PluginDir`], when there is one, so it
+/// is installed again after a restart.
+pub fn install(
+    world: &mut World,
+    owner: Option<TenantId>,
+    bytes: &[u8],
+) -> anyhow::Result<NodeMetadata> {
+    let plugin = compile(world, owner, bytes)?;
+    if let (Some(tenant), Some(dir)) = (plugin.owner(), world.get_resource::<PluginDir>()) {
+        save(&dir.0, tenant, plugin.node_type(), bytes)?;
+    }
+    Ok(register(world, plugin))
+}
+
+/// Compiles a plugin and checks that it may claim its node type.
+fn compile(world: &World, owner: Option<TenantId>, bytes: &[u8]) -> anyhow::Result<WasmPlugin> {
+    let runtime = world
+        .get_resource::<WasmRuntime>()
+        .ok_or_else(|| anyhow::anyhow!("WasmRuntime resource not found"))?;
+    let plugin = WasmPlugin::compile(&runtime.engine, owner, bytes)?;
+
+    // Engine-wide node types are visible to every tenant, so a plugin may only claim a node
+    // type that is free or already provided by plugins.
+    let node_type = plugin.node_type();
+    let builtin = world
+        .get_resource::<NodeRegistry>()
+        .ok_or_else(|| anyhow::anyhow!("NodeRegistry resource not found"))?
+        .get(node_type)
+        .is_some();
+    let provided = world
+        .get_resource::<PluginRegistry>()
+        .is_some_and(|plugins| plugins.provides(node_type));
+    if builtin && !provided {
+        anyhow::bail!(
+            "Node type '{}' is already provided by the engine",
+            node_type
+        );
+    }
+    Ok(plugin)
+}
+
+fn register(world: &mut World, plugin: WasmPlugin) -> NodeMetadata {
+    let metadata = plugin.metadata.clone();
+    let owner = plugin.owner.clone();
+    let existed = world
+        .resource::<NodeRegistry>()
+        .get_for(owner.as_ref(), &metadata.id)
+        .is_some();
+    register_factory(&mut world.resource_mut::<NodeRegistry>(), &plugin);
+    let replaced = world
+        .get_resource_or_insert_with(PluginRegistry::default)
+        .insert(plugin);
+    tracing::info!(node_type = %metadata.id, tenant = ?owner, replaced, "Installed WASM plugin");
+
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let (added, updated) = if existed {
+            (vec![], vec![metadata.id.clone()])
+        } else {
+            (vec![metadata.id.clone()], vec![])
+        };
+        let _ = bus.send(SystemEvent::RegistryChanged {
+            added,
+            updated,
+            removed: vec![],
+            tenant_id: owner.map(|tenant| tenant.0),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+    metadata
+}
+
+fn register_factory(nodes: &mut NodeRegistry, plugin: &WasmPlugin) {
+    let factory = Box::new(PluginNodeFactory {
+        metadata: plugin.metadata.clone(),
+    });
+    match &plugin.owner {
+        Some(tenant) => nodes.register_for(tenant, &plugin.metadata.id, factory),
+        None => nodes.register(&plugin.metadata.id, factory),
+    }
+}
+
+/// Writes a tenant's module to `tenants/<tenant>/<node type>.wasm` (or `.wat`) below `dir`,
+/// replacing an earlier install of the same node type.
+fn save(dir: &Path, tenant: &TenantId, node_type: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    let dir = dir.join("tenants").join(encode_file_name(&tenant.0));
+    std::fs::create_dir_all(&dir)?;
+    let stem = encode_file_name(&node_type.to_lowercase());
+    let (extension, stale) = if bytes.starts_with(b"\0asm") {
+        ("wasm", "wat")
+    } else {
+        ("wat", "wasm")
+    };
+    let _ = std::fs::remove_file(dir.join(format!("{stem}.{stale}")));
+    std::fs::write(dir.join(format!("{stem}.{extension}")), bytes)?;
+    Ok(())
+}
+
+/// Escapes `name` into a single path component: anything but ASCII letters, digits, `-`, `_`
+/// and non-leading `.` becomes `%XX`.
+fn encode_file_name(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for (i, byte) in name.bytes().enumerate() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => encoded.push(byte as char),
+            b'.' if i > 0 => encoded.push('.'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn decode_file_name(name: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Installs every `.wasm` and `.wat` module in `dir` as a global plugin, and those saved in
+/// `dir/tenants/<tenant>/` for their tenant. Modules that fail to load are logged and skipped.
+pub fn load_dir(world: &mut World, dir: &Path) -> Vec<NodeMetadata> {
+    let mut loaded = load_modules(world, None, dir);
+    let Ok(tenants) = std::fs::read_dir(dir.join("tenants")) else {
+        return loaded;
+    };
+    for path in tenants.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let tenant = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(decode_file_name);
+        match tenant {
+            Some(tenant) if path.is_dir() => {
+                loaded.extend(load_modules(world, Some(TenantId::from(tenant)), &path))
+            }
+            _ => {
+                tracing::warn!(path = %path.display(), "Skipping unexpected entry in plugin tenants directory")
+            }
+        }
+    }
+    loaded
+}
+
+fn load_modules(world: &mut World, owner: Option<TenantId>, dir: &Path) -> Vec<NodeMetadata> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut loaded = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if !path
+            .extension()
+            .is_some_and(|ext| ext == "wasm" || ext == "wat")
+        {
+            continue;
+        }
+        match std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| compile(world, owner.clone(), &bytes))
+        {
+            Ok(plugin) => loaded.push(register(world, plugin)),
+            Err(e) => tracing::warn!(path = %path.display(), error = %e, "Skipping WASM plugin"),
+        }
+    }
+    loaded
+}
+
+/// The settings of a node run by a plugin, and the plugin resolved for its tenant when the
+/// workflow was deployed.
+#[derive(Component, Clone)]
+pub struct PluginNode {
+    pub plugin: WasmPlugin,
+    pub settings: Value,
+}
+
+/// Builds nodes of a plugin's node type, resolving the plugin for the node's tenant.
+pub struct PluginNodeFactory {
+    metadata: NodeMetadata,
+}
+
+impl NodeFactory for PluginNodeFactory {
+    fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
+        let tenant = entity
+            .get::<NodeConfig>()
+            .and_then(|node| node.tenant_id.clone());
+        let world = entity.world();
+        let plugin = world
+            .get_resource::<PluginRegistry>()
+            .and_then(|plugins| plugins.resolve(tenant.as_ref(), &self.metadata.id))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Plugin node type '{}' is not installed for this tenant",
+                    self.metadata.id
+                )
+            })?;
+        let runtime = world
+            .get_resource::<WasmRuntime>()
+            .ok_or_else(|| anyhow::anyhow!("WasmRuntime resource not found"))?;
+        plugin.validate(&runtime.engine, config)?;
+
+        entity.insert(PluginNode {
+            plugin,
+            settings: config.clone(),
+        });
+        Ok(())
+    }
+
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Value> {
+        world
+            .get::<PluginNode>(entity)
+            .map(|node| node.settings.clone())
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        self.metadata.clone()
+    }
+}
@@ -69,6 +69,8 @@ impl NodeFactory for YamlNodeFactory {
                 .iter()
                 .map(|s| serde_json::to_value(s).unwrap())
                 .collect(),
+            version: meta.version.clone(),
+            deprecated: meta.deprecated.clone(),
         }
     }
 }
@@ -1,8 +1,22 @@
 use async_channel::{Receiver, Sender};
 use bevy_ecs::prelude::*;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::Semaphore;
+pub mod browser;
+pub mod chaos;
+pub mod connection_health;
+pub mod http_policy;
+pub mod mailer;
+pub mod message_format;
+pub mod profiler;
+pub mod prompts;
+pub mod quotas;
+pub mod recorder;
+pub mod redaction;
 pub mod registry;
+pub mod settings;
+pub mod sharding;
 pub mod templates;
 
 pub use registry::NodeRegistry;
@@ -26,12 +40,171 @@ impl Default for GlobalHttpClient {
     }
 }
 
-#[derive(Resource, Clone, Default)]
-pub struct WorkDone(pub bool);
+/// Raised by any system that made progress this tick; the main loop idles while it stays low.
+///
+/// Atomic so workers only need `Res<WorkDone>`, which keeps them from serializing on it under
+/// the parallel executor.
+#[derive(Resource, Default)]
+pub struct WorkDone(AtomicBool);
+
+impl WorkDone {
+    pub fn mark(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&mut self) {
+        *self.0.get_mut() = false;
+    }
+}
+
+/// Traces cancelled by `ApiCommand::CancelRun`, keyed by tenant and trace id. The transport
+/// worker drops their tickets, so results of calls still in flight at cancellation don't
+/// restart the run.
+///
+/// Entries expire after [`CancelledRuns::TTL`], the same retention the janitor gives traces.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct CancelledRuns(
+    std::collections::HashMap<(ferroflux_iam::TenantId, String), std::time::Instant>,
+);
+
+impl CancelledRuns {
+    pub const TTL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+    pub fn cancel(&mut self, tenant: &ferroflux_iam::TenantId, trace_id: &str) {
+        self.0.retain(|_, at| at.elapsed() < Self::TTL);
+        self.0.insert(
+            (tenant.clone(), trace_id.to_string()),
+            std::time::Instant::now(),
+        );
+    }
+
+    /// Whether `tenant` cancelled the trace. Nodes without a tenant are shared by every
+    /// tenant, so for them (`None`) any tenant's cancellation counts.
+    pub fn is_cancelled(&self, tenant: Option<&ferroflux_iam::TenantId>, trace_id: &str) -> bool {
+        match tenant {
+            Some(tenant) => self.0.contains_key(&(tenant.clone(), trace_id.to_string())),
+            None => self.0.keys().any(|(_, cancelled)| cancelled == trace_id),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Runs started by `ApiCommand::ShadowRun`, keyed by trace id, with the mocks their
+/// side-effecting nodes answer with. Entries expire after [`CancelledRuns::TTL`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ShadowRuns(
+    std::collections::HashMap<
+        String,
+        (
+            std::time::Instant,
+            std::collections::HashMap<String, crate::components::shadow::MockConfig>,
+        ),
+    >,
+);
+
+impl ShadowRuns {
+    pub fn start(
+        &mut self,
+        trace_id: &str,
+        mocks: std::collections::HashMap<String, crate::components::shadow::MockConfig>,
+    ) {
+        self.0
+            .retain(|_, (at, _)| at.elapsed() < CancelledRuns::TTL);
+        self.0
+            .insert(trace_id.to_string(), (std::time::Instant::now(), mocks));
+    }
+
+    /// The mocks of a shadow run, or `None` if the trace is not one.
+    pub fn mocks(
+        &self,
+        trace_id: &str,
+    ) -> Option<&std::collections::HashMap<String, crate::components::shadow::MockConfig>> {
+        self.0.get(trace_id).map(|(_, mocks)| mocks)
+    }
+}
+
+/// Runs started by `ApiCommand::ReplayRun`, keyed by the replay's trace id, with the recorded
+/// results still to be fed back per node. Entries expire after [`CancelledRuns::TTL`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ReplayRuns(
+    std::collections::HashMap<
+        String,
+        (
+            std::time::Instant,
+            std::collections::HashMap<
+                uuid::Uuid,
+                std::collections::VecDeque<crate::store::database::IoRecording>,
+            >,
+        ),
+    >,
+);
+
+impl ReplayRuns {
+    pub fn start(
+        &mut self,
+        trace_id: &str,
+        recordings: impl IntoIterator<Item = crate::store::database::IoRecording>,
+    ) {
+        self.0
+            .retain(|_, (at, _)| at.elapsed() < CancelledRuns::TTL);
+        let mut by_node: std::collections::HashMap<_, std::collections::VecDeque<_>> =
+            std::collections::HashMap::new();
+        for recording in recordings {
+            by_node
+                .entry(recording.node_id)
+                .or_default()
+                .push_back(recording);
+        }
+        self.0
+            .insert(trace_id.to_string(), (std::time::Instant::now(), by_node));
+    }
+
+    pub fn is_replaying(&self, trace_id: &str) -> bool {
+        self.0.contains_key(trace_id)
+    }
+
+    /// The next recorded result of `node_id` in the replay, if any is left.
+    pub fn next(
+        &mut self,
+        trace_id: &str,
+        node_id: uuid::Uuid,
+    ) -> Option<crate::store::database::IoRecording> {
+        self.0.get_mut(trace_id)?.1.get_mut(&node_id)?.pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Root directory for the File node; each tenant gets its own subdirectory.
+#[derive(Resource, Clone, Debug)]
+pub struct FileSandbox(pub std::path::PathBuf);
+
+impl Default for FileSandbox {
+    fn default() -> Self {
+        Self(ferroflux_security::sandbox::sandbox_root())
+    }
+}
 
 #[derive(Resource, Clone)]
 pub struct AgentConcurrency(pub Arc<Semaphore>);
 
+/// The size `AgentConcurrency` was created with or last resized to, plus permits still owed
+/// after a shrink (held permits can only be withdrawn once they are released).
+#[derive(Resource, Clone, Debug, Default)]
+pub struct AgentConcurrencyLimit {
+    pub limit: usize,
+    pub owed: usize,
+}
+
 #[derive(Resource, Clone)]
 pub struct AgentResultChannel {
     pub tx: Sender<(Entity, String, std::collections::HashMap<String, String>)>,
@@ -60,10 +233,247 @@ impl Default for HttpResultChannel {
     }
 }
 
+/// A finished speech call: the node, the ticket it consumed and the transcript or audio
+/// reference.
+pub type SpeechOutcome = (
+    Entity,
+    crate::store::SecureTicket,
+    anyhow::Result<serde_json::Value>,
+);
+
+#[derive(Resource, Clone)]
+pub struct SpeechResultChannel {
+    pub tx: Sender<SpeechOutcome>,
+    pub rx: Receiver<SpeechOutcome>,
+}
+
+impl Default for SpeechResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+/// A finished embedding call or vector search: the node, the ticket it consumed and the
+/// vector or matches.
+pub type VectorOutcome = (
+    Entity,
+    crate::store::SecureTicket,
+    anyhow::Result<serde_json::Value>,
+);
+
+#[derive(Resource, Clone)]
+pub struct EmbeddingResultChannel {
+    pub tx: Sender<VectorOutcome>,
+    pub rx: Receiver<VectorOutcome>,
+}
+
+impl Default for EmbeddingResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct VectorSearchResultChannel {
+    pub tx: Sender<VectorOutcome>,
+    pub rx: Receiver<VectorOutcome>,
+}
+
+impl Default for VectorSearchResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+/// A finished connector call: the node, the ticket it consumed and the call's result.
+pub type ConnectorOutcome = (
+    Entity,
+    crate::store::SecureTicket,
+    anyhow::Result<serde_json::Value>,
+);
+
+#[derive(Resource, Clone)]
+pub struct SqlResultChannel {
+    pub tx: Sender<ConnectorOutcome>,
+    pub rx: Receiver<ConnectorOutcome>,
+}
+
+impl Default for SqlResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct RedisResultChannel {
+    pub tx: Sender<ConnectorOutcome>,
+    pub rx: Receiver<ConnectorOutcome>,
+}
+
+impl Default for RedisResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EmailResultChannel {
+    pub tx: Sender<ConnectorOutcome>,
+    pub rx: Receiver<ConnectorOutcome>,
+}
+
+impl Default for EmailResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct GeoIpResultChannel {
+    pub tx: Sender<ConnectorOutcome>,
+    pub rx: Receiver<ConnectorOutcome>,
+}
+
+impl Default for GeoIpResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+/// A finished IMAP poll: the node, its advanced cursor and the raw messages fetched, oldest
+/// first.
+pub type ImapPollOutcome = (
+    Entity,
+    anyhow::Result<(
+        crate::components::connectors::ImapState,
+        Vec<(u32, Vec<u8>)>,
+    )>,
+);
+
+#[derive(Resource, Clone)]
+pub struct ImapResultChannel {
+    pub tx: Sender<ImapPollOutcome>,
+    pub rx: Receiver<ImapPollOutcome>,
+}
+
+impl Default for ImapResultChannel {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { tx, rx }
+    }
+}
+
+/// The vector store Embedding nodes index into and VectorSearch nodes query.
+#[derive(Resource, Clone)]
+pub struct VectorIndex(pub std::sync::Arc<dyn crate::store::vector::VectorStore>);
+
 #[derive(Resource, Clone, Default)]
 pub struct GraphTopology {
     // Source -> [(SourcePort, TargetEntity)]
     pub adjacency: std::collections::HashMap<Entity, Vec<(Option<String>, Entity)>>,
+    // (Source, SourcePort, Target) -> TargetPort, for edges that name one
+    pub target_ports: std::collections::HashMap<(Entity, Option<String>, Entity), String>,
+    /// Bumped on every change, so consumers can tell whether their own derived state is stale.
+    pub revision: u64,
+    // Edge entity -> the edge as indexed, needed to undo it once the component is gone
+    edges: std::collections::HashMap<Entity, crate::components::Edge>,
+    built: bool,
+}
+
+impl GraphTopology {
+    /// Replaces the whole cache with `edges`.
+    pub fn rebuild<'a>(
+        &mut self,
+        edges: impl IntoIterator<Item = (Entity, &'a crate::components::Edge)>,
+    ) {
+        self.adjacency.clear();
+        self.target_ports.clear();
+        self.edges.clear();
+        for (entity, edge) in edges {
+            self.index(entity, edge.clone());
+        }
+        self.built = true;
+        self.revision += 1;
+    }
+
+    /// Whether [`rebuild`](Self::rebuild) has run since the resource was created.
+    pub fn is_built(&self) -> bool {
+        self.built
+    }
+
+    /// Adds an edge, replacing what was indexed for the same edge entity before.
+    pub fn insert_edge(&mut self, entity: Entity, edge: &crate::components::Edge) {
+        self.unindex(entity);
+        self.index(entity, edge.clone());
+        self.revision += 1;
+    }
+
+    pub fn remove_edge(&mut self, entity: Entity) {
+        if self.unindex(entity) {
+            self.revision += 1;
+        }
+    }
+
+    /// Drops every edge into or out of `node`.
+    pub fn remove_node(&mut self, node: Entity) {
+        let attached: Vec<Entity> = self
+            .edges
+            .iter()
+            .filter(|(_, edge)| edge.source == node || edge.target == node)
+            .map(|(entity, _)| *entity)
+            .collect();
+        for entity in attached {
+            self.remove_edge(entity);
+        }
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    fn index(&mut self, entity: Entity, edge: crate::components::Edge) {
+        self.adjacency
+            .entry(edge.source)
+            .or_default()
+            .push((edge.source_handle.clone(), edge.target));
+        if let Some(target_handle) = &edge.target_handle {
+            self.target_ports.insert(
+                (edge.source, edge.source_handle.clone(), edge.target),
+                target_handle.clone(),
+            );
+        }
+        self.edges.insert(entity, edge);
+    }
+
+    fn unindex(&mut self, entity: Entity) -> bool {
+        let Some(edge) = self.edges.remove(&entity) else {
+            return false;
+        };
+        let route = (edge.source_handle.clone(), edge.target);
+        let mut parallel = false;
+        if let Some(targets) = self.adjacency.get_mut(&edge.source) {
+            if let Some(position) = targets.iter().position(|t| *t == route) {
+                targets.remove(position);
+            }
+            parallel = targets.contains(&route);
+            if targets.is_empty() {
+                self.adjacency.remove(&edge.source);
+            }
+        }
+        // A parallel edge between the same ports keeps the input port mapping alive.
+        if !parallel {
+            self.target_ports
+                .remove(&(edge.source, edge.source_handle, edge.target));
+        }
+        true
+    }
 }
 #[derive(Resource, Clone)]
 pub struct PipelineResultChannel {
@@ -77,3 +487,50 @@ impl Default for PipelineResultChannel {
         Self { tx, rx }
     }
 }
+
+/// A `TriggerAndWait` run being followed to its terminal nodes.
+pub struct PendingRun {
+    /// The tenant that triggered the run, whose cancellations apply to it.
+    pub tenant: ferroflux_iam::TenantId,
+    pub reply: crate::api::RunReply,
+    pub deadline: std::time::Instant,
+    /// Payloads that reached a terminal node so far.
+    pub results: Vec<serde_json::Value>,
+    /// Tickets already collected; outboxes of nodes without edges are never drained.
+    pub seen: std::collections::HashSet<uuid::Uuid>,
+}
+
+impl PendingRun {
+    pub fn new(
+        tenant: ferroflux_iam::TenantId,
+        reply: crate::api::RunReply,
+        deadline: std::time::Instant,
+    ) -> Self {
+        Self {
+            tenant,
+            reply,
+            deadline,
+            results: Vec::new(),
+            seen: std::collections::HashSet::new(),
+        }
+    }
+}
+
+/// Runs awaited through `ApiCommand::TriggerAndWait`, keyed by trace id.
+#[derive(Resource, Default)]
+pub struct PendingRuns(pub std::collections::HashMap<String, PendingRun>);
+
+/// A delivery awaiting settlement once its ticket reaches a terminal node.
+pub struct PendingAmqpAck {
+    pub acker: lapin::acker::Acker,
+    pub requeue: bool,
+    pub deadline: std::time::Instant,
+    /// The delivery's trace, so tickets that dropped the delivery key still settle it.
+    pub trace_id: String,
+    /// Set once a ticket of the delivery is handed to a node with no outgoing edges.
+    pub at_terminal: bool,
+}
+
+/// Outstanding AMQP deliveries, keyed by the `amqp_delivery` ticket metadata.
+#[derive(Resource, Clone, Default)]
+pub struct AmqpPendingAcks(pub Arc<dashmap::DashMap<uuid::Uuid, PendingAmqpAck>>);
@@ -0,0 +1,160 @@
+//! Headless browser sessions for the Browser node.
+//!
+//! [`HeadlessBrowser`] hands out one [`BrowserPage`] per ticket from a pluggable
+//! [`BrowserBackend`]. With the `browser` feature the default backend is Chromium, driven over
+//! the DevTools protocol by `chromiumoxide` and launched on first use; without it the resource
+//! has no backend and Browser nodes fail their tickets.
+
+use crate::store::SecureTicket;
+use async_channel::{Receiver, Sender};
+use async_trait::async_trait;
+use bevy_ecs::prelude::{Entity, Resource};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait BrowserBackend: Send + Sync {
+    async fn open(&self) -> anyhow::Result<Box<dyn BrowserPage>>;
+}
+
+/// A single tab. Dropped or closed once the node's script has finished.
+#[async_trait]
+pub trait BrowserPage: Send {
+    async fn goto(&mut self, url: &str) -> anyhow::Result<()>;
+    /// The URL actually shown, after redirects.
+    async fn current_url(&mut self) -> anyhow::Result<Option<String>>;
+    /// Whether anything matches `selector` right now.
+    async fn exists(&mut self, selector: &str) -> anyhow::Result<bool>;
+    async fn texts(&mut self, selector: &str) -> anyhow::Result<Vec<String>>;
+    async fn attributes(
+        &mut self,
+        selector: &str,
+        attribute: &str,
+    ) -> anyhow::Result<Vec<Option<String>>>;
+    /// PNG bytes.
+    async fn screenshot(&mut self, full_page: bool) -> anyhow::Result<Vec<u8>>;
+    async fn close(self: Box<Self>) -> anyhow::Result<()>;
+}
+
+/// A finished script: the node, the ticket it consumed and the extracted values.
+pub type BrowserOutcome = (Entity, SecureTicket, anyhow::Result<Value>);
+
+#[derive(Resource, Clone)]
+pub struct HeadlessBrowser {
+    pub backend: Option<Arc<dyn BrowserBackend>>,
+    pub tx: Sender<BrowserOutcome>,
+    pub rx: Receiver<BrowserOutcome>,
+}
+
+impl HeadlessBrowser {
+    pub fn new(backend: Option<Arc<dyn BrowserBackend>>) -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        Self { backend, tx, rx }
+    }
+}
+
+impl Default for HeadlessBrowser {
+    fn default() -> Self {
+        #[cfg(feature = "browser")]
+        let backend: Option<Arc<dyn BrowserBackend>> =
+            Some(Arc::new(chromium::ChromiumBackend::default()));
+        #[cfg(not(feature = "browser"))]
+        let backend = None;
+        Self::new(backend)
+    }
+}
+
+#[cfg(feature = "browser")]
+pub mod chromium {
+    use super::{BrowserBackend, BrowserPage};
+    use async_trait::async_trait;
+    use chromiumoxide::browser::{Browser, BrowserConfig};
+    use chromiumoxide::page::{Page, ScreenshotParams};
+    use futures::StreamExt;
+    use tokio::sync::OnceCell;
+
+    /// Launches Chromium (from `CHROME` or the usual install locations) on first use and
+    /// keeps it running for later pages.
+    #[derive(Default)]
+    pub struct ChromiumBackend {
+        browser: OnceCell<Browser>,
+    }
+
+    impl ChromiumBackend {
+        async fn browser(&self) -> anyhow::Result<&Browser> {
+            self.browser
+                .get_or_try_init(|| async {
+                    let config = BrowserConfig::builder()
+                        .no_sandbox()
+                        .build()
+                        .map_err(anyhow::Error::msg)?;
+                    let (browser, mut handler) = Browser::launch(config).await?;
+                    tokio::spawn(async move {
+                        while let Some(event) = handler.next().await {
+                            if event.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    Ok(browser)
+                })
+                .await
+        }
+    }
+
+    #[async_trait]
+    impl BrowserBackend for ChromiumBackend {
+        async fn open(&self) -> anyhow::Result<Box<dyn BrowserPage>> {
+            let page = self.browser().await?.new_page("about:blank").await?;
+            Ok(Box::new(ChromiumPage(page)))
+        }
+    }
+
+    struct ChromiumPage(Page);
+
+    #[async_trait]
+    impl BrowserPage for ChromiumPage {
+        async fn goto(&mut self, url: &str) -> anyhow::Result<()> {
+            self.0.goto(url).await?.wait_for_navigation().await?;
+            Ok(())
+        }
+
+        async fn current_url(&mut self) -> anyhow::Result<Option<String>> {
+            Ok(self.0.url().await?)
+        }
+
+        async fn exists(&mut self, selector: &str) -> anyhow::Result<bool> {
+            Ok(!self.0.find_elements(selector).await?.is_empty())
+        }
+
+        async fn texts(&mut self, selector: &str) -> anyhow::Result<Vec<String>> {
+            let mut texts = Vec::new();
+            for element in self.0.find_elements(selector).await? {
+                texts.push(element.inner_text().await?.unwrap_or_default());
+            }
+            Ok(texts)
+        }
+
+        async fn attributes(
+            &mut self,
+            selector: &str,
+            attribute: &str,
+        ) -> anyhow::Result<Vec<Option<String>>> {
+            let mut values = Vec::new();
+            for element in self.0.find_elements(selector).await? {
+                values.push(element.attribute(attribute).await?);
+            }
+            Ok(values)
+        }
+
+        async fn screenshot(&mut self, full_page: bool) -> anyhow::Result<Vec<u8>> {
+            let params = ScreenshotParams::builder().full_page(full_page).build();
+            Ok(self.0.screenshot(params).await?)
+        }
+
+        async fn close(self: Box<Self>) -> anyhow::Result<()> {
+            self.0.close().await?;
+            Ok(())
+        }
+    }
+}
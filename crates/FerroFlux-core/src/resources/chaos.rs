@@ -0,0 +1,99 @@
+//! Fault injection for resilience testing.
+//!
+//! Insert a [`FaultInjector`] resource into a test world to make nodes fail on purpose, so
+//! retry and error-handling paths can be exercised. The engine never inserts it on its own;
+//! without the resource every injection point is a no-op.
+
+use bevy_ecs::prelude::Resource;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Ticket metadata key marking a ticket whose next `BlobStore::claim` must fail.
+pub const CHAOS_CLAIM_FAULT_KEY: &str = "chaos_claim_fault";
+
+/// Profile key applied to node types without a profile of their own.
+pub const ANY_NODE_TYPE: &str = "*";
+
+/// Failure rates for one node type. Rates are probabilities in `0.0..=1.0`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FaultProfile {
+    /// HTTP requests answer with a synthetic `500 Internal Server Error`.
+    #[serde(default)]
+    pub http_error_rate: f64,
+    /// HTTP requests are delayed by `latency_ms` before being sent.
+    #[serde(default)]
+    pub latency_rate: f64,
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Tickets are dropped in transport instead of reaching the node.
+    #[serde(default)]
+    pub drop_rate: f64,
+    /// Tickets reach the node, but claiming their blob fails.
+    #[serde(default)]
+    pub claim_error_rate: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    HttpError,
+    Latency,
+    Drop,
+    ClaimError,
+}
+
+#[derive(Resource, Clone)]
+pub struct FaultInjector {
+    profiles: HashMap<String, FaultProfile>,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl FaultInjector {
+    /// Creates an injector with no profiles. The seed makes a run reproducible.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            profiles: HashMap::new(),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Sets the profile for a node type (or [`ANY_NODE_TYPE`]).
+    pub fn with_profile(mut self, node_type: impl Into<String>, profile: FaultProfile) -> Self {
+        self.profiles.insert(node_type.into(), profile);
+        self
+    }
+
+    fn profile(&self, node_type: &str) -> Option<&FaultProfile> {
+        self.profiles
+            .get(node_type)
+            .or_else(|| self.profiles.get(ANY_NODE_TYPE))
+    }
+
+    /// Rolls the dice for `fault` on a node of the given type.
+    pub fn should_inject(&self, node_type: &str, fault: Fault) -> bool {
+        let Some(profile) = self.profile(node_type) else {
+            return false;
+        };
+        let rate = match fault {
+            Fault::HttpError => profile.http_error_rate,
+            Fault::Latency => profile.latency_rate,
+            Fault::Drop => profile.drop_rate,
+            Fault::ClaimError => profile.claim_error_rate,
+        };
+        if rate <= 0.0 {
+            return false;
+        }
+        let mut rng = self.rng.lock().unwrap();
+        rng.gen_bool(rate.min(1.0))
+    }
+
+    /// The delay to add before an HTTP request, if latency is injected this time.
+    pub fn latency(&self, node_type: &str) -> Option<Duration> {
+        let latency_ms = self.profile(node_type)?.latency_ms;
+        self.should_inject(node_type, Fault::Latency)
+            .then(|| Duration::from_millis(latency_ms))
+    }
+}
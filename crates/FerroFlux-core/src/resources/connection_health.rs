@@ -0,0 +1,295 @@
+//! Per-connection health tracking.
+//!
+//! Nodes that call out through a stored connection report every outcome to
+//! [`ConnectionHealth`], keyed by tenant and connection slug, so error rates are shared by all
+//! workflows using the connection. A run of authentication failures trips it; the
+//! `connection_health_worker` then marks the connection `error`, emits
+//! `SystemEvent::ConnectionUnhealthy` and, when `connections.pause_dependent_workflows` is set,
+//! holds the workflows that use it in [`PausedWorkflows`] until `ApiCommand::ConnectionVerified`.
+
+use crate::resources::settings::ConnectionHealthSettings;
+use async_channel::{Receiver, Sender};
+use bevy_ecs::prelude::Resource;
+use dashmap::DashMap;
+use ferroflux_iam::TenantId;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Arc;
+
+/// Error text that marks a failure as a rejected credential rather than a broken call.
+const AUTH_ERROR_MARKERS: &[&str] = &[
+    "unauthorized",
+    "authentication failed",
+    "access denied",
+    "invalid credentials",
+    "invalid api key",
+    "login failed",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionOutcome {
+    Success,
+    AuthFailure,
+    Failure,
+}
+
+impl ConnectionOutcome {
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            200..=399 => Self::Success,
+            401 | 403 => Self::AuthFailure,
+            _ => Self::Failure,
+        }
+    }
+
+    pub fn from_error(error: &str) -> Self {
+        let error = error.to_ascii_lowercase();
+        if AUTH_ERROR_MARKERS
+            .iter()
+            .any(|marker| error.contains(marker))
+        {
+            Self::AuthFailure
+        } else {
+            Self::Failure
+        }
+    }
+}
+
+#[derive(Default)]
+struct ConnectionStats {
+    /// Most recent outcomes, `true` for failures.
+    recent: VecDeque<bool>,
+    /// Authentication failures since the last success.
+    auth_failures: u32,
+    workflows: BTreeSet<String>,
+    tripped: bool,
+}
+
+impl ConnectionStats {
+    fn error_rate(&self) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        self.recent.iter().filter(|failed| **failed).count() as f64 / self.recent.len() as f64
+    }
+}
+
+/// A connection that reached the authentication failure threshold.
+#[derive(Clone, Debug)]
+pub struct ConnectionTrip {
+    pub tenant: TenantId,
+    pub slug: String,
+    pub auth_failures: u32,
+    pub error_rate: f64,
+    /// Workflows seen using the connection.
+    pub workflows: Vec<String>,
+}
+
+#[derive(Resource, Clone)]
+pub struct ConnectionHealth {
+    stats: Arc<DashMap<(TenantId, String), ConnectionStats>>,
+    tx: Sender<ConnectionTrip>,
+    rx: Receiver<ConnectionTrip>,
+    verified_tx: Sender<(TenantId, String)>,
+    verified_rx: Receiver<(TenantId, String)>,
+}
+
+impl Default for ConnectionHealth {
+    fn default() -> Self {
+        let (tx, rx) = async_channel::unbounded();
+        let (verified_tx, verified_rx) = async_channel::unbounded();
+        Self {
+            stats: Arc::default(),
+            tx,
+            rx,
+            verified_tx,
+            verified_rx,
+        }
+    }
+}
+
+impl ConnectionHealth {
+    /// Records one call made through `slug`. Safe to call from async tasks.
+    pub fn record(
+        &self,
+        settings: &ConnectionHealthSettings,
+        tenant: &TenantId,
+        slug: &str,
+        workflow_id: Option<&str>,
+        outcome: ConnectionOutcome,
+    ) {
+        let mut stats = self
+            .stats
+            .entry((tenant.clone(), slug.to_string()))
+            .or_default();
+        if let Some(workflow_id) = workflow_id {
+            stats.workflows.insert(workflow_id.to_string());
+        }
+        stats
+            .recent
+            .push_back(outcome != ConnectionOutcome::Success);
+        while stats.recent.len() > settings.error_window.max(1) {
+            stats.recent.pop_front();
+        }
+        match outcome {
+            ConnectionOutcome::Success => stats.auth_failures = 0,
+            ConnectionOutcome::AuthFailure => stats.auth_failures += 1,
+            ConnectionOutcome::Failure => {}
+        }
+
+        if !stats.tripped
+            && settings.auth_failure_threshold > 0
+            && stats.auth_failures >= settings.auth_failure_threshold
+        {
+            stats.tripped = true;
+            let _ = self.tx.try_send(ConnectionTrip {
+                tenant: tenant.clone(),
+                slug: slug.to_string(),
+                auth_failures: stats.auth_failures,
+                error_rate: stats.error_rate(),
+                workflows: stats.workflows.iter().cloned().collect(),
+            });
+        }
+    }
+
+    /// Share of failed calls among the most recent `connections.error_window`.
+    pub fn error_rate(&self, tenant: &TenantId, slug: &str) -> Option<f64> {
+        self.stats
+            .get(&(tenant.clone(), slug.to_string()))
+            .map(|stats| stats.error_rate())
+    }
+
+    pub fn is_tripped(&self, tenant: &TenantId, slug: &str) -> bool {
+        self.stats
+            .get(&(tenant.clone(), slug.to_string()))
+            .is_some_and(|stats| stats.tripped)
+    }
+
+    /// Forgets the connection's history once it has been re-verified.
+    pub fn reset(&self, tenant: &TenantId, slug: &str) {
+        self.stats.remove(&(tenant.clone(), slug.to_string()));
+    }
+
+    /// Connections tripped since the last call.
+    pub fn drain_trips(&self) -> Vec<ConnectionTrip> {
+        std::iter::from_fn(|| self.rx.try_recv().ok()).collect()
+    }
+
+    /// Reports a connection that passed verification, for `connection_health_worker` to reset
+    /// and resume its workflows. Safe to call from async tasks.
+    pub fn report_verified(&self, tenant: &TenantId, slug: &str) {
+        let _ = self
+            .verified_tx
+            .try_send((tenant.clone(), slug.to_string()));
+    }
+
+    /// Connections verified since the last call.
+    pub fn drain_verified(&self) -> Vec<(TenantId, String)> {
+        std::iter::from_fn(|| self.verified_rx.try_recv().ok()).collect()
+    }
+}
+
+/// Pause reason recorded by `ApiCommand::PauseWorkflow`; never a valid connection slug.
+pub const OPERATOR_PAUSE: &str = "@operator";
+
+/// Workflows whose tickets the transport worker holds back, with the connections that
+/// paused them (or [`OPERATOR_PAUSE`]). A workflow resumes once every one of those connections
+/// is re-verified, or when an operator resumes it.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PausedWorkflows(pub HashMap<(TenantId, String), BTreeSet<String>>);
+
+impl PausedWorkflows {
+    pub fn pause(&mut self, tenant: &TenantId, workflow_id: &str, slug: &str) {
+        self.0
+            .entry((tenant.clone(), workflow_id.to_string()))
+            .or_default()
+            .insert(slug.to_string());
+    }
+
+    /// Drops `slug` as a reason to pause; returns the workflows that are now running again.
+    pub fn release(&mut self, tenant: &TenantId, slug: &str) -> Vec<String> {
+        let mut resumed = Vec::new();
+        self.0.retain(|(paused_tenant, workflow_id), slugs| {
+            if paused_tenant == tenant && slugs.remove(slug) && slugs.is_empty() {
+                resumed.push(workflow_id.clone());
+                return false;
+            }
+            true
+        });
+        resumed
+    }
+
+    /// Lifts every pause on the workflow; returns false if it wasn't paused.
+    pub fn resume(&mut self, tenant: &TenantId, workflow_id: &str) -> bool {
+        self.0
+            .remove(&(tenant.clone(), workflow_id.to_string()))
+            .is_some()
+    }
+
+    pub fn is_paused(&self, tenant: &TenantId, workflow_id: &str) -> bool {
+        self.0
+            .contains_key(&(tenant.clone(), workflow_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consecutive_auth_failures_trip_once() {
+        let settings = ConnectionHealthSettings {
+            auth_failure_threshold: 2,
+            error_window: 4,
+            pause_dependent_workflows: false,
+        };
+        let health = ConnectionHealth::default();
+        let tenant = TenantId::from("acme");
+        let record =
+            |workflow, outcome| health.record(&settings, &tenant, "crm", Some(workflow), outcome);
+
+        record("sync", ConnectionOutcome::AuthFailure);
+        record("sync", ConnectionOutcome::Success);
+        record("export", ConnectionOutcome::AuthFailure);
+        assert!(health.drain_trips().is_empty());
+
+        record("sync", ConnectionOutcome::Failure);
+        record("sync", ConnectionOutcome::AuthFailure);
+        record("sync", ConnectionOutcome::AuthFailure);
+        let trips = health.drain_trips();
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].auth_failures, 2);
+        assert_eq!(trips[0].workflows, vec!["export", "sync"]);
+        // The window only keeps the last four calls, all failures.
+        assert_eq!(health.error_rate(&tenant, "crm"), Some(1.0));
+        assert!(health.is_tripped(&tenant, "crm"));
+
+        health.reset(&tenant, "crm");
+        assert!(!health.is_tripped(&tenant, "crm"));
+    }
+
+    #[test]
+    fn test_outcome_classification() {
+        assert_eq!(
+            ConnectionOutcome::from_status(204),
+            ConnectionOutcome::Success
+        );
+        assert_eq!(
+            ConnectionOutcome::from_status(401),
+            ConnectionOutcome::AuthFailure
+        );
+        assert_eq!(
+            ConnectionOutcome::from_status(503),
+            ConnectionOutcome::Failure
+        );
+        assert_eq!(
+            ConnectionOutcome::from_error(
+                "error returned from database: password authentication failed for user \"etl\""
+            ),
+            ConnectionOutcome::AuthFailure
+        );
+        assert_eq!(
+            ConnectionOutcome::from_error("syntax error at or near \"SELEC\""),
+            ConnectionOutcome::Failure
+        );
+    }
+}
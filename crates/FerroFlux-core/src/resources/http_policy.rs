@@ -0,0 +1,271 @@
+//! Per-host circuit breaking and concurrency caps for outbound HTTP.
+//!
+//! The HTTP node asks [`HttpClientPolicy`] before every call and reports the outcome after it.
+//! A host that fails `http.failure_threshold` times in a row is cut off for `http.open_ms`:
+//! calls to it fail immediately instead of tying up a blocking thread each. Once the period is
+//! over a single probe call is let through, and its outcome closes or re-opens the circuit.
+//! Independently, `http.max_concurrent_per_host` bounds the requests in flight to one host.
+//!
+//! [`HttpSettings`] holds the engine-wide timeout, redirect and proxy defaults that each node's
+//! `HttpConfig` can override.
+
+use crate::components::HttpConfig;
+use crate::resources::settings::{HttpClientSettings, NetworkSettings};
+use bevy_ecs::prelude::Resource;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    /// Calls fail fast until the instant passes.
+    Open(Instant),
+    /// The open period is over and one probe call is in flight.
+    HalfOpen,
+}
+
+/// Whether a finished call counts against its host: transport errors, rate limiting and
+/// server errors do, client errors are the caller's fault.
+pub fn is_host_failure(status: u16) -> bool {
+    status == 0 || status == 429 || status >= 500
+}
+
+/// A host whose circuit just opened.
+#[derive(Clone, Debug)]
+pub struct CircuitOpened {
+    pub host: String,
+    pub consecutive_failures: u32,
+    pub open_for: Duration,
+}
+
+struct HostStats {
+    consecutive_failures: u32,
+    state: CircuitState,
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct HttpClientPolicy {
+    hosts: Arc<DashMap<String, HostStats>>,
+    limits: Arc<DashMap<String, Arc<Semaphore>>>,
+}
+
+impl HttpClientPolicy {
+    /// Admits a call to `host`, or returns how long its circuit stays open.
+    pub fn check(&self, host: &str) -> Result<(), Duration> {
+        let Some(mut stats) = self.hosts.get_mut(host) else {
+            return Ok(());
+        };
+        match stats.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::HalfOpen => Err(Duration::ZERO),
+            CircuitState::Open(until) => {
+                let now = Instant::now();
+                if now < until {
+                    Err(until - now)
+                } else {
+                    stats.state = CircuitState::HalfOpen;
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Records a finished call; returns the opened circuit when this failure tripped it.
+    pub fn record(
+        &self,
+        settings: &HttpClientSettings,
+        host: &str,
+        success: bool,
+    ) -> Option<CircuitOpened> {
+        let mut stats = self.hosts.entry(host.to_string()).or_insert(HostStats {
+            consecutive_failures: 0,
+            state: CircuitState::Closed,
+        });
+        if success {
+            stats.consecutive_failures = 0;
+            stats.state = CircuitState::Closed;
+            return None;
+        }
+
+        stats.consecutive_failures += 1;
+        let trips = match stats.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => {
+                settings.failure_threshold > 0
+                    && stats.consecutive_failures >= settings.failure_threshold
+            }
+            CircuitState::Open(_) => false,
+        };
+        if !trips {
+            return None;
+        }
+        let open_for = Duration::from_millis(settings.open_ms);
+        stats.state = CircuitState::Open(Instant::now() + open_for);
+        Some(CircuitOpened {
+            host: host.to_string(),
+            consecutive_failures: stats.consecutive_failures,
+            open_for,
+        })
+    }
+
+    /// Waits for a slot under `http.max_concurrent_per_host`; `None` when uncapped.
+    ///
+    /// The cap in force when a host is first seen sticks until [`reset`](Self::reset).
+    pub async fn acquire(
+        &self,
+        settings: &HttpClientSettings,
+        host: &str,
+    ) -> Option<OwnedSemaphorePermit> {
+        if settings.max_concurrent_per_host == 0 {
+            return None;
+        }
+        let semaphore = self
+            .limits
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(settings.max_concurrent_per_host)))
+            .clone();
+        semaphore.acquire_owned().await.ok()
+    }
+
+    pub fn state(&self, host: &str) -> CircuitState {
+        self.hosts
+            .get(host)
+            .map_or(CircuitState::Closed, |stats| stats.state)
+    }
+
+    /// Closes the host's circuit and drops its concurrency limiter.
+    pub fn reset(&self, host: &str) {
+        self.hosts.remove(host);
+        self.limits.remove(host);
+    }
+}
+
+/// Engine-wide defaults for outbound requests of the HTTP node.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// Time allowed for a whole request, response body included; 0 for none.
+    pub timeout_ms: u64,
+    /// Redirects followed before giving up; 0 returns the redirect itself.
+    pub max_redirects: usize,
+    /// Proxy every request goes through. The `HTTP_PROXY` family of variables applies if unset.
+    /// Proxies are held to the same `network` policy as request URLs, so an internal one must
+    /// be listed in `network.ssrf_allowlist`.
+    pub proxy: Option<String>,
+    /// Hosts, domains or CIDR ranges reached directly instead of through `proxy`.
+    pub no_proxy: Vec<String>,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 30_000,
+            max_redirects: 10,
+            proxy: None,
+            no_proxy: Vec::new(),
+        }
+    }
+}
+
+impl HttpSettings {
+    /// These defaults with the node's own settings laid over them.
+    pub fn for_node(&self, config: &HttpConfig) -> HttpSettings {
+        HttpSettings {
+            timeout_ms: config.timeout_ms.unwrap_or(self.timeout_ms),
+            max_redirects: config.max_redirects.unwrap_or(self.max_redirects),
+            proxy: config.proxy.clone().or_else(|| self.proxy.clone()),
+            no_proxy: config
+                .no_proxy
+                .clone()
+                .unwrap_or_else(|| self.no_proxy.clone()),
+        }
+    }
+
+    /// Builds a blocking client whose proxy and redirect targets must pass `network`; it must
+    /// not be called from async code.
+    pub fn blocking_client(
+        &self,
+        network: &NetworkSettings,
+    ) -> Result<reqwest::blocking::Client, String> {
+        let max = self.max_redirects;
+        let hops = network.clone();
+        let redirects = match max {
+            0 => reqwest::redirect::Policy::none(),
+            max => reqwest::redirect::Policy::custom(move |attempt| {
+                // `previous` includes the original request.
+                if attempt.previous().len() > max {
+                    return attempt.error(format!("more than {} redirects", max));
+                }
+                match hops.validate_url(attempt.url().as_str()) {
+                    Ok(()) => attempt.follow(),
+                    Err(e) => attempt.error(format!("Blocked redirect: {}", e)),
+                }
+            }),
+        };
+        let mut builder = reqwest::blocking::Client::builder()
+            .redirect(redirects)
+            .timeout((self.timeout_ms > 0).then(|| Duration::from_millis(self.timeout_ms)));
+        if let Some(proxy) = &self.proxy {
+            network
+                .validate_url(proxy)
+                .map_err(|e| format!("Blocked proxy: {}", e))?;
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| format!("Invalid proxy {}", e))?
+                .no_proxy(reqwest::NoProxy::from_string(&self.no_proxy.join(",")));
+            builder = builder.proxy(proxy);
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(open_ms: u64) -> HttpClientSettings {
+        HttpClientSettings {
+            failure_threshold: 2,
+            open_ms,
+            max_concurrent_per_host: 0,
+        }
+    }
+
+    #[test]
+    fn test_circuit_opens_after_threshold() {
+        let policy = HttpClientPolicy::default();
+        let settings = settings(60_000);
+
+        assert!(policy.record(&settings, "api.example.com", false).is_none());
+        assert!(policy.record(&settings, "api.example.com", true).is_none());
+        assert!(policy.record(&settings, "api.example.com", false).is_none());
+        let opened = policy.record(&settings, "api.example.com", false).unwrap();
+        assert_eq!(opened.consecutive_failures, 2);
+
+        assert!(policy.check("api.example.com").is_err());
+        assert!(policy.check("other.example.com").is_ok());
+        policy.reset("api.example.com");
+        assert_eq!(policy.state("api.example.com"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let policy = HttpClientPolicy::default();
+        let settings = settings(0);
+        policy.record(&settings, "flaky", false);
+        policy.record(&settings, "flaky", false);
+
+        // Only one probe gets through once the open period is over.
+        assert!(policy.check("flaky").is_ok());
+        assert_eq!(policy.state("flaky"), CircuitState::HalfOpen);
+        assert!(policy.check("flaky").is_err());
+
+        // A failed probe re-opens straight away.
+        assert!(policy.record(&settings, "flaky", false).is_some());
+        assert!(policy.check("flaky").is_ok());
+        policy.record(&settings, "flaky", true);
+        assert_eq!(policy.state("flaky"), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,216 @@
+//! Outbound email over SMTP.
+//!
+//! [`EmailSender`] is shared by the Email node and by engine-level flows such as IAM magic
+//! links and member invitations, so both go through the same transport setup and network validation.
+
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::Resource;
+use ferroflux_iam::members::INVITATION_TTL;
+use ferroflux_iam::{IamStore, Invitation, Principal, Role, TenantId};
+use lettre::message::{Mailbox, header::ContentType};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (usually port 587).
+    #[default]
+    StartTls,
+    /// Implicit TLS (usually port 465).
+    Tls,
+    /// No encryption. Only for local relays and tests.
+    None,
+}
+
+/// SMTP credentials, as stored in an email connection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SmtpSettings {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Default sender address, e.g. `FerroFlux <noreply@example.com>`.
+    pub from: String,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+impl SmtpSettings {
+    /// Reads system mail settings from `FERROFLUX_SMTP_*` environment variables.
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(format!("FERROFLUX_SMTP_{}", name)).ok();
+        Some(Self {
+            host: var("HOST")?,
+            port: var("PORT").and_then(|p| p.parse().ok()),
+            username: var("USERNAME"),
+            password: var("PASSWORD"),
+            from: var("FROM")?,
+            security: match var("SECURITY").as_deref() {
+                Some("tls") => SmtpSecurity::Tls,
+                Some("none") => SmtpSecurity::None,
+                _ => SmtpSecurity::StartTls,
+            },
+        })
+    }
+
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.security {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OutgoingEmail {
+    pub to: Vec<String>,
+    /// Overrides `SmtpSettings::from`.
+    pub from: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub html: bool,
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct EmailSender {
+    /// Settings used for mail sent by the engine itself (not by workflows).
+    system: Option<SmtpSettings>,
+}
+
+impl EmailSender {
+    pub fn new(system: Option<SmtpSettings>) -> Self {
+        Self { system }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(SmtpSettings::from_env())
+    }
+
+    pub async fn send(&self, settings: &SmtpSettings, email: OutgoingEmail) -> Result<()> {
+        let port = settings.port();
+        ferroflux_security::network::validate_host_port(&settings.host, port)
+            .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+
+        let from: Mailbox = email
+            .from
+            .as_deref()
+            .unwrap_or(&settings.from)
+            .parse()
+            .context("Invalid sender address")?;
+        let mut builder = Message::builder().from(from).subject(email.subject);
+        for to in &email.to {
+            builder = builder.to(to
+                .parse()
+                .with_context(|| format!("Invalid recipient address '{}'", to))?);
+        }
+        let message = builder
+            .header(if email.html {
+                ContentType::TEXT_HTML
+            } else {
+                ContentType::TEXT_PLAIN
+            })
+            .body(email.body)?;
+
+        let mut transport = match settings.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host)?,
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+            }
+        }
+        .port(port);
+        if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        transport.build().send(message).await?;
+        Ok(())
+    }
+
+    /// Sends mail using the engine's own SMTP settings.
+    pub async fn send_system(&self, email: OutgoingEmail) -> Result<()> {
+        let settings = self
+            .system
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("System email is not configured (FERROFLUX_SMTP_*)"))?;
+        self.send(settings, email).await
+    }
+
+    /// Creates a magic link for `email` and mails it. `link_base` receives the token as a
+    /// `token` query parameter. Returns the user id the link signs in as.
+    pub async fn send_magic_link(
+        &self,
+        iam: &IamStore,
+        email: &str,
+        link_base: &str,
+    ) -> Result<String> {
+        let (token, user_id) = iam.create_magic_link(email).await?;
+        let mut link = url::Url::parse(link_base).context("Invalid magic link base URL")?;
+        link.query_pairs_mut().append_pair("token", &token);
+
+        self.send_system(OutgoingEmail {
+            to: vec![email.to_string()],
+            subject: "Your FerroFlux sign-in link".to_string(),
+            body: format!(
+                "Use the link below to sign in. It expires in 15 minutes.\n\n{}\n",
+                link
+            ),
+            ..Default::default()
+        })
+        .await?;
+        Ok(user_id)
+    }
+
+    /// Invites `email` to `tenant` and mails the invitation. `link_base` receives the token as
+    /// a `token` query parameter; the page behind it should call `IamStore::accept_invite`
+    /// once the recipient has signed in.
+    pub async fn send_invitation(
+        &self,
+        iam: &IamStore,
+        inviter: &Principal,
+        tenant: &TenantId,
+        email: &str,
+        role: Role,
+        link_base: &str,
+    ) -> Result<Invitation> {
+        let mut link = url::Url::parse(link_base).context("Invalid invitation base URL")?;
+        if !matches!(link.scheme(), "http" | "https") || link.host_str().is_none() {
+            anyhow::bail!("Invalid invitation base URL: expected an http(s) URL");
+        }
+        let invitation = iam.invite_member(inviter, tenant, email, role).await?;
+        link.query_pairs_mut()
+            .append_pair("token", &invitation.token);
+        let days = INVITATION_TTL.num_days();
+        let organization = iam
+            .get_user_tenants(&inviter.user_id)
+            .await?
+            .into_iter()
+            .find(|(id, _, _)| *id == tenant.0)
+            .map(|(_, name, _)| name)
+            .unwrap_or_else(|| tenant.to_string());
+
+        self.send_system(OutgoingEmail {
+            to: vec![invitation.email.clone()],
+            subject: format!("You're invited to {} on FerroFlux", organization),
+            body: format!(
+                "You have been invited to join {} as {}. The invitation expires in {} day{}.\n\n{}\n",
+                organization,
+                role,
+                days,
+                if days == 1 { "" } else { "s" },
+                link
+            ),
+            ..Default::default()
+        })
+        .await?;
+        Ok(invitation)
+    }
+}
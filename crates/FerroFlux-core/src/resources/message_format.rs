@@ -0,0 +1,549 @@
+//! ICU MessageFormat rendering.
+//!
+//! Supports the subset of the ICU syntax that notification templates need:
+//!
+//! - `{name}` — plain argument (numbers are formatted for the locale)
+//! - `{count, number}` / `{count, number, integer}`
+//! - `{when, date, short|medium|long|full}` / `{when, time, short|medium}`
+//! - `{count, plural, offset:1 =0 {...} one {# item} other {# items}}`
+//! - `{place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}`
+//! - `{gender, select, female {...} male {...} other {...}}`
+//!
+//! Argument names may be dotted paths into the data (`{user.name}`). Apostrophes quote
+//! syntax characters the same way ICU does (`'{'` renders `{`, `''` renders `'`).
+//! Plural categories, digit grouping and date patterns come from CLDR via `icu4x`.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
+use fixed_decimal::FixedDecimal;
+use icu_calendar::Gregorian;
+use icu_datetime::options::length;
+use icu_datetime::{TimeFormatter, TypedDateFormatter};
+use icu_decimal::FixedDecimalFormatter;
+use icu_locid::Locale;
+use icu_plurals::{PluralCategory, PluralOperands, PluralRules};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Locale used when neither the node nor the payload names one.
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    /// `#` inside a plural branch.
+    Pound,
+    Argument(String),
+    Number {
+        name: String,
+        integer: bool,
+    },
+    Date {
+        name: String,
+        length: length::Date,
+    },
+    Time {
+        name: String,
+        length: length::Time,
+    },
+    Plural {
+        name: String,
+        ordinal: bool,
+        offset: f64,
+        branches: Vec<(String, Vec<Part>)>,
+    },
+    Select {
+        name: String,
+        branches: Vec<(String, Vec<Part>)>,
+    },
+}
+
+/// Renders `template` against `data` using the plural rules and number/date formats of `locale`.
+pub fn format(template: &str, data: &Value, locale: &str) -> anyhow::Result<String> {
+    let locale: Locale = locale
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid locale '{}'", locale))?;
+    let parts = Parser::new(template).parse()?;
+    let mut out = String::new();
+    Renderer {
+        locale: &locale,
+        data,
+    }
+    .render(&parts, None, &mut out)?;
+    Ok(out)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(template: &str) -> Self {
+        Self {
+            chars: template.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn parse(mut self) -> anyhow::Result<Vec<Part>> {
+        let parts = self.message(false)?;
+        if self.pos < self.chars.len() {
+            anyhow::bail!("Unmatched '}}' at offset {}", self.pos);
+        }
+        Ok(parts)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Parses text and arguments up to an unmatched `}` (left unconsumed) or the end.
+    fn message(&mut self, in_plural: bool) -> anyhow::Result<Vec<Part>> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\'' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\'') => {
+                            text.push('\'');
+                            self.pos += 1;
+                        }
+                        Some('{' | '}') => self.quoted(&mut text),
+                        Some('#') if in_plural => self.quoted(&mut text),
+                        _ => text.push('\''),
+                    }
+                }
+                '{' => {
+                    self.pos += 1;
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.argument(in_plural)?);
+                }
+                '}' => break,
+                '#' if in_plural => {
+                    self.pos += 1;
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Pound);
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(parts)
+    }
+
+    /// Copies a quoted literal; the opening apostrophe is already consumed.
+    fn quoted(&mut self, text: &mut String) {
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '\'' {
+                if self.peek() == Some('\'') {
+                    text.push('\'');
+                    self.pos += 1;
+                } else {
+                    return;
+                }
+            } else {
+                text.push(c);
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn word(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !matches!(c, ',' | '{' | '}'))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn expect(&mut self, expected: char) -> anyhow::Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => anyhow::bail!(
+                "Expected '{}' at offset {}, found '{}'",
+                expected,
+                self.pos,
+                c
+            ),
+            None => anyhow::bail!("Expected '{}', found end of template", expected),
+        }
+    }
+
+    /// Parses an argument; the opening `{` is already consumed.
+    fn argument(&mut self, in_plural: bool) -> anyhow::Result<Part> {
+        let name = self.word();
+        if name.is_empty() {
+            anyhow::bail!("Missing argument name at offset {}", self.pos);
+        }
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Part::Argument(name));
+        }
+        self.expect(',')?;
+        let kind = self.word();
+        let part = match kind.as_str() {
+            "number" | "date" | "time" => {
+                let style = self.style()?;
+                match kind.as_str() {
+                    "number" => Part::Number {
+                        name,
+                        integer: match style.as_deref() {
+                            None => false,
+                            Some("integer") => true,
+                            Some(other) => anyhow::bail!("Unsupported number style '{}'", other),
+                        },
+                    },
+                    "date" => Part::Date {
+                        name,
+                        length: match style.as_deref() {
+                            Some("short") => length::Date::Short,
+                            None | Some("medium") => length::Date::Medium,
+                            Some("long") => length::Date::Long,
+                            Some("full") => length::Date::Full,
+                            Some(other) => anyhow::bail!("Unsupported date style '{}'", other),
+                        },
+                    },
+                    _ => Part::Time {
+                        name,
+                        // Long and full time styles include a zone name, which plain
+                        // JSON timestamps don't carry.
+                        length: match style.as_deref() {
+                            Some("short") => length::Time::Short,
+                            None | Some("medium") => length::Time::Medium,
+                            Some(other) => anyhow::bail!("Unsupported time style '{}'", other),
+                        },
+                    },
+                }
+            }
+            "plural" | "selectordinal" => {
+                self.expect(',')?;
+                let mut offset = 0.0;
+                self.skip_whitespace();
+                if self.chars[self.pos..].starts_with(&['o', 'f', 'f', 's', 'e', 't', ':']) {
+                    self.pos += "offset:".len();
+                    let value = self.word();
+                    offset = value
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid plural offset '{}'", value))?;
+                }
+                Part::Plural {
+                    name,
+                    ordinal: kind == "selectordinal",
+                    offset,
+                    branches: self.branches(true)?,
+                }
+            }
+            "select" => {
+                self.expect(',')?;
+                Part::Select {
+                    name,
+                    branches: self.branches(in_plural)?,
+                }
+            }
+            other => anyhow::bail!("Unsupported argument type '{}'", other),
+        };
+        if matches!(part, Part::Plural { .. } | Part::Select { .. }) {
+            self.expect('}')?;
+        }
+        Ok(part)
+    }
+
+    /// Reads an optional `, style` followed by the closing `}`.
+    fn style(&mut self) -> anyhow::Result<Option<String>> {
+        self.skip_whitespace();
+        if self.peek() == Some(',') {
+            self.pos += 1;
+            let style = self.word();
+            self.expect('}')?;
+            Ok(Some(style))
+        } else {
+            self.expect('}')?;
+            Ok(None)
+        }
+    }
+
+    fn branches(&mut self, in_plural: bool) -> anyhow::Result<Vec<(String, Vec<Part>)>> {
+        let mut branches = Vec::new();
+        loop {
+            let key = self.word();
+            if key.is_empty() {
+                break;
+            }
+            self.expect('{')?;
+            let message = self.message(in_plural)?;
+            self.expect('}')?;
+            branches.push((key, message));
+        }
+        if !branches.iter().any(|(key, _)| key == "other") {
+            anyhow::bail!("Plural and select arguments require an 'other' branch");
+        }
+        Ok(branches)
+    }
+}
+
+struct Renderer<'a> {
+    locale: &'a Locale,
+    data: &'a Value,
+}
+
+impl Renderer<'_> {
+    fn render(&self, parts: &[Part], pound: Option<&str>, out: &mut String) -> anyhow::Result<()> {
+        for part in parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Pound => out.push_str(pound.unwrap_or("#")),
+                Part::Argument(name) => match self.lookup(name)? {
+                    Value::String(s) => out.push_str(s),
+                    Value::Number(_) => out.push_str(&self.format_number(self.number(name)?)?),
+                    other => out.push_str(&other.to_string()),
+                },
+                Part::Number { name, integer } => {
+                    let mut number = self.number(name)?;
+                    if *integer {
+                        number.half_even(0);
+                    }
+                    out.push_str(&self.format_number(number)?);
+                }
+                Part::Date { name, length } => {
+                    let formatter = TypedDateFormatter::<Gregorian>::try_new_with_length(
+                        &self.locale.into(),
+                        *length,
+                    )
+                    .map_err(|e| anyhow::anyhow!("Date format unavailable: {}", e))?;
+                    let date = self.datetime(name)?.date;
+                    out.push_str(&formatter.format_to_string(&date));
+                }
+                Part::Time { name, length } => {
+                    let formatter =
+                        TimeFormatter::try_new_with_length(&self.locale.into(), *length)
+                            .map_err(|e| anyhow::anyhow!("Time format unavailable: {}", e))?;
+                    out.push_str(&formatter.format_to_string(&self.datetime(name)?));
+                }
+                Part::Plural {
+                    name,
+                    ordinal,
+                    offset,
+                    branches,
+                } => {
+                    let value = self.number(name)?;
+                    let exact = format!("={}", value);
+                    let adjusted = if *offset == 0.0 {
+                        value
+                    } else {
+                        let raw: f64 = value.to_string().parse()?;
+                        decimal(raw - offset)?
+                    };
+                    let rules = if *ordinal {
+                        PluralRules::try_new_ordinal(&self.locale.into())
+                    } else {
+                        PluralRules::try_new_cardinal(&self.locale.into())
+                    }
+                    .map_err(|e| anyhow::anyhow!("Plural rules unavailable: {}", e))?;
+                    let operands = PluralOperands::from(&adjusted);
+                    let category = category_name(rules.category_for(operands));
+                    let branch = pick(branches, &exact)
+                        .or_else(|| pick(branches, category))
+                        .or_else(|| pick(branches, "other"))
+                        .unwrap_or_default();
+                    let formatted = self.format_number(adjusted)?;
+                    self.render(branch, Some(&formatted), out)?;
+                }
+                Part::Select { name, branches } => {
+                    let key = match self.lookup(name)? {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    let branch = pick(branches, &key)
+                        .or_else(|| pick(branches, "other"))
+                        .unwrap_or_default();
+                    self.render(branch, pound, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> anyhow::Result<&Value> {
+        name.split('.')
+            .try_fold(self.data, |value, key| match value {
+                Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => value.get(key),
+            })
+            .filter(|value| !value.is_null())
+            .ok_or_else(|| anyhow::anyhow!("Missing argument '{}'", name))
+    }
+
+    fn number(&self, name: &str) -> anyhow::Result<FixedDecimal> {
+        match self.lookup(name)? {
+            Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => Ok(FixedDecimal::from(i)),
+                (_, Some(u), _) => Ok(FixedDecimal::from(u)),
+                (_, _, Some(f)) => decimal(f),
+                _ => anyhow::bail!("Argument '{}' is not a number", name),
+            },
+            Value::String(s) => FixedDecimal::from_str(s.trim())
+                .map_err(|_| anyhow::anyhow!("Argument '{}' is not a number", name)),
+            _ => anyhow::bail!("Argument '{}' is not a number", name),
+        }
+    }
+
+    /// Reads an RFC 3339 timestamp, a `YYYY-MM-DD` date or an epoch number (seconds, or
+    /// milliseconds when large enough). Timestamps keep the wall-clock time they were written in.
+    fn datetime(&self, name: &str) -> anyhow::Result<icu_calendar::DateTime<Gregorian>> {
+        let value = self.lookup(name)?;
+        let naive = match value {
+            Value::Number(n) => n.as_f64().and_then(|n| {
+                let millis = if n.abs() >= 1e11 { n } else { n * 1000.0 };
+                DateTime::from_timestamp_millis(millis.round() as i64).map(|dt| dt.naive_utc())
+            }),
+            Value::String(s) => DateTime::parse_from_rfc3339(s.trim())
+                .map(|dt| dt.naive_local())
+                .ok()
+                .or_else(|| NaiveDateTime::parse_from_str(s.trim(), "%Y-%m-%dT%H:%M:%S%.f").ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                }),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow::anyhow!("Argument '{}' is not a date: {}", name, value))?;
+        icu_calendar::DateTime::try_new_gregorian_datetime(
+            naive.year(),
+            naive.month() as u8,
+            naive.day() as u8,
+            naive.hour() as u8,
+            naive.minute() as u8,
+            naive.second() as u8,
+        )
+        .map_err(|e| anyhow::anyhow!("Argument '{}' is out of range: {}", name, e))
+    }
+
+    fn format_number(&self, number: FixedDecimal) -> anyhow::Result<String> {
+        let formatter = FixedDecimalFormatter::try_new(&self.locale.into(), Default::default())
+            .map_err(|e| anyhow::anyhow!("Number format unavailable: {}", e))?;
+        Ok(formatter.format_to_string(&number))
+    }
+}
+
+fn decimal(value: f64) -> anyhow::Result<FixedDecimal> {
+    // `f64`'s `Display` never uses exponent notation, which `FixedDecimal` can't read.
+    FixedDecimal::from_str(&value.to_string())
+        .map_err(|_| anyhow::anyhow!("Cannot format {} as a number", value))
+}
+
+fn pick<'a>(branches: &'a [(String, Vec<Part>)], key: &str) -> Option<&'a [Part]> {
+    branches
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, parts)| parts.as_slice())
+}
+
+fn category_name(category: PluralCategory) -> &'static str {
+    match category {
+        PluralCategory::Zero => "zero",
+        PluralCategory::One => "one",
+        PluralCategory::Two => "two",
+        PluralCategory::Few => "few",
+        PluralCategory::Many => "many",
+        PluralCategory::Other => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plural_select_and_quoting() {
+        let template = "{gender, select, female {She} male {He} other {They}} invited \
+                        {guests, plural, offset:1 =0 {nobody} =1 {you} one {you and # other} \
+                        other {you and # others}}. '{'literal'}' it''s";
+        let render = |gender: &str, guests: u32| {
+            format(
+                template,
+                &json!({ "gender": gender, "guests": guests }),
+                "en",
+            )
+            .unwrap()
+        };
+        assert_eq!(render("female", 0), "She invited nobody. {literal} it's");
+        assert_eq!(
+            render("male", 2),
+            "He invited you and 1 other. {literal} it's"
+        );
+        assert_eq!(
+            render("x", 1002),
+            "They invited you and 1,001 others. {literal} it's"
+        );
+    }
+
+    #[test]
+    fn test_locale_specific_rules() {
+        // Polish distinguishes one/few/many.
+        let template = "{n, plural, one {# plik} few {# pliki} many {# plików} other {# pliku}}";
+        let polish = |n: f64| format(template, &json!({ "n": n }), "pl").unwrap();
+        assert_eq!(polish(1.0), "1 plik");
+        assert_eq!(polish(3.0), "3 pliki");
+        assert_eq!(polish(5.0), "5 plików");
+        assert_eq!(polish(1.5), "1,5 pliku");
+
+        let ordinal = "{n, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}";
+        assert_eq!(format(ordinal, &json!({ "n": 22 }), "en").unwrap(), "22nd");
+    }
+
+    #[test]
+    fn test_number_and_date_formats() {
+        let template = "{total, number} / {total, number, integer} am {when, date, long} um {when, time, short}";
+        let data = json!({ "total": 1234567.5, "when": "2024-03-01T13:05:00+01:00" });
+        assert_eq!(
+            format(template, &data, "de").unwrap(),
+            "1.234.567,5 / 1.234.568 am 1. März 2024 um 13:05"
+        );
+        assert_eq!(
+            format(
+                "{when, date, short}",
+                &json!({ "when": "2024-03-01" }),
+                "en-US"
+            )
+            .unwrap(),
+            "3/1/24"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let data = json!({ "n": 1 });
+        assert!(format("{n, plural, one {x}}", &data, "en").is_err());
+        assert!(format("{missing}", &data, "en").is_err());
+        assert!(format("{n, number", &data, "en").is_err());
+        assert!(format("{n}", &data, "not a locale!").is_err());
+    }
+}
@@ -0,0 +1,288 @@
+//! Engine self-profiling.
+//!
+//! Systems registered through [`profiled`] report their run time to the [`EngineProfiler`]
+//! resource; [`App::update`](crate::app::App::update) adds the tick duration and, once per
+//! interval, broadcasts a [`SystemEvent::EngineStats`] summary on the event bus. Without the
+//! resource the wrapper does nothing, so profiling costs nothing when it is switched off.
+
+use crate::api::events::{SystemEvent, SystemEventBus, SystemTiming};
+use crate::components::core::NodeConfig;
+use bevy_ecs::archetype::ArchetypeComponentId;
+use bevy_ecs::component::{ComponentId, Tick};
+use bevy_ecs::prelude::*;
+use bevy_ecs::query::Access;
+use bevy_ecs::schedule::InternedSystemSet;
+use bevy_ecs::world::unsafe_world_cell::UnsafeWorldCell;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default reporting interval.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+/// Number of systems listed in each report.
+const TOP_SYSTEMS: usize = 5;
+
+/// Run time accumulated by one system since the last report.
+#[derive(Debug, Default)]
+struct SystemSample {
+    name: String,
+    runs: AtomicU64,
+    total_ns: AtomicU64,
+    max_ns: AtomicU64,
+}
+
+impl SystemSample {
+    fn record(&self, elapsed: Duration) {
+        let ns = elapsed.as_nanos() as u64;
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.total_ns.fetch_add(ns, Ordering::Relaxed);
+        self.max_ns.fetch_max(ns, Ordering::Relaxed);
+    }
+
+    fn take(&self) -> SystemTiming {
+        SystemTiming {
+            name: self.name.clone(),
+            runs: self.runs.swap(0, Ordering::Relaxed),
+            total_ms: self.total_ns.swap(0, Ordering::Relaxed) as f64 / 1e6,
+            max_ms: self.max_ns.swap(0, Ordering::Relaxed) as f64 / 1e6,
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EngineProfiler {
+    interval: Duration,
+    /// Shared with every [`Profiled`] system; systems record without locking.
+    systems: Arc<Mutex<Vec<Arc<SystemSample>>>>,
+    window_start: Instant,
+    ticks: u64,
+    tick_total: Duration,
+    tick_max: Duration,
+}
+
+impl Default for EngineProfiler {
+    fn default() -> Self {
+        Self::new(DEFAULT_INTERVAL)
+    }
+}
+
+impl EngineProfiler {
+    /// Reports every `interval`. `Duration::ZERO` reports after every tick.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            systems: Arc::default(),
+            window_start: Instant::now(),
+            ticks: 0,
+            tick_total: Duration::ZERO,
+            tick_max: Duration::ZERO,
+        }
+    }
+
+    /// Reads the interval from `FERROFLUX_STATS_INTERVAL_SECS`. Profiling is off when it is
+    /// unset or `0`.
+    pub fn from_env() -> Option<Self> {
+        match std::env::var("FERROFLUX_STATS_INTERVAL_SECS") {
+            Ok(secs) => match secs.trim().parse::<u64>() {
+                Ok(0) => None,
+                Ok(secs) => Some(Self::new(Duration::from_secs(secs))),
+                Err(_) => {
+                    tracing::warn!(value = %secs, "Invalid FERROFLUX_STATS_INTERVAL_SECS, using default");
+                    Some(Self::default())
+                }
+            },
+            Err(_) => None,
+        }
+    }
+
+    fn register(&self, name: String) -> Arc<SystemSample> {
+        let sample = Arc::new(SystemSample {
+            name,
+            ..SystemSample::default()
+        });
+        self.systems.lock().unwrap().push(sample.clone());
+        sample
+    }
+
+    /// Adds one schedule run to the current window.
+    pub fn record_tick(&mut self, elapsed: Duration) {
+        self.ticks += 1;
+        self.tick_total += elapsed;
+        self.tick_max = self.tick_max.max(elapsed);
+    }
+
+    fn is_due(&self) -> bool {
+        self.window_start.elapsed() >= self.interval
+    }
+
+    /// Closes the current window and returns its stats.
+    fn take_report(&mut self, entity_count: u32, node_count: u32) -> SystemEvent {
+        let window = self.window_start.elapsed();
+        let mut systems: Vec<SystemTiming> = self
+            .systems
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|sample| sample.take())
+            .filter(|timing| timing.runs > 0)
+            .collect();
+        systems.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        systems.truncate(TOP_SYSTEMS);
+
+        let event = SystemEvent::EngineStats {
+            ticks: self.ticks,
+            avg_tick_ms: if self.ticks == 0 {
+                0.0
+            } else {
+                self.tick_total.as_secs_f64() * 1e3 / self.ticks as f64
+            },
+            max_tick_ms: self.tick_max.as_secs_f64() * 1e3,
+            busy_ratio: if window.is_zero() {
+                0.0
+            } else {
+                (self.tick_total.as_secs_f64() / window.as_secs_f64()).min(1.0)
+            },
+            slowest_systems: systems,
+            entity_count,
+            node_count,
+            memory_bytes: resident_memory_bytes(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+
+        self.window_start = Instant::now();
+        self.ticks = 0;
+        self.tick_total = Duration::ZERO;
+        self.tick_max = Duration::ZERO;
+        event
+    }
+}
+
+/// Records a tick that took `elapsed` and broadcasts `EngineStats` when the interval is up.
+///
+/// Called by [`App::update`](crate::app::App::update) after the schedule has run.
+pub fn record_tick(world: &mut World, elapsed: Duration) {
+    let due = match world.get_resource_mut::<EngineProfiler>() {
+        Some(mut profiler) => {
+            profiler.record_tick(elapsed);
+            profiler.is_due()
+        }
+        None => return,
+    };
+    if !due {
+        return;
+    }
+
+    let entity_count = world.entities().len();
+    let node_count = world.query::<&NodeConfig>().iter(world).count() as u32;
+    let event = world
+        .resource_mut::<EngineProfiler>()
+        .take_report(entity_count, node_count);
+    if let Some(bus) = world.get_resource::<SystemEventBus>() {
+        let _ = bus.send(event);
+    }
+}
+
+/// Resident set size of the process, where the platform exposes it.
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Wraps a system so its run time is reported to the [`EngineProfiler`].
+pub fn profiled<M, S: IntoSystem<(), (), M>>(system: S) -> Profiled<S::System> {
+    Profiled {
+        inner: IntoSystem::into_system(system),
+        sample: None,
+    }
+}
+
+/// A system timed by the [`EngineProfiler`]; see [`profiled`].
+pub struct Profiled<S> {
+    inner: S,
+    sample: Option<Arc<SystemSample>>,
+}
+
+impl<S: System<In = (), Out = ()>> System for Profiled<S> {
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.inner.name()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.inner.component_access()
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        self.inner.archetype_component_access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.inner.is_send()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        self.inner.is_exclusive()
+    }
+
+    fn has_deferred(&self) -> bool {
+        self.inner.has_deferred()
+    }
+
+    unsafe fn run_unsafe(&mut self, input: (), world: UnsafeWorldCell) {
+        let start = Instant::now();
+        // SAFETY: forwarded unchanged; the caller upholds the inner system's contract.
+        unsafe { self.inner.run_unsafe(input, world) };
+        if let Some(sample) = &self.sample {
+            sample.record(start.elapsed());
+        }
+    }
+
+    fn run(&mut self, input: (), world: &mut World) {
+        // Exclusive systems (e.g. `api_command_worker`) are only runnable through `run`.
+        let start = Instant::now();
+        self.inner.run(input, world);
+        if let Some(sample) = &self.sample {
+            sample.record(start.elapsed());
+        }
+    }
+
+    fn apply_deferred(&mut self, world: &mut World) {
+        self.inner.apply_deferred(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.inner.initialize(world);
+        if let Some(profiler) = world.get_resource::<EngineProfiler>() {
+            let name = self.inner.name();
+            // `ferroflux_core::systems::io::http::http_worker` -> `http_worker`
+            let short = name.rsplit("::").next().unwrap_or(&name).to_string();
+            self.sample = Some(profiler.register(short));
+        }
+    }
+
+    fn update_archetype_component_access(&mut self, world: UnsafeWorldCell) {
+        self.inner.update_archetype_component_access(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: Tick) {
+        self.inner.check_change_tick(change_tick);
+    }
+
+    fn default_system_sets(&self) -> Vec<InternedSystemSet> {
+        // Keeps `.before(worker)` / `.after(worker)` ordering working on wrapped systems.
+        self.inner.default_system_sets()
+    }
+
+    fn get_last_run(&self) -> Tick {
+        self.inner.get_last_run()
+    }
+
+    fn set_last_run(&mut self, last_run: Tick) {
+        self.inner.set_last_run(last_run);
+    }
+}
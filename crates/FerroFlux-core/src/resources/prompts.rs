@@ -0,0 +1,126 @@
+//! Versioned prompt templates shared by agents.
+//!
+//! Templates live in the `prompt_templates` table of the `PersistentStore`; the
+//! [`PromptLibrary`] resource mirrors it so `agent_prep` can resolve references without a
+//! database round trip. `ApiCommand::SavePromptTemplate` and `ApiCommand::DeletePromptTemplate`
+//! write through to both, so an edited prompt reaches deployed agents on their next ticket.
+
+use crate::store::database::{PersistentStore, PromptTemplate};
+use bevy_ecs::prelude::Resource;
+use ferroflux_iam::TenantId;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// A reference to a library template: `name` for the latest version or `name@version` for a
+/// fixed one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromptRef {
+    pub name: String,
+    pub version: Option<u32>,
+}
+
+impl FromStr for PromptRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (name, version) = match s.rsplit_once('@') {
+            Some((name, version)) => (
+                name,
+                Some(
+                    version
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid prompt version in '{}'", s))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        validate_name(name)?;
+        Ok(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+impl std::fmt::Display for PromptRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "{}@{}", self.name, version),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Template names may not be empty or contain `@`, which separates the version.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() || name.contains('@') {
+        anyhow::bail!("Invalid prompt template name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Every prompt template version, by tenant and name.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PromptLibrary {
+    templates: HashMap<TenantId, HashMap<String, BTreeMap<u32, PromptTemplate>>>,
+}
+
+impl PromptLibrary {
+    pub async fn load(store: &PersistentStore) -> anyhow::Result<Self> {
+        let mut library = Self::default();
+        for (tenant, template) in store.load_prompt_templates().await? {
+            library.insert(&tenant, template);
+        }
+        Ok(library)
+    }
+
+    pub fn insert(&mut self, tenant: &TenantId, template: PromptTemplate) {
+        self.templates
+            .entry(tenant.clone())
+            .or_default()
+            .entry(template.name.clone())
+            .or_default()
+            .insert(template.version, template);
+    }
+
+    /// Removes one version, or the whole template when `version` is `None`.
+    pub fn remove(&mut self, tenant: &TenantId, name: &str, version: Option<u32>) {
+        let Some(templates) = self.templates.get_mut(tenant) else {
+            return;
+        };
+        match version {
+            Some(version) => {
+                if let Some(versions) = templates.get_mut(name) {
+                    versions.remove(&version);
+                    if versions.is_empty() {
+                        templates.remove(name);
+                    }
+                }
+            }
+            None => {
+                templates.remove(name);
+            }
+        }
+    }
+
+    pub fn resolve(&self, tenant: &TenantId, prompt: &PromptRef) -> Option<&PromptTemplate> {
+        let versions = self.templates.get(tenant)?.get(&prompt.name)?;
+        match prompt.version {
+            Some(version) => versions.get(&version),
+            None => versions.values().next_back(),
+        }
+    }
+
+    /// The tenant's templates, every version, ordered by name then version.
+    pub fn list(&self, tenant: &TenantId) -> Vec<&PromptTemplate> {
+        let mut templates: Vec<_> = self
+            .templates
+            .get(tenant)
+            .into_iter()
+            .flat_map(|templates| templates.values())
+            .flat_map(|versions| versions.values())
+            .collect();
+        templates.sort_by(|a, b| (&a.name, a.version).cmp(&(&b.name, b.version)));
+        templates
+    }
+}
@@ -0,0 +1,252 @@
+//! Tenant quotas, so one tenant of a shared engine can't exhaust it for the others.
+//!
+//! Limits live in the `tenant_limits` table of the `PersistentStore`; [`TenantQuotas`] mirrors
+//! them together with the usage they cap, so the systems enforcing them never wait on the
+//! database. `ApiCommand::SetTenantLimits` writes through to both.
+//!
+//! - `max_workflows`: `load_blueprint` rejects a graph that would add one workflow too many.
+//! - `max_runs_per_day` and `max_payload_mb`: checked by [`admit_run`] wherever a run starts —
+//!   trigger commands refuse it, while cron, webhook and connector sources (AMQP, IMAP, SSE,
+//!   WebSocket) drop the tick, request or message. `max_payload_mb` caps the size of the
+//!   payload a single run starts with; it does not limit the tenant's total blob storage.
+//! - `max_agent_tokens_per_month`: `agent_exec` answers further requests with a 429 instead of
+//!   calling the provider.
+//!
+//! Token usage is seeded from `llm_usage` at startup; the daily run count is kept in memory
+//! only and starts over when the engine restarts.
+
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::NodeConfig;
+use crate::store::database::{PersistentStore, TenantLimits};
+use bevy_ecs::prelude::Resource;
+use chrono::{Datelike, NaiveDate, Utc};
+use ferroflux_iam::TenantId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quota {
+    Workflows,
+    RunsPerDay,
+    AgentTokensPerMonth,
+    PayloadMb,
+}
+
+impl Quota {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Quota::Workflows => "workflows",
+            Quota::RunsPerDay => "runs_per_day",
+            Quota::AgentTokensPerMonth => "agent_tokens_per_month",
+            Quota::PayloadMb => "payload_mb",
+        }
+    }
+}
+
+impl std::fmt::Display for Quota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Work refused because the tenant reached one of its limits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaError {
+    pub tenant_id: TenantId,
+    pub quota: Quota,
+    pub limit: u64,
+    /// Usage at the time of the refusal, including the refused payload for `payload_mb`.
+    pub used: u64,
+}
+
+impl std::fmt::Display for QuotaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Tenant '{}' exceeded its {} quota ({} of {})",
+            self.tenant_id, self.quota, self.used, self.limit
+        )
+    }
+}
+
+impl std::error::Error for QuotaError {}
+
+impl QuotaError {
+    /// Logs the refusal and broadcasts it as a `SystemEvent::QuotaExceeded`.
+    pub fn notify(&self, bus: Option<&SystemEventBus>, workflow_id: Option<String>) {
+        tracing::warn!(tenant = %self.tenant_id, quota = %self.quota, limit = self.limit, used = self.used, "Tenant quota exceeded");
+        if let Some(bus) = bus {
+            let _ = bus.send(SystemEvent::QuotaExceeded {
+                tenant_id: self.tenant_id.0.clone(),
+                quota: self.quota,
+                limit: self.limit,
+                used: self.used,
+                workflow_id,
+                timestamp: Utc::now().timestamp_millis(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct QuotaState {
+    limits: HashMap<TenantId, TenantLimits>,
+    /// Runs started on the given day.
+    runs: HashMap<TenantId, (NaiveDate, u64)>,
+    /// Agent tokens used in the month starting on the given day.
+    tokens: HashMap<TenantId, (NaiveDate, u64)>,
+}
+
+/// Limits and current usage by tenant, shared by every clone. Tenants without limits are
+/// unlimited.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TenantQuotas(Arc<Mutex<QuotaState>>);
+
+fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+impl TenantQuotas {
+    pub async fn load(store: &PersistentStore) -> anyhow::Result<Self> {
+        let quotas = Self::default();
+        for (tenant, limits) in store.load_tenant_limits().await? {
+            quotas.set_limits(&tenant, limits);
+        }
+        let month = month_start(Utc::now().date_naive());
+        for (tenant, tokens) in store.token_totals_since(month).await? {
+            quotas.record_agent_tokens(&tenant, tokens.max(0) as u64);
+        }
+        Ok(quotas)
+    }
+
+    pub fn limits(&self, tenant: &TenantId) -> TenantLimits {
+        let state = self.0.lock().unwrap();
+        state.limits.get(tenant).copied().unwrap_or_default()
+    }
+
+    pub fn set_limits(&self, tenant: &TenantId, limits: TenantLimits) {
+        let mut state = self.0.lock().unwrap();
+        state.limits.insert(tenant.clone(), limits);
+    }
+
+    /// Checks that the tenant may load one more workflow on top of the `loaded` ones.
+    pub fn check_workflows(&self, tenant: &TenantId, loaded: usize) -> Result<(), QuotaError> {
+        let limit = self.limits(tenant).max_workflows;
+        check(tenant, Quota::Workflows, limit, loaded as u64)
+    }
+
+    /// Admits a run starting with a payload of `payload_bytes` and counts it.
+    pub fn start_run(&self, tenant: &TenantId, payload_bytes: usize) -> Result<(), QuotaError> {
+        let today = Utc::now().date_naive();
+        let mut state = self.0.lock().unwrap();
+        let limits = state.limits.get(tenant).copied().unwrap_or_default();
+
+        if let Some(limit) = limits.max_payload_mb {
+            let megabytes = (payload_bytes as u64).div_ceil(1024 * 1024);
+            if megabytes > limit {
+                return Err(QuotaError {
+                    tenant_id: tenant.clone(),
+                    quota: Quota::PayloadMb,
+                    limit,
+                    used: megabytes,
+                });
+            }
+        }
+
+        let runs = state.runs.entry(tenant.clone()).or_insert((today, 0));
+        if runs.0 != today {
+            *runs = (today, 0);
+        }
+        check(tenant, Quota::RunsPerDay, limits.max_runs_per_day, runs.1)?;
+        runs.1 += 1;
+        Ok(())
+    }
+
+    /// Checks that the tenant has agent tokens left this month.
+    pub fn check_agent_tokens(&self, tenant: &TenantId) -> Result<(), QuotaError> {
+        let limit = self.limits(tenant).max_agent_tokens_per_month;
+        check(
+            tenant,
+            Quota::AgentTokensPerMonth,
+            limit,
+            self.agent_tokens_this_month(tenant),
+        )
+    }
+
+    pub fn record_agent_tokens(&self, tenant: &TenantId, tokens: u64) {
+        let month = month_start(Utc::now().date_naive());
+        let mut state = self.0.lock().unwrap();
+        let used = state.tokens.entry(tenant.clone()).or_insert((month, 0));
+        if used.0 != month {
+            *used = (month, 0);
+        }
+        used.1 += tokens;
+    }
+
+    pub fn runs_today(&self, tenant: &TenantId) -> u64 {
+        let state = self.0.lock().unwrap();
+        match state.runs.get(tenant) {
+            Some((day, runs)) if *day == Utc::now().date_naive() => *runs,
+            _ => 0,
+        }
+    }
+
+    pub fn agent_tokens_this_month(&self, tenant: &TenantId) -> u64 {
+        let state = self.0.lock().unwrap();
+        match state.tokens.get(tenant) {
+            Some((month, tokens)) if *month == month_start(Utc::now().date_naive()) => *tokens,
+            _ => 0,
+        }
+    }
+}
+
+/// Admits a run through the tenant's quotas, logging and broadcasting a refusal. Every ingress
+/// starts its runs through here; without a `TenantQuotas` resource every run is admitted.
+pub fn admit_run(
+    quotas: Option<&TenantQuotas>,
+    bus: Option<&SystemEventBus>,
+    tenant: &TenantId,
+    workflow_id: Option<String>,
+    payload_bytes: usize,
+) -> Result<(), QuotaError> {
+    let Some(quotas) = quotas else {
+        return Ok(());
+    };
+    quotas
+        .start_run(tenant, payload_bytes)
+        .inspect_err(|e| e.notify(bus, workflow_id))
+}
+
+/// [`admit_run`] for a run started by a source node, counted against the node's tenant.
+pub fn admit_node_run(
+    quotas: Option<&TenantQuotas>,
+    bus: Option<&SystemEventBus>,
+    node: &NodeConfig,
+    payload_bytes: usize,
+) -> Result<(), QuotaError> {
+    let tenant = node
+        .tenant_id
+        .clone()
+        .unwrap_or_else(|| TenantId::from("default_tenant"));
+    admit_run(
+        quotas,
+        bus,
+        &tenant,
+        node.workflow_id.clone(),
+        payload_bytes,
+    )
+}
+
+fn check(tenant: &TenantId, quota: Quota, limit: Option<u64>, used: u64) -> Result<(), QuotaError> {
+    match limit {
+        Some(limit) if used >= limit => Err(QuotaError {
+            tenant_id: tenant.clone(),
+            quota,
+            limit,
+            used,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,199 @@
+//! Records live HTTP traffic as test fixtures.
+//!
+//! While authoring an integration YAML, set `FERROFLUX_HTTP_RECORD=<file>` (or insert an
+//! [`HttpRecorder`] yourself) and run the flow against the real API. Every request made by
+//! the HTTP worker is written to the file with credentials redacted. In a wiremock test, load
+//! the file with [`HttpFixtures::load`] and mount one mock per [`RecordedExchange`].
+
+use anyhow::Result;
+use bevy_ecs::prelude::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Replacement text for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Header and query parameter names (lowercase) whose values are always redacted.
+const SENSITIVE_NAMES: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "api_key",
+    "apikey",
+    "access_token",
+    "client_secret",
+    "password",
+    "signature",
+];
+
+fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_NAMES.contains(&name.as_str())
+        || ["token", "secret", "api-key", "key"]
+            .iter()
+            .any(|marker| name.ends_with(marker))
+}
+
+/// One request/response pair.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub method: String,
+    pub path: String,
+    /// Query string without the leading `?`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub request_headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub response_headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub response_body: String,
+}
+
+/// The on-disk fixture file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpFixtures {
+    pub exchanges: Vec<RecordedExchange>,
+}
+
+impl HttpFixtures {
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// A request as sent by the HTTP worker, before redaction.
+pub struct HttpRequestRecord<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: &'a [(String, String)],
+    pub body: Option<&'a [u8]>,
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct HttpRecorder {
+    fixtures: Arc<Mutex<HttpFixtures>>,
+    /// When set, the whole fixture file is rewritten after every exchange.
+    output: Option<PathBuf>,
+}
+
+impl HttpRecorder {
+    /// Records in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records into `path`, keeping the file up to date as requests complete.
+    pub fn to_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            output: Some(path.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Enables recording when `FERROFLUX_HTTP_RECORD` names an output file.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("FERROFLUX_HTTP_RECORD")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(Self::to_file)
+    }
+
+    pub fn fixtures(&self) -> HttpFixtures {
+        self.fixtures.lock().unwrap().clone()
+    }
+
+    /// Redacts and stores one exchange.
+    pub fn record(
+        &self,
+        request: HttpRequestRecord<'_>,
+        status: u16,
+        response_headers: &[(String, String)],
+        response_body: &str,
+    ) {
+        // Values of sensitive headers are also scrubbed wherever else they show up.
+        let mut secrets: Vec<String> = Vec::new();
+        let mut redact_headers = |headers: &[(String, String)]| -> BTreeMap<String, String> {
+            headers
+                .iter()
+                .map(|(name, value)| {
+                    if is_sensitive(name) {
+                        secrets.extend(value.split_whitespace().map(str::to_string));
+                        (name.to_ascii_lowercase(), REDACTED.to_string())
+                    } else {
+                        (name.to_ascii_lowercase(), value.clone())
+                    }
+                })
+                .collect()
+        };
+        let request_headers = redact_headers(request.headers);
+        let response_headers = redact_headers(response_headers);
+        // Scheme words like "Bearer" are not secret.
+        secrets.retain(|s| s.len() >= 8);
+
+        let (path, query) = match url::Url::parse(request.url) {
+            Ok(url) => {
+                let pairs: Vec<(String, String)> = url
+                    .query_pairs()
+                    .map(|(k, v)| {
+                        if is_sensitive(&k) {
+                            secrets.push(v.to_string());
+                            (k.into_owned(), REDACTED.to_string())
+                        } else {
+                            (k.into_owned(), v.into_owned())
+                        }
+                    })
+                    .collect();
+                let query = (!pairs.is_empty()).then(|| {
+                    url::form_urlencoded::Serializer::new(String::new())
+                        .extend_pairs(pairs)
+                        .finish()
+                });
+                (url.path().to_string(), query)
+            }
+            Err(_) => (request.url.to_string(), None),
+        };
+
+        let scrub = |text: &str| {
+            secrets
+                .iter()
+                .filter(|s| !s.is_empty())
+                .fold(text.to_string(), |acc, secret| {
+                    acc.replace(secret.as_str(), REDACTED)
+                })
+        };
+
+        let exchange = RecordedExchange {
+            method: request.method.to_uppercase(),
+            path: scrub(&path),
+            query,
+            request_headers,
+            request_body: request
+                .body
+                .filter(|b| !b.is_empty())
+                .map(|b| scrub(&String::from_utf8_lossy(b))),
+            status,
+            response_headers,
+            response_body: scrub(response_body),
+        };
+
+        let mut fixtures = self.fixtures.lock().unwrap();
+        fixtures.exchanges.push(exchange);
+        if let Some(path) = &self.output
+            && let Err(e) = fixtures.save(path)
+        {
+            tracing::error!(path = ?path, error = %e, "Failed to write HTTP fixtures");
+        }
+    }
+}
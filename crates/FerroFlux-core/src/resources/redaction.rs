@@ -0,0 +1,116 @@
+//! Masks known secret values before they leave the engine.
+//!
+//! The `DatabaseSecretStore` registers every credential it resolves (and every secret it reads
+//! from the environment) with its [`SecretRedactor`]; the HTTP worker does the same for the
+//! environment secrets it reads directly. [`SystemEventBus::send`] redacts every event before
+//! it is broadcast, the engine's `AnalyticsBatcher` redacts tracked payloads, and with
+//! `capture.redact_request_bodies` set the recorded HTTP request bodies are scrubbed too.
+//!
+//! [`SystemEventBus::send`]: crate::api::events::SystemEventBus::send
+
+use crate::resources::recorder::REDACTED;
+use bevy_ecs::prelude::Resource;
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+
+/// Shorter values (ports, flags, region codes) would mask ordinary text.
+pub const MIN_SECRET_LEN: usize = 6;
+
+/// Connection fields that describe where and how to connect rather than hold a secret.
+const PUBLIC_FIELDS: &[&str] = &[
+    "auth_scheme",
+    "auth_type",
+    "base_url",
+    "host",
+    "port",
+    "region",
+    "url",
+    "username",
+];
+
+/// The secret values seen so far, shared by every clone.
+#[derive(Resource, Clone, Default)]
+pub struct SecretRedactor {
+    /// Longest first, so a secret containing another is masked whole.
+    secrets: Arc<RwLock<Vec<String>>>,
+}
+
+impl SecretRedactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, secret: &str) {
+        let secret = secret.trim();
+        if secret.len() < MIN_SECRET_LEN {
+            return;
+        }
+        let mut secrets = self.secrets.write().unwrap();
+        if let Err(at) = secrets.binary_search_by(|known| {
+            secret
+                .len()
+                .cmp(&known.len())
+                .then_with(|| known.as_str().cmp(secret))
+        }) {
+            secrets.insert(at, secret.to_string());
+        }
+    }
+
+    /// Registers every string in a resolved connection except its [`PUBLIC_FIELDS`].
+    pub fn register_credentials(&self, credentials: &Value) {
+        match credentials {
+            Value::String(secret) => self.register(secret),
+            Value::Array(items) => items
+                .iter()
+                .for_each(|item| self.register_credentials(item)),
+            Value::Object(fields) => fields
+                .iter()
+                .filter(|(name, _)| !PUBLIC_FIELDS.contains(&name.as_str()))
+                .for_each(|(_, value)| self.register_credentials(value)),
+            _ => {}
+        }
+    }
+
+    /// Registers the credential part of a header value, e.g. the token of `Bearer <token>`.
+    pub fn register_header(&self, value: &str) {
+        if let Some(credential) = value.split_whitespace().next_back() {
+            self.register(credential);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.read().unwrap().is_empty()
+    }
+
+    /// `text` with every known secret replaced by `[REDACTED]`.
+    pub fn redact(&self, text: &str) -> String {
+        let secrets = self.secrets.read().unwrap();
+        secrets
+            .iter()
+            .filter(|secret| text.contains(secret.as_str()))
+            .fold(text.to_string(), |acc, secret| {
+                acc.replace(secret.as_str(), REDACTED)
+            })
+    }
+
+    /// Redacts every string in `value`, object keys included.
+    pub fn redact_value(&self, value: &mut Value) {
+        if self.is_empty() {
+            return;
+        }
+        match value {
+            Value::String(text) => *text = self.redact(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(fields) => {
+                *fields = std::mem::take(fields)
+                    .into_iter()
+                    .map(|(name, mut value)| {
+                        self.redact_value(&mut value);
+                        (self.redact(&name), value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::nodes::definition::{NodeDefinition, PlatformDefinition};
-use crate::traits::node_factory::NodeFactory;
+use crate::traits::node_factory::{NodeFactory, NodeMetadata};
 use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
 use std::collections::HashMap;
 
 #[derive(Resource, Default, Clone)]
@@ -16,9 +17,13 @@ impl DefinitionRegistry {
     }
 }
 
+/// Node factories by node type. Engine-wide types are visible to every tenant; types a tenant
+/// installed (its plugins) are namespaced by the tenant and only visible to it, shadowing an
+/// engine-wide type of the same id.
 #[derive(Resource, Default)]
 pub struct NodeRegistry {
     factories: HashMap<String, Box<dyn NodeFactory>>,
+    tenant_factories: HashMap<(TenantId, String), Box<dyn NodeFactory>>,
 }
 
 impl NodeRegistry {
@@ -30,19 +35,172 @@ impl NodeRegistry {
         self.factories.insert(node_type.to_lowercase(), factory);
     }
 
+    /// Removes a node type, returning whether it was registered.
+    pub fn unregister(&mut self, node_type: &str) -> bool {
+        self.factories.remove(&node_type.to_lowercase()).is_some()
+    }
+
+    /// Registers a node type visible to `tenant` only.
+    pub fn register_for(
+        &mut self,
+        tenant: &TenantId,
+        node_type: &str,
+        factory: Box<dyn NodeFactory>,
+    ) {
+        self.tenant_factories
+            .insert((tenant.clone(), node_type.to_lowercase()), factory);
+    }
+
+    /// Whether `tenant` registered `node_type` itself.
+    pub fn has_tenant_type(&self, tenant: &TenantId, node_type: &str) -> bool {
+        self.tenant_factories
+            .contains_key(&(tenant.clone(), node_type.to_lowercase()))
+    }
+
+    /// The engine-wide factory for `node_type`.
     pub fn get(&self, node_type: &str) -> Option<&dyn NodeFactory> {
         self.factories
             .get(&node_type.to_lowercase())
             .map(|b| b.as_ref())
     }
 
+    /// The factory `tenant` builds `node_type` with: its own, else the engine-wide one.
+    pub fn get_for(&self, tenant: Option<&TenantId>, node_type: &str) -> Option<&dyn NodeFactory> {
+        let node_type = node_type.to_lowercase();
+        tenant
+            .and_then(|tenant| {
+                self.tenant_factories
+                    .get(&(tenant.clone(), node_type.clone()))
+            })
+            .or_else(|| self.factories.get(&node_type))
+            .map(|b| b.as_ref())
+    }
+
     pub fn clear(&mut self) {
         self.factories.clear();
+        self.tenant_factories.clear();
     }
 
+    /// Metadata of the engine-wide node types.
     pub fn list_templates(&self) -> Vec<crate::traits::node_factory::NodeMetadata> {
         self.factories.values().map(|f| f.metadata()).collect()
     }
+
+    /// Metadata of the node types visible to `tenant`.
+    pub fn list_templates_for(&self, tenant: Option<&TenantId>) -> Vec<NodeMetadata> {
+        let Some(tenant) = tenant else {
+            return self.list_templates();
+        };
+        let own: HashMap<&str, &dyn NodeFactory> = self
+            .tenant_factories
+            .iter()
+            .filter(|((owner, _), _)| owner == tenant)
+            .map(|((_, node_type), factory)| (node_type.as_str(), factory.as_ref()))
+            .collect();
+        self.factories
+            .iter()
+            .filter(|(node_type, _)| !own.contains_key(node_type.as_str()))
+            .map(|(_, factory)| factory.metadata())
+            .chain(own.values().map(|factory| factory.metadata()))
+            .collect()
+    }
+
+    /// Metadata of every engine-wide node type, ordered by category then name, for building an
+    /// "add node" palette.
+    pub fn catalog(&self) -> Vec<NodeMetadata> {
+        self.catalog_for(None)
+    }
+
+    /// [`catalog`](Self::catalog) including the node types `tenant` installed.
+    pub fn catalog_for(&self, tenant: Option<&TenantId>) -> Vec<NodeMetadata> {
+        let mut catalog = self.list_templates_for(tenant);
+        catalog.sort_by(|a, b| {
+            (a.category.to_lowercase(), a.name.to_lowercase(), &a.id).cmp(&(
+                b.category.to_lowercase(),
+                b.name.to_lowercase(),
+                &b.id,
+            ))
+        });
+        catalog
+    }
+
+    /// Node types matching `query`, best match first.
+    ///
+    /// Matching is fuzzy: the query's characters must appear in order in the type's name, id
+    /// or description, ignoring case and whitespace, with contiguous runs and word starts
+    /// ranking higher. An empty query matches everything, in catalog order. `category`
+    /// restricts results to one category (case-insensitive).
+    pub fn search(&self, query: &str, category: Option<&str>) -> Vec<NodeMetadata> {
+        self.search_for(None, query, category)
+    }
+
+    /// [`search`](Self::search) including the node types `tenant` installed.
+    pub fn search_for(
+        &self,
+        tenant: Option<&TenantId>,
+        query: &str,
+        category: Option<&str>,
+    ) -> Vec<NodeMetadata> {
+        let mut matches: Vec<(u32, NodeMetadata)> = self
+            .catalog_for(tenant)
+            .into_iter()
+            .filter(|meta| category.is_none_or(|c| meta.category.eq_ignore_ascii_case(c)))
+            .filter_map(|meta| {
+                let description = meta.description.as_deref().unwrap_or("");
+                let score = [
+                    fuzzy_score(query, &meta.name).map(|s| s * 2),
+                    fuzzy_score(query, &meta.id).map(|s| s * 2),
+                    fuzzy_score(query, description),
+                ]
+                .into_iter()
+                .flatten()
+                .max()?;
+                Some((score, meta))
+            })
+            .collect();
+        // Stable, so equal scores keep catalog order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matches.into_iter().map(|(_, meta)| meta).collect()
+    }
+}
+
+/// Scores how well `query` matches `text`: `None` unless every non-space character of the
+/// query appears in `text` in order, ignoring case.
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_match: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[next])) {
+            continue;
+        }
+        score += 1;
+        if previous_match.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        let word_start = i == 0
+            || !text[i - 1].is_alphanumeric()
+            || (text[i - 1].is_lowercase() && c.is_uppercase());
+        if word_start {
+            score += 3;
+        }
+        previous_match = Some(i);
+        next += 1;
+    }
+    (next == query.len()).then_some(score)
 }
 
 impl DefinitionRegistry {
@@ -0,0 +1,460 @@
+//! Runtime engine settings.
+//!
+//! [`EngineSettings`] is layered from its defaults, a settings file named by
+//! `FERROFLUX_SETTINGS_FILE` (YAML or JSON), the row stored in [`PersistentStore`] and finally
+//! the environment. The [`RuntimeSettings`] resource publishes the current value over a
+//! `watch` channel: systems read [`RuntimeSettings::current`] when they need it, background
+//! tasks [`RuntimeSettings::subscribe`] to hear about changes, and
+//! `ApiCommand::UpdateSettings` patches it without a restart.
+
+use crate::components::shadow::MockConfig;
+use crate::store::database::PersistentStore;
+use bevy_ecs::prelude::Resource;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::Arc;
+use tokio::sync::watch;
+use url::Url;
+
+/// Names the settings file read at startup.
+pub const SETTINGS_FILE_ENV: &str = "FERROFLUX_SETTINGS_FILE";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EngineSettings {
+    pub network: NetworkSettings,
+    pub shadow: ShadowSettings,
+    pub capture: CaptureSettings,
+    pub concurrency: ConcurrencySettings,
+    pub analytics: AnalyticsSettings,
+    pub connections: ConnectionHealthSettings,
+    pub http: HttpClientSettings,
+    pub transport: TransportSettings,
+    pub files: FileSettings,
+    pub execution: ExecutionSettings,
+}
+
+/// Outbound request policy shared by the HTTP node and the connectors.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Turns SSRF protection off entirely. Local development only.
+    pub allow_internal_ips: bool,
+    /// Internal destinations that stay reachable with protection on: exact hostnames,
+    /// `*.suffix` wildcards, IP addresses or CIDR ranges.
+    pub ssrf_allowlist: Vec<String>,
+}
+
+impl NetworkSettings {
+    fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.ssrf_allowlist.iter().any(|entry| {
+            let entry = entry.trim().to_ascii_lowercase();
+            match entry.strip_prefix("*.") {
+                Some(suffix) => host.ends_with(&format!(".{}", suffix)),
+                None => host == entry,
+            }
+        })
+    }
+
+    fn allows_ip(&self, ip: IpAddr) -> bool {
+        self.ssrf_allowlist.iter().any(|entry| {
+            let entry = entry.trim();
+            entry.parse::<IpNet>().is_ok_and(|net| net.contains(&ip))
+                || entry.parse::<IpAddr>().is_ok_and(|allowed| allowed == ip)
+        })
+    }
+
+    /// Returns the first of `host`'s resolved addresses that the policy forbids.
+    pub fn blocked_address(
+        &self,
+        host: &str,
+        addrs: impl IntoIterator<Item = IpAddr>,
+    ) -> Option<IpAddr> {
+        if self.allow_internal_ips || self.allows_host(host) {
+            return None;
+        }
+        addrs
+            .into_iter()
+            .find(|ip| ferroflux_security::network::is_blocked_ip(*ip) && !self.allows_ip(*ip))
+    }
+
+    /// Resolves the URL's host and rejects it if it lands on a forbidden address.
+    pub fn validate_url(&self, url: &str) -> Result<(), String> {
+        if self.allow_internal_ips {
+            return Ok(());
+        }
+        let url = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        let host = url.host_str().ok_or("URL missing host")?;
+        let port = url.port_or_known_default().ok_or("URL missing port")?;
+        let addrs = (host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve host '{}': {}", host, e))?;
+        match self.blocked_address(host, addrs.map(|addr| addr.ip())) {
+            Some(ip) => Err(format!(
+                "Host '{}' resolves to blocked IP address '{}'",
+                host, ip
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShadowSettings {
+    /// Mocks added to every shadow run; a request's own mock for the same tool wins.
+    pub default_mocks: HashMap<String, MockConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureSettings {
+    /// Whether an installed `HttpRecorder` records exchanges.
+    pub http_exchanges: bool,
+    /// Whether known secret values are masked in recorded HTTP request bodies. Headers and
+    /// query parameters with sensitive names are redacted regardless.
+    pub redact_request_bodies: bool,
+    /// Whether outputs of source and side-effecting nodes are stored in `PersistentStore`
+    /// for `ApiCommand::ReplayRun`.
+    pub io_results: bool,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            http_exchanges: true,
+            redact_request_bodies: true,
+            io_results: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcurrencySettings {
+    /// Permits in the `AgentConcurrency` semaphore.
+    pub max_agent_requests: usize,
+}
+
+impl Default for ConcurrencySettings {
+    fn default() -> Self {
+        Self {
+            max_agent_requests: 50,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsSettings {
+    /// When false, tracked events are dropped instead of reaching the backend.
+    pub enabled: bool,
+    /// Buffered events that trigger an early flush.
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+}
+
+impl Default for AnalyticsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            batch_size: 1000,
+            flush_interval_ms: 2000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionHealthSettings {
+    /// Authentication failures in a row after which a connection is marked `error`; 0 never.
+    pub auth_failure_threshold: u32,
+    /// Recent calls per connection that its error rate is computed over.
+    pub error_window: usize,
+    /// Holds tickets in workflows using a failing connection until it is re-verified.
+    pub pause_dependent_workflows: bool,
+}
+
+impl Default for ConnectionHealthSettings {
+    fn default() -> Self {
+        Self {
+            auth_failure_threshold: 3,
+            error_window: 50,
+            pause_dependent_workflows: false,
+        }
+    }
+}
+
+/// Per-host limits applied by the HTTP node through `HttpClientPolicy`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpClientSettings {
+    /// Failed calls in a row after which a host's circuit opens; 0 never.
+    pub failure_threshold: u32,
+    /// How long an open circuit fails calls before letting a probe through.
+    pub open_ms: u64,
+    /// Requests in flight per host; 0 for no cap.
+    pub max_concurrent_per_host: usize,
+}
+
+impl Default for HttpClientSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_ms: 30_000,
+            max_concurrent_per_host: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransportSettings {
+    /// Where tickets for full `spill` inboxes are written; a temp directory when unset.
+    pub spill_dir: Option<String>,
+}
+
+impl TransportSettings {
+    pub fn spill_dir(&self) -> std::path::PathBuf {
+        self.spill_dir
+            .as_ref()
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("ferroflux-spill"))
+    }
+}
+
+/// Directory overrides and limits for the File node's tenant sandboxes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileSettings {
+    /// Tenant directories used instead of `<sandbox root>/<tenant>`.
+    pub tenant_roots: HashMap<String, String>,
+    /// Bytes a tenant directory may hold in total; 0 for no limit.
+    pub tenant_quota_bytes: u64,
+    /// Per-tenant replacements for `tenant_quota_bytes`.
+    pub quota_overrides: HashMap<String, u64>,
+    /// Largest file a write or append may leave behind; 0 for no limit.
+    pub max_file_bytes: u64,
+    /// Lets SQL nodes open SQLite database files inside the tenant's directory.
+    pub allow_sqlite: bool,
+}
+
+impl FileSettings {
+    pub fn quota_for(&self, tenant: &str) -> Option<u64> {
+        let quota = self
+            .quota_overrides
+            .get(tenant)
+            .copied()
+            .unwrap_or(self.tenant_quota_bytes);
+        (quota > 0).then_some(quota)
+    }
+}
+
+/// How the schedule runs its workers. Read once when the engine is built.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionSettings {
+    pub mode: ExecutionMode,
+    /// Threads of the compute pool in sharded mode; 0 uses every available core.
+    pub threads: usize,
+    pub shard_by: ShardKey,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// One system at a time on the engine thread.
+    #[default]
+    Sequential,
+    /// Bevy's parallel executor. Workers built on `run_sharded` also fan their per-node work
+    /// out by shard.
+    Sharded,
+}
+
+/// What nodes are grouped by when sharding. Nodes of one shard are always processed in order
+/// on a single thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardKey {
+    #[default]
+    Workflow,
+    Tenant,
+}
+
+impl EngineSettings {
+    /// Defaults with environment overrides applied; used where no [`RuntimeSettings`]
+    /// resource is installed.
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        settings.apply_env();
+        settings
+    }
+
+    /// Loads every layer: defaults, settings file, stored row, environment.
+    pub async fn load(store: Option<&PersistentStore>) -> anyhow::Result<Self> {
+        let mut layered = serde_json::to_value(Self::default())?;
+        if let Ok(path) = std::env::var(SETTINGS_FILE_ENV)
+            && !path.is_empty()
+        {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read settings file {}: {}", path, e))?;
+            merge(&mut layered, serde_yaml::from_str(&text)?);
+        }
+        if let Some(store) = store
+            && let Some(stored) = store.load_engine_settings().await?
+        {
+            merge(&mut layered, serde_json::from_str(&stored)?);
+        }
+        let mut settings: Self = serde_json::from_value(layered)?;
+        settings.apply_env();
+        Ok(settings)
+    }
+
+    /// Returns a copy with `patch` merged in; keys missing from the patch are kept.
+    pub fn patched(&self, patch: Value) -> anyhow::Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        merge(&mut value, patch);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// The older `FERROFLUX_*` variables keep working and take precedence.
+    fn apply_env(&mut self) {
+        if std::env::var("FERROFLUX_ALLOW_INTERNAL_IPS").unwrap_or_default() == "true" {
+            self.network.allow_internal_ips = true;
+        }
+        if let Ok(list) = std::env::var("FERROFLUX_SSRF_ALLOWLIST") {
+            self.network.ssrf_allowlist.extend(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|entry| !entry.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        if let Some(limit) = std::env::var("FERROFLUX_MAX_AGENT_REQUESTS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            self.concurrency.max_agent_requests = limit;
+        }
+    }
+}
+
+/// Merges `patch` into `base`: objects key by key, anything else replaces.
+pub fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// The live [`EngineSettings`], shared with anything that wants change notifications.
+#[derive(Resource, Clone)]
+pub struct RuntimeSettings {
+    tx: Arc<watch::Sender<Arc<EngineSettings>>>,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self::new(EngineSettings::default())
+    }
+}
+
+impl RuntimeSettings {
+    pub fn new(settings: EngineSettings) -> Self {
+        Self {
+            tx: Arc::new(watch::channel(Arc::new(settings)).0),
+        }
+    }
+
+    pub fn current(&self) -> Arc<EngineSettings> {
+        self.tx.borrow().clone()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<Arc<EngineSettings>> {
+        self.tx.subscribe()
+    }
+
+    /// Publishes `settings` to every subscriber.
+    pub fn replace(&self, settings: EngineSettings) {
+        self.tx.send_replace(Arc::new(settings));
+    }
+
+    /// The resource's current value, or [`EngineSettings::from_env`] when it is absent.
+    pub fn effective(settings: Option<&Self>) -> Arc<EngineSettings> {
+        settings
+            .map(Self::current)
+            .unwrap_or_else(|| Arc::new(EngineSettings::from_env()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_patch_keeps_unrelated_sections() {
+        let settings = EngineSettings::default()
+            .patched(json!({ "concurrency": { "max_agent_requests": 4 } }))
+            .unwrap();
+        let settings = settings
+            .patched(json!({ "network": { "ssrf_allowlist": ["10.1.0.0/16"] } }))
+            .unwrap();
+        assert_eq!(settings.concurrency.max_agent_requests, 4);
+        assert_eq!(settings.network.ssrf_allowlist, vec!["10.1.0.0/16"]);
+        assert!(settings.analytics.enabled);
+        assert!(
+            EngineSettings::default()
+                .patched(json!({ "concurrency": { "max_agent_requests": "many" } }))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ssrf_allowlist() {
+        let network = NetworkSettings {
+            allow_internal_ips: false,
+            ssrf_allowlist: vec!["*.corp.internal".into(), "10.1.0.0/16".into()],
+        };
+        let private: IpAddr = "10.2.0.5".parse().unwrap();
+        let allowed: IpAddr = "10.1.3.4".parse().unwrap();
+        let public: IpAddr = "93.184.216.34".parse().unwrap();
+
+        assert_eq!(
+            network.blocked_address("db.local", [private]),
+            Some(private)
+        );
+        assert_eq!(network.blocked_address("db.local", [allowed, public]), None);
+        assert_eq!(
+            network.blocked_address("api.corp.internal", [private]),
+            None
+        );
+        assert_eq!(
+            network.blocked_address("corp.internal", [private]),
+            Some(private)
+        );
+        assert!(network.validate_url("http://127.0.0.1:8080/x").is_err());
+    }
+
+    #[test]
+    fn test_subscribers_see_updates() {
+        let settings = RuntimeSettings::default();
+        let mut rx = settings.subscribe();
+        let patched = settings
+            .current()
+            .patched(json!({ "analytics": { "enabled": false } }))
+            .unwrap();
+        settings.replace(patched);
+        assert!(rx.has_changed().unwrap());
+        assert!(!rx.borrow_and_update().analytics.enabled);
+        assert!(!settings.current().analytics.enabled);
+    }
+}
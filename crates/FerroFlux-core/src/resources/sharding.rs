@@ -0,0 +1,111 @@
+//! Sharded execution.
+//!
+//! With [`ExecutionMode::Sharded`](super::settings::ExecutionMode) the schedule runs on Bevy's
+//! parallel executor, so workers that touch different components overlap. Systems then run on
+//! compute-pool threads, which have no Tokio context: spawn through
+//! [`TokioRuntime`](super::TokioRuntime) rather than `tokio::spawn`.
+//!
+//! Within a worker, per-node work can go through [`run_sharded`]: nodes are partitioned by
+//! workflow or tenant and each shard is processed on its own compute-pool thread, in order.
+//! Only the Transform and Contract workers do; every other worker processes its nodes one after
+//! another. Script, Switch and Router evaluate Rhai on the engine's `NonSend` `Engine`, I/O
+//! workers only hand requests to the Tokio runtime, and the transport writes into other nodes'
+//! inboxes, so none of them has per-node work that could run on another thread.
+//!
+//! The pool only has more than one thread when the crate is built with the `parallel` feature,
+//! which `tests/sharded_execution_test.rs` requires.
+
+use super::settings::{ExecutionSettings, ShardKey};
+use crate::components::NodeConfig;
+use bevy_ecs::prelude::*;
+use bevy_tasks::{ComputeTaskPool, TaskPool, TaskPoolBuilder};
+
+/// Installed by the `AppBuilder` in sharded mode; its absence means sequential execution.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Sharding {
+    pub shards: usize,
+    pub key: ShardKey,
+}
+
+impl Sharding {
+    /// One shard per pool thread. Sets up the compute pool, so call it before the schedule
+    /// first runs.
+    pub fn new(settings: &ExecutionSettings) -> Self {
+        let threads = match settings.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let pool = ComputeTaskPool::get_or_init(|| {
+            TaskPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name("ferroflux-shard".to_string())
+                .build()
+        });
+        Self {
+            shards: pool.thread_num().max(1),
+            key: settings.shard_by,
+        }
+    }
+
+    /// The shard a node belongs to. Nodes without the key (no workflow or tenant) are spread
+    /// by their own id.
+    pub fn shard_of(&self, node: &NodeConfig) -> usize {
+        let id = node.id.to_string();
+        let key = match self.key {
+            ShardKey::Workflow => node.workflow_id.as_deref(),
+            ShardKey::Tenant => node.tenant_id.as_ref().map(|tenant| tenant.as_ref()),
+        }
+        .unwrap_or(&id);
+        (fnv1a(key.as_bytes()) % self.shards as u64) as usize
+    }
+}
+
+/// Runs `work` over `jobs` and returns the results in job order.
+///
+/// Under `Sharding` the jobs are grouped by their node's shard and the groups run in parallel;
+/// jobs of one shard keep their relative order. Without it everything runs inline.
+pub fn run_sharded<J, R>(
+    sharding: Option<&Sharding>,
+    jobs: Vec<(&NodeConfig, J)>,
+    work: impl Fn(J) -> R + Sync,
+) -> Vec<R>
+where
+    J: Send,
+    R: Send + 'static,
+{
+    let sharding = match sharding {
+        Some(sharding) if sharding.shards > 1 && jobs.len() > 1 => sharding,
+        _ => return jobs.into_iter().map(|(_, job)| work(job)).collect(),
+    };
+
+    let mut groups: Vec<Vec<(usize, J)>> = (0..sharding.shards).map(|_| Vec::new()).collect();
+    let total = jobs.len();
+    for (index, (node, job)) in jobs.into_iter().enumerate() {
+        groups[sharding.shard_of(node)].push((index, job));
+    }
+
+    let work = &work;
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let mut results: Vec<(usize, R)> = Vec::with_capacity(total);
+    for group in pool.scope(|scope| {
+        for group in groups.into_iter().filter(|group| !group.is_empty()) {
+            scope.spawn(async move {
+                group
+                    .into_iter()
+                    .map(|(index, job)| (index, work(job)))
+                    .collect::<Vec<_>>()
+            });
+        }
+    }) {
+        results.extend(group);
+    }
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Stable across runs and platforms, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
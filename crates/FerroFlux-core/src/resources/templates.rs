@@ -1,5 +1,18 @@
 use bevy_ecs::prelude::*;
 use handlebars::{Context, Handlebars, HelperResult, Output, RenderContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Template language of a user-authored string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateSyntax {
+    /// `{{variable}}` interpolation with the helpers registered below.
+    #[default]
+    Handlebars,
+    /// ICU MessageFormat with locale-aware plurals, selects, numbers and dates.
+    Icu,
+}
 
 #[derive(Resource)]
 pub struct TemplateEngine {
@@ -108,4 +121,33 @@ impl TemplateEngine {
     ) -> Result<String, handlebars::RenderError> {
         self.hbs.render_template(template, data)
     }
+
+    /// Renders an ICU MessageFormat template for `locale` (e.g. `"de-CH"`).
+    pub fn render_icu(
+        &self,
+        template: &str,
+        data: &serde_json::Value,
+        locale: &str,
+    ) -> anyhow::Result<String> {
+        super::message_format::format(template, data, locale)
+    }
+
+    /// Renders `template` in the given syntax. `locale` only affects ICU templates and
+    /// defaults to `message_format::DEFAULT_LOCALE`.
+    pub fn render_with(
+        &self,
+        syntax: TemplateSyntax,
+        template: &str,
+        data: &serde_json::Value,
+        locale: Option<&str>,
+    ) -> anyhow::Result<String> {
+        match syntax {
+            TemplateSyntax::Handlebars => Ok(self.render(template, data)?),
+            TemplateSyntax::Icu => self.render_icu(
+                template,
+                data,
+                locale.unwrap_or(super::message_format::DEFAULT_LOCALE),
+            ),
+        }
+    }
 }
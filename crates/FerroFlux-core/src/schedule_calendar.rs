@@ -0,0 +1,115 @@
+//! Upcoming runs of time-triggered workflows.
+//!
+//! [`upcoming_runs`] projects every active Cron trigger of a tenant over a date range, so a UI
+//! can draw a schedule calendar. [`overlapping_runs`] groups runs of different workflows that
+//! fire close together, which is where heavy jobs end up competing for the engine.
+
+use crate::components::{CronConfig, Frequency, NodeConfig};
+use crate::systems::scheduler::NextRun;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use ferroflux_iam::TenantId;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Runs listed per trigger at most, so a per-minute trigger over a long range stays bounded.
+pub const MAX_RUNS_PER_TRIGGER: usize = 10_000;
+
+/// One future firing of a trigger.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledRun {
+    pub workflow_id: Option<String>,
+    /// The trigger node.
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub frequency: Frequency,
+    pub fire_at: DateTime<Utc>,
+}
+
+/// Runs of the tenant's Cron triggers in `[from, to)`, ordered by fire time.
+///
+/// Triggers the scheduler has already picked up are projected from their pending run, others
+/// from their configured `start_at`.
+pub fn upcoming_runs(
+    world: &mut World,
+    tenant: &TenantId,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<ScheduledRun> {
+    let mut runs = Vec::new();
+    let mut query = world.query::<(&CronConfig, &NodeConfig, Option<&NextRun>)>();
+    for (config, node_config, next_run) in query.iter(world) {
+        let node_tenant = node_config
+            .tenant_id
+            .clone()
+            .unwrap_or_else(|| TenantId::from("default_tenant"));
+        if &node_tenant != tenant {
+            continue;
+        }
+
+        let base = next_run.map(|n| n.0).unwrap_or(config.start_at);
+        for fire_at in fire_times(base, config.frequency.interval(), from, to) {
+            runs.push(ScheduledRun {
+                workflow_id: node_config.workflow_id.clone(),
+                node_id: node_config.id,
+                node_name: node_config.name.clone(),
+                frequency: config.frequency.clone(),
+                fire_at,
+            });
+        }
+    }
+    runs.sort_by(|a, b| a.fire_at.cmp(&b.fire_at).then(a.node_id.cmp(&b.node_id)));
+    runs
+}
+
+/// Times from `base` stepping by `interval` that fall in `[from, to)`.
+fn fire_times(
+    base: DateTime<Utc>,
+    interval: Option<Duration>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    let Some(step) = interval.filter(|step| *step > Duration::zero()) else {
+        return if base >= from && base < to {
+            vec![base]
+        } else {
+            Vec::new()
+        };
+    };
+
+    // Jump straight to the first occurrence at or after `from`.
+    let mut next = base;
+    if next < from {
+        let behind = (from - base).num_milliseconds();
+        let step_ms = step.num_milliseconds();
+        next = base + Duration::milliseconds((behind + step_ms - 1) / step_ms * step_ms);
+    }
+
+    let mut times = Vec::new();
+    while next < to && times.len() < MAX_RUNS_PER_TRIGGER {
+        times.push(next);
+        next += step;
+    }
+    times
+}
+
+/// Groups of runs from at least two workflows that fire within `window` of the group's first run.
+///
+/// `runs` must be ordered by fire time, as returned by [`upcoming_runs`].
+pub fn overlapping_runs(runs: &[ScheduledRun], window: Duration) -> Vec<Vec<ScheduledRun>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < runs.len() {
+        let end = runs[start..]
+            .iter()
+            .position(|run| run.fire_at - runs[start].fire_at > window)
+            .map_or(runs.len(), |offset| start + offset);
+        let group = &runs[start..end];
+        let first_workflow = &group[0].workflow_id;
+        if group.iter().any(|run| &run.workflow_id != first_workflow) {
+            groups.push(group.to_vec());
+        }
+        start = end;
+    }
+    groups
+}
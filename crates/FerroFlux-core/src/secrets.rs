@@ -1,8 +1,10 @@
-use crate::store::database::PersistentStore;
+use crate::resources::redaction::SecretRedactor;
+use crate::store::database::{EncryptedSecret, PersistentStore, WrappedKey};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use bevy_ecs::system::Resource;
 use ferroflux_iam::TenantId;
+use ferroflux_security::encryption::{decrypt, encrypt, generate_key};
 use serde_json::Value;
 use std::env;
 
@@ -34,20 +36,177 @@ impl SecretStore for EnvSecretStore {
     }
 }
 
+/// Per-tenant data keys for connection credentials.
+///
+/// ## Security
+/// - Each tenant's connections are encrypted with its own AES-256 key, created on first use.
+/// - Data keys are stored in `tenant_keys` wrapped (encrypted) by the master key, so a leaked
+///   data key only exposes one tenant.
+/// - [`TenantKeyring::rotate_tenant_key`] replaces a tenant's key and re-encrypts its
+///   connections in one transaction; the old key is deleted.
+/// - Rows encrypted directly with the master key (no `key_version`) stay readable and move to
+///   the tenant key on its next rotation.
+#[derive(Clone)]
+pub struct TenantKeyring {
+    store: PersistentStore,
+    master_key: Vec<u8>,
+}
+
+impl TenantKeyring {
+    pub fn new(store: PersistentStore, master_key: Vec<u8>) -> Self {
+        Self { store, master_key }
+    }
+
+    /// Encrypts `plaintext` with the tenant's current data key.
+    pub async fn seal(&self, tenant: &TenantId, plaintext: &[u8]) -> Result<EncryptedSecret> {
+        let (version, key) = self.current_key(tenant).await?;
+        let (ciphertext, nonce) = encrypt(plaintext, &key)?;
+        Ok(EncryptedSecret {
+            ciphertext,
+            nonce,
+            key_version: Some(version),
+        })
+    }
+
+    pub async fn open(&self, tenant: &TenantId, secret: &EncryptedSecret) -> Result<Vec<u8>> {
+        let key = match secret.key_version {
+            Some(version) => self.key(tenant, version).await?,
+            None => self.master_key.clone(),
+        };
+        decrypt(&secret.ciphertext, &key, &secret.nonce)
+    }
+
+    /// Encrypts `credentials` with the tenant's key and saves the connection.
+    pub async fn save_connection(
+        &self,
+        tenant: &TenantId,
+        slug: &str,
+        name: &str,
+        provider_type: &str,
+        credentials: &Value,
+        status: &str,
+    ) -> Result<()> {
+        let plaintext = serde_json::to_vec(credentials)?;
+        let secret = self.seal(tenant, &plaintext).await?;
+        let saved = self
+            .store
+            .save_sealed_connection(tenant, slug, name, provider_type, &secret, status)
+            .await;
+        if saved.is_err() {
+            // The key may have been rotated between sealing and saving.
+            let secret = self.seal(tenant, &plaintext).await?;
+            return self
+                .store
+                .save_sealed_connection(tenant, slug, name, provider_type, &secret, status)
+                .await;
+        }
+        saved
+    }
+
+    /// Replaces the tenant's data key and re-encrypts all its connections with the new one.
+    /// Returns the new key version.
+    #[tracing::instrument(skip(self))]
+    pub async fn rotate_tenant_key(&self, tenant: &TenantId) -> Result<u32> {
+        let current = self.store.get_tenant_key(tenant, None).await?;
+        let key = generate_key();
+        let (wrapped_key, nonce) = encrypt(&key, &self.master_key)?;
+        let wrapped = WrappedKey {
+            version: current.map_or(1, |current| current.version + 1),
+            wrapped_key,
+            nonce,
+        };
+
+        let mut connections = Vec::new();
+        for (slug, secret) in self.store.list_connection_secrets(tenant).await? {
+            let plaintext = self
+                .open(tenant, &secret)
+                .await
+                .with_context(|| format!("Failed to decrypt connection '{}'", slug))?;
+            let (ciphertext, nonce) = encrypt(&plaintext, &key)?;
+            let rotated = EncryptedSecret {
+                ciphertext,
+                nonce,
+                key_version: Some(wrapped.version),
+            };
+            connections.push((slug, secret.key_version, rotated));
+        }
+
+        self.store
+            .replace_tenant_key(tenant, &wrapped, &connections)
+            .await?;
+        tracing::info!(%tenant, version = wrapped.version, connections = connections.len(), "Tenant key rotated");
+        Ok(wrapped.version)
+    }
+
+    /// The tenant's current key, created on first use.
+    async fn current_key(&self, tenant: &TenantId) -> Result<(u32, Vec<u8>)> {
+        if let Some(wrapped) = self.store.get_tenant_key(tenant, None).await? {
+            return Ok((wrapped.version, self.unwrap_key(&wrapped)?));
+        }
+        let key = generate_key();
+        let (wrapped_key, nonce) = encrypt(&key, &self.master_key)?;
+        let first = WrappedKey {
+            version: 1,
+            wrapped_key,
+            nonce,
+        };
+        if self.store.insert_first_tenant_key(tenant, &first).await? {
+            return Ok((1, key));
+        }
+        // Another caller created it first.
+        let wrapped = self
+            .store
+            .get_tenant_key(tenant, None)
+            .await?
+            .context("Tenant key disappeared")?;
+        Ok((wrapped.version, self.unwrap_key(&wrapped)?))
+    }
+
+    async fn key(&self, tenant: &TenantId, version: u32) -> Result<Vec<u8>> {
+        let wrapped = self
+            .store
+            .get_tenant_key(tenant, Some(version))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Tenant key {} not found", version))?;
+        self.unwrap_key(&wrapped)
+    }
+
+    fn unwrap_key(&self, wrapped: &WrappedKey) -> Result<Vec<u8>> {
+        decrypt(&wrapped.wrapped_key, &self.master_key, &wrapped.nonce)
+            .context("Failed to unwrap tenant key")
+    }
+}
+
 /// Implementation that reads encrypted connections from the database.
 ///
 /// ## Security
 /// - Retrieves encrypted blobs from the `connections` table.
-/// - Decrypts them using the `master_key` and the stored `nonce`.
+/// - Decrypts them with the tenant's data key from the [`TenantKeyring`] and the stored
+///   `nonce`.
 #[derive(Clone, Resource)]
 pub struct DatabaseSecretStore {
     store: PersistentStore,
-    master_key: Vec<u8>,
+    keyring: TenantKeyring,
+    redactor: SecretRedactor,
 }
 
 impl DatabaseSecretStore {
     pub fn new(store: PersistentStore, master_key: Vec<u8>) -> Self {
-        Self { store, master_key }
+        let keyring = TenantKeyring::new(store.clone(), master_key);
+        Self {
+            store,
+            keyring,
+            redactor: SecretRedactor::default(),
+        }
+    }
+
+    pub fn keyring(&self) -> &TenantKeyring {
+        &self.keyring
+    }
+
+    /// Knows every secret this store has handed out.
+    pub fn redactor(&self) -> &SecretRedactor {
+        &self.redactor
     }
 }
 
@@ -55,23 +214,30 @@ impl DatabaseSecretStore {
 impl SecretStore for DatabaseSecretStore {
     async fn get_secret(&self, _tenant: &TenantId, key: &str) -> Result<String> {
         // Fallback to env for single values for now.
-        env::var(key)
-            .map_err(|_| anyhow::anyhow!("Secret '{}' not found in environment (DB fallback)", key))
+        let secret = env::var(key).map_err(|_| {
+            anyhow::anyhow!("Secret '{}' not found in environment (DB fallback)", key)
+        })?;
+        self.redactor.register(&secret);
+        Ok(secret)
     }
 
     async fn resolve_connection(&self, tenant: &TenantId, slug: &str) -> Result<Value> {
         // Fully async execution (no block_on needed)
-        let (_pt, enc_data, nonce, _, _) =
-            self.store
-                .get_connection_by_slug(tenant, slug)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", slug))?;
+        let (_pt, secret) = self
+            .store
+            .get_connection_secret(tenant, slug)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection '{}' not found", slug))?;
 
-        let decrypted = ferroflux_security::encryption::decrypt(&enc_data, &self.master_key, &nonce)
+        let decrypted = self
+            .keyring
+            .open(tenant, &secret)
+            .await
             .context("Decryption failed")?;
 
         let json: Value =
             serde_json::from_slice(&decrypted).context("Invalid JSON in connection data")?;
+        self.redactor.register_credentials(&json);
 
         Ok(json)
     }
@@ -32,6 +32,9 @@ impl AnalyticsBackend for NoopStore {
     ) -> anyhow::Result<Vec<AnalyticsEvent>> {
         Ok(vec![])
     }
+    async fn get_edge_traffic(&self, _tenant_id: &str) -> anyhow::Result<Vec<EdgeTrafficMetric>> {
+        Ok(vec![])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +58,18 @@ pub struct PerformanceMetric {
     pub error_rate: f64,
 }
 
+/// Messages and bytes that crossed one edge (`EdgeTraversal` events).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeTrafficMetric {
+    pub source_id: String,
+    pub source_handle: Option<String>,
+    pub target_id: String,
+    pub messages: i64,
+    pub total_bytes: i64,
+    pub avg_bytes: f64,
+    pub max_bytes: i64,
+}
+
 #[async_trait]
 pub trait AnalyticsBackend: Send + Sync {
     /// Ingests a batch of events.
@@ -81,4 +96,7 @@ pub trait AnalyticsBackend: Send + Sync {
         tenant_id: &str,
         trace_id: &str,
     ) -> anyhow::Result<Vec<AnalyticsEvent>>;
+
+    /// Retrieves message counts and payload volumes per edge.
+    async fn get_edge_traffic(&self, tenant_id: &str) -> anyhow::Result<Vec<EdgeTrafficMetric>>;
 }
@@ -1,31 +1,43 @@
+use crate::resources::redaction::SecretRedactor;
+use crate::resources::settings::{AnalyticsSettings, EngineSettings, RuntimeSettings};
 use crate::store::analytics::{AnalyticsBackend, AnalyticsEvent};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::time::{self, Duration};
 use tracing::error;
 
 pub struct AnalyticsBatcher {
     tx: mpsc::UnboundedSender<AnalyticsEvent>,
     backend: Arc<dyn AnalyticsBackend>,
+    redactor: SecretRedactor,
 }
 
 impl AnalyticsBatcher {
     pub fn new(backend: Arc<dyn AnalyticsBackend>) -> Self {
+        Self::with_settings(backend, RuntimeSettings::default().subscribe())
+    }
+
+    /// Follows the `analytics` section of the engine settings as it changes.
+    pub fn with_settings(
+        backend: Arc<dyn AnalyticsBackend>,
+        mut settings: watch::Receiver<Arc<EngineSettings>>,
+    ) -> Self {
         let (tx, mut rx) = mpsc::unbounded_channel::<AnalyticsEvent>();
         let backend_clone = backend.clone();
 
         tokio::spawn(async move {
-            let mut buffer = Vec::with_capacity(1000);
-            let mut interval = time::interval(Duration::from_secs(2));
-
-            // Interval setup: first tick is immediate, so we consume it.
-            interval.tick().await;
+            let mut config = settings.borrow_and_update().analytics.clone();
+            let mut buffer = Vec::with_capacity(config.batch_size);
+            let mut interval = flush_interval(&config).await;
 
             loop {
                 tokio::select! {
                     Some(event) = rx.recv() => {
+                        if !config.enabled {
+                            continue;
+                        }
                         buffer.push(event);
-                        if buffer.len() >= 1000 {
+                        if buffer.len() >= config.batch_size {
                             let batch = std::mem::take(&mut buffer);
                             if let Err(e) = backend_clone.ingest_batch(batch).await {
                                 error!("Failed to flush analytics batch (size limit): {}", e);
@@ -40,20 +52,45 @@ impl AnalyticsBatcher {
                             }
                         }
                     }
+                    Ok(()) = settings.changed() => {
+                        let next = settings.borrow_and_update().analytics.clone();
+                        if next.flush_interval_ms != config.flush_interval_ms {
+                            interval = flush_interval(&next).await;
+                        }
+                        config = next;
+                    }
                 }
             }
         });
 
-        Self { tx, backend }
+        Self {
+            tx,
+            backend,
+            redactor: SecretRedactor::default(),
+        }
+    }
+
+    /// Masks the secrets known to `redactor` in the payload of every tracked event.
+    pub fn with_redactor(mut self, redactor: SecretRedactor) -> Self {
+        self.redactor = redactor;
+        self
     }
 
     pub fn backend(&self) -> &Arc<dyn AnalyticsBackend> {
         &self.backend
     }
 
-    pub fn track(&self, event: AnalyticsEvent) {
+    pub fn track(&self, mut event: AnalyticsEvent) {
+        self.redactor.redact_value(&mut event.payload);
         if let Err(e) = self.tx.send(event) {
             error!("Failed to send analytics event to batcher: {}", e);
         }
     }
 }
+
+async fn flush_interval(config: &AnalyticsSettings) -> time::Interval {
+    let mut interval = time::interval(Duration::from_millis(config.flush_interval_ms.max(1)));
+    // The first tick is immediate, so we consume it.
+    interval.tick().await;
+    interval
+}
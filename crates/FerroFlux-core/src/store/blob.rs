@@ -1,6 +1,7 @@
 use bevy_ecs::prelude::Resource;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -30,6 +31,10 @@ pub trait BlobProvider: Send + Sync + std::fmt::Debug {
     fn delete(&self, id: &Uuid) -> anyhow::Result<bool>;
     fn update_metadata(&self, id: &Uuid, metadata: HashMap<String, String>) -> anyhow::Result<()>;
     fn list_expired(&self, ttl: std::time::Duration) -> Vec<Uuid>;
+    /// Payload length in bytes. Providers that can answer without reading the payload should.
+    fn size(&self, id: &Uuid) -> anyhow::Result<Option<usize>> {
+        Ok(self.retrieve(id)?.map(|(data, _)| data.len()))
+    }
 }
 
 /// In-memory implementation of BlobProvider.
@@ -86,24 +91,38 @@ impl BlobProvider for MemoryProvider {
             .map(|(id, _)| *id)
             .collect()
     }
+
+    fn size(&self, id: &Uuid) -> anyhow::Result<Option<usize>> {
+        let guard = self.storage.read().unwrap();
+        Ok(guard.get(id).map(|e| e.data.len()))
+    }
 }
 
 #[derive(Clone, Debug, Resource)]
 pub struct BlobStore {
     provider: Arc<dyn BlobProvider>,
+    /// Whether injected claim faults are honoured. Shared by every clone of the store.
+    fault_injection: Arc<AtomicBool>,
 }
 
 impl Default for BlobStore {
     fn default() -> Self {
-        Self {
-            provider: Arc::new(MemoryProvider::default()),
-        }
+        Self::new(Arc::new(MemoryProvider::default()))
     }
 }
 
 impl BlobStore {
     pub fn new(provider: Arc<dyn BlobProvider>) -> Self {
-        Self { provider }
+        Self {
+            provider,
+            fault_injection: Arc::default(),
+        }
+    }
+
+    /// Turns injected claim faults on or off. The transport worker keeps this in step with
+    /// the presence of a `FaultInjector`, so without one the fault marker is ignored.
+    pub fn set_fault_injection(&self, enabled: bool) {
+        self.fault_injection.store(enabled, Ordering::Relaxed);
     }
 
     pub fn check_in(&self, data: &[u8]) -> anyhow::Result<SecureTicket> {
@@ -126,12 +145,29 @@ impl BlobStore {
     }
 
     pub fn claim(&self, ticket: &SecureTicket) -> anyhow::Result<Vec<u8>> {
+        if self.fault_injection.load(Ordering::Relaxed)
+            && ticket
+                .metadata
+                .contains_key(crate::resources::chaos::CHAOS_CLAIM_FAULT_KEY)
+        {
+            return Err(anyhow::anyhow!("Injected fault: blob claim failed"));
+        }
         match self.provider.retrieve(&ticket.id)? {
             Some((data, _)) => Ok(data),
             None => Err(anyhow::anyhow!("Ticket not found")),
         }
     }
 
+    /// Size of the ticket's payload, if it is still stored.
+    pub fn size(&self, ticket: &SecureTicket) -> Option<usize> {
+        self.provider.size(&ticket.id).ok().flatten()
+    }
+
+    /// Drops the ticket's payload. Other tickets sharing its id lose it too.
+    pub fn release(&self, ticket: &SecureTicket) -> anyhow::Result<bool> {
+        self.provider.delete(&ticket.id)
+    }
+
     pub fn recover_ticket(&self, id: &Uuid) -> Option<SecureTicket> {
         self.provider
             .retrieve(id)
@@ -45,6 +45,16 @@ impl PersistentStore {
                 updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 FOREIGN KEY(tenant_id) REFERENCES tenants(id)
             );
+            CREATE TABLE IF NOT EXISTS workflow_versions (
+                tenant_id TEXT NOT NULL,
+                workflow_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                blueprint_json TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (tenant_id, workflow_id, version)
+            );
             CREATE TABLE IF NOT EXISTS checkpoints (
                 token TEXT PRIMARY KEY,
                 node_id TEXT,
@@ -64,8 +74,90 @@ impl PersistentStore {
                 status TEXT DEFAULT 'unverified',
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
                 updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                key_version INTEGER,
                 UNIQUE(tenant_id, slug)
             );
+            CREATE TABLE IF NOT EXISTS tenant_keys (
+                tenant_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                wrapped_key BLOB NOT NULL,
+                nonce BLOB NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (tenant_id, version)
+            );
+            CREATE TABLE IF NOT EXISTS node_usage (
+                tenant_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                node_type TEXT NOT NULL,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                favorite INTEGER NOT NULL DEFAULT 0,
+                last_used_at DATETIME,
+                PRIMARY KEY (tenant_id, user_id, node_type)
+            );
+            CREATE TABLE IF NOT EXISTS canvas_snippets (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                snippet_json TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS shared_counters (
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                value INTEGER NOT NULL DEFAULT 0,
+                window_seconds INTEGER,
+                window_started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (tenant_id, name)
+            );
+            CREATE TABLE IF NOT EXISTS io_recordings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tenant_id TEXT NOT NULL,
+                trace_id TEXT NOT NULL,
+                node_id TEXT NOT NULL,
+                port TEXT,
+                output BLOB NOT NULL,
+                metadata TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_io_recordings_trace ON io_recordings (tenant_id, trace_id);
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                tenant_id TEXT NOT NULL,
+                workflow_id TEXT NOT NULL DEFAULT '',
+                model TEXT NOT NULL,
+                day TEXT NOT NULL,
+                requests INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (tenant_id, workflow_id, model, day)
+            );
+            CREATE TABLE IF NOT EXISTS prompt_templates (
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                template TEXT NOT NULL,
+                variables TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (tenant_id, name, version)
+            );
+            CREATE TABLE IF NOT EXISTS prompt_template_versions (
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                last_version INTEGER NOT NULL,
+                PRIMARY KEY (tenant_id, name)
+            );
+            CREATE TABLE IF NOT EXISTS tenant_limits (
+                tenant_id TEXT PRIMARY KEY,
+                max_workflows INTEGER, -- NULL means unlimited, in every column
+                max_runs_per_day INTEGER,
+                max_agent_tokens_per_month INTEGER,
+                max_payload_mb INTEGER,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS engine_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                settings_json TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
             "#,
         )
         .execute(&pool)
@@ -100,10 +192,22 @@ impl PersistentStore {
         )
         .execute(&pool)
         .await;
+        let _ = sqlx::query("ALTER TABLE connections ADD COLUMN key_version INTEGER")
+            .execute(&pool)
+            .await;
+
+        crate::store::vector::SqliteVectorStore::migrate(&pool).await?;
 
         Ok(Self { pool })
     }
 
+    /// The default vector store, sharing this database.
+    pub fn vector_store(&self) -> crate::store::vector::SqliteVectorStore {
+        crate::store::vector::SqliteVectorStore::from_pool(self.pool.clone())
+    }
+
+    /// Saves the workflow and snapshots it in `workflow_versions`. Returns the new version
+    /// number.
     pub async fn save_workflow(
         &self,
         tenant: &TenantId,
@@ -112,7 +216,8 @@ impl PersistentStore {
         description: Option<&str>,
         json: &str,
         status: &str,
-    ) -> Result<()> {
+    ) -> Result<u32> {
+        let mut tx = self.pool.begin().await?;
         sqlx::query(
             r#"
             INSERT INTO workflows (id, name, description, blueprint_json, tenant_id, status)
@@ -132,9 +237,106 @@ impl PersistentStore {
         .bind(json)
         .bind(tenant.as_ref())
         .bind(status)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
-        Ok(())
+        let version: i64 = sqlx::query(
+            r#"
+            INSERT INTO workflow_versions (tenant_id, workflow_id, version, name, description, blueprint_json)
+            SELECT ?, ?, COALESCE(MAX(version), 0) + 1, ?, ?, ?
+            FROM workflow_versions WHERE tenant_id = ? AND workflow_id = ?
+            RETURNING version
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(id)
+        .bind(name)
+        .bind(description)
+        .bind(json)
+        .bind(tenant.as_ref())
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("version");
+        tx.commit().await?;
+        Ok(version as u32)
+    }
+
+    /// Snapshots of a workflow, newest first. They outlive `delete_workflow`, so a deleted
+    /// workflow can be restored with `rollback_to_version`.
+    pub async fn list_versions(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+    ) -> Result<Vec<WorkflowVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT workflow_id, version, name, description, blueprint_json, created_at
+            FROM workflow_versions
+            WHERE tenant_id = ? AND workflow_id = ?
+            ORDER BY version DESC
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(WorkflowVersion::from_row).collect())
+    }
+
+    pub async fn get_version(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        version: u32,
+    ) -> Result<Option<WorkflowVersion>> {
+        let row = sqlx::query(
+            r#"
+            SELECT workflow_id, version, name, description, blueprint_json, created_at
+            FROM workflow_versions
+            WHERE tenant_id = ? AND workflow_id = ? AND version = ?
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(workflow_id)
+        .bind(version as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(WorkflowVersion::from_row))
+    }
+
+    /// Restores a snapshot as the current workflow. The restore is saved as a new version,
+    /// so it can itself be rolled back; the workflow keeps its status. Returns the new
+    /// snapshot.
+    pub async fn rollback_to_version(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        version: u32,
+    ) -> Result<WorkflowVersion> {
+        let snapshot = self
+            .get_version(tenant, workflow_id, version)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("Workflow '{}' has no version {}", workflow_id, version)
+            })?;
+        let status = self
+            .get_workflow(tenant, workflow_id)
+            .await?
+            .map(|(.., status)| status)
+            .unwrap_or_else(|| "active".to_string());
+        let restored = self
+            .save_workflow(
+                tenant,
+                workflow_id,
+                &snapshot.name,
+                snapshot.description.as_deref(),
+                &snapshot.blueprint_json,
+                &status,
+            )
+            .await?;
+        self.get_version(tenant, workflow_id, restored)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Workflow version {} vanished", restored))
     }
 
     pub async fn load_active_workflows(
@@ -285,7 +487,10 @@ impl PersistentStore {
         }
     }
 
-    /// Save a connection with encrypted credentials.
+    /// Save a connection with credentials encrypted directly by the master key.
+    ///
+    /// Prefer [`Self::save_sealed_connection`] with a secret from `TenantKeyring::seal`, which
+    /// encrypts with the tenant's own key.
     #[allow(clippy::too_many_arguments)]
     pub async fn save_connection(
         &self,
@@ -296,17 +501,41 @@ impl PersistentStore {
         encrypted_data: &[u8],
         nonce: &[u8],
         status: &str,
+    ) -> Result<()> {
+        let secret = EncryptedSecret {
+            ciphertext: encrypted_data.to_vec(),
+            nonce: nonce.to_vec(),
+            key_version: None,
+        };
+        self.save_sealed_connection(tenant, slug, name, provider_type, &secret, status)
+            .await
+    }
+
+    /// Save a connection whose credentials were encrypted with the tenant's data key. Fails
+    /// if that key was rotated away in the meantime.
+    pub async fn save_sealed_connection(
+        &self,
+        tenant: &TenantId,
+        slug: &str,
+        name: &str,
+        provider_type: &str,
+        secret: &EncryptedSecret,
+        status: &str,
     ) -> Result<()> {
         let id = uuid::Uuid::new_v4().to_string();
-        sqlx::query(
+        let version = secret.key_version.map(i64::from);
+        let saved = sqlx::query(
             r#"
-            INSERT INTO connections (id, tenant_id, slug, name, provider_type, encrypted_data, nonce, status)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO connections (id, tenant_id, slug, name, provider_type, encrypted_data, nonce, key_version, status)
+            SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?
+            WHERE ? IS NULL
+                OR EXISTS (SELECT 1 FROM tenant_keys WHERE tenant_id = ? AND version = ?)
             ON CONFLICT(tenant_id, slug) DO UPDATE SET
                 name = excluded.name,
                 provider_type = excluded.provider_type,
                 encrypted_data = excluded.encrypted_data,
                 nonce = excluded.nonce,
+                key_version = excluded.key_version,
                 status = excluded.status,
                 updated_at = CURRENT_TIMESTAMP
             "#,
@@ -316,11 +545,163 @@ impl PersistentStore {
         .bind(slug)
         .bind(name)
         .bind(provider_type)
-        .bind(encrypted_data)
-        .bind(nonce)
+        .bind(&secret.ciphertext)
+        .bind(&secret.nonce)
+        .bind(version)
         .bind(status)
+        .bind(version)
+        .bind(tenant.as_ref())
+        .bind(version)
         .execute(&self.pool)
         .await?;
+        if saved.rows_affected() == 0 {
+            anyhow::bail!(
+                "Tenant key {} no longer exists",
+                version.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+
+    /// A connection's provider type and encrypted credentials.
+    pub async fn get_connection_secret(
+        &self,
+        tenant: &TenantId,
+        slug: &str,
+    ) -> Result<Option<(String, EncryptedSecret)>> {
+        let row = sqlx::query(
+            "SELECT provider_type, encrypted_data, nonce, key_version FROM connections WHERE tenant_id = ? AND slug = ?",
+        )
+        .bind(tenant.as_ref())
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|row| (row.get("provider_type"), encrypted_secret(&row))))
+    }
+
+    /// Encrypted credentials of all the tenant's connections, by slug.
+    pub async fn list_connection_secrets(
+        &self,
+        tenant: &TenantId,
+    ) -> Result<Vec<(String, EncryptedSecret)>> {
+        let rows = sqlx::query(
+            "SELECT slug, encrypted_data, nonce, key_version FROM connections WHERE tenant_id = ? ORDER BY slug",
+        )
+        .bind(tenant.as_ref())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("slug"), encrypted_secret(row)))
+            .collect())
+    }
+
+    /// The tenant's data key at `version`, or its current one, wrapped by the master key.
+    pub async fn get_tenant_key(
+        &self,
+        tenant: &TenantId,
+        version: Option<u32>,
+    ) -> Result<Option<WrappedKey>> {
+        let row = sqlx::query(
+            r#"
+            SELECT version, wrapped_key, nonce FROM tenant_keys
+            WHERE tenant_id = ? AND (? IS NULL OR version = ?)
+            ORDER BY version DESC LIMIT 1
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(version.map(i64::from))
+        .bind(version.map(i64::from))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|row| WrappedKey {
+            version: row.get::<i64, _>("version") as u32,
+            wrapped_key: row.get("wrapped_key"),
+            nonce: row.get("nonce"),
+        }))
+    }
+
+    /// Stores the tenant's first data key. Returns `false`, storing nothing, when the tenant
+    /// already has one.
+    pub async fn insert_first_tenant_key(
+        &self,
+        tenant: &TenantId,
+        key: &WrappedKey,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO tenant_keys (tenant_id, version, wrapped_key, nonce)
+            SELECT ?, ?, ?, ?
+            WHERE NOT EXISTS (SELECT 1 FROM tenant_keys WHERE tenant_id = ?)
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(i64::from(key.version))
+        .bind(&key.wrapped_key)
+        .bind(&key.nonce)
+        .bind(tenant.as_ref())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Atomically makes `key` the tenant's data key: stores the re-encrypted connections,
+    /// checks that none is left on another key and deletes the tenant's older keys.
+    ///
+    /// Each entry is `(slug, key version it was read with, re-encrypted secret)`. Fails, and
+    /// changes nothing, if any connection was saved or added since it was read.
+    pub async fn replace_tenant_key(
+        &self,
+        tenant: &TenantId,
+        key: &WrappedKey,
+        connections: &[(String, Option<u32>, EncryptedSecret)],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO tenant_keys (tenant_id, version, wrapped_key, nonce) VALUES (?, ?, ?, ?)",
+        )
+        .bind(tenant.as_ref())
+        .bind(i64::from(key.version))
+        .bind(&key.wrapped_key)
+        .bind(&key.nonce)
+        .execute(&mut *tx)
+        .await?;
+        for (slug, read_version, secret) in connections {
+            let updated = sqlx::query(
+                r#"
+                UPDATE connections SET encrypted_data = ?, nonce = ?, key_version = ?
+                WHERE tenant_id = ? AND slug = ? AND key_version IS ?
+                "#,
+            )
+            .bind(&secret.ciphertext)
+            .bind(&secret.nonce)
+            .bind(i64::from(key.version))
+            .bind(tenant.as_ref())
+            .bind(slug)
+            .bind(read_version.map(i64::from))
+            .execute(&mut *tx)
+            .await?;
+            if updated.rows_affected() != 1 {
+                anyhow::bail!("Connection '{}' changed during key rotation", slug);
+            }
+        }
+        let stale: i64 = sqlx::query(
+            "SELECT COUNT(*) AS stale FROM connections WHERE tenant_id = ? AND key_version IS NOT ?",
+        )
+        .bind(tenant.as_ref())
+        .bind(i64::from(key.version))
+        .fetch_one(&mut *tx)
+        .await?
+        .get("stale");
+        if stale > 0 {
+            anyhow::bail!("Connections were added during key rotation");
+        }
+        sqlx::query("DELETE FROM tenant_keys WHERE tenant_id = ? AND version < ?")
+            .bind(tenant.as_ref())
+            .bind(i64::from(key.version))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
         Ok(())
     }
 
@@ -419,4 +800,628 @@ impl PersistentStore {
             .await?;
         Ok(())
     }
+
+    /// Records that a user placed a node of the given type (palette "Recently used").
+    pub async fn record_node_usage(
+        &self,
+        tenant: &TenantId,
+        user_id: &str,
+        node_type: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO node_usage (tenant_id, user_id, node_type, use_count, last_used_at)
+            VALUES (?, ?, ?, 1, CURRENT_TIMESTAMP)
+            ON CONFLICT(tenant_id, user_id, node_type) DO UPDATE SET
+                use_count = use_count + 1,
+                last_used_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(user_id)
+        .bind(node_type)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Pins or unpins a node type in the user's palette favorites.
+    pub async fn set_node_favorite(
+        &self,
+        tenant: &TenantId,
+        user_id: &str,
+        node_type: &str,
+        favorite: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO node_usage (tenant_id, user_id, node_type, favorite)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(tenant_id, user_id, node_type) DO UPDATE SET
+                favorite = excluded.favorite
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(user_id)
+        .bind(node_type)
+        .bind(favorite)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// List a user's node usage, most recently used first.
+    /// Returns: Vec<(node_type, use_count, last_used_at, favorite)>
+    pub async fn list_node_usage(
+        &self,
+        tenant: &TenantId,
+        user_id: &str,
+    ) -> Result<Vec<(String, i64, Option<String>, bool)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT node_type, use_count, last_used_at, favorite
+            FROM node_usage
+            WHERE tenant_id = ? AND user_id = ?
+            ORDER BY last_used_at DESC, use_count DESC
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut usage = Vec::new();
+        for row in rows {
+            usage.push((
+                row.get("node_type"),
+                row.get("use_count"),
+                row.try_get("last_used_at").ok().flatten(),
+                row.get("favorite"),
+            ));
+        }
+        Ok(usage)
+    }
+
+    /// Save a canvas snippet (a serialized partial selection). Returns its ID.
+    pub async fn save_snippet(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        snippet_json: &str,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO canvas_snippets (id, tenant_id, name, snippet_json) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(tenant.as_ref())
+        .bind(name)
+        .bind(snippet_json)
+        .execute(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// List a tenant's snippets, newest first.
+    /// Returns: Vec<(id, name, created_at)>
+    pub async fn list_snippets(&self, tenant: &TenantId) -> Result<Vec<(String, String, String)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, created_at
+            FROM canvas_snippets
+            WHERE tenant_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut snippets = Vec::new();
+        for row in rows {
+            let created_at: String = row.try_get("created_at").unwrap_or_default();
+            snippets.push((row.get("id"), row.get("name"), created_at));
+        }
+        Ok(snippets)
+    }
+
+    pub async fn get_snippet(&self, tenant: &TenantId, id: &str) -> Result<Option<String>> {
+        let row =
+            sqlx::query("SELECT snippet_json FROM canvas_snippets WHERE tenant_id = ? AND id = ?")
+                .bind(tenant.as_ref())
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(|r| r.get("snippet_json")))
+    }
+
+    pub async fn delete_snippet(&self, tenant: &TenantId, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM canvas_snippets WHERE tenant_id = ? AND id = ?")
+            .bind(tenant.as_ref())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Atomically adds `amount` to a tenant's counter and returns the new value.
+    ///
+    /// With `window_seconds`, the counter restarts from zero once the current window (which
+    /// opens at the first increment) has elapsed.
+    pub async fn increment_counter(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        amount: i64,
+        window_seconds: Option<u64>,
+    ) -> Result<i64> {
+        // SQLite evaluates every SET expression against the pre-update row.
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO shared_counters (tenant_id, name, value, window_seconds, window_started_at)
+            VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT(tenant_id, name) DO UPDATE SET
+                value = CASE WHEN {expired} THEN excluded.value ELSE value + excluded.value END,
+                window_started_at = CASE WHEN {expired} THEN CURRENT_TIMESTAMP ELSE window_started_at END,
+                window_seconds = excluded.window_seconds
+            RETURNING value
+            "#,
+            expired = COUNTER_WINDOW_EXPIRED
+        ))
+        .bind(tenant.as_ref())
+        .bind(name)
+        .bind(amount)
+        .bind(window_seconds.map(|w| w as i64))
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("value"))
+    }
+
+    /// Current value of a tenant's counter (0 if missing or its window has elapsed).
+    pub async fn get_counter(&self, tenant: &TenantId, name: &str) -> Result<i64> {
+        let row = sqlx::query(&format!(
+            "SELECT CASE WHEN {} THEN 0 ELSE value END AS value FROM shared_counters WHERE tenant_id = ? AND name = ?",
+            COUNTER_WINDOW_EXPIRED
+        ))
+        .bind(tenant.as_ref())
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|r| r.get("value")).unwrap_or(0))
+    }
+
+    pub async fn reset_counter(&self, tenant: &TenantId, name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM shared_counters WHERE tenant_id = ? AND name = ?")
+            .bind(tenant.as_ref())
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Appends one recorded node output of a run.
+    pub async fn record_io(
+        &self,
+        tenant: &TenantId,
+        trace_id: &str,
+        recording: &IoRecording,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO io_recordings (tenant_id, trace_id, node_id, port, output, metadata)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(trace_id)
+        .bind(recording.node_id.to_string())
+        .bind(recording.port.as_deref())
+        .bind(&recording.output)
+        .bind(serde_json::to_string(&recording.metadata)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Everything recorded for a run, in the order it was produced.
+    pub async fn load_io_recordings(
+        &self,
+        tenant: &TenantId,
+        trace_id: &str,
+    ) -> Result<Vec<IoRecording>> {
+        let rows = sqlx::query(
+            "SELECT node_id, port, output, metadata FROM io_recordings WHERE tenant_id = ? AND trace_id = ? ORDER BY id",
+        )
+        .bind(tenant.as_ref())
+        .bind(trace_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let node_id: String = row.get("node_id");
+                let metadata: String = row.get("metadata");
+                Ok(IoRecording {
+                    node_id: uuid::Uuid::parse_str(&node_id)?,
+                    port: row.get("port"),
+                    output: row.get("output"),
+                    metadata: serde_json::from_str(&metadata)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Adds one LLM call to today's (UTC) usage of the tenant, workflow and model.
+    pub async fn record_usage(
+        &self,
+        tenant: &TenantId,
+        workflow_id: Option<&str>,
+        model: &str,
+        usage: &TokenUsage,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO llm_usage (tenant_id, workflow_id, model, day, requests, prompt_tokens, completion_tokens)
+            VALUES (?, ?, ?, ?, 1, ?, ?)
+            ON CONFLICT(tenant_id, workflow_id, model, day) DO UPDATE SET
+                requests = requests + 1,
+                prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                completion_tokens = completion_tokens + excluded.completion_tokens
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(workflow_id.unwrap_or_default())
+        .bind(model)
+        .bind(chrono::Utc::now().date_naive().to_string())
+        .bind(usage.prompt_tokens as i64)
+        .bind(usage.completion_tokens as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Daily usage per workflow and model for the days in `range`, oldest first.
+    pub async fn get_usage(
+        &self,
+        tenant: &TenantId,
+        range: std::ops::RangeInclusive<chrono::NaiveDate>,
+    ) -> Result<Vec<UsageRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day, workflow_id, model, requests, prompt_tokens, completion_tokens
+            FROM llm_usage
+            WHERE tenant_id = ? AND day >= ? AND day <= ?
+            ORDER BY day, workflow_id, model
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(range.start().to_string())
+        .bind(range.end().to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let day: String = row.get("day");
+                let workflow_id: String = row.get("workflow_id");
+                Ok(UsageRecord {
+                    day: day.parse()?,
+                    workflow_id: (!workflow_id.is_empty()).then_some(workflow_id),
+                    model: row.get("model"),
+                    requests: row.get("requests"),
+                    prompt_tokens: row.get("prompt_tokens"),
+                    completion_tokens: row.get("completion_tokens"),
+                })
+            })
+            .collect()
+    }
+
+    /// Stores a new version of a tenant's prompt template and returns it. Versions start at 1
+    /// and are never reused, even after a delete.
+    pub async fn save_prompt_template(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        template: &str,
+        variables: &[String],
+    ) -> Result<PromptTemplate> {
+        let mut tx = self.pool.begin().await?;
+        let version: i64 = sqlx::query(
+            r#"
+            INSERT INTO prompt_template_versions (tenant_id, name, last_version)
+            VALUES (?, ?, 1)
+            ON CONFLICT(tenant_id, name) DO UPDATE SET last_version = last_version + 1
+            RETURNING last_version
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(name)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("last_version");
+        let row = sqlx::query(
+            r#"
+            INSERT INTO prompt_templates (tenant_id, name, version, template, variables)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING version, created_at
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(name)
+        .bind(version)
+        .bind(template)
+        .bind(serde_json::to_string(variables)?)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(PromptTemplate {
+            name: name.to_string(),
+            version: row.get::<i64, _>("version") as u32,
+            template: template.to_string(),
+            variables: variables.to_vec(),
+            created_at: row.try_get("created_at").unwrap_or_default(),
+        })
+    }
+
+    /// Every prompt template version, by tenant. Used to fill the `PromptLibrary` at startup.
+    pub async fn load_prompt_templates(&self) -> Result<Vec<(TenantId, PromptTemplate)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT tenant_id, name, version, template, variables, created_at
+            FROM prompt_templates
+            ORDER BY tenant_id, name, version
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let variables: String = row.get("variables");
+                Ok((
+                    TenantId(row.get("tenant_id")),
+                    PromptTemplate {
+                        name: row.get("name"),
+                        version: row.get::<i64, _>("version") as u32,
+                        template: row.get("template"),
+                        variables: serde_json::from_str(&variables)?,
+                        created_at: row.try_get("created_at").unwrap_or_default(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Deletes one version of a prompt template, or all of them when `version` is `None`.
+    pub async fn delete_prompt_template(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        version: Option<u32>,
+    ) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM prompt_templates WHERE tenant_id = ? AND name = ? AND (? IS NULL OR version = ?)",
+        )
+        .bind(tenant.as_ref())
+        .bind(name)
+        .bind(version.map(i64::from))
+        .bind(version.map(i64::from))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Engine-wide `EngineSettings` overrides. Unlike every other table this one is not
+    /// tenant-scoped: it holds a single row.
+    /// Total LLM tokens per tenant since `since` (inclusive).
+    pub async fn token_totals_since(
+        &self,
+        since: chrono::NaiveDate,
+    ) -> Result<Vec<(TenantId, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT tenant_id, SUM(prompt_tokens + completion_tokens) AS tokens
+            FROM llm_usage
+            WHERE day >= ?
+            GROUP BY tenant_id
+            "#,
+        )
+        .bind(since.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (TenantId(row.get("tenant_id")), row.get("tokens")))
+            .collect())
+    }
+
+    pub async fn save_tenant_limits(&self, tenant: &TenantId, limits: &TenantLimits) -> Result<()> {
+        let column = |limit: Option<u64>| limit.map(|limit| limit as i64);
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_limits (tenant_id, max_workflows, max_runs_per_day, max_agent_tokens_per_month, max_payload_mb, updated_at)
+            VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT(tenant_id) DO UPDATE SET
+                max_workflows = excluded.max_workflows,
+                max_runs_per_day = excluded.max_runs_per_day,
+                max_agent_tokens_per_month = excluded.max_agent_tokens_per_month,
+                max_payload_mb = excluded.max_payload_mb,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(tenant.as_ref())
+        .bind(column(limits.max_workflows))
+        .bind(column(limits.max_runs_per_day))
+        .bind(column(limits.max_agent_tokens_per_month))
+        .bind(column(limits.max_payload_mb))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn load_tenant_limits(&self) -> Result<Vec<(TenantId, TenantLimits)>> {
+        let rows = sqlx::query(
+            "SELECT tenant_id, max_workflows, max_runs_per_day, max_agent_tokens_per_month, max_payload_mb FROM tenant_limits",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let column = |row: &sqlx::sqlite::SqliteRow, name: &str| {
+            row.get::<Option<i64>, _>(name).map(|limit| limit as u64)
+        };
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    TenantId(row.get("tenant_id")),
+                    TenantLimits {
+                        max_workflows: column(row, "max_workflows"),
+                        max_runs_per_day: column(row, "max_runs_per_day"),
+                        max_agent_tokens_per_month: column(row, "max_agent_tokens_per_month"),
+                        max_payload_mb: column(row, "max_payload_mb"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    pub async fn load_engine_settings(&self) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT settings_json FROM engine_settings WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|r| r.get("settings_json")))
+    }
+
+    pub async fn save_engine_settings(&self, json: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO engine_settings (id, settings_json, updated_at)
+            VALUES (1, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT(id) DO UPDATE SET
+                settings_json = excluded.settings_json,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(json)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// A node output captured while `capture.io_results` is on, replayed by `ApiCommand::ReplayRun`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IoRecording {
+    pub node_id: uuid::Uuid,
+    /// The output port the result left on.
+    pub port: Option<String>,
+    pub output: Vec<u8>,
+    pub metadata: std::collections::HashMap<String, String>,
 }
+
+/// Token counts reported by an LLM provider for one call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// One day of a tenant's LLM usage for a workflow and model.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UsageRecord {
+    pub day: chrono::NaiveDate,
+    /// `None` for agents outside a workflow.
+    pub workflow_id: Option<String>,
+    pub model: String,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+impl UsageRecord {
+    pub fn total_tokens(&self) -> i64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Usage caps of one tenant, enforced through `TenantQuotas`. `None` means unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TenantLimits {
+    /// Distinct workflows loaded at once.
+    pub max_workflows: Option<u64>,
+    /// Runs started per UTC day, by triggers and source nodes.
+    pub max_runs_per_day: Option<u64>,
+    /// LLM tokens (prompt and completion) per calendar month.
+    pub max_agent_tokens_per_month: Option<u64>,
+    /// Largest payload a single run may start with, in megabytes. This caps each trigger's
+    /// payload, not the tenant's total blob storage.
+    pub max_payload_mb: Option<u64>,
+}
+
+/// Connection credentials as stored: AES-256-GCM ciphertext and nonce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedSecret {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+    /// Version of the tenant data key used, or `None` for rows encrypted directly with the
+    /// master key.
+    pub key_version: Option<u32>,
+}
+
+/// A tenant data key, encrypted with the master key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub version: u32,
+    pub wrapped_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+fn encrypted_secret(row: &sqlx::sqlite::SqliteRow) -> EncryptedSecret {
+    EncryptedSecret {
+        ciphertext: row.get("encrypted_data"),
+        nonce: row.get("nonce"),
+        key_version: row
+            .get::<Option<i64>, _>("key_version")
+            .map(|version| version as u32),
+    }
+}
+
+/// A snapshot of a workflow, taken each time it is saved.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WorkflowVersion {
+    pub workflow_id: String,
+    pub version: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub blueprint_json: String,
+    pub created_at: String,
+}
+
+impl WorkflowVersion {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Self {
+        Self {
+            workflow_id: row.get("workflow_id"),
+            version: row.get::<i64, _>("version") as u32,
+            name: row.get("name"),
+            description: row.get("description"),
+            blueprint_json: row.get("blueprint_json"),
+            created_at: row.try_get("created_at").unwrap_or_default(),
+        }
+    }
+}
+
+/// One version of a prompt template from the `PromptLibrary`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub version: u32,
+    /// Template text, rendered with the agent's `template_syntax`.
+    pub template: String,
+    /// Payload fields the template reads; an agent fails before calling its provider when one
+    /// is missing.
+    pub variables: Vec<String>,
+    pub created_at: String,
+}
+
+const COUNTER_WINDOW_EXPIRED: &str = "window_seconds IS NOT NULL \
+    AND strftime('%s', 'now') - strftime('%s', window_started_at) >= window_seconds";
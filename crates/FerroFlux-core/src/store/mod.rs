@@ -2,6 +2,8 @@ pub mod analytics;
 pub mod batcher;
 pub mod cache;
 pub mod database;
+pub mod spill;
+pub mod vector;
 
 pub use database::PersistentStore;
 // pub use database::SecureTicket; // Only if it was in database.rs (it's not)
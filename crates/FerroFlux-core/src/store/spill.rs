@@ -0,0 +1,46 @@
+//! On-disk overflow for bounded inboxes.
+//!
+//! A spilled ticket is written as one JSON file holding its metadata and base64 payload, and
+//! checked back into the [`BlobStore`] under a fresh id when it is restored.
+
+use super::{BlobStore, SecureTicket};
+use base64::{Engine, engine::general_purpose};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize)]
+struct SpilledTicket {
+    metadata: HashMap<String, String>,
+    data: String,
+}
+
+/// Writes `ticket` under `dir`. With `release`, its payload is dropped from the store.
+pub fn spill(
+    store: &BlobStore,
+    dir: &Path,
+    ticket: &SecureTicket,
+    release: bool,
+) -> anyhow::Result<PathBuf> {
+    let data = store.claim(ticket)?;
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", uuid::Uuid::new_v4()));
+    let spilled = SpilledTicket {
+        metadata: ticket.metadata.clone(),
+        data: general_purpose::STANDARD.encode(data),
+    };
+    std::fs::write(&path, serde_json::to_vec(&spilled)?)?;
+    if release {
+        store.release(ticket)?;
+    }
+    Ok(path)
+}
+
+/// Reads a spilled ticket back into the store and removes its file.
+pub fn restore(store: &BlobStore, path: &Path) -> anyhow::Result<SecureTicket> {
+    let spilled: SpilledTicket = serde_json::from_slice(&std::fs::read(path)?)?;
+    let data = general_purpose::STANDARD.decode(spilled.data)?;
+    let ticket = store.check_in_with_metadata(&data, spilled.metadata)?;
+    std::fs::remove_file(path)?;
+    Ok(ticket)
+}
@@ -0,0 +1,386 @@
+//! Vector storage for retrieval (RAG) workflows.
+//!
+//! Embedding nodes upsert into, and VectorSearch nodes query, a [`VectorStore`]. Every
+//! collection is scoped to a tenant. The default backend keeps vectors in the engine's SQLite
+//! database and scans a collection per query, which is fine for a few hundred thousand
+//! records; with the `qdrant` feature, [`qdrant::QdrantVectorStore`] talks to a Qdrant server
+//! instead.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use ferroflux_iam::TenantId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Row, Sqlite};
+
+/// A vector and the document it was computed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorRecord {
+    /// Caller-chosen id; upserting the same id again replaces the record.
+    pub id: String,
+    pub vector: Vec<f32>,
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+/// A search hit, best first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMatch {
+    pub id: String,
+    /// Cosine similarity, from -1 to 1.
+    pub score: f32,
+    pub metadata: Value,
+}
+
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Inserts the records, replacing any with the same id. Creates the collection if needed.
+    async fn upsert(
+        &self,
+        tenant: &TenantId,
+        collection: &str,
+        records: Vec<VectorRecord>,
+    ) -> Result<()>;
+
+    /// The `top_k` records most similar to `vector`. A missing collection has no matches.
+    async fn search(
+        &self,
+        tenant: &TenantId,
+        collection: &str,
+        vector: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<VectorMatch>>;
+
+    async fn delete(&self, tenant: &TenantId, collection: &str, ids: &[String]) -> Result<()>;
+}
+
+/// Cosine similarity; 0 when either vector is all zeros or the lengths differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Vectors in a `vector_records` table, searched by a full scan of the collection.
+#[derive(Clone, Debug)]
+pub struct SqliteVectorStore {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteVectorStore {
+    /// Uses `pool`, creating the table if it doesn't exist.
+    pub async fn new(pool: Pool<Sqlite>) -> Result<Self> {
+        Self::migrate(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    pub(crate) fn from_pool(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+
+    pub(crate) async fn migrate(pool: &Pool<Sqlite>) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS vector_records (
+                tenant_id TEXT NOT NULL,
+                collection TEXT NOT NULL,
+                id TEXT NOT NULL,
+                vector BLOB NOT NULL,
+                metadata TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (tenant_id, collection, id)
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+fn encode(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[async_trait]
+impl VectorStore for SqliteVectorStore {
+    async fn upsert(
+        &self,
+        tenant: &TenantId,
+        collection: &str,
+        records: Vec<VectorRecord>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for record in records {
+            sqlx::query(
+                r#"
+                INSERT INTO vector_records (tenant_id, collection, id, vector, metadata)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(tenant_id, collection, id) DO UPDATE SET
+                    vector = excluded.vector,
+                    metadata = excluded.metadata,
+                    updated_at = CURRENT_TIMESTAMP
+                "#,
+            )
+            .bind(tenant.as_ref())
+            .bind(collection)
+            .bind(&record.id)
+            .bind(encode(&record.vector))
+            .bind(serde_json::to_string(&record.metadata)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        tenant: &TenantId,
+        collection: &str,
+        vector: &[f32],
+        top_k: usize,
+    ) -> Result<Vec<VectorMatch>> {
+        let rows = sqlx::query(
+            "SELECT id, vector, metadata FROM vector_records WHERE tenant_id = ? AND collection = ?",
+        )
+        .bind(tenant.as_ref())
+        .bind(collection)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut matches = Vec::with_capacity(rows.len());
+        for row in rows {
+            let stored = decode(&row.try_get::<Vec<u8>, _>("vector")?);
+            if stored.len() != vector.len() {
+                continue;
+            }
+            matches.push(VectorMatch {
+                id: row.try_get("id")?,
+                score: cosine_similarity(vector, &stored),
+                metadata: serde_json::from_str(&row.try_get::<String, _>("metadata")?)?,
+            });
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(top_k);
+        Ok(matches)
+    }
+
+    async fn delete(&self, tenant: &TenantId, collection: &str, ids: &[String]) -> Result<()> {
+        for id in ids {
+            sqlx::query(
+                "DELETE FROM vector_records WHERE tenant_id = ? AND collection = ? AND id = ?",
+            )
+            .bind(tenant.as_ref())
+            .bind(collection)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "qdrant")]
+pub mod qdrant {
+    use super::{VectorMatch, VectorRecord, VectorStore};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use ferroflux_iam::TenantId;
+    use serde_json::{Value, json};
+
+    /// A Qdrant server over its REST API. Each tenant's collection maps to a Qdrant
+    /// collection named `<tenant>__<collection>__<hash>`, created with cosine distance on first
+    /// upsert. The names are sanitized for Qdrant, so the hash of the exact tenant and
+    /// collection is what keeps tenants apart.
+    /// Point ids are UUIDv5s of the record ids; the original id is kept in the payload.
+    #[derive(Clone, Debug)]
+    pub struct QdrantVectorStore {
+        client: reqwest::Client,
+        url: String,
+        api_key: Option<String>,
+    }
+
+    impl QdrantVectorStore {
+        pub fn new(url: impl Into<String>, api_key: Option<String>) -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                url: url.into().trim_end_matches('/').to_string(),
+                api_key,
+            }
+        }
+
+        /// From `QDRANT_URL` and `QDRANT_API_KEY`, if the URL is set.
+        pub fn from_env() -> Option<Self> {
+            let url = std::env::var("QDRANT_URL").ok()?;
+            Some(Self::new(url, std::env::var("QDRANT_API_KEY").ok()))
+        }
+
+        fn collection(tenant: &TenantId, collection: &str) -> String {
+            let tenant = tenant.as_ref();
+            // Length-prefixed so no tenant/collection split hashes like another.
+            let key = format!("{}:{}{}", tenant.len(), tenant, collection);
+            let hash = blake3::hash(key.as_bytes()).to_hex();
+            format!("{}__{}__{}", tenant, collection, &hash[..16])
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        }
+
+        fn point_id(id: &str) -> String {
+            uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, id.as_bytes()).to_string()
+        }
+
+        fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+            let request = self.client.request(method, format!("{}{}", self.url, path));
+            match &self.api_key {
+                Some(key) => request.header("api-key", key),
+                None => request,
+            }
+        }
+
+        async fn ensure_collection(&self, name: &str, size: usize) -> Result<()> {
+            let path = format!("/collections/{}", name);
+            let response = self.request(reqwest::Method::GET, &path).send().await?;
+            if response.status().is_success() {
+                return Ok(());
+            }
+            self.request(reqwest::Method::PUT, &path)
+                .json(&json!({ "vectors": { "size": size, "distance": "Cosine" } }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl VectorStore for QdrantVectorStore {
+        async fn upsert(
+            &self,
+            tenant: &TenantId,
+            collection: &str,
+            records: Vec<VectorRecord>,
+        ) -> Result<()> {
+            let Some(size) = records.first().map(|record| record.vector.len()) else {
+                return Ok(());
+            };
+            let name = Self::collection(tenant, collection);
+            self.ensure_collection(&name, size).await?;
+            let points: Vec<Value> = records
+                .into_iter()
+                .map(|record| {
+                    json!({
+                        "id": Self::point_id(&record.id),
+                        "vector": record.vector,
+                        "payload": { "_id": record.id, "metadata": record.metadata },
+                    })
+                })
+                .collect();
+            self.request(
+                reqwest::Method::PUT,
+                &format!("/collections/{}/points?wait=true", name),
+            )
+            .json(&json!({ "points": points }))
+            .send()
+            .await?
+            .error_for_status()?;
+            Ok(())
+        }
+
+        async fn search(
+            &self,
+            tenant: &TenantId,
+            collection: &str,
+            vector: &[f32],
+            top_k: usize,
+        ) -> Result<Vec<VectorMatch>> {
+            let name = Self::collection(tenant, collection);
+            let response = self
+                .request(
+                    reqwest::Method::POST,
+                    &format!("/collections/{}/points/search", name),
+                )
+                .json(&json!({ "vector": vector, "limit": top_k, "with_payload": true }))
+                .send()
+                .await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(Vec::new());
+            }
+            let body: Value = response.error_for_status()?.json().await?;
+            Ok(body
+                .get("result")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|hit| VectorMatch {
+                    id: hit
+                        .pointer("/payload/_id")
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| hit["id"].to_string()),
+                    score: hit["score"].as_f64().unwrap_or_default() as f32,
+                    metadata: hit
+                        .pointer("/payload/metadata")
+                        .cloned()
+                        .unwrap_or(Value::Null),
+                })
+                .collect())
+        }
+
+        async fn delete(&self, tenant: &TenantId, collection: &str, ids: &[String]) -> Result<()> {
+            let name = Self::collection(tenant, collection);
+            let points: Vec<String> = ids.iter().map(|id| Self::point_id(id)).collect();
+            let response = self
+                .request(
+                    reqwest::Method::POST,
+                    &format!("/collections/{}/points/delete?wait=true", name),
+                )
+                .json(&json!({ "points": points }))
+                .send()
+                .await?;
+            if response.status() != reqwest::StatusCode::NOT_FOUND {
+                response.error_for_status()?;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_collection_names_keep_tenants_apart() {
+            let name = |tenant: &str, collection: &str| {
+                QdrantVectorStore::collection(&TenantId::from(tenant), collection)
+            };
+            assert_ne!(name("acme", "x__y"), name("acme__x", "y"));
+            assert_ne!(name("a.b", "docs"), name("a_b", "docs"));
+            assert_eq!(name("acme", "docs"), name("acme", "docs"));
+        }
+    }
+}
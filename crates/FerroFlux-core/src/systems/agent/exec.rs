@@ -1,78 +1,276 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::NodeConfig;
 use crate::components::pipeline::{ExecutionResult, ReadyToExecute};
+use crate::integrations::registry::StreamDef;
+use crate::resources::quotas::TenantQuotas;
 use crate::resources::{GlobalHttpClient, PipelineResultChannel, WorkDone};
 use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::Value;
+use std::time::Duration;
+use uuid::Uuid;
 
-#[tracing::instrument(skip(commands, query, http_client, runtime, channel, work_done))]
+/// Sends prepared agent requests. When the provider streams, every delta is published as a
+/// `SystemEvent::AgentToken` as it arrives and the assembled text becomes the result body.
+/// A failed request moves on to the next of the agent's fallback providers, if any.
+///
+/// Once the node's tenant has used up its monthly agent tokens, requests are not sent and
+/// fail with status 429.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    commands,
+    query,
+    http_client,
+    runtime,
+    channel,
+    work_done,
+    event_bus,
+    quotas
+))]
 pub fn agent_exec(
     mut commands: Commands,
-    query: Query<(Entity, &ReadyToExecute), Without<ExecutionResult>>,
+    query: Query<(Entity, &ReadyToExecute, Option<&NodeConfig>), Without<ExecutionResult>>,
     http_client: Res<GlobalHttpClient>,
     runtime: Res<crate::resources::TokioRuntime>,
     channel: Res<PipelineResultChannel>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
+    event_bus: Res<SystemEventBus>,
+    quotas: Option<Res<TenantQuotas>>,
 ) {
     let (tx, rx) = (&channel.tx, &channel.rx);
 
     // 1. Poll completed tasks
     while let Ok((entity, result)) = rx.try_recv() {
         commands.entity(entity).insert(result);
-        work_done.0 = true;
+        work_done.mark();
     }
 
     // 2. Spawn new tasks
-    for (entity, ready) in query.iter() {
+    for (entity, ready, node) in query.iter() {
+        if let Some(quotas) = &quotas {
+            let tenant = node
+                .and_then(|node| node.tenant_id.clone())
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            if let Err(e) = quotas.check_agent_tokens(&tenant) {
+                e.notify(
+                    Some(&event_bus),
+                    node.and_then(|node| node.workflow_id.clone()),
+                );
+                commands
+                    .entity(entity)
+                    .remove::<ReadyToExecute>()
+                    .insert(ExecutionResult {
+                        status: 429,
+                        raw_body: e.to_string(),
+                        trace_id: ready.trace_id.clone(),
+                        context: ready.context.clone(),
+                    });
+                work_done.mark();
+                continue;
+            }
+        }
+
         let client = http_client.client.clone();
         let tx_clone = tx.clone();
         let entity_id = entity;
-        let ready_clone = ready.clone();
+        let mut ready_clone = ready.clone();
+        let event_tx = event_bus.clone();
 
         commands.entity(entity).remove::<ReadyToExecute>();
-        work_done.0 = true;
+        work_done.mark();
 
         runtime.0.spawn(async move {
             let span = tracing::info_span!("agent_request", node_id = %ready_clone.context.node_id, trace_id = %ready_clone.trace_id);
             let _enter = span.enter();
 
-            let method = match ready_clone.method.as_str() {
-                "GET" => reqwest::Method::GET,
-                "POST" => reqwest::Method::POST,
-                "PUT" => reqwest::Method::PUT,
-                "DELETE" => reqwest::Method::DELETE,
-                _ => reqwest::Method::POST,
+            let mut fallbacks = std::mem::take(&mut ready_clone.fallbacks).into_iter();
+            let mut fallback_from = Vec::new();
+            let mut attempt = ready_clone;
+            let mut result = loop {
+                let result = send(&client, attempt, &event_tx).await;
+                let failed = !(200..300).contains(&result.status);
+                match fallbacks.next() {
+                    Some(next) if failed => {
+                        tracing::warn!(
+                            provider = %result.context.provider_name,
+                            model = %result.context.model_name,
+                            status = result.status,
+                            fallback = %next.context.provider_name,
+                            "Agent provider failed, falling back"
+                        );
+                        fallback_from.push(format!(
+                            "{}/{}",
+                            result.context.provider_name, result.context.model_name
+                        ));
+                        attempt = next;
+                    }
+                    _ => break result,
+                }
             };
+            result.context.fallback_from = fallback_from;
 
-            tracing::debug!(method = %method, url = %ready_clone.url, "Sending HTTP request");
+            let _ = tx_clone.send((entity_id, result)).await;
+        });
+    }
+}
 
-            let mut request_builder = client.request(method, &ready_clone.url);
+/// Sends one prepared request. Transport errors become status 500 and running past the
+/// request's `timeout_ms` becomes 504. A streamed response only has to start within the
+/// limit, since its tokens have already been published by the time it could expire.
+async fn send(
+    client: &reqwest::Client,
+    ready: ReadyToExecute,
+    event_tx: &SystemEventBus,
+) -> ExecutionResult {
+    let deadline = ready
+        .timeout_ms
+        .map(|ms| tokio::time::Instant::now() + Duration::from_millis(ms));
+    let timed_out = || {
+        tracing::error!(timeout_ms = ?ready.timeout_ms, "HTTP request timed out");
+        (
+            504,
+            format!(
+                "Request Timed Out after {} ms",
+                ready.timeout_ms.unwrap_or_default()
+            ),
+        )
+    };
 
-            for (k, v) in &ready_clone.headers {
-                request_builder = request_builder.header(k, v);
-            }
+    let method = match ready.method.as_str() {
+        "GET" => reqwest::Method::GET,
+        "POST" => reqwest::Method::POST,
+        "PUT" => reqwest::Method::PUT,
+        "DELETE" => reqwest::Method::DELETE,
+        _ => reqwest::Method::POST,
+    };
 
-            request_builder = request_builder.body(ready_clone.body.clone());
+    tracing::debug!(method = %method, url = %ready.url, "Sending HTTP request");
 
-            let response_result = request_builder.send().await;
+    let mut request_builder = client.request(method, &ready.url);
 
-            let (status, raw_body) = match response_result {
-                Ok(resp) => {
-                    let status = resp.status().as_u16();
-                    tracing::debug!(status = %status, "Received HTTP response");
-                    (status, resp.text().await.unwrap_or_default())
-                }
-                Err(e) => {
-                    tracing::error!(error = %e, "HTTP request failed");
-                    (500, format!("Request Failed: {}", e))
+    for (k, v) in &ready.headers {
+        request_builder = request_builder.header(k, v);
+    }
+
+    request_builder = request_builder.body(ready.body.clone());
+
+    let mut context = ready.context.clone();
+    let (status, raw_body) = match within(deadline, request_builder.send()).await {
+        Some(Ok(resp)) => {
+            let status = resp.status().as_u16();
+            tracing::debug!(status = %status, "Received HTTP response");
+            match &ready.stream {
+                Some(stream) if resp.status().is_success() => {
+                    let text =
+                        read_stream(resp, stream, context.node_id, &ready.trace_id, event_tx).await;
+                    // Already the completion text; there is no response JSON to map.
+                    context.output_transform = None;
+                    (status, text)
                 }
-            };
+                _ => match within(deadline, resp.text()).await {
+                    Some(text) => (status, text.unwrap_or_default()),
+                    None => timed_out(),
+                },
+            }
+        }
+        Some(Err(e)) => {
+            tracing::error!(error = %e, "HTTP request failed");
+            (500, format!("Request Failed: {}", e))
+        }
+        None => timed_out(),
+    };
 
-            let result = ExecutionResult {
-                status,
-                raw_body,
-                trace_id: ready_clone.trace_id,
-                context: ready_clone.context,
-            };
+    ExecutionResult {
+        status,
+        raw_body,
+        trace_id: ready.trace_id,
+        context,
+    }
+}
 
-            let _ = tx_clone.send((entity_id, result)).await;
-        });
+/// Runs `future` to completion, or until `deadline` if there is one.
+async fn within<F: Future>(deadline: Option<tokio::time::Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+enum StreamLine {
+    Delta(String),
+    Done,
+    Skip,
+}
+
+fn parse_stream_line(line: &str, stream: &StreamDef) -> StreamLine {
+    let line = line.trim();
+    if line.is_empty()
+        || line.starts_with(':')
+        || line.starts_with("event:")
+        || line.starts_with("id:")
+        || line.starts_with("retry:")
+    {
+        return StreamLine::Skip;
+    }
+    let data = line
+        .strip_prefix("data:")
+        .map(str::trim_start)
+        .unwrap_or(line);
+    if data == stream.done_marker {
+        return StreamLine::Done;
+    }
+    match serde_json::from_str::<Value>(data) {
+        Ok(chunk) => match chunk.pointer(&stream.delta_pointer).and_then(Value::as_str) {
+            Some(delta) if !delta.is_empty() => StreamLine::Delta(delta.to_string()),
+            _ => StreamLine::Skip,
+        },
+        Err(_) => {
+            tracing::debug!(line = %data, "Skipping unparseable stream chunk");
+            StreamLine::Skip
+        }
+    }
+}
+
+/// Reads the response body chunk by chunk until the done marker or the end of the body,
+/// returning the concatenated deltas.
+async fn read_stream(
+    mut resp: reqwest::Response,
+    stream: &StreamDef,
+    node_id: Uuid,
+    trace_id: &str,
+    event_tx: &SystemEventBus,
+) -> String {
+    let mut text = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut ended = false;
+    while !ended {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => pending.extend_from_slice(&chunk),
+            // Flush a last line that has no trailing newline.
+            Ok(None) => {
+                pending.push(b'\n');
+                ended = true;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Agent stream interrupted");
+                break;
+            }
+        }
+        while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            match parse_stream_line(&String::from_utf8_lossy(&line), stream) {
+                StreamLine::Delta(delta) => {
+                    let _ = event_tx.send(SystemEvent::AgentToken {
+                        node_id,
+                        trace_id: trace_id.to_string(),
+                        delta: delta.clone(),
+                    });
+                    text.push_str(&delta);
+                }
+                StreamLine::Done => return text,
+                StreamLine::Skip => {}
+            }
+        }
     }
+    text
 }
@@ -1,26 +1,99 @@
+use crate::api::events::{SchemaViolation, SystemEvent};
+use crate::components::agent::{OUTPUT_REPAIR_KEY, OutputRepair};
 use crate::components::pipeline::ExecutionResult;
-use crate::components::{Outbox, WorkDone};
+use crate::components::{AgentConfig, ExpectedOutput, Inbox, NodeConfig, Outbox, WorkDone};
+use crate::resources::TokioRuntime;
+use crate::resources::quotas::TenantQuotas;
 use crate::store::BlobStore;
+use crate::store::database::{PersistentStore, TokenUsage};
 use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
 use serde_json::{Value, json};
 
-#[tracing::instrument(skip(commands, query, store, work_done, event_bus, outbox_query))]
+/// Violations reported per rejected answer, and fed back to the model on repair.
+const MAX_VIOLATIONS: usize = 10;
+
+/// Turns each finished provider call into the node's output and telemetry. Token counts in
+/// the response are added to the tenant's usage when a database is attached, and to its
+/// monthly quota.
+///
+/// Agents with a JSON `output_mode` have the answer validated against its schema. A rejected
+/// answer goes back to the front of the inbox with the violations (see [`OutputRepair`])
+/// until `max_repair_attempts` is used up, then to the `error` port.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    commands,
+    query,
+    store,
+    work_done,
+    event_bus,
+    outbox_query,
+    nodes,
+    agents,
+    inboxes,
+    db,
+    runtime,
+    quotas
+))]
 pub fn agent_post(
     mut commands: Commands,
     query: Query<(Entity, &ExecutionResult)>,
     store: Res<BlobStore>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
     event_bus: Res<crate::api::events::SystemEventBus>,
     mut outbox_query: Query<&mut Outbox>,
+    nodes: Query<&NodeConfig>,
+    agents: Query<(&AgentConfig, Option<&ExpectedOutput>)>,
+    mut inboxes: Query<&mut Inbox>,
+    db: Option<Res<PersistentStore>>,
+    runtime: Option<Res<TokioRuntime>>,
+    quotas: Option<Res<TenantQuotas>>,
 ) {
     for (entity, result) in query.iter() {
-        work_done.0 = true;
+        work_done.mark();
+
+        let body = serde_json::from_str::<Value>(&result.raw_body).ok();
+        let usage = body.as_ref().and_then(token_usage);
+        let model = match result.context.model_name.as_str() {
+            "" => body
+                .as_ref()
+                .and_then(|body| body.get("model"))
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string(),
+            model => model.to_string(),
+        };
+        if let (Some(usage), Some(quotas)) = (usage, &quotas) {
+            let tenant = nodes
+                .get(entity)
+                .ok()
+                .and_then(|node| node.tenant_id.clone())
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            quotas.record_agent_tokens(&tenant, usage.prompt_tokens + usage.completion_tokens);
+        }
+        if let (Some(usage), Some(db), Some(runtime)) = (usage, &db, &runtime) {
+            let node = nodes.get(entity).ok();
+            let tenant = node
+                .and_then(|node| node.tenant_id.clone())
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let workflow_id = node.and_then(|node| node.workflow_id.clone());
+            let db = PersistentStore::clone(db);
+            let model = model.clone();
+            runtime.0.spawn(async move {
+                if let Err(e) = db
+                    .record_usage(&tenant, workflow_id.as_deref(), &model, &usage)
+                    .await
+                {
+                    tracing::warn!(error = %e, "Failed to record LLM usage");
+                }
+            });
+        }
 
         let mut success = false;
-        let final_output_str;
+        let mut final_output_str;
 
         if result.status >= 200 && result.status < 300 {
-            if let Ok(_json_data) = serde_json::from_str::<Value>(&result.raw_body) {
+            if body.is_some() {
                 if let Some(transform_text) = &result.context.output_transform {
                     match jmespath::compile(transform_text) {
                         Ok(expr) => match jmespath::Variable::from_json(&result.raw_body) {
@@ -55,6 +128,29 @@ pub fn agent_post(
             final_output_str = format!("HTTP Error {}: {}", result.status, result.raw_body);
         }
 
+        // Structured output
+        let mut violations = Vec::new();
+        let mut repair = None;
+        if success
+            && let Ok((agent, expected)) = agents.get(entity)
+            && let Some(schema) = agent.output_mode.schema(expected)
+        {
+            match validate_output(&final_output_str, &schema) {
+                Ok(json) => final_output_str = json,
+                Err(found) => {
+                    success = false;
+                    violations = found;
+                    if result.context.repair_attempt < agent.max_repair_attempts {
+                        repair = Some(OutputRepair {
+                            attempt: result.context.repair_attempt + 1,
+                            output: final_output_str.clone(),
+                            violations: violations.iter().map(|v| v.message.clone()).collect(),
+                        });
+                    }
+                }
+            }
+        }
+
         // Merge Result
         let output = crate::systems::utils::merge_result(
             &result.context.input_json,
@@ -65,26 +161,71 @@ pub fn agent_post(
         // Telemetry
         let elapsed = (chrono::Utc::now().timestamp_millis() as u64)
             .saturating_sub(result.context.start_time);
-        let _ = event_bus
-            .0
-            .send(crate::api::events::SystemEvent::NodeTelemetry {
-                trace_id: result.trace_id.clone(),
-                node_id: result.context.node_id,
-                node_type: "Agent".to_string(),
-                execution_ms: elapsed,
-                success,
-                details: json!({
-                    "provider": result.context.provider_name,
-                    "model": result.context.model_name,
-                    "status": result.status,
-                }),
-            });
+        let _ = event_bus.send(crate::api::events::SystemEvent::NodeTelemetry {
+            trace_id: result.trace_id.clone(),
+            node_id: result.context.node_id,
+            node_type: "Agent".to_string(),
+            execution_ms: elapsed,
+            success,
+            details: json!({
+                "provider": result.context.provider_name,
+                "model": model,
+                "status": result.status,
+                "usage": usage,
+                "fallback_from": result.context.fallback_from,
+                "repair_attempt": result.context.repair_attempt,
+                "violations": violations.len(),
+            }),
+        });
 
         // Store result and push to Outbox
         let mut metadata = std::collections::HashMap::new();
         metadata.insert("trace_id".to_string(), result.trace_id.clone());
 
-        if let Ok(ticket) = store.check_in_with_metadata(output.as_bytes(), metadata)
+        if let Some(repair) = repair {
+            // Ask again with the original input
+            tracing::warn!(
+                node_id = %result.context.node_id,
+                trace_id = %result.trace_id,
+                attempt = repair.attempt,
+                violations = violations.len(),
+                "Agent output failed validation, requesting a repair"
+            );
+            metadata.insert(
+                OUTPUT_REPAIR_KEY.to_string(),
+                serde_json::to_string(&repair).unwrap_or_default(),
+            );
+            if let Ok(input) = serde_json::to_vec(&result.context.input_json)
+                && let Ok(ticket) = store.check_in_with_metadata(&input, metadata)
+                && let Ok(mut inbox) = inboxes.get_mut(entity)
+            {
+                inbox.queue.push_front(ticket);
+            }
+        } else if !violations.is_empty() {
+            tracing::warn!(
+                node_id = %result.context.node_id,
+                trace_id = %result.trace_id,
+                violations = violations.len(),
+                "Agent output failed validation"
+            );
+            let rejected = json!({
+                "payload": result.context.input_json,
+                "output": final_output_str,
+                "violations": violations,
+            });
+            if let Ok(ticket) =
+                store.check_in_with_metadata(rejected.to_string().as_bytes(), metadata)
+                && let Ok(mut outbox) = outbox_query.get_mut(entity)
+            {
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+            let _ = event_bus.send(SystemEvent::ContractViolation {
+                trace_id: result.trace_id.clone(),
+                node_id: result.context.node_id,
+                violations,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            });
+        } else if let Ok(ticket) = store.check_in_with_metadata(output.as_bytes(), metadata)
             && let Ok(mut outbox) = outbox_query.get_mut(entity)
         {
             outbox.queue.push_back((None, ticket));
@@ -101,3 +242,61 @@ pub fn agent_post(
         commands.entity(entity).remove::<ExecutionResult>();
     }
 }
+
+/// Token counts from an OpenAI-, Anthropic-, Gemini- or Ollama-style response body.
+fn token_usage(body: &Value) -> Option<TokenUsage> {
+    let count = |pointer: &str| body.pointer(pointer).and_then(Value::as_u64);
+    [
+        ("/usage/prompt_tokens", "/usage/completion_tokens"),
+        ("/usage/input_tokens", "/usage/output_tokens"),
+        (
+            "/usageMetadata/promptTokenCount",
+            "/usageMetadata/candidatesTokenCount",
+        ),
+        ("/prompt_eval_count", "/eval_count"),
+    ]
+    .into_iter()
+    .find_map(|(prompt, completion)| {
+        let (prompt_tokens, completion_tokens) = (count(prompt), count(completion));
+        (prompt_tokens.is_some() || completion_tokens.is_some()).then(|| TokenUsage {
+            prompt_tokens: prompt_tokens.unwrap_or_default(),
+            completion_tokens: completion_tokens.unwrap_or_default(),
+        })
+    })
+}
+
+/// Parses a model's answer, tolerating a Markdown code fence around it, and checks it against
+/// `schema`. Returns the answer as compact JSON.
+fn validate_output(text: &str, schema: &Value) -> Result<String, Vec<SchemaViolation>> {
+    let violation = |message: String| SchemaViolation {
+        path: String::new(),
+        schema_path: String::new(),
+        message,
+    };
+    let mut text = text.trim();
+    if let Some(fenced) = text.strip_prefix("```") {
+        let fenced = fenced.strip_suffix("```").unwrap_or(fenced);
+        text = fenced
+            .split_once('\n')
+            .map_or(fenced, |(_, body)| body)
+            .trim();
+    }
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e| vec![violation(format!("Response is not valid JSON: {}", e))])?;
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| vec![violation(format!("Invalid output schema: {}", e))])?;
+    let violations: Vec<SchemaViolation> = validator
+        .iter_errors(&value)
+        .take(MAX_VIOLATIONS)
+        .map(|e| SchemaViolation {
+            path: e.instance_path.to_string(),
+            schema_path: e.schema_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+    if violations.is_empty() {
+        Ok(value.to_string())
+    } else {
+        Err(violations)
+    }
+}
@@ -1,8 +1,10 @@
+use crate::components::agent::{OUTPUT_REPAIR_KEY, OutputRepair};
 use crate::components::pipeline::{ExecutionContext, ReadyToExecute};
 use crate::components::{
-    AgentConfig, ExpectedOutput, Inbox, NodeConfig, Outbox, PinnedOutput, WorkDone,
+    AgentConfig, ExpectedOutput, Inbox, NodeConfig, Outbox, OutputMode, PinnedOutput, WorkDone,
 };
 use crate::integrations::registry::IntegrationRegistry;
+use crate::resources::prompts::{PromptLibrary, PromptRef};
 use crate::resources::templates::TemplateEngine;
 use crate::secrets::{DatabaseSecretStore, SecretStore};
 use crate::store::BlobStore;
@@ -18,6 +20,7 @@ use uuid::Uuid;
     registry,
     template_engine,
     secret_store,
+    prompt_library,
     work_done,
     event_bus
 ))]
@@ -40,7 +43,8 @@ pub fn agent_prep(
     registry: Res<IntegrationRegistry>,
     template_engine: Res<TemplateEngine>,
     secret_store: Res<DatabaseSecretStore>,
-    mut work_done: ResMut<WorkDone>,
+    prompt_library: Option<Res<PromptLibrary>>,
+    work_done: Res<WorkDone>,
     event_bus: Res<crate::api::events::SystemEventBus>,
     runtime: Res<crate::resources::TokioRuntime>,
 ) {
@@ -56,19 +60,23 @@ pub fn agent_prep(
             while let Some(_ticket) = inbox.queue.pop_front() {
                 tracing::info!(entity = ?entity, "Node is PINNED. Skipping execution.");
                 outbox.queue.push_back((None, pinned.0.clone()));
-                work_done.0 = true;
+                work_done.mark();
             }
             continue;
         }
 
         if let Some(ticket) = inbox.queue.pop_front() {
-            work_done.0 = true;
+            work_done.mark();
 
             let trace_id = ticket
                 .metadata
                 .get("trace_id")
                 .cloned()
                 .unwrap_or_else(|| Uuid::new_v4().to_string());
+            let repair = ticket
+                .metadata
+                .get(OUTPUT_REPAIR_KEY)
+                .and_then(|repair| serde_json::from_str::<OutputRepair>(repair).ok());
 
             // Retrieve Input
             let payload_bytes = match store.claim(&ticket) {
@@ -81,187 +89,336 @@ pub fn agent_prep(
 
             let input_json: Value = serde_json::from_slice(&payload_bytes).unwrap_or(json!({}));
 
-            // Lookup Integration
-            let integration_def = match registry.definitions.get(&config.provider) {
-                Some(p) => p,
-                None => {
-                    let _ = event_bus
-                        .0
-                        .send(crate::api::events::SystemEvent::NodeError {
-                            trace_id: trace_id.clone(),
-                            node_id: node_config.id,
-                            error: format!("Integration '{}' not found", config.provider),
-                            timestamp: chrono::Utc::now().timestamp(),
-                        });
-                    continue;
-                }
-            };
-
-            let action_name = "chat_completion";
-            let action_def = match integration_def.actions.get(action_name) {
-                Some(a) => a,
-                None => continue,
-            };
-
             let tenant = node_config
                 .tenant_id
                 .clone()
                 .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let prompts = resolve_prompts(config, &input_json, &tenant, prompt_library.as_deref());
+            let render = |provider: &str, model: &str, connection_slug: Option<&str>| {
+                render_request(
+                    config,
+                    prompts.as_ref().map_err(Clone::clone)?,
+                    provider,
+                    model,
+                    connection_slug,
+                    &input_json,
+                    expected_opt,
+                    repair.as_ref(),
+                    &tenant,
+                    node_config.id,
+                    &trace_id,
+                    &registry,
+                    &template_engine,
+                    &secret_store,
+                    &runtime,
+                )
+            };
 
-            // Resolve Secret (Async -> Sync block)
-            // Resolve Secret (Async -> Sync block)
-            let rt = runtime.clone();
-            let ss = secret_store.clone();
-            let t_clone = tenant.clone();
-            // We need to clone capture data that is moved into async block if convenient,
-            // but references should work with block_on if we dont use async move?
-            // However, config is reference from query.
-            // integration_def is reference from registry.
-            // Let's use async block.
-
-            let api_key = tokio::task::block_in_place(move || {
-                rt.0.block_on(async {
-                    if let Some(slug) = &config.connection_slug {
-                        match ss.resolve_connection(&t_clone, slug).await {
-                            Ok(json_val) => json_val
-                                .get("api_key")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string()),
-                            Err(_) => None,
+            let mut ready = match render(
+                &config.provider,
+                &config.model,
+                config.connection_slug.as_deref(),
+            ) {
+                Ok(ready) => ready,
+                Err(error) => {
+                    let _ = event_bus.send(crate::api::events::SystemEvent::NodeError {
+                        trace_id: trace_id.clone(),
+                        node_id: node_config.id,
+                        error,
+                        timestamp: chrono::Utc::now().timestamp(),
+                    });
+                    continue;
+                }
+            };
+
+            // Fallbacks are rendered up front, so exec can switch providers without a round
+            // trip through the ECS.
+            if let Some(chain) = &config.fallback {
+                ready.timeout_ms = chain.timeout_ms;
+                for target in &chain.targets {
+                    match render(
+                        &target.provider,
+                        &target.model,
+                        target.connection_slug.as_deref(),
+                    ) {
+                        Ok(mut fallback) => {
+                            fallback.timeout_ms = chain.timeout_ms;
+                            ready.fallbacks.push(fallback);
+                        }
+                        Err(error) => {
+                            tracing::warn!(node_id = %node_config.id, provider = %target.provider, error = %error, "Skipping agent fallback")
                         }
-                    } else {
-                        let var = integration_def
-                            .verify_params
-                            .get("api_key")
-                            .cloned()
-                            .unwrap_or("API_KEY".to_string());
-                        ss.get_secret(&t_clone, &var).await.ok()
-                    }
-                })
-            })
-            .unwrap_or_default();
-
-            // Prepare Context
-            let mut context_json = input_json.clone();
-
-            // Render user prompt
-            let user_prompt_template = input_json
-                .get("user_prompt")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&config.user_prompt_template);
-            let user_prompt = template_engine
-                .render(user_prompt_template, &input_json)
-                .unwrap_or_else(|_| user_prompt_template.to_string());
-
-            // Setup context with defaults and config overrides
-            if let Some(obj) = context_json.as_object_mut() {
-                for input in &action_def.inputs {
-                    if !obj.contains_key(&input.name)
-                        && let Some(def) = &input.default
-                    {
-                        obj.insert(input.name.clone(), def.clone());
                     }
                 }
+            }
 
-                obj.insert("model".to_string(), json!(config.model));
-
-                // Render system instruction
-                let mut system_instruction = template_engine
-                    .render(&config.system_instruction, &input_json)
-                    .unwrap_or_else(|_| config.system_instruction.clone());
-
-                // Expected Output instructions
-                if let Some(expected) = expected_opt
-                    && !expected.aggregated_schema.is_empty()
-                {
-                    let mut keys: Vec<_> = expected.aggregated_schema.iter().cloned().collect();
-                    keys.sort(); // Consistent ordering for tests
-                    let schema_instruction =
-                        format!("\nEnsure Output matches JSON schema keys: {:?}", keys);
-                    system_instruction.push_str(&schema_instruction);
-                }
+            commands.entity(entity).insert(ready);
 
-                obj.insert("system_instruction".to_string(), json!(system_instruction));
-                obj.insert("user_prompt".to_string(), json!(user_prompt));
-                obj.insert("api_key".to_string(), json!(api_key));
-                obj.insert("tools".to_string(), json!(config.tools));
-                obj.insert("tool_choice".to_string(), json!(config.tool_choice));
-            }
+            tracing::info!(node_id = %node_config.id, trace_id = %trace_id, model = %config.model, "Agent prep complete");
+        }
+    }
+}
 
-            // Generic messages array
-            let mut messages = Vec::new();
-            let system_instruction = context_json
-                .get("system_instruction")
-                .and_then(|v| v.as_str())
-                .unwrap_or(&config.system_instruction);
-            if !system_instruction.is_empty() {
-                messages.push(json!({"role": "system", "content": system_instruction}));
-            }
-            if let Some(hist) = context_json.get("history").and_then(|h| h.as_array()) {
-                for msg in hist {
-                    messages.push(msg.clone());
+/// The templates an agent renders its system and user messages from.
+struct Prompts {
+    system: String,
+    user: String,
+}
+
+/// Picks the agent's prompt templates: prompt library references first, then the payload's
+/// `user_prompt`, then the inline config. A library template fails when the payload lacks one
+/// of its variables.
+fn resolve_prompts(
+    config: &AgentConfig,
+    input_json: &Value,
+    tenant: &TenantId,
+    library: Option<&PromptLibrary>,
+) -> Result<Prompts, String> {
+    let lookup = |reference: &str| -> Result<String, String> {
+        let prompt: PromptRef = reference
+            .parse()
+            .map_err(|e: anyhow::Error| e.to_string())?;
+        let template = library
+            .and_then(|library| library.resolve(tenant, &prompt))
+            .ok_or_else(|| format!("Prompt template '{}' not found", prompt))?;
+        let missing: Vec<&str> = template
+            .variables
+            .iter()
+            .filter(|variable| {
+                input_json
+                    .pointer(&format!("/{}", variable.replace('.', "/")))
+                    .is_none()
+            })
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Prompt template '{}@{}' is missing variables: {}",
+                template.name,
+                template.version,
+                missing.join(", ")
+            ));
+        }
+        Ok(template.template.clone())
+    };
+
+    let system = match &config.system_prompt_ref {
+        Some(reference) => lookup(reference)?,
+        None => config.system_instruction.clone(),
+    };
+    let user = match &config.user_prompt_ref {
+        Some(reference) => lookup(reference)?,
+        None => input_json
+            .get("user_prompt")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&config.user_prompt_template)
+            .to_string(),
+    };
+    Ok(Prompts { system, user })
+}
+
+/// Renders the chat request for one provider and model: resolves the API key, fills the
+/// action's input defaults and renders the prompts, message history, body, path and headers.
+#[allow(clippy::too_many_arguments)]
+fn render_request(
+    config: &AgentConfig,
+    prompts: &Prompts,
+    provider: &str,
+    model: &str,
+    connection_slug: Option<&str>,
+    input_json: &Value,
+    expected_opt: Option<&ExpectedOutput>,
+    repair: Option<&OutputRepair>,
+    tenant: &TenantId,
+    node_id: Uuid,
+    trace_id: &str,
+    registry: &IntegrationRegistry,
+    template_engine: &TemplateEngine,
+    secret_store: &DatabaseSecretStore,
+    runtime: &crate::resources::TokioRuntime,
+) -> Result<ReadyToExecute, String> {
+    // Lookup Integration
+    let integration_def = registry
+        .definitions
+        .get(provider)
+        .ok_or_else(|| format!("Integration '{}' not found", provider))?;
+
+    let action_name = "chat_completion";
+    let action_def = integration_def
+        .actions
+        .get(action_name)
+        .ok_or_else(|| format!("Integration '{}' has no '{}' action", provider, action_name))?;
+
+    // Resolve Secret (Async -> Sync block)
+    let api_key = tokio::task::block_in_place(|| {
+        runtime.0.block_on(async {
+            if let Some(slug) = connection_slug {
+                match secret_store.resolve_connection(tenant, slug).await {
+                    Ok(json_val) => json_val
+                        .get("api_key")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    Err(_) => None,
                 }
+            } else {
+                let var = integration_def
+                    .verify_params
+                    .get("api_key")
+                    .cloned()
+                    .unwrap_or("API_KEY".to_string());
+                secret_store.get_secret(tenant, &var).await.ok()
             }
-            messages.push(json!({"role": "user", "content": user_prompt}));
-            if let Some(obj) = context_json.as_object_mut() {
-                obj.insert("messages".to_string(), json!(messages));
+        })
+    })
+    .unwrap_or_default();
+
+    // Prepare Context
+    let mut context_json = input_json.clone();
+
+    // Render user prompt
+    let locale = config
+        .locale
+        .as_deref()
+        .or_else(|| input_json.get("locale").and_then(|v| v.as_str()));
+    let user_prompt = template_engine
+        .render_with(config.template_syntax, &prompts.user, input_json, locale)
+        .unwrap_or_else(|_| prompts.user.clone());
+
+    // A repair re-asks the same question, with the rejected answer and what was wrong with it.
+    let repair_prompt = repair.map(|repair| {
+        format!(
+            "Your previous response did not pass validation: {}. Reply again with only the corrected JSON.",
+            repair.violations.join("; ")
+        )
+    });
+
+    // Setup context with defaults and config overrides
+    if let Some(obj) = context_json.as_object_mut() {
+        for input in &action_def.inputs {
+            if !obj.contains_key(&input.name)
+                && let Some(def) = &input.default
+            {
+                obj.insert(input.name.clone(), def.clone());
             }
+        }
 
-            // Message Transform
-            let history_string = if let Some(transform_template) = &action_def.message_transform {
-                template_engine
-                    .render(transform_template, &context_json)
-                    .unwrap_or_else(|_| json!(messages).to_string())
-            } else {
-                json!(messages).to_string()
-            };
-            if let Some(obj) = context_json.as_object_mut() {
-                obj.insert("history".to_string(), json!(history_string));
-            }
+        obj.insert("model".to_string(), json!(model));
+
+        // Render system instruction
+        let mut system_instruction = template_engine
+            .render_with(config.template_syntax, &prompts.system, input_json, locale)
+            .unwrap_or_else(|_| prompts.system.clone());
+
+        // Expected Output instructions
+        if let Some(expected) = expected_opt
+            && !expected.aggregated_schema.is_empty()
+        {
+            let mut keys: Vec<_> = expected.aggregated_schema.iter().cloned().collect();
+            keys.sort(); // Consistent ordering for tests
+            let schema_instruction =
+                format!("\nEnsure Output matches JSON schema keys: {:?}", keys);
+            system_instruction.push_str(&schema_instruction);
+        }
+        if let OutputMode::JsonSchema(schema) = &config.output_mode {
+            system_instruction.push_str(&format!(
+                "\nRespond with JSON matching this schema: {}",
+                schema
+            ));
+        }
 
-            // Render Body, Path, Headers
-            let body = if let Some(tpl) = &action_def.implementation.config.body_template {
-                template_engine
-                    .render(tpl, &context_json)
-                    .unwrap_or_else(|_| "{}".to_string())
-            } else {
-                "{}".to_string()
-            };
+        obj.insert("system_instruction".to_string(), json!(system_instruction));
+        obj.insert(
+            "user_prompt".to_string(),
+            json!(repair_prompt.as_deref().unwrap_or(&user_prompt)),
+        );
+        obj.insert("api_key".to_string(), json!(api_key));
+        obj.insert("tools".to_string(), json!(config.tools));
+        obj.insert("tool_choice".to_string(), json!(config.tool_choice));
+        obj.insert("stream".to_string(), json!(action_def.stream.is_some()));
+        if !matches!(config.output_mode, OutputMode::Text) {
+            obj.insert("json_mode".to_string(), json!(true));
+        }
+    }
 
-            let path = template_engine
-                .render(&action_def.implementation.config.path, &context_json)
-                .unwrap_or_else(|_| action_def.implementation.config.path.clone());
-            let url = format!("{}{}", integration_def.base_url, path);
+    // Generic messages array
+    let mut messages = Vec::new();
+    let system_instruction = context_json
+        .get("system_instruction")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&prompts.system);
+    if !system_instruction.is_empty() {
+        messages.push(json!({"role": "system", "content": system_instruction}));
+    }
+    if let Some(hist) = context_json.get("history").and_then(|h| h.as_array()) {
+        for msg in hist {
+            messages.push(msg.clone());
+        }
+    }
+    messages.push(json!({"role": "user", "content": user_prompt}));
+    if let (Some(repair), Some(repair_prompt)) = (repair, &repair_prompt) {
+        messages.push(json!({"role": "assistant", "content": repair.output}));
+        messages.push(json!({"role": "user", "content": repair_prompt}));
+    }
+    if let Some(obj) = context_json.as_object_mut() {
+        obj.insert("messages".to_string(), json!(messages));
+    }
 
-            let mut headers = std::collections::HashMap::new();
-            for (k, v) in &action_def.implementation.config.headers {
-                if let Ok(val) = template_engine.render(v, &context_json) {
-                    headers.insert(k.clone(), val);
-                }
-            }
+    // Message Transform
+    let history_string = if let Some(transform_template) = &action_def.message_transform {
+        template_engine
+            .render(transform_template, &context_json)
+            .unwrap_or_else(|_| json!(messages).to_string())
+    } else {
+        json!(messages).to_string()
+    };
+    if let Some(obj) = context_json.as_object_mut() {
+        obj.insert("history".to_string(), json!(history_string));
+    }
 
-            let method = action_def.implementation.config.method.clone();
-
-            commands.entity(entity).insert(ReadyToExecute {
-                method,
-                url,
-                headers,
-                body,
-                trace_id: trace_id.clone(),
-                context: ExecutionContext {
-                    provider_name: config.provider.clone(),
-                    model_name: config.model.clone(),
-                    node_id: node_config.id,
-                    result_key: config.result_key.clone(),
-                    output_transform: action_def.output_transform.as_ref().map(|t| t.text.clone()),
-                    input_json: input_json.clone(),
-                    start_time: chrono::Utc::now().timestamp_millis() as u64,
-                },
-            });
+    // Render Body, Path, Headers
+    let body = if let Some(tpl) = &action_def.implementation.config.body_template {
+        template_engine
+            .render(tpl, &context_json)
+            .unwrap_or_else(|_| "{}".to_string())
+    } else {
+        "{}".to_string()
+    };
 
-            tracing::info!(node_id = %node_config.id, trace_id = %trace_id, model = %config.model, "Agent prep complete");
+    let path = template_engine
+        .render(&action_def.implementation.config.path, &context_json)
+        .unwrap_or_else(|_| action_def.implementation.config.path.clone());
+    let url = format!("{}{}", integration_def.base_url, path);
+
+    let mut headers = std::collections::HashMap::new();
+    for (k, v) in &action_def.implementation.config.headers {
+        if let Ok(val) = template_engine.render(v, &context_json) {
+            headers.insert(k.clone(), val);
         }
     }
+
+    let method = action_def.implementation.config.method.clone();
+
+    Ok(ReadyToExecute {
+        method,
+        url,
+        headers,
+        body,
+        trace_id: trace_id.to_string(),
+        context: ExecutionContext {
+            provider_name: provider.to_string(),
+            model_name: model.to_string(),
+            node_id,
+            result_key: config.result_key.clone(),
+            output_transform: action_def.output_transform.as_ref().map(|t| t.text.clone()),
+            input_json: input_json.clone(),
+            start_time: chrono::Utc::now().timestamp_millis() as u64,
+            fallback_from: Vec::new(),
+            repair_attempt: repair.map_or(0, |repair| repair.attempt),
+        },
+        stream: action_def.stream.clone(),
+        timeout_ms: None,
+        fallbacks: Vec::new(),
+    })
 }
 
 #[cfg(test)]
@@ -298,6 +455,7 @@ mod tests {
             documentation: None,
             message_transform: None,
             output_transform: None,
+            stream: None,
             implementation: ActionImplementation {
                 impl_type: "http".to_string(),
                 config: IntegrationConfig {
@@ -305,6 +463,7 @@ mod tests {
                     path: "/chat".to_string(),
                     headers: HashMap::new(),
                     body_template: Some("{\"prompt\": \"{{{user_prompt}}}\"}".to_string()),
+                    pagination: None,
                 },
             },
         };
@@ -340,7 +499,7 @@ mod tests {
         world.insert_resource(DatabaseSecretStore::new(db, vec![0u8; 32]));
 
         let (event_tx, _) = tokio::sync::broadcast::channel(10);
-        world.insert_resource(SystemEventBus(event_tx));
+        world.insert_resource(SystemEventBus::new(event_tx));
 
         // Entity
         let ticket = store
@@ -363,6 +522,7 @@ mod tests {
                     node_type: "Agent".to_string(),
                     workflow_id: None,
                     tenant_id: Some(TenantId::from("default_tenant")),
+                    version: None,
                 },
                 inbox,
                 Outbox::default(),
@@ -1,5 +1,4 @@
-use crate::api::handlers;
-use crate::api::{ApiCommand, ApiReceiver};
+use crate::api::{ApiCommand, ApiReceiver, authz, handlers};
 use bevy_ecs::prelude::*;
 
 /// System: API Command Consumer
@@ -11,6 +10,8 @@ use bevy_ecs::prelude::*;
 /// - This system drains that channel every tick.
 /// - It performs "Exclusive World Access" operations like Spawning Entities (`LoadGraph`),
 ///   which cannot be done easily from async handlers.
+/// - Every request is authorized against its principal's tenant memberships before
+///   dispatch; rejected requests are audited and dropped.
 #[tracing::instrument(skip(world))]
 pub fn api_command_worker(world: &mut World) {
     if !world.contains_resource::<ApiReceiver>() {
@@ -19,19 +20,46 @@ pub fn api_command_worker(world: &mut World) {
 
     let receiver = world.resource::<ApiReceiver>().0.clone();
 
-    while let Ok(cmd) = receiver.try_recv() {
-        let result = match cmd {
+    while let Ok(request) = receiver.try_recv() {
+        if let Err(e) = authz::authorize(&request.principal, &request.command) {
+            authz::audit_denial(world, &request, &e);
+            continue;
+        }
+
+        let principal = &request.principal;
+        let result = match request.command {
             ApiCommand::LoadGraph(tenant, yaml) => {
                 handlers::graph::handle_load_graph(world, tenant, yaml)
             }
             ApiCommand::TriggerNode(tenant, uuid, payload) => {
-                handlers::trigger::handle_trigger_node(world, tenant, uuid, payload)
+                handlers::trigger::handle_trigger_node(world, principal, tenant, uuid, payload)
             }
             ApiCommand::TriggerWorkflow(tenant, workflow_id, payload) => {
-                handlers::trigger::handle_trigger_workflow(world, tenant, workflow_id, payload)
+                handlers::trigger::handle_trigger_workflow(world, principal, tenant, workflow_id, payload)
             }
+            ApiCommand::TriggerAndWait {
+                tenant_id,
+                workflow_id,
+                payload,
+                timeout_ms,
+                reply,
+            } => handlers::trigger::handle_trigger_and_wait(
+                world,
+                principal,
+                tenant_id,
+                workflow_id,
+                payload,
+                std::time::Duration::from_millis(timeout_ms),
+                reply,
+            ),
             ApiCommand::PinNode(tenant, node_id, ticket_id) => {
-                handlers::pin::handle_pin_node(world, tenant, node_id, ticket_id)
+                handlers::pin::handle_pin_node(world, principal, tenant, node_id, ticket_id)
+            }
+            ApiCommand::PinNodeOutput(tenant, node_id, output) => {
+                handlers::pin::handle_pin_node_output(world, principal, tenant, node_id, output)
+            }
+            ApiCommand::UnpinNode(tenant, node_id) => {
+                handlers::pin::handle_unpin_node(world, principal, tenant, node_id)
             }
             ApiCommand::ReloadDefinitions => handlers::registry::handle_reload_definitions(world),
             ApiCommand::SimulateNode {
@@ -42,12 +70,99 @@ pub fn api_command_worker(world: &mut World) {
                 mock_config,
             } => handlers::simulation::handle_simulate_node(
                 world,
+                principal,
                 tenant_id,
                 node_id,
                 input_ticket,
                 trace_id,
                 mock_config,
             ),
+            ApiCommand::ShadowRun {
+                tenant_id,
+                workflow_id,
+                payload,
+                mock_config,
+            } => handlers::simulation::handle_shadow_run(
+                world,
+                principal,
+                tenant_id,
+                workflow_id,
+                payload,
+                mock_config,
+            ),
+            ApiCommand::ReplayRun {
+                tenant_id,
+                trace_id,
+            } => handlers::replay::handle_replay_run(world, principal, tenant_id, trace_id),
+            ApiCommand::UpdateSettings(patch) => {
+                handlers::settings::handle_update_settings(world, patch)
+            }
+            ApiCommand::ConnectionVerified(tenant, slug) => {
+                handlers::connection::handle_connection_verified(world, tenant, slug)
+            }
+            ApiCommand::VerifyConnection {
+                tenant_id,
+                slug,
+                reply,
+            } => handlers::connection::handle_verify_connection(world, tenant_id, slug, reply),
+            ApiCommand::PauseWorkflow(tenant, workflow_id) => {
+                handlers::run::handle_pause_workflow(world, tenant, workflow_id)
+            }
+            ApiCommand::ResumeWorkflow(tenant, workflow_id) => {
+                handlers::run::handle_resume_workflow(world, tenant, workflow_id)
+            }
+            ApiCommand::CancelRun(tenant, trace_id) => {
+                handlers::run::handle_cancel_run(world, principal, tenant, trace_id)
+            }
+            ApiCommand::SavePromptTemplate {
+                tenant_id,
+                name,
+                template,
+                variables,
+            } => handlers::prompt::handle_save_prompt_template(
+                world, tenant_id, name, template, variables,
+            ),
+            ApiCommand::DeletePromptTemplate {
+                tenant_id,
+                name,
+                version,
+            } => handlers::prompt::handle_delete_prompt_template(world, tenant_id, name, version),
+            ApiCommand::RotateTenantKey(tenant) => {
+                handlers::connection::handle_rotate_tenant_key(world, tenant)
+            }
+            ApiCommand::SetTenantLimits { tenant_id, limits } => {
+                handlers::quota::handle_set_tenant_limits(world, tenant_id, limits)
+            }
+            ApiCommand::ListTemplates { tenant_id, reply } => {
+                handlers::template::handle_list_templates(world, tenant_id, reply)
+            }
+            ApiCommand::ListNodeTypes {
+                tenant_id,
+                query,
+                category,
+                reply,
+            } => {
+                handlers::registry::handle_list_node_types(world, tenant_id, query, category, reply)
+            }
+            ApiCommand::InstantiateTemplate {
+                tenant_id,
+                template_id,
+                workflow_id,
+                connections,
+                reply,
+            } => handlers::template::handle_instantiate_template(
+                world,
+                tenant_id,
+                template_id,
+                workflow_id,
+                connections,
+                reply,
+            ),
+            ApiCommand::InstallPlugin {
+                tenant_id,
+                ticket,
+                reply,
+            } => handlers::registry::handle_install_plugin(world, tenant_id, ticket, reply),
         };
 
         if let Err(e) = result {
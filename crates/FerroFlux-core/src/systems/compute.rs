@@ -1,3 +1,5 @@
+pub mod plugin;
 pub mod wasm;
 
+pub use plugin::plugin_worker;
 pub use wasm::{wasm_worker, WasmRuntime};
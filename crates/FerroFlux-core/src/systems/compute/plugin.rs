@@ -0,0 +1,66 @@
+use super::WasmRuntime;
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::{Inbox, NodeConfig, Outbox, WorkDone};
+use crate::nodes::plugin::PluginNode;
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+/// System: WASM Plugin Worker
+///
+/// Runs nodes whose type comes from a plugin (see `nodes::plugin`). Each input payload goes
+/// through the plugin's `execute` export in a fresh sandboxed instance; its result is emitted
+/// as the output payload. Traps, exhausted fuel and invalid output route the input to the
+/// `error` port.
+pub fn plugin_worker(
+    mut query: Query<(&PluginNode, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    wasm_runtime: Option<Res<WasmRuntime>>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+) {
+    let Some(runtime) = wasm_runtime else {
+        return;
+    };
+
+    for (node, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let start = Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<Vec<u8>> {
+                let data = store.claim(&ticket)?;
+                let input: Value = serde_json::from_slice(&data).unwrap_or(Value::Null);
+                let output = node
+                    .plugin
+                    .execute(&runtime.engine, &node.settings, &input)?;
+                Ok(serde_json::to_vec(&output)?)
+            })();
+
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: node.plugin.node_type().to_string(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok(_) => json!({}),
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            match result.and_then(|bytes| store.check_in(&bytes)) {
+                Ok(mut new_ticket) => {
+                    new_ticket.metadata = ticket.metadata;
+                    outbox.queue.push_back((None, new_ticket));
+                }
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "WASM plugin failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
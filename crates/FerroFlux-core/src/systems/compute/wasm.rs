@@ -51,7 +51,7 @@ pub fn wasm_worker(
     mut query: Query<(Entity, &mut Inbox, &mut Outbox, &ComputeConfig)>,
     wasm_runtime: Option<Res<WasmRuntime>>,
     blob_store: Option<Res<BlobStore>>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
 ) {
     let runtime = match wasm_runtime {
         Some(r) => r,
@@ -69,7 +69,7 @@ pub fn wasm_worker(
         }
 
         while let Some(ticket) = inbox.queue.pop_front() {
-            work_done.0 = true;
+            work_done.mark();
 
             // 1. Claim Data from BlobStore
             let input_bytes_arc = match store.claim(&ticket) {
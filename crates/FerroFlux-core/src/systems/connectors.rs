@@ -1,9 +1,27 @@
+pub mod amqp;
+pub mod email;
 pub mod ftp;
+pub mod geoip;
+pub mod health;
+pub mod imap;
+pub mod redis;
 pub mod rss;
+pub mod sql;
+pub mod sse;
 pub mod ssh;
+pub mod websocket;
 pub mod xml;
 
+pub use self::amqp::{amqp_ack_worker, amqp_sink_worker, amqp_source_worker};
+pub use self::email::email_worker;
 pub use self::ftp::ftp_worker;
+pub use self::geoip::geoip_worker;
+pub use self::health::connection_health_worker;
+pub use self::imap::imap_worker;
+pub use self::redis::redis_worker;
 pub use self::rss::rss_worker;
+pub use self::sql::sql_query_worker;
+pub use self::sse::sse_worker;
 pub use self::ssh::ssh_worker;
+pub use self::websocket::websocket_worker;
 pub use self::xml::xml_worker;
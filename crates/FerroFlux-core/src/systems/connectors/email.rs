@@ -0,0 +1,128 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::EmailConfig;
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::mailer::{EmailSender, OutgoingEmail, SmtpSettings};
+use crate::resources::{EmailResultChannel, TokioRuntime, WorkDone};
+use crate::secrets::{DatabaseSecretStore, SecretStore};
+use crate::store::BlobStore;
+use crate::systems::io::templating::apply_template;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+/// System: Email Worker
+///
+/// Renders one email per inbound ticket and sends it as a background task, then forwards the
+/// ticket unchanged. Failures route the ticket to the `error` port.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    sender,
+    secret_store,
+    event_bus,
+    channel,
+    runtime,
+    work_done
+))]
+pub fn email_worker(
+    mut query: Query<(Entity, &EmailConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    sender: Res<EmailSender>,
+    secret_store: Res<DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    channel: Res<EmailResultChannel>,
+    runtime: Res<TokioRuntime>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished sends
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, _, node_config, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        match result {
+            Ok(_) => outbox.queue.push_back((None, ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Email send failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new sends
+    for (entity, config, node_config, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let email = store.claim(&ticket).and_then(|data| {
+                let payload: Value = serde_json::from_slice(&data).unwrap_or(Value::Null);
+                render(config, &payload)
+            });
+            let email = match email {
+                Ok(email) => email,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let secret_store = secret_store.clone();
+            let sender = sender.clone();
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let slug = config.connection_slug.clone();
+            let node_id = node_config.id;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let recipients = email.to.len();
+                let result = async {
+                    let connection = secret_store.resolve_connection(&tenant, &slug).await?;
+                    let settings: SmtpSettings = serde_json::from_value(connection)?;
+                    sender.send(&settings, email).await
+                }
+                .await;
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "Email".into(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(_) => json!({ "recipients": recipients }),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    },
+                });
+                let _ = tx
+                    .send((entity, ticket, result.map(|_| json!(recipients))))
+                    .await;
+            });
+        }
+    }
+}
+
+fn render(config: &EmailConfig, payload: &Value) -> anyhow::Result<OutgoingEmail> {
+    let to: Vec<String> = apply_template(&config.to, payload)
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+    if to.is_empty() {
+        return Err(anyhow::anyhow!("No recipients"));
+    }
+    Ok(OutgoingEmail {
+        to,
+        from: config.from.clone(),
+        subject: apply_template(&config.subject, payload),
+        body: apply_template(&config.body, payload),
+        html: config.html,
+    })
+}
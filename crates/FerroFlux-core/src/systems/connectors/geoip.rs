@@ -0,0 +1,282 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{GeoIpConfig, GeoIpState};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::settings::{NetworkSettings, RuntimeSettings};
+use crate::resources::{GeoIpResultChannel, GlobalHttpClient, TokioRuntime, WorkDone};
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use maxminddb::{MaxMindDBError, Reader, geoip2};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Reverse DNS answers kept per node before the cache is cleared.
+const HOSTNAME_CACHE_LIMIT: usize = 4096;
+
+/// How long a reverse DNS lookup may take before the hostname is reported as `null`.
+const REVERSE_DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// System: Geo/IP Enrichment
+///
+/// Adds an object under `result_key` with the client `ip`, its `geo` location (MaxMind
+/// database or lookup API), the parsed `user_agent` and the reverse DNS `hostname`. Lookups
+/// run as a background task; those that find nothing (or, for reverse DNS, time out) yield
+/// `null`. A missing database or failing API routes to `error`.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    http_client,
+    event_bus,
+    channel,
+    runtime,
+    settings,
+    work_done
+))]
+pub fn geoip_worker(
+    mut query: Query<(
+        Entity,
+        &GeoIpConfig,
+        &NodeConfig,
+        &mut GeoIpState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    http_client: Res<GlobalHttpClient>,
+    event_bus: Res<SystemEventBus>,
+    channel: Res<GeoIpResultChannel>,
+    runtime: Res<TokioRuntime>,
+    settings: Option<Res<RuntimeSettings>>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished lookups
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, _, node_config, _, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|payload| {
+            store.check_in_with_metadata(&serde_json::to_vec(&payload)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Geo/IP enrichment failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new lookups
+    let settings = RuntimeSettings::effective(settings.as_deref());
+    for (entity, config, node_config, mut state, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let prepared = (|| -> anyhow::Result<_> {
+                let data = store.claim(&ticket)?;
+                let payload: Value = serde_json::from_slice(&data)?;
+                if !payload.is_object() {
+                    return Err(anyhow::anyhow!("Geo/IP input must be a JSON object"));
+                }
+                let ip = payload
+                    .get(&config.ip_field)
+                    .and_then(|v| v.as_str())
+                    .and_then(parse_ip);
+                let reader = match (&config.database_path, &state.reader, ip) {
+                    (Some(_), Some(reader), Some(_)) => Some(reader.clone()),
+                    (Some(path), None, Some(_)) => {
+                        let reader = Arc::new(Reader::open_readfile(path).map_err(|e| {
+                            anyhow::anyhow!("Failed to open GeoIP database: {}", e)
+                        })?);
+                        state.reader = Some(reader.clone());
+                        Some(reader)
+                    }
+                    _ => None,
+                };
+                Ok((payload, ip, reader))
+            })();
+            let (payload, ip, reader) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let client = http_client.client.clone();
+            let network = settings.network.clone();
+            let hostnames = state.hostnames.clone();
+            let config = config.clone();
+            let node_id = node_config.id;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let result = enrich(
+                    &config,
+                    ip,
+                    reader.as_deref(),
+                    &hostnames,
+                    &network,
+                    &client,
+                    &payload,
+                )
+                .await;
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "GeoIP".into(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(enrichment) => enrichment.clone(),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    },
+                });
+                let result = result.map(|enrichment| {
+                    let mut payload = payload;
+                    if let Some(object) = payload.as_object_mut() {
+                        object.insert(config.result_key.clone(), enrichment);
+                    }
+                    payload
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+/// Builds the object written under `result_key`.
+async fn enrich(
+    config: &GeoIpConfig,
+    ip: Option<IpAddr>,
+    reader: Option<&Reader<Vec<u8>>>,
+    hostnames: &Mutex<HashMap<IpAddr, Option<String>>>,
+    network: &NetworkSettings,
+    client: &reqwest::Client,
+    payload: &Value,
+) -> anyhow::Result<Value> {
+    let mut enrichment = Map::new();
+    enrichment.insert("ip".into(), json!(ip.map(|ip| ip.to_string())));
+
+    if let Some(ip) = ip {
+        if let Some(reader) = reader {
+            enrichment.insert("geo".into(), lookup_database(reader, ip)?);
+        } else if let Some(api_url) = &config.api_url {
+            // Internal addresses have no location; don't leak them to a third party.
+            let geo = if ferroflux_security::network::is_blocked_ip(ip) {
+                Value::Null
+            } else {
+                lookup_api(api_url, ip, network, client).await?
+            };
+            enrichment.insert("geo".into(), geo);
+        }
+
+        if config.reverse_dns {
+            enrichment.insert("hostname".into(), json!(reverse_dns(ip, hostnames).await));
+        }
+    }
+
+    if let Some(field) = &config.user_agent_field {
+        let user_agent = payload
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(parse_user_agent)
+            .unwrap_or(Value::Null);
+        enrichment.insert("user_agent".into(), user_agent);
+    }
+    Ok(Value::Object(enrichment))
+}
+
+/// Resolves the PTR record for `ip`. Answers, including misses, are cached; timeouts are not.
+async fn reverse_dns(
+    ip: IpAddr,
+    hostnames: &Mutex<HashMap<IpAddr, Option<String>>>,
+) -> Option<String> {
+    if let Some(hostname) = hostnames.lock().unwrap().get(&ip) {
+        return hostname.clone();
+    }
+    let lookup = tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip).ok());
+    let Ok(Ok(hostname)) = tokio::time::timeout(REVERSE_DNS_TIMEOUT, lookup).await else {
+        return None;
+    };
+    let mut hostnames = hostnames.lock().unwrap();
+    if hostnames.len() >= HOSTNAME_CACHE_LIMIT {
+        hostnames.clear();
+    }
+    hostnames.insert(ip, hostname.clone());
+    hostname
+}
+
+/// Accepts a bare address, `ip:port`, or an `X-Forwarded-For` list (first entry wins).
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let first = value.split(',').next()?.trim();
+    first
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| first.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+fn lookup_database(reader: &Reader<Vec<u8>>, ip: IpAddr) -> anyhow::Result<Value> {
+    let city: geoip2::City = match reader.lookup(ip) {
+        Ok(city) => city,
+        Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(Value::Null),
+        Err(e) => return Err(anyhow::anyhow!("GeoIP lookup failed: {}", e)),
+    };
+    let country = city.country.as_ref();
+    let subdivision = city.subdivisions.as_ref().and_then(|s| s.first());
+    let location = city.location.as_ref();
+    Ok(json!({
+        "country_code": country.and_then(|c| c.iso_code),
+        "country": english(country.and_then(|c| c.names.as_ref())),
+        "region_code": subdivision.and_then(|s| s.iso_code),
+        "region": english(subdivision.and_then(|s| s.names.as_ref())),
+        "city": english(city.city.as_ref().and_then(|c| c.names.as_ref())),
+        "postal_code": city.postal.as_ref().and_then(|p| p.code),
+        "continent_code": city.continent.as_ref().and_then(|c| c.code),
+        "latitude": location.and_then(|l| l.latitude),
+        "longitude": location.and_then(|l| l.longitude),
+        "accuracy_radius_km": location.and_then(|l| l.accuracy_radius),
+        "time_zone": location.and_then(|l| l.time_zone),
+    }))
+}
+
+fn english<'a>(names: Option<&BTreeMap<&'a str, &'a str>>) -> Option<&'a str> {
+    names.and_then(|names| names.get("en").copied())
+}
+
+async fn lookup_api(
+    api_url: &str,
+    ip: IpAddr,
+    network: &NetworkSettings,
+    client: &reqwest::Client,
+) -> anyhow::Result<Value> {
+    let url = api_url.replace("{ip}", &ip.to_string());
+    network
+        .validate_url(&url)
+        .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+    let response = client.get(&url).send().await?.error_for_status()?;
+    Ok(response.json::<Value>().await?)
+}
+
+fn parse_user_agent(user_agent: &str) -> Value {
+    let parser = woothee::parser::Parser::new();
+    match parser.parse(user_agent) {
+        Some(ua) => json!({
+            "name": ua.name,
+            "version": ua.version,
+            "category": ua.category,
+            "os": ua.os,
+            "os_version": ua.os_version,
+            "vendor": ua.vendor,
+            "is_bot": ua.category == "crawler",
+        }),
+        None => json!({ "name": "UNKNOWN", "is_bot": false }),
+    }
+}
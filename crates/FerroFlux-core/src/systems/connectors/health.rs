@@ -0,0 +1,79 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::resources::TokioRuntime;
+use crate::resources::connection_health::{ConnectionHealth, PausedWorkflows};
+use crate::resources::settings::RuntimeSettings;
+use crate::store::database::PersistentStore;
+use bevy_ecs::prelude::*;
+
+/// System: Connection Health
+///
+/// Acts on connections `ConnectionHealth` has tripped: marks them `error` in the store,
+/// pauses their workflows when `connections.pause_dependent_workflows` is set, and emits
+/// `ConnectionUnhealthy`. Connections that passed a `VerifyConnection` check since are reset
+/// and their workflows resumed.
+#[tracing::instrument(skip(health, settings, paused, store, runtime, event_bus))]
+pub fn connection_health_worker(
+    health: Option<Res<ConnectionHealth>>,
+    settings: Option<Res<RuntimeSettings>>,
+    paused: Option<ResMut<PausedWorkflows>>,
+    store: Option<Res<PersistentStore>>,
+    runtime: Option<Res<TokioRuntime>>,
+    event_bus: Res<SystemEventBus>,
+) {
+    let Some(health) = health else {
+        return;
+    };
+    let mut paused = paused;
+    for (tenant, slug) in health.drain_verified() {
+        health.reset(&tenant, &slug);
+        if let Some(paused) = paused.as_mut() {
+            for workflow_id in paused.release(&tenant, &slug) {
+                tracing::info!(%tenant, %workflow_id, "Workflow resumed");
+            }
+        }
+    }
+
+    let trips = health.drain_trips();
+    if trips.is_empty() {
+        return;
+    }
+    let settings = RuntimeSettings::effective(settings.as_deref());
+
+    for trip in trips {
+        tracing::warn!(
+            tenant = %trip.tenant,
+            slug = %trip.slug,
+            auth_failures = trip.auth_failures,
+            "Connection keeps failing authentication; marking it as error"
+        );
+
+        if let (Some(store), Some(runtime)) = (&store, &runtime) {
+            let store = PersistentStore::clone(store);
+            let (tenant, slug) = (trip.tenant.clone(), trip.slug.clone());
+            runtime.0.spawn(async move {
+                if let Err(e) = store.mark_connection_status(&tenant, &slug, "error").await {
+                    tracing::error!(%slug, error = %e, "Failed to mark connection status");
+                }
+            });
+        }
+
+        let mut paused_workflows = Vec::new();
+        if settings.connections.pause_dependent_workflows
+            && let Some(paused) = paused.as_mut()
+        {
+            for workflow_id in &trip.workflows {
+                paused.pause(&trip.tenant, workflow_id, &trip.slug);
+                paused_workflows.push(workflow_id.clone());
+            }
+        }
+
+        let _ = event_bus.send(SystemEvent::ConnectionUnhealthy {
+            tenant_id: trip.tenant.as_ref().to_string(),
+            slug: trip.slug,
+            auth_failures: trip.auth_failures,
+            error_rate: trip.error_rate,
+            paused_workflows,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+}
@@ -0,0 +1,327 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{ImapConfig, ImapState};
+use crate::components::core::{NodeConfig, Outbox};
+use crate::resources::quotas::{TenantQuotas, admit_node_run};
+use crate::resources::{ImapResultChannel, TokioRuntime, WorkDone};
+use crate::secrets::{DatabaseSecretStore, SecretStore};
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use mail_parser::{MessageParser, MimeHeaders};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// IMAP credentials, as stored in an IMAP connection.
+#[derive(Deserialize)]
+struct ImapSettings {
+    host: String,
+    /// Implicit TLS port. Defaults to 993.
+    #[serde(default)]
+    port: Option<u16>,
+    username: String,
+    password: String,
+}
+
+/// System: IMAP Inbox Poller
+///
+/// Each node polls on its own `interval_seconds`, as a background task. New messages become
+/// tickets with a fresh trace id; attachments are checked into the `BlobStore` separately and
+/// referenced by id. Messages refused by the tenant's quotas are skipped.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    secret_store,
+    event_bus,
+    channel,
+    runtime,
+    quotas,
+    work_done
+))]
+pub fn imap_worker(
+    mut query: Query<(
+        Entity,
+        &ImapConfig,
+        &NodeConfig,
+        &mut ImapState,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    secret_store: Res<DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    channel: Res<ImapResultChannel>,
+    runtime: Res<TokioRuntime>,
+    quotas: Option<Res<TenantQuotas>>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished polls
+    while let Ok((entity, result)) = channel.rx.try_recv() {
+        let Ok((_, _, node_config, mut state, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        state.polling = false;
+        let execution_ms = state
+            .last_poll
+            .map_or(0, |started| started.elapsed().as_millis() as u64);
+        let result = result.and_then(|(cursor, messages)| {
+            state.last_uid = cursor.last_uid;
+            state.uid_validity = cursor.uid_validity;
+            let count = messages.len();
+            for (uid, raw) in messages {
+                // The cursor has already moved past this batch, so skip bad messages
+                // rather than losing the rest of it.
+                let payload = match message_to_payload(uid, &raw, &store) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        tracing::warn!(node_id = %node_config.id, uid, error = %e, "Skipping unreadable message");
+                        continue;
+                    }
+                };
+                let bytes = serde_json::to_vec(&payload)?;
+                if admit_node_run(
+                    quotas.as_deref(),
+                    Some(&*event_bus),
+                    node_config,
+                    bytes.len(),
+                )
+                .is_err()
+                {
+                    continue;
+                }
+                let mut ticket = store.check_in(&bytes)?;
+                ticket
+                    .metadata
+                    .insert("trace_id".into(), uuid::Uuid::new_v4().to_string());
+                outbox.queue.push_back((None, ticket));
+            }
+            Ok(count)
+        });
+        work_done.mark();
+
+        // Quiet polls are not worth an event.
+        if matches!(result, Ok(0)) {
+            continue;
+        }
+        let _ = event_bus.send(SystemEvent::NodeTelemetry {
+            trace_id: "system".into(),
+            node_id: node_config.id,
+            node_type: "IMAP".into(),
+            execution_ms,
+            success: result.is_ok(),
+            details: match &result {
+                Ok(count) => json!({ "message": "Polled mailbox", "new_messages": count }),
+                Err(e) => json!({ "error": e.to_string() }),
+            },
+        });
+        if let Err(e) = result {
+            tracing::error!(node_id = %node_config.id, error = %e, "IMAP poll failed");
+        }
+    }
+
+    // 2. Start due polls
+    for (entity, config, node_config, mut state, _) in query.iter_mut() {
+        if state.polling {
+            continue;
+        }
+        if let Some(last) = state.last_poll
+            && last.elapsed() < Duration::from_secs(config.interval_seconds)
+        {
+            continue;
+        }
+        state.last_poll = Some(Instant::now());
+        state.polling = true;
+
+        let tx = channel.tx.clone();
+        let secret_store = secret_store.clone();
+        let config = config.clone();
+        let mut cursor = state.clone();
+        let tenant = node_config
+            .tenant_id
+            .clone()
+            .unwrap_or_else(|| TenantId::from("default_tenant"));
+
+        runtime.0.spawn(async move {
+            let result = async {
+                let connection = secret_store
+                    .resolve_connection(&tenant, &config.connection_slug)
+                    .await?;
+                let settings: ImapSettings = serde_json::from_value(connection)?;
+                // The imap client is blocking; its socket timeouts bound how long this runs.
+                tokio::task::spawn_blocking(move || {
+                    let messages = fetch_new(&config, &settings, &mut cursor)?;
+                    Ok((cursor, messages))
+                })
+                .await?
+            }
+            .await;
+            let _ = tx.send((entity, result)).await;
+        });
+    }
+}
+
+/// Fetches raw messages newer than `state.last_uid`, oldest first, and advances the cursor.
+fn fetch_new(
+    config: &ImapConfig,
+    settings: &ImapSettings,
+    state: &mut ImapState,
+) -> anyhow::Result<Vec<(u32, Vec<u8>)>> {
+    let port = settings.port.unwrap_or(993);
+    ferroflux_security::network::validate_host_port(&settings.host, port)
+        .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+
+    let client = connect(
+        &settings.host,
+        port,
+        Duration::from_secs(config.timeout_seconds),
+    )?;
+    let mut session = client
+        .login(&settings.username, &settings.password)
+        .map_err(|(e, _)| e)?;
+
+    let mailbox = session.select(&config.folder)?;
+    if state.uid_validity != mailbox.uid_validity {
+        state.uid_validity = mailbox.uid_validity;
+        state.last_uid = None;
+    }
+
+    let Some(last_uid) = state.last_uid else {
+        // First poll: start from the current end of the mailbox instead of replaying it.
+        let newest = match mailbox.uid_next {
+            Some(next) => next.saturating_sub(1),
+            None => session.uid_search("ALL")?.into_iter().max().unwrap_or(0),
+        };
+        state.last_uid = Some(newest);
+        let _ = session.logout();
+        return Ok(Vec::new());
+    };
+
+    let mut criteria = format!("UID {}:*", last_uid + 1);
+    if let Some(from) = &config.from_filter {
+        criteria.push_str(&format!(" FROM {}", quote(from)));
+    }
+    if let Some(subject) = &config.subject_filter {
+        criteria.push_str(&format!(" SUBJECT {}", quote(subject)));
+    }
+    // `n:*` always matches the newest message, even when its UID is below `n`.
+    let mut uids: Vec<u32> = session
+        .uid_search(&criteria)?
+        .into_iter()
+        .filter(|uid| *uid > last_uid)
+        .collect();
+    uids.sort_unstable();
+    uids.truncate(config.max_messages);
+    if uids.is_empty() {
+        let _ = session.logout();
+        return Ok(Vec::new());
+    }
+
+    let uid_set = uids
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    // BODY.PEEK leaves the \Seen flag alone, so the mailbox still looks unread to people.
+    let fetches = session.uid_fetch(&uid_set, "(UID BODY.PEEK[])")?;
+    let mut messages: Vec<(u32, Vec<u8>)> = fetches
+        .iter()
+        .filter_map(|fetch| Some((fetch.uid?, fetch.body()?.to_vec())))
+        .collect();
+    messages.sort_by_key(|(uid, _)| *uid);
+    let _ = session.logout();
+
+    if let Some(newest) = uids.last() {
+        state.last_uid = Some(*newest);
+    }
+    Ok(messages)
+}
+
+/// Opens an implicit-TLS connection whose socket gives up after `timeout` on connect, reads
+/// and writes.
+fn connect(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> anyhow::Result<imap::Client<native_tls::TlsStream<TcpStream>>> {
+    let mut last_error = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(tcp) => {
+                tcp.set_read_timeout(Some(timeout))?;
+                tcp.set_write_timeout(Some(timeout))?;
+                let tls = native_tls::TlsConnector::new()?.connect(host, tcp)?;
+                let mut client = imap::Client::new(tls);
+                client.read_greeting()?;
+                return Ok(client);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(match last_error {
+        Some(e) => e.into(),
+        None => anyhow::anyhow!("No addresses found for {}", host),
+    })
+}
+
+/// Quotes a string for use in an IMAP SEARCH command.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Parses a raw RFC 822 message into the ticket payload emitted by the IMAP node.
+///
+/// Attachments are checked into `store` on their own and listed with their `ticket_id`, so
+/// large files never travel inside the message payload.
+pub fn message_to_payload(uid: u32, raw: &[u8], store: &BlobStore) -> anyhow::Result<Value> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse message {}", uid))?;
+
+    let addresses = |address: Option<&mail_parser::Address>| -> Vec<Value> {
+        address
+            .map(|a| {
+                a.iter()
+                    .map(|addr| json!({ "name": addr.name(), "address": addr.address() }))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut attachments = Vec::new();
+    for part in message.attachments() {
+        let content_type = part
+            .content_type()
+            .map(|ct| match ct.subtype() {
+                Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+                None => ct.ctype().to_string(),
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        let filename = part.attachment_name().unwrap_or("attachment").to_string();
+        let metadata = HashMap::from([
+            ("filename".to_string(), filename.clone()),
+            ("content_type".to_string(), content_type.clone()),
+        ]);
+        let ticket = store.check_in_with_metadata(part.contents(), metadata)?;
+        attachments.push(json!({
+            "filename": filename,
+            "content_type": content_type,
+            "size": part.len(),
+            "ticket_id": ticket.id,
+        }));
+    }
+
+    Ok(json!({
+        "uid": uid,
+        "message_id": message.message_id(),
+        "subject": message.subject(),
+        "from": addresses(message.from()),
+        "to": addresses(message.to()),
+        "cc": addresses(message.cc()),
+        "date": message.date().map(|d| d.to_rfc3339()),
+        "text": message.body_text(0),
+        "html": message.body_html(0),
+        "attachments": attachments,
+    }))
+}
@@ -0,0 +1,222 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{RedisConfig, RedisOperation, RedisState};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::{RedisResultChannel, TokioRuntime, WorkDone};
+use crate::secrets::{DatabaseSecretStore, SecretStore};
+use crate::store::BlobStore;
+use crate::systems::io::templating::apply_template;
+use crate::systems::utils::merge_result;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use redis::AsyncConnectionConfig;
+use redis::aio::MultiplexedConnection;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+/// System: Redis Worker
+///
+/// Runs one command per inbound ticket as a background task. The reply is merged into the
+/// payload via `result_key`; connection or command failures, including calls that outlast
+/// `timeout_ms`, route the original ticket to `error`.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+#[tracing::instrument(skip(query, store, secret_store, event_bus, channel, runtime, work_done))]
+pub fn redis_worker(
+    mut query: Query<(
+        Entity,
+        &RedisConfig,
+        &NodeConfig,
+        &RedisState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    secret_store: Res<DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    channel: Res<RedisResultChannel>,
+    runtime: Res<TokioRuntime>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished commands
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, config, node_config, _, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|reply| {
+            let payload: Value =
+                serde_json::from_slice(&store.claim(&ticket)?).unwrap_or(Value::Null);
+            let output = merge_result(&payload, &reply.to_string(), config.result_key.as_ref());
+            store.check_in_with_metadata(output.as_bytes(), ticket.metadata.clone())
+        });
+        match output {
+            Ok(out) => outbox.queue.push_back((None, out)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Redis command failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new commands
+    for (entity, config, node_config, state, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let payload = match store.claim(&ticket) {
+                Ok(data) => serde_json::from_slice(&data).unwrap_or(Value::Null),
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let secret_store = secret_store.clone();
+            let cache = state.connection.clone();
+            let config = config.clone();
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let node_id = node_config.id;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let result = async {
+                    let mut conn = connect(&config, &secret_store, &tenant, &cache).await?;
+                    let reply = execute(&mut conn, &config, &payload).await;
+                    if reply.is_err() {
+                        // Drop the connection so the next ticket reconnects.
+                        *cache.lock().await = None;
+                    }
+                    reply
+                }
+                .await;
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "Redis".into(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(_) => json!({ "operation": format!("{:?}", config.operation) }),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+/// Returns the node's cached connection, opening one when there is none.
+async fn connect(
+    config: &RedisConfig,
+    secret_store: &DatabaseSecretStore,
+    tenant: &TenantId,
+    cache: &tokio::sync::Mutex<Option<MultiplexedConnection>>,
+) -> anyhow::Result<MultiplexedConnection> {
+    let mut cached = cache.lock().await;
+    if let Some(conn) = cached.as_ref() {
+        return Ok(conn.clone());
+    }
+
+    let url = resolve_url(config, secret_store, tenant).await?;
+    validate_url(&url).map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+    let timeout = Duration::from_millis(config.timeout_ms);
+    let conn = redis::Client::open(url)?
+        .get_multiplexed_async_connection_with_config(
+            &AsyncConnectionConfig::new()
+                .set_connection_timeout(timeout)
+                .set_response_timeout(timeout),
+        )
+        .await?;
+    *cached = Some(conn.clone());
+    Ok(conn)
+}
+
+async fn execute(
+    conn: &mut MultiplexedConnection,
+    config: &RedisConfig,
+    payload: &Value,
+) -> anyhow::Result<Value> {
+    let key = apply_template(&config.key, payload);
+    let value = match &config.value {
+        Some(template) => apply_template(template, payload),
+        None => payload.to_string(),
+    };
+
+    let reply = match config.operation {
+        RedisOperation::Get => {
+            let value: Option<String> = redis::cmd("GET").arg(&key).query_async(conn).await?;
+            // Values written by this node are usually JSON; surface them structured.
+            value.map_or(Value::Null, |v| {
+                serde_json::from_str(&v).unwrap_or(Value::String(v))
+            })
+        }
+        RedisOperation::Set => {
+            let mut cmd = redis::cmd("SET");
+            cmd.arg(&key).arg(&value);
+            if let Some(ttl) = config.ttl_seconds {
+                cmd.arg("EX").arg(ttl);
+            }
+            let status: String = cmd.query_async(conn).await?;
+            json!(status)
+        }
+        RedisOperation::Incr => json!(
+            redis::cmd("INCR")
+                .arg(&key)
+                .query_async::<i64>(conn)
+                .await?
+        ),
+        RedisOperation::Publish => {
+            json!(
+                redis::cmd("PUBLISH")
+                    .arg(&key)
+                    .arg(&value)
+                    .query_async::<i64>(conn)
+                    .await?
+            )
+        }
+        RedisOperation::LPush => {
+            json!(
+                redis::cmd("LPUSH")
+                    .arg(&key)
+                    .arg(&value)
+                    .query_async::<i64>(conn)
+                    .await?
+            )
+        }
+    };
+    Ok(reply)
+}
+
+async fn resolve_url(
+    config: &RedisConfig,
+    secret_store: &DatabaseSecretStore,
+    tenant: &TenantId,
+) -> anyhow::Result<String> {
+    let Some(slug) = &config.connection_slug else {
+        return config
+            .url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Redis node needs a url or connection_slug"));
+    };
+    let connection = secret_store.resolve_connection(tenant, slug).await?;
+    connection
+        .get("url")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Redis connection is missing a 'url' field"))
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid Redis URL: {}", e))?;
+    if !matches!(parsed.scheme(), "redis" | "rediss") {
+        return Err(format!("Unsupported Redis scheme '{}'", parsed.scheme()));
+    }
+    let host = parsed.host_str().ok_or("Redis URL missing host")?;
+    ferroflux_security::network::validate_host_port(host, parsed.port().unwrap_or(6379))
+}
@@ -15,7 +15,7 @@ pub fn rss_worker(
     event_bus: Res<SystemEventBus>,
     mut local: Local<Option<std::time::Instant>>,
 ) {
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     // Simple throttle (10s global)
     if let Some(last) = *local
@@ -0,0 +1,299 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{SqlQueryConfig, SqlQueryState};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::connection_health::{ConnectionHealth, ConnectionOutcome};
+use crate::resources::settings::{FileSettings, RuntimeSettings};
+use crate::resources::{FileSandbox, SqlResultChannel, TokioRuntime, WorkDone};
+use crate::secrets::{DatabaseSecretStore, SecretStore};
+use crate::store::BlobStore;
+use base64::{Engine as _, engine::general_purpose};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use ferroflux_security::sandbox;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::{Value, json};
+use sqlx::any::{AnyArguments, AnyPoolOptions, AnyRow, AnyTypeInfoKind};
+use sqlx::{Arguments, Column, Row, ValueRef};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// System: SQL Query Worker
+///
+/// Runs the configured statement once per inbound ticket as a background task and emits the
+/// resulting rows as a JSON array. Failures, including queries that outlast `timeout_ms`, are
+/// routed to the `error` port with the original ticket.
+///
+/// SQLite is refused unless `files.allow_sqlite` is set, and then only for database files
+/// inside the tenant's file directory.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    secret_store,
+    event_bus,
+    channel,
+    runtime,
+    settings,
+    health,
+    sandbox_root,
+    work_done
+))]
+pub fn sql_query_worker(
+    mut query: Query<(
+        Entity,
+        &SqlQueryConfig,
+        &NodeConfig,
+        &SqlQueryState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    secret_store: Res<DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    channel: Res<SqlResultChannel>,
+    runtime: Res<TokioRuntime>,
+    settings: Option<Res<RuntimeSettings>>,
+    health: Option<Res<ConnectionHealth>>,
+    sandbox_root: Option<Res<FileSandbox>>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished queries
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, _, node_config, _, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|rows| {
+            store.check_in_with_metadata(&serde_json::to_vec(&rows)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(out) => outbox.queue.push_back((None, out)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "SQL query failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new queries
+    let settings = RuntimeSettings::effective(settings.as_deref());
+    let root = sandbox_root
+        .map(|s| s.0.clone())
+        .unwrap_or_else(|| FileSandbox::default().0);
+    for (entity, config, node_config, state, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let arguments = store.claim(&ticket).and_then(|data| {
+                let payload: Value = serde_json::from_slice(&data).unwrap_or(Value::Null);
+                bind_params(&payload, &config.params)
+            });
+            let arguments = match arguments {
+                Ok(arguments) => arguments,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let secret_store = secret_store.clone();
+            let cache = state.pool.clone();
+            let health = health.as_deref().cloned();
+            let health_settings = settings.connections.clone();
+            let files = settings.files.clone();
+            let root = root.clone();
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let workflow_id = node_config.workflow_id.clone();
+            let node_id = node_config.id;
+            let slug = config.connection_slug.clone();
+            let statement = config.query.clone();
+            let max_rows = config.max_rows;
+            let timeout = Duration::from_millis(config.timeout_ms);
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let call = async {
+                    let pool =
+                        connect(&secret_store, &tenant, &slug, &cache, (&files, &root)).await?;
+                    let rows: Vec<AnyRow> = sqlx::query_with(&statement, arguments)
+                        .fetch(&pool)
+                        .take(max_rows)
+                        .try_collect()
+                        .await?;
+                    rows.iter()
+                        .map(row_to_json)
+                        .collect::<anyhow::Result<Vec<Value>>>()
+                };
+                let result = match tokio::time::timeout(timeout, call).await {
+                    Ok(result) => result,
+                    Err(_) => Err(anyhow::anyhow!(
+                        "SQL query timed out after {} ms",
+                        timeout.as_millis()
+                    )),
+                };
+
+                if let Some(health) = &health {
+                    health.record(
+                        &health_settings,
+                        &tenant,
+                        &slug,
+                        workflow_id.as_deref(),
+                        match &result {
+                            Ok(_) => ConnectionOutcome::Success,
+                            Err(e) => ConnectionOutcome::from_error(&e.to_string()),
+                        },
+                    );
+                }
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "SQL".into(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(rows) => json!({ "rows": rows.len() }),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result.map(Value::Array))).await;
+            });
+        }
+    }
+}
+
+/// Returns the node's cached pool, (re)opening it when the resolved connection URL changed.
+async fn connect(
+    secret_store: &DatabaseSecretStore,
+    tenant: &TenantId,
+    slug: &str,
+    cache: &tokio::sync::Mutex<Option<(String, sqlx::AnyPool)>>,
+    (files, root): (&FileSettings, &Path),
+) -> anyhow::Result<sqlx::AnyPool> {
+    let connection = secret_store.resolve_connection(tenant, slug).await?;
+    let url = connection
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Connection '{}' is missing a 'url' field", slug))?
+        .to_string();
+
+    let mut cached = cache.lock().await;
+    if let Some((cached_url, pool)) = cached.as_ref()
+        && *cached_url == url
+        && !pool.is_closed()
+    {
+        return Ok(pool.clone());
+    }
+
+    let connect_url = validate_url(&url, tenant.as_ref(), files, root)
+        .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+
+    sqlx::any::install_default_drivers();
+    let pool = AnyPoolOptions::new()
+        .max_connections(4)
+        .connect(&connect_url)
+        .await?;
+    *cached = Some((url, pool.clone()));
+    Ok(pool)
+}
+
+/// Checks a connection URL and returns the one to connect with.
+fn validate_url(
+    url: &str,
+    tenant: &str,
+    files: &FileSettings,
+    root: &Path,
+) -> Result<String, String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid database URL: {}", e))?;
+    let default_port = match parsed.scheme() {
+        "sqlite" => return sqlite_url(url, tenant, files, root),
+        "postgres" | "postgresql" => 5432,
+        "mysql" | "mariadb" => 3306,
+        other => return Err(format!("Unsupported database scheme '{}'", other)),
+    };
+    let host = parsed.host_str().ok_or("Database URL missing host")?;
+    ferroflux_security::network::validate_host_port(host, parsed.port().unwrap_or(default_port))?;
+    Ok(url.to_string())
+}
+
+/// Pins a SQLite URL to a database file inside the tenant's file directory.
+///
+/// Options are refused: they could open an in-memory database shared across connections or
+/// change how the path is interpreted.
+fn sqlite_url(
+    url: &str,
+    tenant: &str,
+    files: &FileSettings,
+    root: &Path,
+) -> Result<String, String> {
+    if !files.allow_sqlite {
+        return Err("SQLite databases are disabled".to_string());
+    }
+    let relative = url
+        .strip_prefix("sqlite://")
+        .or_else(|| url.strip_prefix("sqlite:"))
+        .unwrap_or_default();
+    if relative.contains('?') || relative.contains(":memory:") || relative.starts_with("file:") {
+        return Err("SQLite URLs may only name a database file".to_string());
+    }
+    let tenant_root = match files.tenant_roots.get(tenant) {
+        Some(dir) => PathBuf::from(dir),
+        None => sandbox::tenant_root(root, tenant)?,
+    };
+    let path = sandbox::resolve_path(&tenant_root, relative)?;
+    Ok(format!("sqlite://{}", path.display()))
+}
+
+fn bind_params(payload: &Value, params: &[String]) -> anyhow::Result<AnyArguments<'static>> {
+    let mut arguments = AnyArguments::default();
+    for path in params {
+        let value = path
+            .split('.')
+            .try_fold(payload, |current, key| current.get(key))
+            .unwrap_or(&Value::Null);
+        let bound = match value {
+            Value::Null => arguments.add(Option::<String>::None),
+            Value::Bool(b) => arguments.add(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => arguments.add(i),
+                None => arguments.add(n.as_f64().unwrap_or_default()),
+            },
+            Value::String(s) => arguments.add(s.clone()),
+            // Nested structures are bound as their JSON text.
+            other => arguments.add(other.to_string()),
+        };
+        bound.map_err(|e| anyhow::anyhow!("Failed to bind parameter '{}': {}", path, e))?;
+    }
+    Ok(arguments)
+}
+
+/// Converts a row into a JSON object keyed by column name.
+fn row_to_json(row: &AnyRow) -> anyhow::Result<Value> {
+    let mut object = serde_json::Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        // Expression columns carry no declared type, so dispatch on the value itself.
+        let kind = row.try_get_raw(i)?.type_info().kind();
+        let value = match kind {
+            AnyTypeInfoKind::Null => Value::Null,
+            AnyTypeInfoKind::Bool => json!(row.try_get::<Option<bool>, _>(i)?),
+            AnyTypeInfoKind::SmallInt | AnyTypeInfoKind::Integer | AnyTypeInfoKind::BigInt => {
+                json!(row.try_get::<Option<i64>, _>(i)?)
+            }
+            AnyTypeInfoKind::Real | AnyTypeInfoKind::Double => {
+                json!(row.try_get::<Option<f64>, _>(i)?)
+            }
+            AnyTypeInfoKind::Text => json!(row.try_get::<Option<String>, _>(i)?),
+            AnyTypeInfoKind::Blob => {
+                let bytes = row.try_get::<Option<Vec<u8>>, _>(i)?;
+                // Binary columns are emitted base64-encoded.
+                json!(bytes.map(|b| general_purpose::STANDARD.encode(b)))
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(Value::Object(object))
+}
@@ -0,0 +1,340 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{SseConfig, SseEvent, SseState, SseStreamEvent};
+use crate::components::core::{NodeConfig, Outbox};
+use crate::resources::quotas::{TenantQuotas, admit_node_run};
+use crate::resources::settings::{NetworkSettings, RuntimeSettings};
+use crate::resources::{GlobalHttpClient, TokioRuntime};
+use crate::store::BlobStore;
+use crate::systems::io::auth::connection_auth_headers;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+/// Delay before retrying a stream that could not even be set up (bad URL, blocked host).
+const SETUP_RETRY: Duration = Duration::from_secs(5);
+/// Reconnect delay until the server suggests one with `retry:`.
+const DEFAULT_RETRY: Duration = Duration::from_secs(1);
+
+/// Incremental `text/event-stream` parser.
+///
+/// Feed it raw chunks as they arrive; complete events are returned once their terminating
+/// blank line has been seen.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: String,
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    /// Starts a parser that resumes from a previously seen event ID.
+    pub fn resume(last_event_id: Option<String>) -> Self {
+        Self {
+            last_event_id,
+            ..Self::default()
+        }
+    }
+
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Reconnect delay requested by the server via `retry:`.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            if self.data.is_empty() {
+                return None;
+            }
+            let mut data = std::mem::take(&mut self.data);
+            data.pop(); // trailing newline added per data line
+            return Some(SseEvent {
+                event: if event.is_empty() {
+                    "message".to_string()
+                } else {
+                    event
+                },
+                data,
+                id: self.last_event_id.clone(),
+            });
+        }
+        if line.starts_with(':') {
+            return None; // comment / keep-alive
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// System: SSE Subscriber
+///
+/// Each node owns a background task that holds the stream open and reconnects with
+/// `Last-Event-ID`. Events become tickets with a fresh trace id; connection changes are
+/// reported as `NodeTelemetry` with a `status` of `connected` or `disconnected`. Events
+/// refused by the tenant's quotas are dropped.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    http_client,
+    secret_store,
+    event_bus,
+    runtime,
+    settings,
+    quotas
+))]
+pub fn sse_worker(
+    mut query: Query<(&SseConfig, &NodeConfig, &mut SseState, &mut Outbox)>,
+    store: Res<BlobStore>,
+    http_client: Res<GlobalHttpClient>,
+    secret_store: Res<crate::secrets::DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    runtime: Res<TokioRuntime>,
+    settings: Option<Res<RuntimeSettings>>,
+    quotas: Option<Res<TenantQuotas>>,
+) {
+    let settings = RuntimeSettings::effective(settings.as_deref());
+    for (config, node_config, mut state, mut outbox) in query.iter_mut() {
+        let node_id = node_config.id;
+        let status = |success: bool, details: Value| {
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                node_id,
+                node_type: "SSE".into(),
+                trace_id: "system".into(),
+                execution_ms: 0,
+                success,
+                details,
+            });
+        };
+
+        if state.events.as_ref().is_some_and(|rx| rx.is_closed()) {
+            state.events = None;
+        }
+
+        // 1. Start the stream task
+        if state.events.is_none()
+            && state
+                .last_connect_attempt
+                .is_none_or(|last| last.elapsed() >= SETUP_RETRY)
+        {
+            state.last_connect_attempt = Some(Instant::now());
+            match resolve_request(
+                config,
+                node_config,
+                &settings.network,
+                &secret_store,
+                &runtime,
+            ) {
+                Ok((url, headers)) => {
+                    let (tx, rx) = async_channel::bounded(1024);
+                    runtime.0.spawn(run_stream(
+                        http_client.client.clone(),
+                        url,
+                        headers,
+                        state.last_event_id.clone(),
+                        Duration::from_secs(config.max_backoff_seconds.max(1)),
+                        tx,
+                    ));
+                    state.events = Some(rx);
+                }
+                Err(e) => status(
+                    false,
+                    json!({ "status": "disconnected", "error": e.to_string() }),
+                ),
+            }
+        }
+
+        // 2. Drain events into tickets
+        let Some(rx) = state.events.clone() else {
+            continue;
+        };
+        while let Ok(message) = rx.try_recv() {
+            let event = match message {
+                SseStreamEvent::Connected => {
+                    status(true, json!({ "status": "connected" }));
+                    continue;
+                }
+                SseStreamEvent::Disconnected(reason) => {
+                    status(false, json!({ "status": "disconnected", "error": reason }));
+                    continue;
+                }
+                SseStreamEvent::Event(event) => event,
+            };
+            if event.id.is_some() {
+                state.last_event_id = event.id.clone();
+            }
+            if !config.events.is_empty() && !config.events.contains(&event.event) {
+                continue;
+            }
+
+            let payload = json!({
+                "event": event.event,
+                "data": serde_json::from_str::<Value>(&event.data)
+                    .unwrap_or(Value::String(event.data.clone())),
+                "id": event.id,
+            });
+            let Ok(bytes) = serde_json::to_vec(&payload) else {
+                continue;
+            };
+            if admit_node_run(
+                quotas.as_deref(),
+                Some(&*event_bus),
+                node_config,
+                bytes.len(),
+            )
+            .is_err()
+            {
+                continue;
+            }
+            match store.check_in(&bytes) {
+                Ok(mut ticket) => {
+                    ticket
+                        .metadata
+                        .insert("trace_id".into(), uuid::Uuid::new_v4().to_string());
+                    outbox.queue.push_back((None, ticket));
+                }
+                Err(e) => tracing::error!(%node_id, error = %e, "Failed to store SSE event"),
+            }
+        }
+    }
+}
+
+/// Streams events until the node is removed (the receiver is dropped).
+async fn run_stream(
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    last_event_id: Option<String>,
+    max_backoff: Duration,
+    tx: async_channel::Sender<SseStreamEvent>,
+) {
+    let mut parser = SseParser::resume(last_event_id);
+    let mut backoff = DEFAULT_RETRY;
+    loop {
+        let mut request = client
+            .get(&url)
+            .header("Accept", "text/event-stream")
+            .header("Cache-Control", "no-cache");
+        for (name, value) in &headers {
+            request = request.header(name, value);
+        }
+        if let Some(id) = parser.last_event_id() {
+            request = request.header("Last-Event-ID", id);
+        }
+
+        let reason = match request.send().await {
+            Ok(mut response) if response.status().is_success() => {
+                backoff = parser.retry().unwrap_or(DEFAULT_RETRY);
+                if tx.send(SseStreamEvent::Connected).await.is_err() {
+                    return;
+                }
+                // A partial event from the previous connection is discarded.
+                parser = SseParser {
+                    retry: parser.retry,
+                    ..SseParser::resume(parser.last_event_id)
+                };
+                loop {
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => {
+                            for event in parser.feed(&chunk) {
+                                if tx.send(SseStreamEvent::Event(event)).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        Ok(None) => break "Stream ended".to_string(),
+                        Err(e) => break e.to_string(),
+                    }
+                }
+            }
+            Ok(response) => format!("HTTP {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+
+        if tx.send(SseStreamEvent::Disconnected(reason)).await.is_err() {
+            return;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+/// Resolves the stream URL and auth headers, applying the connection if one is configured.
+fn resolve_request(
+    config: &SseConfig,
+    node_config: &NodeConfig,
+    network: &NetworkSettings,
+    secret_store: &crate::secrets::DatabaseSecretStore,
+    runtime: &TokioRuntime,
+) -> anyhow::Result<(String, Vec<(String, String)>)> {
+    use crate::secrets::SecretStore;
+
+    let mut url = config.url.clone();
+    let mut headers = Vec::new();
+    if let Some(slug) = config.connection_slug.clone() {
+        let tenant = node_config
+            .tenant_id
+            .clone()
+            .unwrap_or_else(|| TenantId::from("default_tenant"));
+        let ss = secret_store.clone();
+        let rt = runtime.clone();
+        let connection = tokio::task::block_in_place(move || {
+            rt.0.block_on(async move { ss.resolve_connection(&tenant, &slug).await })
+        })?;
+        if let Some(base) = connection.get("base_url").and_then(|v| v.as_str()) {
+            let path = url.trim_start_matches('/');
+            url = if path.is_empty() {
+                base.trim_end_matches('/').to_string()
+            } else {
+                format!("{}/{}", base.trim_end_matches('/'), path)
+            };
+        }
+        headers = connection_auth_headers(&connection);
+    }
+
+    network
+        .validate_url(&url)
+        .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+    Ok((url, headers))
+}
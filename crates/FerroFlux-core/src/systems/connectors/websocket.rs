@@ -0,0 +1,282 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{WebSocketConfig, WebSocketEvent, WebSocketState};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::TokioRuntime;
+use crate::resources::quotas::{TenantQuotas, admit_node_run};
+use crate::store::BlobStore;
+use base64::{Engine as _, engine::general_purpose};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Delay before retrying a connection that could not even be set up (bad URL, blocked host).
+const SETUP_RETRY: Duration = Duration::from_secs(5);
+/// Frames buffered for sending while the socket is down.
+const OUTGOING_CAPACITY: usize = 256;
+
+/// System: WebSocket Client
+///
+/// Each node owns a background task that keeps the socket open and reconnects with
+/// exponential backoff. Status changes are reported as `NodeTelemetry` with a `status` of
+/// `connected` or `disconnected`. Inbox tickets are forwarded unchanged once their frame is
+/// queued; a full send buffer routes them to `error`. Inbound frames refused by the tenant's
+/// quotas are dropped.
+#[allow(clippy::type_complexity)]
+#[tracing::instrument(skip(query, store, secret_store, event_bus, runtime, quotas))]
+pub fn websocket_worker(
+    mut query: Query<(
+        &WebSocketConfig,
+        &NodeConfig,
+        &mut WebSocketState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    secret_store: Res<crate::secrets::DatabaseSecretStore>,
+    event_bus: Res<SystemEventBus>,
+    runtime: Res<TokioRuntime>,
+    quotas: Option<Res<TenantQuotas>>,
+) {
+    for (config, node_config, mut state, mut inbox, mut outbox) in query.iter_mut() {
+        let node_id = node_config.id;
+        let status = |success: bool, details: Value| {
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                node_id,
+                node_type: "WebSocket".into(),
+                trace_id: "system".into(),
+                execution_ms: 0,
+                success,
+                details,
+            });
+        };
+
+        // 1. Start the connection task
+        if state.events.is_none()
+            && state
+                .last_connect_attempt
+                .is_none_or(|last| last.elapsed() >= SETUP_RETRY)
+        {
+            state.last_connect_attempt = Some(Instant::now());
+            match resolve_url(config, node_config, &secret_store, &runtime).and_then(|url| {
+                validate_url(&url)
+                    .map_err(|e| anyhow::anyhow!("Security Validation Failed: {}", e))?;
+                Ok(url)
+            }) {
+                Ok(url) => {
+                    let (events_tx, events_rx) = async_channel::bounded(1024);
+                    let (outgoing_tx, outgoing_rx) = async_channel::bounded(OUTGOING_CAPACITY);
+                    let max_backoff = Duration::from_secs(config.max_backoff_seconds.max(1));
+                    runtime
+                        .0
+                        .spawn(run_connection(url, max_backoff, events_tx, outgoing_rx));
+                    state.events = Some(events_rx);
+                    state.outgoing = Some(outgoing_tx);
+                }
+                Err(e) => status(
+                    false,
+                    json!({ "status": "disconnected", "error": e.to_string() }),
+                ),
+            }
+        }
+
+        // 2. Inbound frames and status changes
+        if let Some(events) = state.events.as_ref() {
+            while let Ok(event) = events.try_recv() {
+                let data = match event {
+                    WebSocketEvent::Connected => {
+                        status(true, json!({ "status": "connected" }));
+                        continue;
+                    }
+                    WebSocketEvent::Disconnected(reason) => {
+                        status(false, json!({ "status": "disconnected", "error": reason }));
+                        continue;
+                    }
+                    WebSocketEvent::Text(text) => json!({
+                        "data": serde_json::from_str::<Value>(&text).unwrap_or(Value::String(text)),
+                        "binary": false,
+                    }),
+                    WebSocketEvent::Binary(bytes) => json!({
+                        "data": general_purpose::STANDARD.encode(bytes),
+                        "binary": true,
+                    }),
+                };
+                let Ok(bytes) = serde_json::to_vec(&data) else {
+                    continue;
+                };
+                if admit_node_run(
+                    quotas.as_deref(),
+                    Some(&*event_bus),
+                    node_config,
+                    bytes.len(),
+                )
+                .is_err()
+                {
+                    continue;
+                }
+                match store.check_in(&bytes) {
+                    Ok(mut ticket) => {
+                        ticket
+                            .metadata
+                            .insert("trace_id".into(), uuid::Uuid::new_v4().to_string());
+                        outbox.queue.push_back((None, ticket));
+                    }
+                    Err(e) => {
+                        tracing::error!(%node_id, error = %e, "Failed to store WebSocket frame")
+                    }
+                }
+            }
+        }
+
+        // 3. Outbound frames
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let start = Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<()> {
+                let data = store.claim(&ticket)?;
+                // String payloads go out verbatim, anything else as JSON text.
+                let text = match serde_json::from_slice::<Value>(&data) {
+                    Ok(Value::String(s)) => s,
+                    Ok(other) => other.to_string(),
+                    Err(_) => String::from_utf8_lossy(&data).into_owned(),
+                };
+                let outgoing = state
+                    .outgoing
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("WebSocket is not connected"))?;
+                outgoing
+                    .try_send(text)
+                    .map_err(|e| anyhow::anyhow!("WebSocket send buffer: {}", e))
+            })();
+
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id,
+                node_type: "WebSocket".into(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok(_) => json!({ "message": "Frame queued" }),
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            match result {
+                Ok(_) => outbox.queue.push_back((None, ticket)),
+                Err(e) => {
+                    tracing::error!(%node_id, error = %e, "WebSocket send failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
+
+/// Keeps a socket open until the node is removed (its channels are dropped).
+async fn run_connection(
+    url: String,
+    max_backoff: Duration,
+    events: async_channel::Sender<WebSocketEvent>,
+    outgoing: async_channel::Receiver<String>,
+) {
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let reason = match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((stream, _)) => {
+                backoff = Duration::from_secs(1);
+                if events.send(WebSocketEvent::Connected).await.is_err() {
+                    return;
+                }
+                let (mut sink, mut source) = stream.split();
+                loop {
+                    tokio::select! {
+                        frame = source.next() => match frame {
+                            Some(Ok(Message::Text(text))) => {
+                                if events.send(WebSocketEvent::Text(text)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Some(Ok(Message::Binary(bytes))) => {
+                                if events.send(WebSocketEvent::Binary(bytes)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            // Pings are answered by tungstenite itself.
+                            Some(Ok(Message::Close(_))) | None => break "Closed by server".to_string(),
+                            Some(Ok(_)) => {}
+                            Some(Err(e)) => break e.to_string(),
+                        },
+                        text = outgoing.recv() => match text {
+                            Ok(text) => {
+                                if let Err(e) = sink.send(Message::Text(text)).await {
+                                    break e.to_string();
+                                }
+                            }
+                            Err(_) => {
+                                // The node was removed.
+                                let _ = sink.close().await;
+                                return;
+                            }
+                        },
+                    }
+                }
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if events
+            .send(WebSocketEvent::Disconnected(reason))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+fn resolve_url(
+    config: &WebSocketConfig,
+    node_config: &NodeConfig,
+    secret_store: &crate::secrets::DatabaseSecretStore,
+    runtime: &TokioRuntime,
+) -> anyhow::Result<String> {
+    use crate::secrets::SecretStore;
+
+    let Some(slug) = config.connection_slug.clone() else {
+        return Ok(config.url.clone());
+    };
+    let tenant = node_config
+        .tenant_id
+        .clone()
+        .unwrap_or_else(|| TenantId::from("default_tenant"));
+    let ss = secret_store.clone();
+    let rt = runtime.clone();
+    let connection = tokio::task::block_in_place(move || {
+        rt.0.block_on(async move { ss.resolve_connection(&tenant, &slug).await })
+    })?;
+    Ok(connection
+        .get("url")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&config.url)
+        .to_string())
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid WebSocket URL: {}", e))?;
+    if !matches!(parsed.scheme(), "ws" | "wss") {
+        return Err(format!(
+            "Unsupported WebSocket scheme '{}'",
+            parsed.scheme()
+        ));
+    }
+    let host = parsed.host_str().ok_or("WebSocket URL missing host")?;
+    ferroflux_security::network::validate_host_port(
+        host,
+        parsed.port_or_known_default().unwrap_or(443),
+    )
+}
@@ -1,21 +1,26 @@
 use crate::api::events::{SystemEvent, SystemEventBus};
-use crate::components::control::CheckpointConfig;
-use crate::components::core::{Inbox, NodeConfig};
+use crate::components::control::{
+    CheckpointConfig, CounterConfig, CounterOperation, OverflowPolicy, RateLimitConfig,
+    RateLimitState,
+};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::resources::{TokioRuntime, WorkDone};
 use ferroflux_iam::TenantId;
 use crate::store::BlobStore;
 use crate::store::database::PersistentStore;
 use bevy_ecs::prelude::*;
-use serde_json::json;
+use serde_json::{Value, json};
 use uuid::Uuid;
 
-#[tracing::instrument(skip(query, store, db, event_bus))]
+#[tracing::instrument(skip(query, store, db, event_bus, runtime))]
 pub fn checkpoint_worker(
     mut query: Query<(&CheckpointConfig, &NodeConfig, &mut Inbox)>,
     store: Res<BlobStore>,
     db: Res<PersistentStore>,
     event_bus: Res<SystemEventBus>,
+    runtime: Res<TokioRuntime>,
 ) {
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     for (_config, node_config, mut inbox) in query.iter_mut() {
         while let Some(ticket) = inbox.queue.pop_front() {
@@ -36,7 +41,8 @@ pub fn checkpoint_worker(
             // Generate Token
             let token = Uuid::new_v4().to_string();
 
-            // Spawn tokio task.
+            // Spawn tokio task. Through the handle, as the parallel executor runs systems on
+            // compute-pool threads that have no Tokio context.
             let db_clone = db.clone();
             let event_tx_clone = event_tx.clone();
             let node_id_clone = node_config.id;
@@ -52,7 +58,7 @@ pub fn checkpoint_worker(
                 .clone()
                 .unwrap_or_else(|| TenantId::from("default_tenant"));
 
-            tokio::spawn(async move {
+            runtime.0.spawn(async move {
                 let span = tracing::info_span!("checkpoint_save", node_id = %node_id_clone, trace_id = %trace_id_clone_1);
                 let _enter = span.enter();
 
@@ -98,3 +104,176 @@ pub fn checkpoint_worker(
         }
     }
 }
+
+/// Applies a counter operation. Returns the resulting value and whether it exceeds the limit.
+///
+/// Shared by the Counter node and the `counter` tool.
+pub async fn apply_counter(
+    db: &PersistentStore,
+    tenant: &TenantId,
+    config: &CounterConfig,
+    name: &str,
+) -> anyhow::Result<(i64, bool)> {
+    let value = match config.operation {
+        CounterOperation::Increment => {
+            db.increment_counter(tenant, name, config.amount, config.window_seconds)
+                .await?
+        }
+        CounterOperation::Get => db.get_counter(tenant, name).await?,
+        CounterOperation::Reset => {
+            db.reset_counter(tenant, name).await?;
+            0
+        }
+    };
+    Ok((value, config.limit.is_some_and(|limit| value > limit)))
+}
+
+/// System: Shared Counter
+///
+/// Passes tickets through, or out of `limit_exceeded` once the counter is over its limit.
+#[tracing::instrument(skip(query, store, db, runtime))]
+pub fn counter_worker(
+    mut query: Query<(&CounterConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    db: Res<PersistentStore>,
+    runtime: Res<TokioRuntime>,
+) {
+    for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let payload = match store.claim(&ticket) {
+                Ok(bytes) => serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null),
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "Failed to claim ticket for counter");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                    continue;
+                }
+            };
+
+            let name = crate::systems::io::templating::apply_template(&config.name, &payload);
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let db = db.clone();
+            let rt = runtime.clone();
+            let counter_config = config.clone();
+            let result = tokio::task::block_in_place(move || {
+                rt.0.block_on(
+                    async move { apply_counter(&db, &tenant, &counter_config, &name).await },
+                )
+            });
+
+            let (value, exceeded) = match result {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "Counter update failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                    continue;
+                }
+            };
+
+            let port = exceeded.then(|| "limit_exceeded".to_string());
+            let out = match &config.result_key {
+                Some(key) => {
+                    let mut enriched = payload;
+                    if let Some(obj) = enriched.as_object_mut() {
+                        obj.insert(key.clone(), json!({ "value": value, "exceeded": exceeded }));
+                    }
+                    serde_json::to_vec(&enriched)
+                        .ok()
+                        .and_then(|bytes| {
+                            store
+                                .check_in_with_metadata(&bytes, ticket.metadata.clone())
+                                .ok()
+                        })
+                        .unwrap_or(ticket)
+                }
+                None => ticket,
+            };
+            outbox.queue.push_back((port, out));
+        }
+    }
+}
+
+/// System: Rate Limiter
+///
+/// Meters tickets through a token bucket. The bucket starts full and refills continuously at
+/// `max_per_interval / interval_ms`; queued tickets are released in arrival order while tokens last.
+#[tracing::instrument(skip(query, event_bus, work_done))]
+pub fn rate_limit_worker(
+    mut query: Query<(
+        &RateLimitConfig,
+        &NodeConfig,
+        &mut RateLimitState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+) {
+    let event_tx = event_bus.clone();
+    let now = std::time::Instant::now();
+
+    for (config, node_config, mut state, mut inbox, mut outbox) in query.iter_mut() {
+        let burst = config.max_per_interval as f64;
+
+        // 1. Refill
+        match state.last_refill {
+            Some(last) => {
+                let rate = burst / config.interval_ms.max(1) as f64;
+                let refill = now.duration_since(last).as_secs_f64() * 1000.0 * rate;
+                state.tokens = (state.tokens + refill).min(burst);
+            }
+            None => state.tokens = burst,
+        }
+        state.last_refill = Some(now);
+
+        // 2. Enqueue, applying the overflow policy once the queue is full
+        while !inbox.queue.is_empty() {
+            if state.queue.len() < config.queue_capacity {
+                if let Some(ticket) = inbox.queue.pop_front() {
+                    state.queue.push_back(ticket);
+                }
+                continue;
+            }
+            if config.overflow == OverflowPolicy::Backpressure {
+                break;
+            }
+            let Some(ticket) = inbox.queue.pop_front() else {
+                break;
+            };
+            work_done.mark();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+            tracing::warn!(node_id = %node_config.id, policy = ?config.overflow, "Rate limiter queue full");
+            if config.overflow == OverflowPolicy::DeadLetter {
+                outbox.queue.push_back((Some("dead_letter".into()), ticket));
+            }
+            let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: "RateLimit".to_string(),
+                execution_ms: 0,
+                success: false,
+                details: json!({ "overflow": config.overflow }),
+            });
+        }
+
+        // 3. Release while tokens last
+        while state.tokens >= 1.0 {
+            let Some(ticket) = state.queue.pop_front() else {
+                break;
+            };
+            state.tokens -= 1.0;
+            work_done.mark();
+            let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                trace_id: ticket.metadata.get("trace_id").cloned().unwrap_or_default(),
+                node_id: node_config.id,
+                node_type: "RateLimit".to_string(),
+                execution_ms: 0,
+                success: true,
+                details: json!({ "queued": state.queue.len() }),
+            });
+            outbox.queue.push_back((None, ticket));
+        }
+    }
+}
@@ -24,15 +24,17 @@ pub async fn execute_integration_action(
 ) -> Result<String, String> {
     // 1. Load Connection
     let conn = store
-        .get_connection_by_slug(tenant, slug)
+        .get_connection_secret(tenant, slug)
         .await
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "Connection not found".to_string())?;
 
-    let (provider_type, data, nonce, _, _) = conn;
+    let (provider_type, secret) = conn;
 
     // 2. Decrypt
-    let plaintext = ferroflux_security::encryption::decrypt(&data, master_key, &nonce)
+    let plaintext = crate::secrets::TenantKeyring::new(store.clone(), master_key.to_vec())
+        .open(tenant, &secret)
+        .await
         .map_err(|e| format!("Decryption Failed: {}", e))?;
 
     let connection_fields: Value =
@@ -198,6 +200,7 @@ mod tests {
             documentation: None,
             message_transform: None,
             output_transform: None,
+            stream: None,
             implementation: ActionImplementation {
                 impl_type: "http".to_string(),
                 config: IntegrationConfig {
@@ -205,6 +208,7 @@ mod tests {
                     path: "/test".to_string(),
                     headers: HashMap::new(),
                     body_template: None,
+                    pagination: None,
                 },
             },
         };
@@ -284,6 +288,7 @@ mod tests {
             documentation: None,
             message_transform: None,
             output_transform: None,
+            stream: None,
             implementation: ActionImplementation {
                 impl_type: "http".to_string(),
                 config: IntegrationConfig {
@@ -291,6 +296,7 @@ mod tests {
                     path: "/test".to_string(),
                     headers: HashMap::new(),
                     body_template: None,
+                    pagination: None,
                 },
             },
         };
@@ -1,5 +1,7 @@
-use crate::components::{Outbox, WorkDone};
-use crate::store::SecureTicket;
+use crate::api::events::SystemEventBus;
+use crate::components::{NodeConfig, Outbox, WorkDone};
+use crate::resources::quotas::{TenantQuotas, admit_node_run};
+use crate::store::{BlobStore, SecureTicket};
 use async_channel::{Receiver, Sender};
 use bevy_ecs::prelude::*;
 use once_cell::sync::OnceCell;
@@ -12,11 +14,16 @@ pub static WEBHOOK_QUEUE: OnceCell<(Sender<(Uuid, SecureTicket)>, Receiver<(Uuid
 
 // Note: run_webhook_server has been moved to the App crate to keep Core headless.
 
-#[tracing::instrument(skip(outbox_query, node_router, work_done))]
+/// Routes queued webhook requests to their nodes. Each request starts a run, so it is
+/// admitted through the tenant's quotas first; a refused request's payload is released.
+#[tracing::instrument(skip(outbox_query, node_router, work_done, store, quotas, event_bus))]
 pub fn ingest_webhooks(
-    mut outbox_query: Query<&mut Outbox>,
+    mut outbox_query: Query<(&mut Outbox, Option<&NodeConfig>)>,
     node_router: Res<crate::resources::NodeRouter>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
+    store: Res<BlobStore>,
+    quotas: Option<Res<TenantQuotas>>,
+    event_bus: Option<Res<SystemEventBus>>,
 ) {
     let queue = match WEBHOOK_QUEUE.get() {
         Some((_, rx)) => rx,
@@ -25,10 +32,18 @@ pub fn ingest_webhooks(
     while let Ok((node_id, ticket)) = queue.try_recv() {
         // O(1) Lookup
         if let Some(&entity) = node_router.0.get(&node_id) {
-            if let Ok(mut outbox) = outbox_query.get_mut(entity) {
+            if let Ok((mut outbox, node)) = outbox_query.get_mut(entity) {
+                if let Some(node) = node {
+                    let size = store.size(&ticket).unwrap_or(0);
+                    if admit_node_run(quotas.as_deref(), event_bus.as_deref(), node, size).is_err()
+                    {
+                        let _ = store.release(&ticket);
+                        continue;
+                    }
+                }
                 tracing::info!(webhook_id = %node_id, entity = ?entity, "Routing Webhook to Node");
                 outbox.queue.push_back((None, ticket.clone()));
-                work_done.0 = true;
+                work_done.mark();
             } else {
                 tracing::warn!(entity = ?entity, "Found Node in Router, but missing Outbox component");
             }
@@ -1,5 +1,15 @@
 pub mod auth;
+pub mod browser;
+pub mod embedding;
+pub mod file;
 pub mod http;
+pub mod speech;
 pub mod templating;
+pub mod vector_search;
 
+pub use self::browser::browser_worker;
+pub use self::embedding::embedding_worker;
+pub use self::file::file_worker;
 pub use self::http::http_worker;
+pub use self::speech::speech_worker;
+pub use self::vector_search::vector_search_worker;
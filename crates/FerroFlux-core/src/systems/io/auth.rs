@@ -1,5 +1,6 @@
 use crate::components::AuthConfig;
 use base64::{Engine as _, engine::general_purpose};
+use serde_json::Value;
 use std::env;
 
 pub fn resolve_auth_headers(auth_config: &AuthConfig) -> Vec<(String, String)> {
@@ -35,6 +36,42 @@ pub fn resolve_auth_headers(auth_config: &AuthConfig) -> Vec<(String, String)> {
     headers
 }
 
+/// Headers implied by a resolved HTTP connection (`auth_type`, `credentials`,
+/// `auth_scheme` and `custom_headers`).
+pub fn connection_auth_headers(conn_data: &Value) -> Vec<(String, String)> {
+    let mut headers = Vec::new();
+    let credentials = conn_data.get("credentials").and_then(|v| v.as_str());
+    match (
+        conn_data.get("auth_type").and_then(|v| v.as_str()),
+        credentials,
+    ) {
+        (Some("Bearer"), Some(cred)) => {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", cred)));
+        }
+        (Some("Basic"), Some(cred)) => {
+            let encoded = general_purpose::STANDARD.encode(cred);
+            headers.push(("Authorization".to_string(), format!("Basic {}", encoded)));
+        }
+        (Some("Custom Scheme"), Some(cred)) => {
+            let scheme = conn_data
+                .get("auth_scheme")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Bearer");
+            headers.push(("Authorization".to_string(), format!("{} {}", scheme, cred)));
+        }
+        _ => {}
+    }
+
+    if let Some(custom) = conn_data.get("custom_headers").and_then(|v| v.as_object()) {
+        for (k, v) in custom {
+            if let Some(val_str) = v.as_str() {
+                headers.push((k.clone(), val_str.to_string()));
+            }
+        }
+    }
+    headers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,258 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::{BrowserConfig, BrowserStep};
+use crate::resources::browser::{BrowserBackend, BrowserPage, HeadlessBrowser};
+use crate::resources::settings::{NetworkSettings, RuntimeSettings};
+use crate::resources::{TokioRuntime, WorkDone};
+use crate::store::BlobStore;
+use crate::systems::io::templating::apply_template;
+use bevy_ecs::prelude::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often `wait_for_selector` looks for its selector.
+const SELECTOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// System: Browser Worker
+///
+/// Runs the node's script on a fresh page of the `HeadlessBrowser` backend for each ticket,
+/// as a background task bounded by `timeout_ms`. Each page load, including where redirects
+/// land, must pass `network` policy. Extracted values are merged into the payload under
+/// `result_key`; failures and timeouts route the original ticket to `error`.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[tracing::instrument(skip(query, store, browser, runtime, event_bus, work_done, settings))]
+pub fn browser_worker(
+    mut query: Query<(Entity, &BrowserConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    browser: Res<HeadlessBrowser>,
+    runtime: Res<TokioRuntime>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+    settings: Option<Res<RuntimeSettings>>,
+) {
+    // 1. Collect finished scripts
+    while let Ok((entity, ticket, result)) = browser.rx.try_recv() {
+        let Ok((_, config, node_config, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|extracted| {
+            let mut payload = match store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            {
+                Some(Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            payload.insert(config.result_key.clone(), extracted);
+            store.check_in_with_metadata(&serde_json::to_vec(&payload)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Browser script failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new scripts
+    let network = RuntimeSettings::effective(settings.as_deref())
+        .network
+        .clone();
+    for (entity, config, node_config, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let Some(backend) = browser.backend.clone() else {
+                let _ = browser.tx.try_send((
+                    entity,
+                    ticket,
+                    Err(anyhow::anyhow!(
+                        "Browser node needs an engine built with the `browser` feature"
+                    )),
+                ));
+                continue;
+            };
+
+            let payload = store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+                .unwrap_or(Value::Null);
+            let url = apply_template(&config.url, &payload);
+            let steps: Vec<BrowserStep> = config
+                .steps
+                .iter()
+                .map(|step| match step {
+                    BrowserStep::Navigate { url } => BrowserStep::Navigate {
+                        url: apply_template(url, &payload),
+                    },
+                    other => other.clone(),
+                })
+                .collect();
+
+            let timeout = Duration::from_millis(config.timeout_ms);
+            let store = store.clone();
+            let network = network.clone();
+            let tx = browser.tx.clone();
+            let event_tx = event_bus.clone();
+            let node_id = node_config.id;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let script = run_script(backend, &store, &network, &url, steps, start + timeout);
+                let result = tokio::time::timeout(timeout, script)
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(anyhow::anyhow!(
+                            "Browser script timed out after {} ms",
+                            timeout.as_millis()
+                        ))
+                    });
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "Browser".to_string(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(_) => json!({ "url": url }),
+                        Err(e) => json!({ "url": url, "error": e.to_string() }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+async fn run_script(
+    backend: Arc<dyn BrowserBackend>,
+    store: &BlobStore,
+    network: &NetworkSettings,
+    url: &str,
+    steps: Vec<BrowserStep>,
+    deadline: Instant,
+) -> anyhow::Result<Value> {
+    let mut page = backend.open().await?;
+    let result = run_steps(page.as_mut(), store, network, url, steps, deadline).await;
+    if let Err(e) = page.close().await {
+        tracing::warn!(error = %e, "Failed to close browser page");
+    }
+    result
+}
+
+async fn run_steps(
+    page: &mut dyn BrowserPage,
+    store: &BlobStore,
+    network: &NetworkSettings,
+    url: &str,
+    steps: Vec<BrowserStep>,
+    deadline: Instant,
+) -> anyhow::Result<Value> {
+    let mut output = serde_json::Map::new();
+    navigate(page, network, url).await?;
+
+    for step in steps {
+        match step {
+            BrowserStep::Navigate { url } => navigate(page, network, &url).await?,
+            BrowserStep::WaitForSelector {
+                selector,
+                timeout_ms,
+            } => {
+                let until = timeout_ms
+                    .map(|ms| (Instant::now() + Duration::from_millis(ms)).min(deadline))
+                    .unwrap_or(deadline);
+                while !page.exists(&selector).await? {
+                    if Instant::now() >= until {
+                        anyhow::bail!("Timed out waiting for '{}'", selector);
+                    }
+                    tokio::time::sleep(SELECTOR_POLL_INTERVAL).await;
+                }
+            }
+            BrowserStep::ExtractText { selector, key, all } => {
+                let texts = page.texts(&selector).await?;
+                output.insert(key, pick(texts.into_iter().map(Value::String), all));
+            }
+            BrowserStep::ExtractAttribute {
+                selector,
+                attribute,
+                key,
+                all,
+            } => {
+                // The name ends up inside a script evaluated in the page.
+                if attribute.is_empty()
+                    || !attribute
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+                {
+                    anyhow::bail!("Invalid attribute name '{}'", attribute);
+                }
+                let values = page.attributes(&selector, &attribute).await?;
+                let values = values
+                    .into_iter()
+                    .map(|value| value.map(Value::String).unwrap_or(Value::Null));
+                output.insert(key, pick(values, all));
+            }
+            BrowserStep::Screenshot { key, full_page } => {
+                let png = page.screenshot(full_page).await?;
+                let metadata =
+                    HashMap::from([("content_type".to_string(), "image/png".to_string())]);
+                let ticket = store.check_in_with_metadata(&png, metadata)?;
+                output.insert(
+                    key,
+                    json!({ "blob_id": ticket.id, "bytes": png.len(), "content_type": "image/png" }),
+                );
+            }
+        }
+    }
+
+    if let Some(current) = page.current_url().await? {
+        output.insert("url".to_string(), Value::String(current));
+    }
+    Ok(Value::Object(output))
+}
+
+/// Loads `url` if the network policy allows it, and checks where the page ended up.
+async fn navigate(
+    page: &mut dyn BrowserPage,
+    network: &NetworkSettings,
+    url: &str,
+) -> anyhow::Result<()> {
+    check_url(network, url).await?;
+    page.goto(url).await?;
+    if let Some(landed) = page.current_url().await?
+        && landed != url
+    {
+        check_url(network, &landed)
+            .await
+            .map_err(|e| anyhow::anyhow!("Redirect to {} refused: {}", landed, e))?;
+    }
+    Ok(())
+}
+
+async fn check_url(network: &NetworkSettings, url: &str) -> anyhow::Result<()> {
+    let parsed = url::Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        anyhow::bail!("Only http and https pages can be loaded, not '{}'", url);
+    }
+    let network = network.clone();
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || network.validate_url(&url))
+        .await?
+        .map_err(anyhow::Error::msg)
+}
+
+fn pick(mut values: impl Iterator<Item = Value>, all: bool) -> Value {
+    if all {
+        Value::Array(values.collect())
+    } else {
+        values.next().unwrap_or(Value::Null)
+    }
+}
@@ -0,0 +1,314 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::EmbeddingConfig;
+use crate::integrations::{AuthDef, IntegrationRegistry};
+use crate::resources::{
+    EmbeddingResultChannel, GlobalHttpClient, TokioRuntime, VectorIndex, WorkDone,
+};
+use crate::secrets::{DatabaseSecretStore, EnvSecretStore, SecretStore};
+use crate::store::BlobStore;
+use crate::store::vector::{VectorRecord, VectorStore};
+use crate::systems::io::speech::{authorize, credentials};
+use crate::systems::io::templating::apply_template;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The integration action an Embedding node calls.
+const EMBEDDINGS_ACTION: &str = "embeddings";
+
+/// System: Embedding Worker
+///
+/// Sends each ticket's text to the node's provider as a background request and places the
+/// returned vector under `result_key`. Nodes with a `collection` also upsert the vector, with
+/// the incoming payload as metadata, into the [`VectorIndex`].
+///
+/// Failures, including a missing vector store for an indexing node, route the original ticket
+/// to `error`.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    registry,
+    client,
+    channel,
+    index,
+    secret_store,
+    runtime,
+    event_bus,
+    work_done
+))]
+pub fn embedding_worker(
+    mut query: Query<(
+        Entity,
+        &EmbeddingConfig,
+        &NodeConfig,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    registry: Res<IntegrationRegistry>,
+    client: Res<GlobalHttpClient>,
+    channel: Res<EmbeddingResultChannel>,
+    index: Option<Res<VectorIndex>>,
+    secret_store: Option<Res<DatabaseSecretStore>>,
+    runtime: Res<TokioRuntime>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished calls
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, config, node_config, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|vector| {
+            let mut payload = match store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            {
+                Some(Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            payload.insert(config.result_key.clone(), vector);
+            store.check_in_with_metadata(&serde_json::to_vec(&payload)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Embedding failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new calls
+    let secrets: Arc<dyn SecretStore> = match &secret_store {
+        Some(store) => Arc::new(store.as_ref().clone()),
+        None => Arc::new(EnvSecretStore),
+    };
+    for (entity, config, node_config, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let payload = store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+                .unwrap_or(Value::Null);
+            let call = match prepare(config, &registry, &payload, index.as_deref()) {
+                Ok(call) => call,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let client = client.client.clone();
+            let secrets = secrets.clone();
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let node_id = node_config.id;
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let slug = config.connection_slug.clone();
+            let timeout = Duration::from_millis(config.timeout_ms);
+            let provider = config.provider.clone();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let result = async {
+                    let credentials =
+                        credentials(secrets.as_ref(), &tenant, slug.as_deref(), &call.key_name)
+                            .await?;
+                    call.send(&client, &tenant, credentials, timeout).await
+                }
+                .await;
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "Embedding".to_string(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(vector) => json!({
+                            "provider": provider,
+                            "dimensions": vector.as_array().map_or(0, Vec::len),
+                        }),
+                        Err(e) => json!({ "provider": provider, "error": e.to_string() }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+/// Where an indexing node upserts its vector.
+struct IndexTarget {
+    store: Arc<dyn VectorStore>,
+    collection: String,
+    id: String,
+    metadata: Value,
+}
+
+/// A provider request with its templates still unrendered; credentials are only resolved in
+/// the background task.
+struct EmbeddingCall {
+    method: String,
+    /// Base URL plus the action's path template.
+    url: String,
+    headers: HashMap<String, String>,
+    body_template: Option<String>,
+    /// Payload fields plus `model` and `text`.
+    context: serde_json::Map<String, Value>,
+    vector_pointer: String,
+    index: Option<IndexTarget>,
+    /// Secret holding the API key when the node has no connection.
+    key_name: String,
+    auth: Option<AuthDef>,
+}
+
+fn prepare(
+    config: &EmbeddingConfig,
+    registry: &IntegrationRegistry,
+    payload: &Value,
+    index: Option<&VectorIndex>,
+) -> anyhow::Result<EmbeddingCall> {
+    let def = registry
+        .definitions
+        .get(&config.provider)
+        .ok_or_else(|| anyhow::anyhow!("Integration '{}' not found", config.provider))?;
+    let action = def
+        .actions
+        .get(EMBEDDINGS_ACTION)
+        .or_else(|| def.resources.get(EMBEDDINGS_ACTION))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Integration '{}' has no '{}' action",
+                config.provider,
+                EMBEDDINGS_ACTION
+            )
+        })?;
+
+    let text = payload
+        .get(&config.input_field)
+        .and_then(Value::as_str)
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("No text in '{}'", config.input_field))?;
+    let mut context = match payload {
+        Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    if let Some(model) = &config.model {
+        context.insert("model".to_string(), json!(model));
+    }
+    context.insert("text".to_string(), json!(text));
+
+    let index = match &config.collection {
+        Some(collection) => {
+            let index = index.ok_or_else(|| anyhow::anyhow!("No vector store is configured"))?;
+            let id = match config
+                .id_field
+                .as_ref()
+                .and_then(|field| payload.get(field))
+            {
+                Some(Value::String(id)) => id.clone(),
+                Some(Value::Null) | None => uuid::Uuid::new_v4().to_string(),
+                Some(id) => id.to_string(),
+            };
+            Some(IndexTarget {
+                store: index.0.clone(),
+                collection: collection.clone(),
+                id,
+                metadata: payload.clone(),
+            })
+        }
+        None => None,
+    };
+
+    let implementation = &action.implementation.config;
+    Ok(EmbeddingCall {
+        method: implementation.method.clone(),
+        url: format!("{}{}", def.base_url, implementation.path),
+        headers: implementation.headers.clone(),
+        body_template: implementation.body_template.clone(),
+        context,
+        vector_pointer: config.vector_pointer.clone(),
+        index,
+        key_name: def
+            .verify_params
+            .get("api_key")
+            .cloned()
+            .unwrap_or_else(|| "API_KEY".to_string()),
+        auth: def.auth.clone(),
+    })
+}
+
+impl EmbeddingCall {
+    async fn send(
+        mut self,
+        client: &reqwest::Client,
+        tenant: &TenantId,
+        credentials: serde_json::Map<String, Value>,
+        timeout: Duration,
+    ) -> anyhow::Result<Value> {
+        self.context.extend(credentials);
+        let context = Value::Object(self.context);
+
+        let method = reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes())?;
+        let url = apply_template(&self.url, &context);
+        let mut request = client.request(method, &url).timeout(timeout);
+        for (name, template) in &self.headers {
+            request = request.header(name, apply_template(template, &context));
+        }
+        request = authorize(request, self.auth.as_ref(), &self.headers, &context);
+        if let Some(template) = &self.body_template {
+            request = request.body(apply_template(template, &context));
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        if !status.is_success() {
+            let detail = String::from_utf8_lossy(&bytes);
+            anyhow::bail!(
+                "Embedding provider returned {}: {}",
+                status,
+                detail.chars().take(500).collect::<String>()
+            );
+        }
+        let mut body: Value = serde_json::from_slice(&bytes)?;
+        let raw = body
+            .pointer_mut(&self.vector_pointer)
+            .map(Value::take)
+            .ok_or_else(|| anyhow::anyhow!("No vector at '{}'", self.vector_pointer))?;
+        let vector: Vec<f32> = raw
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Vector at '{}' is not an array", self.vector_pointer))?
+            .iter()
+            .map(|x| x.as_f64().map(|x| x as f32))
+            .collect::<Option<_>>()
+            .ok_or_else(|| anyhow::anyhow!("Vector at '{}' is not numeric", self.vector_pointer))?;
+
+        if let Some(target) = self.index {
+            let record = VectorRecord {
+                id: target.id,
+                vector,
+                metadata: target.metadata,
+            };
+            target
+                .store
+                .upsert(tenant, &target.collection, vec![record])
+                .await?;
+        }
+        Ok(raw)
+    }
+}
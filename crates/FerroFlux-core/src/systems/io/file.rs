@@ -0,0 +1,234 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::{FileIoConfig, FileMode, FileOperation};
+use crate::resources::FileSandbox;
+use crate::resources::settings::RuntimeSettings;
+use crate::store::BlobStore;
+use crate::systems::io::templating::apply_template;
+use base64::{Engine as _, engine::general_purpose};
+use bevy_ecs::prelude::*;
+use ferroflux_security::sandbox;
+use serde_json::{Value, json};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// System: File Worker
+///
+/// Handles one file operation per ticket under `<sandbox>/<tenant>/`, or the directory
+/// configured for the tenant in `files.tenant_roots`. Paths that would leave the tenant
+/// directory are rejected by `ferroflux_security::sandbox`. Reads and lists add their result
+/// under `result_key`; writes, appends and deletes forward the ticket unchanged. A write that
+/// would exceed `files.max_file_bytes` or the tenant's quota is refused. Failures route to `error`.
+#[tracing::instrument(skip(query, store, sandbox_root, event_bus, settings))]
+pub fn file_worker(
+    mut query: Query<(&FileIoConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    sandbox_root: Option<Res<FileSandbox>>,
+    event_bus: Res<SystemEventBus>,
+    settings: Option<Res<RuntimeSettings>>,
+) {
+    let root = sandbox_root
+        .map(|s| s.0.clone())
+        .unwrap_or_else(|| FileSandbox::default().0);
+    let settings = RuntimeSettings::effective(settings.as_deref());
+    let files = &settings.files;
+
+    for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let start = Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<(Option<Vec<u8>>, Value)> {
+                let data = store.claim(&ticket)?;
+                let payload: Value = serde_json::from_slice(&data).unwrap_or(Value::Null);
+
+                let tenant = node_config
+                    .tenant_id
+                    .as_ref()
+                    .map(|t| t.as_ref())
+                    .unwrap_or("default_tenant");
+                let tenant_root = match files.tenant_roots.get(tenant) {
+                    Some(dir) => PathBuf::from(dir),
+                    None => sandbox::tenant_root(&root, tenant).map_err(anyhow::Error::msg)?,
+                };
+                std::fs::create_dir_all(&tenant_root)?;
+                let relative = apply_template(&config.path, &payload);
+                let lists_root = config.operation == FileOperation::List
+                    && matches!(relative.trim(), "" | "." | "/");
+                let path = if lists_root {
+                    tenant_root.clone()
+                } else {
+                    sandbox::resolve_path(&tenant_root, &relative).map_err(anyhow::Error::msg)?
+                };
+
+                match config.operation {
+                    FileOperation::Read => {
+                        let bytes = std::fs::read(&path)?;
+                        let size = bytes.len();
+                        let content = match config.mode {
+                            FileMode::Text => Value::String(String::from_utf8(bytes)?),
+                            FileMode::Bytes => {
+                                Value::String(general_purpose::STANDARD.encode(bytes))
+                            }
+                            FileMode::Json => serde_json::from_slice(&bytes)?,
+                        };
+                        let mut output = match payload {
+                            Value::Object(map) => map,
+                            _ => serde_json::Map::new(),
+                        };
+                        output.insert(config.result_key.clone(), content);
+                        Ok((
+                            Some(serde_json::to_vec(&output)?),
+                            json!({ "operation": "read", "path": relative, "bytes": size }),
+                        ))
+                    }
+                    FileOperation::Write | FileOperation::Append => {
+                        let value = match &config.content_field {
+                            Some(field) => payload
+                                .get(field)
+                                .cloned()
+                                .ok_or_else(|| anyhow::anyhow!("Field '{}' not found", field))?,
+                            None => payload,
+                        };
+                        let bytes = match (&config.mode, value) {
+                            (FileMode::Text, Value::String(s)) => s.into_bytes(),
+                            (FileMode::Text, other) => other.to_string().into_bytes(),
+                            (FileMode::Bytes, Value::String(s)) => {
+                                general_purpose::STANDARD.decode(s)?
+                            }
+                            (FileMode::Bytes, _) => {
+                                anyhow::bail!("Bytes mode expects a base64 string")
+                            }
+                            (FileMode::Json, value) => serde_json::to_vec_pretty(&value)?,
+                        };
+                        let append = config.operation == FileOperation::Append;
+                        let existing = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        let new_size = bytes.len() as u64 + if append { existing } else { 0 };
+                        if files.max_file_bytes > 0 && new_size > files.max_file_bytes {
+                            anyhow::bail!(
+                                "File would grow to {} bytes, over the {} byte limit",
+                                new_size,
+                                files.max_file_bytes
+                            );
+                        }
+                        if let Some(quota) = files.quota_for(tenant) {
+                            let usage =
+                                disk_usage(&tenant_root)?.saturating_sub(existing) + new_size;
+                            if usage > quota {
+                                anyhow::bail!(
+                                    "Tenant file quota exceeded: {} of {} bytes",
+                                    usage,
+                                    quota
+                                );
+                            }
+                        }
+
+                        if let Some(parent) = path.parent() {
+                            std::fs::create_dir_all(parent)?;
+                        }
+                        let mut file = std::fs::OpenOptions::new()
+                            .create(true)
+                            .write(true)
+                            .append(append)
+                            .truncate(!append)
+                            .open(&path)?;
+                        file.write_all(&bytes)?;
+                        let operation = if append { "append" } else { "write" };
+                        Ok((
+                            None,
+                            json!({ "operation": operation, "path": relative, "bytes": bytes.len() }),
+                        ))
+                    }
+                    FileOperation::List => {
+                        let mut entries = Vec::new();
+                        for entry in std::fs::read_dir(&path)? {
+                            let entry = entry?;
+                            let meta = entry.metadata()?;
+                            let entry_path = entry.path();
+                            let relative_path = entry_path
+                                .strip_prefix(&tenant_root)
+                                .unwrap_or(&entry_path)
+                                .components()
+                                .map(|c| c.as_os_str().to_string_lossy())
+                                .collect::<Vec<_>>()
+                                .join("/");
+                            entries.push(json!({
+                                "name": entry.file_name().to_string_lossy(),
+                                "path": relative_path,
+                                "is_dir": meta.is_dir(),
+                                "bytes": if meta.is_dir() { 0 } else { meta.len() },
+                            }));
+                        }
+                        entries.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+                        let count = entries.len();
+                        let mut output = match payload {
+                            Value::Object(map) => map,
+                            _ => serde_json::Map::new(),
+                        };
+                        output.insert(config.result_key.clone(), Value::Array(entries));
+                        Ok((
+                            Some(serde_json::to_vec(&output)?),
+                            json!({ "operation": "list", "path": relative, "entries": count }),
+                        ))
+                    }
+                    FileOperation::Delete => {
+                        let meta = std::fs::symlink_metadata(&path)?;
+                        if meta.is_dir() {
+                            std::fs::remove_dir(&path)?;
+                        } else {
+                            std::fs::remove_file(&path)?;
+                        }
+                        Ok((
+                            None,
+                            json!({ "operation": "delete", "path": relative, "bytes": meta.len() }),
+                        ))
+                    }
+                }
+            })();
+
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: "File".into(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok((_, details)) => details.clone(),
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            let output = result.and_then(|(output, _)| match output {
+                Some(bytes) => {
+                    let mut new_ticket = store.check_in(&bytes)?;
+                    new_ticket.metadata = ticket.metadata.clone();
+                    Ok(new_ticket)
+                }
+                None => Ok(ticket.clone()),
+            });
+            match output {
+                Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "File operation failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
+
+/// Bytes held by regular files under `dir`; symlinks aren't followed or counted.
+fn disk_usage(dir: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.path().symlink_metadata()?;
+        if meta.is_dir() {
+            total += disk_usage(&entry.path())?;
+        } else if meta.is_file() {
+            total += meta.len();
+        }
+    }
+    Ok(total)
+}
@@ -1,17 +1,21 @@
 use crate::api::events::{SystemEvent, SystemEventBus};
 use crate::components::{
-    AuthConfig, HttpConfig, Inbox, NodeConfig, Outbox, PayloadMapper, PinnedOutput, SecretConfig,
+    AuthConfig, HttpConfig, Inbox, NodeConfig, Outbox, PaginationConfig, PaginationOutput,
+    PayloadMapper, PinnedOutput, SecretConfig,
 };
 use ferroflux_iam::TenantId;
+use crate::resources::chaos::{Fault, FaultInjector};
+use crate::resources::connection_health::{ConnectionHealth, ConnectionOutcome};
+use crate::resources::http_policy::{HttpClientPolicy, HttpSettings, is_host_failure};
+use crate::resources::recorder::{HttpRecorder, HttpRequestRecord};
+use crate::resources::settings::RuntimeSettings;
 use crate::resources::{HttpResultChannel, TokioRuntime, WorkDone};
 use crate::secrets::{DatabaseSecretStore, SecretStore};
 use crate::store::BlobStore;
-use crate::systems::io::auth::resolve_auth_headers;
+use crate::systems::io::auth::{connection_auth_headers, resolve_auth_headers};
 use crate::systems::io::templating::apply_template;
 use crate::systems::utils::merge_result;
-use base64::{Engine as _, engine::general_purpose};
 use bevy_ecs::prelude::*;
-use ipnet::IpNet;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::env;
@@ -23,8 +27,25 @@ use uuid::Uuid;
 /// System: HTTP I/O Worker
 ///
 /// **Role**: Handles outbound HTTP requests via `reqwest`.
+/// With an `HttpClientPolicy` installed, calls to a host whose circuit is open fail fast and
+/// in-flight requests per host are capped before a blocking thread is taken. Timeouts, redirects
+/// and proxying come from the node's `HttpConfig` over the `HttpSettings` defaults.
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
-#[tracing::instrument(skip(query, store, work_done, event_bus, channel, secret_store, runtime))]
+#[tracing::instrument(skip(
+    query,
+    store,
+    work_done,
+    event_bus,
+    channel,
+    secret_store,
+    runtime,
+    chaos,
+    recorder,
+    settings,
+    health,
+    policy,
+    http_settings
+))]
 pub fn http_worker(
     mut query: Query<(
         Entity,
@@ -38,14 +59,22 @@ pub fn http_worker(
         &mut Outbox,
     )>,
     store: Res<BlobStore>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
     event_bus: Res<SystemEventBus>,
     channel: Res<HttpResultChannel>,
     secret_store: Res<DatabaseSecretStore>,
     runtime: Res<TokioRuntime>,
+    chaos: Option<Res<FaultInjector>>,
+    recorder: Option<Res<HttpRecorder>>,
+    settings: Option<Res<RuntimeSettings>>,
+    health: Option<Res<ConnectionHealth>>,
+    policy: Option<Res<HttpClientPolicy>>,
+    http_settings: Option<Res<HttpSettings>>,
 ) {
     let (tx, rx) = (&channel.tx, &channel.rx);
-    let event_tx = event_bus.0.clone();
+    let settings = RuntimeSettings::effective(settings.as_deref());
+    let http_settings = http_settings.as_deref().cloned().unwrap_or_default();
+    let event_tx = event_bus.clone();
 
     // 1. Poll Results
     while let Ok((entity, result_str, metadata)) = rx.try_recv() {
@@ -75,7 +104,7 @@ pub fn http_worker(
                     "HTTP Result recorded"
                 );
                 outbox.queue.push_back((None, ticket));
-                work_done.0 = true;
+                work_done.mark();
             }
         }
     }
@@ -97,11 +126,11 @@ pub fn http_worker(
             if let Some(pinned) = pinned_opt {
                 tracing::info!(entity = ?entity, "Node is PINNED. Skipping execution.");
                 outbox.queue.push_back((None, pinned.0.clone()));
-                work_done.0 = true;
+                work_done.mark();
                 continue;
             }
 
-            work_done.0 = true;
+            work_done.mark();
             let start = Instant::now();
 
             tracing::debug!(url = %config.url, "Spawning HTTP task");
@@ -143,14 +172,19 @@ pub fn http_worker(
                 }
             }
 
+            let redactor = secret_store.redactor().clone();
             if let Some(auth_config) = auth_opt {
                 let headers = resolve_auth_headers(auth_config);
+                for (_, value) in &headers {
+                    redactor.register_header(value);
+                }
                 dynamic_headers.extend(headers);
             }
 
             if let Some(secret_config) = secret_opt
                 && let Ok(val) = env::var(&secret_config.lookup_key)
             {
+                redactor.register(&val);
                 let header_val = secret_config.template.replace("{}", &val);
                 dynamic_headers.push((secret_config.header_name.clone(), header_val));
             }
@@ -161,6 +195,9 @@ pub fn http_worker(
             let entity_id = entity;
             let input_val_for_merge = input_json.clone().unwrap_or(json!({}));
             let result_key = config.result_key.clone();
+            let pagination = config.pagination.clone();
+            let full_response = config.full_response && pagination.is_none();
+            let client_settings = http_settings.for_node(config);
             let trace_id_clone = trace_id.clone();
             let event_tx_clone = event_tx.clone();
             let node_id = node_config.id;
@@ -172,6 +209,36 @@ pub fn http_worker(
                 .cloned()
                 .unwrap_or_else(|| TenantId::from("default_tenant"));
 
+            let injected_latency = chaos
+                .as_ref()
+                .and_then(|c| c.latency(&node_config.node_type));
+            let inject_error = chaos
+                .as_ref()
+                .is_some_and(|c| c.should_inject(&node_config.node_type, Fault::HttpError));
+            let recorder = recorder
+                .as_deref()
+                .filter(|_| settings.capture.http_exchanges)
+                .cloned();
+            let redact_bodies = settings.capture.redact_request_bodies;
+            let network = settings.network.clone();
+            let health = health.as_deref().cloned();
+            let policy = policy.as_deref().cloned();
+            let policy_settings = settings.http.clone();
+            let health_settings = settings.connections.clone();
+            let workflow_id = node_config.workflow_id.clone();
+            let tenant_for_health = tenant.clone();
+            let record_health = move |slug: &str, outcome| {
+                if let Some(health) = &health {
+                    health.record(
+                        &health_settings,
+                        &tenant_for_health,
+                        slug,
+                        workflow_id.as_deref(),
+                        outcome,
+                    );
+                }
+            };
+
             let _ = event_tx_clone.send(SystemEvent::Log {
                 level: "INFO".into(),
                 message: format!("HTTP Request to {}", url_str),
@@ -183,8 +250,8 @@ pub fn http_worker(
                 let span = tracing::info_span!("http_request", node_id = %node_id, trace_id = %trace_id_clone);
                 let _enter = span.enter();
 
-                if let Some(slug) = connection_slug_opt {
-                    match secret_store_clone.resolve_connection(&tenant, &slug).await {
+                if let Some(slug) = &connection_slug_opt {
+                    match secret_store_clone.resolve_connection(&tenant, slug).await {
                         Ok(conn_data) => {
                             if let Some(base) = conn_data.get("base_url").and_then(|v| v.as_str()) {
                                 let base = base.trim_end_matches('/');
@@ -196,61 +263,14 @@ pub fn http_worker(
                                 }
                             }
 
-                            if let Some(auth_type) =
-                                conn_data.get("auth_type").and_then(|v| v.as_str())
-                            {
-                                match auth_type {
-                                    "Bearer" => {
-                                        if let Some(cred) =
-                                            conn_data.get("credentials").and_then(|v| v.as_str())
-                                        {
-                                            dynamic_headers.push((
-                                                "Authorization".to_string(),
-                                                format!("Bearer {}", cred),
-                                            ));
-                                        }
-                                    }
-                                    "Basic" => {
-                                        if let Some(cred) =
-                                            conn_data.get("credentials").and_then(|v| v.as_str())
-                                        {
-                                            let encoded = general_purpose::STANDARD.encode(cred);
-                                            dynamic_headers.push((
-                                                "Authorization".to_string(),
-                                                format!("Basic {}", encoded),
-                                            ));
-                                        }
-                                    }
-                                    "Custom Scheme" => {
-                                        let scheme = conn_data
-                                            .get("auth_scheme")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("Bearer");
-
-                                        if let Some(cred) =
-                                            conn_data.get("credentials").and_then(|v| v.as_str())
-                                        {
-                                            dynamic_headers.push((
-                                                "Authorization".to_string(),
-                                                format!("{} {}", scheme, cred),
-                                            ));
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-
-                            if let Some(headers) =
-                                conn_data.get("custom_headers").and_then(|v| v.as_object())
-                            {
-                                for (k, v) in headers {
-                                    if let Some(val_str) = v.as_str() {
-                                        dynamic_headers.push((k.clone(), val_str.to_string()));
-                                    }
-                                }
+                            let headers = connection_auth_headers(&conn_data);
+                            for (_, value) in &headers {
+                                redactor.register_header(value);
                             }
+                            dynamic_headers.extend(headers);
                         }
                         Err(e) => {
+                            record_health(slug, ConnectionOutcome::Failure);
                             let _ = tx_clone
                                 .send((
                                     entity_id,
@@ -263,81 +283,187 @@ pub fn http_worker(
                     }
                 }
 
+                let host = Url::parse(&url_str)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_string));
+                let mut permit = None;
+                if let (Some(policy), Some(host)) = (&policy, &host) {
+                    if let Err(retry_in) = policy.check(host) {
+                        let _ = event_tx_clone.send(SystemEvent::NodeTelemetry {
+                            trace_id: trace_id_clone.clone(),
+                            node_id,
+                            node_type: "Http".to_string(),
+                            execution_ms: start.elapsed().as_millis() as u64,
+                            success: false,
+                            details: json!({
+                                "url": url_str,
+                                "host": host,
+                                "circuit_open": true,
+                                "retry_in_ms": retry_in.as_millis() as u64,
+                            }),
+                        });
+                        let _ = tx_clone
+                            .send((
+                                entity_id,
+                                format!("Error: Circuit open for host {}", host),
+                                HashMap::from([("trace_id".to_string(), trace_id_clone)]),
+                            ))
+                            .await;
+                        return;
+                    }
+                    permit = policy.acquire(&policy_settings, host).await;
+                }
+
+                if let Some(delay) = injected_latency {
+                    tokio::time::sleep(delay).await;
+                }
+
                 let url_for_thread = url_str.clone();
+                let redactor_for_thread = redactor.clone();
                 let result = tokio::task::spawn_blocking(move || {
-                    let parsed_url = match Url::parse(&url_for_thread) {
-                        Ok(u) => u,
-                        Err(e) => return (format!("Error: Invalid URL {}", e), 0),
-                    };
+                    if inject_error {
+                        return (vec!["Error: HTTP 500 Internal Server Error (injected)".to_string()], 500);
+                    }
 
-                    let host_str = match parsed_url.host_str() {
-                        Some(h) => h,
-                        None => return ("Error: No Host".to_string(), 0),
+                    // Keep what was sent so the exchange can be recorded afterwards.
+                    let sent = recorder.as_ref().map(|_| {
+                        if redact_bodies {
+                            redactor_for_thread
+                                .redact(&String::from_utf8_lossy(&data_clone))
+                                .into_bytes()
+                        } else {
+                            data_clone.clone()
+                        }
+                    });
+
+                    let client = match client_settings.blocking_client(&network) {
+                        Ok(client) => client,
+                        Err(e) => return (vec![format!("Error: {}", e)], 0),
                     };
+                    let fetch = |url: &str| -> (String, u16) {
+                        let parsed_url = match Url::parse(url) {
+                            Ok(u) => u,
+                            Err(e) => return (format!("Error: Invalid URL {}", e), 0),
+                        };
+
+                        let host_str = match parsed_url.host_str() {
+                            Some(h) => h,
+                            None => return ("Error: No Host".to_string(), 0),
+                        };
+
+                        let port = parsed_url.port_or_known_default().unwrap_or(80);
+
+                        let socket_addrs = match format!("{}:{}", host_str, port).to_socket_addrs() {
+                            Ok(iter) => iter,
+                            Err(e) => return (format!("Error: DNS Resolution Failed {}", e), 0),
+                        };
+
+                        if let Some(ip) = network
+                            .blocked_address(host_str, socket_addrs.map(|addr| addr.ip()))
+                        {
+                            return (format!("Error: Blocked Internal IP {}", ip), 403);
+                        }
 
-                    let port = parsed_url.port_or_known_default().unwrap_or(80);
+                        let mut request = match method.as_str() {
+                            "POST" => client.post(url).body(data_clone.clone()),
+                            _ => client.get(url),
+                        };
 
-                    let socket_addrs = match format!("{}:{}", host_str, port).to_socket_addrs() {
-                        Ok(iter) => iter,
-                        Err(e) => return (format!("Error: DNS Resolution Failed {}", e), 0),
-                    };
+                        for (name, val) in &dynamic_headers {
+                            request = request.header(name, val);
+                        }
 
-                    let allow_internal =
-                        env::var("FERROFLUX_ALLOW_INTERNAL_IPS").unwrap_or_default() == "true";
-
-                    if !allow_internal {
-                        let blocklist = [
-                            "127.0.0.0/8",
-                            "10.0.0.0/8",
-                            "172.16.0.0/12",
-                            "192.168.0.0/16",
-                            "169.254.0.0/16",
-                        ];
-
-                        for addr in socket_addrs {
-                            let ip = addr.ip();
-                            for range in &blocklist {
-                                if let Ok(net) = range.parse::<IpNet>()
-                                    && net.contains(&ip)
-                                {
-                                    return (format!("Error: Blocked Internal IP {}", ip), 403);
+                        let sent_at = Instant::now();
+                        match request.send() {
+                            Ok(resp) => {
+                                let status = resp.status();
+                                let response_headers: Vec<(String, String)> = resp
+                                    .headers()
+                                    .iter()
+                                    .map(|(k, v)| {
+                                        (k.to_string(), v.to_str().unwrap_or_default().to_string())
+                                    })
+                                    .collect();
+                                let text = resp.text().unwrap_or_default();
+
+                                if let (Some(recorder), Some(body)) = (&recorder, &sent) {
+                                    recorder.record(
+                                        HttpRequestRecord {
+                                            method: &method,
+                                            url,
+                                            headers: &dynamic_headers,
+                                            body: (method == "POST").then_some(body.as_slice()),
+                                        },
+                                        status.as_u16(),
+                                        &response_headers,
+                                        &text,
+                                    );
+                                }
+
+                                if full_response {
+                                    let duration_ms = sent_at.elapsed().as_millis() as u64;
+                                    let response = full_response_output(
+                                        status.as_u16(),
+                                        &response_headers,
+                                        text,
+                                        duration_ms,
+                                    );
+                                    (response.to_string(), status.as_u16())
+                                } else if status.is_success() {
+                                    (text, status.as_u16())
+                                } else {
+                                    (format!("Error: HTTP {}", status), status.as_u16())
                                 }
                             }
+                            Err(e) => (format!("Error: {}", e), 0),
                         }
-                    }
-
-                    let client = reqwest::blocking::Client::new();
-                    let mut request = match method.as_str() {
-                        "POST" => client.post(&url_for_thread).body(data_clone),
-                        _ => client.get(&url_for_thread),
                     };
 
-                    for (name, val) in dynamic_headers {
-                        request = request.header(name, val);
-                    }
-
-                    match request.send() {
-                        Ok(resp) => {
-                            let code = resp.status().as_u16();
-                            if resp.status().is_success() {
-                                (resp.text().unwrap_or_default(), code)
-                            } else {
-                                (format!("Error: HTTP {}", resp.status()), code)
-                            }
+                    match &pagination {
+                        Some(pagination) => fetch_pages(pagination, &url_for_thread, fetch),
+                        None => {
+                            let (text, status) = fetch(&url_for_thread);
+                            (vec![text], status)
                         }
-                        Err(e) => (format!("Error: {}", e), 0),
                     }
                 })
                 .await;
+                drop(permit);
 
-                if let Ok((result_text, status_code)) = result {
-                    let output = merge_result(
-                        &input_val_for_merge,
-                        &result_text,
-                        result_key.as_ref(),
-                    );
+                let blocked = |results: &[String]| {
+                    results.iter().any(|text| text.starts_with("Error: Blocked"))
+                };
+
+                if let (Some(slug), Ok((results, status_code))) = (&connection_slug_opt, &result) {
+                    // A blocked destination is our policy, not a rejected credential.
+                    let outcome = if blocked(results) {
+                        ConnectionOutcome::Failure
+                    } else {
+                        ConnectionOutcome::from_status(*status_code)
+                    };
+                    record_health(slug, outcome);
+                }
 
-                    let success = !result_text.starts_with("Error:");
+                if let (Some(policy), Some(host), Ok((results, status_code))) =
+                    (&policy, &host, &result)
+                {
+                    // Our own SSRF block says nothing about the host's health.
+                    let failed = is_host_failure(*status_code) && !blocked(results);
+                    if let Some(opened) = policy.record(&policy_settings, host, !failed) {
+                        tracing::warn!(host = %opened.host, failures = opened.consecutive_failures, "HTTP circuit opened");
+                        let _ = event_tx_clone.send(SystemEvent::HostCircuitOpened {
+                            host: opened.host,
+                            consecutive_failures: opened.consecutive_failures,
+                            open_ms: opened.open_for.as_millis() as u64,
+                            timestamp: chrono::Utc::now().timestamp_millis(),
+                        });
+                    }
+                }
+
+                if let Ok((results, status_code)) = result {
+                    // Full responses carry error statuses as data rather than as an error text.
+                    let success = status_code < 400
+                        && !results.iter().any(|text| text.starts_with("Error:"));
                     let elapsed = start.elapsed().as_millis() as u64;
 
                     let _ = event_tx_clone.send(SystemEvent::NodeTelemetry {
@@ -352,12 +478,169 @@ pub fn http_worker(
                         }),
                     });
 
-                    let mut out_meta = HashMap::new();
-                    out_meta.insert("trace_id".to_string(), trace_id_clone);
+                    for result_text in results {
+                        let output = merge_result(
+                            &input_val_for_merge,
+                            &result_text,
+                            result_key.as_ref(),
+                        );
+
+                        let mut out_meta = HashMap::new();
+                        out_meta.insert("trace_id".to_string(), trace_id_clone.clone());
 
-                    let _ = tx_clone.send((entity_id, output, out_meta)).await;
+                        let _ = tx_clone.send((entity_id, output, out_meta)).await;
+                    }
                 }
             });
         }
     }
 }
+
+/// The `{status, headers, body, duration_ms}` output of a node with `full_response` set.
+///
+/// Repeated headers are joined with `, `; the body is parsed as JSON when it is JSON.
+fn full_response_output(
+    status: u16,
+    headers: &[(String, String)],
+    text: String,
+    duration_ms: u64,
+) -> Value {
+    let mut header_map = serde_json::Map::new();
+    for (name, value) in headers {
+        match header_map.get_mut(name) {
+            Some(Value::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(value);
+            }
+            _ => {
+                header_map.insert(name.clone(), Value::String(value.clone()));
+            }
+        }
+    }
+    let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+    json!({
+        "status": status,
+        "headers": header_map,
+        "body": body,
+        "duration_ms": duration_ms,
+    })
+}
+
+/// Follows `pagination` from `url`, fetching each page with `fetch`.
+///
+/// Returns the node's outputs and the last status seen. A failed page fails the whole request.
+/// Paging stops at a next-page URL on another origin, which would otherwise receive the
+/// request's headers, credentials included.
+fn fetch_pages(
+    pagination: &PaginationConfig,
+    url: &str,
+    fetch: impl Fn(&str) -> (String, u16),
+) -> (Vec<String>, u16) {
+    let mut page = pagination.start_page;
+    let mut next = match &pagination.page_param {
+        Some(param) => with_query(url, param, &page.to_string()),
+        None => Some(url.to_string()),
+    };
+    let origin = Url::parse(url).ok().map(|u| u.origin());
+    let mut pages = Vec::new();
+    let mut status = 0;
+
+    while let Some(current) = next.take() {
+        if pages.len() >= pagination.max_pages as usize {
+            break;
+        }
+
+        let (text, page_status) = fetch(&current);
+        status = page_status;
+        if text.starts_with("Error:") {
+            return (vec![text], status);
+        }
+        let body: Value = match serde_json::from_str(&text) {
+            Ok(body) => body,
+            Err(e) => return (vec![format!("Error: Page is not JSON {}", e)], status),
+        };
+
+        let items = match &pagination.items_field {
+            Some(f) => field(&body, f).cloned().unwrap_or(Value::Null),
+            None => body.clone(),
+        };
+        let empty = match &items {
+            Value::Null => true,
+            Value::Array(items) => items.is_empty(),
+            _ => false,
+        };
+        if empty {
+            break;
+        }
+        pages.push(items);
+
+        let text_of = |f: &Option<String>| {
+            f.as_deref()
+                .and_then(|f| field(&body, f))
+                .and_then(|v| match v {
+                    Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+        };
+        next = if let Some(next_url) = text_of(&pagination.next_url_field) {
+            let joined = Url::parse(&current).and_then(|base| base.join(&next_url));
+            match joined {
+                Ok(next) if Some(next.origin()) == origin => Some(next.into()),
+                Ok(next) => {
+                    tracing::warn!(next = %next, "Next page is on another origin; stopped paging");
+                    None
+                }
+                Err(_) => None,
+            }
+        } else if let Some(cursor) = text_of(&pagination.cursor_field) {
+            with_query(&current, &pagination.cursor_param, &cursor)
+        } else if let Some(param) = &pagination.page_param {
+            page += 1;
+            with_query(&current, param, &page.to_string())
+        } else {
+            None
+        };
+    }
+
+    let outputs = match pagination.output {
+        PaginationOutput::Combined => {
+            let combined: Vec<Value> = pages
+                .into_iter()
+                .flat_map(|items| match items {
+                    Value::Array(items) => items,
+                    item => vec![item],
+                })
+                .collect();
+            vec![Value::Array(combined).to_string()]
+        }
+        PaginationOutput::PerPage if pages.is_empty() => vec!["[]".to_string()],
+        PaginationOutput::PerPage => pages.iter().map(Value::to_string).collect(),
+    };
+    (outputs, status)
+}
+
+/// `url` with the query parameter `param` set to `value`.
+fn with_query(url: &str, param: &str, value: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| k != param)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(kept)
+        .append_pair(param, value);
+    Some(parsed.into())
+}
+
+/// A top-level key of `body`, or a JSON pointer when `f` starts with `/`.
+fn field<'a>(body: &'a Value, f: &str) -> Option<&'a Value> {
+    if f.starts_with('/') {
+        body.pointer(f)
+    } else {
+        body.get(f)
+    }
+}
@@ -0,0 +1,410 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::{SpeechConfig, SpeechDirection};
+use crate::integrations::{AuthDef, IntegrationRegistry};
+use crate::resources::{GlobalHttpClient, SpeechResultChannel, TokioRuntime, WorkDone};
+use crate::secrets::{DatabaseSecretStore, EnvSecretStore, SecretStore};
+use crate::store::BlobStore;
+use crate::systems::io::templating::apply_template;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Content type assumed for synthesized audio when the provider doesn't say.
+const DEFAULT_AUDIO_TYPE: &str = "audio/mpeg";
+
+/// System: Speech Worker (STT / TTS)
+///
+/// Sends each ticket to the node's provider as a background request.
+/// - **Speech-to-text** uploads the referenced audio blob as multipart `file` (with `model` and
+///   `language` fields) and places the provider's JSON response, or `{ "text": .. }` for plain
+///   text responses, under `result_key`.
+/// - **Text-to-speech** posts the rendered body template and checks the returned audio into the
+///   BlobStore, placing `{ blob_id, bytes, content_type }` under `result_key`.
+///
+/// Failures route the original ticket to `error`.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[tracing::instrument(skip(
+    query,
+    store,
+    registry,
+    client,
+    channel,
+    secret_store,
+    runtime,
+    event_bus,
+    work_done
+))]
+pub fn speech_worker(
+    mut query: Query<(Entity, &SpeechConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    registry: Res<IntegrationRegistry>,
+    client: Res<GlobalHttpClient>,
+    channel: Res<SpeechResultChannel>,
+    secret_store: Option<Res<DatabaseSecretStore>>,
+    runtime: Res<TokioRuntime>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished calls
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, config, node_config, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|value| {
+            let mut payload = match store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            {
+                Some(Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            payload.insert(config.result_key.clone(), value);
+            store.check_in_with_metadata(&serde_json::to_vec(&payload)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Speech call failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new calls
+    let secrets: Arc<dyn SecretStore> = match &secret_store {
+        Some(store) => Arc::new(store.as_ref().clone()),
+        None => Arc::new(EnvSecretStore),
+    };
+    for (entity, config, node_config, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let payload = store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+                .unwrap_or(Value::Null);
+            let call = match prepare(config, &registry, &store, &payload) {
+                Ok(call) => call,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let client = client.client.clone();
+            let secrets = secrets.clone();
+            let store = store.clone();
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let node_id = node_config.id;
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let slug = config.connection_slug.clone();
+            let timeout = Duration::from_millis(config.timeout_ms);
+            let provider = config.provider.clone();
+            let direction = config.direction;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let result = async {
+                    let credentials =
+                        credentials(secrets.as_ref(), &tenant, slug.as_deref(), &call.key_name)
+                            .await?;
+                    call.send(&client, &store, credentials, timeout).await
+                }
+                .await;
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "Speech".to_string(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(_) => json!({ "provider": provider, "action": direction.action() }),
+                        Err(e) => json!({
+                            "provider": provider,
+                            "action": direction.action(),
+                            "error": e.to_string(),
+                        }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+/// A provider request with its templates still unrendered; credentials are only resolved in
+/// the background task.
+struct SpeechCall {
+    direction: SpeechDirection,
+    method: String,
+    /// Base URL plus the action's path template.
+    url: String,
+    headers: HashMap<String, String>,
+    body_template: Option<String>,
+    /// Payload fields plus `model`, `voice`, `language` and `text`.
+    context: serde_json::Map<String, Value>,
+    /// Audio bytes and content type, for speech-to-text.
+    audio: Option<(Vec<u8>, String)>,
+    /// Secret holding the API key when the node has no connection.
+    key_name: String,
+    /// Applied with `api_key` unless the action's headers already carry it.
+    auth: Option<AuthDef>,
+}
+
+fn prepare(
+    config: &SpeechConfig,
+    registry: &IntegrationRegistry,
+    store: &BlobStore,
+    payload: &Value,
+) -> anyhow::Result<SpeechCall> {
+    let def = registry
+        .definitions
+        .get(&config.provider)
+        .ok_or_else(|| anyhow::anyhow!("Integration '{}' not found", config.provider))?;
+    let action_name = config.direction.action();
+    let action = def
+        .actions
+        .get(action_name)
+        .or_else(|| def.resources.get(action_name))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Integration '{}' has no '{}' action",
+                config.provider,
+                action_name
+            )
+        })?;
+
+    let mut context = match payload {
+        Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    for (key, value) in [
+        ("model", &config.model),
+        ("voice", &config.voice),
+        ("language", &config.language),
+    ] {
+        if let Some(value) = value {
+            context.insert(key.to_string(), json!(value));
+        }
+    }
+
+    let input = payload.get(config.input_field());
+    let audio = match config.direction {
+        SpeechDirection::SpeechToText => Some(audio_blob(store, input)?),
+        SpeechDirection::TextToSpeech => {
+            let text = input
+                .and_then(Value::as_str)
+                .filter(|text| !text.trim().is_empty())
+                .ok_or_else(|| anyhow::anyhow!("No text in '{}'", config.input_field()))?;
+            context.insert("text".to_string(), json!(text));
+            None
+        }
+    };
+
+    let implementation = &action.implementation.config;
+    Ok(SpeechCall {
+        direction: config.direction,
+        method: implementation.method.clone(),
+        url: format!("{}{}", def.base_url, implementation.path),
+        headers: implementation.headers.clone(),
+        body_template: implementation.body_template.clone(),
+        context,
+        audio,
+        key_name: def
+            .verify_params
+            .get("api_key")
+            .cloned()
+            .unwrap_or_else(|| "API_KEY".to_string()),
+        auth: def.auth.clone(),
+    })
+}
+
+/// Loads the audio an input refers to, either `{ "blob_id": .. }` or a bare blob id.
+fn audio_blob(store: &BlobStore, input: Option<&Value>) -> anyhow::Result<(Vec<u8>, String)> {
+    let blob_id = match input {
+        Some(Value::Object(map)) => map.get("blob_id").and_then(Value::as_str),
+        Some(Value::String(id)) => Some(id.as_str()),
+        _ => None,
+    }
+    .ok_or_else(|| anyhow::anyhow!("No audio blob reference in the payload"))?;
+    let ticket = uuid::Uuid::parse_str(blob_id)
+        .ok()
+        .and_then(|id| store.recover_ticket(&id))
+        .ok_or_else(|| anyhow::anyhow!("Audio blob {} not found", blob_id))?;
+    let bytes = store.claim(&ticket)?;
+
+    let content_type = input
+        .and_then(|input| input.get("content_type"))
+        .and_then(Value::as_str)
+        .or_else(|| ticket.metadata.get("content_type").map(String::as_str))
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    Ok((bytes, content_type))
+}
+
+/// The connection's fields when the node names one, otherwise `api_key` from the secret store
+/// if it is set. Keyless providers (e.g. a local Whisper server) get an empty map.
+pub(crate) async fn credentials(
+    secrets: &dyn SecretStore,
+    tenant: &TenantId,
+    slug: Option<&str>,
+    key_name: &str,
+) -> anyhow::Result<serde_json::Map<String, Value>> {
+    if let Some(slug) = slug {
+        return match secrets.resolve_connection(tenant, slug).await? {
+            Value::Object(map) => Ok(map),
+            _ => anyhow::bail!("Connection '{}' is not an object", slug),
+        };
+    }
+    let mut map = serde_json::Map::new();
+    if let Ok(key) = secrets.get_secret(tenant, key_name).await {
+        map.insert("api_key".to_string(), json!(key));
+    }
+    Ok(map)
+}
+
+/// Applies the integration's auth with the resolved `api_key`, unless the action's own
+/// `headers` already set that header.
+pub(crate) fn authorize(
+    request: reqwest::RequestBuilder,
+    auth: Option<&AuthDef>,
+    headers: &HashMap<String, String>,
+    context: &Value,
+) -> reqwest::RequestBuilder {
+    let Some(key) = context.get("api_key").and_then(Value::as_str) else {
+        return request;
+    };
+    let has = |name: &str| headers.keys().any(|h| h.eq_ignore_ascii_case(name));
+    match auth {
+        Some(AuthDef::Bearer) if !has("authorization") => request.bearer_auth(key),
+        Some(AuthDef::ApiKey {
+            in_header: true,
+            key_name,
+        }) if !has(key_name) => request.header(key_name, key),
+        Some(AuthDef::ApiKey {
+            in_header: false,
+            key_name,
+        }) => request.query(&[(key_name, key)]),
+        _ => request,
+    }
+}
+
+impl SpeechCall {
+    async fn send(
+        mut self,
+        client: &reqwest::Client,
+        store: &BlobStore,
+        credentials: serde_json::Map<String, Value>,
+        timeout: Duration,
+    ) -> anyhow::Result<Value> {
+        self.context.extend(credentials);
+        let context = Value::Object(self.context);
+
+        let method = reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes())?;
+        let url = apply_template(&self.url, &context);
+        let mut request = client.request(method, &url).timeout(timeout);
+        for (name, template) in &self.headers {
+            request = request.header(name, apply_template(template, &context));
+        }
+        request = authorize(request, self.auth.as_ref(), &self.headers, &context);
+        request = match (self.audio, &self.body_template) {
+            (Some((bytes, content_type)), _) => {
+                let fields: Vec<(&str, &str)> = ["model", "language"]
+                    .into_iter()
+                    .filter_map(|key| Some((key, context.get(key)?.as_str()?)))
+                    .collect();
+                let (boundary_type, body) = multipart(&fields, &bytes, &content_type);
+                request.header("Content-Type", boundary_type).body(body)
+            }
+            (None, Some(template)) => request.body(apply_template(template, &context)),
+            (None, None) => request,
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let bytes = response.bytes().await?;
+        if !status.is_success() {
+            let detail = String::from_utf8_lossy(&bytes);
+            anyhow::bail!(
+                "Speech provider returned {}: {}",
+                status,
+                detail.chars().take(500).collect::<String>()
+            );
+        }
+
+        match self.direction {
+            SpeechDirection::SpeechToText => Ok(match serde_json::from_slice(&bytes) {
+                Ok(Value::Object(map)) => Value::Object(map),
+                _ => json!({ "text": String::from_utf8_lossy(&bytes).trim() }),
+            }),
+            SpeechDirection::TextToSpeech => {
+                let content_type = content_type.unwrap_or_else(|| DEFAULT_AUDIO_TYPE.to_string());
+                let metadata = HashMap::from([("content_type".to_string(), content_type.clone())]);
+                let ticket = store.check_in_with_metadata(&bytes, metadata)?;
+                Ok(json!({
+                    "blob_id": ticket.id,
+                    "bytes": bytes.len(),
+                    "content_type": content_type,
+                }))
+            }
+        }
+    }
+}
+
+/// A `multipart/form-data` body with text `fields` and the audio as `file`. Returns the
+/// content type (carrying the boundary) and the body.
+fn multipart(fields: &[(&str, &str)], audio: &[u8], content_type: &str) -> (String, Vec<u8>) {
+    let boundary = format!("ferroflux-{}", uuid::Uuid::new_v4().simple());
+    let mut body = Vec::with_capacity(audio.len() + 512);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.{}\"\r\nContent-Type: {}\r\n\r\n",
+            boundary,
+            extension(content_type),
+            content_type
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(audio);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+/// Whisper-style APIs pick the decoder from the file name.
+fn extension(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/ogg" => "ogg",
+        "audio/webm" => "webm",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => "bin",
+    }
+}
@@ -0,0 +1,136 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::VectorSearchConfig;
+use crate::resources::{TokioRuntime, VectorIndex, VectorSearchResultChannel, WorkDone};
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+/// System: Vector Search Worker
+///
+/// Queries the [`VectorIndex`] in the background with the vector in each ticket's
+/// `vector_field` and places the `top_k` matches under `result_key`. A ticket without a
+/// numeric vector, or an engine without a vector store, routes to `error`.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+#[tracing::instrument(skip(query, store, channel, index, runtime, event_bus, work_done))]
+pub fn vector_search_worker(
+    mut query: Query<(
+        Entity,
+        &VectorSearchConfig,
+        &NodeConfig,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    channel: Res<VectorSearchResultChannel>,
+    index: Option<Res<VectorIndex>>,
+    runtime: Res<TokioRuntime>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+) {
+    // 1. Collect finished searches
+    while let Ok((entity, ticket, result)) = channel.rx.try_recv() {
+        let Ok((_, config, node_config, _, mut outbox)) = query.get_mut(entity) else {
+            continue;
+        };
+        let output = result.and_then(|matches| {
+            let mut payload = match store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            {
+                Some(Value::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+            payload.insert(config.result_key.clone(), matches);
+            store.check_in_with_metadata(&serde_json::to_vec(&payload)?, ticket.metadata.clone())
+        });
+        match output {
+            Ok(new_ticket) => outbox.queue.push_back((None, new_ticket)),
+            Err(e) => {
+                tracing::error!(node_id = %node_config.id, error = %e, "Vector search failed");
+                outbox.queue.push_back((Some("error".into()), ticket));
+            }
+        }
+        work_done.mark();
+    }
+
+    // 2. Start new searches
+    for (entity, config, node_config, mut inbox, _) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let payload = store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+                .unwrap_or(Value::Null);
+            let prepared = index
+                .as_ref()
+                .map(|index| index.0.clone())
+                .ok_or_else(|| anyhow::anyhow!("No vector store is configured"))
+                .and_then(|index| Ok((index, query_vector(&payload, &config.vector_field)?)));
+            let (index, vector) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    let _ = channel.tx.try_send((entity, ticket, Err(e)));
+                    continue;
+                }
+            };
+
+            let tx = channel.tx.clone();
+            let event_tx = event_bus.clone();
+            let node_id = node_config.id;
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let collection = config.collection.clone();
+            let top_k = config.top_k;
+            let min_score = config.min_score;
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            runtime.0.spawn(async move {
+                let start = Instant::now();
+                let result = index
+                    .search(&tenant, &collection, &vector, top_k)
+                    .await
+                    .and_then(|mut matches| {
+                        if let Some(min_score) = min_score {
+                            matches.retain(|m| m.score >= min_score);
+                        }
+                        Ok(serde_json::to_value(matches)?)
+                    });
+
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id,
+                    node_type: "VectorSearch".to_string(),
+                    execution_ms: start.elapsed().as_millis() as u64,
+                    success: result.is_ok(),
+                    details: match &result {
+                        Ok(matches) => json!({
+                            "collection": collection,
+                            "matches": matches.as_array().map_or(0, Vec::len),
+                        }),
+                        Err(e) => json!({ "collection": collection, "error": e.to_string() }),
+                    },
+                });
+                let _ = tx.send((entity, ticket, result)).await;
+            });
+        }
+    }
+}
+
+fn query_vector(payload: &Value, field: &str) -> anyhow::Result<Vec<f32>> {
+    payload
+        .get(field)
+        .and_then(Value::as_array)
+        .filter(|values| !values.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("No vector in '{}'", field))?
+        .iter()
+        .map(|x| x.as_f64().map(|x| x as f32))
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow::anyhow!("Vector in '{}' is not numeric", field))
+}
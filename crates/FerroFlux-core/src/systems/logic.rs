@@ -1,4 +1,5 @@
 use crate::components::{Edge, EdgeLabel, Inbox, SwitchConfig, WorkDone};
+use crate::resources::sharding::{Sharding, run_sharded};
 use crate::store::{BlobStore, SecureTicket};
 use bevy_ecs::prelude::*;
 use rhai::{Engine, Scope};
@@ -20,18 +21,18 @@ pub fn switch_worker_safe(
     edge_query: Query<(&Edge, Option<&EdgeLabel>)>,
     store: Res<BlobStore>,
     engine: NonSend<Engine>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
     event_bus: Res<crate::api::events::SystemEventBus>,
 ) {
     let mut actions: Vec<(Entity, SecureTicket)> = Vec::new();
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     // 1. Collect Valid Actions
     {
         let mut switches = param_set.p0();
         for (entity, config, node_config, mut inbox) in switches.iter_mut() {
             while let Some(ticket) = inbox.queue.pop_front() {
-                work_done.0 = true;
+                work_done.mark();
                 let start = std::time::Instant::now();
 
                 // Claim & Eval
@@ -134,14 +135,14 @@ pub fn script_worker(
     )>,
     store: Res<BlobStore>,
     engine: NonSend<Engine>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
     event_bus: Res<crate::api::events::SystemEventBus>,
 ) {
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
         while let Some(ticket) = inbox.queue.pop_front() {
-            work_done.0 = true;
+            work_done.mark();
             let start = std::time::Instant::now();
 
             let (data, trace_id) = match store.claim(&ticket) {
@@ -204,3 +205,282 @@ pub fn script_worker(
         }
     }
 }
+
+/// System: Data Contract
+///
+/// Validates each payload (or its `target_field`) against `ContractConfig::schema`.
+/// Conforming tickets pass through untouched. Others leave via `error` as
+/// `{ "payload": ..., "violations": [...] }` and a `ContractViolation` event is broadcast.
+/// Validation needs no shared state once the schema is compiled, so under `Sharding` the
+/// nodes' queues are worked through in parallel, one shard per thread.
+#[tracing::instrument(skip(query, store, event_bus, sharding))]
+pub fn contract_worker(
+    mut query: Query<(
+        &crate::components::ContractConfig,
+        &crate::components::NodeConfig,
+        &mut crate::components::ContractState,
+        &mut Inbox,
+        &mut crate::components::Outbox,
+    )>,
+    store: Res<BlobStore>,
+    event_bus: Res<crate::api::events::SystemEventBus>,
+    sharding: Option<Res<Sharding>>,
+) {
+    let mut jobs = Vec::new();
+    let mut outboxes = Vec::new();
+    for (config, node_config, mut state, mut inbox, outbox) in query.iter_mut() {
+        if inbox.queue.is_empty() {
+            continue;
+        }
+        let validator = match &state.validator {
+            Some(validator) => Ok(validator.clone()),
+            None => compile_contract(config)
+                .map(|validator| {
+                    let validator = std::sync::Arc::new(validator);
+                    state.validator = Some(validator.clone());
+                    validator
+                })
+                .map_err(|e| e.to_string()),
+        };
+        let tickets = std::mem::take(&mut inbox.queue);
+        jobs.push((node_config, (config, node_config, validator, tickets)));
+        outboxes.push(outbox);
+    }
+
+    let outputs = run_sharded(
+        sharding.as_deref(),
+        jobs,
+        |(config, node_config, validator, tickets)| {
+            tickets
+                .into_iter()
+                .map(|ticket| {
+                    check_contract(config, node_config, &validator, ticket, &store, &event_bus)
+                })
+                .collect::<Vec<_>>()
+        },
+    );
+    for (mut outbox, output) in outboxes.into_iter().zip(outputs) {
+        outbox.queue.extend(output);
+    }
+}
+
+/// Checks one ticket against the node's contract and returns it, or its rejection, with the
+/// port it leaves through.
+fn check_contract(
+    config: &crate::components::ContractConfig,
+    node_config: &crate::components::NodeConfig,
+    validator: &Result<std::sync::Arc<jsonschema::Validator>, String>,
+    ticket: SecureTicket,
+    store: &BlobStore,
+    event_bus: &crate::api::events::SystemEventBus,
+) -> (Option<String>, SecureTicket) {
+    use crate::api::events::{SchemaViolation, SystemEvent};
+    use serde_json::{Value, json};
+
+    let start = std::time::Instant::now();
+    let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+    let result = (|| -> anyhow::Result<(Value, Vec<SchemaViolation>)> {
+        let validator = validator.as_ref().map_err(|e| anyhow::anyhow!(e.clone()))?;
+        let payload: Value = serde_json::from_slice(&store.claim(&ticket)?)
+            .map_err(|e| anyhow::anyhow!("Payload is not JSON: {}", e))?;
+        let target = match &config.target_field {
+            Some(field) => payload.get(field).unwrap_or(&Value::Null),
+            None => &payload,
+        };
+        let violations = validator
+            .iter_errors(target)
+            .take(config.max_violations)
+            .map(|e| SchemaViolation {
+                path: e.instance_path.to_string(),
+                schema_path: e.schema_path.to_string(),
+                message: e.to_string(),
+            })
+            .collect();
+        Ok((payload, violations))
+    })();
+
+    let _ = event_bus.send(SystemEvent::NodeTelemetry {
+        trace_id: trace_id.clone(),
+        node_id: node_config.id,
+        node_type: "Contract".into(),
+        execution_ms: start.elapsed().as_millis() as u64,
+        success: matches!(&result, Ok((_, violations)) if violations.is_empty()),
+        details: match &result {
+            Ok((_, violations)) => json!({ "violations": violations.len() }),
+            Err(e) => json!({ "error": e.to_string() }),
+        },
+    });
+
+    match result {
+        Ok((_, violations)) if violations.is_empty() => (None, ticket),
+        Ok((payload, violations)) => {
+            tracing::warn!(node_id = %node_config.id, violations = violations.len(), "Payload violates data contract");
+            let rejected = json!({ "payload": payload, "violations": violations });
+            let output = match store.check_in(&serde_json::to_vec(&rejected).unwrap_or_default()) {
+                Ok(mut new_ticket) => {
+                    new_ticket.metadata = ticket.metadata.clone();
+                    new_ticket
+                }
+                Err(_) => ticket,
+            };
+            let _ = event_bus.send(SystemEvent::ContractViolation {
+                trace_id,
+                node_id: node_config.id,
+                violations,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            });
+            (Some("error".into()), output)
+        }
+        Err(e) => {
+            tracing::error!(node_id = %node_config.id, error = %e, "Contract check failed");
+            (Some("error".into()), ticket)
+        }
+    }
+}
+
+fn compile_contract(
+    config: &crate::components::ContractConfig,
+) -> anyhow::Result<jsonschema::Validator> {
+    use crate::components::SchemaDraft;
+    use jsonschema::Draft;
+
+    let mut options = jsonschema::options();
+    let draft = match config.draft {
+        SchemaDraft::Auto => None,
+        SchemaDraft::Draft4 => Some(Draft::Draft4),
+        SchemaDraft::Draft6 => Some(Draft::Draft6),
+        SchemaDraft::Draft7 => Some(Draft::Draft7),
+        SchemaDraft::Draft201909 => Some(Draft::Draft201909),
+        SchemaDraft::Draft202012 => Some(Draft::Draft202012),
+    };
+    if let Some(draft) = draft {
+        options.with_draft(draft);
+    }
+    options
+        .build(&config.schema)
+        .map_err(|e| anyhow::anyhow!("Invalid contract schema: {}", e))
+}
+
+pub fn router_worker(
+    mut query: Query<(
+        &crate::components::RouterConfig,
+        &crate::components::NodeConfig,
+        &mut Inbox,
+        &mut crate::components::Outbox,
+    )>,
+    store: Res<BlobStore>,
+    engine: NonSend<Engine>,
+    work_done: Res<WorkDone>,
+    event_bus: Res<crate::api::events::SystemEventBus>,
+) {
+    use serde_json::{Value, json};
+
+    for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            work_done.mark();
+            let start = std::time::Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<(Value, Vec<String>)> {
+                let payload: Value = serde_json::from_slice(&store.claim(&ticket)?)
+                    .map_err(|e| anyhow::anyhow!("Payload is not JSON: {}", e))?;
+                let value = evaluate_route(config, &engine, &payload)?;
+                let mut ports: Vec<String> = Vec::new();
+                for case in &config.cases {
+                    if case.values.iter().any(|v| loosely_equal(v, &value))
+                        && !ports.contains(&case.port)
+                    {
+                        ports.push(case.port.clone());
+                        if !config.match_all {
+                            break;
+                        }
+                    }
+                }
+                if ports.is_empty() {
+                    ports.push(config.default_port.clone());
+                }
+                Ok((value, ports))
+            })();
+
+            let _ = event_bus.send(crate::api::events::SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: "Router".into(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok((value, ports)) => json!({ "value": value, "ports": ports }),
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            match result {
+                Ok((_, ports)) => {
+                    tracing::debug!(node_id = %node_config.id, ports = ?ports, "Router routed");
+                    for port in ports {
+                        outbox.queue.push_back((Some(port), ticket.clone()));
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "Router expression failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
+
+fn evaluate_route(
+    config: &crate::components::RouterConfig,
+    engine: &Engine,
+    payload: &serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    use crate::components::RouterLanguage;
+
+    match config.language {
+        RouterLanguage::Jmespath => {
+            let expr = jmespath::compile(&config.expression)
+                .map_err(|e| anyhow::anyhow!("Invalid JMESPath expression: {}", e))?;
+            let result = expr
+                .search(payload)
+                .map_err(|e| anyhow::anyhow!("JMESPath evaluation failed: {}", e))?;
+            Ok(serde_json::to_value(&*result)?)
+        }
+        RouterLanguage::Rhai => {
+            let mut scope = Scope::new();
+            scope.push_dynamic(
+                "input",
+                rhai::serde::to_dynamic(payload).map_err(|e| anyhow::anyhow!("{}", e))?,
+            );
+            let result = engine
+                .eval_with_scope::<rhai::Dynamic>(&mut scope, &config.expression)
+                .map_err(|e| anyhow::anyhow!("Rhai evaluation failed: {}", e))?;
+            rhai::serde::from_dynamic(&result).map_err(|e| anyhow::anyhow!("{}", e))
+        }
+    }
+}
+
+/// JSON equality, except that scalars compare by text or number (`"200"` == `200` == `200.0`).
+fn loosely_equal(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    use serde_json::Value;
+
+    fn scalar_text(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    if expected == actual {
+        return true;
+    }
+    match (scalar_text(expected), scalar_text(actual)) {
+        (Some(a), Some(b)) => {
+            a == b || matches!((a.parse::<f64>(), b.parse::<f64>()), (Ok(x), Ok(y)) if x == y)
+        }
+        _ => false,
+    }
+}
@@ -1,12 +1,20 @@
 pub mod aggregator;
+pub mod anomaly;
+pub mod compression;
+pub mod datetime;
 pub mod expression;
+pub mod join;
 pub mod stats;
 pub mod splitter;
 pub mod transform;
 pub mod window;
 
 pub use self::aggregator::aggregator_worker;
+pub use self::anomaly::anomaly_worker;
+pub use self::compression::compression_worker;
+pub use self::datetime::datetime_worker;
 pub use self::expression::expression_worker;
+pub use self::join::join_worker;
 pub use self::stats::stats_worker;
 pub use self::splitter::splitter_worker;
 pub use self::transform::transform_worker;
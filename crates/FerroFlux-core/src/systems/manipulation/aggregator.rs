@@ -23,7 +23,7 @@ pub fn aggregator_worker(
     store: Res<BlobStore>,
     event_bus: Res<SystemEventBus>,
 ) {
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     for (config, node_config, mut state, mut inbox, mut outbox) in query.iter_mut() {
         // 1. Ingest
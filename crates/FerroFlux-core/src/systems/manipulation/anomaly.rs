@@ -0,0 +1,168 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::manipulation::{
+    AnomalyBaseline, AnomalyConfig, AnomalyMethod, AnomalyState,
+};
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use serde_json::{Value, json};
+use std::time::Instant;
+
+/// System: Anomaly Worker (Streaming Outlier Detection)
+///
+/// ## Architecture: Rolling Baselines
+/// Like the window node, this node keeps state between tickets, but instead of a raw buffer
+/// it keeps one compact baseline per key (see `AnomalyState`):
+/// - **EWMA**: the value is compared with the weighted mean, scaled by the weighted std dev.
+/// - **SeasonalNaive**: the value is compared with the one `season_length` samples earlier,
+///   scaled by the weighted std dev of past seasonal residuals.
+///
+/// Each value is scored against the baseline *before* it is folded in. Anomalous tickets go
+/// to the `anomaly` port, everything else to the default port, both enriched with the score.
+#[tracing::instrument(skip(query, store, event_bus))]
+pub fn anomaly_worker(
+    mut query: Query<(
+        &AnomalyConfig,
+        &NodeConfig,
+        &mut AnomalyState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+) {
+    let event_tx = event_bus.clone();
+
+    for (config, node_config, mut state, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let start = Instant::now();
+            let trace_id = ticket
+                .metadata
+                .get("trace_id")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let payload_bytes = match store.claim(&ticket) {
+                Ok(bytes) => bytes,
+                Err(_) => continue,
+            };
+
+            let mut json_val: Value = serde_json::from_slice(&payload_bytes).unwrap_or(Value::Null);
+
+            // 1. Extract value and key
+            let value = json_val.get(&config.target_field).and_then(|v| v.as_f64());
+            let key = match config.key_field.as_ref().and_then(|f| json_val.get(f)) {
+                Some(Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+                None => "default".to_string(),
+            };
+
+            let Some(value) = value else {
+                // Nothing to score; pass through untouched.
+                if let Ok(mut new_ticket) = store.check_in(&payload_bytes) {
+                    new_ticket.metadata = ticket.metadata;
+                    outbox.queue.push_back((None, new_ticket));
+                }
+                continue;
+            };
+
+            // 2. Score against the baseline, then update it
+            let baseline = state.baselines.entry(key.clone()).or_default();
+            let warmed_up = baseline.count >= config.warmup.max(1);
+            let scored = observe(baseline, config, value);
+            let is_anomaly =
+                warmed_up && scored.is_some_and(|(_, score)| score.abs() > config.threshold);
+
+            // 3. Enrich
+            let (expected, score) = match scored {
+                Some((expected, score)) => (json!(expected), score),
+                None => (Value::Null, 0.0),
+            };
+            let direction = if !is_anomaly {
+                Value::Null
+            } else if score > 0.0 {
+                json!("above")
+            } else {
+                json!("below")
+            };
+            let result = json!({
+                "key": key,
+                "expected": expected,
+                "score": score,
+                "direction": direction,
+                "is_anomaly": is_anomaly,
+            });
+            if let Some(obj) = json_val.as_object_mut() {
+                obj.insert(config.result_key.clone(), result.clone());
+            }
+
+            // 4. Output
+            if let Ok(bytes) = serde_json::to_vec(&json_val)
+                && let Ok(mut new_ticket) = store.check_in(&bytes)
+            {
+                new_ticket.metadata = ticket.metadata;
+                let port = is_anomaly.then(|| "anomaly".to_string());
+                outbox.queue.push_back((port, new_ticket));
+            }
+
+            let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                node_id: node_config.id,
+                node_type: "Anomaly".to_string(),
+                trace_id,
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: true,
+                details: result,
+            });
+        }
+    }
+}
+
+/// Scores `value` against `baseline` and folds it in.
+///
+/// Returns the expected value and the signed score, or `None` while the baseline has nothing
+/// to compare against yet.
+fn observe(
+    baseline: &mut AnomalyBaseline,
+    config: &AnomalyConfig,
+    value: f64,
+) -> Option<(f64, f64)> {
+    let alpha = config.alpha.clamp(f64::EPSILON, 1.0);
+    let season_length = config.season_length.max(1);
+
+    let expected = match config.method {
+        AnomalyMethod::Ewma => (baseline.count > 0).then_some(baseline.mean),
+        AnomalyMethod::SeasonalNaive => (baseline.history.len() == season_length)
+            .then(|| baseline.history.front().copied())
+            .flatten(),
+    };
+    // A flat baseline makes any change infinitely surprising; keep the score finite for JSON.
+    let scored = expected.map(|expected| {
+        let std_dev = baseline.variance.sqrt().max(f64::EPSILON);
+        (expected, (value - expected) / std_dev)
+    });
+
+    match config.method {
+        AnomalyMethod::Ewma => {
+            if baseline.count == 0 {
+                baseline.mean = value;
+            } else {
+                let diff = value - baseline.mean;
+                let increment = alpha * diff;
+                baseline.mean += increment;
+                baseline.variance = (1.0 - alpha) * (baseline.variance + diff * increment);
+            }
+        }
+        AnomalyMethod::SeasonalNaive => {
+            if let Some(expected) = expected {
+                let residual = value - expected;
+                baseline.variance = (1.0 - alpha) * baseline.variance + alpha * residual * residual;
+            }
+            baseline.history.push_back(value);
+            if baseline.history.len() > season_length {
+                baseline.history.pop_front();
+            }
+        }
+    }
+    baseline.count += 1;
+    scored
+}
@@ -0,0 +1,210 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::io::FileMode;
+use crate::components::manipulation::{CompressionConfig, CompressionFormat, CompressionOperation};
+use crate::store::BlobStore;
+use base64::{Engine as _, engine::general_purpose};
+use bevy_ecs::prelude::*;
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::{Value, json};
+use std::io::{Cursor, Read, Write};
+use std::time::Instant;
+
+/// System: Compression Worker
+///
+/// Compresses a payload (or one field of it) to base64-encoded gzip/zstd, or reverses that,
+/// extracting zip archives entry by entry. Decompressed output is capped at
+/// `max_output_bytes`; oversized, corrupt or unrecognised input routes to `error`.
+#[tracing::instrument(skip(query, store, event_bus))]
+pub fn compression_worker(
+    mut query: Query<(&CompressionConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+) {
+    for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let start = Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<(Vec<u8>, Value)> {
+                let data = store.claim(&ticket)?;
+                let payload: Option<Value> = serde_json::from_slice(&data).ok();
+                let input = read_input(config, payload.as_ref(), &data)?;
+
+                let (format, result) = match config.operation {
+                    CompressionOperation::Compress => {
+                        let (format, bytes) = compress(&config.format, &input)?;
+                        (format, json!(general_purpose::STANDARD.encode(bytes)))
+                    }
+                    CompressionOperation::Decompress => {
+                        let format = match config.format {
+                            CompressionFormat::Auto => detect(&input)?,
+                            ref format => format.clone(),
+                        };
+                        let result = decompress(&format, &input, config)?;
+                        (format, result)
+                    }
+                };
+
+                let details = json!({
+                    "operation": config.operation,
+                    "format": format,
+                    "input_bytes": input.len(),
+                });
+                let output = match &config.result_key {
+                    Some(key) => {
+                        let mut output = match payload {
+                            Some(Value::Object(map)) => map,
+                            _ => serde_json::Map::new(),
+                        };
+                        output.insert(key.clone(), result);
+                        serde_json::to_vec(&output)?
+                    }
+                    None => serde_json::to_vec(&result)?,
+                };
+                Ok((output, details))
+            })();
+
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: "Compression".into(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok((bytes, details)) => {
+                        let mut details = details.clone();
+                        details["output_bytes"] = json!(bytes.len());
+                        details
+                    }
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            match result.and_then(|(bytes, _)| store.check_in(&bytes)) {
+                Ok(mut new_ticket) => {
+                    new_ticket.metadata = ticket.metadata;
+                    outbox.queue.push_back((None, new_ticket));
+                }
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "Compression failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
+
+/// Collects the bytes to work on. Compressed input is expected as base64 when it arrives as
+/// a JSON string; anything else is taken as-is.
+fn read_input(
+    config: &CompressionConfig,
+    payload: Option<&Value>,
+    raw: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let value = match &config.source_field {
+        Some(field) => payload
+            .and_then(|p| p.get(field))
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| anyhow::anyhow!("Field '{}' not found", field))?,
+        None => match payload {
+            Some(value @ Value::String(_)) => value,
+            _ => return Ok(raw.to_vec()),
+        },
+    };
+    Ok(match (&config.operation, value) {
+        (CompressionOperation::Decompress, Value::String(s)) => {
+            general_purpose::STANDARD.decode(s.trim())?
+        }
+        (CompressionOperation::Decompress, _) => {
+            anyhow::bail!("Compressed input must be a base64 string")
+        }
+        (CompressionOperation::Compress, Value::String(s)) => s.clone().into_bytes(),
+        (CompressionOperation::Compress, other) => serde_json::to_vec(other)?,
+    })
+}
+
+fn detect(bytes: &[u8]) -> anyhow::Result<CompressionFormat> {
+    match bytes {
+        [0x1f, 0x8b, ..] => Ok(CompressionFormat::Gzip),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Ok(CompressionFormat::Zstd),
+        [b'P', b'K', 0x03, 0x04, ..] | [b'P', b'K', 0x05, 0x06, ..] => Ok(CompressionFormat::Zip),
+        _ => anyhow::bail!("Input is not gzip, zstd or zip data"),
+    }
+}
+
+fn compress(
+    format: &CompressionFormat,
+    input: &[u8],
+) -> anyhow::Result<(CompressionFormat, Vec<u8>)> {
+    match format {
+        CompressionFormat::Auto | CompressionFormat::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(input)?;
+            Ok((CompressionFormat::Gzip, encoder.finish()?))
+        }
+        CompressionFormat::Zstd => Ok((CompressionFormat::Zstd, zstd::encode_all(input, 0)?)),
+        CompressionFormat::Zip => anyhow::bail!("Zip archives can only be extracted"),
+    }
+}
+
+fn decompress(
+    format: &CompressionFormat,
+    input: &[u8],
+    config: &CompressionConfig,
+) -> anyhow::Result<Value> {
+    let mut budget = config.max_output_bytes;
+    match format {
+        CompressionFormat::Auto => unreachable!("format is detected before decompression"),
+        CompressionFormat::Gzip => {
+            let bytes = read_limited(MultiGzDecoder::new(input), &mut budget, config)?;
+            to_value(bytes, &config.mode)
+        }
+        CompressionFormat::Zstd => {
+            let bytes = read_limited(zstd::Decoder::new(input)?, &mut budget, config)?;
+            to_value(bytes, &config.mode)
+        }
+        CompressionFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(input))?;
+            let mut entries = serde_json::Map::new();
+            for index in 0..archive.len() {
+                let entry = archive.by_index(index)?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let name = entry.name().to_string();
+                let bytes = read_limited(entry, &mut budget, config)?;
+                entries.insert(name, to_value(bytes, &config.mode)?);
+            }
+            Ok(Value::Object(entries))
+        }
+    }
+}
+
+/// Reads until EOF, failing as soon as the remaining `budget` is exceeded.
+fn read_limited(
+    reader: impl Read,
+    budget: &mut u64,
+    config: &CompressionConfig,
+) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(*budget + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > *budget {
+        anyhow::bail!(
+            "Decompressed size exceeds the limit of {} bytes",
+            config.max_output_bytes
+        );
+    }
+    *budget -= bytes.len() as u64;
+    Ok(bytes)
+}
+
+fn to_value(bytes: Vec<u8>, mode: &FileMode) -> anyhow::Result<Value> {
+    Ok(match mode {
+        FileMode::Text => Value::String(String::from_utf8(bytes)?),
+        FileMode::Bytes => Value::String(general_purpose::STANDARD.encode(bytes)),
+        FileMode::Json => serde_json::from_slice(&bytes)?,
+    })
+}
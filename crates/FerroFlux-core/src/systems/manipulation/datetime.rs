@@ -0,0 +1,216 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::manipulation::{DateTimeConfig, TimeFormat, TimeUnit};
+use crate::store::BlobStore;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde_json::{Value, json};
+use std::fmt::Write as _;
+use std::time::Instant;
+
+/// Offset-less layouts tried by `TimeFormat::Auto`, interpreted in `input_timezone`.
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// System: Date/Time Worker
+///
+/// Stateless like the transform node: each ticket's timestamp is parsed, converted, shifted,
+/// truncated and formatted (see `DateTimeConfig`), and the result is written under
+/// `result_key`. Unparseable input routes the ticket to `error`.
+#[tracing::instrument(skip(query, store, event_bus))]
+pub fn datetime_worker(
+    mut query: Query<(&DateTimeConfig, &NodeConfig, &mut Inbox, &mut Outbox)>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+) {
+    for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let start = Instant::now();
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+
+            let result = (|| -> anyhow::Result<(Vec<u8>, Value)> {
+                let data = store.claim(&ticket)?;
+                let payload: Value = serde_json::from_slice(&data).unwrap_or(Value::Null);
+                let result = evaluate(config, &payload)?;
+
+                let mut output = match payload {
+                    Value::Object(map) => map,
+                    _ => serde_json::Map::new(),
+                };
+                output.insert(config.result_key.clone(), result.clone());
+                Ok((serde_json::to_vec(&output)?, result))
+            })();
+
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: "DateTime".into(),
+                execution_ms: start.elapsed().as_millis() as u64,
+                success: result.is_ok(),
+                details: match &result {
+                    Ok((_, value)) => json!({ "result": value }),
+                    Err(e) => json!({ "error": e.to_string() }),
+                },
+            });
+
+            match result.and_then(|(bytes, _)| store.check_in(&bytes)) {
+                Ok(mut new_ticket) => {
+                    new_ticket.metadata = ticket.metadata;
+                    outbox.queue.push_back((None, new_ticket));
+                }
+                Err(e) => {
+                    tracing::error!(node_id = %node_config.id, error = %e, "Date/time conversion failed");
+                    outbox.queue.push_back((Some("error".into()), ticket));
+                }
+            }
+        }
+    }
+}
+
+fn evaluate(config: &DateTimeConfig, payload: &Value) -> anyhow::Result<Value> {
+    let input_tz = parse_timezone(config.input_timezone.as_deref())?;
+    let tz = parse_timezone(config.timezone.as_deref())?;
+
+    let utc = match &config.source_field {
+        Some(field) => {
+            let value = payload
+                .get(field)
+                .filter(|v| !v.is_null())
+                .ok_or_else(|| anyhow::anyhow!("Field '{}' not found", field))?;
+            parse_input(value, &config.input_format, input_tz)?
+        }
+        None => Utc::now(),
+    };
+
+    let mut local = utc.with_timezone(&tz);
+    if let Some(shift) = &config.shift {
+        local = local
+            .checked_add_signed(parse_shift(shift)?)
+            .ok_or_else(|| anyhow::anyhow!("Shift '{}' is out of range", shift))?;
+    }
+    if let Some(unit) = &config.truncate {
+        local = truncate(local, unit)?;
+    }
+    format_output(&local, &config.output_format)
+}
+
+fn parse_timezone(name: Option<&str>) -> anyhow::Result<Tz> {
+    match name {
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| anyhow::anyhow!("Unknown timezone '{}'", name)),
+        None => Ok(Tz::UTC),
+    }
+}
+
+fn parse_input(value: &Value, format: &TimeFormat, input_tz: Tz) -> anyhow::Result<DateTime<Utc>> {
+    let text = value.as_str();
+    let number = value
+        .as_f64()
+        .or_else(|| text.and_then(|s| s.trim().parse::<f64>().ok()));
+    let invalid = || anyhow::anyhow!("Cannot parse '{}' as {:?}", value, format);
+
+    let parsed = match format {
+        TimeFormat::EpochSeconds => number.and_then(|secs| from_epoch_millis(secs * 1000.0)),
+        TimeFormat::EpochMillis => number.and_then(from_epoch_millis),
+        TimeFormat::Iso8601 => text.and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok()),
+        TimeFormat::Rfc2822 => text.and_then(|s| DateTime::parse_from_rfc2822(s.trim()).ok()),
+        TimeFormat::Custom(pattern) => text.and_then(|s| {
+            DateTime::parse_from_str(s, pattern).ok().or_else(|| {
+                NaiveDateTime::parse_from_str(s, pattern)
+                    .ok()
+                    .or_else(|| {
+                        NaiveDate::parse_from_str(s, pattern)
+                            .ok()
+                            .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    })
+                    .and_then(|naive| localize(input_tz, naive))
+            })
+        }),
+        TimeFormat::Auto => match number {
+            // Anything past the year 5138 in seconds is taken to be milliseconds.
+            Some(n) if n.abs() >= 1e11 => from_epoch_millis(n),
+            Some(n) => from_epoch_millis(n * 1000.0),
+            None => text.map(str::trim).and_then(|s| {
+                DateTime::parse_from_rfc3339(s)
+                    .or_else(|_| DateTime::parse_from_rfc2822(s))
+                    .ok()
+                    .or_else(|| {
+                        NAIVE_FORMATS
+                            .iter()
+                            .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+                            .or_else(|| {
+                                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                                    .ok()
+                                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                            })
+                            .and_then(|naive| localize(input_tz, naive))
+                    })
+            }),
+        },
+    };
+    parsed.map(|dt| dt.with_timezone(&Utc)).ok_or_else(invalid)
+}
+
+fn from_epoch_millis(millis: f64) -> Option<DateTime<chrono::FixedOffset>> {
+    DateTime::from_timestamp_millis(millis.round() as i64).map(|dt| dt.fixed_offset())
+}
+
+/// Pins a wall-clock time to `tz`. Times skipped by a DST change don't exist and yield `None`.
+fn localize(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<chrono::FixedOffset>> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+}
+
+/// Parses `+1h 30m`, `-7days`, `15min`, ...
+fn parse_shift(shift: &str) -> anyhow::Result<Duration> {
+    let shift = shift.trim();
+    let (negative, magnitude) = match shift.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, shift.strip_prefix('+').unwrap_or(shift)),
+    };
+    let duration = humantime::parse_duration(magnitude.trim())
+        .map_err(|e| anyhow::anyhow!("Invalid shift '{}': {}", shift, e))?;
+    let duration = Duration::from_std(duration)?;
+    Ok(if negative { -duration } else { duration })
+}
+
+fn truncate(dt: DateTime<Tz>, unit: &TimeUnit) -> anyhow::Result<DateTime<Tz>> {
+    let date = dt.date_naive();
+    let naive = match unit {
+        TimeUnit::Minute => date.and_hms_opt(dt.hour(), dt.minute(), 0),
+        TimeUnit::Hour => date.and_hms_opt(dt.hour(), 0, 0),
+        TimeUnit::Day => date.and_hms_opt(0, 0, 0),
+        TimeUnit::Week => {
+            (date - Duration::days(dt.weekday().num_days_from_monday() as i64)).and_hms_opt(0, 0, 0)
+        }
+        TimeUnit::Month => date.with_day(1).and_then(|d| d.and_hms_opt(0, 0, 0)),
+    }
+    .ok_or_else(|| anyhow::anyhow!("Cannot truncate {} to {:?}", dt, unit))?;
+    dt.timezone()
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow::anyhow!("{} does not exist in {}", naive, dt.timezone()))
+}
+
+fn format_output(dt: &DateTime<Tz>, format: &TimeFormat) -> anyhow::Result<Value> {
+    Ok(match format {
+        TimeFormat::Auto | TimeFormat::Iso8601 => json!(dt.to_rfc3339()),
+        TimeFormat::Rfc2822 => json!(dt.to_rfc2822()),
+        TimeFormat::EpochSeconds => json!(dt.timestamp()),
+        TimeFormat::EpochMillis => json!(dt.timestamp_millis()),
+        TimeFormat::Custom(pattern) => {
+            // `to_string()` would panic on an invalid pattern; `write!` reports it instead.
+            let mut out = String::new();
+            write!(out, "{}", dt.format(pattern))
+                .map_err(|_| anyhow::anyhow!("Invalid format pattern '{}'", pattern))?;
+            json!(out)
+        }
+    })
+}
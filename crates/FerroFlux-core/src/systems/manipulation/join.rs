@@ -0,0 +1,167 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::core::{Inbox, NodeConfig, Outbox};
+use crate::components::manipulation::{JoinConfig, JoinState, PendingJoin};
+use crate::store::BlobStore;
+use crate::systems::transport::INPUT_PORT_KEY;
+use bevy_ecs::prelude::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// System: Join Worker (Fan-In Rejoin)
+///
+/// Buffers tickets per correlation key until every configured input port has delivered,
+/// then emits one merged ticket keyed by port name.
+/// - **Stateful**: Uses `JoinState`; incomplete groups are expired every tick, not only
+///   when new tickets arrive.
+/// - Relies on the transport stamping `input_port`, so edges into a Join must set `target_handle`.
+#[tracing::instrument(skip(query, store, event_bus))]
+pub fn join_worker(
+    mut query: Query<(
+        &JoinConfig,
+        &NodeConfig,
+        &mut JoinState,
+        &mut Inbox,
+        &mut Outbox,
+    )>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+) {
+    let event_tx = event_bus.clone();
+
+    for (config, node_config, mut state, mut inbox, mut outbox) in query.iter_mut() {
+        // 1. Ingest
+        while let Some(ticket) = inbox.queue.pop_front() {
+            let port = ticket.metadata.get(INPUT_PORT_KEY).cloned();
+            let payload = store
+                .claim(&ticket)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok());
+
+            let (Some(port), Some(payload)) = (port, payload) else {
+                tracing::warn!(node_id = %node_config.id, "Join received a ticket without an input port or JSON payload");
+                outbox.queue.push_back((Some("error".into()), ticket));
+                continue;
+            };
+            if !config.inputs.contains(&port) {
+                tracing::warn!(node_id = %node_config.id, port = %port, "Join received a ticket on an undeclared port");
+                outbox.queue.push_back((Some("error".into()), ticket));
+                continue;
+            }
+            let key = match &config.correlation_field {
+                Some(field) => match payload.get(field) {
+                    Some(Value::String(s)) => Some(s.clone()),
+                    Some(Value::Null) | None => None,
+                    Some(other) => Some(other.to_string()),
+                },
+                None => ticket.metadata.get("trace_id").cloned(),
+            };
+            let Some(key) = key else {
+                tracing::warn!(node_id = %node_config.id, "Join received a ticket without a correlation key");
+                outbox.queue.push_back((Some("error".into()), ticket));
+                continue;
+            };
+
+            let pending = state
+                .pending
+                .entry(key.clone())
+                .or_insert_with(|| PendingJoin {
+                    started: Instant::now(),
+                    metadata: ticket.metadata.clone(),
+                    parts: serde_json::Map::new(),
+                });
+            pending.parts.insert(port, payload);
+
+            if config
+                .inputs
+                .iter()
+                .all(|input| pending.parts.contains_key(input))
+                && let Some(pending) = state.pending.remove(&key)
+            {
+                let elapsed = pending.started.elapsed();
+                emit(
+                    &store,
+                    &mut outbox,
+                    None,
+                    Value::Object(pending.parts),
+                    pending.metadata.clone(),
+                );
+                let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                    node_id: node_config.id,
+                    node_type: "Join".to_string(),
+                    trace_id: pending
+                        .metadata
+                        .get("trace_id")
+                        .cloned()
+                        .unwrap_or_default(),
+                    execution_ms: elapsed.as_millis() as u64,
+                    success: true,
+                    details: json!({ "key": key, "inputs": config.inputs.len() }),
+                });
+            }
+        }
+
+        // 2. Expire incomplete groups
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let expired: Vec<String> = state
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.started.elapsed() >= timeout)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in expired {
+            let Some(pending) = state.pending.remove(&key) else {
+                continue;
+            };
+            let missing: Vec<&String> = config
+                .inputs
+                .iter()
+                .filter(|input| !pending.parts.contains_key(*input))
+                .collect();
+            tracing::warn!(node_id = %node_config.id, key = %key, missing = ?missing, "Join timed out");
+
+            let _ = event_tx.send(SystemEvent::NodeTelemetry {
+                node_id: node_config.id,
+                node_type: "Join".to_string(),
+                trace_id: pending
+                    .metadata
+                    .get("trace_id")
+                    .cloned()
+                    .unwrap_or_default(),
+                execution_ms: pending.started.elapsed().as_millis() as u64,
+                success: false,
+                details: json!({ "key": key, "timed_out": true, "missing": missing }),
+            });
+
+            let partial = Value::Object(pending.parts);
+            if config.emit_partial {
+                emit(&store, &mut outbox, None, partial, pending.metadata);
+            } else {
+                let report = json!({ "partial": partial, "missing": missing });
+                emit(
+                    &store,
+                    &mut outbox,
+                    Some("error".into()),
+                    report,
+                    pending.metadata,
+                );
+            }
+        }
+    }
+}
+
+fn emit(
+    store: &BlobStore,
+    outbox: &mut Outbox,
+    port: Option<String>,
+    value: Value,
+    mut metadata: HashMap<String, String>,
+) {
+    metadata.remove(INPUT_PORT_KEY);
+    if let Ok(bytes) = serde_json::to_vec(&value)
+        && let Ok(ticket) = store.check_in_with_metadata(&bytes, metadata)
+    {
+        outbox.queue.push_back((port, ticket));
+    }
+}
@@ -18,7 +18,7 @@ pub fn splitter_worker(
     store: Res<BlobStore>,
     event_bus: Res<SystemEventBus>,
 ) {
-    let event_tx = event_bus.0.clone();
+    let event_tx = event_bus.clone();
 
     for (config, node_config, mut inbox, mut outbox) in query.iter_mut() {
         while let Some(ticket) = inbox.queue.pop_front() {
@@ -1,3 +1,4 @@
+use crate::resources::profiler::profiled;
 use bevy_ecs::prelude::*;
 
 pub mod agent;
@@ -13,7 +14,10 @@ pub mod logic;
 pub mod manipulation;
 pub mod observability;
 pub mod pipeline;
+pub mod replay;
 pub mod scheduler;
+pub mod settings;
+pub mod shadow;
 pub mod transport;
 pub mod utils;
 
@@ -27,37 +31,94 @@ pub use scheduler::*;
 pub use transport::*;
 
 /// Registers all core systems to the schedule.
+///
+/// Each system is wrapped with [`profiled`] so it shows up in `EngineStats`.
 pub fn register_core_systems(schedule: &mut Schedule) {
     schedule.add_systems((
-        scheduler::scheduler_worker,
-        gateway::ingest_webhooks,
-        logic::switch_worker_safe,
-        logic::script_worker,
-        agent::agent_prep,
-        agent::agent_exec,
-        agent::agent_post,
-        io::http_worker,
+        profiled(scheduler::scheduler_worker),
+        profiled(gateway::ingest_webhooks),
+        profiled(logic::switch_worker_safe),
+        profiled(logic::script_worker),
+        profiled(agent::agent_prep),
+        profiled(agent::agent_exec),
+        profiled(agent::agent_post),
+        profiled(io::http_worker),
+        profiled(settings::settings_worker),
+    ));
+
+    // Shadow and replayed tickets must be answered before any side-effecting worker takes them.
+    schedule.add_systems(
+        (
+            profiled(shadow::shadow_worker),
+            profiled(replay::replay_worker),
+        )
+            .before(agent::agent_prep)
+            .before(io::http_worker)
+            .before(io::file_worker)
+            .before(io::browser_worker)
+            .before(io::speech_worker)
+            .before(io::embedding_worker)
+            .before(connectors::amqp_sink_worker)
+            .before(connectors::email_worker)
+            .before(connectors::ftp_worker)
+            .before(connectors::redis_worker)
+            .before(connectors::sql_query_worker)
+            .before(connectors::ssh_worker)
+            .before(connectors::websocket_worker),
+    );
+
+    schedule.add_systems((
+        profiled(transport::update_graph_topology), // Optimization: Needs to run before transport
+        profiled(transport::transport_worker),
+        // Must observe outboxes before transport drains them
+        profiled(connectors::amqp_ack_worker).before(transport::transport_worker),
+        profiled(observability::run_waiter_worker).before(transport::transport_worker),
+        profiled(replay::io_recorder_worker).before(transport::transport_worker),
+        profiled(janitor::janitor_worker),
+        profiled(manipulation::splitter_worker),
+        profiled(compute::wasm_worker),
+        profiled(compute::plugin_worker),
+        profiled(observability::telemetry_worker),
     ));
 
     schedule.add_systems((
-        transport::update_graph_topology, // Optimization: Needs to run before transport
-        transport::transport_worker,
-        janitor::janitor_worker,
-        manipulation::splitter_worker,
-        compute::wasm_worker,
-        observability::telemetry_worker,
+        profiled(manipulation::aggregator_worker),
+        profiled(manipulation::transform_worker),
+        profiled(manipulation::stats_worker),
+        profiled(manipulation::window_worker),
+        profiled(manipulation::anomaly_worker),
+        profiled(manipulation::expression_worker),
+        profiled(control::checkpoint_worker),
+        profiled(control::counter_worker),
+        profiled(connectors::rss_worker),
+        profiled(connectors::xml_worker),
+        profiled(connectors::ftp_worker),
+        profiled(connectors::ssh_worker),
+        profiled(connectors::amqp_source_worker),
+        profiled(connectors::amqp_sink_worker),
+        profiled(connectors::sql_query_worker),
+        profiled(connectors::redis_worker),
+        profiled(connectors::email_worker),
+        profiled(connectors::imap_worker),
+        profiled(connectors::websocket_worker),
+        profiled(connectors::sse_worker),
     ));
 
+    // Bevy caps a system tuple at 20 entries.
     schedule.add_systems((
-        manipulation::aggregator_worker,
-        manipulation::transform_worker,
-        manipulation::stats_worker,
-        manipulation::window_worker,
-        manipulation::expression_worker,
-        control::checkpoint_worker,
-        connectors::rss_worker,
-        connectors::xml_worker,
-        connectors::ftp_worker,
-        connectors::ssh_worker,
+        profiled(connectors::geoip_worker),
+        profiled(io::file_worker),
+        profiled(manipulation::datetime_worker),
+        profiled(manipulation::compression_worker),
+        profiled(logic::contract_worker),
+        profiled(connectors::connection_health_worker),
+        profiled(logic::router_worker),
+        profiled(manipulation::join_worker),
+        profiled(control::rate_limit_worker),
+        profiled(io::browser_worker),
+        profiled(io::speech_worker),
+        profiled(io::embedding_worker),
+        profiled(io::vector_search_worker),
+        profiled(crate::nodes::loader::node_definition_watcher),
     ));
 }
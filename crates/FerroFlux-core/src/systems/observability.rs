@@ -1,8 +1,13 @@
+use crate::api::RunError;
 use crate::api::events::SystemEventBus;
 use crate::components::observability::*;
-use crate::resources::WorkDone;
+use crate::components::{Inbox, Outbox};
+use crate::resources::{CancelledRuns, GraphTopology, PendingRuns, WorkDone};
+use crate::store::{BlobStore, SecureTicket};
 use bevy_ecs::prelude::*;
 use chrono::Utc;
+use std::collections::HashSet;
+use std::time::Instant;
 use uuid::Uuid;
 
 /// System: Telemetry System (The Observer)
@@ -13,7 +18,7 @@ use uuid::Uuid;
 pub fn telemetry_worker(
     _event_bus: Res<SystemEventBus>,
     _query: Query<(Entity, &Trace, &TraceNode, &TraceStart, &TraceInput)>,
-    mut _work_done: ResMut<WorkDone>,
+    _work_done: Res<WorkDone>,
 ) {
     // This system could theoretically watch for changes and emit events.
     // For now, let's focus on emitting NodeTelemetry when a node completes.
@@ -37,3 +42,107 @@ pub fn spawn_trace(
         ))
         .id()
 }
+
+/// System: Run Waiter
+///
+/// Follows `TriggerAndWait` runs by trace id, inspecting outboxes before `transport_worker`
+/// drains them:
+/// - A payload emitted on a port with no outgoing edge reached a terminal node and is collected.
+/// - A payload emitted on such a port named `error` fails the run with `RunError::Failed`.
+/// - Once a run has results and none of its tickets wait in an inbox or outbox, they are sent.
+///   Tickets held by in-flight async calls (HTTP, agents) aren't visible here, so a parallel
+///   branch still waiting on one is not included.
+/// - A run past its deadline fails with `RunError::TimedOut`.
+/// - A run its tenant cancelled fails with `RunError::Cancelled`, whatever it collected so far.
+#[tracing::instrument(skip(inboxes, outboxes, topology, store, pending, cancelled))]
+pub fn run_waiter_worker(
+    inboxes: Query<&Inbox>,
+    outboxes: Query<(Entity, &Outbox)>,
+    topology: Res<GraphTopology>,
+    store: Res<BlobStore>,
+    pending: Option<ResMut<PendingRuns>>,
+    cancelled: Option<Res<CancelledRuns>>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    if pending.0.is_empty() {
+        return;
+    }
+
+    let trace_of = |ticket: &SecureTicket| {
+        ticket
+            .metadata
+            .get("trace_id")
+            .filter(|trace_id| pending.0.contains_key(*trace_id))
+            .cloned()
+    };
+    let mut busy = HashSet::new();
+    let mut arrived = Vec::new();
+    for inbox in inboxes.iter() {
+        busy.extend(inbox.queue.iter().filter_map(trace_of));
+    }
+    for (entity, outbox) in outboxes.iter() {
+        let edges = topology.adjacency.get(&entity);
+        for (port, ticket) in &outbox.queue {
+            let Some(trace_id) = trace_of(ticket) else {
+                continue;
+            };
+            if edges.is_some_and(|e| e.iter().any(|(handle, _)| handle == port)) {
+                busy.insert(trace_id);
+            } else {
+                let failed = port
+                    .as_deref()
+                    .is_some_and(|p| p.eq_ignore_ascii_case("error"));
+                arrived.push((trace_id, ticket.clone(), failed));
+            }
+        }
+    }
+
+    let mut failures = Vec::new();
+    for (trace_id, ticket, failed) in arrived {
+        let Some(run) = pending.0.get_mut(&trace_id) else {
+            continue;
+        };
+        if !run.seen.insert(ticket.id) {
+            continue;
+        }
+        let payload = store
+            .claim(&ticket)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or(serde_json::Value::Null);
+        if failed {
+            failures.push((trace_id, payload));
+        } else {
+            run.results.push(payload);
+        }
+    }
+    for (trace_id, payload) in failures {
+        if let Some(run) = pending.0.remove(&trace_id) {
+            tracing::warn!(%trace_id, "Awaited run failed");
+            run.reply.send(Err(RunError::Failed(payload)));
+        }
+    }
+
+    let now = Instant::now();
+    pending.0.retain(|trace_id, run| {
+        if cancelled
+            .as_deref()
+            .is_some_and(|cancelled| cancelled.is_cancelled(Some(&run.tenant), trace_id))
+        {
+            tracing::info!(%trace_id, "Awaited run cancelled");
+            run.reply.send(Err(RunError::Cancelled));
+            false
+        } else if !run.results.is_empty() && !busy.contains(trace_id) {
+            run.reply.send(Ok(std::mem::take(&mut run.results)));
+            false
+        } else if run.deadline <= now {
+            tracing::warn!(%trace_id, "Awaited run timed out");
+            run.reply.send(Err(RunError::TimedOut));
+            false
+        } else {
+            true
+        }
+    });
+}
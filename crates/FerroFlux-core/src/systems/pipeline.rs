@@ -14,6 +14,7 @@ use std::collections::HashMap;
 ///
 /// NOTE: In a real implementation, this would likely be an async system or spawned task.
 /// For this MVP, we execute synchronously when an "Exec" signal is received (implied).
+#[allow(clippy::type_complexity)]
 pub fn pipeline_execution_system(
     mut query: Query<(
         Entity,
@@ -21,13 +22,14 @@ pub fn pipeline_execution_system(
         &mut crate::components::Inbox,
         &mut crate::components::Outbox,
         Option<&crate::components::shadow::ShadowExecution>,
+        Option<&crate::components::core::NodeConfig>,
     )>,
     node_registry: Res<DefinitionRegistry>,
     tool_registry: Res<ToolRegistry>,
     store: Res<crate::store::BlobStore>,
     bus: Res<crate::api::events::SystemEventBus>,
 ) {
-    for (_entity, mut node, mut inbox, mut outbox, shadow_exec) in query.iter_mut() {
+    for (_entity, mut node, mut inbox, mut outbox, shadow_exec, node_config) in query.iter_mut() {
         while let Some(ticket) = inbox.queue.pop_front() {
             // 1. Load Data/Context
             if let Ok(data) = store.claim(&ticket) {
@@ -54,6 +56,7 @@ pub fn pipeline_execution_system(
                     ticket.metadata.get("trace_id").cloned().unwrap_or_default(),
                     Some(bus.clone()),
                     shadow_exec,
+                    node_config.and_then(|c| c.tenant_id.clone()),
                 ) {
                     Ok(ports) => ports,
                     Err(e) => {
@@ -103,6 +106,7 @@ pub fn execute_pipeline_node(
     trace_id: String,
     event_bus: Option<crate::api::events::SystemEventBus>,
     shadow_exec: Option<&crate::components::shadow::ShadowExecution>,
+    tenant_id: Option<ferroflux_iam::TenantId>,
 ) -> Result<Vec<String>> {
     let def = definitions
         .definitions
@@ -166,6 +170,7 @@ pub fn execute_pipeline_node(
             local: &mut ctx_map,
             memory: global_memory,
             trace_id: trace_id.clone(),
+            tenant_id: tenant_id.clone(),
             event_bus: event_bus.clone(),
             shadow_mode: shadow_exec.is_some(),
             shadow_masks: masks_ref,
@@ -213,6 +218,7 @@ pub fn execute_pipeline_node(
                     local: &mut ctx_map,
                     memory: global_memory,
                     trace_id: trace_id.clone(),
+                    tenant_id: tenant_id.clone(),
                     event_bus: event_bus.clone(),
                     shadow_mode: shadow_exec.is_some(),
                     shadow_masks: masks_ref,
@@ -280,7 +286,7 @@ pub fn execute_pipeline_node(
     // Emit Node Completion Event
     // This allows UI/Trace to see the final outputs of this node execution
     if let Some(bus) = &event_bus {
-        let _ = bus.0.send(crate::api::events::SystemEvent::NodeTelemetry {
+        let _ = bus.send(crate::api::events::SystemEvent::NodeTelemetry {
             trace_id: trace_id.clone(),
             node_id: uuid::Uuid::default(), // TODO: Pass Node UUID or Entity ID?
             node_type: def.meta.name.clone(),
@@ -0,0 +1,187 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::shadow::SHADOW_KEY;
+use crate::components::{Inbox, NodeConfig, Outbox};
+use crate::resources::settings::RuntimeSettings;
+use crate::resources::{GraphTopology, ReplayRuns, TokioRuntime, WorkDone};
+use crate::store::BlobStore;
+use crate::store::database::{IoRecording, PersistentStore};
+use crate::systems::shadow::SideEffecting;
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde_json::json;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Ticket metadata key naming the recorded trace a replayed ticket belongs to.
+pub const REPLAY_OF_KEY: &str = "replay_of";
+
+/// System: I/O Recorder
+///
+/// **Role**: With `capture.io_results` on, stores every output of a source node (no incoming
+/// edges: webhooks, schedules, connector reads) and of a side-effecting node (see
+/// [`SideEffecting`]) in `PersistentStore`, keyed by node and trace. Untraced source output is
+/// given a `trace_id` first so the run can be found later. Shadow and replayed tickets are not
+/// recorded.
+///
+/// Must run before `transport_worker` drains the outboxes.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all)]
+pub fn io_recorder_worker(
+    mut outboxes: Query<(Entity, &NodeConfig, &mut Outbox)>,
+    side_effecting: Query<(), SideEffecting>,
+    topology: Res<GraphTopology>,
+    store: Res<BlobStore>,
+    settings: Option<Res<RuntimeSettings>>,
+    db: Option<Res<PersistentStore>>,
+    runtime: Option<Res<TokioRuntime>>,
+    mut recorded: Local<HashSet<Uuid>>,
+    mut targets: Local<(u64, HashSet<Entity>)>,
+) {
+    let (Some(db), Some(runtime)) = (db, runtime) else {
+        return;
+    };
+    if !RuntimeSettings::effective(settings.as_deref())
+        .capture
+        .io_results
+    {
+        return;
+    }
+    if targets.0 != topology.revision || targets.1.is_empty() {
+        targets.1 = topology
+            .adjacency
+            .values()
+            .flatten()
+            .map(|(_, target)| *target)
+            .collect();
+        targets.0 = topology.revision;
+    }
+
+    let mut queued = HashSet::new();
+    for (entity, node_config, mut outbox) in outboxes.iter_mut() {
+        let source = !targets.1.contains(&entity);
+        if outbox.queue.is_empty() || !(source || side_effecting.contains(entity)) {
+            continue;
+        }
+        for (port, ticket) in outbox.queue.iter_mut() {
+            queued.insert(ticket.id);
+            if recorded.contains(&ticket.id)
+                || ticket.metadata.contains_key(SHADOW_KEY)
+                || ticket.metadata.contains_key(REPLAY_OF_KEY)
+            {
+                continue;
+            }
+            if source && !ticket.metadata.contains_key("trace_id") {
+                ticket
+                    .metadata
+                    .insert("trace_id".to_string(), Uuid::new_v4().to_string());
+            }
+            let Some(trace_id) = ticket.metadata.get("trace_id").cloned() else {
+                continue;
+            };
+            let Ok(output) = store.claim(ticket) else {
+                continue;
+            };
+            recorded.insert(ticket.id);
+
+            let recording = IoRecording {
+                node_id: node_config.id,
+                port: port.clone(),
+                output,
+                metadata: ticket.metadata.clone(),
+            };
+            let tenant = node_config
+                .tenant_id
+                .clone()
+                .unwrap_or_else(|| TenantId::from("default_tenant"));
+            let db = PersistentStore::clone(&db);
+            runtime.0.spawn(async move {
+                if let Err(e) = db.record_io(&tenant, &trace_id, &recording).await {
+                    tracing::error!(%trace_id, error = %e, "Failed to record node output");
+                }
+            });
+        }
+    }
+    // Outputs stay queued while paused or when nothing is connected to them.
+    recorded.retain(|id| queued.contains(id));
+}
+
+/// System: Replay Interceptor
+///
+/// **Role**: Feeds recorded results back to side-effecting nodes reached by a run started with
+/// `ApiCommand::ReplayRun`, in the order they were recorded, so nothing touches the network.
+/// A node with no recorded result left drops the ticket and reports a failed `NodeTelemetry`.
+///
+/// Must run before every worker matched by [`SideEffecting`].
+#[allow(clippy::type_complexity)]
+#[tracing::instrument(skip_all)]
+pub fn replay_worker(
+    mut query: Query<(&NodeConfig, &mut Inbox, &mut Outbox), SideEffecting>,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+    runs: Option<ResMut<ReplayRuns>>,
+) {
+    let Some(mut runs) = runs else {
+        return;
+    };
+    if runs.is_empty() {
+        return;
+    }
+
+    for (node_config, mut inbox, mut outbox) in query.iter_mut() {
+        let trace_of = |metadata: &std::collections::HashMap<String, String>| {
+            metadata.get("trace_id").cloned()
+        };
+        if !inbox
+            .queue
+            .iter()
+            .any(|ticket| trace_of(&ticket.metadata).is_some_and(|t| runs.is_replaying(&t)))
+        {
+            continue;
+        }
+
+        let queued = std::mem::take(&mut inbox.queue);
+        for ticket in queued {
+            let Some(trace_id) =
+                trace_of(&ticket.metadata).filter(|trace_id| runs.is_replaying(trace_id))
+            else {
+                inbox.queue.push_back(ticket);
+                continue;
+            };
+            work_done.mark();
+
+            let Some(recording) = runs.next(&trace_id, node_config.id) else {
+                tracing::warn!(node_id = %node_config.id, %trace_id, "No recorded result to replay");
+                let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                    trace_id,
+                    node_id: node_config.id,
+                    node_type: node_config.node_type.clone(),
+                    execution_ms: 0,
+                    success: false,
+                    details: json!({ "replay": true, "error": "No recorded result" }),
+                });
+                continue;
+            };
+
+            // The recorded status and similar keys, but this replay's correlation ids.
+            let mut metadata = ticket.metadata.clone();
+            for (key, value) in recording.metadata {
+                if key != "trace_id" {
+                    metadata.insert(key, value);
+                }
+            }
+            let Ok(out) = store.check_in_with_metadata(&recording.output, metadata) else {
+                continue;
+            };
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: node_config.node_type.clone(),
+                execution_ms: 0,
+                success: true,
+                details: json!({ "replay": true }),
+            });
+            outbox.queue.push_back((recording.port, out));
+        }
+    }
+}
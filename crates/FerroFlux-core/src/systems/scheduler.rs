@@ -1,45 +1,69 @@
-use crate::components::{CronConfig, Frequency, Outbox, WorkDone};
+use crate::api::events::SystemEventBus;
+use crate::components::{CronConfig, NodeConfig, Outbox, WorkDone};
+use crate::resources::quotas::{TenantQuotas, admit_node_run, admit_run};
 use crate::store::BlobStore;
 use bevy_ecs::prelude::*;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Utc};
+use ferroflux_iam::TenantId;
 use std::collections::HashMap;
 
 /// Runtime component to track the next execution time.
 #[derive(Component)]
 pub struct NextRun(pub DateTime<Utc>);
 
-#[tracing::instrument(skip(commands, query, store, work_done))]
+/// Fires due Cron nodes. A tick is skipped, not queued, while the node's tenant is over its
+/// daily run quota.
+#[allow(clippy::type_complexity)]
+#[tracing::instrument(skip(commands, query, store, work_done, quotas, event_bus))]
 pub fn scheduler_worker(
     mut commands: Commands,
-    mut query: Query<(Entity, &CronConfig, Option<&mut NextRun>, &mut Outbox)>,
+    mut query: Query<(
+        Entity,
+        &CronConfig,
+        Option<&mut NextRun>,
+        &mut Outbox,
+        Option<&NodeConfig>,
+    )>,
     store: Res<BlobStore>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
+    quotas: Option<Res<TenantQuotas>>,
+    event_bus: Option<Res<SystemEventBus>>,
 ) {
     let now = Utc::now();
 
-    for (entity, config, mut next_run_opt, mut outbox) in query.iter_mut() {
+    for (entity, config, mut next_run_opt, mut outbox, node) in query.iter_mut() {
         match next_run_opt {
             Some(ref mut next_run) => {
                 if now >= next_run.0 {
                     tracing::info!(entity = ?entity, "Triggering Cron Node");
 
+                    let admitted = match node {
+                        Some(node) => {
+                            admit_node_run(quotas.as_deref(), event_bus.as_deref(), node, 0).is_ok()
+                        }
+                        None => admit_run(
+                            quotas.as_deref(),
+                            event_bus.as_deref(),
+                            &TenantId::from("default_tenant"),
+                            None,
+                            0,
+                        )
+                        .is_ok(),
+                    };
+
                     // Trigger: Push generic ticket
                     let mut metadata = HashMap::new();
                     metadata.insert("trigger".to_string(), "cron".to_string());
 
-                    if let Ok(ticket) = store.check_in_with_metadata(b"CRON_TRIGGER", metadata) {
+                    if admitted
+                        && let Ok(ticket) = store.check_in_with_metadata(b"CRON_TRIGGER", metadata)
+                    {
                         outbox.queue.push_back((None, ticket));
-                        work_done.0 = true;
+                        work_done.mark();
                     }
 
                     // Calculate next run
-                    let next = match config.frequency {
-                        Frequency::Once => None,
-                        Frequency::Minutes => Some(next_run.0 + Duration::minutes(1)),
-                        Frequency::Hourly => Some(next_run.0 + Duration::hours(1)),
-                        Frequency::Daily => Some(next_run.0 + Duration::days(1)),
-                        Frequency::Weekly => Some(next_run.0 + Duration::days(7)),
-                    };
+                    let next = config.frequency.interval().map(|step| next_run.0 + step);
 
                     match next {
                         Some(n) => {
@@ -0,0 +1,42 @@
+use crate::resources::settings::RuntimeSettings;
+use crate::resources::{AgentConcurrency, AgentConcurrencyLimit};
+use bevy_ecs::prelude::*;
+
+/// System: Settings Applier
+///
+/// Carries `RuntimeSettings` changes into resources that can't read them on demand. For now
+/// that is the `AgentConcurrency` semaphore: growing adds permits straight away, shrinking
+/// removes idle permits and takes the rest back on later ticks as running requests finish.
+pub fn settings_worker(
+    settings: Option<Res<RuntimeSettings>>,
+    concurrency: Option<Res<AgentConcurrency>>,
+    applied: Option<ResMut<AgentConcurrencyLimit>>,
+) {
+    let (Some(settings), Some(concurrency), Some(mut applied)) = (settings, concurrency, applied)
+    else {
+        return;
+    };
+    let target = settings.current().concurrency.max_agent_requests;
+    let current = applied.limit;
+
+    if target > current {
+        // Cancel any outstanding shrink before adding permits.
+        let cancelled = applied.owed.min(target - current);
+        applied.owed -= cancelled;
+        concurrency.0.add_permits(target - current - cancelled);
+    } else if target < current {
+        applied.owed += current - target;
+    }
+    if applied.owed > 0 {
+        let forgotten = concurrency.0.forget_permits(applied.owed);
+        applied.owed -= forgotten;
+    }
+    if target != current {
+        tracing::info!(
+            from = current,
+            to = target,
+            "Agent concurrency limit changed"
+        );
+        applied.limit = target;
+    }
+}
@@ -0,0 +1,129 @@
+use crate::api::events::{SystemEvent, SystemEventBus};
+use crate::components::connectors::{
+    AmqpSinkConfig, EmailConfig, FtpConfig, RedisConfig, SqlQueryConfig, SshConfig, WebSocketConfig,
+};
+use crate::components::shadow::{MockConfig, SHADOW_KEY, ShadowExecution};
+use crate::components::{
+    AgentConfig, BrowserConfig, EmbeddingConfig, FileIoConfig, HttpConfig, Inbox, NodeConfig,
+    Outbox, SpeechConfig,
+};
+use crate::resources::{ShadowRuns, WorkDone};
+use crate::store::{BlobStore, SecureTicket};
+use bevy_ecs::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Nodes that reach outside the engine: HTTP, agents, outbound connectors and local I/O.
+pub type SideEffecting = Or<(
+    With<HttpConfig>,
+    With<AgentConfig>,
+    With<AmqpSinkConfig>,
+    With<EmailConfig>,
+    With<FtpConfig>,
+    With<RedisConfig>,
+    With<SqlQueryConfig>,
+    With<SshConfig>,
+    With<WebSocketConfig>,
+    With<FileIoConfig>,
+    With<BrowserConfig>,
+    With<SpeechConfig>,
+    With<EmbeddingConfig>,
+)>;
+
+/// System: Shadow Interceptor
+///
+/// **Role**: Answers shadow tickets at side-effecting nodes before their workers see them.
+/// A ticket is shadowed when it carries the `shadow` flag, belongs to a trace in
+/// `ShadowRuns`, or waits at a node marked with `ShadowExecution`. The node emits its mock
+/// (looked up by node id, then node type) or, without one, passes its input through, and
+/// reports `NodeTelemetry` as if it had run. A mock's `delay_ms` is reported, not slept.
+///
+/// Must run before every worker matched by [`SideEffecting`].
+#[allow(clippy::type_complexity)]
+#[tracing::instrument(skip(query, store, event_bus, work_done, runs))]
+pub fn shadow_worker(
+    mut query: Query<
+        (
+            &NodeConfig,
+            Option<&ShadowExecution>,
+            &mut Inbox,
+            &mut Outbox,
+        ),
+        SideEffecting,
+    >,
+    store: Res<BlobStore>,
+    event_bus: Res<SystemEventBus>,
+    work_done: Res<WorkDone>,
+    runs: Option<Res<ShadowRuns>>,
+) {
+    let runs = runs.as_deref();
+    for (node_config, execution, mut inbox, mut outbox) in query.iter_mut() {
+        let shadowed = |ticket: &SecureTicket| execution.is_some() || is_shadow(runs, ticket);
+        if !inbox.queue.iter().any(shadowed) {
+            continue;
+        }
+
+        let queued = std::mem::take(&mut inbox.queue);
+        for ticket in queued {
+            if !shadowed(&ticket) {
+                inbox.queue.push_back(ticket);
+                continue;
+            }
+            let trace_id = ticket.metadata.get("trace_id").cloned().unwrap_or_default();
+            let mocks = runs
+                .and_then(|runs| runs.mocks(&trace_id))
+                .or(execution.map(|e| &e.mocked_tools));
+            let mock = mocks.and_then(|mocks| mock_for(mocks, node_config));
+
+            let output = match mock {
+                Some(mock) => serde_json::to_vec(&mock.return_value).ok(),
+                None => store.claim(&ticket).ok(),
+            };
+            let mut metadata = ticket.metadata.clone();
+            metadata.insert(SHADOW_KEY.to_string(), "true".to_string());
+            let Some(out) =
+                output.and_then(|bytes| store.check_in_with_metadata(&bytes, metadata).ok())
+            else {
+                tracing::warn!(node_id = %node_config.id, "Shadow run dropped a ticket");
+                continue;
+            };
+
+            tracing::info!(
+                node_id = %node_config.id,
+                node_type = %node_config.node_type,
+                mocked = mock.is_some(),
+                "Shadow Mode: Intercepted node execution"
+            );
+            let _ = event_bus.send(SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id: node_config.id,
+                node_type: node_config.node_type.clone(),
+                execution_ms: mock.map_or(0, |mock| mock.delay_ms),
+                success: true,
+                details: json!({ "shadow": true, "mocked": mock.is_some() }),
+            });
+            outbox.queue.push_back((None, out));
+            work_done.mark();
+        }
+    }
+}
+
+fn is_shadow(runs: Option<&ShadowRuns>, ticket: &SecureTicket) -> bool {
+    ticket
+        .metadata
+        .get(SHADOW_KEY)
+        .is_some_and(|flag| flag == "true")
+        || ticket
+            .metadata
+            .get("trace_id")
+            .is_some_and(|trace_id| runs.is_some_and(|runs| runs.mocks(trace_id).is_some()))
+}
+
+fn mock_for<'a>(
+    mocks: &'a HashMap<String, MockConfig>,
+    node_config: &NodeConfig,
+) -> Option<&'a MockConfig> {
+    mocks
+        .get(&node_config.id.to_string())
+        .or_else(|| mocks.get(&node_config.node_type))
+}
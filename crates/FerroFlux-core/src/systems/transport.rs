@@ -1,52 +1,79 @@
 use crate::api::events::{SystemEvent, SystemEventBus};
-use crate::components::{Edge, Inbox, Outbox, core::NodeConfig};
-use crate::resources::{GraphTopology, WorkDone};
-use crate::store::SecureTicket;
+use crate::components::{
+    BackpressurePolicy, BackpressureState, Edge, Inbox, InboxCapacity, Outbox, core::NodeConfig,
+};
+use crate::resources::chaos::{CHAOS_CLAIM_FAULT_KEY, Fault, FaultInjector};
+use crate::resources::connection_health::PausedWorkflows;
+use crate::resources::settings::RuntimeSettings;
+use crate::resources::{CancelledRuns, GraphTopology, WorkDone};
+use crate::store::{BlobStore, SecureTicket, spill};
 use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
 
 /// System: Update Graph Topology
 ///
 /// **Role**: maintains the `GraphTopology` resource, which is an optimized adjacency cache.
+/// Only edges added, changed or removed since the last run are applied, and despawned nodes
+/// take their edges out of the cache. The full rebuild only happens on the first run.
 #[allow(clippy::type_complexity)]
-#[tracing::instrument(skip(topology, changed_edges, edge_query, removed_edges))]
+#[tracing::instrument(skip(topology, changed_edges, edge_query, removed_edges, removed_nodes))]
 pub fn update_graph_topology(
     mut topology: ResMut<GraphTopology>,
-    changed_edges: Query<Entity, Or<(Changed<Edge>, Added<Edge>)>>,
-    edge_query: Query<&Edge>,
+    changed_edges: Query<(Entity, &Edge), Changed<Edge>>,
+    edge_query: Query<(Entity, &Edge)>,
     mut removed_edges: RemovedComponents<Edge>,
+    mut removed_nodes: RemovedComponents<NodeConfig>,
 ) {
-    let mut needs_rebuild = false;
-
-    // Rebuild if any edge changed/added or if the cache is currently empty but world has edges.
-    if !changed_edges.is_empty() {
-        needs_rebuild = true;
+    // Startup, tests, or a replaced resource: the world's edges were never indexed.
+    if !topology.is_built() {
+        tracing::debug!("Graph topology uninitialized, building adjacency cache");
+        removed_edges.clear();
+        removed_nodes.clear();
+        topology.rebuild(edge_query.iter());
+        return;
     }
 
-    if removed_edges.read().count() > 0 {
-        needs_rebuild = true;
+    let removed: Vec<Entity> = removed_edges.read().collect();
+    let despawned: Vec<Entity> = removed_nodes.read().collect();
+    if removed.is_empty() && despawned.is_empty() && changed_edges.is_empty() {
+        return;
     }
 
-    // Special case: if topology is empty but there are edges, we must rebuild (handle startup/tests)
-    if topology.adjacency.is_empty() && !edge_query.is_empty() {
-        needs_rebuild = true;
+    for entity in removed {
+        // Re-added edges show up as changed below, after their old route is gone.
+        topology.remove_edge(entity);
     }
-
-    if needs_rebuild {
-        tracing::debug!("Graph topology changed or uninitialized, rebuilding adjacency cache");
-        topology.adjacency.clear();
-        for edge in edge_query.iter() {
-            topology
-                .adjacency
-                .entry(edge.source)
-                .or_default()
-                .push((edge.source_handle.clone(), edge.target));
-        }
+    for node in despawned {
+        topology.remove_node(node);
+    }
+    for (entity, edge) in changed_edges.iter() {
+        topology.insert_edge(entity, edge);
     }
+    tracing::debug!(
+        edges = topology.edge_count(),
+        revision = topology.revision,
+        "Graph topology updated"
+    );
 }
 
+/// Ticket metadata naming the input port (the edge's `target_handle`) a ticket arrived on.
+pub const INPUT_PORT_KEY: &str = "input_port";
+
 /// System: Transport Worker (The Circulatory System)
 ///
 /// **Role**: Moves Data Tickets from `Outbox` queues to connected `Inbox` queues.
+/// Each delivered ticket is stamped with [`INPUT_PORT_KEY`] when its edge names an input port.
+/// Every delivery is reported as an `EdgeTraversal` carrying the payload size, the raw
+/// material for per-edge volume analytics.
+/// When a `FaultInjector` is present, tickets may be dropped or poisoned on the way.
+/// Outboxes of nodes in a `PausedWorkflows` entry are left untouched until it is resumed, and
+/// tickets of runs in `CancelledRuns` are discarded instead of delivered.
+///
+/// Targets with an `InboxCapacity` apply its `BackpressurePolicy` once full: `Block` stops
+/// draining the source's outbox, `DropOldest` evicts the inbox head and `Spill` writes the
+/// ticket to disk, refilling the inbox from there first on later ticks. Spilling needs the
+/// target's `BackpressureState` and otherwise falls back to blocking.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 #[tracing::instrument(skip(
     inbox_query,
     outbox_query,
@@ -54,42 +81,207 @@ pub fn update_graph_topology(
     topology,
     work_done,
     bus,
-    trace_query
+    trace_query,
+    chaos,
+    paused,
+    cancelled,
+    store,
+    settings
 ))]
 pub fn transport_worker(
-    mut inbox_query: Query<&mut Inbox>,
+    mut inbox_query: Query<(
+        Entity,
+        &mut Inbox,
+        Option<&InboxCapacity>,
+        Option<&mut BackpressureState>,
+    )>,
     mut outbox_query: Query<&mut Outbox>,
     node_query: Query<(Entity, &NodeConfig)>, // Need to map Entity -> UUID
     topology: Res<GraphTopology>,
-    mut work_done: ResMut<WorkDone>,
+    work_done: Res<WorkDone>,
     bus: Res<SystemEventBus>,
     mut trace_query: Query<(
         &mut crate::components::observability::TraceNode,
         &crate::components::observability::Trace,
     )>,
+    chaos: Option<Res<FaultInjector>>,
+    paused: Option<Res<PausedWorkflows>>,
+    cancelled: Option<Res<CancelledRuns>>,
+    store: Option<Res<BlobStore>>,
+    settings: Option<Res<RuntimeSettings>>,
 ) {
+    if let Some(store) = &store {
+        store.set_fault_injection(chaos.is_some());
+    }
+
     // 1. Build Entity -> UUID Map (Optimization: Move to resource if slow)
     let node_map: std::collections::HashMap<Entity, uuid::Uuid> =
         node_query.iter().map(|(e, c)| (e, c.id)).collect();
 
-    // 2. Iterate Sources with Active Connections (from cache)
+    // 2. Refill bounded inboxes from their spill files
+    for (entity, mut inbox, capacity, state) in inbox_query.iter_mut() {
+        let (Some(capacity), Some(mut state)) = (capacity, state) else {
+            continue;
+        };
+        let tenant = tenant_of(&node_query, entity);
+        if let Some(store) = &store {
+            while !capacity.is_full(inbox.queue.len())
+                && let Some(path) = state.spilled.pop_front()
+            {
+                match spill::restore(store, &path) {
+                    Ok(ticket) if is_cancelled(cancelled.as_deref(), tenant, &ticket) => {
+                        let _ = store.release(&ticket);
+                    }
+                    Ok(ticket) => inbox.queue.push_back(ticket),
+                    Err(e) => {
+                        tracing::error!(path = ?path, error = %e, "Failed to restore spilled ticket")
+                    }
+                }
+                work_done.mark();
+            }
+        }
+        if state.engaged && state.spilled.is_empty() && !capacity.is_full(inbox.queue.len()) {
+            state.engaged = false;
+        }
+    }
+
+    let mut pressure: Vec<(Entity, BackpressurePolicy, usize)> = Vec::new();
+    let mut spill_dir = None;
+
+    // 3. Iterate Sources with Active Connections (from cache)
     for (source, targets) in &topology.adjacency {
         if let Ok(mut outbox) = outbox_query.get_mut(*source) {
             if outbox.queue.is_empty() {
                 continue;
             }
+            if let Some(paused) = &paused
+                && let Ok((_, config)) = node_query.get(*source)
+                && let Some(workflow_id) = &config.workflow_id
+            {
+                let tenant = config
+                    .tenant_id
+                    .clone()
+                    .unwrap_or_else(|| TenantId::from("default_tenant"));
+                if paused.is_paused(&tenant, workflow_id) {
+                    continue;
+                }
+            }
+
+            // 4. Broadcast Tickets (Filtering by Port)
+            let mut items = std::mem::take(&mut outbox.queue);
+            let tenant = tenant_of(&node_query, *source);
+
+            while let Some((port, ticket)) = items.pop_front() {
+                if is_cancelled(cancelled.as_deref(), tenant, &ticket) {
+                    if let Some(store) = &store {
+                        let _ = store.release(&ticket);
+                    }
+                    work_done.mark();
+                    continue;
+                }
+                let receivers = targets.iter().filter(|(handle, _)| handle == &port).count();
 
-            // 3. Broadcast Tickets (Filtering by Port)
-            let items: Vec<(Option<String>, SecureTicket)> = outbox.queue.drain(..).collect();
+                // A full blocking target holds this ticket and everything behind it.
+                let blocked = targets
+                    .iter()
+                    .filter(|(handle, _)| handle == &port)
+                    .find_map(|(_, target)| {
+                        let (_, inbox, capacity, state) = inbox_query.get(*target).ok()?;
+                        let capacity = capacity?;
+                        let blocks = match capacity.policy {
+                            BackpressurePolicy::Block => true,
+                            BackpressurePolicy::Spill => state.is_none(),
+                            BackpressurePolicy::DropOldest => false,
+                        };
+                        (blocks && capacity.is_full(inbox.queue.len()))
+                            .then_some((*target, inbox.queue.len()))
+                    });
+                if let Some((target, queued)) = blocked {
+                    items.push_front((port, ticket));
+                    pressure.push((target, BackpressurePolicy::Block, queued));
+                    break;
+                }
 
-            for (port, ticket) in items {
+                let bytes = store
+                    .as_ref()
+                    .and_then(|store| store.size(&ticket))
+                    .unwrap_or(0) as u64;
                 for (edge_handle, target_entity) in targets {
                     // Exact match on port name (handle).
                     // If outbox says "Success", only edges from "Success" fire.
                     if edge_handle == &port
-                        && let Ok(mut inbox) = inbox_query.get_mut(*target_entity)
+                        && let Ok((_, mut inbox, capacity, mut state)) =
+                            inbox_query.get_mut(*target_entity)
                     {
-                        inbox.queue.push_back(ticket.clone());
+                        let mut ticket = ticket.clone();
+                        // A claim fault only applies to the node it was injected for.
+                        ticket.metadata.remove(CHAOS_CLAIM_FAULT_KEY);
+                        match topology
+                            .target_ports
+                            .get(&(*source, port.clone(), *target_entity))
+                        {
+                            Some(input_port) => {
+                                ticket
+                                    .metadata
+                                    .insert(INPUT_PORT_KEY.to_string(), input_port.clone());
+                            }
+                            None => {
+                                ticket.metadata.remove(INPUT_PORT_KEY);
+                            }
+                        }
+                        if let Some(chaos) = &chaos
+                            && let Ok((_, target_config)) = node_query.get(*target_entity)
+                        {
+                            if chaos.should_inject(&target_config.node_type, Fault::Drop) {
+                                tracing::warn!(target = ?target_entity, "Chaos: dropped ticket");
+                                continue;
+                            }
+                            if chaos.should_inject(&target_config.node_type, Fault::ClaimError) {
+                                ticket
+                                    .metadata
+                                    .insert(CHAOS_CLAIM_FAULT_KEY.to_string(), "true".to_string());
+                            }
+                        }
+
+                        let full = capacity.is_some_and(|c| c.is_full(inbox.queue.len()));
+                        match (capacity.map(|c| c.policy), state.as_deref_mut(), &store) {
+                            (Some(BackpressurePolicy::DropOldest), state, _) if full => {
+                                inbox.queue.pop_front();
+                                if let Some(state) = state {
+                                    state.dropped += 1;
+                                }
+                                inbox.queue.push_back(ticket.clone());
+                                pressure.push((
+                                    *target_entity,
+                                    BackpressurePolicy::DropOldest,
+                                    inbox.queue.len(),
+                                ));
+                            }
+                            // Once anything is spilled, later tickets queue behind it on disk.
+                            (Some(BackpressurePolicy::Spill), Some(state), Some(store))
+                                if full || !state.spilled.is_empty() =>
+                            {
+                                let dir = spill_dir.get_or_insert_with(|| {
+                                    RuntimeSettings::effective(settings.as_deref())
+                                        .transport
+                                        .spill_dir()
+                                });
+                                // Only the last copy of a broadcast ticket may free its payload.
+                                match spill::spill(store, dir, &ticket, receivers == 1) {
+                                    Ok(path) => state.spilled.push_back(path),
+                                    Err(e) => {
+                                        tracing::error!(target = ?target_entity, error = %e, "Failed to spill ticket, delivering in memory");
+                                        inbox.queue.push_back(ticket.clone());
+                                    }
+                                }
+                                pressure.push((
+                                    *target_entity,
+                                    BackpressurePolicy::Spill,
+                                    inbox.queue.len(),
+                                ));
+                            }
+                            _ => inbox.queue.push_back(ticket.clone()),
+                        }
                         tracing::debug!(source = ?source, target = ?target_entity, port = ?port, "Moved ticket");
 
                         let target_uuid = node_map.get(target_entity).cloned().unwrap_or_default();
@@ -106,16 +298,72 @@ pub fn transport_worker(
                         }
 
                         // Signal Visualizer
-                        let _ = bus.0.send(SystemEvent::EdgeTraversal {
+                        let _ = bus.send(SystemEvent::EdgeTraversal {
+                            trace_id: ticket.metadata.get("trace_id").cloned().unwrap_or_default(),
                             source_id: node_map.get(source).cloned().unwrap_or_default(),
+                            source_handle: port.clone(),
                             target_id: target_uuid,
+                            bytes,
                             timestamp: chrono::Utc::now().timestamp_millis(),
                         });
 
-                        work_done.0 = true;
+                        work_done.mark();
                     }
                 }
             }
+
+            // Whatever a blocked target held back goes out first next tick.
+            outbox.queue = items;
+        }
+    }
+
+    // 5. Report inboxes that just filled up
+    let mut reported = std::collections::HashSet::new();
+    for (target, policy, inbox_len) in pressure {
+        if !reported.insert(target) {
+            continue;
+        }
+        let Ok((_, _, _, state)) = inbox_query.get_mut(target) else {
+            continue;
+        };
+        if let Some(mut state) = state {
+            if state.engaged {
+                continue;
+            }
+            state.engaged = true;
         }
+        let node_id = node_map.get(&target).cloned().unwrap_or_default();
+        tracing::warn!(node_id = %node_id, policy = policy.as_str(), inbox_len, "Backpressure engaged");
+        let _ = bus.send(SystemEvent::BackpressureEngaged {
+            node_id,
+            policy: policy.as_str().to_string(),
+            inbox_len,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        });
     }
 }
+
+fn tenant_of<'a>(
+    node_query: &'a Query<(Entity, &NodeConfig)>,
+    entity: Entity,
+) -> Option<&'a TenantId> {
+    node_query
+        .get(entity)
+        .ok()
+        .and_then(|(_, config)| config.tenant_id.as_ref())
+}
+
+/// Whether `ticket`, held by a node of `tenant`, belongs to a run that tenant cancelled.
+fn is_cancelled(
+    cancelled: Option<&CancelledRuns>,
+    tenant: Option<&TenantId>,
+    ticket: &SecureTicket,
+) -> bool {
+    cancelled.is_some_and(|cancelled| {
+        !cancelled.is_empty()
+            && ticket
+                .metadata
+                .get("trace_id")
+                .is_some_and(|trace_id| cancelled.is_cancelled(tenant, trace_id))
+    })
+}
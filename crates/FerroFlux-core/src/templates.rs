@@ -0,0 +1,190 @@
+//! Starter workflows that users instantiate instead of starting from an empty canvas.
+//!
+//! A template is a workflow blueprint with catalog metadata. Its nodes name connections by
+//! placeholder slugs, declared under `connections`, that are bound to the tenant's own
+//! connections when the template is instantiated. `AppBuilder` loads the directory named by
+//! `FERROFLUX_TEMPLATES` (`templates` by default); templates shipped inside a binary can be
+//! added with [`TemplateLibrary::register_yaml`].
+//!
+//! ```yaml
+//! id: "support-triage"
+//! name: "Support triage"
+//! category: "Customer Support"
+//! connections:
+//!   - slug: "llm"
+//!     platform: "openai"
+//! nodes:
+//!   - id: "11111111-1111-1111-1111-111111111111"
+//!     name: "Classify"
+//!     type: "core.action.agent"
+//!     config:
+//!       connection_slug: "llm"
+//! edges: []
+//! ```
+
+use crate::graph_loader::{
+    BUNDLE_FORMAT_VERSION, ImportOptions, ImportedWorkflow, WorkflowBlueprint, WorkflowBundle,
+    import_workflow,
+};
+use bevy_ecs::prelude::*;
+use ferroflux_iam::TenantId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// A connection the template needs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionPlaceholder {
+    /// Slug the template's node configs use for it.
+    pub slug: String,
+    /// Platform the bound connection should be for, e.g. `openai`.
+    #[serde(default)]
+    pub platform: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowTemplate {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub connections: Vec<ConnectionPlaceholder>,
+    #[serde(flatten)]
+    pub graph: WorkflowBlueprint,
+}
+
+/// Catalog entry of a template, without its graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateSummary {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub description: Option<String>,
+    pub connections: Vec<ConnectionPlaceholder>,
+    pub node_count: usize,
+}
+
+/// Template summaries by category, each sorted by name.
+pub type TemplatesByCategory = BTreeMap<String, Vec<TemplateSummary>>;
+
+/// Starter templates, by id.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TemplateLibrary {
+    templates: BTreeMap<String, WorkflowTemplate>,
+}
+
+impl TemplateLibrary {
+    /// Adds a template, replacing one with the same id.
+    pub fn register(&mut self, template: WorkflowTemplate) {
+        self.templates.insert(template.id.clone(), template);
+    }
+
+    /// Parses and registers a YAML template, e.g. one embedded with `include_str!`.
+    pub fn register_yaml(&mut self, yaml: &str) -> anyhow::Result<()> {
+        let template: WorkflowTemplate = serde_yaml::from_str(yaml)?;
+        self.register(template);
+        Ok(())
+    }
+
+    /// Registers every `.yaml`/`.yml` template in `path`.
+    pub fn load_from_directory(&mut self, path: &Path) -> anyhow::Result<usize> {
+        let mut count = 0;
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if matches!(
+                path.extension().and_then(|s| s.to_str()),
+                Some("yaml" | "yml")
+            ) {
+                self.register_yaml(&std::fs::read_to_string(&path)?)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse {:?}: {}", path, e))?;
+                count += 1;
+            }
+        }
+        tracing::info!(count, path = ?path, "Loaded workflow templates");
+        Ok(count)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&WorkflowTemplate> {
+        self.templates.get(id)
+    }
+
+    pub fn by_category(&self) -> TemplatesByCategory {
+        let mut categories = TemplatesByCategory::new();
+        for template in self.templates.values() {
+            categories
+                .entry(template.category.clone())
+                .or_default()
+                .push(TemplateSummary {
+                    id: template.id.clone(),
+                    name: template.name.clone(),
+                    category: template.category.clone(),
+                    description: template.description.clone(),
+                    connections: template.connections.clone(),
+                    node_count: template.graph.nodes.len(),
+                });
+        }
+        for templates in categories.values_mut() {
+            templates.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        categories
+    }
+}
+
+/// Loads a template into the tenant's workspace as a new workflow.
+///
+/// `connections` binds every placeholder slug of the template to one of the tenant's
+/// connection slugs. The workflow id defaults to the template id with a random suffix, so
+/// each instance gets its own node UUIDs; reusing a `workflow_id` replaces that workflow.
+pub fn instantiate_template(
+    world: &mut World,
+    tenant: TenantId,
+    template_id: &str,
+    workflow_id: Option<String>,
+    connections: HashMap<String, String>,
+) -> anyhow::Result<ImportedWorkflow> {
+    let template = world
+        .get_resource::<TemplateLibrary>()
+        .and_then(|library| library.get(template_id))
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", template_id))?;
+
+    let unbound: Vec<&str> = template
+        .connections
+        .iter()
+        .map(|placeholder| placeholder.slug.as_str())
+        .filter(|slug| !connections.contains_key(*slug))
+        .collect();
+    if !unbound.is_empty() {
+        anyhow::bail!(
+            "Template '{}' needs connections for: {}",
+            template_id,
+            unbound.join(", ")
+        );
+    }
+
+    let workflow_id = workflow_id.unwrap_or_else(|| {
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        format!("{}-{}", template.id, &suffix[..8])
+    });
+    let bundle = WorkflowBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        id: workflow_id,
+        workflow: template.graph,
+        prompts: Vec::new(),
+        connections: template
+            .connections
+            .into_iter()
+            .map(|placeholder| placeholder.slug)
+            .collect(),
+        layout: None,
+    };
+    let options = ImportOptions {
+        workflow_id: None,
+        connections,
+    };
+    import_workflow(world, tenant, bundle, &options)
+}
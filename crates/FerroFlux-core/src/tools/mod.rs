@@ -17,6 +17,8 @@ pub struct ToolContext<'a> {
     pub memory: &'a mut HashMap<String, Value>,
     /// Correlation ID for the execution flow.
     pub trace_id: String,
+    /// Tenant owning the execution, for tenant-scoped tools.
+    pub tenant_id: Option<ferroflux_iam::TenantId>,
     /// System event bus for emitting telemetry.
     pub event_bus: Option<crate::api::events::SystemEventBus>,
     /// Whether the current execution is a safe simulation ("Shadow Mode").
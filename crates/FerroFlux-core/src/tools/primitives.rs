@@ -1,3 +1,4 @@
+pub mod counter;
 pub mod emit;
 pub mod http_client;
 pub mod json_query;
@@ -10,6 +11,7 @@ pub mod switch;
 pub mod trace;
 pub mod variable;
 
+pub use self::counter::CounterTool;
 pub use self::emit::EmitTool;
 pub use self::http_client::HttpClientTool;
 pub use self::json_query::JsonQueryTool;
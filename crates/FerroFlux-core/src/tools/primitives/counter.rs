@@ -0,0 +1,46 @@
+use crate::components::control::CounterConfig;
+use crate::store::database::PersistentStore;
+use crate::tools::{Tool, ToolContext};
+use anyhow::Result;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+
+/// Tool: shared, tenant-scoped counters.
+///
+/// Params mirror `CounterConfig` (`name`, `operation`, `amount`, `window_seconds`, `limit`).
+/// Returns `{ "value": i64, "exceeded": bool }`.
+pub struct CounterTool {
+    store: PersistentStore,
+    runtime: tokio::runtime::Handle,
+}
+
+impl CounterTool {
+    pub fn new(store: PersistentStore, runtime: tokio::runtime::Handle) -> Self {
+        Self { store, runtime }
+    }
+}
+
+impl Tool for CounterTool {
+    fn id(&self) -> &'static str {
+        "counter"
+    }
+
+    fn run(&self, context: &mut ToolContext, params: Value) -> Result<Value> {
+        let config: CounterConfig = serde_json::from_value(params)?;
+        let tenant = context
+            .tenant_id
+            .clone()
+            .unwrap_or_else(|| TenantId::from("default_tenant"));
+
+        let (value, exceeded) = tokio::task::block_in_place(|| {
+            self.runtime
+                .block_on(crate::systems::control::apply_counter(
+                    &self.store,
+                    &tenant,
+                    &config,
+                    &config.name,
+                ))
+        })?;
+        Ok(json!({ "value": value, "exceeded": exceeded }))
+    }
+}
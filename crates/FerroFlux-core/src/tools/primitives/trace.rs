@@ -23,7 +23,7 @@ impl Tool for TraceTool {
 
         // Emit Log event
         if let Some(bus) = context.event_bus.as_ref() {
-            let _ = bus.0.send(SystemEvent::Log {
+            let _ = bus.send(SystemEvent::Log {
                 level: "TRACE".to_string(),
                 message: format!("{}: {}", label, data),
                 trace_id,
@@ -14,6 +14,7 @@ fn test_switch_tool() {
         local: &mut local,
         memory: &mut memory,
         trace_id: "test-trace".to_string(),
+        tenant_id: None,
         event_bus: None,
         shadow_mode: false,
         shadow_masks: &HashMap::new(),
@@ -51,6 +52,7 @@ fn test_json_query_tool() {
         local: &mut local,
         memory: &mut memory,
         trace_id: "test-trace".to_string(),
+        tenant_id: None,
         event_bus: None,
         shadow_mode: false,
         shadow_masks: &HashMap::new(),
@@ -82,6 +84,7 @@ fn test_emit_tool() {
         local: &mut local,
         memory: &mut memory,
         trace_id: "test-trace".to_string(),
+        tenant_id: None,
         event_bus: None,
         shadow_mode: false,
         shadow_masks: &HashMap::new(),
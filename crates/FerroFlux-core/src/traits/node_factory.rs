@@ -18,6 +18,40 @@ pub struct NodeMetadata {
     pub inputs: Vec<PortMetadata>,
     pub outputs: Vec<PortMetadata>,
     pub settings: Vec<Value>, // Using Value for schema to avoid circular deps for now
+    /// Version of the node type, dot-separated numbers such as `1.2.0`. Saved with each node,
+    /// so a workflow loaded after an upgrade can be migrated or flagged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Set when the node type should no longer be used: why, or what replaces it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+/// Version assumed for nodes saved before their node type was versioned; it is older than
+/// any version a node type declares.
+pub const UNVERSIONED: &str = "0";
+
+/// Whether node type version `saved` predates `current`. Versions compare numerically part by
+/// part (`1.10` is newer than `1.9`); missing parts count as zero and non-numeric parts
+/// compare as text.
+pub fn is_older_version(saved: &str, current: &str) -> bool {
+    let parts = |v: &str| -> Vec<String> {
+        let v = v.trim().trim_start_matches('v');
+        v.split('.').map(str::to_string).collect()
+    };
+    let (saved, current) = (parts(saved), parts(current));
+    for i in 0..saved.len().max(current.len()) {
+        let a = saved.get(i).map_or("0", String::as_str);
+        let b = current.get(i).map_or("0", String::as_str);
+        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        };
+        if order.is_ne() {
+            return order.is_lt();
+        }
+    }
+    false
 }
 
 /// Trait for creating node entities from JSON configuration.
@@ -32,4 +66,69 @@ pub trait NodeFactory: Send + Sync {
 
     /// Returns metadata about the node for UI/docs.
     fn metadata(&self) -> NodeMetadata;
+
+    /// Upgrades the config of a node saved against an older version of this node type,
+    /// before `build` sees it. `from_version` is [`UNVERSIONED`] for nodes saved before this
+    /// node type had a version. Returns `None` when there is nothing to migrate, in which case
+    /// the saved config is used as is.
+    fn migrate(&self, _from_version: &str, _config: &Value) -> Option<anyhow::Result<Value>> {
+        None
+    }
+}
+
+/// Per-user palette usage for a single node type.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeUsage {
+    pub node_type: String,
+    pub use_count: i64,
+    pub last_used_at: Option<String>,
+    pub favorite: bool,
+}
+
+/// The node palette for a user: all templates plus "Recently used" and "Favorites" sections.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NodeCatalog {
+    pub templates: Vec<NodeMetadata>,
+    /// Template IDs, most recently used first.
+    pub recent: Vec<String>,
+    /// Template IDs pinned by the user.
+    pub favorites: Vec<String>,
+    pub usage: Vec<NodeUsage>,
+}
+
+impl NodeCatalog {
+    /// Maximum number of entries in the "Recently used" section.
+    pub const RECENT_LIMIT: usize = 8;
+
+    /// Builds the catalog from templates and usage rows (as returned by
+    /// `PersistentStore::list_node_usage`, most recent first).
+    ///
+    /// Usage for node types that no longer exist in the registry is dropped.
+    pub fn build(templates: Vec<NodeMetadata>, usage: Vec<NodeUsage>) -> Self {
+        let known: std::collections::HashSet<&str> =
+            templates.iter().map(|t| t.id.as_str()).collect();
+        let usage: Vec<NodeUsage> = usage
+            .into_iter()
+            .filter(|u| known.contains(u.node_type.as_str()))
+            .collect();
+
+        let recent = usage
+            .iter()
+            .filter(|u| u.use_count > 0)
+            .take(Self::RECENT_LIMIT)
+            .map(|u| u.node_type.clone())
+            .collect();
+        let favorites = usage
+            .iter()
+            .filter(|u| u.favorite)
+            .map(|u| u.node_type.clone())
+            .collect();
+
+        Self {
+            templates,
+            recent,
+            favorites,
+            usage,
+        }
+    }
 }
@@ -25,7 +25,7 @@ fn setup_world() -> World {
     world.insert_resource(ferroflux_core::resources::templates::TemplateEngine::default());
     world.insert_resource(ferroflux_core::resources::PipelineResultChannel::default());
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
 
     // Runtime
     let runtime = Runtime::new().unwrap();
@@ -64,7 +64,7 @@ fn test_agent_missing_provider_event() {
     // Subscribe to Event Bus
     let mut rx = {
         let bus = world.resource::<SystemEventBus>();
-        bus.0.subscribe()
+        bus.subscribe()
     };
 
     let store = world.resource::<BlobStore>().clone();
@@ -82,13 +82,19 @@ fn test_agent_missing_provider_event() {
             system_instruction: "You are a test agent.".to_string(),
             provider: "invalid_provider".to_string(), // <--- ERROR SOURCE
             user_prompt_template: "{{user_prompt}}".to_string(),
+            system_prompt_ref: None,
+            user_prompt_ref: None,
             tools: vec![],
             tool_choice: ferroflux_core::components::agent::ToolChoice::Auto,
             output_mode: OutputMode::Text,
+            max_repair_attempts: 0,
             result_key: None,
             generation_settings: ferroflux_core::components::agent::GenerationSettings::default(),
             history_config: ferroflux_core::components::agent::HistoryConfig::default(),
             connection_slug: None,
+            template_syntax: Default::default(),
+            locale: None,
+            fallback: None,
         },
         NodeConfig {
             id: node_id,
@@ -96,6 +102,7 @@ fn test_agent_missing_provider_event() {
             node_type: "Agent".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         ExpectedOutput {
             aggregated_schema: std::collections::HashSet::new(),
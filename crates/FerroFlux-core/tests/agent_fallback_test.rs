@@ -0,0 +1,241 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::agent::{AgentConfig, FallbackChain, FallbackTarget};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::integrations::IntegrationRegistry;
+use ferroflux_core::integrations::registry::{
+    ActionImplementation, AuthType, IntegrationAction, IntegrationConfig, IntegrationDef,
+};
+use ferroflux_core::resources::{
+    GlobalHttpClient, PipelineResultChannel, TokioRuntime, WorkDone, templates::TemplateEngine,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::agent::{agent_exec, agent_post, agent_prep};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A chat provider whose responses are merged unmapped, so the test sees the raw JSON.
+fn provider(name: &str, base_url: String) -> IntegrationDef {
+    let action = IntegrationAction {
+        inputs: vec![],
+        outputs: vec![],
+        category: None,
+        subcategory: None,
+        documentation: None,
+        message_transform: None,
+        implementation: ActionImplementation {
+            impl_type: "http".to_string(),
+            config: IntegrationConfig {
+                method: "POST".to_string(),
+                path: "/chat".to_string(),
+                headers: HashMap::new(),
+                body_template: Some(
+                    r#"{"model": "{{model}}", "prompt": {{json user_prompt}}}"#.to_string(),
+                ),
+                pagination: None,
+            },
+        },
+        output_transform: None,
+        stream: None,
+    };
+    IntegrationDef {
+        name: name.to_string(),
+        base_url,
+        auth: None,
+        connection_schema: None,
+        actions: HashMap::from([("chat_completion".to_string(), action)]),
+        icon_url: None,
+        verify_endpoint: None,
+        capabilities: None,
+        utilities: HashMap::new(),
+        resources: HashMap::new(),
+        auth_type: AuthType::None,
+        verify_params: HashMap::new(),
+    }
+}
+
+async fn setup_world(server: &MockServer) -> (World, Schedule) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let db = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .unwrap();
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        db,
+        vec![0; 32],
+    ));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TemplateEngine::default());
+    world.insert_resource(PipelineResultChannel::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+
+    let mut registry = IntegrationRegistry::default();
+    for name in ["primary", "backup", "last_resort"] {
+        registry.definitions.insert(
+            name.to_string(),
+            provider(name, format!("{}/{}", server.uri(), name)),
+        );
+    }
+    world.insert_resource(registry);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((agent_prep, agent_exec, agent_post));
+    (world, schedule)
+}
+
+fn agent(targets: &[(&str, &str)], timeout_ms: Option<u64>) -> AgentConfig {
+    AgentConfig {
+        provider: "primary".to_string(),
+        model: "big-model".to_string(),
+        system_instruction: String::new(),
+        result_key: Some("answer".to_string()),
+        fallback: Some(FallbackChain {
+            targets: targets
+                .iter()
+                .map(|(provider, model)| FallbackTarget {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                    connection_slug: None,
+                })
+                .collect(),
+            timeout_ms,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Runs one ticket through the agent and returns its output and the node's telemetry details.
+async fn run(server: &MockServer, config: AgentConfig) -> (Value, Value) {
+    let (mut world, mut schedule) = setup_world(server).await;
+    let mut events = world.resource::<SystemEventBus>().subscribe();
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(br#"{"user_prompt": "Hi"}"#).unwrap());
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Assistant".to_string(),
+                node_type: "Agent".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    for _ in 0..150 {
+        schedule.run(&mut world);
+        if let Some((_, ticket)) = world.get::<Outbox>(entity).unwrap().queue.front() {
+            let output = serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap();
+            let details = std::iter::from_fn(|| events.try_recv().ok())
+                .find_map(|event| match event {
+                    SystemEvent::NodeTelemetry { details, .. } => Some(details),
+                    _ => None,
+                })
+                .expect("no telemetry");
+            return (output, details);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("agent produced no output");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failed_provider_falls_back_to_next_entry() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/chat"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("overloaded"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/backup/chat"))
+        .respond_with(ResponseTemplate::new(429).set_body_string("slow down"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/last_resort/chat"))
+        .and(body_partial_json(json!({ "model": "small-model" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "text": "Hello!" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (output, details) = run(
+        &server,
+        agent(
+            &[("backup", "mid-model"), ("last_resort", "small-model")],
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(output["answer"]["text"], "Hello!");
+    assert_eq!(details["provider"], "last_resort");
+    assert_eq!(details["model"], "small-model");
+    assert_eq!(
+        details["fallback_from"],
+        json!(["primary/big-model", "backup/mid-model"])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_timed_out_provider_falls_back() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/primary/chat"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "text": "too late" }))
+                .set_delay(Duration::from_secs(2)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/backup/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "text": "in time" })))
+        .mount(&server)
+        .await;
+
+    let (output, details) = run(&server, agent(&[("backup", "mid-model")], Some(200))).await;
+    assert_eq!(output["answer"]["text"], "in time");
+    assert_eq!(details["provider"], "backup");
+    assert_eq!(details["fallback_from"], json!(["primary/big-model"]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_last_failure_is_the_result_when_every_provider_fails() {
+    let server = MockServer::start().await;
+    for (name, status) in [("primary", 500), ("backup", 502)] {
+        Mock::given(method("POST"))
+            .and(path(format!("/{}/chat", name)))
+            .respond_with(ResponseTemplate::new(status).set_body_string(name))
+            .mount(&server)
+            .await;
+    }
+
+    // Unknown providers in the chain are skipped.
+    let (output, details) = run(
+        &server,
+        agent(&[("missing", "x"), ("backup", "mid-model")], None),
+    )
+    .await;
+    assert_eq!(output["answer"], "HTTP Error 502: backup");
+    assert_eq!(details["provider"], "backup");
+    assert_eq!(details["status"], 502);
+    assert_eq!(details["fallback_from"], json!(["primary/big-model"]));
+}
@@ -0,0 +1,188 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::agent::{AgentConfig, HistoryConfig, OutputMode, ToolChoice};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::integrations::IntegrationRegistry;
+use ferroflux_core::integrations::registry::{
+    ActionImplementation, AuthType, IntegrationAction, IntegrationConfig, IntegrationDef,
+    OutputTransform, StreamDef,
+};
+use ferroflux_core::resources::{
+    GlobalHttpClient, PipelineResultChannel, TokioRuntime, WorkDone, templates::TemplateEngine,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::agent::{agent_exec, agent_post, agent_prep};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn setup_world(base_url: String) -> (World, Schedule) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let db = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .unwrap();
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        db,
+        vec![0; 32],
+    ));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TemplateEngine::default());
+    world.insert_resource(PipelineResultChannel::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "application/json".to_string());
+    let action = IntegrationAction {
+        inputs: vec![],
+        outputs: vec![],
+        category: None,
+        subcategory: None,
+        documentation: None,
+        message_transform: None,
+        implementation: ActionImplementation {
+            impl_type: "http".to_string(),
+            config: IntegrationConfig {
+                method: "POST".to_string(),
+                path: "/chat/completions".to_string(),
+                headers,
+                body_template: Some(
+                    r#"{"model": "{{model}}", "stream": {{stream}}, "prompt": {{json user_prompt}}}"#
+                        .to_string(),
+                ),
+                pagination: None,
+            },
+        },
+        output_transform: Some(OutputTransform {
+            text: "choices[0].message.content".to_string(),
+            tool_calls: None,
+        }),
+        stream: Some(StreamDef {
+            delta_pointer: "/choices/0/delta/content".to_string(),
+            done_marker: "[DONE]".to_string(),
+        }),
+    };
+    let mut registry = IntegrationRegistry::default();
+    registry.definitions.insert(
+        "streaming_provider".to_string(),
+        IntegrationDef {
+            name: "streaming_provider".to_string(),
+            base_url,
+            auth: None,
+            connection_schema: None,
+            actions: HashMap::from([("chat_completion".to_string(), action)]),
+            icon_url: None,
+            verify_endpoint: None,
+            capabilities: None,
+            utilities: HashMap::new(),
+            resources: HashMap::new(),
+            auth_type: AuthType::None,
+            verify_params: HashMap::new(),
+        },
+    );
+    world.insert_resource(registry);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((agent_prep, agent_exec, agent_post));
+    (world, schedule)
+}
+
+fn agent() -> AgentConfig {
+    AgentConfig {
+        provider: "streaming_provider".to_string(),
+        model: "gpt-mock".to_string(),
+        system_instruction: String::new(),
+        user_prompt_template: "{{user_prompt}}".to_string(),
+        system_prompt_ref: None,
+        user_prompt_ref: None,
+        generation_settings: Default::default(),
+        output_mode: OutputMode::Text,
+        max_repair_attempts: 0,
+        history_config: HistoryConfig {
+            enabled: false,
+            window_size: 0,
+            session_id_key: String::new(),
+        },
+        tools: vec![],
+        tool_choice: ToolChoice::Auto,
+        result_key: Some("answer".to_string()),
+        connection_slug: None,
+        template_syntax: Default::default(),
+        locale: None,
+        fallback: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_streamed_completion_emits_tokens_and_assembles_output() {
+    let server = MockServer::start().await;
+    let body = [
+        r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#,
+        "",
+        r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#,
+        "",
+        r#"data: {"choices":[{"delta":{"content":"lo"}}]}"#,
+        "",
+        "data: [DONE]",
+        "",
+    ]
+    .join("\n");
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(body_partial_json(json!({ "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let (mut world, mut schedule) = setup_world(server.uri()).await;
+    let mut events = world.resource::<SystemEventBus>().subscribe();
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(br#"{"user_prompt": "Say hello"}"#).unwrap());
+    let node_id = uuid::Uuid::new_v4();
+    let entity = world
+        .spawn((
+            agent(),
+            NodeConfig {
+                id: node_id,
+                name: "Greeter".to_string(),
+                node_type: "Agent".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut output = None;
+    for _ in 0..100 {
+        schedule.run(&mut world);
+        if let Some((_, ticket)) = world.get::<Outbox>(entity).unwrap().queue.front() {
+            output = Some(serde_json::from_slice::<Value>(&store.claim(ticket).unwrap()).unwrap());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let output = output.expect("agent produced no output");
+    assert_eq!(output["answer"], "Hello");
+
+    let deltas: Vec<String> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter_map(|event| match event {
+            SystemEvent::AgentToken {
+                node_id: id, delta, ..
+            } if id == node_id => Some(delta),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(deltas, ["Hel", "lo"]);
+}
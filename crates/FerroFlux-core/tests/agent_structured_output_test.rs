@@ -0,0 +1,224 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::agent::{AgentConfig, OutputMode};
+use ferroflux_core::components::{ExpectedOutput, Inbox, NodeConfig, Outbox};
+use ferroflux_core::integrations::IntegrationRegistry;
+use ferroflux_core::integrations::registry::{
+    ActionImplementation, AuthType, IntegrationAction, IntegrationConfig, IntegrationDef,
+};
+use ferroflux_core::resources::{
+    GlobalHttpClient, PipelineResultChannel, TokioRuntime, WorkDone, templates::TemplateEngine,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::agent::{agent_exec, agent_post, agent_prep};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A chat provider that receives the whole conversation and returns its text unmapped.
+fn provider(base_url: String) -> IntegrationDef {
+    let action = IntegrationAction {
+        inputs: vec![],
+        outputs: vec![],
+        category: None,
+        subcategory: None,
+        documentation: None,
+        message_transform: None,
+        implementation: ActionImplementation {
+            impl_type: "http".to_string(),
+            config: IntegrationConfig {
+                method: "POST".to_string(),
+                path: "/chat".to_string(),
+                headers: HashMap::new(),
+                body_template: Some(r#"{"messages": {{json messages}}}"#.to_string()),
+                pagination: None,
+            },
+        },
+        output_transform: None,
+        stream: None,
+    };
+    IntegrationDef {
+        name: "mock".to_string(),
+        base_url,
+        auth: None,
+        connection_schema: None,
+        actions: HashMap::from([("chat_completion".to_string(), action)]),
+        icon_url: None,
+        verify_endpoint: None,
+        capabilities: None,
+        utilities: HashMap::new(),
+        resources: HashMap::new(),
+        auth_type: AuthType::None,
+        verify_params: HashMap::new(),
+    }
+}
+
+fn text(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_string(body)
+}
+
+/// Runs one ticket through a JSON-strict agent expecting `name` and `address.city`, and
+/// returns the port and payload it emitted plus the events seen on the bus.
+async fn run(
+    server: &MockServer,
+    max_repair_attempts: u32,
+) -> (Option<String>, Value, Vec<SystemEvent>) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let db = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .unwrap();
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        db,
+        vec![0; 32],
+    ));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TemplateEngine::default());
+    world.insert_resource(PipelineResultChannel::default());
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    let mut registry = IntegrationRegistry::default();
+    registry
+        .definitions
+        .insert("mock".to_string(), provider(server.uri()));
+    world.insert_resource(registry);
+
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(br#"{"user_prompt": "Who?"}"#).unwrap());
+    let entity = world
+        .spawn((
+            AgentConfig {
+                provider: "mock".to_string(),
+                model: "test-model".to_string(),
+                system_instruction: "Extract".to_string(),
+                output_mode: OutputMode::JsonStrict,
+                max_repair_attempts,
+                ..Default::default()
+            },
+            ExpectedOutput {
+                aggregated_schema: HashSet::from(["name".to_string(), "address.city".to_string()]),
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Extractor".to_string(),
+                node_type: "Agent".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((agent_prep, agent_exec, agent_post));
+    for _ in 0..150 {
+        schedule.run(&mut world);
+        if let Some((port, ticket)) = world.get_mut::<Outbox>(entity).unwrap().queue.pop_front() {
+            let payload = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap();
+            let seen = std::iter::from_fn(|| events.try_recv().ok()).collect();
+            return (port, payload, seen);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("agent produced no output");
+}
+
+#[test]
+fn test_expected_output_schema_nests_dotted_keys() {
+    let expected = ExpectedOutput {
+        aggregated_schema: HashSet::from(["name".to_string(), "address.city".to_string()]),
+    };
+    assert_eq!(
+        expected.json_schema(),
+        json!({
+            "type": "object",
+            "required": ["address", "name"],
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "required": ["city"],
+                    "properties": { "city": {} },
+                },
+                "name": {},
+            },
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invalid_output_is_repaired_with_the_validation_errors() {
+    let server = MockServer::start().await;
+    // The repair request carries the rejected answer and what was wrong with it.
+    Mock::given(method("POST"))
+        .and(path("/chat"))
+        .and(body_string_contains("did not pass validation"))
+        .respond_with(text(
+            "```json\n{\"name\": \"Ada\", \"address\": {\"city\": \"London\"}}\n```",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat"))
+        .respond_with(text(r#"{"name": "Ada"}"#))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (port, payload, events) = run(&server, 2).await;
+    assert_eq!(port, None);
+    assert_eq!(
+        payload,
+        json!({ "name": "Ada", "address": { "city": "London" } })
+    );
+    let attempts: Vec<(bool, Value)> = events
+        .iter()
+        .filter_map(|event| match event {
+            SystemEvent::NodeTelemetry {
+                success, details, ..
+            } => Some((*success, details["repair_attempt"].clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(attempts, [(false, json!(0)), (true, json!(1))]);
+    assert!(
+        !events
+            .iter()
+            .any(|e| matches!(e, SystemEvent::ContractViolation { .. }))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exhausted_repairs_route_to_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat"))
+        .respond_with(text("Sorry, I cannot do that."))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let (port, payload, events) = run(&server, 1).await;
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(payload["payload"]["user_prompt"], "Who?");
+    assert_eq!(payload["output"], "Sorry, I cannot do that.");
+    assert!(
+        payload["violations"][0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Response is not valid JSON")
+    );
+    assert!(events.iter().any(
+        |e| matches!(e, SystemEvent::ContractViolation { violations, .. } if violations.len() == 1)
+    ));
+}
@@ -47,7 +47,7 @@ async fn setup_world(mock_server_url: String) -> (World, Schedule) {
     world.insert_resource(ferroflux_core::resources::templates::TemplateEngine::default());
     world.insert_resource(ferroflux_core::resources::PipelineResultChannel::default());
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(ferroflux_core::api::events::SystemEventBus(tx));
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
 
     // Runtime
     // Runtime
@@ -79,6 +79,7 @@ async fn setup_world(mock_server_url: String) -> (World, Schedule) {
                     body_template: Some(
                         r#"{"model": "{{model}}", "messages": {{{json messages}}}}"#.to_string(),
                     ),
+                    pagination: None,
                 },
             },
             message_transform: None, // Use parsed messages array as-is
@@ -86,6 +87,7 @@ async fn setup_world(mock_server_url: String) -> (World, Schedule) {
                 text: "choices[0].message.content".to_string(),
                 tool_calls: None,
             }),
+            stream: None,
         },
     );
 
@@ -170,8 +172,11 @@ fn test_agent_templating_and_generation() {
                 model: "gpt-mock".to_string(),
                 system_instruction: "Hello {{context}}".to_string(),
                 user_prompt_template: "{{user_prompt}}".to_string(),
+                system_prompt_ref: None,
+                user_prompt_ref: None,
                 generation_settings: GenerationSettings::default(),
                 output_mode: OutputMode::Text,
+                max_repair_attempts: 0,
                 history_config: ferroflux_core::components::agent::HistoryConfig {
                     enabled: false,
                     window_size: 0,
@@ -181,6 +186,9 @@ fn test_agent_templating_and_generation() {
                 tool_choice: ToolChoice::Auto,
                 result_key: None,
                 connection_slug: None,
+                template_syntax: Default::default(),
+                locale: None,
+                fallback: None,
             },
             ferroflux_core::components::core::NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -188,6 +196,7 @@ fn test_agent_templating_and_generation() {
                 node_type: "agent".to_string(),
                 workflow_id: None,
                 tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
             },
             ExpectedOutput {
                 aggregated_schema: schema,
@@ -264,13 +273,19 @@ fn test_agent_tools_payload() {
                 model: "gpt-mock".to_string(),
                 system_instruction: "Sys".to_string(),
                 user_prompt_template: "User".to_string(),
+                system_prompt_ref: None,
+                user_prompt_ref: None,
                 generation_settings: GenerationSettings::default(),
                 output_mode: OutputMode::Text,
+                max_repair_attempts: 0,
                 history_config: ferroflux_core::components::agent::HistoryConfig { enabled: false, window_size: 0, session_id_key: "".to_string() },
                 tools: vec![tool_def],
                 tool_choice: ToolChoice::Auto,
                 result_key: None,
                 connection_slug: None,
+                template_syntax: Default::default(),
+                locale: None,
+                fallback: None,
             },
             ferroflux_core::components::core::NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -278,6 +293,7 @@ fn test_agent_tools_payload() {
                 node_type: "agent".to_string(),
                 workflow_id: None,
                 tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
             },
 
             ExpectedOutput::default(),
@@ -337,13 +353,19 @@ fn test_agent_retry_logic() {
                 model: "gpt-mock".to_string(),
                 system_instruction: "Sys".to_string(),
                 user_prompt_template: "User".to_string(),
+                system_prompt_ref: None,
+                user_prompt_ref: None,
                 generation_settings: GenerationSettings::default(),
                 output_mode: OutputMode::Text,
+                max_repair_attempts: 0,
                 history_config: ferroflux_core::components::agent::HistoryConfig { enabled: false, window_size: 0, session_id_key: "".to_string() },
                 tools: vec![],
                 tool_choice: ToolChoice::Auto,
                 result_key: None,
                 connection_slug: None,
+                template_syntax: Default::default(),
+                locale: None,
+                fallback: None,
             },
             ferroflux_core::components::core::NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -351,6 +373,7 @@ fn test_agent_retry_logic() {
                 node_type: "agent".to_string(),
                 workflow_id: None,
                 tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
             },
 
             ExpectedOutput::default(),
@@ -417,13 +440,19 @@ fn test_agent_structured_output() {
                 model: "gpt-mock".to_string(),
                 system_instruction: "Sys".to_string(),
                 user_prompt_template: "User".to_string(),
+                system_prompt_ref: None,
+                user_prompt_ref: None,
                 generation_settings: GenerationSettings::default(),
                 output_mode: OutputMode::JsonStrict, // STRICT MODE
+                max_repair_attempts: 0,
                 history_config: ferroflux_core::components::agent::HistoryConfig { enabled: false, window_size: 0, session_id_key: "".to_string() },
                 tools: vec![],
                 tool_choice: ToolChoice::Auto,
                 result_key: None,
                 connection_slug: None,
+                template_syntax: Default::default(),
+                locale: None,
+                fallback: None,
             },
             ferroflux_core::components::core::NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -431,6 +460,7 @@ fn test_agent_structured_output() {
                 node_type: "agent".to_string(),
                 workflow_id: None,
                 tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
             },
             ExpectedOutput { aggregated_schema: schema },
             inbox,
@@ -493,13 +523,19 @@ fn test_tracing_propagation() {
                 model: "gpt-mock".to_string(),
                 system_instruction: "Sys".to_string(),
                 user_prompt_template: "User".to_string(),
+                system_prompt_ref: None,
+                user_prompt_ref: None,
                 generation_settings: GenerationSettings::default(),
                 output_mode: OutputMode::Text,
+                max_repair_attempts: 0,
                 history_config: ferroflux_core::components::agent::HistoryConfig { enabled: false, window_size: 0, session_id_key: "".to_string() },
                 tools: vec![],
                 tool_choice: ToolChoice::Auto,
                 result_key: None,
                 connection_slug: None,
+                template_syntax: Default::default(),
+                locale: None,
+                fallback: None,
             },
             ferroflux_core::components::core::NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -507,6 +543,7 @@ fn test_tracing_propagation() {
                 node_type: "agent".to_string(),
                 workflow_id: None,
                 tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
             },
             ExpectedOutput::default(),
             inbox,
@@ -515,7 +552,7 @@ fn test_tracing_propagation() {
 
         // Subscribe to events
         let event_bus = world.resource::<ferroflux_core::api::events::SystemEventBus>();
-        let mut rx = event_bus.0.subscribe();
+        let mut rx = event_bus.subscribe();
 
         let mut found_telemetry = false;
         
@@ -0,0 +1,146 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::manipulation::{AnomalyConfig, AnomalyMethod, AnomalyState};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::manipulation::anomaly_worker;
+use serde_json::{Value, json};
+
+/// Runs `inputs` through one anomaly node and returns `(port, result)` per ticket.
+fn run(config: AnomalyConfig, inputs: Vec<Value>) -> Vec<(Option<String>, Value)> {
+    let mut world = World::new();
+    let mut schedule = Schedule::default();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    schedule.add_systems(anomaly_worker);
+
+    let mut inbox = Inbox::default();
+    for payload in inputs {
+        let ticket = store
+            .check_in(&serde_json::to_vec(&payload).unwrap())
+            .unwrap();
+        inbox.queue.push_back(ticket);
+    }
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Test Anomaly".to_string(),
+                node_type: "Anomaly".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            AnomalyState::default(),
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    schedule.run(&mut world);
+
+    let outbox = world.get::<Outbox>(entity).unwrap();
+    outbox
+        .queue
+        .iter()
+        .map(|(port, ticket)| {
+            let json: Value = serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap();
+            (port.clone(), json["anomaly"].clone())
+        })
+        .collect()
+}
+
+fn config(method: AnomalyMethod) -> AnomalyConfig {
+    AnomalyConfig {
+        target_field: "value".to_string(),
+        key_field: Some("host".to_string()),
+        method,
+        alpha: 0.3,
+        season_length: 4,
+        threshold: 3.0,
+        warmup: 6,
+        result_key: "anomaly".to_string(),
+    }
+}
+
+#[test]
+fn test_ewma_flags_spikes_per_key() {
+    let series = [10.0, 11.0, 9.0, 10.0, 11.0, 9.0, 10.0, 11.0];
+    let mut inputs: Vec<Value> = series
+        .iter()
+        .flat_map(|v| {
+            [
+                json!({ "host": "a", "value": v }),
+                json!({ "host": "c", "value": v }),
+            ]
+        })
+        .collect();
+    // A fresh key is still warming up, so its first values are never flagged.
+    inputs.push(json!({ "host": "b", "value": 500.0 }));
+    inputs.push(json!({ "host": "b", "value": 1.0 }));
+    inputs.push(json!({ "host": "a", "value": 40.0 }));
+    inputs.push(json!({ "host": "c", "value": -20.0 }));
+
+    let results = run(config(AnomalyMethod::Ewma), inputs);
+    assert_eq!(results.len(), 20);
+
+    for (port, result) in &results[..18] {
+        assert_eq!(*port, None);
+        assert_eq!(result["is_anomaly"], false);
+    }
+    assert_eq!(results[16].1["expected"], Value::Null);
+    assert_eq!(results[17].1["key"], "b");
+
+    let (port, spike) = &results[18];
+    assert_eq!(port.as_deref(), Some("anomaly"));
+    assert_eq!(spike["key"], "a");
+    assert_eq!(spike["direction"], "above");
+    assert!(spike["score"].as_f64().unwrap() > 3.0);
+
+    let (port, drop) = &results[19];
+    assert_eq!(port.as_deref(), Some("anomaly"));
+    assert_eq!(drop["key"], "c");
+    assert_eq!(drop["direction"], "below");
+}
+
+#[test]
+fn test_seasonal_naive_follows_the_pattern() {
+    let mut config = config(AnomalyMethod::SeasonalNaive);
+    config.key_field = None;
+    // Three seasons of a daily-ish shape, with a little noise.
+    let mut values = vec![
+        1.0, 10.0, 20.0, 5.0, 1.5, 10.5, 19.5, 5.0, 1.0, 9.5, 20.5, 5.5,
+    ];
+    // 20 would be normal an hour later, but not now.
+    values.push(20.0);
+    // Back on pattern.
+    values.push(10.0);
+    let inputs = values.iter().map(|v| json!({ "value": v })).collect();
+
+    let results = run(config, inputs);
+    assert_eq!(results.len(), 14);
+    // Large swings that follow the season are not anomalies.
+    for (port, result) in &results[..12] {
+        assert_eq!(*port, None, "unexpected anomaly: {}", result);
+    }
+    assert_eq!(results[4].1["expected"], 1.0);
+
+    let (port, result) = &results[12];
+    assert_eq!(port.as_deref(), Some("anomaly"));
+    assert_eq!(result["expected"], 1.0);
+    assert_eq!(result["direction"], "above");
+    assert_eq!(results[13].0, None);
+}
+
+#[test]
+fn test_missing_value_passes_through() {
+    let results = run(
+        config(AnomalyMethod::Ewma),
+        vec![json!({ "host": "a", "status": "down" })],
+    );
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0], (None, Value::Null));
+}
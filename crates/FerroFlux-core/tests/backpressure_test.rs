@@ -0,0 +1,174 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::{
+    BackpressurePolicy, BackpressureState, Inbox, InboxCapacity, NodeConfig, Outbox,
+};
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::transport::transport_worker;
+use tokio::sync::broadcast;
+
+fn node(name: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        node_type: "test".to_string(),
+        workflow_id: None,
+        tenant_id: None,
+        version: None,
+    }
+}
+
+/// A splitter feeding a sink whose inbox holds two tickets.
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    store: BlobStore,
+    events: broadcast::Receiver<SystemEvent>,
+    splitter: Entity,
+    sink: Entity,
+}
+
+impl Harness {
+    fn new(policy: BackpressurePolicy) -> Self {
+        let mut world = World::new();
+        let (tx, events) = broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(WorkDone::default());
+        let store = BlobStore::default();
+        world.insert_resource(store.clone());
+        let mut settings = EngineSettings::default();
+        settings.transport.spill_dir = Some(
+            std::env::temp_dir()
+                .join(format!("spill_{}", uuid::Uuid::new_v4()))
+                .to_string_lossy()
+                .into_owned(),
+        );
+        world.insert_resource(RuntimeSettings::new(settings));
+
+        let splitter = world.spawn((node("Splitter"), Outbox::default())).id();
+        let sink = world
+            .spawn((
+                node("Sink"),
+                Inbox::default(),
+                InboxCapacity {
+                    capacity: 2,
+                    policy,
+                },
+                BackpressureState::default(),
+            ))
+            .id();
+        let mut topology = GraphTopology::default();
+        topology.adjacency.insert(splitter, vec![(None, sink)]);
+        world.insert_resource(topology);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(transport_worker);
+        Self {
+            world,
+            schedule,
+            store,
+            events,
+            splitter,
+            sink,
+        }
+    }
+
+    fn emit(&mut self, items: std::ops::Range<u32>) {
+        for i in items {
+            let ticket = self.store.check_in(i.to_string().as_bytes()).unwrap();
+            self.world
+                .get_mut::<Outbox>(self.splitter)
+                .unwrap()
+                .queue
+                .push_back((None, ticket));
+        }
+        self.schedule.run(&mut self.world);
+    }
+
+    fn inbox(&self) -> Vec<String> {
+        self.world
+            .get::<Inbox>(self.sink)
+            .unwrap()
+            .queue
+            .iter()
+            .map(|t| String::from_utf8(self.store.claim(t).unwrap()).unwrap())
+            .collect()
+    }
+
+    /// Lets the sink consume its whole inbox, then runs a tick.
+    fn drain_sink(&mut self) {
+        self.world
+            .get_mut::<Inbox>(self.sink)
+            .unwrap()
+            .queue
+            .clear();
+        self.schedule.run(&mut self.world);
+    }
+
+    fn engaged_events(&mut self) -> Vec<String> {
+        let mut policies = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            if let SystemEvent::BackpressureEngaged { policy, .. } = event {
+                policies.push(policy);
+            }
+        }
+        policies
+    }
+}
+
+#[test]
+fn test_block_holds_tickets_upstream() {
+    let mut h = Harness::new(BackpressurePolicy::Block);
+    h.emit(0..5);
+    assert_eq!(h.inbox(), ["0", "1"]);
+    assert_eq!(h.world.get::<Outbox>(h.splitter).unwrap().queue.len(), 3);
+    assert_eq!(h.engaged_events(), ["block"]);
+
+    // Still blocked: no repeated telemetry.
+    h.schedule.run(&mut h.world);
+    assert!(h.engaged_events().is_empty());
+
+    h.drain_sink();
+    assert_eq!(h.inbox(), ["2", "3"]);
+    h.drain_sink();
+    assert_eq!(h.inbox(), ["4"]);
+    assert!(h.world.get::<Outbox>(h.splitter).unwrap().queue.is_empty());
+}
+
+#[test]
+fn test_drop_oldest_keeps_newest() {
+    let mut h = Harness::new(BackpressurePolicy::DropOldest);
+    h.emit(0..5);
+    assert_eq!(h.inbox(), ["3", "4"]);
+    assert_eq!(h.world.get::<BackpressureState>(h.sink).unwrap().dropped, 3);
+    assert_eq!(h.engaged_events(), ["drop_oldest"]);
+}
+
+#[test]
+fn test_spill_restores_in_order() {
+    let mut h = Harness::new(BackpressurePolicy::Spill);
+    h.emit(0..5);
+    assert_eq!(h.inbox(), ["0", "1"]);
+    let spilled = h
+        .world
+        .get::<BackpressureState>(h.sink)
+        .unwrap()
+        .spilled
+        .clone();
+    assert_eq!(spilled.len(), 3);
+    assert!(spilled.iter().all(|path| path.exists()));
+    assert_eq!(h.engaged_events(), ["spill"]);
+
+    // New tickets queue behind the spilled ones.
+    h.world.get_mut::<Inbox>(h.sink).unwrap().queue.clear();
+    h.emit(5..6);
+    assert_eq!(h.inbox(), ["2", "3"]);
+    h.drain_sink();
+    assert_eq!(h.inbox(), ["4", "5"]);
+    assert!(spilled.iter().all(|path| !path.exists()));
+    h.drain_sink();
+    assert!(h.inbox().is_empty());
+    assert!(!h.world.get::<BackpressureState>(h.sink).unwrap().engaged);
+}
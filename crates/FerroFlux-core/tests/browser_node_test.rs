@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::components::io::{BrowserConfig, BrowserStep};
+use ferroflux_core::resources::browser::{BrowserBackend, BrowserPage, HeadlessBrowser};
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::browser_worker;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// One element of a fake page: its text and attributes.
+type FakeElement = (&'static str, HashMap<&'static str, &'static str>);
+
+/// Serves canned pages keyed by URL; redirects are modelled as a URL mapping to another.
+#[derive(Default)]
+struct FakeBrowser {
+    pages: HashMap<&'static str, HashMap<&'static str, Vec<FakeElement>>>,
+    redirects: HashMap<&'static str, &'static str>,
+    visited: Arc<Mutex<Vec<String>>>,
+}
+
+struct FakePage {
+    pages: HashMap<&'static str, HashMap<&'static str, Vec<FakeElement>>>,
+    redirects: HashMap<&'static str, &'static str>,
+    visited: Arc<Mutex<Vec<String>>>,
+    url: Option<String>,
+}
+
+#[async_trait]
+impl BrowserBackend for FakeBrowser {
+    async fn open(&self) -> anyhow::Result<Box<dyn BrowserPage>> {
+        Ok(Box::new(FakePage {
+            pages: self.pages.clone(),
+            redirects: self.redirects.clone(),
+            visited: self.visited.clone(),
+            url: None,
+        }))
+    }
+}
+
+impl FakePage {
+    fn elements(&self, selector: &str) -> Vec<FakeElement> {
+        self.url
+            .as_deref()
+            .and_then(|url| self.pages.get(url))
+            .and_then(|page| page.get(selector))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl BrowserPage for FakePage {
+    async fn goto(&mut self, url: &str) -> anyhow::Result<()> {
+        self.visited.lock().unwrap().push(url.to_string());
+        let landed = self.redirects.get(url).copied().unwrap_or(url);
+        self.url = Some(landed.to_string());
+        Ok(())
+    }
+
+    async fn current_url(&mut self) -> anyhow::Result<Option<String>> {
+        Ok(self.url.clone())
+    }
+
+    async fn exists(&mut self, selector: &str) -> anyhow::Result<bool> {
+        Ok(!self.elements(selector).is_empty())
+    }
+
+    async fn texts(&mut self, selector: &str) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .elements(selector)
+            .into_iter()
+            .map(|(text, _)| text.to_string())
+            .collect())
+    }
+
+    async fn attributes(
+        &mut self,
+        selector: &str,
+        attribute: &str,
+    ) -> anyhow::Result<Vec<Option<String>>> {
+        Ok(self
+            .elements(selector)
+            .into_iter()
+            .map(|(_, attrs)| attrs.get(attribute).map(|v| v.to_string()))
+            .collect())
+    }
+
+    async fn screenshot(&mut self, _full_page: bool) -> anyhow::Result<Vec<u8>> {
+        Ok(b"\x89PNG fake".to_vec())
+    }
+
+    async fn close(self: Box<Self>) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn fake_browser() -> FakeBrowser {
+    let listing = HashMap::from([
+        ("h1", vec![("Changelog", HashMap::new())]),
+        (
+            "a.release",
+            vec![
+                ("v2.0", HashMap::from([("href", "/releases/2.0")])),
+                ("v1.9", HashMap::from([("href", "/releases/1.9")])),
+            ],
+        ),
+    ]);
+    FakeBrowser {
+        pages: HashMap::from([("http://127.0.0.1/changelog", listing)]),
+        redirects: HashMap::from([("http://127.0.0.1/moved", "http://10.0.0.5/admin")]),
+        ..Default::default()
+    }
+}
+
+async fn run(backend: FakeBrowser, config: BrowserConfig) -> (Option<String>, Value, BlobStore) {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(HeadlessBrowser::new(Some(Arc::new(backend))));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    let mut settings = EngineSettings::default();
+    settings.network.ssrf_allowlist = vec!["127.0.0.1".to_string()];
+    world.insert_resource(RuntimeSettings::new(settings));
+
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(br#"{ "page": "changelog" }"#).unwrap());
+    let node = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Scrape Changelog".to_string(),
+                node_type: "Browser".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(browser_worker);
+
+    for _ in 0..50 {
+        schedule.run(&mut world);
+        if let Some((port, ticket)) = world.get_mut::<Outbox>(node).unwrap().queue.pop_front() {
+            let payload = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap();
+            return (port, payload, store);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Browser worker timed out");
+}
+
+fn config(url: &str, steps: Vec<BrowserStep>) -> BrowserConfig {
+    BrowserConfig {
+        url: url.to_string(),
+        steps,
+        timeout_ms: 1_000,
+        result_key: "page".to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_browser_extracts_and_screenshots() {
+    let (port, output, store) = run(
+        fake_browser(),
+        config(
+            "http://127.0.0.1/{{page}}",
+            vec![
+                BrowserStep::WaitForSelector {
+                    selector: "a.release".to_string(),
+                    timeout_ms: None,
+                },
+                BrowserStep::ExtractText {
+                    selector: "h1".to_string(),
+                    key: "title".to_string(),
+                    all: false,
+                },
+                BrowserStep::ExtractAttribute {
+                    selector: "a.release".to_string(),
+                    attribute: "href".to_string(),
+                    key: "links".to_string(),
+                    all: true,
+                },
+                BrowserStep::Screenshot {
+                    key: "shot".to_string(),
+                    full_page: true,
+                },
+            ],
+        ),
+    )
+    .await;
+
+    assert_eq!(port, None);
+    assert_eq!(output["page"]["title"], "Changelog");
+    assert_eq!(
+        output["page"]["links"],
+        json!(["/releases/2.0", "/releases/1.9"])
+    );
+    assert_eq!(output["page"]["url"], "http://127.0.0.1/changelog");
+
+    let blob_id = output["page"]["shot"]["blob_id"].as_str().unwrap();
+    let shot = store
+        .recover_ticket(&blob_id.parse().unwrap())
+        .expect("screenshot stored");
+    assert_eq!(shot.metadata["content_type"], "image/png");
+    assert_eq!(store.claim(&shot).unwrap(), b"\x89PNG fake");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_browser_enforces_policy_and_timeouts() {
+    // Blocked before the page is ever asked to load.
+    let backend = fake_browser();
+    let visited = backend.visited.clone();
+    let (port, _, _) = run(backend, config("http://10.0.0.5/admin", vec![])).await;
+    assert_eq!(port.as_deref(), Some("error"));
+    assert!(visited.lock().unwrap().is_empty());
+
+    // A redirect into the internal network is refused too.
+    let (port, _, _) = run(fake_browser(), config("http://127.0.0.1/moved", vec![])).await;
+    assert_eq!(port.as_deref(), Some("error"));
+
+    for url in ["file:///etc/passwd", "javascript:alert(1)"] {
+        let (port, _, _) = run(fake_browser(), config(url, vec![])).await;
+        assert_eq!(port.as_deref(), Some("error"), "{}", url);
+    }
+
+    let (port, _, _) = run(
+        fake_browser(),
+        config(
+            "http://127.0.0.1/changelog",
+            vec![BrowserStep::WaitForSelector {
+                selector: "#never".to_string(),
+                timeout_ms: Some(150),
+            }],
+        ),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+}
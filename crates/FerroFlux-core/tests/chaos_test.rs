@@ -0,0 +1,107 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::core::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::chaos::{CHAOS_CLAIM_FAULT_KEY, FaultInjector, FaultProfile};
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use std::collections::HashMap;
+
+fn node(world: &mut World, node_type: &str) -> Entity {
+    world
+        .spawn((
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: node_type.to_string(),
+                node_type: node_type.to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            Inbox::default(),
+            Outbox::default(),
+        ))
+        .id()
+}
+
+fn setup(chaos: FaultInjector) -> (World, Schedule, Entity, Entity, Entity) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(GraphTopology::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(chaos);
+
+    let source = node(&mut world, "Webhook");
+    let flaky = node(&mut world, "Http");
+    let stable = node(&mut world, "Log");
+    for target in [flaky, stable] {
+        world.spawn(Edge {
+            source,
+            source_handle: None,
+            target,
+            target_handle: None,
+        });
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((update_graph_topology, transport_worker).chain());
+    (world, schedule, source, flaky, stable)
+}
+
+fn emit(world: &mut World, source: Entity) {
+    let ticket = world.resource::<BlobStore>().check_in(b"{}").unwrap();
+    world
+        .get_mut::<Outbox>(source)
+        .unwrap()
+        .queue
+        .push_back((None, ticket));
+}
+
+#[test]
+fn test_drop_faults_only_hit_targeted_node_type() {
+    let chaos = FaultInjector::new(7).with_profile(
+        "Http",
+        FaultProfile {
+            drop_rate: 1.0,
+            ..Default::default()
+        },
+    );
+    let (mut world, mut schedule, source, flaky, stable) = setup(chaos);
+
+    emit(&mut world, source);
+    schedule.run(&mut world);
+
+    assert!(world.get::<Inbox>(flaky).unwrap().queue.is_empty());
+    assert_eq!(world.get::<Inbox>(stable).unwrap().queue.len(), 1);
+}
+
+#[test]
+fn test_claim_faults_poison_delivered_tickets() {
+    let chaos = FaultInjector::new(7).with_profile(
+        "Http",
+        FaultProfile {
+            claim_error_rate: 1.0,
+            ..Default::default()
+        },
+    );
+    let (mut world, mut schedule, source, flaky, stable) = setup(chaos);
+
+    emit(&mut world, source);
+    schedule.run(&mut world);
+
+    let store = world.resource::<BlobStore>().clone();
+    let flaky_ticket = world.get::<Inbox>(flaky).unwrap().queue[0].clone();
+    let stable_ticket = world.get::<Inbox>(stable).unwrap().queue[0].clone();
+    assert!(store.claim(&flaky_ticket).is_err());
+    assert!(store.claim(&stable_ticket).is_ok());
+}
+
+#[test]
+fn test_claim_fault_marker_is_ignored_without_injector() {
+    let store = BlobStore::default();
+    let metadata = HashMap::from([(CHAOS_CLAIM_FAULT_KEY.to_string(), "true".to_string())]);
+    let ticket = store.check_in_with_metadata(b"{}", metadata).unwrap();
+    assert!(store.claim(&ticket).is_ok());
+}
@@ -0,0 +1,128 @@
+use base64::{Engine as _, engine::general_purpose};
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::io::FileMode;
+use ferroflux_core::components::manipulation::{
+    CompressionConfig, CompressionFormat, CompressionOperation,
+};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::manipulation::compression_worker;
+use serde_json::{Value, json};
+use std::io::Write;
+
+/// Runs one payload through a compression node and returns `(port, output)`.
+fn run(config: CompressionConfig, input: Value) -> (Option<String>, Value) {
+    let mut world = World::new();
+    let mut schedule = Schedule::default();
+    let (tx, _) = tokio::sync::broadcast::channel(10);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    schedule.add_systems(compression_worker);
+
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(
+        store
+            .check_in(&serde_json::to_vec(&input).unwrap())
+            .unwrap(),
+    );
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Test Compression".to_string(),
+                node_type: "Compression".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+    schedule.run(&mut world);
+
+    let (port, ticket) = world.get::<Outbox>(entity).unwrap().queue[0].clone();
+    let output = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap_or(Value::Null);
+    (port, output)
+}
+
+fn config(operation: CompressionOperation, format: CompressionFormat) -> CompressionConfig {
+    CompressionConfig {
+        operation,
+        format,
+        source_field: None,
+        mode: FileMode::Json,
+        result_key: None,
+        max_output_bytes: 1024 * 1024,
+    }
+}
+
+#[test]
+fn test_compression_round_trip() {
+    let report = json!({ "rows": [1, 2, 3], "name": "export" });
+    for format in [CompressionFormat::Gzip, CompressionFormat::Zstd] {
+        let (port, compressed) = run(
+            config(CompressionOperation::Compress, format.clone()),
+            report.clone(),
+        );
+        assert_eq!(port, None);
+        assert!(compressed.is_string());
+
+        // Decompress from a field, detecting the format.
+        let mut decompress = config(CompressionOperation::Decompress, CompressionFormat::Auto);
+        decompress.source_field = Some("body".to_string());
+        decompress.result_key = Some("report".to_string());
+        let (port, output) = run(decompress, json!({ "id": 7, "body": compressed }));
+        assert_eq!(port, None, "{:?}", format);
+        assert_eq!(output["id"], 7);
+        assert_eq!(output["report"], report);
+    }
+}
+
+#[test]
+fn test_zip_extraction() {
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    archive.add_directory("export/", options).unwrap();
+    archive.start_file("export/a.csv", options).unwrap();
+    archive.write_all(b"id,total\n1,10\n").unwrap();
+    archive.start_file("readme.txt", options).unwrap();
+    archive.write_all(b"hello").unwrap();
+    let bytes = archive.finish().unwrap().into_inner();
+
+    let mut extract = config(CompressionOperation::Decompress, CompressionFormat::Auto);
+    extract.mode = FileMode::Text;
+    let (port, output) = run(extract, json!(general_purpose::STANDARD.encode(bytes)));
+    assert_eq!(port, None);
+    assert_eq!(
+        output,
+        json!({ "export/a.csv": "id,total\n1,10\n", "readme.txt": "hello" })
+    );
+}
+
+#[test]
+fn test_decompression_guards() {
+    // 1 MiB of zeros compresses to about a kilobyte.
+    let mut compress = config(CompressionOperation::Compress, CompressionFormat::Gzip);
+    compress.mode = FileMode::Text;
+    let (_, bomb) = run(compress, json!("0".repeat(1024 * 1024)));
+
+    let mut limited = config(CompressionOperation::Decompress, CompressionFormat::Auto);
+    limited.mode = FileMode::Text;
+    limited.max_output_bytes = 64 * 1024;
+    let (port, _) = run(limited.clone(), bomb);
+    assert_eq!(port.as_deref(), Some("error"));
+
+    let (port, _) = run(
+        limited,
+        json!(general_purpose::STANDARD.encode("plain text")),
+    );
+    assert_eq!(port.as_deref(), Some("error"));
+
+    let zip_only = config(CompressionOperation::Compress, CompressionFormat::Zip);
+    let (port, _) = run(zip_only, json!({}));
+    assert_eq!(port.as_deref(), Some("error"));
+}
@@ -0,0 +1,149 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::connection::handle_connection_verified;
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::connection_health::{
+    ConnectionHealth, ConnectionOutcome, PausedWorkflows,
+};
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{GraphTopology, TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::connection_health_worker;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use ferroflux_iam::TenantId;
+use std::time::Duration;
+
+fn node(name: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        node_type: "Http".to_string(),
+        workflow_id: Some("billing".to_string()),
+        tenant_id: Some(TenantId::from("acme")),
+        version: None,
+    }
+}
+
+async fn connection_status(store: &PersistentStore, tenant: &TenantId, expected: &str) -> bool {
+    for _ in 0..50 {
+        let (_, _, _, _, status) = store
+            .get_connection_by_slug(tenant, "crm")
+            .await
+            .unwrap()
+            .unwrap();
+        if status == expected {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failing_connection_pauses_workflows_until_verified() {
+    let tenant = TenantId::from("acme");
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    store
+        .save_connection(&tenant, "crm", "CRM", "http", b"{}", b"nonce", "active")
+        .await
+        .unwrap();
+
+    let mut world = World::new();
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(store.clone());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    let settings = EngineSettings::default()
+        .patched(serde_json::json!({ "connections": { "pause_dependent_workflows": true } }))
+        .unwrap();
+    world.insert_resource(RuntimeSettings::new(settings.clone()));
+    let health = ConnectionHealth::default();
+    world.insert_resource(health.clone());
+    world.insert_resource(PausedWorkflows::default());
+    world.insert_resource(GraphTopology::default());
+    world.insert_resource(WorkDone::default());
+
+    let blob_store = BlobStore::default();
+    let source = world
+        .spawn((node("Fetch Invoices"), Inbox::default(), Outbox::default()))
+        .id();
+    let target = world
+        .spawn((node("Push to CRM"), Inbox::default(), Outbox::default()))
+        .id();
+    world.spawn(Edge {
+        source,
+        source_handle: None,
+        target,
+        target_handle: None,
+    });
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((
+        connection_health_worker,
+        (update_graph_topology, transport_worker).chain(),
+    ));
+
+    // Two rejections don't trip the default threshold of three.
+    for _ in 0..2 {
+        health.record(
+            &settings.connections,
+            &tenant,
+            "crm",
+            Some("billing"),
+            ConnectionOutcome::AuthFailure,
+        );
+    }
+    schedule.run(&mut world);
+    assert!(
+        !world
+            .resource::<PausedWorkflows>()
+            .is_paused(&tenant, "billing")
+    );
+
+    health.record(
+        &settings.connections,
+        &tenant,
+        "crm",
+        Some("billing"),
+        ConnectionOutcome::AuthFailure,
+    );
+    schedule.run(&mut world);
+
+    let event = std::iter::from_fn(|| events.try_recv().ok())
+        .find_map(|e| match e {
+            SystemEvent::ConnectionUnhealthy {
+                slug,
+                auth_failures,
+                paused_workflows,
+                ..
+            } => Some((slug, auth_failures, paused_workflows)),
+            _ => None,
+        })
+        .expect("ConnectionUnhealthy event");
+    assert_eq!(event, ("crm".to_string(), 3, vec!["billing".to_string()]));
+    assert!(
+        world
+            .resource::<PausedWorkflows>()
+            .is_paused(&tenant, "billing")
+    );
+    assert!(connection_status(&store, &tenant, "error").await);
+
+    // Tickets stay in the paused workflow's outboxes.
+    let ticket = blob_store.check_in(b"{}").unwrap();
+    world
+        .get_mut::<Outbox>(source)
+        .unwrap()
+        .queue
+        .push_back((None, ticket));
+    schedule.run(&mut world);
+    assert_eq!(world.get::<Outbox>(source).unwrap().queue.len(), 1);
+    assert!(world.get::<Inbox>(target).unwrap().queue.is_empty());
+
+    handle_connection_verified(&mut world, tenant.clone(), "crm".to_string()).unwrap();
+    schedule.run(&mut world);
+    assert!(world.get::<Outbox>(source).unwrap().queue.is_empty());
+    assert_eq!(world.get::<Inbox>(target).unwrap().queue.len(), 1);
+    assert!(!health.is_tripped(&tenant, "crm"));
+    assert!(connection_status(&store, &tenant, "active").await);
+}
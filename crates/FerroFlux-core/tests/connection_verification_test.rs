@@ -0,0 +1,171 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::Reply;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::api::handlers::connection::handle_verify_connection;
+use ferroflux_core::integrations::{IntegrationDef, IntegrationRegistry};
+use ferroflux_core::resources::TokioRuntime;
+use ferroflux_core::resources::connection_health::{ConnectionHealth, PausedWorkflows};
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::connection_health_worker;
+use ferroflux_iam::TenantId;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn world(server: &MockServer) -> (World, PersistentStore) {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let secrets = DatabaseSecretStore::new(store.clone(), vec![7; 32]);
+    let tenant = TenantId::from("acme");
+    for (slug, key) in [("good", "sk-good"), ("bad", "sk-bad")] {
+        secrets
+            .keyring()
+            .save_connection(
+                &tenant,
+                slug,
+                slug,
+                "crm",
+                &json!({ "token": key }),
+                "unverified",
+            )
+            .await
+            .unwrap();
+    }
+
+    let crm: IntegrationDef = serde_yaml::from_str(&format!(
+        r#"
+name: crm
+base_url: "{}/api"
+auth: {{ type: bearer }}
+verify_endpoint: /me
+verify_params: {{ api_key: token }}
+actions: {{}}
+"#,
+        server.uri()
+    ))
+    .unwrap();
+    let mut registry = IntegrationRegistry::default();
+    registry.register(crm).unwrap();
+
+    let mut world = World::new();
+    world.insert_resource(SystemEventBus::new(tokio::sync::broadcast::channel(16).0));
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(ConnectionHealth::default());
+    world.insert_resource(store.clone());
+    world.insert_resource(secrets);
+    world.insert_resource(registry);
+    (world, store)
+}
+
+async fn status(store: &PersistentStore, slug: &str, expected: &str) -> bool {
+    for _ in 0..50 {
+        let (_, _, _, _, status) = store
+            .get_connection_by_slug(&TenantId::from("acme"), slug)
+            .await
+            .unwrap()
+            .unwrap();
+        if status == expected {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    false
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_verification_calls_the_verify_endpoint_and_records_the_status() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/me"))
+        .and(header("authorization", "Bearer sk-good"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1 })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/me"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("invalid token"))
+        .mount(&server)
+        .await;
+    let (mut world, store) = world(&server).await;
+
+    let acme = TenantId::from("acme");
+    let mut paused = PausedWorkflows::default();
+    paused.pause(&acme, "orders", "good");
+    world.insert_resource(paused);
+
+    let (reply, rx) = Reply::channel();
+    handle_verify_connection(&mut world, acme.clone(), "good".into(), reply).unwrap();
+    let good = rx.await.unwrap().unwrap();
+    assert!(good.verified);
+    assert_eq!(good.http_status, Some(200));
+    assert!(status(&store, "good", "active").await);
+
+    // The health worker resumes the connection's workflows on the next tick.
+    let mut schedule = Schedule::default();
+    schedule.add_systems(connection_health_worker);
+    schedule.run(&mut world);
+    assert!(world.resource::<PausedWorkflows>().0.is_empty());
+
+    let (reply, rx) = Reply::channel();
+    handle_verify_connection(&mut world, acme, "bad".into(), reply).unwrap();
+    let bad = rx.await.unwrap().unwrap();
+    assert!(!bad.verified);
+    assert_eq!(bad.status, "error");
+    assert_eq!(bad.http_status, Some(401));
+    assert!(bad.error.unwrap().contains("invalid token"));
+    assert!(status(&store, "bad", "error").await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unknown_connections_are_reported_to_the_caller() {
+    let server = MockServer::start().await;
+    let (mut world, _) = world(&server).await;
+
+    let (reply, rx) = Reply::channel();
+    handle_verify_connection(&mut world, TenantId::from("acme"), "nope".into(), reply).unwrap();
+    assert!(rx.await.unwrap().unwrap_err().contains("not found"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unreachable_providers_are_reported_without_the_request_url() {
+    let server = MockServer::start().await;
+    let (mut world, store) = world(&server).await;
+    let secrets = world.resource::<DatabaseSecretStore>().clone();
+    secrets
+        .keyring()
+        .save_connection(
+            &TenantId::from("acme"),
+            "offline",
+            "offline",
+            "offline_crm",
+            &json!({ "token": "sk-secret" }),
+            "unverified",
+        )
+        .await
+        .unwrap();
+    // Nothing listens on the discard port.
+    let offline: IntegrationDef = serde_yaml::from_str(
+        r#"
+name: offline_crm
+base_url: "http://127.0.0.1:9"
+verify_endpoint: "/me?key={{api_key}}"
+verify_params: { api_key: token }
+actions: {}
+"#,
+    )
+    .unwrap();
+    world
+        .resource_mut::<IntegrationRegistry>()
+        .register(offline)
+        .unwrap();
+
+    let (reply, rx) = Reply::channel();
+    handle_verify_connection(&mut world, TenantId::from("acme"), "offline".into(), reply).unwrap();
+    let outcome = rx.await.unwrap().unwrap();
+    assert!(!outcome.verified);
+    let error = outcome.error.unwrap();
+    assert!(!error.contains("sk-secret"), "{error}");
+    assert!(!error.contains("127.0.0.1"), "{error}");
+    assert!(status(&store, "offline", "error").await);
+}
@@ -0,0 +1,192 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::{
+    ContractConfig, ContractState, Inbox, NodeConfig, Outbox, SchemaDraft,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::logic::contract_worker;
+use serde_json::{Value, json};
+use tokio::sync::broadcast;
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    store: BlobStore,
+    entity: Entity,
+    events: broadcast::Receiver<SystemEvent>,
+}
+
+impl Harness {
+    fn new(config: ContractConfig) -> Self {
+        let mut world = World::new();
+        let (tx, events) = broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        let store = BlobStore::default();
+        world.insert_resource(store.clone());
+        let entity = world
+            .spawn((
+                config,
+                ContractState::default(),
+                NodeConfig {
+                    id: uuid::Uuid::new_v4(),
+                    name: "Webhook Contract".to_string(),
+                    node_type: "Contract".to_string(),
+                    workflow_id: None,
+                    tenant_id: None,
+                    version: None,
+                },
+                Inbox::default(),
+                Outbox::default(),
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(contract_worker);
+        Self {
+            world,
+            schedule,
+            store,
+            entity,
+            events,
+        }
+    }
+
+    fn run(&mut self, input: Value) -> (Option<String>, Value) {
+        let ticket = self
+            .store
+            .check_in(&serde_json::to_vec(&input).unwrap())
+            .unwrap();
+        self.world
+            .get_mut::<Inbox>(self.entity)
+            .unwrap()
+            .queue
+            .push_back(ticket);
+        self.schedule.run(&mut self.world);
+        let (port, ticket) = self
+            .world
+            .get_mut::<Outbox>(self.entity)
+            .unwrap()
+            .queue
+            .pop_front()
+            .unwrap();
+        let output = serde_json::from_slice(&self.store.claim(&ticket).unwrap()).unwrap();
+        (port, output)
+    }
+}
+
+fn order_contract() -> ContractConfig {
+    ContractConfig {
+        schema: json!({
+            "type": "object",
+            "required": ["id", "items"],
+            "properties": {
+                "id": { "type": "string" },
+                "items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["sku"],
+                        "properties": { "qty": { "type": "integer", "minimum": 1 } }
+                    }
+                }
+            }
+        }),
+        draft: SchemaDraft::Draft7,
+        target_field: None,
+        max_violations: 20,
+    }
+}
+
+#[test]
+fn test_contract_passes_valid_payloads() {
+    let mut harness = Harness::new(order_contract());
+    let order = json!({ "id": "o-1", "items": [{ "sku": "A", "qty": 2 }] });
+    let (port, output) = harness.run(order.clone());
+    assert_eq!(port, None);
+    assert_eq!(output, order);
+    assert!(
+        std::iter::from_fn(|| harness.events.try_recv().ok())
+            .all(|e| !matches!(e, SystemEvent::ContractViolation { .. }))
+    );
+}
+
+#[test]
+fn test_contract_reports_violation_paths() {
+    let mut harness = Harness::new(order_contract());
+    let order = json!({ "id": 7, "items": [{ "sku": "A" }, { "qty": 0 }] });
+    let (port, output) = harness.run(order.clone());
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(output["payload"], order);
+
+    let mut paths: Vec<(String, String)> = output["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["path"].as_str().unwrap().to_string(),
+                v["schema_path"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            ("/id".to_string(), "/properties/id/type".to_string()),
+            (
+                "/items/1".to_string(),
+                "/properties/items/items/required".to_string()
+            ),
+            (
+                "/items/1/qty".to_string(),
+                "/properties/items/items/properties/qty/minimum".to_string()
+            ),
+        ]
+    );
+
+    let event = std::iter::from_fn(|| harness.events.try_recv().ok())
+        .find_map(|e| match e {
+            SystemEvent::ContractViolation { violations, .. } => Some(violations),
+            _ => None,
+        })
+        .expect("ContractViolation event");
+    assert_eq!(event.len(), 3);
+}
+
+#[test]
+fn test_contract_target_field_and_limits() {
+    let mut config = order_contract();
+    config.target_field = Some("body".to_string());
+    config.max_violations = 1;
+    let mut harness = Harness::new(config);
+
+    let (port, output) = harness.run(json!({ "body": { "id": 1, "items": "none" } }));
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(output["violations"].as_array().unwrap().len(), 1);
+
+    let (port, _) = harness.run(json!({ "body": { "id": "o-2", "items": [] } }));
+    assert_eq!(port, None);
+}
+
+#[test]
+fn test_contract_draft_selection() {
+    // `exclusiveMinimum` is a boolean modifier in draft 4 and a number from draft 6 on.
+    let schema = json!({ "type": "number", "minimum": 5, "exclusiveMinimum": true });
+    let mut draft4 = Harness::new(ContractConfig {
+        schema: schema.clone(),
+        draft: SchemaDraft::Draft4,
+        target_field: Some("n".to_string()),
+        max_violations: 20,
+    });
+    assert_eq!(draft4.run(json!({ "n": 5 })).0.as_deref(), Some("error"));
+    assert_eq!(draft4.run(json!({ "n": 6 })).0, None);
+
+    // Under draft 7 the same schema is invalid and every ticket is rejected.
+    let mut draft7 = Harness::new(ContractConfig {
+        schema,
+        draft: SchemaDraft::Draft7,
+        target_field: Some("n".to_string()),
+        max_violations: 20,
+    });
+    assert_eq!(draft7.run(json!({ "n": 6 })).0.as_deref(), Some("error"));
+}
@@ -3,6 +3,7 @@ use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
 use ferroflux_core::components::control::CheckpointConfig;
 use ferroflux_core::components::core::{Inbox, NodeConfig};
 use ferroflux_iam::TenantId;
+use ferroflux_core::resources::TokioRuntime;
 use ferroflux_core::store::BlobStore;
 use ferroflux_core::store::database::PersistentStore;
 use ferroflux_core::systems::control::checkpoint_worker;
@@ -16,12 +17,13 @@ async fn setup_world_async() -> World {
     world.insert_resource(blob_store);
 
     let (tx, _) = broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
 
     let db_url = "sqlite::memory:";
     let store = PersistentStore::new(db_url).await.unwrap();
     // Create table if not exists (PersistentStore already does this in new())
     world.insert_resource(store);
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
 
     world
 }
@@ -34,7 +36,7 @@ async fn test_checkpoint_hibernate() {
 
     let store = world.resource::<BlobStore>().clone();
     // Subscribe to events
-    let mut event_rx = world.resource::<SystemEventBus>().0.subscribe();
+    let mut event_rx = world.resource::<SystemEventBus>().subscribe();
 
     // 1. Create Checkpoint Node
     let node_id = Uuid::new_v4();
@@ -55,6 +57,7 @@ async fn test_checkpoint_hibernate() {
             node_type: "Switch".to_string(),
             workflow_id: None,
             tenant_id: Some(TenantId::from("default_tenant")),
+            version: None,
         },
         config,
         inbox,
@@ -0,0 +1,138 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::manipulation::{DateTimeConfig, TimeFormat, TimeUnit};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::manipulation::datetime_worker;
+use serde_json::{Value, json};
+
+/// Runs one payload through a date/time node and returns `(port, result)`.
+fn run(config: DateTimeConfig, input: Value) -> (Option<String>, Value) {
+    let mut world = World::new();
+    let mut schedule = Schedule::default();
+    let (tx, _) = tokio::sync::broadcast::channel(10);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    schedule.add_systems(datetime_worker);
+
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(
+        store
+            .check_in(&serde_json::to_vec(&input).unwrap())
+            .unwrap(),
+    );
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Test DateTime".to_string(),
+                node_type: "DateTime".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+    schedule.run(&mut world);
+
+    let (port, ticket) = world.get::<Outbox>(entity).unwrap().queue[0].clone();
+    let output: Value = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap();
+    (port, output["datetime"].clone())
+}
+
+fn base_config() -> DateTimeConfig {
+    DateTimeConfig {
+        source_field: Some("ts".to_string()),
+        input_format: TimeFormat::Auto,
+        input_timezone: None,
+        timezone: None,
+        shift: None,
+        truncate: None,
+        output_format: TimeFormat::Iso8601,
+        result_key: "datetime".to_string(),
+    }
+}
+
+#[test]
+fn test_datetime_format_conversions() {
+    let cases = [
+        // (input, output format, expected)
+        (
+            json!(1709294400),
+            TimeFormat::Iso8601,
+            json!("2024-03-01T12:00:00+00:00"),
+        ),
+        (
+            json!(1709294400123_i64),
+            TimeFormat::EpochSeconds,
+            json!(1709294400),
+        ),
+        (
+            json!("Fri, 01 Mar 2024 13:00:00 +0100"),
+            TimeFormat::EpochMillis,
+            json!(1709294400000_i64),
+        ),
+        (
+            json!("2024-03-01T12:00:00Z"),
+            TimeFormat::Rfc2822,
+            json!("Fri, 1 Mar 2024 12:00:00 +0000"),
+        ),
+        (
+            json!("2024-03-01 12:00"),
+            TimeFormat::Custom("%d/%m/%Y %H:%M".to_string()),
+            json!("01/03/2024 12:00"),
+        ),
+    ];
+    for (input, output_format, expected) in cases {
+        let mut config = base_config();
+        config.output_format = output_format;
+        let (port, result) = run(config, json!({ "ts": input }));
+        assert_eq!(port, None);
+        assert_eq!(result, expected, "input {}", input);
+    }
+}
+
+#[test]
+fn test_datetime_timezone_shift_and_truncate() {
+    // Local midnight in Berlin, one day after the input.
+    let mut config = base_config();
+    config.timezone = Some("Europe/Berlin".to_string());
+    config.shift = Some("+1day".to_string());
+    config.truncate = Some(TimeUnit::Day);
+    let (_, result) = run(config, json!({ "ts": "2024-03-30T12:00:00Z" }));
+    // Crosses the DST switch on 31 March: midnight is still +01:00.
+    assert_eq!(result, "2024-03-31T00:00:00+01:00");
+
+    // Offset-less input is read in the input zone.
+    let mut config = base_config();
+    config.input_timezone = Some("America/New_York".to_string());
+    config.shift = Some("-90m".to_string());
+    config.truncate = Some(TimeUnit::Hour);
+    let (_, result) = run(config, json!({ "ts": "2024-07-04 09:15:00" }));
+    assert_eq!(result, "2024-07-04T11:00:00+00:00");
+
+    let mut config = base_config();
+    config.truncate = Some(TimeUnit::Week);
+    let (_, result) = run(config, json!({ "ts": "2024-03-01T12:00:00Z" }));
+    assert_eq!(result, "2024-02-26T00:00:00+00:00");
+}
+
+#[test]
+fn test_datetime_errors_route_to_error() {
+    let (port, _) = run(base_config(), json!({ "ts": "not a date" }));
+    assert_eq!(port.as_deref(), Some("error"));
+
+    let mut bad_zone = base_config();
+    bad_zone.timezone = Some("Mars/Olympus".to_string());
+    let (port, _) = run(bad_zone, json!({ "ts": 0 }));
+    assert_eq!(port.as_deref(), Some("error"));
+
+    let mut bad_pattern = base_config();
+    bad_pattern.output_format = TimeFormat::Custom("%Q".to_string());
+    let (port, _) = run(bad_pattern, json!({ "ts": 0 }));
+    assert_eq!(port.as_deref(), Some("error"));
+}
@@ -0,0 +1,55 @@
+use ferroflux_core::resources::mailer::{EmailSender, OutgoingEmail, SmtpSecurity, SmtpSettings};
+use ferroflux_iam::{IamStore, Principal, Role, TenantId};
+use serde_json::json;
+
+fn email() -> OutgoingEmail {
+    OutgoingEmail {
+        to: vec!["user@example.com".to_string()],
+        subject: "Hello".to_string(),
+        body: "World".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_smtp_connection_parsing_and_host_validation() {
+    let settings: SmtpSettings = serde_json::from_value(json!({
+        "host": "127.0.0.1",
+        "username": "mailer",
+        "password": "secret",
+        "from": "FerroFlux <noreply@example.com>",
+        "security": "tls"
+    }))
+    .unwrap();
+    assert_eq!(settings.security, SmtpSecurity::Tls);
+
+    let err = EmailSender::default()
+        .send(&settings, email())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Security Validation Failed"));
+}
+
+#[tokio::test]
+async fn test_system_email_requires_configuration() {
+    let err = EmailSender::new(None)
+        .send_system(email())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not configured"));
+}
+
+#[tokio::test]
+async fn test_invitation_link_is_checked_before_inviting() {
+    // The tenant doesn't exist, so getting as far as inviting would fail differently.
+    let iam = IamStore::new("sqlite::memory:").await.unwrap();
+    let acme = TenantId::from("acme");
+    let owner = Principal::new("ada").with_membership(acme.clone(), Role::Owner);
+    for link in ["not a url", "javascript:alert(1)"] {
+        let err = EmailSender::new(None)
+            .send_invitation(&iam, &owner, &acme, "bob@example.com", Role::Editor, link)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid invitation base URL"));
+    }
+}
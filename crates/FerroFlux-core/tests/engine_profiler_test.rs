@@ -0,0 +1,83 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::app::App;
+use ferroflux_core::components::core::NodeConfig;
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::profiler::{EngineProfiler, profiled};
+use std::time::Duration;
+
+fn slow_worker() {
+    std::thread::sleep(Duration::from_millis(20));
+}
+
+fn fast_worker() {}
+
+fn setup_app(
+    profiler: Option<EngineProfiler>,
+) -> (App, tokio::sync::broadcast::Receiver<SystemEvent>) {
+    let mut world = World::new();
+    let (tx, rx) = tokio::sync::broadcast::channel(16);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(WorkDone::default());
+    if let Some(profiler) = profiler {
+        world.insert_resource(profiler);
+    }
+    world.spawn(NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: "Node".to_string(),
+        node_type: "Http".to_string(),
+        workflow_id: None,
+        tenant_id: None,
+        version: None,
+    });
+    world.spawn_empty();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((profiled(slow_worker), profiled(fast_worker)));
+    (App { world, schedule }, rx)
+}
+
+#[test]
+fn test_engine_stats_report_slowest_systems() {
+    let (mut app, mut rx) = setup_app(Some(EngineProfiler::new(Duration::ZERO)));
+    app.update();
+
+    let Ok(SystemEvent::EngineStats {
+        ticks,
+        max_tick_ms,
+        slowest_systems,
+        entity_count,
+        node_count,
+        ..
+    }) = rx.try_recv()
+    else {
+        panic!("Expected EngineStats");
+    };
+    assert_eq!(ticks, 1);
+    assert!(max_tick_ms >= 20.0);
+    assert_eq!(entity_count, 2);
+    assert_eq!(node_count, 1);
+    assert_eq!(slowest_systems.len(), 2);
+    assert_eq!(slowest_systems[0].name, "slow_worker");
+    assert_eq!(slowest_systems[0].runs, 1);
+    assert!(slowest_systems[0].total_ms >= 20.0);
+
+    // The window resets after each report.
+    app.update();
+    let Ok(SystemEvent::EngineStats { ticks, .. }) = rx.try_recv() else {
+        panic!("Expected EngineStats");
+    };
+    assert_eq!(ticks, 1);
+}
+
+#[test]
+fn test_engine_stats_wait_for_interval() {
+    let (mut app, mut rx) = setup_app(Some(EngineProfiler::new(Duration::from_secs(3600))));
+    app.update();
+    assert!(rx.try_recv().is_err());
+
+    // Without the resource, wrapped systems still run and nothing is reported.
+    let (mut app, mut rx) = setup_app(None);
+    app.update();
+    assert!(rx.try_recv().is_err());
+}
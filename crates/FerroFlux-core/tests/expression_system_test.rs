@@ -13,7 +13,7 @@ fn test_expression_evaluation() {
 
     // 1. Setup Resources
     let (tx, _) = tokio::sync::broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
     let store = BlobStore::default();
     world.insert_resource(store.clone());
 
@@ -33,6 +33,7 @@ fn test_expression_evaluation() {
         node_type: "Expression".to_string(),
         workflow_id: None,
         tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+        version: None,
     };
 
     let mut inbox = Inbox::default();
@@ -70,7 +71,7 @@ fn test_expression_functions() {
     let mut schedule = Schedule::default();
 
     let (tx, _) = tokio::sync::broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
     let store = BlobStore::default();
     world.insert_resource(store.clone());
 
@@ -87,6 +88,7 @@ fn test_expression_functions() {
         node_type: "Expression".to_string(),
         workflow_id: None,
         tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+        version: None,
     };
 
     let mut inbox = Inbox::default();
@@ -0,0 +1,247 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::components::io::{FileIoConfig, FileMode, FileOperation};
+use ferroflux_core::resources::FileSandbox;
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::file_worker;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    store: BlobStore,
+    root: PathBuf,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("file_node_{}", uuid::Uuid::new_v4()));
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        let store = BlobStore::default();
+        world.insert_resource(store.clone());
+        world.insert_resource(FileSandbox(root.clone()));
+        let mut schedule = Schedule::default();
+        schedule.add_systems(file_worker);
+        Self {
+            world,
+            schedule,
+            store,
+            root,
+        }
+    }
+
+    /// Sends one payload through a fresh File node and returns `(port, payload)`.
+    fn run(&mut self, tenant: &str, config: FileIoConfig, input: Value) -> (Option<String>, Value) {
+        let mut inbox = Inbox::default();
+        inbox.queue.push_back(
+            self.store
+                .check_in(&serde_json::to_vec(&input).unwrap())
+                .unwrap(),
+        );
+        let entity = self
+            .world
+            .spawn((
+                config,
+                NodeConfig {
+                    id: uuid::Uuid::new_v4(),
+                    name: "File".to_string(),
+                    node_type: "File".to_string(),
+                    workflow_id: None,
+                    tenant_id: Some(ferroflux_iam::TenantId::from(tenant)),
+                    version: None,
+                },
+                inbox,
+                Outbox::default(),
+            ))
+            .id();
+        self.schedule.run(&mut self.world);
+
+        let (port, ticket) = self
+            .world
+            .get_mut::<Outbox>(entity)
+            .unwrap()
+            .queue
+            .pop_front()
+            .unwrap();
+        let payload = serde_json::from_slice(&self.store.claim(&ticket).unwrap()).unwrap();
+        (port, payload)
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.root).ok();
+    }
+}
+
+fn config(operation: FileOperation, path: &str, mode: FileMode) -> FileIoConfig {
+    FileIoConfig {
+        operation,
+        path: path.to_string(),
+        mode,
+        content_field: None,
+        result_key: "content".to_string(),
+    }
+}
+
+#[test]
+fn test_file_write_then_read_json() {
+    let mut harness = Harness::new();
+    let report = json!({ "report_id": "r-42", "total": 7 });
+
+    let (port, output) = harness.run(
+        "acme",
+        config(
+            FileOperation::Write,
+            "reports/{{report_id}}.json",
+            FileMode::Json,
+        ),
+        report.clone(),
+    );
+    assert_eq!(port, None);
+    assert_eq!(output, report);
+    assert!(harness.root.join("acme/reports/r-42.json").exists());
+
+    let (port, output) = harness.run(
+        "acme",
+        config(FileOperation::Read, "reports/r-42.json", FileMode::Json),
+        json!({ "request": 1 }),
+    );
+    assert_eq!(port, None);
+    assert_eq!(output["request"], 1);
+    assert_eq!(output["content"], report);
+}
+
+#[test]
+fn test_file_append_text_field() {
+    let mut harness = Harness::new();
+    let mut append = config(FileOperation::Append, "log.txt", FileMode::Text);
+    append.content_field = Some("line".to_string());
+
+    harness.run("acme", append.clone(), json!({ "line": "first\n" }));
+    harness.run("acme", append, json!({ "line": "second\n" }));
+
+    let (_, output) = harness.run(
+        "acme",
+        config(FileOperation::Read, "log.txt", FileMode::Bytes),
+        json!({}),
+    );
+    // "first\nsecond\n" in base64
+    assert_eq!(output["content"], "Zmlyc3QKc2Vjb25kCg==");
+}
+
+#[test]
+fn test_file_paths_stay_in_tenant_sandbox() {
+    let mut harness = Harness::new();
+    harness.run(
+        "acme",
+        config(FileOperation::Write, "secret.txt", FileMode::Text),
+        json!("acme only"),
+    );
+
+    // Another tenant sees its own (empty) directory.
+    let (port, _) = harness.run(
+        "globex",
+        config(FileOperation::Read, "secret.txt", FileMode::Text),
+        json!({}),
+    );
+    assert_eq!(port.as_deref(), Some("error"));
+
+    for path in ["../acme/secret.txt", "/etc/passwd", "{{path}}"] {
+        let (port, _) = harness.run(
+            "globex",
+            config(FileOperation::Read, path, FileMode::Text),
+            json!({ "path": "../../outside.txt" }),
+        );
+        assert_eq!(port.as_deref(), Some("error"), "{} was not rejected", path);
+    }
+}
+
+#[test]
+fn test_file_list_and_delete() {
+    let mut harness = Harness::new();
+    for path in ["b.txt", "a.txt", "archive/old.txt"] {
+        harness.run(
+            "acme",
+            config(FileOperation::Write, path, FileMode::Text),
+            json!("hello"),
+        );
+    }
+
+    let (port, output) = harness.run(
+        "acme",
+        config(FileOperation::List, "", FileMode::Text),
+        json!({}),
+    );
+    assert_eq!(port, None);
+    assert_eq!(
+        output["content"],
+        json!([
+            { "name": "a.txt", "path": "a.txt", "is_dir": false, "bytes": 5 },
+            { "name": "archive", "path": "archive", "is_dir": true, "bytes": 0 },
+            { "name": "b.txt", "path": "b.txt", "is_dir": false, "bytes": 5 },
+        ])
+    );
+
+    let (port, _) = harness.run(
+        "acme",
+        config(FileOperation::Delete, "archive/old.txt", FileMode::Text),
+        json!({}),
+    );
+    assert_eq!(port, None);
+    let (_, output) = harness.run(
+        "acme",
+        config(FileOperation::List, "archive", FileMode::Text),
+        json!({}),
+    );
+    assert_eq!(output["content"], json!([]));
+
+    // Deleting outside the sandbox or a missing file fails.
+    for path in ["../globex", "missing.txt"] {
+        let (port, _) = harness.run(
+            "acme",
+            config(FileOperation::Delete, path, FileMode::Text),
+            json!({}),
+        );
+        assert_eq!(port.as_deref(), Some("error"), "{}", path);
+    }
+}
+
+#[test]
+fn test_file_quotas_and_tenant_roots() {
+    let mut harness = Harness::new();
+    let globex_root = harness.root.join("custom/globex");
+    let mut settings = EngineSettings::default();
+    settings.files.tenant_quota_bytes = 10;
+    settings.files.max_file_bytes = 8;
+    settings.files.tenant_roots.insert(
+        "globex".to_string(),
+        globex_root.to_string_lossy().into_owned(),
+    );
+    harness
+        .world
+        .insert_resource(RuntimeSettings::new(settings));
+
+    let write = |path: &str| config(FileOperation::Write, path, FileMode::Text);
+    let (port, _) = harness.run("acme", write("a.txt"), json!("123456"));
+    assert_eq!(port, None);
+    // Over the per-file limit.
+    let (port, _) = harness.run("acme", write("big.txt"), json!("123456789"));
+    assert_eq!(port.as_deref(), Some("error"));
+    // Would take the tenant to 12 of 10 bytes.
+    let (port, _) = harness.run("acme", write("b.txt"), json!("123456"));
+    assert_eq!(port.as_deref(), Some("error"));
+    // Overwriting counts the replaced file as freed.
+    let (port, _) = harness.run("acme", write("a.txt"), json!("12345678"));
+    assert_eq!(port, None);
+
+    let (port, _) = harness.run("globex", write("report.txt"), json!("q1"));
+    assert_eq!(port, None);
+    assert!(globex_root.join("report.txt").exists());
+    assert!(!harness.root.join("globex").exists());
+}
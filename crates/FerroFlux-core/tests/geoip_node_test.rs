@@ -0,0 +1,150 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::connectors::{GeoIpConfig, GeoIpState};
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::{GeoIpResultChannel, GlobalHttpClient, TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::connectors::geoip_worker;
+use serde_json::{Value, json};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CHROME_UA: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Runs `inputs` through one Geo/IP node and returns `(port, payload)` per ticket, in the order
+/// the lookups finished.
+fn run(config: GeoIpConfig, inputs: Vec<Value>) -> Vec<(Option<String>, Value)> {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(GeoIpResultChannel::default());
+    world.insert_resource(WorkDone::default());
+
+    let expected = inputs.len();
+    let mut inbox = Inbox::default();
+    for payload in inputs {
+        inbox.queue.push_back(
+            store
+                .check_in(&serde_json::to_vec(&payload).unwrap())
+                .unwrap(),
+        );
+    }
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Enrich".to_string(),
+                node_type: "GeoIP".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            GeoIpState::default(),
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(geoip_worker);
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while world.get::<Outbox>(entity).unwrap().queue.len() < expected {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "lookups never finished"
+        );
+        schedule.run(&mut world);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let outbox = world.get::<Outbox>(entity).unwrap();
+    outbox
+        .queue
+        .iter()
+        .map(|(port, ticket)| {
+            let payload = serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap();
+            (port.clone(), payload)
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_geoip_api_and_user_agent_enrichment() {
+    unsafe {
+        std::env::set_var("FERROFLUX_ALLOW_INTERNAL_IPS", "true");
+    }
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/8.8.8.8/json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "country_code": "US", "city": "Mountain View" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = GeoIpConfig {
+        ip_field: "client_ip".to_string(),
+        user_agent_field: Some("ua".to_string()),
+        database_path: None,
+        api_url: Some(format!("{}/{{ip}}/json", server.uri())),
+        reverse_dns: false,
+        result_key: "enrichment".to_string(),
+    };
+    let results = run(
+        config,
+        vec![
+            json!({ "client_ip": "8.8.8.8, 10.0.0.1", "ua": CHROME_UA }),
+            // Private addresses are never sent to the lookup API.
+            json!({ "client_ip": "192.168.1.20:51000", "ua": "Googlebot/2.1 (+http://www.google.com/bot.html)" }),
+            json!({ "ua": CHROME_UA }),
+        ],
+    );
+    assert_eq!(results.len(), 3);
+    let by_ip = |ip: Value| {
+        results
+            .iter()
+            .find(|(_, payload)| payload["enrichment"]["ip"] == ip)
+            .unwrap()
+    };
+
+    let (port, public) = by_ip(json!("8.8.8.8"));
+    assert_eq!(*port, None);
+    let enrichment = &public["enrichment"];
+    assert_eq!(enrichment["ip"], "8.8.8.8");
+    assert_eq!(enrichment["geo"]["city"], "Mountain View");
+    assert_eq!(enrichment["user_agent"]["name"], "Chrome");
+    assert_eq!(enrichment["user_agent"]["os"], "Windows 10");
+    assert_eq!(enrichment["user_agent"]["is_bot"], false);
+    assert_eq!(public["ua"], CHROME_UA);
+
+    let enrichment = &by_ip(json!("192.168.1.20")).1["enrichment"];
+    assert_eq!(enrichment["ip"], "192.168.1.20");
+    assert_eq!(enrichment["geo"], Value::Null);
+    assert_eq!(enrichment["user_agent"]["is_bot"], true);
+
+    let enrichment = &by_ip(Value::Null).1["enrichment"];
+    assert_eq!(enrichment["ip"], Value::Null);
+    assert_eq!(enrichment["user_agent"]["name"], "Chrome");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_geoip_missing_database_routes_to_error() {
+    let config = GeoIpConfig {
+        ip_field: "ip".to_string(),
+        user_agent_field: None,
+        database_path: Some("/nonexistent/GeoLite2-City.mmdb".to_string()),
+        api_url: None,
+        reverse_dns: false,
+        result_key: "enrichment".to_string(),
+    };
+    let results = run(config, vec![json!({ "ip": "8.8.8.8" })]);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.as_deref(), Some("error"));
+}
@@ -0,0 +1,143 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::{
+    core::{Inbox, NodeConfig, Outbox},
+    io::HttpConfig,
+};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::http_policy::{CircuitState, HttpClientPolicy};
+use ferroflux_core::resources::settings::{EngineSettings, HttpClientSettings, RuntimeSettings};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::http_worker;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn setup_world(policy: HttpClientPolicy) -> (World, Schedule) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(ferroflux_core::resources::HttpResultChannel::default());
+    let store = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init in-memory DB");
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        store,
+        vec![0; 32],
+    ));
+    world.insert_resource(ferroflux_core::resources::TokioRuntime(
+        tokio::runtime::Handle::current(),
+    ));
+
+    let mut settings = EngineSettings::default();
+    settings.network.allow_internal_ips = true;
+    settings.http = HttpClientSettings {
+        failure_threshold: 2,
+        open_ms: 60_000,
+        max_concurrent_per_host: 1,
+    };
+    world.insert_resource(RuntimeSettings::new(settings));
+    world.insert_resource(policy);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(http_worker);
+    (world, schedule)
+}
+
+/// Sends one GET through a fresh HTTP node and returns its output payload.
+async fn call(world: &mut World, schedule: &mut Schedule, url: &str) -> String {
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(store.check_in(b"{}").unwrap());
+    let node = world
+        .spawn((
+            HttpConfig {
+                url: url.to_string(),
+                method: "GET".to_string(),
+                result_key: None,
+                connection_slug: None,
+                pagination: None,
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Status".to_string(),
+                node_type: "Http".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    for _ in 0..50 {
+        schedule.run(world);
+        let ticket = world
+            .get::<Outbox>(node)
+            .and_then(|o| o.queue.front().map(|(_, t)| t.clone()));
+        if let Some(ticket) = ticket {
+            world.despawn(node);
+            return String::from_utf8(store.claim(&ticket).unwrap()).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Http worker timed out");
+}
+
+#[test]
+fn test_failing_host_opens_circuit() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let policy = HttpClientPolicy::default();
+        let (mut world, mut schedule) = setup_world(policy.clone()).await;
+        let mut events = world.resource::<SystemEventBus>().subscribe();
+        let url = format!("{}/status", server.uri());
+
+        for _ in 0..2 {
+            let output = call(&mut world, &mut schedule, &url).await;
+            assert!(output.contains("HTTP 503"), "{}", output);
+        }
+        let output = call(&mut world, &mut schedule, &url).await;
+        assert!(output.starts_with("Error: Circuit open"), "{}", output);
+
+        // The third call never reached the server.
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert!(matches!(policy.state("127.0.0.1"), CircuitState::Open(_)));
+
+        let mut opened = 0;
+        let mut fast_failures = 0;
+        while let Ok(event) = events.try_recv() {
+            match event {
+                SystemEvent::HostCircuitOpened {
+                    host,
+                    consecutive_failures,
+                    ..
+                } => {
+                    assert_eq!(host, "127.0.0.1");
+                    assert_eq!(consecutive_failures, 2);
+                    opened += 1;
+                }
+                SystemEvent::NodeTelemetry { details, .. } if details["circuit_open"] == true => {
+                    fast_failures += 1;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!((opened, fast_failures), (1, 1));
+    });
+}
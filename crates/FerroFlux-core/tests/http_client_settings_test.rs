@@ -0,0 +1,215 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::{
+    core::{Inbox, NodeConfig, Outbox},
+    io::HttpConfig,
+};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::http_policy::HttpSettings;
+use ferroflux_core::resources::settings::{EngineSettings, NetworkSettings, RuntimeSettings};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::http_worker;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn setup_world(http_settings: HttpSettings) -> (World, Schedule) {
+    setup_world_with(
+        http_settings,
+        NetworkSettings {
+            allow_internal_ips: true,
+            ..NetworkSettings::default()
+        },
+    )
+    .await
+}
+
+async fn setup_world_with(
+    http_settings: HttpSettings,
+    network: NetworkSettings,
+) -> (World, Schedule) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
+    world.insert_resource(ferroflux_core::resources::HttpResultChannel::default());
+    let store = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init in-memory DB");
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        store,
+        vec![0; 32],
+    ));
+    world.insert_resource(ferroflux_core::resources::TokioRuntime(
+        tokio::runtime::Handle::current(),
+    ));
+    world.insert_resource(RuntimeSettings::new(EngineSettings {
+        network,
+        ..EngineSettings::default()
+    }));
+    world.insert_resource(http_settings);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(http_worker);
+    (world, schedule)
+}
+
+/// Sends one GET through a fresh HTTP node and returns its output payload.
+async fn call(world: &mut World, schedule: &mut Schedule, config: HttpConfig) -> String {
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(store.check_in(b"{}").unwrap());
+    let node = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Caller".to_string(),
+                node_type: "Http".to_string(),
+                workflow_id: None,
+                tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    for _ in 0..200 {
+        schedule.run(world);
+        if let Some((_, ticket)) = world.get::<Outbox>(node).unwrap().queue.front() {
+            return String::from_utf8(store.claim(ticket).unwrap().to_vec()).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("HTTP node did not respond");
+}
+
+fn get(url: String) -> HttpConfig {
+    HttpConfig {
+        url,
+        method: "GET".to_string(),
+        result_key: None,
+        connection_slug: None,
+        pagination: None,
+        full_response: false,
+        timeout_ms: None,
+        max_redirects: None,
+        proxy: None,
+        no_proxy: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_slow_responses_time_out_with_the_node_override() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+    let (mut world, mut schedule) = setup_world(HttpSettings::default()).await;
+
+    let started = Instant::now();
+    let output = call(
+        &mut world,
+        &mut schedule,
+        HttpConfig {
+            timeout_ms: Some(200),
+            ..get(format!("{}/slow", server.uri()))
+        },
+    )
+    .await;
+
+    assert!(output.starts_with("Error:"), "{}", output);
+    assert!(started.elapsed() < Duration::from_secs(4));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_redirects_follow_the_engine_limit_unless_the_node_overrides_it() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("location", "/new"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/new"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("moved"))
+        .mount(&server)
+        .await;
+    let (mut world, mut schedule) = setup_world(HttpSettings {
+        max_redirects: 0,
+        ..HttpSettings::default()
+    })
+    .await;
+
+    let url = format!("{}/old", server.uri());
+    let stopped = call(&mut world, &mut schedule, get(url.clone())).await;
+    assert_eq!(stopped, "Error: HTTP 301 Moved Permanently");
+
+    let followed = call(
+        &mut world,
+        &mut schedule,
+        HttpConfig {
+            max_redirects: Some(1),
+            ..get(url)
+        },
+    )
+    .await;
+    assert_eq!(followed, "moved");
+}
+
+/// SSRF protection on, with only the mock server's loopback address let through.
+fn loopback_only() -> NetworkSettings {
+    NetworkSettings {
+        allow_internal_ips: false,
+        ssrf_allowlist: vec!["127.0.0.1".to_string()],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_redirects_to_blocked_addresses_are_not_followed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/metadata"))
+        .respond_with(
+            ResponseTemplate::new(302)
+                .insert_header("location", "http://169.254.169.254/latest/meta-data/"),
+        )
+        .mount(&server)
+        .await;
+    let (mut world, mut schedule) =
+        setup_world_with(HttpSettings::default(), loopback_only()).await;
+
+    let output = call(
+        &mut world,
+        &mut schedule,
+        get(format!("{}/metadata", server.uri())),
+    )
+    .await;
+    assert!(output.contains("Blocked redirect"), "{}", output);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_node_proxies_are_held_to_the_network_policy() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("direct"))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let (mut world, mut schedule) =
+        setup_world_with(HttpSettings::default(), loopback_only()).await;
+
+    let output = call(
+        &mut world,
+        &mut schedule,
+        HttpConfig {
+            proxy: Some("http://10.0.0.1:6379".to_string()),
+            ..get(format!("{}/anything", server.uri()))
+        },
+    )
+    .await;
+    assert!(output.starts_with("Error: Blocked proxy"), "{}", output);
+}
@@ -0,0 +1,223 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::{
+    core::{Inbox, NodeConfig, Outbox},
+    io::{HttpConfig, PaginationConfig, PaginationOutput},
+};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::http_worker;
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn setup_world() -> (World, Schedule) {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
+    world.insert_resource(ferroflux_core::resources::HttpResultChannel::default());
+    let store = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init in-memory DB");
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        store,
+        vec![0; 32],
+    ));
+    world.insert_resource(ferroflux_core::resources::TokioRuntime(
+        tokio::runtime::Handle::current(),
+    ));
+    let mut settings = EngineSettings::default();
+    settings.network.allow_internal_ips = true;
+    world.insert_resource(RuntimeSettings::new(settings));
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(http_worker);
+    (world, schedule)
+}
+
+/// Runs a paginated GET through a fresh HTTP node and returns the `expected` outputs.
+async fn fetch(url: String, pagination: PaginationConfig, expected: usize) -> Vec<Value> {
+    let (mut world, mut schedule) = setup_world().await;
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(store.check_in(b"{}").unwrap());
+    let node = world
+        .spawn((
+            HttpConfig {
+                url,
+                method: "GET".to_string(),
+                result_key: None,
+                connection_slug: None,
+                pagination: Some(pagination),
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Lister".to_string(),
+                node_type: "Http".to_string(),
+                workflow_id: None,
+                tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    for _ in 0..100 {
+        schedule.run(&mut world);
+        let outbox = world.get::<Outbox>(node).unwrap();
+        if outbox.queue.len() >= expected {
+            return outbox
+                .queue
+                .iter()
+                .map(|(_, ticket)| serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap())
+                .collect();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("HTTP node did not emit {} outputs", expected);
+}
+
+fn pagination() -> PaginationConfig {
+    serde_json::from_value(json!({})).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cursor_pages_are_combined_into_one_array() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users"))
+        .and(query_param("after", "c2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": 3 }],
+            "meta": { "next": null }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": 1 }, { "id": 2 }],
+            "meta": { "next": "c2" }
+        })))
+        .mount(&server)
+        .await;
+
+    let outputs = fetch(
+        format!("{}/users?limit=2", server.uri()),
+        PaginationConfig {
+            items_field: Some("data".to_string()),
+            cursor_field: Some("/meta/next".to_string()),
+            cursor_param: "after".to_string(),
+            ..pagination()
+        },
+        1,
+    )
+    .await;
+
+    assert_eq!(outputs, [json!([{ "id": 1 }, { "id": 2 }, { "id": 3 }])]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_next_urls_are_followed_with_one_output_per_page() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orders/2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "items": ["c"] })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orders"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "items": ["a", "b"], "next": "/orders/2" })),
+        )
+        .mount(&server)
+        .await;
+
+    let outputs = fetch(
+        format!("{}/orders", server.uri()),
+        PaginationConfig {
+            items_field: Some("items".to_string()),
+            next_url_field: Some("next".to_string()),
+            output: PaginationOutput::PerPage,
+            ..pagination()
+        },
+        2,
+    )
+    .await;
+
+    assert_eq!(outputs, [json!(["a", "b"]), json!(["c"])]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_page_numbers_stop_at_an_empty_page_or_the_page_limit() {
+    let server = MockServer::start().await;
+    for (page, items) in [("1", json!([1, 2])), ("2", json!([3])), ("3", json!([]))] {
+        Mock::given(method("GET"))
+            .and(path("/events"))
+            .and(query_param("page", page))
+            .respond_with(ResponseTemplate::new(200).set_body_json(items))
+            .mount(&server)
+            .await;
+    }
+    let by_page = PaginationConfig {
+        page_param: Some("page".to_string()),
+        ..pagination()
+    };
+
+    let all = fetch(format!("{}/events", server.uri()), by_page.clone(), 1).await;
+    assert_eq!(all, [json!([1, 2, 3])]);
+
+    let first = fetch(
+        format!("{}/events", server.uri()),
+        PaginationConfig {
+            max_pages: 1,
+            ..by_page
+        },
+        1,
+    )
+    .await;
+    assert_eq!(first, [json!([1, 2])]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_next_urls_on_another_origin_are_not_followed() {
+    let server = MockServer::start().await;
+    let elsewhere = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "items": ["stolen"] })))
+        .expect(0)
+        .mount(&elsewhere)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orders"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": ["a"],
+            "next": format!("{}/collect", elsewhere.uri())
+        })))
+        .mount(&server)
+        .await;
+
+    let outputs = fetch(
+        format!("{}/orders", server.uri()),
+        PaginationConfig {
+            items_field: Some("items".to_string()),
+            next_url_field: Some("next".to_string()),
+            ..pagination()
+        },
+        1,
+    )
+    .await;
+
+    assert_eq!(outputs, [json!(["a"])]);
+    elsewhere.verify().await;
+}
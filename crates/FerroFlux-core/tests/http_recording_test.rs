@@ -0,0 +1,168 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::integration::PayloadMapper;
+use ferroflux_core::components::{
+    core::{Inbox, NodeConfig, Outbox},
+    io::HttpConfig,
+};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::recorder::{HttpFixtures, HttpRecorder, REDACTED};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::http_worker;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "sk_live_0123456789";
+const TOKEN: &str = "tok_abcdefghijkl";
+
+async fn setup_world(recorder: Option<HttpRecorder>) -> (World, Schedule) {
+    // Enable internal IP access for tests
+    unsafe {
+        env::set_var("FERROFLUX_ALLOW_INTERNAL_IPS", "true");
+    }
+
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
+    world.insert_resource(ferroflux_core::resources::HttpResultChannel::default());
+    let store = ferroflux_core::store::database::PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init in-memory DB");
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        store,
+        vec![0; 32],
+    ));
+    world.insert_resource(ferroflux_core::resources::TokioRuntime(
+        tokio::runtime::Handle::current(),
+    ));
+    if let Some(recorder) = recorder {
+        world.insert_resource(recorder);
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(http_worker);
+    (world, schedule)
+}
+
+/// Sends one POST through the HTTP worker and returns the output payload.
+async fn call(world: &mut World, schedule: &mut Schedule, base_url: &str) -> String {
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(br#"{"amount": 42}"#).unwrap());
+
+    world.spawn((
+        HttpConfig {
+            url: format!("{}/v1/charges?limit=2&api_key={}", base_url, API_KEY),
+            method: "POST".to_string(),
+            result_key: None,
+            connection_slug: None,
+            pagination: None,
+            full_response: false,
+            timeout_ms: None,
+            max_redirects: None,
+            proxy: None,
+            no_proxy: None,
+        },
+        PayloadMapper {
+            template: None,
+            headers: HashMap::from([("Authorization".to_string(), format!("Bearer {}", TOKEN))]),
+        },
+        NodeConfig {
+            id: uuid::Uuid::new_v4(),
+            name: "Charges".to_string(),
+            node_type: "Http".to_string(),
+            workflow_id: None,
+            tenant_id: None,
+            version: None,
+        },
+        inbox,
+        Outbox::default(),
+    ));
+
+    for _ in 0..50 {
+        schedule.run(world);
+        let ticket = world
+            .query::<&Outbox>()
+            .get_single(world)
+            .ok()
+            .and_then(|o| o.queue.front().map(|(_, t)| t.clone()));
+        if let Some(ticket) = ticket {
+            return String::from_utf8(store.claim(&ticket).unwrap()).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("Http worker timed out");
+}
+
+/// Mounts recorded exchanges on a mock server, as an integration test would.
+async fn mount(server: &MockServer, fixtures: &HttpFixtures) {
+    for exchange in &fixtures.exchanges {
+        let mut mock = Mock::given(method(exchange.method.as_str())).and(path(&exchange.path));
+        if let Some(query) = &exchange.query {
+            for (k, v) in url::form_urlencoded::parse(query.as_bytes()) {
+                if v != REDACTED {
+                    mock = mock.and(query_param(k.as_ref(), v.as_ref()));
+                }
+            }
+        }
+        let mut response =
+            ResponseTemplate::new(exchange.status).set_body_string(exchange.response_body.clone());
+        for (name, value) in &exchange.response_headers {
+            response = response.insert_header(name.as_str(), value.as_str());
+        }
+        mock.respond_with(response).mount(server).await;
+    }
+}
+
+#[test]
+fn test_recorded_exchange_is_redacted_and_replayable() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let live = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/charges"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-request-id", "req_1")
+                    .set_body_json(serde_json::json!({ "id": "ch_1", "amount": 42 })),
+            )
+            .mount(&live)
+            .await;
+
+        let recorder = HttpRecorder::new();
+        let (mut world, mut schedule) = setup_world(Some(recorder.clone())).await;
+        let recorded_output = call(&mut world, &mut schedule, &live.uri()).await;
+
+        let fixtures = recorder.fixtures();
+        assert_eq!(fixtures.exchanges.len(), 1);
+        let exchange = &fixtures.exchanges[0];
+        assert_eq!(exchange.method, "POST");
+        assert_eq!(exchange.path, "/v1/charges");
+        assert_eq!(exchange.status, 200);
+        assert_eq!(exchange.request_headers["authorization"], REDACTED);
+        assert_eq!(exchange.request_body.as_deref(), Some(r#"{"amount": 42}"#));
+        assert_eq!(exchange.response_headers["x-request-id"], "req_1");
+
+        let file = env::temp_dir().join(format!("fixtures_{}.json", uuid::Uuid::new_v4()));
+        fixtures.save(&file).unwrap();
+        let saved = std::fs::read_to_string(&file).unwrap();
+        assert!(!saved.contains(API_KEY));
+        assert!(!saved.contains(TOKEN));
+
+        // Replay the fixture file against a fresh mock server.
+        let replay = MockServer::start().await;
+        mount(&replay, &HttpFixtures::load(&file).unwrap()).await;
+        std::fs::remove_file(&file).ok();
+
+        let (mut world, mut schedule) = setup_world(None).await;
+        let replayed_output = call(&mut world, &mut schedule, &replay.uri()).await;
+        assert_eq!(replayed_output, recorded_output);
+    });
+}
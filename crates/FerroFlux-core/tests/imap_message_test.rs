@@ -0,0 +1,50 @@
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::connectors::imap::message_to_payload;
+
+const RAW: &str = "From: Ada Lovelace <ada@example.com>\r\n\
+To: ops@example.com\r\n\
+Subject: Invoice 42\r\n\
+Message-ID: <42@example.com>\r\n\
+Date: Tue, 1 Jul 2025 10:00:00 +0000\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+\r\n\
+--b\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+\r\n\
+Please find the invoice attached.\r\n\
+--b\r\n\
+Content-Type: text/csv; name=\"invoice.csv\"\r\n\
+Content-Disposition: attachment; filename=\"invoice.csv\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+aWQsdG90YWwKNDIsOTkuNQo=\r\n\
+--b--\r\n";
+
+#[test]
+fn test_message_payload_checks_in_attachments() {
+    let store = BlobStore::default();
+    let payload = message_to_payload(7, RAW.as_bytes(), &store).unwrap();
+
+    assert_eq!(payload["uid"], 7);
+    assert_eq!(payload["subject"], "Invoice 42");
+    assert_eq!(payload["message_id"], "42@example.com");
+    assert_eq!(payload["from"][0]["address"], "ada@example.com");
+    assert_eq!(payload["from"][0]["name"], "Ada Lovelace");
+    assert_eq!(payload["to"][0]["address"], "ops@example.com");
+    assert!(
+        payload["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Please find the invoice attached.")
+    );
+
+    let attachment = &payload["attachments"][0];
+    assert_eq!(attachment["filename"], "invoice.csv");
+    assert_eq!(attachment["content_type"], "text/csv");
+
+    let id: uuid::Uuid = serde_json::from_value(attachment["ticket_id"].clone()).unwrap();
+    let ticket = store.recover_ticket(&id).expect("attachment stored");
+    assert_eq!(ticket.metadata["filename"], "invoice.csv");
+    assert_eq!(store.claim(&ticket).unwrap(), b"id,total\n42,99.5\n");
+}
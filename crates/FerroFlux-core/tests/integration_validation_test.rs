@@ -0,0 +1,91 @@
+use ferroflux_core::integrations::{IntegrationDef, IntegrationRegistry};
+
+const BROKEN: &str = r#"
+name: chatty
+base_url: "https://chat.example.com"
+auth_type: oauth2
+auth:
+  type: oauth2
+  grant_type: authorization_code
+  scopes: [chat.write]
+connection_schema:
+  - { name: workspace, type: string }
+actions:
+  post_message:
+    inputs:
+      - { name: channel, type: string, dynamic_source: list_channels }
+      - { name: text, type: string }
+    implementation:
+      type: http
+      config:
+        method: POST
+        path: "/{{workspace}}/channels/{{channel}}/messages"
+        headers: { X-Thread: "{{thread_id}}" }
+        body_template: '{ "text": {{json text}} {{#if urgent}}, "priority": "high" }'
+    output_transform:
+      text: "message.["
+"#;
+
+fn locations(def: &IntegrationDef) -> Vec<String> {
+    def.validate()
+        .into_iter()
+        .map(|e| format!("{} - {}", e.location, e.message))
+        .collect()
+}
+
+#[test]
+fn test_bundled_integrations_are_valid() {
+    for yaml in [
+        include_str!("../../../openai.yaml"),
+        include_str!("../../../elevenlabs.yaml"),
+    ] {
+        let def: IntegrationDef = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(locations(&def), Vec::<String>::new(), "{}", def.name);
+    }
+}
+
+#[test]
+fn test_every_problem_in_a_definition_is_reported() {
+    let def: IntegrationDef = serde_yaml::from_str(BROKEN).unwrap();
+    let errors = locations(&def);
+
+    let expected = [
+        "auth.token_url",
+        "auth.auth_url",
+        "actions.post_message.headers.X-Thread - references undefined input 'thread_id'",
+        "actions.post_message.body_template - template does not compile",
+        "actions.post_message.inputs.channel.dynamic_source - no action or utility named 'list_channels'",
+        "actions.post_message.output_transform.text - invalid JMESPath expression",
+    ];
+    assert_eq!(errors.len(), expected.len(), "{:#?}", errors);
+    for (error, expected) in errors.iter().zip(expected) {
+        assert!(error.starts_with(expected), "{} != {}", error, expected);
+    }
+}
+
+#[test]
+fn test_invalid_definitions_are_rejected_at_load_time() {
+    let dir = std::env::temp_dir().join(format!("integrations_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("chatty.yaml"), BROKEN).unwrap();
+    std::fs::write(dir.join("garbled.yaml"), "name: [unclosed").unwrap();
+    std::fs::write(
+        dir.join("openai.yaml"),
+        include_str!("../../../openai.yaml"),
+    )
+    .unwrap();
+
+    let mut registry = IntegrationRegistry::default();
+    let loaded = registry.load_from_directory(dir.to_str().unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(loaded, 1);
+    assert!(registry.definitions.contains_key("openai"));
+    assert!(!registry.definitions.contains_key("chatty"));
+    assert_eq!(registry.invalid["chatty"].len(), 6);
+    assert!(
+        registry.invalid["garbled.yaml"][0]
+            .message
+            .starts_with("invalid YAML")
+    );
+}
@@ -28,7 +28,7 @@ async fn setup_world() -> (World, Schedule) {
 
     // Event Bus
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(ferroflux_core::api::events::SystemEventBus(tx));
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
     // Insert the new HttpResultChannel resource
     world.insert_resource(ferroflux_core::resources::HttpResultChannel::default());
 
@@ -84,6 +84,12 @@ fn test_http_worker_get_success() {
                 method: "GET".to_string(),
                 result_key: None,
                 connection_slug: None,
+                pagination: None,
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
             },
             NodeConfig {
                 id: node_id,
@@ -91,6 +97,7 @@ fn test_http_worker_get_success() {
                 node_type: "Http".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             inbox,
             Outbox::default(),
@@ -150,6 +157,12 @@ fn test_http_worker_post_enrichment() {
                 method: "POST".to_string(),
                 result_key: Some("api_response".to_string()),
                 connection_slug: None,
+                pagination: None,
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
             },
             NodeConfig {
                 id: uuid::Uuid::new_v4(),
@@ -157,6 +170,7 @@ fn test_http_worker_post_enrichment() {
                 node_type: "Http".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             inbox,
             Outbox::default(),
@@ -191,3 +205,81 @@ fn test_http_worker_post_enrichment() {
         assert!(success, "Http worker timed out");
     });
 }
+
+#[test]
+fn test_http_worker_full_response() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let mock_server = MockServer::start().await;
+        let (mut world, mut schedule) = setup_world().await;
+
+        Mock::given(method("GET"))
+            .and(path("/limited"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .set_body_json(serde_json::json!({"error": "slow down"})),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let store = world.resource::<BlobStore>().clone();
+        let ticket = store.check_in(b"{}").unwrap();
+        let mut inbox = Inbox::default();
+        inbox.queue.push_back(ticket);
+
+        world.spawn((
+            HttpConfig {
+                url: format!("{}/limited", mock_server.uri()),
+                method: "GET".to_string(),
+                result_key: Some("response".to_string()),
+                connection_slug: None,
+                pagination: None,
+                full_response: true,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Limited".to_string(),
+                node_type: "Http".to_string(),
+                workflow_id: None,
+                tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ));
+
+        // Error statuses come through as data for downstream switches
+        let mut success = false;
+        for _ in 0..50 {
+            schedule.run(&mut world);
+
+            let ticket = {
+                let mut query = world.query::<&Outbox>();
+                let outbox = query.get_single(&world).ok();
+                outbox.and_then(|o| o.queue.front().map(|(_port, t)| t.clone()))
+            };
+
+            if let Some(t) = ticket {
+                let data = store.claim(&t).unwrap();
+                let output: serde_json::Value = serde_json::from_slice(&data).unwrap();
+                let response = &output["response"];
+
+                assert_eq!(response["status"], 429);
+                assert_eq!(response["headers"]["x-ratelimit-remaining"], "0");
+                assert_eq!(response["body"]["error"], "slow down");
+                assert!(response["duration_ms"].is_u64());
+
+                success = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        assert!(success, "Http worker timed out");
+    });
+}
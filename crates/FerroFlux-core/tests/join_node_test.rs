@@ -0,0 +1,165 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::manipulation::{JoinConfig, JoinState};
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::manipulation::join_worker;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn node(name: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        node_type: "Join".to_string(),
+        workflow_id: None,
+        tenant_id: None,
+        version: None,
+    }
+}
+
+/// Two branches feeding ports `user` and `orders` of a Join node.
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    store: BlobStore,
+    branches: [Entity; 2],
+    join: Entity,
+}
+
+impl Harness {
+    fn new(config: JoinConfig) -> Self {
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(GraphTopology::default());
+        world.insert_resource(WorkDone::default());
+        let store = BlobStore::default();
+        world.insert_resource(store.clone());
+
+        let join = world
+            .spawn((
+                config,
+                JoinState::default(),
+                node("Rejoin"),
+                Inbox::default(),
+                Outbox::default(),
+            ))
+            .id();
+        let branches = ["user", "orders"].map(|port| {
+            let branch = world.spawn((node(port), Outbox::default())).id();
+            world.spawn(Edge {
+                source: branch,
+                source_handle: None,
+                target: join,
+                target_handle: Some(port.to_string()),
+            });
+            branch
+        });
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_graph_topology, transport_worker, join_worker).chain());
+        Self {
+            world,
+            schedule,
+            store,
+            branches,
+            join,
+        }
+    }
+
+    fn send(&mut self, branch: usize, trace_id: &str, payload: Value) {
+        let metadata = HashMap::from([("trace_id".to_string(), trace_id.to_string())]);
+        let ticket = self
+            .store
+            .check_in_with_metadata(&serde_json::to_vec(&payload).unwrap(), metadata)
+            .unwrap();
+        self.world
+            .get_mut::<Outbox>(self.branches[branch])
+            .unwrap()
+            .queue
+            .push_back((None, ticket));
+        self.schedule.run(&mut self.world);
+    }
+
+    fn take_output(&mut self) -> Vec<(Option<String>, Value)> {
+        let items: Vec<_> = self
+            .world
+            .get_mut::<Outbox>(self.join)
+            .unwrap()
+            .queue
+            .drain(..)
+            .collect();
+        items
+            .into_iter()
+            .map(|(port, ticket)| {
+                let value = serde_json::from_slice(&self.store.claim(&ticket).unwrap()).unwrap();
+                (port, value)
+            })
+            .collect()
+    }
+}
+
+fn config(timeout_ms: u64) -> JoinConfig {
+    JoinConfig {
+        inputs: vec!["user".to_string(), "orders".to_string()],
+        correlation_field: None,
+        timeout_ms,
+        emit_partial: false,
+    }
+}
+
+#[test]
+fn test_join_merges_branches_by_trace() {
+    let mut h = Harness::new(config(60_000));
+
+    h.send(0, "t1", json!({ "name": "Ada" }));
+    h.send(0, "t2", json!({ "name": "Grace" }));
+    assert!(h.take_output().is_empty());
+
+    h.send(1, "t2", json!([3]));
+    h.send(1, "t1", json!([1, 2]));
+    assert_eq!(
+        h.take_output(),
+        vec![
+            (None, json!({ "user": { "name": "Grace" }, "orders": [3] })),
+            (None, json!({ "user": { "name": "Ada" }, "orders": [1, 2] })),
+        ]
+    );
+    assert!(h.world.get::<JoinState>(h.join).unwrap().pending.is_empty());
+}
+
+#[test]
+fn test_join_correlation_field_and_timeout() {
+    let mut h = Harness::new(JoinConfig {
+        correlation_field: Some("order_id".to_string()),
+        ..config(60_000)
+    });
+
+    // Different traces, same order.
+    h.send(0, "a", json!({ "order_id": 7, "name": "Ada" }));
+    h.send(1, "b", json!({ "order_id": 7, "total": 10 }));
+    let output = h.take_output();
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].1["orders"]["total"], 10);
+
+    // A lone branch expires to the error port with what arrived.
+    h.world.get_mut::<JoinConfig>(h.join).unwrap().timeout_ms = 0;
+    h.send(0, "c", json!({ "order_id": 8, "name": "Grace" }));
+    assert_eq!(
+        h.take_output(),
+        vec![(
+            Some("error".to_string()),
+            json!({
+                "partial": { "user": { "order_id": 8, "name": "Grace" } },
+                "missing": ["orders"],
+            })
+        )]
+    );
+
+    // Without a correlation value the ticket is rejected.
+    h.send(1, "d", json!({ "total": 1 }));
+    assert_eq!(h.take_output()[0].0.as_deref(), Some("error"));
+}
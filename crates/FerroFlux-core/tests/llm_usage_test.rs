@@ -0,0 +1,166 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::pipeline::{ExecutionContext, ExecutionResult};
+use ferroflux_core::components::{NodeConfig, Outbox};
+use ferroflux_core::resources::{TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::{PersistentStore, TokenUsage};
+use ferroflux_core::systems::agent::agent_post;
+use ferroflux_iam::TenantId;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_usage_is_aggregated_per_workflow_model_and_day() {
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let acme = TenantId::from("acme");
+    let usage = |prompt_tokens, completion_tokens| TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+    };
+    db.record_usage(&acme, Some("wf-1"), "gpt-4o", &usage(100, 20))
+        .await
+        .unwrap();
+    db.record_usage(&acme, Some("wf-1"), "gpt-4o", &usage(50, 5))
+        .await
+        .unwrap();
+    db.record_usage(&acme, Some("wf-1"), "gpt-4o-mini", &usage(10, 1))
+        .await
+        .unwrap();
+    db.record_usage(&acme, None, "gpt-4o", &usage(7, 3))
+        .await
+        .unwrap();
+    db.record_usage(
+        &TenantId::from("other"),
+        Some("wf-1"),
+        "gpt-4o",
+        &usage(1, 1),
+    )
+    .await
+    .unwrap();
+
+    let today = chrono::Utc::now().date_naive();
+    let records = db.get_usage(&acme, today..=today).await.unwrap();
+    assert_eq!(records.len(), 3);
+    let gpt4o = records
+        .iter()
+        .find(|r| r.workflow_id.as_deref() == Some("wf-1") && r.model == "gpt-4o")
+        .unwrap();
+    assert_eq!(gpt4o.day, today);
+    assert_eq!(gpt4o.requests, 2);
+    assert_eq!(gpt4o.prompt_tokens, 150);
+    assert_eq!(gpt4o.completion_tokens, 25);
+    assert_eq!(gpt4o.total_tokens(), 175);
+    assert!(
+        records
+            .iter()
+            .any(|r| r.workflow_id.is_none() && r.requests == 1)
+    );
+
+    let yesterday = today.pred_opt().unwrap();
+    assert!(
+        db.get_usage(&acme, yesterday..=yesterday)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+fn result(model: &str, raw_body: serde_json::Value) -> ExecutionResult {
+    ExecutionResult {
+        status: 200,
+        raw_body: raw_body.to_string(),
+        trace_id: "trace-1".to_string(),
+        context: ExecutionContext {
+            provider_name: "anthropic".to_string(),
+            model_name: model.to_string(),
+            node_id: uuid::Uuid::new_v4(),
+            result_key: None,
+            output_transform: None,
+            input_json: json!({}),
+            start_time: 0,
+            fallback_from: Vec::new(),
+            repair_attempt: 0,
+        },
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_agent_post_records_token_usage() {
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let mut world = World::new();
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(db.clone());
+
+    let node = |name: &str| NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        node_type: "Agent".to_string(),
+        workflow_id: Some("support-bot".to_string()),
+        tenant_id: Some(TenantId::from("acme")),
+        version: None,
+    };
+    world.spawn((
+        node("Answer"),
+        Outbox::default(),
+        result(
+            "claude-sonnet",
+            json!({
+                "content": [{ "type": "text", "text": "Hi" }],
+                "usage": { "input_tokens": 42, "output_tokens": 8 },
+            }),
+        ),
+    ));
+    world.spawn((
+        node("Summarize"),
+        Outbox::default(),
+        result(
+            "claude-sonnet",
+            json!({
+                "content": [{ "type": "text", "text": "Short" }],
+                "usage": { "input_tokens": 100, "output_tokens": 12 },
+            }),
+        ),
+    ));
+    // No usage reported: nothing is recorded.
+    world.spawn((
+        node("Local"),
+        Outbox::default(),
+        result("llama3", json!({ "text": "ok" })),
+    ));
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(agent_post);
+    schedule.run(&mut world);
+
+    let mut reported = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let SystemEvent::NodeTelemetry { details, .. } = event {
+            reported.push(details["usage"].clone());
+        }
+    }
+    assert!(reported.contains(&json!({ "prompt_tokens": 42, "completion_tokens": 8 })));
+    assert!(reported.contains(&serde_json::Value::Null));
+
+    let today = chrono::Utc::now().date_naive();
+    for _ in 0..50 {
+        let records = db
+            .get_usage(&TenantId::from("acme"), today..=today)
+            .await
+            .unwrap();
+        if records.first().is_some_and(|r| r.requests == 2) {
+            assert_eq!(records.len(), 1);
+            assert_eq!(records[0].workflow_id.as_deref(), Some("support-bot"));
+            assert_eq!(records[0].model, "claude-sonnet");
+            assert_eq!(records[0].prompt_tokens, 142);
+            assert_eq!(records[0].completion_tokens, 20);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Usage was not recorded");
+}
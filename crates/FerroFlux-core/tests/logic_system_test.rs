@@ -20,7 +20,7 @@ fn setup_world() -> (World, Schedule) {
 
     // Event Bus
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(ferroflux_core::api::events::SystemEventBus(tx));
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
 
     // Rhai Engine
     let engine = Engine::new();
@@ -62,6 +62,7 @@ fn test_script_node_execution() {
             node_type: "Script".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         inbox,
         Outbox::default(),
@@ -109,6 +110,7 @@ fn test_script_node_enrichment() {
             node_type: "Script".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         inbox,
         Outbox::default(),
@@ -154,6 +156,7 @@ fn test_switch_node_boolean_routing() {
                 node_type: "Switch".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             inbox,
         ))
@@ -218,6 +221,7 @@ fn test_switch_node_string_routing() {
                 node_type: "Switch".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             inbox,
         ))
@@ -17,7 +17,7 @@ fn setup_world() -> World {
     // Resources
     world.insert_resource(BlobStore::default());
     let (tx, _) = broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
 
     world
 }
@@ -55,6 +55,7 @@ fn test_splitter_logic() {
             node_type: "Split".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         config,
         inbox,
@@ -107,6 +108,7 @@ fn test_aggregator_logic() {
             node_type: "Aggregate".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         config,
         BatchState::default(),
@@ -165,6 +167,7 @@ fn test_transform_logic() {
             node_type: "Transform".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         config,
         inbox,
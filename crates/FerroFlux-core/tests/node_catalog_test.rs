@@ -0,0 +1,144 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::{ApiCommand, ApiReceiver, ApiRequest, Reply};
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::systems::api_worker::api_command_worker;
+use ferroflux_core::traits::node_factory::{NodeFactory, NodeMetadata};
+use ferroflux_iam::{Principal, Role, TenantId};
+use serde_json::Value;
+
+struct MetadataFactory(NodeMetadata);
+
+impl NodeFactory for MetadataFactory {
+    fn build(&self, _entity: &mut EntityWorldMut, _config: &Value) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn serialize(&self, _world: &World, _entity: Entity) -> Option<Value> {
+        None
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        self.0.clone()
+    }
+}
+
+fn registry() -> NodeRegistry {
+    let mut registry = NodeRegistry::new();
+    for (id, name, category, description) in [
+        ("http/request", "HTTP Request", "Network", "Calls a URL."),
+        ("core/switch", "Switch", "Logic", "Routes by a condition."),
+        (
+            "email/send",
+            "Send Email",
+            "Network",
+            "Sends mail over SMTP.",
+        ),
+        (
+            "core/split",
+            "Split",
+            "Logic",
+            "Splits an array into items.",
+        ),
+    ] {
+        registry.register(
+            id,
+            Box::new(MetadataFactory(NodeMetadata {
+                id: id.to_string(),
+                name: name.to_string(),
+                category: category.to_string(),
+                platform: None,
+                description: Some(description.to_string()),
+                inputs: vec![],
+                outputs: vec![],
+                settings: vec![],
+                version: None,
+                deprecated: None,
+            })),
+        );
+    }
+    registry
+}
+
+fn ids(nodes: &[NodeMetadata]) -> Vec<&str> {
+    nodes.iter().map(|n| n.id.as_str()).collect()
+}
+
+#[test]
+fn test_catalog_is_ordered_by_category_and_name() {
+    assert_eq!(
+        ids(&registry().catalog()),
+        ["core/split", "core/switch", "http/request", "email/send"]
+    );
+}
+
+#[test]
+fn test_search_is_fuzzy_and_ranks_the_best_match_first() {
+    let registry = registry();
+    assert_eq!(ids(&registry.search("", None)), ids(&registry.catalog()));
+
+    // Initials and abbreviations match, in order only.
+    assert_eq!(ids(&registry.search("htreq", None)), ["http/request"]);
+    assert!(registry.search("qerh", None).is_empty());
+    // Contiguous matches beat scattered ones.
+    assert_eq!(registry.search("sw", None)[0].id, "core/switch");
+    // Descriptions are searched too, and whitespace in the query is ignored.
+    assert_eq!(ids(&registry.search("smt p", None)), ["email/send"]);
+
+    assert_eq!(
+        ids(&registry.search("s", Some("logic"))),
+        ["core/split", "core/switch"]
+    );
+    assert!(registry.search("http", Some("Logic")).is_empty());
+}
+
+#[test]
+fn test_list_node_types_replies_to_readers() {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    world.insert_resource(registry());
+    let (api, rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(rx));
+
+    let acme = TenantId::from("acme");
+    let viewer = Principal::new("vera").with_membership(acme.clone(), Role::Viewer);
+    let (reply, mut rx) = Reply::channel();
+    api.try_send(ApiRequest::new(
+        viewer,
+        ApiCommand::ListNodeTypes {
+            tenant_id: acme,
+            query: "mail".to_string(),
+            category: Some("Network".to_string()),
+            reply,
+        },
+    ))
+    .unwrap();
+    api_command_worker(&mut world);
+
+    assert_eq!(ids(&rx.try_recv().unwrap().unwrap()), ["email/send"]);
+}
+
+#[test]
+fn test_list_node_types_answers_outsiders_with_the_denial() {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    world.insert_resource(registry());
+    let (api, rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(rx));
+
+    let outsider = Principal::new("mallory").with_membership(TenantId::from("globex"), Role::Owner);
+    let (reply, mut rx) = Reply::channel();
+    api.try_send(ApiRequest::new(
+        outsider,
+        ApiCommand::ListNodeTypes {
+            tenant_id: TenantId::from("acme"),
+            query: String::new(),
+            category: None,
+            reply,
+        },
+    ))
+    .unwrap();
+    api_command_worker(&mut world);
+
+    assert!(rx.try_recv().unwrap().unwrap_err().contains("acme"));
+}
@@ -0,0 +1,153 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::nodes::loader::{NodeDefinitionLoader, node_definition_watcher};
+use ferroflux_core::resources::registry::{DefinitionRegistry, NodeRegistry};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn node_yaml(id: &str, name: &str) -> String {
+    format!(
+        r#"meta:
+  id: {id}
+  name: {name}
+  category: Custom
+  type: Action
+interface:
+  inputs:
+    - name: Exec
+      type: flow
+  outputs:
+    - name: Success
+      type: flow
+  settings: []
+execution:
+  - id: call
+    tool: http_client
+    params:
+      url: "https://example.com"
+      method: GET
+"#
+    )
+}
+
+fn nodes_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("nodes_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    dir
+}
+
+fn write(path: &Path, content: &str) {
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_scan_adds_updates_and_removes_node_types() {
+    let dir = nodes_dir();
+    let mut loader = NodeDefinitionLoader::new(&dir);
+    let mut nodes = NodeRegistry::new();
+    let mut defs = DefinitionRegistry::default();
+
+    write(
+        &dir.join("weather.yaml"),
+        &node_yaml("custom.weather", "Weather"),
+    );
+    write(
+        &dir.join("nested/quote.yml"),
+        &node_yaml("custom.quote", "Quote"),
+    );
+    write(&dir.join("README.md"), "not a node");
+    let changes = loader.scan(&mut nodes, &mut defs);
+    assert_eq!(changes.added, ["custom.quote", "custom.weather"]);
+    assert!(changes.errors.is_empty());
+    assert_eq!(
+        nodes.get("custom.weather").unwrap().metadata().name,
+        "Weather"
+    );
+    assert!(defs.definitions.contains_key("custom.quote"));
+
+    // Unchanged files are not reloaded.
+    assert!(loader.scan(&mut nodes, &mut defs).is_empty());
+
+    write(
+        &dir.join("weather.yaml"),
+        &node_yaml("custom.weather", "Forecast"),
+    );
+    std::fs::remove_file(dir.join("nested/quote.yml")).unwrap();
+    let changes = loader.scan(&mut nodes, &mut defs);
+    assert_eq!(changes.updated, ["custom.weather"]);
+    assert_eq!(changes.removed, ["custom.quote"]);
+    assert_eq!(
+        nodes.get("custom.weather").unwrap().metadata().name,
+        "Forecast"
+    );
+    assert!(nodes.get("custom.quote").is_none());
+    assert!(!defs.definitions.contains_key("custom.quote"));
+
+    // Renaming the id in place replaces the old type.
+    write(
+        &dir.join("weather.yaml"),
+        &node_yaml("custom.forecast", "Forecast"),
+    );
+    let changes = loader.scan(&mut nodes, &mut defs);
+    assert_eq!(changes.added, ["custom.forecast"]);
+    assert_eq!(changes.removed, ["custom.weather"]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_invalid_definitions_keep_the_last_good_version() {
+    let dir = nodes_dir();
+    let mut loader = NodeDefinitionLoader::new(&dir);
+    let mut nodes = NodeRegistry::new();
+    let mut defs = DefinitionRegistry::default();
+
+    write(
+        &dir.join("weather.yaml"),
+        &node_yaml("custom.weather", "Weather"),
+    );
+    loader.scan(&mut nodes, &mut defs);
+
+    write(&dir.join("weather.yaml"), "meta: [unclosed");
+    let changes = loader.scan(&mut nodes, &mut defs);
+    assert!(changes.is_empty());
+    assert_eq!(changes.errors.len(), 1);
+    assert!(nodes.get("custom.weather").is_some());
+
+    // Deleting the broken file still removes the node it used to define.
+    std::fs::remove_file(dir.join("weather.yaml")).unwrap();
+    let changes = loader.scan(&mut nodes, &mut defs);
+    assert_eq!(changes.removed, ["custom.weather"]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_watcher_announces_registry_changes() {
+    let dir = nodes_dir();
+    write(
+        &dir.join("weather.yaml"),
+        &node_yaml("custom.weather", "Weather"),
+    );
+
+    let mut world = World::new();
+    let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(NodeRegistry::new());
+    world.insert_resource(DefinitionRegistry::default());
+    world.insert_resource(NodeDefinitionLoader::new(&dir).with_interval(Duration::ZERO));
+    let mut schedule = Schedule::default();
+    schedule.add_systems(node_definition_watcher);
+
+    schedule.run(&mut world);
+    match rx.try_recv().unwrap() {
+        SystemEvent::RegistryChanged { added, .. } => assert_eq!(added, ["custom.weather"]),
+        other => panic!("unexpected event {}", other.kind()),
+    }
+
+    // Nothing changed, nothing to announce.
+    schedule.run(&mut world);
+    assert!(rx.try_recv().is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
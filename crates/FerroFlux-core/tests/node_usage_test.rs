@@ -0,0 +1,98 @@
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::traits::node_factory::{NodeCatalog, NodeMetadata, NodeUsage};
+use ferroflux_iam::TenantId;
+
+fn template(id: &str) -> NodeMetadata {
+    NodeMetadata {
+        id: id.to_string(),
+        name: id.to_string(),
+        category: "Test".to_string(),
+        platform: None,
+        description: None,
+        inputs: vec![],
+        outputs: vec![],
+        settings: vec![],
+        version: None,
+        deprecated: None,
+    }
+}
+
+#[tokio::test]
+async fn test_node_usage_tracking() {
+    let store = PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init DB");
+    let tenant = TenantId::from("default_tenant");
+
+    store
+        .record_node_usage(&tenant, "alice", "http/action.request")
+        .await
+        .unwrap();
+    store
+        .record_node_usage(&tenant, "alice", "http/action.request")
+        .await
+        .unwrap();
+    store
+        .set_node_favorite(&tenant, "alice", "openai/chat.completion", true)
+        .await
+        .unwrap();
+    // Another user's usage must not leak
+    store
+        .record_node_usage(&tenant, "bob", "core/switch")
+        .await
+        .unwrap();
+
+    let usage = store.list_node_usage(&tenant, "alice").await.unwrap();
+    assert_eq!(usage.len(), 2);
+
+    let http = usage
+        .iter()
+        .find(|(node_type, ..)| node_type == "http/action.request")
+        .unwrap();
+    assert_eq!(http.1, 2);
+    assert!(http.2.is_some());
+    assert!(!http.3);
+
+    let openai = usage
+        .iter()
+        .find(|(node_type, ..)| node_type == "openai/chat.completion")
+        .unwrap();
+    assert_eq!(openai.1, 0);
+    assert!(openai.3);
+}
+
+#[test]
+fn test_node_catalog_sections() {
+    let templates = vec![template("a"), template("b"), template("c")];
+    let usage = vec![
+        NodeUsage {
+            node_type: "b".to_string(),
+            use_count: 3,
+            last_used_at: Some("2024-01-02 00:00:00".to_string()),
+            favorite: false,
+        },
+        NodeUsage {
+            node_type: "removed".to_string(),
+            use_count: 5,
+            last_used_at: Some("2024-01-01 12:00:00".to_string()),
+            favorite: true,
+        },
+        NodeUsage {
+            node_type: "a".to_string(),
+            use_count: 1,
+            last_used_at: Some("2024-01-01 00:00:00".to_string()),
+            favorite: true,
+        },
+        NodeUsage {
+            node_type: "c".to_string(),
+            use_count: 0,
+            last_used_at: None,
+            favorite: true,
+        },
+    ];
+
+    let catalog = NodeCatalog::build(templates, usage);
+    assert_eq!(catalog.recent, vec!["b", "a"]);
+    assert_eq!(catalog.favorites, vec!["a", "c"]);
+    assert_eq!(catalog.usage.len(), 3);
+}
@@ -0,0 +1,153 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::NodeConfig;
+use ferroflux_core::graph_analysis::{GraphWarningKind, analyze_blueprint};
+use ferroflux_core::graph_loader::{WorkflowBlueprint, load_graph_from_str, save_graph};
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::traits::node_factory::{
+    NodeFactory, NodeMetadata, UNVERSIONED, is_older_version,
+};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+
+#[derive(Component)]
+struct Stored(Value);
+
+/// Version 2 of a node type that renamed its `url` setting to `endpoint`.
+struct Fetch {
+    deprecated: Option<String>,
+}
+
+impl NodeFactory for Fetch {
+    fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
+        entity.insert(Stored(config.clone()));
+        Ok(())
+    }
+
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Value> {
+        world.get::<Stored>(entity).map(|s| s.0.clone())
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            id: "fetch".to_string(),
+            name: "Fetch".to_string(),
+            category: "Network".to_string(),
+            platform: None,
+            description: None,
+            inputs: vec![],
+            outputs: vec![],
+            settings: vec![],
+            version: Some("2.0".to_string()),
+            deprecated: self.deprecated.clone(),
+        }
+    }
+
+    fn migrate(&self, from_version: &str, config: &Value) -> Option<anyhow::Result<Value>> {
+        if !from_version.starts_with("1.") && from_version != UNVERSIONED {
+            return None;
+        }
+        Some(Ok(json!({ "endpoint": config["url"] })))
+    }
+}
+
+const YAML: &str = r###"
+nodes:
+  - id: "99999999-9999-9999-9999-999999999999"
+    name: "Get users"
+    type: "fetch"
+    version: "1.4"
+    config:
+      url: "https://example.com/users"
+edges: []
+"###;
+
+fn world(deprecated: Option<&str>) -> World {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    let mut registry = NodeRegistry::default();
+    registry.register(
+        "fetch",
+        Box::new(Fetch {
+            deprecated: deprecated.map(str::to_string),
+        }),
+    );
+    world.insert_resource(registry);
+    world
+}
+
+/// Version findings of the analysis; a lone node is also reported as unreachable.
+fn version_warnings(blueprint: &WorkflowBlueprint, world: &World) -> Vec<GraphWarningKind> {
+    analyze_blueprint(blueprint, world.resource::<NodeRegistry>())
+        .into_iter()
+        .map(|w| w.kind)
+        .filter(|kind| *kind != GraphWarningKind::UnreachableNode)
+        .collect()
+}
+
+#[test]
+fn test_versions_compare_numerically() {
+    assert!(is_older_version("1.9", "1.10"));
+    assert!(is_older_version("1", "1.0.1"));
+    assert!(is_older_version("v1.2", "2"));
+    assert!(!is_older_version("1.0", "1"));
+    assert!(!is_older_version("2.1", "2.0.9"));
+}
+
+#[test]
+fn test_older_nodes_are_migrated_on_load_and_saved_with_the_new_version() {
+    let mut world = world(None);
+    load_graph_from_str(&mut world, TenantId::from("acme"), YAML).unwrap();
+
+    let (config, stored) = world.query::<(&NodeConfig, &Stored)>().single(&world);
+    assert_eq!(config.version.as_deref(), Some("2.0"));
+    assert_eq!(stored.0, json!({ "endpoint": "https://example.com/users" }));
+
+    let path = std::env::temp_dir().join(format!("versioned_{}.yaml", uuid::Uuid::new_v4()));
+    save_graph(&mut world, path.to_str().unwrap()).unwrap();
+    let saved: WorkflowBlueprint =
+        serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(saved.nodes[0].version.as_deref(), Some("2.0"));
+    assert!(version_warnings(&saved, &world).is_empty());
+}
+
+#[test]
+fn test_outdated_and_deprecated_nodes_are_reported() {
+    let world = world(Some("Use http_request instead"));
+    let blueprint: WorkflowBlueprint = serde_yaml::from_str(YAML).unwrap();
+
+    assert_eq!(
+        version_warnings(&blueprint, &world),
+        [
+            GraphWarningKind::OutdatedNode {
+                saved: "1.4".to_string(),
+                current: "2.0".to_string(),
+            },
+            GraphWarningKind::DeprecatedNode {
+                reason: "Use http_request instead".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_nodes_saved_before_versioning_are_migrated_and_reported() {
+    let yaml = YAML.replace("    version: \"1.4\"\n", "");
+    let blueprint: WorkflowBlueprint = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(blueprint.nodes[0].version, None);
+
+    let mut world = world(None);
+    assert_eq!(
+        version_warnings(&blueprint, &world),
+        [GraphWarningKind::OutdatedNode {
+            saved: UNVERSIONED.to_string(),
+            current: "2.0".to_string(),
+        }]
+    );
+
+    load_graph_from_str(&mut world, TenantId::from("acme"), &yaml).unwrap();
+    let (config, stored) = world.query::<(&NodeConfig, &Stored)>().single(&world);
+    assert_eq!(config.version.as_deref(), Some("2.0"));
+    assert_eq!(stored.0, json!({ "endpoint": "https://example.com/users" }));
+}
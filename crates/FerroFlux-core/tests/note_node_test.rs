@@ -0,0 +1,55 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::NoteConfig;
+use ferroflux_core::graph_analysis::analyze_blueprint;
+use ferroflux_core::graph_loader::{WorkflowBlueprint, load_graph_from_str, save_graph};
+use ferroflux_core::nodes::register_core_nodes;
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_iam::TenantId;
+
+const YAML: &str = r###"
+nodes:
+  - id: "44444444-4444-4444-4444-444444444444"
+    name: "Readme"
+    type: "note"
+    config:
+      content: "## Billing flow\nRuns nightly."
+      color: "#fde68a"
+edges: []
+"###;
+
+#[test]
+fn test_note_survives_load_and_save() {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    let mut registry = NodeRegistry::default();
+    register_core_nodes(&mut registry);
+    world.insert_resource(registry);
+
+    load_graph_from_str(&mut world, TenantId::from("default_tenant"), YAML).unwrap();
+
+    let note = world
+        .query::<&NoteConfig>()
+        .iter(&world)
+        .next()
+        .cloned()
+        .expect("note should be spawned");
+    assert_eq!(note.content, "## Billing flow\nRuns nightly.");
+
+    let path = std::env::temp_dir().join(format!("note_{}.yaml", uuid::Uuid::new_v4()));
+    save_graph(&mut world, path.to_str().unwrap()).unwrap();
+    let saved: WorkflowBlueprint =
+        serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(saved.nodes.len(), 1);
+    assert_eq!(saved.nodes[0].node_type, "note");
+    assert_eq!(
+        saved.nodes[0].config["content"],
+        "## Billing flow\nRuns nightly."
+    );
+    assert_eq!(saved.nodes[0].config["color"], "#fde68a");
+
+    // A lone note is not "unreachable".
+    assert!(analyze_blueprint(&saved, world.resource::<NodeRegistry>()).is_empty());
+}
@@ -1,6 +1,6 @@
 use bevy_ecs::prelude::*;
 use chrono::{Duration, Utc};
-use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
 use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
 use ferroflux_core::components::observability::*;
 use ferroflux_core::resources::{GraphTopology, WorkDone};
@@ -20,7 +20,7 @@ fn test_trace_propagation_via_transport() {
     world.insert_resource(WorkDone::default());
     world.insert_resource(GraphTopology::default());
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
 
     // Nodes
     let node_a_id = Uuid::new_v4();
@@ -34,6 +34,7 @@ fn test_trace_propagation_via_transport() {
                 node_type: "test".to_string(),
                 workflow_id: None,
                 tenant_id: None,
+                version: None,
             },
             Inbox::default(),
             Outbox::default(),
@@ -48,6 +49,7 @@ fn test_trace_propagation_via_transport() {
                 node_type: "test".to_string(),
                 workflow_id: None,
                 tenant_id: None,
+                version: None,
             },
             Inbox::default(),
             Outbox::default(),
@@ -94,11 +96,72 @@ fn test_trace_propagation_via_transport() {
     assert_eq!(inbox_b.queue.len(), 1);
 }
 
+#[test]
+fn test_edge_traversal_reports_payload_size() {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(GraphTopology::default());
+    let (tx, mut rx) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+
+    let splitter_id = Uuid::new_v4();
+    let node = |id: Uuid, name: &str| NodeConfig {
+        id,
+        name: name.to_string(),
+        node_type: "test".to_string(),
+        workflow_id: None,
+        tenant_id: None,
+        version: None,
+    };
+    let splitter = world
+        .spawn((node(splitter_id, "Splitter"), Outbox::default()))
+        .id();
+    let sink = world
+        .spawn((node(Uuid::new_v4(), "Sink"), Inbox::default()))
+        .id();
+    world
+        .resource_mut::<GraphTopology>()
+        .adjacency
+        .insert(splitter, vec![(Some("item".to_string()), sink)]);
+
+    for payload in [&b"[1,2,3]"[..], &b"{}"[..]] {
+        let ticket = world.resource::<BlobStore>().check_in(payload).unwrap();
+        world
+            .get_mut::<Outbox>(splitter)
+            .unwrap()
+            .queue
+            .push_back((Some("item".to_string()), ticket));
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(transport_worker);
+    schedule.run(&mut world);
+
+    let mut traversals = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let SystemEvent::EdgeTraversal {
+            source_id,
+            source_handle,
+            bytes,
+            ..
+        } = event
+        {
+            traversals.push((source_id, source_handle, bytes));
+        }
+    }
+    let item = Some("item".to_string());
+    assert_eq!(
+        traversals,
+        vec![(splitter_id, item.clone(), 7), (splitter_id, item, 2)]
+    );
+}
+
 #[test]
 fn test_janitor_trace_pruning() {
     let mut world = World::new();
     let (tx, _) = tokio::sync::broadcast::channel(100);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
     world.insert_resource(BlobStore::default());
     world.insert_resource(ferroflux_core::systems::janitor::JanitorTimer::default());
 
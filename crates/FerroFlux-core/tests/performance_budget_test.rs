@@ -0,0 +1,119 @@
+//! Coarse time budgets for the engine's hot paths. They are loose enough for shared CI runners
+//! and edge hardware but catch order-of-magnitude regressions. Debug builds get 10x the budget;
+//! `FERROFLUX_PERF_BUDGET_SCALE` overrides the factor. `cargo bench --bench core_paths` has
+//! the precise numbers.
+
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::templates::TemplateEngine;
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::templating::apply_template;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+fn budget(release_ms: u64) -> Duration {
+    let scale = std::env::var("FERROFLUX_PERF_BUDGET_SCALE")
+        .ok()
+        .and_then(|scale| scale.parse::<f64>().ok())
+        .unwrap_or(if cfg!(debug_assertions) { 10.0 } else { 1.0 });
+    Duration::from_secs_f64(release_ms as f64 / 1000.0 * scale)
+}
+
+fn assert_within(what: &str, elapsed: Duration, release_ms: u64) {
+    assert!(
+        elapsed < budget(release_ms),
+        "{} took {:?} (budget {:?})",
+        what,
+        elapsed,
+        budget(release_ms)
+    );
+}
+
+fn node(i: usize) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: format!("Node {}", i),
+        node_type: "Transform".to_string(),
+        workflow_id: Some("budget".to_string()),
+        tenant_id: None,
+        version: None,
+    }
+}
+
+#[test]
+fn test_transport_budget() {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(16);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(GraphTopology::default());
+    world.insert_resource(WorkDone::default());
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+
+    let source = world.spawn((node(0), Outbox::default())).id();
+    let targets: Vec<Entity> = (1..=4)
+        .map(|i| world.spawn((node(i), Inbox::default())).id())
+        .collect();
+    for target in &targets {
+        world.spawn(Edge {
+            source,
+            source_handle: None,
+            target: *target,
+            target_handle: None,
+        });
+    }
+    let mut schedule = Schedule::default();
+    schedule.add_systems((update_graph_topology, transport_worker).chain());
+    schedule.run(&mut world);
+
+    // 10k tickets fanned out to 4 targets: 40k deliveries in one tick.
+    world.get_mut::<Outbox>(source).unwrap().queue = (0..10_000)
+        .map(|i| (None, store.check_in(format!("{}", i).as_bytes()).unwrap()))
+        .collect();
+    let start = Instant::now();
+    schedule.run(&mut world);
+    assert_within("40k deliveries", start.elapsed(), 200);
+    for target in &targets {
+        assert_eq!(world.get::<Inbox>(*target).unwrap().queue.len(), 10_000);
+    }
+}
+
+#[test]
+fn test_blob_store_budget() {
+    let store = BlobStore::default();
+    let payload = vec![b'x'; 1024];
+
+    let start = Instant::now();
+    let tickets: Vec<_> = (0..10_000)
+        .map(|_| store.check_in(&payload).unwrap())
+        .collect();
+    for ticket in &tickets {
+        assert_eq!(store.claim(ticket).unwrap().len(), 1024);
+    }
+    assert_within("10k 1 KiB check-in/claim round trips", start.elapsed(), 200);
+}
+
+#[test]
+fn test_template_budget() {
+    let data = json!({
+        "user": { "name": "Ada" },
+        "items": (0..20).map(|i| json!({ "sku": format!("SKU-{}", i), "qty": i })).collect::<Vec<_>>(),
+    });
+    let template = "Hi {{user.name}}: {{#each items}}{{sku}} x{{qty}}, {{/each}}";
+    let engine = TemplateEngine::default();
+
+    let start = Instant::now();
+    for _ in 0..1_000 {
+        std::hint::black_box(engine.render(template, &data).unwrap());
+    }
+    assert_within("1k TemplateEngine renders", start.elapsed(), 100);
+
+    let start = Instant::now();
+    for _ in 0..1_000 {
+        std::hint::black_box(apply_template(template, &data));
+    }
+    assert_within("1k apply_template renders", start.elapsed(), 200);
+}
@@ -0,0 +1,91 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::pin::{handle_pin_node_output, handle_unpin_node};
+use ferroflux_core::components::{NodeConfig, PinnedOutput};
+use ferroflux_core::store::BlobStore;
+use ferroflux_iam::{Principal, TenantId};
+use serde_json::json;
+
+fn setup() -> (World, Entity, uuid::Uuid) {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(BlobStore::default());
+    let node_id = uuid::Uuid::new_v4();
+    let entity = world
+        .spawn(NodeConfig {
+            id: node_id,
+            name: "Lookup".to_string(),
+            node_type: "Http".to_string(),
+            workflow_id: Some("crm".to_string()),
+            tenant_id: Some(TenantId::from("acme")),
+            version: None,
+        })
+        .id();
+    (world, entity, node_id)
+}
+
+#[test]
+fn test_pin_and_unpin_node_output() {
+    let (mut world, entity, node_id) = setup();
+    let mut events = world.resource::<SystemEventBus>().subscribe();
+    let tenant = TenantId::from("acme");
+    let output = json!({ "customer": { "id": 17, "tier": "gold" } });
+
+    handle_pin_node_output(
+        &mut world,
+        &Principal::system(),
+        tenant.clone(),
+        node_id,
+        output.clone(),
+    )
+    .unwrap();
+    let pinned = world.get::<PinnedOutput>(entity).unwrap().0.clone();
+    assert_eq!(pinned.metadata["pinned"], "true");
+    let stored = world.resource::<BlobStore>().claim(&pinned).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&stored).unwrap(),
+        output
+    );
+    match events.try_recv().unwrap() {
+        SystemEvent::NodePinChanged {
+            node_id: id,
+            ticket_id,
+            ..
+        } => {
+            assert_eq!(id, node_id);
+            assert_eq!(ticket_id, Some(pinned.id));
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    handle_unpin_node(&mut world, &Principal::system(), tenant.clone(), node_id).unwrap();
+    assert!(world.get::<PinnedOutput>(entity).is_none());
+    assert!(matches!(
+        events.try_recv().unwrap(),
+        SystemEvent::NodePinChanged {
+            ticket_id: None,
+            ..
+        }
+    ));
+
+    // Unpinning again is a no-op without an event.
+    handle_unpin_node(&mut world, &Principal::system(), tenant, node_id).unwrap();
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn test_pin_requires_node_of_tenant() {
+    let (mut world, entity, node_id) = setup();
+    assert!(
+        handle_pin_node_output(
+            &mut world,
+            &Principal::system(),
+            TenantId::from("globex"),
+            node_id,
+            json!({})
+        )
+        .is_err()
+    );
+    assert!(world.get::<PinnedOutput>(entity).is_none());
+}
@@ -0,0 +1,270 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::{ApiCommand, ApiRequest};
+use ferroflux_core::app::AppBuilder;
+use ferroflux_core::components::agent::AgentConfig;
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::integrations::IntegrationRegistry;
+use ferroflux_core::integrations::registry::{
+    ActionImplementation, AuthType, IntegrationAction, IntegrationConfig, IntegrationDef,
+};
+use ferroflux_core::resources::prompts::{PromptLibrary, PromptRef};
+use ferroflux_core::resources::{
+    GlobalHttpClient, PipelineResultChannel, TokioRuntime, WorkDone, templates::TemplateEngine,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::agent::{agent_exec, agent_post, agent_prep};
+use ferroflux_iam::TenantId;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn save(tenant: &TenantId, name: &str, template: &str, variables: &[&str]) -> ApiRequest {
+    ApiRequest::system(ApiCommand::SavePromptTemplate {
+        tenant_id: tenant.clone(),
+        name: name.to_string(),
+        template: template.to_string(),
+        variables: variables.iter().map(|v| v.to_string()).collect(),
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_templates_are_versioned_and_survive_a_restart() {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let (mut app, api_tx, ..) = AppBuilder::new()
+        .with_store(store.clone())
+        .with_master_key(vec![7; 32])
+        .build()
+        .await
+        .unwrap();
+    let acme = TenantId::from("acme");
+    let latest: PromptRef = "greeting".parse().unwrap();
+
+    for request in [
+        save(&acme, "greeting", "Hello {{name}}", &["name"]),
+        save(&acme, "greeting", "Hi {{name}}!", &["name"]),
+        save(&TenantId::from("other"), "greeting", "Yo", &[]),
+        // Rejected: '@' separates the version.
+        save(&acme, "bad@name", "x", &[]),
+    ] {
+        api_tx.send(request).await.unwrap();
+    }
+    app.update();
+
+    let library = app.world.resource::<PromptLibrary>();
+    assert_eq!(library.list(&acme).len(), 2);
+    let current = library.resolve(&acme, &latest).unwrap();
+    assert_eq!(
+        (current.version, current.template.as_str()),
+        (2, "Hi {{name}}!")
+    );
+    let pinned = library
+        .resolve(&acme, &"greeting@1".parse().unwrap())
+        .unwrap();
+    assert_eq!(pinned.template, "Hello {{name}}");
+    assert_eq!(pinned.variables, ["name"]);
+
+    // Deleting the latest version makes the previous one current; versions are not reused.
+    api_tx
+        .send(ApiRequest::system(ApiCommand::DeletePromptTemplate {
+            tenant_id: acme.clone(),
+            name: "greeting".to_string(),
+            version: Some(2),
+        }))
+        .await
+        .unwrap();
+    api_tx
+        .send(save(&acme, "farewell", "Bye", &[]))
+        .await
+        .unwrap();
+    app.update();
+    let library = app.world.resource::<PromptLibrary>();
+    assert_eq!(library.resolve(&acme, &latest).unwrap().version, 1);
+
+    let restarted = PromptLibrary::load(&store).await.unwrap();
+    let names: Vec<_> = restarted
+        .list(&acme)
+        .iter()
+        .map(|t| format!("{}@{}", t.name, t.version))
+        .collect();
+    assert_eq!(names, ["farewell@1", "greeting@1"]);
+    assert_eq!(
+        store
+            .save_prompt_template(&acme, "greeting", "Hey", &[])
+            .await
+            .unwrap()
+            .version,
+        3
+    );
+}
+
+#[test]
+fn test_prompt_refs_parse_name_and_version() {
+    let pinned: PromptRef = "support.triage@12".parse().unwrap();
+    assert_eq!(pinned.name, "support.triage");
+    assert_eq!(pinned.version, Some(12));
+    assert_eq!(pinned.to_string(), "support.triage@12");
+    assert!("triage@latest".parse::<PromptRef>().is_err());
+    assert!("@3".parse::<PromptRef>().is_err());
+}
+
+fn provider(base_url: String) -> IntegrationDef {
+    let action = IntegrationAction {
+        inputs: vec![],
+        outputs: vec![],
+        category: None,
+        subcategory: None,
+        documentation: None,
+        message_transform: None,
+        implementation: ActionImplementation {
+            impl_type: "http".to_string(),
+            config: IntegrationConfig {
+                method: "POST".to_string(),
+                path: "/chat".to_string(),
+                headers: HashMap::new(),
+                body_template: Some(
+                    r#"{"system": {{json system_instruction}}, "prompt": {{json user_prompt}}}"#
+                        .to_string(),
+                ),
+                pagination: None,
+            },
+        },
+        output_transform: None,
+        stream: None,
+    };
+    IntegrationDef {
+        name: "mock".to_string(),
+        base_url,
+        auth: None,
+        connection_schema: None,
+        actions: HashMap::from([("chat_completion".to_string(), action)]),
+        icon_url: None,
+        verify_endpoint: None,
+        capabilities: None,
+        utilities: HashMap::new(),
+        resources: HashMap::new(),
+        auth_type: AuthType::None,
+        verify_params: HashMap::new(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_agents_render_library_templates() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat"))
+        .and(body_partial_json(json!({
+            "system": "You triage tickets for Acme.",
+            "prompt": "Classify: printer on fire",
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "label": "urgent" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let acme = TenantId::from("acme");
+    let mut library = PromptLibrary::default();
+    for (name, template, variables) in [
+        (
+            "triage.system",
+            "You triage tickets for {{company}}.",
+            vec![],
+        ),
+        ("triage.user", "Classify: {{ticket.subject}}", vec![]),
+        (
+            "triage.user",
+            "Classify: {{ticket.body}}",
+            vec!["ticket.body".to_string()],
+        ),
+    ] {
+        let template = db
+            .save_prompt_template(&acme, name, template, &variables)
+            .await
+            .unwrap();
+        library.insert(&acme, template);
+    }
+
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(ferroflux_core::secrets::DatabaseSecretStore::new(
+        db,
+        vec![0; 32],
+    ));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TemplateEngine::default());
+    world.insert_resource(PipelineResultChannel::default());
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(library);
+    let mut registry = IntegrationRegistry::default();
+    registry
+        .definitions
+        .insert("mock".to_string(), provider(server.uri()));
+    world.insert_resource(registry);
+
+    let store = world.resource::<BlobStore>().clone();
+    let spawn = |world: &mut World, user_prompt_ref: &str, payload: serde_json::Value| {
+        let mut inbox = Inbox::default();
+        inbox
+            .queue
+            .push_back(store.check_in(payload.to_string().as_bytes()).unwrap());
+        world
+            .spawn((
+                AgentConfig {
+                    provider: "mock".to_string(),
+                    model: "test-model".to_string(),
+                    system_prompt_ref: Some("triage.system".to_string()),
+                    user_prompt_ref: Some(user_prompt_ref.to_string()),
+                    result_key: Some("triage".to_string()),
+                    ..Default::default()
+                },
+                NodeConfig {
+                    id: uuid::Uuid::new_v4(),
+                    name: "Triage".to_string(),
+                    node_type: "Agent".to_string(),
+                    workflow_id: None,
+                    tenant_id: Some(acme.clone()),
+                    version: None,
+                },
+                inbox,
+                Outbox::default(),
+            ))
+            .id()
+    };
+    let ticket = json!({ "company": "Acme", "ticket": { "subject": "printer on fire" } });
+    // The latest `triage.user` needs `ticket.body`, which this payload lacks.
+    let missing = spawn(&mut world, "triage.user", ticket.clone());
+    let pinned = spawn(&mut world, "triage.user@1", ticket);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((agent_prep, agent_exec, agent_post));
+    for _ in 0..150 {
+        schedule.run(&mut world);
+        if !world.get::<Outbox>(pinned).unwrap().queue.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let (_, output) = world.get::<Outbox>(pinned).unwrap().queue[0].clone();
+    let output: serde_json::Value = serde_json::from_slice(&store.claim(&output).unwrap()).unwrap();
+    assert_eq!(output["triage"]["label"], "urgent");
+
+    assert!(world.get::<Outbox>(missing).unwrap().queue.is_empty());
+    let error = std::iter::from_fn(|| events.try_recv().ok())
+        .find_map(|event| match event {
+            SystemEvent::NodeError { error, .. } => Some(error),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        error,
+        "Prompt template 'triage.user@2' is missing variables: ticket.body"
+    );
+}
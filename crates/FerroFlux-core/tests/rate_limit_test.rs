@@ -0,0 +1,114 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::control::{OverflowPolicy, RateLimitConfig, RateLimitState};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::control::rate_limit_worker;
+use std::time::Duration;
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    entity: Entity,
+}
+
+impl Harness {
+    fn new(config: RateLimitConfig, tickets: usize) -> Self {
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(WorkDone::default());
+        let store = BlobStore::default();
+
+        let mut inbox = Inbox::default();
+        for i in 0..tickets {
+            inbox
+                .queue
+                .push_back(store.check_in(i.to_string().as_bytes()).unwrap());
+        }
+        let entity = world
+            .spawn((
+                config,
+                RateLimitState::default(),
+                NodeConfig {
+                    id: uuid::Uuid::new_v4(),
+                    name: "CRM Rate Limit".to_string(),
+                    node_type: "RateLimit".to_string(),
+                    workflow_id: None,
+                    tenant_id: None,
+                    version: None,
+                },
+                inbox,
+                Outbox::default(),
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(rate_limit_worker);
+        Self {
+            world,
+            schedule,
+            entity,
+        }
+    }
+
+    /// Runs a tick and returns (released, dead-lettered, still in inbox, queued).
+    fn tick(&mut self) -> (usize, usize, usize, usize) {
+        self.schedule.run(&mut self.world);
+        let items: Vec<_> = self
+            .world
+            .get_mut::<Outbox>(self.entity)
+            .unwrap()
+            .queue
+            .drain(..)
+            .collect();
+        let dead = items
+            .iter()
+            .filter(|(port, _)| port.as_deref() == Some("dead_letter"))
+            .count();
+        (
+            items.len() - dead,
+            dead,
+            self.world.get::<Inbox>(self.entity).unwrap().queue.len(),
+            self.world
+                .get::<RateLimitState>(self.entity)
+                .unwrap()
+                .queue
+                .len(),
+        )
+    }
+}
+
+fn config(interval_ms: u64, overflow: OverflowPolicy) -> RateLimitConfig {
+    RateLimitConfig {
+        max_per_interval: 2,
+        interval_ms,
+        queue_capacity: 2,
+        overflow,
+    }
+}
+
+#[test]
+fn test_overflow_policies() {
+    let minute = 60_000;
+
+    let mut h = Harness::new(config(minute, OverflowPolicy::Backpressure), 5);
+    assert_eq!(h.tick(), (2, 0, 3, 0));
+    assert_eq!(h.tick(), (0, 0, 1, 2));
+
+    let mut h = Harness::new(config(minute, OverflowPolicy::Drop), 5);
+    assert_eq!(h.tick(), (2, 0, 0, 0));
+
+    let mut h = Harness::new(config(minute, OverflowPolicy::DeadLetter), 5);
+    assert_eq!(h.tick(), (2, 3, 0, 0));
+}
+
+#[test]
+fn test_tokens_refill_over_interval() {
+    let mut h = Harness::new(config(100, OverflowPolicy::Backpressure), 4);
+    assert_eq!(h.tick(), (2, 0, 2, 0));
+    assert_eq!(h.tick(), (0, 0, 0, 2));
+
+    std::thread::sleep(Duration::from_millis(120));
+    assert_eq!(h.tick(), (2, 0, 0, 0));
+}
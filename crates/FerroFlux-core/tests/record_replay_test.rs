@@ -0,0 +1,234 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::replay::handle_replay_run;
+use ferroflux_core::components::{Edge, HttpConfig, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{GraphTopology, ReplayRuns, TokioRuntime, WorkDone};
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::store::{BlobStore, SecureTicket};
+use ferroflux_core::systems::replay::{REPLAY_OF_KEY, io_recorder_worker, replay_worker};
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use ferroflux_iam::{Principal, TenantId};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+fn node(node_type: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: node_type.to_string(),
+        node_type: node_type.to_string(),
+        workflow_id: Some("orders".to_string()),
+        tenant_id: Some(TenantId::from("acme")),
+        version: None,
+    }
+}
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    webhook: Entity,
+    http: Entity,
+    sink: Entity,
+}
+
+impl Harness {
+    /// Webhook -> Http -> Sink. There is no HTTP worker: the test answers requests itself
+    /// while recording, and only the replay interceptor may answer them during replay.
+    async fn new(db: PersistentStore) -> Self {
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(GraphTopology::default());
+        world.insert_resource(WorkDone::default());
+        world.insert_resource(ReplayRuns::default());
+        world.insert_resource(BlobStore::default());
+        world.insert_resource(db);
+        world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+        let mut settings = EngineSettings::default();
+        settings.capture.io_results = true;
+        world.insert_resource(RuntimeSettings::new(settings));
+
+        let webhook = world
+            .spawn((node("Webhook"), Inbox::default(), Outbox::default()))
+            .id();
+        let http = world
+            .spawn((
+                node("Http"),
+                HttpConfig {
+                    url: "https://payments.example.com/charge".to_string(),
+                    method: "POST".to_string(),
+                    result_key: None,
+                    connection_slug: None,
+                    pagination: None,
+                    full_response: false,
+                    timeout_ms: None,
+                    max_redirects: None,
+                    proxy: None,
+                    no_proxy: None,
+                },
+                Inbox::default(),
+                Outbox::default(),
+            ))
+            .id();
+        let sink = world.spawn((node("Sink"), Inbox::default())).id();
+        for (source, target) in [(webhook, http), (http, sink)] {
+            world.spawn(Edge {
+                source,
+                source_handle: None,
+                target,
+                target_handle: None,
+            });
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                update_graph_topology,
+                replay_worker,
+                io_recorder_worker,
+                transport_worker,
+            )
+                .chain(),
+        );
+        Self {
+            world,
+            schedule,
+            webhook,
+            http,
+            sink,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.schedule.run(&mut self.world);
+    }
+
+    fn sink_tickets(&mut self) -> Vec<SecureTicket> {
+        self.world
+            .get_mut::<Inbox>(self.sink)
+            .unwrap()
+            .queue
+            .drain(..)
+            .collect()
+    }
+
+    fn payload(&self, ticket: &SecureTicket) -> Value {
+        let store = self.world.resource::<BlobStore>();
+        serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recorded_run_replays_without_calling_out() {
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let mut harness = Harness::new(db.clone()).await;
+    let store = harness.world.resource::<BlobStore>().clone();
+    let tenant = TenantId::from("acme");
+
+    // Production run: an untraced webhook delivery, answered by the payment API.
+    let delivery = store.check_in(br#"{"order": 7}"#).unwrap();
+    harness
+        .world
+        .get_mut::<Outbox>(harness.webhook)
+        .unwrap()
+        .queue
+        .push_back((None, delivery));
+    harness.tick();
+    let request = harness
+        .world
+        .get_mut::<Inbox>(harness.http)
+        .unwrap()
+        .queue
+        .pop_front()
+        .unwrap();
+    let trace_id = request.metadata["trace_id"].clone();
+    let mut metadata = request.metadata.clone();
+    metadata.insert("status".to_string(), "ok".to_string());
+    let response = store
+        .check_in_with_metadata(br#"{"charged": true}"#, metadata)
+        .unwrap();
+    harness
+        .world
+        .get_mut::<Outbox>(harness.http)
+        .unwrap()
+        .queue
+        .push_back((None, response));
+    harness.tick();
+    assert_eq!(harness.sink_tickets().len(), 1);
+
+    // Recordings are written in the background.
+    let mut recorded = Vec::new();
+    for _ in 0..100 {
+        recorded = db.load_io_recordings(&tenant, &trace_id).await.unwrap();
+        if recorded.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(recorded.len(), 2);
+
+    // Replay: the webhook re-emits the delivery and the recorded response is fed back.
+    let mut events = harness.world.resource::<SystemEventBus>().subscribe();
+    handle_replay_run(
+        &mut harness.world,
+        &Principal::system(),
+        tenant.clone(),
+        trace_id.clone(),
+    )
+    .unwrap();
+    for _ in 0..3 {
+        harness.tick();
+    }
+    let replayed = harness.sink_tickets();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(harness.payload(&replayed[0]), json!({ "charged": true }));
+    assert_eq!(replayed[0].metadata[REPLAY_OF_KEY], trace_id);
+    assert_eq!(replayed[0].metadata["status"], "ok");
+    assert!(
+        harness
+            .world
+            .get::<Inbox>(harness.http)
+            .unwrap()
+            .queue
+            .is_empty()
+    );
+
+    let replay_id = match events.try_recv().unwrap() {
+        SystemEvent::ReplayStarted {
+            trace_id: replay_id,
+            replay_of,
+            recorded,
+            ..
+        } => {
+            assert_eq!(replay_of, trace_id);
+            assert_eq!(recorded, 2);
+            replay_id
+        }
+        other => panic!("unexpected event {:?}", other),
+    };
+    assert_ne!(replay_id, trace_id);
+
+    // The replay itself is not recorded.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(
+        db.load_io_recordings(&tenant, &replay_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replay_of_unknown_trace_fails() {
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let mut harness = Harness::new(db).await;
+    assert!(
+        handle_replay_run(
+            &mut harness.world,
+            &Principal::system(),
+            TenantId::from("acme"),
+            "missing".to_string()
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,80 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::connectors::{RedisConfig, RedisOperation, RedisState};
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::{RedisResultChannel, TokioRuntime, WorkDone};
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::redis_worker;
+use tokio::runtime::Runtime;
+
+#[test]
+fn test_redis_blocked_host_routes_to_error_port() {
+    let rt = Runtime::new().unwrap();
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(rt.handle().clone()));
+    world.insert_resource(RedisResultChannel::default());
+    world.insert_resource(WorkDone::default());
+    let store = rt
+        .block_on(PersistentStore::new("sqlite::memory:"))
+        .expect("Failed to init DB");
+    world.insert_resource(DatabaseSecretStore::new(store, vec![0; 32]));
+
+    let ticket = world
+        .resource::<BlobStore>()
+        .check_in(br#"{"user": {"id": 7}}"#)
+        .unwrap();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(ticket.clone());
+
+    let node = world
+        .spawn((
+            RedisConfig {
+                // Internal addresses are rejected unless explicitly allowed.
+                url: Some("redis://169.254.169.254:6379".to_string()),
+                operation: RedisOperation::Incr,
+                key: "visits:{{user.id}}".to_string(),
+                value: None,
+                ttl_seconds: None,
+                result_key: Some("visits".to_string()),
+                connection_slug: None,
+                timeout_ms: 5_000,
+            },
+            RedisState::default(),
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Counter".to_string(),
+                node_type: "Redis".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(redis_worker);
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while world.get::<Outbox>(node).unwrap().queue.is_empty() {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "command never answered"
+        );
+        schedule.run(&mut world);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.len(), 1);
+    let (port, routed) = outbox.queue.front().unwrap();
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(routed.id, ticket.id);
+    let state = world.get::<RedisState>(node).unwrap();
+    assert!(state.connection.try_lock().unwrap().is_none());
+}
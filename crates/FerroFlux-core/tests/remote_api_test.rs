@@ -0,0 +1,135 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEvent;
+use ferroflux_core::api::remote::{NodeOwners, RemoteServer, connect};
+use ferroflux_core::api::{ApiCommand, ApiReceiver, ApiRequest, Reply};
+use ferroflux_core::systems::api_worker::api_command_worker;
+use ferroflux_core::templates::TemplateLibrary;
+use ferroflux_iam::{IamStore, Principal, Role, TenantId};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const TEMPLATE: &str = r#"
+id: "lead-follow-up"
+name: "Lead follow-up"
+category: "Sales"
+nodes: []
+edges: []
+"#;
+
+/// Serves a world with one template on a local port, ticking it in the background. Returns
+/// the URL, the engine's event bus, a Viewer API key and the key's tenant.
+async fn serve() -> (
+    String,
+    tokio::sync::broadcast::Sender<SystemEvent>,
+    String,
+    TenantId,
+) {
+    let path = std::env::temp_dir().join(format!("remote_{}.db", uuid::Uuid::new_v4()));
+    let iam = IamStore::new(&format!("sqlite://{}", path.display()))
+        .await
+        .unwrap();
+    let (_, user) = iam.create_magic_link("ada@example.com").await.unwrap();
+    let acme = iam.create_organization(&user, "Acme").await.unwrap();
+    let key = iam
+        .create_api_key(&Principal::system(), &acme, "desktop", Role::Viewer, None)
+        .await
+        .unwrap();
+
+    let (api_tx, api_rx) = async_channel::unbounded();
+    let (event_tx, _) = tokio::sync::broadcast::channel(100);
+    let mut world = World::new();
+    let mut templates = TemplateLibrary::default();
+    templates.register_yaml(TEMPLATE).unwrap();
+    world.insert_resource(templates);
+    world.insert_resource(ApiReceiver(api_rx));
+    tokio::spawn(async move {
+        loop {
+            api_command_worker(&mut world);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = RemoteServer::new(iam, api_tx, event_tx.clone(), NodeOwners::default());
+    tokio::spawn(server.serve(listener));
+    (url, event_tx, key.secret, acme)
+}
+
+fn pin_changed(tenant: &TenantId) -> SystemEvent {
+    SystemEvent::NodePinChanged {
+        tenant_id: tenant.to_string(),
+        node_id: uuid::Uuid::new_v4(),
+        ticket_id: None,
+        timestamp: 0,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commands_run_as_the_token_principal_and_replies_come_back() {
+    let (url, _, secret, acme) = serve().await;
+    assert!(connect(&url, "ffk_not-a-key").await.is_err());
+
+    let (api, _) = connect(&url, &secret).await.unwrap();
+    let (reply, rx) = Reply::channel();
+    api.send(ApiRequest::system(ApiCommand::ListTemplates {
+        tenant_id: acme.clone(),
+        reply,
+    }))
+    .await
+    .unwrap();
+    let catalog = tokio::time::timeout(Duration::from_secs(5), rx)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(catalog["Sales"][0].id, "lead-follow-up");
+
+    // Claiming to be the system principal doesn't help: the key only has Viewer in acme.
+    let (reply, rx) = Reply::channel();
+    api.send(ApiRequest::system(ApiCommand::InstantiateTemplate {
+        tenant_id: acme.clone(),
+        template_id: "lead-follow-up".to_string(),
+        workflow_id: None,
+        connections: HashMap::new(),
+        reply,
+    }))
+    .await
+    .unwrap();
+    let outcome = tokio::time::timeout(Duration::from_secs(5), rx)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(outcome.unwrap_err().contains("has role 'viewer'"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_clients_only_receive_events_of_their_tenants() {
+    let (url, engine_events, secret, acme) = serve().await;
+    let (_api, events) = connect(&url, &secret).await.unwrap();
+    let mut events = events.subscribe();
+
+    engine_events
+        .send(pin_changed(&TenantId::from("globex")))
+        .unwrap();
+    engine_events
+        .send(SystemEvent::HostCircuitOpened {
+            host: "api.example.com".to_string(),
+            consecutive_failures: 5,
+            open_ms: 1000,
+            timestamp: 0,
+        })
+        .unwrap();
+    engine_events.send(pin_changed(&acme)).unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.tenant_id(), Some(acme.as_ref()));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), events.recv())
+            .await
+            .is_err()
+    );
+}
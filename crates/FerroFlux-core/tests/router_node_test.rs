@@ -0,0 +1,119 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::{
+    Inbox, NodeConfig, Outbox, RouteCase, RouterConfig, RouterLanguage,
+};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::logic::router_worker;
+use serde_json::{Value, json};
+
+fn case(port: &str, values: Vec<Value>) -> RouteCase {
+    RouteCase {
+        port: port.to_string(),
+        values,
+    }
+}
+
+/// Routes each input through a fresh Router node and returns the ports it left through.
+fn route(config: RouterConfig, inputs: &[Value]) -> Vec<Vec<String>> {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(WorkDone::default());
+    world.insert_non_send_resource(rhai::Engine::new());
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    let entity = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Route Order".to_string(),
+                node_type: "Router".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            Inbox::default(),
+            Outbox::default(),
+        ))
+        .id();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(router_worker);
+
+    inputs
+        .iter()
+        .map(|input| {
+            let ticket = store.check_in(&serde_json::to_vec(input).unwrap()).unwrap();
+            world
+                .get_mut::<Inbox>(entity)
+                .unwrap()
+                .queue
+                .push_back(ticket);
+            schedule.run(&mut world);
+            world
+                .get_mut::<Outbox>(entity)
+                .unwrap()
+                .queue
+                .drain(..)
+                .map(|(port, _)| port.unwrap_or_default())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_router_selects_first_matching_case() {
+    let config = RouterConfig {
+        expression: "input.status".to_string(),
+        language: RouterLanguage::Rhai,
+        cases: vec![
+            case("ok", vec![json!(200), json!(204)]),
+            case("retry", vec![json!("429"), json!(503)]),
+            case("also_ok", vec![json!(200)]),
+        ],
+        default_port: "other".to_string(),
+        match_all: false,
+    };
+    let ports = route(
+        config,
+        &[
+            json!({ "status": 200 }),
+            json!({ "status": 429 }),
+            json!({ "status": "503" }),
+            json!({ "status": 404 }),
+            json!("not an object"),
+        ],
+    );
+    assert_eq!(
+        ports,
+        vec![
+            vec!["ok"],
+            vec!["retry"],
+            vec!["retry"],
+            vec!["other"],
+            vec!["error"],
+        ]
+    );
+}
+
+#[test]
+fn test_router_match_all_fans_out() {
+    let config: RouterConfig = serde_json::from_value(json!({
+        "expression": "input.tier",
+        "language": "Rhai",
+        "cases": [
+            { "port": "priority", "values": ["gold", "platinum"] },
+            { "port": "audit", "values": ["platinum"] },
+        ],
+        "match_all": true,
+    }))
+    .unwrap();
+    assert_eq!(config.default_port, "default");
+    let ports = route(
+        config,
+        &[json!({ "tier": "platinum" }), json!({ "tier": "bronze" })],
+    );
+    assert_eq!(ports, vec![vec!["priority", "audit"], vec!["default"]]);
+}
@@ -0,0 +1,227 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::run::{
+    handle_cancel_run, handle_pause_workflow, handle_resume_workflow,
+};
+use ferroflux_core::components::observability::{Trace, TraceCancelled};
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::connection_health::PausedWorkflows;
+use ferroflux_core::resources::{GraphTopology, WorkDone};
+use ferroflux_core::store::{BlobStore, SecureTicket};
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use ferroflux_iam::{Principal, TenantId};
+use std::collections::HashMap;
+
+fn node(tenant: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: "Step".to_string(),
+        node_type: "Transform".to_string(),
+        workflow_id: Some("billing".to_string()),
+        tenant_id: Some(TenantId::from(tenant)),
+        version: None,
+    }
+}
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    store: BlobStore,
+    source: Entity,
+    target: Entity,
+}
+
+impl Harness {
+    fn new(tenant: &str) -> Self {
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(GraphTopology::default());
+        world.insert_resource(WorkDone::default());
+        world.insert_resource(PausedWorkflows::default());
+        let store = BlobStore::default();
+        world.insert_resource(store.clone());
+
+        let source = world
+            .spawn((node(tenant), Inbox::default(), Outbox::default()))
+            .id();
+        let target = world
+            .spawn((node(tenant), Inbox::default(), Outbox::default()))
+            .id();
+        world.spawn(Edge {
+            source,
+            source_handle: None,
+            target,
+            target_handle: None,
+        });
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_graph_topology, transport_worker).chain());
+        Self {
+            world,
+            schedule,
+            store,
+            source,
+            target,
+        }
+    }
+
+    fn ticket(&self, trace_id: &str) -> SecureTicket {
+        let metadata = HashMap::from([("trace_id".to_string(), trace_id.to_string())]);
+        self.store.check_in_with_metadata(b"{}", metadata).unwrap()
+    }
+
+    fn emit(&mut self, trace_id: &str) {
+        let ticket = self.ticket(trace_id);
+        self.world
+            .get_mut::<Outbox>(self.source)
+            .unwrap()
+            .queue
+            .push_back((None, ticket));
+    }
+
+    fn delivered(&self) -> Vec<String> {
+        self.world
+            .get::<Inbox>(self.target)
+            .unwrap()
+            .queue
+            .iter()
+            .map(|ticket| ticket.metadata["trace_id"].clone())
+            .collect()
+    }
+}
+
+#[test]
+fn test_pause_holds_tickets_until_resumed() {
+    let mut harness = Harness::new("acme");
+    let tenant = TenantId::from("acme");
+
+    handle_pause_workflow(&mut harness.world, tenant.clone(), "billing".to_string()).unwrap();
+    harness.emit("run-1");
+    harness.schedule.run(&mut harness.world);
+    assert!(harness.delivered().is_empty());
+    assert_eq!(
+        harness
+            .world
+            .get::<Outbox>(harness.source)
+            .unwrap()
+            .queue
+            .len(),
+        1
+    );
+
+    // Another tenant's workflow of the same name is unaffected.
+    assert!(
+        !harness
+            .world
+            .resource::<PausedWorkflows>()
+            .is_paused(&TenantId::from("globex"), "billing")
+    );
+
+    handle_resume_workflow(&mut harness.world, tenant, "billing".to_string()).unwrap();
+    harness.schedule.run(&mut harness.world);
+    assert_eq!(harness.delivered(), ["run-1"]);
+}
+
+#[test]
+fn test_cancel_purges_in_flight_tickets() {
+    let mut harness = Harness::new("acme");
+    let mut events = harness.world.resource::<SystemEventBus>().subscribe();
+    let cancelled_id = uuid::Uuid::new_v4();
+    let cancelled = cancelled_id.to_string();
+    let trace = harness.world.spawn(Trace(cancelled_id)).id();
+
+    // One ticket of the run waiting in an inbox, one in an outbox, plus another run's ticket.
+    let queued = harness.ticket(&cancelled);
+    harness
+        .world
+        .get_mut::<Inbox>(harness.source)
+        .unwrap()
+        .queue
+        .push_back(queued.clone());
+    harness.emit(&cancelled);
+    harness.emit("other");
+
+    // A different tenant's node holding a ticket with the same trace id keeps it.
+    let foreign = harness.ticket(&cancelled);
+    let bystander = harness.world.spawn((node("globex"), Inbox::default())).id();
+    harness
+        .world
+        .get_mut::<Inbox>(bystander)
+        .unwrap()
+        .queue
+        .push_back(foreign);
+
+    handle_cancel_run(
+        &mut harness.world,
+        &Principal::system(),
+        TenantId::from("acme"),
+        cancelled.clone(),
+    )
+    .unwrap();
+    assert!(
+        harness
+            .world
+            .get::<Inbox>(harness.source)
+            .unwrap()
+            .queue
+            .is_empty()
+    );
+    assert!(harness.store.claim(&queued).is_err());
+    assert_eq!(
+        harness.world.get::<Inbox>(bystander).unwrap().queue.len(),
+        1
+    );
+    assert!(harness.world.get::<TraceCancelled>(trace).is_some());
+    match events.try_recv().unwrap() {
+        SystemEvent::RunCancelled {
+            trace_id, purged, ..
+        } => {
+            assert_eq!(trace_id, cancelled);
+            assert_eq!(purged, 2);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+
+    // A result that was still in flight arrives after the cancellation and is dropped.
+    harness.emit(&cancelled);
+    harness.schedule.run(&mut harness.world);
+    assert_eq!(harness.delivered(), ["other"]);
+    assert!(
+        harness
+            .world
+            .get::<Outbox>(harness.source)
+            .unwrap()
+            .queue
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_cancel_only_drops_the_cancelling_tenants_tickets() {
+    let mut harness = Harness::new("globex");
+    let trace_id = uuid::Uuid::new_v4().to_string();
+
+    // Another tenant naming the same trace id doesn't stop this tenant's run.
+    handle_cancel_run(
+        &mut harness.world,
+        &Principal::system(),
+        TenantId::from("acme"),
+        trace_id.clone(),
+    )
+    .unwrap();
+    harness.emit(&trace_id);
+    harness.schedule.run(&mut harness.world);
+    assert_eq!(harness.delivered(), vec![trace_id.clone()]);
+
+    handle_cancel_run(
+        &mut harness.world,
+        &Principal::system(),
+        TenantId::from("globex"),
+        trace_id.clone(),
+    )
+    .unwrap();
+    harness.emit(&trace_id);
+    harness.schedule.run(&mut harness.world);
+    assert!(harness.delivered().is_empty());
+}
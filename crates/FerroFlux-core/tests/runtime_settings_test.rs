@@ -0,0 +1,85 @@
+use ferroflux_core::api::{ApiCommand, ApiRequest};
+use ferroflux_core::app::AppBuilder;
+use ferroflux_core::resources::AgentConcurrency;
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::store::database::PersistentStore;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_update_settings_without_restart() {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let (mut app, api_tx, ..) = AppBuilder::new()
+        .with_store(store.clone())
+        .with_master_key(vec![7; 32])
+        .with_settings(EngineSettings::default())
+        .build()
+        .await
+        .unwrap();
+
+    let settings = app.world.resource::<RuntimeSettings>().clone();
+    let semaphore = app.world.resource::<AgentConcurrency>().0.clone();
+    let mut changes = settings.subscribe();
+    assert_eq!(semaphore.available_permits(), 50);
+
+    api_tx
+        .send(ApiRequest::system(ApiCommand::UpdateSettings(json!({
+            "concurrency": { "max_agent_requests": 5 },
+            "network": { "ssrf_allowlist": ["*.corp.internal"] }
+        }))))
+        .await
+        .unwrap();
+    app.update();
+    app.update();
+
+    assert!(changes.has_changed().unwrap());
+    let current = changes.borrow_and_update().clone();
+    assert_eq!(current.concurrency.max_agent_requests, 5);
+    assert_eq!(current.network.ssrf_allowlist, vec!["*.corp.internal"]);
+    assert!(current.analytics.enabled);
+    assert_eq!(semaphore.available_permits(), 5);
+
+    // Growing again hands the permits back.
+    api_tx
+        .send(ApiRequest::system(ApiCommand::UpdateSettings(json!({
+            "concurrency": { "max_agent_requests": 8 }
+        }))))
+        .await
+        .unwrap();
+    app.update();
+    app.update();
+    assert_eq!(semaphore.available_permits(), 8);
+
+    // Updates are stored as a merged patch and picked up on the next start.
+    let stored = store.load_engine_settings().await.unwrap();
+    let stored: serde_json::Value = serde_json::from_str(&stored.unwrap()).unwrap();
+    assert_eq!(
+        stored,
+        json!({
+            "concurrency": { "max_agent_requests": 8 },
+            "network": { "ssrf_allowlist": ["*.corp.internal"] }
+        })
+    );
+    let reloaded = EngineSettings::load(Some(&store)).await.unwrap();
+    assert_eq!(reloaded.concurrency.max_agent_requests, 8);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invalid_settings_are_rejected() {
+    let (mut app, api_tx, ..) = AppBuilder::new()
+        .with_master_key(vec![7; 32])
+        .with_settings(EngineSettings::default())
+        .build()
+        .await
+        .unwrap();
+    let settings = app.world.resource::<RuntimeSettings>().clone();
+
+    api_tx
+        .send(ApiRequest::system(ApiCommand::UpdateSettings(json!({
+            "concurrency": { "max_agent_requests": "unlimited" }
+        }))))
+        .await
+        .unwrap();
+    app.update();
+
+    assert_eq!(settings.current().concurrency.max_agent_requests, 50);
+}
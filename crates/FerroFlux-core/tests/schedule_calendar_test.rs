@@ -0,0 +1,109 @@
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ferroflux_core::components::{CronConfig, Frequency, NodeConfig};
+use ferroflux_core::schedule_calendar::{overlapping_runs, upcoming_runs};
+use ferroflux_core::systems::scheduler::NextRun;
+use ferroflux_iam::TenantId;
+
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+}
+
+fn spawn_cron(
+    world: &mut World,
+    workflow: &str,
+    tenant: &str,
+    frequency: Frequency,
+    start_at: DateTime<Utc>,
+) -> Entity {
+    world
+        .spawn((
+            CronConfig {
+                frequency,
+                start_at,
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: format!("{} schedule", workflow),
+                node_type: "Cron".to_string(),
+                workflow_id: Some(workflow.to_string()),
+                tenant_id: Some(TenantId::from(tenant)),
+                version: None,
+            },
+        ))
+        .id()
+}
+
+#[test]
+fn test_upcoming_runs_over_range() {
+    let mut world = World::new();
+    let acme = TenantId::from("acme");
+    spawn_cron(&mut world, "backup", "acme", Frequency::Daily, at(1, 2, 0));
+    let report = spawn_cron(
+        &mut world,
+        "report",
+        "acme",
+        Frequency::Hourly,
+        at(1, 0, 30),
+    );
+    spawn_cron(&mut world, "launch", "acme", Frequency::Once, at(10, 9, 0));
+    spawn_cron(
+        &mut world,
+        "other",
+        "globex",
+        Frequency::Hourly,
+        at(1, 0, 0),
+    );
+    // The scheduler already moved this trigger's next run forward.
+    world.entity_mut(report).insert(NextRun(at(5, 1, 30)));
+
+    let runs = upcoming_runs(&mut world, &acme, at(5, 0, 0), at(5, 3, 0));
+    let listed: Vec<(&str, DateTime<Utc>)> = runs
+        .iter()
+        .map(|r| (r.workflow_id.as_deref().unwrap(), r.fire_at))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("report", at(5, 1, 30)),
+            ("backup", at(5, 2, 0)),
+            ("report", at(5, 2, 30)),
+        ]
+    );
+
+    let launch = upcoming_runs(&mut world, &acme, at(9, 0, 0), at(11, 0, 0));
+    assert_eq!(
+        launch
+            .iter()
+            .filter(|r| r.workflow_id.as_deref() == Some("launch"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_overlapping_runs() {
+    let mut world = World::new();
+    let acme = TenantId::from("acme");
+    spawn_cron(&mut world, "backup", "acme", Frequency::Daily, at(1, 2, 0));
+    spawn_cron(
+        &mut world,
+        "reindex",
+        "acme",
+        Frequency::Daily,
+        at(1, 2, 10),
+    );
+    spawn_cron(&mut world, "digest", "acme", Frequency::Daily, at(1, 8, 0));
+
+    let runs = upcoming_runs(&mut world, &acme, at(2, 0, 0), at(4, 0, 0));
+    assert_eq!(runs.len(), 6);
+    let overlaps = overlapping_runs(&runs, Duration::minutes(15));
+    assert_eq!(overlaps.len(), 2);
+    assert!(overlaps.iter().all(|group| {
+        let names: Vec<_> = group
+            .iter()
+            .map(|r| r.workflow_id.clone().unwrap())
+            .collect();
+        names == ["backup", "reindex"]
+    }));
+}
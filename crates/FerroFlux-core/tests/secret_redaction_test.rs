@@ -0,0 +1,237 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SchemaViolation, SystemEvent, SystemEventBus};
+use ferroflux_core::components::{HttpConfig, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::recorder::{HttpRecorder, REDACTED, RecordedExchange};
+use ferroflux_core::resources::redaction::SecretRedactor;
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{HttpResultChannel, TokioRuntime, WorkDone};
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::io::http_worker;
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SECRET: &str = "sk_live_conn_0123456789";
+/// Stored with the connection but never sent as a header.
+const SIGNING_SECRET: &str = "whsec_9876543210";
+
+#[test]
+fn test_registered_credentials_are_masked_in_logs_and_telemetry() {
+    let redactor = SecretRedactor::new();
+    redactor.register_credentials(&json!({
+        "base_url": "https://api.example.com",
+        "auth_type": "Bearer",
+        "credentials": SECRET,
+        "custom_headers": { "X-Account": "acct_987654" },
+        "pin": "1234",
+    }));
+    redactor.register_header("Bearer tok_abcdefghijkl");
+
+    let mut log = SystemEvent::Log {
+        level: "INFO".to_string(),
+        message: format!("GET https://api.example.com?key={} (acct_987654)", SECRET),
+        trace_id: "t1".to_string(),
+        timestamp: 0,
+    };
+    log.redact(&redactor);
+    let SystemEvent::Log { message, .. } = log else {
+        unreachable!()
+    };
+    // Public fields, scheme words and values too short to be secrets are kept.
+    assert_eq!(
+        message,
+        "GET https://api.example.com?key=[REDACTED] ([REDACTED])"
+    );
+    assert_eq!(
+        redactor.redact("pin 1234, Bearer tok"),
+        "pin 1234, Bearer tok"
+    );
+
+    // Every event sent on the bus is redacted, whichever system sends it.
+    let (tx, mut events) = tokio::sync::broadcast::channel(4);
+    let bus = SystemEventBus::new(tx).with_redactor(redactor.clone());
+    bus.send(SystemEvent::NodeTelemetry {
+        trace_id: "t1".to_string(),
+        node_id: uuid::Uuid::nil(),
+        node_type: "Pipeline".to_string(),
+        execution_ms: 0,
+        success: true,
+        details: json!({
+            "outputs": { "headers": ["Bearer tok_abcdefghijkl"], (SECRET): 1 },
+        }),
+    });
+    let SystemEvent::NodeTelemetry { details, .. } = events.try_recv().unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(
+        details,
+        json!({ "outputs": { "headers": ["Bearer [REDACTED]"], (REDACTED): 1 } })
+    );
+
+    bus.send(SystemEvent::NodeError {
+        trace_id: "t1".to_string(),
+        node_id: uuid::Uuid::nil(),
+        error: format!("connect postgres://app:{}@db:5432 failed", SECRET),
+        timestamp: 0,
+    });
+    let SystemEvent::NodeError { error, .. } = events.try_recv().unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(error, "connect postgres://app:[REDACTED]@db:5432 failed");
+
+    bus.send(SystemEvent::AgentToken {
+        node_id: uuid::Uuid::nil(),
+        trace_id: "t1".to_string(),
+        delta: format!("the key is {}", SECRET),
+    });
+    let SystemEvent::AgentToken { delta, .. } = events.try_recv().unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(delta, "the key is [REDACTED]");
+
+    bus.send(SystemEvent::ContractViolation {
+        trace_id: "t1".to_string(),
+        node_id: uuid::Uuid::nil(),
+        violations: vec![SchemaViolation {
+            path: "/token".to_string(),
+            schema_path: "/properties/token/type".to_string(),
+            message: format!("\"{}\" is not of type \"integer\"", SECRET),
+        }],
+        timestamp: 0,
+    });
+    let SystemEvent::ContractViolation { violations, .. } = events.try_recv().unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(
+        violations[0].message,
+        "\"[REDACTED]\" is not of type \"integer\""
+    );
+}
+
+/// Runs one POST through the HTTP worker using a stored connection and returns what was
+/// recorded and the events sent on the bus.
+async fn call(server: &MockServer, redact_bodies: bool) -> (RecordedExchange, Vec<SystemEvent>) {
+    unsafe {
+        std::env::set_var("FERROFLUX_ALLOW_INTERNAL_IPS", "true");
+    }
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let secrets = DatabaseSecretStore::new(db, vec![3; 32]);
+    let tenant = TenantId::from("acme");
+    secrets
+        .keyring()
+        .save_connection(
+            &tenant,
+            "billing",
+            "Billing",
+            "http",
+            &json!({
+                "base_url": server.uri(),
+                "auth_type": "Bearer",
+                "credentials": SECRET,
+                "signing_secret": SIGNING_SECRET,
+            }),
+            "active",
+        )
+        .await
+        .unwrap();
+
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx).with_redactor(secrets.redactor().clone()));
+    world.insert_resource(HttpResultChannel::default());
+    world.insert_resource(secrets);
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    let recorder = HttpRecorder::new();
+    world.insert_resource(recorder.clone());
+    let mut settings = EngineSettings::from_env();
+    settings.capture.redact_request_bodies = redact_bodies;
+    world.insert_resource(RuntimeSettings::new(settings));
+
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    // A flow that copies a credential into the payload (e.g. an "echo my config" step).
+    let payload = json!({ "note": format!("sign with {}", SIGNING_SECRET) });
+    inbox
+        .queue
+        .push_back(store.check_in(payload.to_string().as_bytes()).unwrap());
+    let entity = world
+        .spawn((
+            HttpConfig {
+                url: format!("/v1/echo?ref={}", SECRET),
+                method: "POST".to_string(),
+                result_key: None,
+                connection_slug: Some("billing".to_string()),
+                pagination: None,
+                full_response: false,
+                timeout_ms: None,
+                max_redirects: None,
+                proxy: None,
+                no_proxy: None,
+            },
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Billing".to_string(),
+                node_type: "Http".to_string(),
+                workflow_id: None,
+                tenant_id: Some(tenant),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(http_worker);
+    for _ in 0..100 {
+        schedule.run(&mut world);
+        if !world.get::<Outbox>(entity).unwrap().queue.is_empty() {
+            let seen = std::iter::from_fn(|| events.try_recv().ok()).collect();
+            return (recorder.fixtures().exchanges.remove(0), seen);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Http worker timed out");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_worker_redacts_resolved_connection_secrets() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/echo"))
+        .and(header(
+            "authorization",
+            format!("Bearer {}", SECRET).as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let (exchange, events) = call(&server, true).await;
+    let body: Value = serde_json::from_str(exchange.request_body.as_deref().unwrap()).unwrap();
+    assert_eq!(body["note"], format!("sign with {}", REDACTED));
+    let telemetry = events
+        .iter()
+        .find_map(|event| match event {
+            SystemEvent::NodeTelemetry { details, .. } => Some(details.clone()),
+            _ => None,
+        })
+        .unwrap();
+    let url = telemetry["url"].as_str().unwrap();
+    assert!(
+        url.ends_with(&format!("/v1/echo?ref={}", REDACTED)),
+        "{url}"
+    );
+    assert_eq!(telemetry["status"], 200);
+
+    // Recorded bodies are left alone when redaction is switched off.
+    let (exchange, _) = call(&server, false).await;
+    assert!(exchange.request_body.unwrap().contains(SIGNING_SECRET));
+}
@@ -0,0 +1,166 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::simulation::handle_shadow_run;
+use ferroflux_core::components::shadow::MockConfig;
+use ferroflux_core::components::{Edge, HttpConfig, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::{GraphTopology, ShadowRuns, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::shadow::shadow_worker;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use ferroflux_iam::{Principal, TenantId};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn node(node_type: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: node_type.to_string(),
+        node_type: node_type.to_string(),
+        workflow_id: Some("signup".to_string()),
+        tenant_id: Some(TenantId::from("acme")),
+        version: None,
+    }
+}
+
+struct Harness {
+    world: World,
+    schedule: Schedule,
+    http: Entity,
+    sink: Entity,
+}
+
+impl Harness {
+    /// Webhook -> Http -> Sink, with no HTTP worker: only the interceptor can move tickets on.
+    fn new() -> Self {
+        let mut world = World::new();
+        let (tx, _) = tokio::sync::broadcast::channel(100);
+        world.insert_resource(SystemEventBus::new(tx));
+        world.insert_resource(GraphTopology::default());
+        world.insert_resource(WorkDone::default());
+        world.insert_resource(ShadowRuns::default());
+        world.insert_resource(BlobStore::default());
+
+        let webhook = world.spawn((node("Webhook"), Outbox::default())).id();
+        let http = world
+            .spawn((
+                node("Http"),
+                HttpConfig {
+                    url: "https://api.example.com/signup".to_string(),
+                    method: "POST".to_string(),
+                    result_key: None,
+                    connection_slug: None,
+                    pagination: None,
+                    full_response: false,
+                    timeout_ms: None,
+                    max_redirects: None,
+                    proxy: None,
+                    no_proxy: None,
+                },
+                Inbox::default(),
+                Outbox::default(),
+            ))
+            .id();
+        let sink = world.spawn((node("Sink"), Inbox::default())).id();
+        for (source, target) in [(webhook, http), (http, sink)] {
+            world.spawn(Edge {
+                source,
+                source_handle: None,
+                target,
+                target_handle: None,
+            });
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_graph_topology, transport_worker, shadow_worker).chain());
+        Self {
+            world,
+            schedule,
+            http,
+            sink,
+        }
+    }
+
+    fn run(&mut self, mocks: HashMap<String, MockConfig>) {
+        handle_shadow_run(
+            &mut self.world,
+            &Principal::system(),
+            TenantId::from("acme"),
+            "signup".to_string(),
+            json!({ "email": "ada@example.com" }),
+            mocks,
+        )
+        .unwrap();
+        for _ in 0..3 {
+            self.schedule.run(&mut self.world);
+        }
+    }
+
+    fn received(&self) -> Vec<Value> {
+        let store = self.world.resource::<BlobStore>();
+        self.world
+            .get::<Inbox>(self.sink)
+            .unwrap()
+            .queue
+            .iter()
+            .map(|ticket| serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap())
+            .collect()
+    }
+}
+
+#[test]
+fn test_shadow_run_mocks_side_effecting_nodes() {
+    let mut harness = Harness::new();
+    let mut events = harness.world.resource::<SystemEventBus>().subscribe();
+
+    let mocks = HashMap::from([(
+        "Http".to_string(),
+        MockConfig {
+            return_value: json!({ "status": 201 }),
+            delay_ms: 40,
+        },
+    )]);
+    harness.run(mocks);
+    assert_eq!(harness.received(), [json!({ "status": 201 })]);
+
+    let trace_id = match events.try_recv().unwrap() {
+        SystemEvent::ShadowRunStarted { trace_id, .. } => trace_id,
+        other => panic!("unexpected event {:?}", other),
+    };
+    let telemetry = std::iter::from_fn(|| events.try_recv().ok())
+        .find_map(|event| match event {
+            SystemEvent::NodeTelemetry {
+                trace_id,
+                node_type,
+                execution_ms,
+                details,
+                ..
+            } => Some((trace_id, node_type, execution_ms, details)),
+            _ => None,
+        })
+        .expect("shadowed node reports telemetry");
+    assert_eq!(telemetry.0, trace_id);
+    assert_eq!(telemetry.1, "Http");
+    assert_eq!(telemetry.2, 40);
+    assert_eq!(telemetry.3["shadow"], true);
+}
+
+#[test]
+fn test_shadow_run_passes_input_through_without_mock() {
+    let mut harness = Harness::new();
+
+    // A regular ticket waiting at the same node is left for the HTTP worker.
+    let store = harness.world.resource::<BlobStore>().clone();
+    let live = store.check_in(b"{}").unwrap();
+    harness
+        .world
+        .get_mut::<Inbox>(harness.http)
+        .unwrap()
+        .queue
+        .push_back(live.clone());
+
+    harness.run(HashMap::new());
+    assert_eq!(harness.received(), [json!({ "email": "ada@example.com" })]);
+    let waiting = &harness.world.get::<Inbox>(harness.http).unwrap().queue;
+    assert_eq!(waiting.len(), 1);
+    assert_eq!(waiting[0].id, live.id);
+}
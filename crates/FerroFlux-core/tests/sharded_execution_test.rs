@@ -0,0 +1,295 @@
+use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::ExecutorKind;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::control::CheckpointConfig;
+use ferroflux_core::components::manipulation::TransformConfig;
+use ferroflux_core::components::{ContractConfig, ContractState, SchemaDraft};
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::settings::{ExecutionMode, ExecutionSettings, ShardKey};
+use ferroflux_core::resources::sharding::Sharding;
+use ferroflux_core::resources::{GraphTopology, TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::control::checkpoint_worker;
+use ferroflux_core::systems::logic::contract_worker;
+use ferroflux_core::systems::manipulation::transform_worker;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Sets up a four-thread compute pool. Every test calls it first, since whichever test
+/// touches the pool first decides its size.
+fn sharding(key: ShardKey) -> Sharding {
+    Sharding::new(&ExecutionSettings {
+        mode: ExecutionMode::Sharded,
+        threads: 4,
+        shard_by: key,
+    })
+}
+
+fn node(workflow: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: "Reshape".to_string(),
+        node_type: "Transform".to_string(),
+        workflow_id: Some(workflow.to_string()),
+        tenant_id: None,
+        version: None,
+    }
+}
+
+/// Runs one tick over 12 Transform nodes spread across 6 workflows, each fed 5 tickets, and
+/// returns the trace ids each node emitted, in order.
+fn run(sharding: Option<Sharding>) -> Vec<Vec<String>> {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(1000);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    if let Some(sharding) = sharding {
+        world.insert_resource(sharding);
+    }
+
+    let nodes: Vec<Entity> = (0..12)
+        .map(|i| {
+            let mut inbox = Inbox::default();
+            for n in 0..5 {
+                let metadata = HashMap::from([("trace_id".to_string(), format!("{}-{}", i, n))]);
+                inbox.queue.push_back(
+                    store
+                        .check_in_with_metadata(br#"{"n": 1}"#, metadata)
+                        .unwrap(),
+                );
+            }
+            world
+                .spawn((
+                    TransformConfig {
+                        expression: "n".to_string(),
+                        result_key: Some("out".to_string()),
+                    },
+                    node(&format!("wf-{}", i % 6)),
+                    inbox,
+                    Outbox::default(),
+                ))
+                .id()
+        })
+        .collect();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(transform_worker);
+    schedule.run(&mut world);
+
+    nodes
+        .iter()
+        .map(|entity| {
+            assert!(world.get::<Inbox>(*entity).unwrap().queue.is_empty());
+            world
+                .get::<Outbox>(*entity)
+                .unwrap()
+                .queue
+                .iter()
+                .map(|(_, ticket)| ticket.metadata["trace_id"].clone())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sharded_transform_matches_sequential() {
+    let sharding = sharding(ShardKey::Workflow);
+    // Only a multi-threaded pool (the `parallel` feature) gives more than one shard.
+    assert_eq!(sharding.shards, 4);
+    let sequential = run(None);
+    let sharded = run(Some(sharding));
+    assert_eq!(sequential, sharded);
+    assert_eq!(sharded[7], ["7-0", "7-1", "7-2", "7-3", "7-4"]);
+}
+
+/// Runs one tick over 12 Contract nodes across 6 workflows, each fed a conforming and a
+/// non-conforming ticket, and returns the port and trace id of everything each node emitted.
+fn run_contracts(sharding: Option<Sharding>) -> Vec<Vec<(Option<String>, String)>> {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(1000);
+    world.insert_resource(SystemEventBus::new(tx));
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+    if let Some(sharding) = sharding {
+        world.insert_resource(sharding);
+    }
+
+    let nodes: Vec<Entity> = (0..12)
+        .map(|i| {
+            let mut inbox = Inbox::default();
+            for (n, payload) in [&br#"{"n": 1}"#[..], br#"{"n": "one"}"#].iter().enumerate() {
+                let metadata = HashMap::from([("trace_id".to_string(), format!("{}-{}", i, n))]);
+                inbox
+                    .queue
+                    .push_back(store.check_in_with_metadata(payload, metadata).unwrap());
+            }
+            world
+                .spawn((
+                    ContractConfig {
+                        schema: serde_json::json!({ "properties": { "n": { "type": "integer" } } }),
+                        draft: SchemaDraft::Auto,
+                        target_field: None,
+                        max_violations: 20,
+                    },
+                    ContractState::default(),
+                    node(&format!("wf-{}", i % 6)),
+                    inbox,
+                    Outbox::default(),
+                ))
+                .id()
+        })
+        .collect();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(contract_worker);
+    schedule.run(&mut world);
+
+    nodes
+        .iter()
+        .map(|entity| {
+            world
+                .get::<Outbox>(*entity)
+                .unwrap()
+                .queue
+                .iter()
+                .map(|(port, ticket)| (port.clone(), ticket.metadata["trace_id"].clone()))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sharded_contract_matches_sequential() {
+    let sharding = sharding(ShardKey::Workflow);
+    let sequential = run_contracts(None);
+    let sharded = run_contracts(Some(sharding));
+    assert_eq!(sequential, sharded);
+    assert_eq!(
+        sharded[3],
+        [
+            (None, "3-0".to_string()),
+            (Some("error".to_string()), "3-1".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_shard_follows_key() {
+    sharding(ShardKey::Workflow);
+    let sharding = Sharding {
+        shards: 8,
+        key: ShardKey::Workflow,
+    };
+    let a = node("billing");
+    let b = node("billing");
+    assert_eq!(sharding.shard_of(&a), sharding.shard_of(&b));
+    assert!(sharding.shard_of(&a) < 8);
+
+    let by_tenant = Sharding {
+        shards: 8,
+        key: ShardKey::Tenant,
+    };
+    let mut c = node("billing");
+    let mut d = node("reports");
+    c.tenant_id = Some("acme".into());
+    d.tenant_id = Some("acme".into());
+    assert_eq!(by_tenant.shard_of(&c), by_tenant.shard_of(&d));
+}
+
+#[test]
+fn test_work_done_under_parallel_executor() {
+    sharding(ShardKey::Workflow);
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(GraphTopology::default());
+    world.insert_resource(WorkDone::default());
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+
+    let source = world.spawn((node("wf"), Outbox::default())).id();
+    let target = world
+        .spawn((
+            node("wf"),
+            TransformConfig {
+                expression: "n".to_string(),
+                result_key: None,
+            },
+            Inbox::default(),
+            Outbox::default(),
+        ))
+        .id();
+    world.spawn(Edge {
+        source,
+        source_handle: None,
+        target,
+        target_handle: None,
+    });
+
+    let mut schedule = Schedule::default();
+    schedule.set_executor_kind(ExecutorKind::MultiThreaded);
+    schedule.add_systems((update_graph_topology, transport_worker, transform_worker).chain());
+
+    world
+        .get_mut::<Outbox>(source)
+        .unwrap()
+        .queue
+        .push_back((None, store.check_in(br#"{"n": 1}"#).unwrap()));
+    schedule.run(&mut world);
+    assert!(world.resource::<WorkDone>().is_set());
+    assert_eq!(world.get::<Outbox>(target).unwrap().queue.len(), 1);
+
+    world.resource_mut::<WorkDone>().reset();
+    schedule.run(&mut world);
+    assert!(!world.resource::<WorkDone>().is_set());
+}
+
+#[test]
+fn test_checkpoint_spawns_through_the_runtime_handle() {
+    sharding(ShardKey::Workflow);
+    // The schedule runs outside any Tokio context, as it does on compute-pool threads.
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut world = World::new();
+    let (tx, mut events) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(runtime.handle().clone()));
+    world.insert_resource(
+        runtime
+            .block_on(PersistentStore::new("sqlite::memory:"))
+            .unwrap(),
+    );
+    let store = BlobStore::default();
+    world.insert_resource(store.clone());
+
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(store.check_in(b"freeze_me").unwrap());
+    let mut checkpoint = node("wf");
+    checkpoint.tenant_id = Some("acme".into());
+    world.spawn((
+        checkpoint,
+        CheckpointConfig {
+            timeout_seconds: None,
+        },
+        inbox,
+    ));
+
+    let mut schedule = Schedule::default();
+    schedule.set_executor_kind(ExecutorKind::MultiThreaded);
+    schedule.add_systems(checkpoint_worker);
+    schedule.run(&mut world);
+
+    let created = runtime.block_on(async {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(SystemEvent::CheckpointCreated { token, .. }) = events.recv().await {
+                    return token;
+                }
+            }
+        })
+        .await
+    });
+    assert!(created.is_ok(), "checkpoint was not saved");
+}
@@ -0,0 +1,128 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::control::{CounterConfig, CounterOperation};
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::TokioRuntime;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::control::{apply_counter, counter_worker};
+use ferroflux_iam::TenantId;
+
+fn counter(operation: CounterOperation, limit: Option<i64>) -> CounterConfig {
+    CounterConfig {
+        name: "emails".to_string(),
+        operation,
+        amount: 1,
+        window_seconds: Some(86_400),
+        limit,
+        result_key: None,
+    }
+}
+
+#[tokio::test]
+async fn test_counter_limits_are_tenant_scoped() {
+    let store = PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init DB");
+    let acme = TenantId::from("acme");
+    let globex = TenantId::from("globex");
+    let increment = counter(CounterOperation::Increment, Some(2));
+
+    assert_eq!(
+        apply_counter(&store, &acme, &increment, "emails")
+            .await
+            .unwrap(),
+        (1, false)
+    );
+    assert_eq!(
+        apply_counter(&store, &acme, &increment, "emails")
+            .await
+            .unwrap(),
+        (2, false)
+    );
+    assert_eq!(
+        apply_counter(&store, &acme, &increment, "emails")
+            .await
+            .unwrap(),
+        (3, true)
+    );
+    assert_eq!(
+        apply_counter(&store, &globex, &increment, "emails")
+            .await
+            .unwrap(),
+        (1, false)
+    );
+
+    let get = counter(CounterOperation::Get, None);
+    assert_eq!(
+        apply_counter(&store, &acme, &get, "emails").await.unwrap(),
+        (3, false)
+    );
+
+    let reset = counter(CounterOperation::Reset, None);
+    apply_counter(&store, &acme, &reset, "emails")
+        .await
+        .unwrap();
+    assert_eq!(store.get_counter(&acme, "emails").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_counter_window_expiry_restarts_count() {
+    let store = PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init DB");
+    let tenant = TenantId::from("acme");
+
+    store
+        .increment_counter(&tenant, "burst", 5, Some(0))
+        .await
+        .unwrap();
+    // A zero-second window has always elapsed: the next increment starts a new window.
+    assert_eq!(store.get_counter(&tenant, "burst").await.unwrap(), 0);
+    assert_eq!(
+        store
+            .increment_counter(&tenant, "burst", 2, Some(0))
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unclaimable_tickets_go_to_the_error_port() {
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(
+        PersistentStore::new("sqlite::memory:")
+            .await
+            .expect("Failed to init DB"),
+    );
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+
+    // A ticket from another store has no payload here.
+    let ticket = BlobStore::default().check_in(b"{}").unwrap();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(ticket);
+    let node = world
+        .spawn((
+            counter(CounterOperation::Increment, None),
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Counter".to_string(),
+                node_type: "Counter".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("acme")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(counter_worker);
+    schedule.run(&mut world);
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.len(), 1);
+    assert_eq!(outbox.queue[0].0.as_deref(), Some("error"));
+}
@@ -0,0 +1,30 @@
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_iam::TenantId;
+
+#[tokio::test]
+async fn test_snippets_are_tenant_scoped() {
+    let store = PersistentStore::new("sqlite::memory:")
+        .await
+        .expect("Failed to init DB");
+    let acme = TenantId::from("acme");
+    let globex = TenantId::from("globex");
+
+    let id = store
+        .save_snippet(&acme, "Retry wrapper", r#"{"nodes":[],"connections":[]}"#)
+        .await
+        .unwrap();
+
+    let listed = store.list_snippets(&acme).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].0, id);
+    assert_eq!(listed[0].1, "Retry wrapper");
+
+    assert!(store.get_snippet(&acme, &id).await.unwrap().is_some());
+    assert!(store.get_snippet(&globex, &id).await.unwrap().is_none());
+    assert!(store.list_snippets(&globex).await.unwrap().is_empty());
+
+    store.delete_snippet(&globex, &id).await.unwrap();
+    assert!(store.get_snippet(&acme, &id).await.unwrap().is_some());
+    store.delete_snippet(&acme, &id).await.unwrap();
+    assert!(store.get_snippet(&acme, &id).await.unwrap().is_none());
+}
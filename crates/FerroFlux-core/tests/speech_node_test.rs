@@ -0,0 +1,239 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::components::io::{SpeechConfig, SpeechDirection};
+use ferroflux_core::integrations::{IntegrationDef, IntegrationRegistry};
+use ferroflux_core::resources::{GlobalHttpClient, SpeechResultChannel, TokioRuntime, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::io::speech_worker;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// A Whisper-style `speech_to_text` and an ElevenLabs-style `text_to_speech` provider.
+fn registry(base_url: &str) -> IntegrationRegistry {
+    let whisper: IntegrationDef = serde_yaml::from_str(&format!(
+        r#"
+name: whisper
+base_url: "{base_url}/v1"
+auth: {{ type: bearer }}
+verify_params: {{ api_key: SPEECH_TEST_WHISPER_KEY }}
+actions:
+  speech_to_text:
+    implementation:
+      type: http
+      config: {{ method: POST, path: /audio/transcriptions }}
+"#
+    ))
+    .unwrap();
+    let voices: IntegrationDef = serde_yaml::from_str(&format!(
+        r#"
+name: voices
+base_url: "{base_url}/v1"
+auth: {{ type: api_key, in_header: true, key_name: xi-api-key }}
+verify_params: {{ api_key: SPEECH_TEST_VOICES_KEY }}
+actions:
+  text_to_speech:
+    implementation:
+      type: http
+      config:
+        method: POST
+        path: "/text-to-speech/{{{{voice}}}}"
+        headers: {{ Content-Type: application/json }}
+        body_template: '{{ "text": {{{{json text}}}}, "model_id": {{{{json model}}}} }}'
+"#
+    ))
+    .unwrap();
+    IntegrationRegistry {
+        definitions: HashMap::from([
+            ("whisper".to_string(), whisper),
+            ("voices".to_string(), voices),
+        ]),
+        ..Default::default()
+    }
+}
+
+fn config(direction: SpeechDirection, provider: &str) -> SpeechConfig {
+    SpeechConfig {
+        direction,
+        provider: provider.to_string(),
+        model: None,
+        voice: None,
+        language: None,
+        input_field: None,
+        connection_slug: None,
+        timeout_ms: 2_000,
+        result_key: "speech".to_string(),
+    }
+}
+
+async fn run(
+    server: &MockServer,
+    store: &BlobStore,
+    config: SpeechConfig,
+    payload: Value,
+) -> (Option<String>, Value) {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(SpeechResultChannel::default());
+    world.insert_resource(registry(&server.uri()));
+    world.insert_resource(store.clone());
+
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(
+        store
+            .check_in(&serde_json::to_vec(&payload).unwrap())
+            .unwrap(),
+    );
+    let node = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Voice Command".to_string(),
+                node_type: "Speech".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+    let mut schedule = Schedule::default();
+    schedule.add_systems(speech_worker);
+
+    for _ in 0..100 {
+        schedule.run(&mut world);
+        if let Some((port, ticket)) = world.get_mut::<Outbox>(node).unwrap().queue.pop_front() {
+            let payload = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap();
+            return (port, payload);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Speech worker timed out");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_speech_to_text_uploads_audio_blob() {
+    unsafe {
+        std::env::set_var("SPEECH_TEST_WHISPER_KEY", "sk-whisper");
+    }
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/audio/transcriptions"))
+        .and(header("authorization", "Bearer sk-whisper"))
+        .and(body_string_contains("filename=\"audio.wav\""))
+        .and(body_string_contains("whisper-1"))
+        .and(body_string_contains("RIFF fake wav"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "text": "lights on" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let store = BlobStore::default();
+    let audio = store
+        .check_in_with_metadata(
+            b"RIFF fake wav",
+            HashMap::from([("content_type".to_string(), "audio/wav".to_string())]),
+        )
+        .unwrap();
+    let mut stt = config(SpeechDirection::SpeechToText, "whisper");
+    stt.model = Some("whisper-1".to_string());
+
+    let (port, output) = run(
+        &server,
+        &store,
+        stt,
+        json!({ "audio": { "blob_id": audio.id }, "room": "kitchen" }),
+    )
+    .await;
+    assert_eq!(port, None);
+    assert_eq!(output["speech"]["text"], "lights on");
+    assert_eq!(output["room"], "kitchen");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_text_to_speech_stores_audio() {
+    unsafe {
+        std::env::set_var("SPEECH_TEST_VOICES_KEY", "xi-secret");
+    }
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/text-to-speech/rachel"))
+        .and(header("xi-api-key", "xi-secret"))
+        .and(body_partial_json(
+            json!({ "text": "Say \"hi\"", "model_id": "v2" }),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "audio/mpeg")
+                .set_body_bytes(b"ID3 fake mp3".to_vec()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let store = BlobStore::default();
+    let mut tts = config(SpeechDirection::TextToSpeech, "voices");
+    tts.voice = Some("rachel".to_string());
+    tts.model = Some("v2".to_string());
+
+    let (port, output) = run(&server, &store, tts, json!({ "text": "Say \"hi\"" })).await;
+    assert_eq!(port, None);
+    assert_eq!(output["speech"]["content_type"], "audio/mpeg");
+    assert_eq!(output["speech"]["bytes"], 12);
+    let blob = store
+        .recover_ticket(
+            &output["speech"]["blob_id"]
+                .as_str()
+                .unwrap()
+                .parse()
+                .unwrap(),
+        )
+        .expect("audio stored");
+    assert_eq!(store.claim(&blob).unwrap(), b"ID3 fake mp3");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_speech_failures_route_to_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("bad key"))
+        .mount(&server)
+        .await;
+    let store = BlobStore::default();
+
+    // Nothing to say.
+    let (port, _) = run(
+        &server,
+        &store,
+        config(SpeechDirection::TextToSpeech, "voices"),
+        json!({ "text": "  " }),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+
+    // The provider has no such action.
+    let (port, _) = run(
+        &server,
+        &store,
+        config(SpeechDirection::SpeechToText, "voices"),
+        json!({ "audio": uuid::Uuid::new_v4().to_string() }),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+
+    // Rejected upstream.
+    let mut tts = config(SpeechDirection::TextToSpeech, "voices");
+    tts.voice = Some("rachel".to_string());
+    let (port, output) = run(&server, &store, tts, json!({ "text": "hello" })).await;
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(output["text"], "hello");
+}
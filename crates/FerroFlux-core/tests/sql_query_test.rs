@@ -0,0 +1,214 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::connectors::{SqlQueryConfig, SqlQueryState};
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::settings::{EngineSettings, RuntimeSettings};
+use ferroflux_core::resources::{SqlResultChannel, TokioRuntime, WorkDone};
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::sql_query_worker;
+use ferroflux_iam::TenantId;
+use ferroflux_security::encryption::{encrypt, get_or_create_master_key};
+use serde_json::{Value, json};
+use tokio::runtime::Runtime;
+
+/// An empty database file in a fresh tenant directory, with SQLite allowed there.
+fn sqlite_settings() -> EngineSettings {
+    let dir = std::env::temp_dir().join(format!("sql_tenant_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::File::create(dir.join("analytics.db")).unwrap();
+
+    let mut settings = EngineSettings::default();
+    settings.files.allow_sqlite = true;
+    settings.files.tenant_roots.insert(
+        "default_tenant".to_string(),
+        dir.to_string_lossy().into_owned(),
+    );
+    settings
+}
+
+fn setup_world(rt: &Runtime, connection: Value) -> World {
+    setup_world_with(rt, connection, sqlite_settings())
+}
+
+fn setup_world_with(rt: &Runtime, connection: Value, settings: EngineSettings) -> World {
+    let mut world = World::new();
+    world.insert_resource(RuntimeSettings::new(settings));
+    world.insert_resource(BlobStore::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(rt.handle().clone()));
+    world.insert_resource(SqlResultChannel::default());
+    world.insert_resource(WorkDone::default());
+
+    let secret_store = rt.block_on(async {
+        let store = PersistentStore::new("sqlite::memory:")
+            .await
+            .expect("Failed to init DB");
+        let master_key = get_or_create_master_key().expect("Failed to get master key");
+        let (ciphertext, nonce) = encrypt(connection.to_string().as_bytes(), &master_key).unwrap();
+        store
+            .save_connection(
+                &TenantId::from("default_tenant"),
+                "analytics-db",
+                "Analytics DB",
+                "sql",
+                &ciphertext,
+                &nonce,
+                "active",
+            )
+            .await
+            .unwrap();
+        DatabaseSecretStore::new(store, master_key)
+    });
+    world.insert_resource(secret_store);
+    world
+}
+
+/// Ticks the worker until the query running in the background has answered.
+fn run_query(world: &mut World, node: Entity) {
+    let mut schedule = Schedule::default();
+    schedule.add_systems(sql_query_worker);
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while world.get::<Outbox>(node).unwrap().queue.is_empty() {
+        assert!(std::time::Instant::now() < deadline, "query never answered");
+        schedule.run(world);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
+
+fn spawn_query(world: &mut World, query: &str, params: &[&str], payload: Value) -> Entity {
+    let store = world.resource::<BlobStore>().clone();
+    let mut inbox = Inbox::default();
+    inbox
+        .queue
+        .push_back(store.check_in(payload.to_string().as_bytes()).unwrap());
+
+    world
+        .spawn((
+            SqlQueryConfig {
+                connection_slug: "analytics-db".to_string(),
+                query: query.to_string(),
+                params: params.iter().map(|p| p.to_string()).collect(),
+                max_rows: 1000,
+                timeout_ms: 30_000,
+            },
+            SqlQueryState::default(),
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Query".to_string(),
+                node_type: "SqlQuery".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("default_tenant")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id()
+}
+
+#[test]
+fn test_sql_query_binds_payload_params() {
+    let rt = Runtime::new().unwrap();
+    let mut world = setup_world(&rt, json!({ "url": "sqlite:analytics.db" }));
+    let node = spawn_query(
+        &mut world,
+        "SELECT ? AS name, ? + 1 AS next, ? AS missing",
+        &["user.name", "count", "does.not.exist"],
+        json!({ "user": { "name": "ada" }, "count": 41 }),
+    );
+
+    run_query(&mut world, node);
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.len(), 1);
+    let (port, ticket) = outbox.queue.front().unwrap();
+    assert_eq!(*port, None);
+
+    let store = world.resource::<BlobStore>();
+    let rows: Value = serde_json::from_slice(&store.claim(ticket).unwrap()).unwrap();
+    assert_eq!(
+        rows,
+        json!([{ "name": "ada", "next": 42, "missing": null }])
+    );
+}
+
+#[test]
+fn test_sql_query_failure_routes_to_error_port() {
+    let rt = Runtime::new().unwrap();
+    let mut world = setup_world(&rt, json!({ "url": "sqlite:analytics.db" }));
+    let node = spawn_query(&mut world, "SELECT * FROM missing_table", &[], json!({}));
+
+    run_query(&mut world, node);
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.len(), 1);
+    assert_eq!(outbox.queue.front().unwrap().0.as_deref(), Some("error"));
+}
+
+#[test]
+fn test_sql_query_stops_at_max_rows() {
+    let rt = Runtime::new().unwrap();
+    let mut world = setup_world(&rt, json!({ "url": "sqlite:analytics.db" }));
+    let node = spawn_query(
+        &mut world,
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n LIMIT 50) SELECT i FROM n",
+        &[],
+        json!({}),
+    );
+    world.get_mut::<SqlQueryConfig>(node).unwrap().max_rows = 3;
+
+    run_query(&mut world, node);
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    let store = world.resource::<BlobStore>();
+    let rows: Value = serde_json::from_slice(&store.claim(&outbox.queue[0].1).unwrap()).unwrap();
+    assert_eq!(rows, json!([{ "i": 1 }, { "i": 2 }, { "i": 3 }]));
+}
+
+#[test]
+fn test_sql_query_refuses_sqlite_outside_the_tenant_directory() {
+    let rt = Runtime::new().unwrap();
+    let disabled = setup_world_with(
+        &rt,
+        json!({ "url": "sqlite:analytics.db" }),
+        EngineSettings::default(),
+    );
+    let mut cases = vec![disabled];
+    for url in [
+        "sqlite::memory:",
+        "sqlite:analytics.db?mode=memory&cache=shared",
+        "sqlite:///etc/ferroflux.db",
+        "sqlite:../other_tenant/analytics.db",
+    ] {
+        cases.push(setup_world(&rt, json!({ "url": url })));
+    }
+
+    for mut world in cases {
+        let node = spawn_query(&mut world, "SELECT 1 AS one", &[], json!({}));
+        run_query(&mut world, node);
+
+        let outbox = world.get::<Outbox>(node).unwrap();
+        assert_eq!(outbox.queue.front().unwrap().0.as_deref(), Some("error"));
+    }
+}
+
+#[test]
+fn test_sql_query_times_out() {
+    let rt = Runtime::new().unwrap();
+    let mut world = setup_world(&rt, json!({ "url": "sqlite:analytics.db" }));
+    let node = spawn_query(
+        &mut world,
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n) SELECT max(i) FROM n",
+        &[],
+        json!({}),
+    );
+    world.get_mut::<SqlQueryConfig>(node).unwrap().timeout_ms = 50;
+
+    run_query(&mut world, node);
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.front().unwrap().0.as_deref(), Some("error"));
+}
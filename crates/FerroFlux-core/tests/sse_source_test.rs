@@ -0,0 +1,134 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::connectors::{SseConfig, SseState};
+use ferroflux_core::components::core::{NodeConfig, Outbox};
+use ferroflux_core::resources::{GlobalHttpClient, TokioRuntime};
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::sse::SseParser;
+use ferroflux_core::systems::connectors::sse_worker;
+use serde_json::Value;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn test_parser_handles_split_chunks_and_fields() {
+    let mut parser = SseParser::default();
+    assert!(
+        parser
+            .feed(b": keep-alive\nretry: 250\nevent: delta\nda")
+            .is_empty()
+    );
+    let events = parser.feed(b"ta: {\"a\":1}\r\nid: 7\r\n\r\ndata: line 1\ndata: line 2\n\n");
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event, "delta");
+    assert_eq!(events[0].data, r#"{"a":1}"#);
+    assert_eq!(events[0].id.as_deref(), Some("7"));
+    assert_eq!(events[1].event, "message");
+    assert_eq!(events[1].data, "line 1\nline 2");
+    assert_eq!(parser.retry(), Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn test_sse_reconnects_with_last_event_id() {
+    unsafe {
+        std::env::set_var("FERROFLUX_ALLOW_INTERNAL_IPS", "true");
+    }
+    let rt = Runtime::new().unwrap();
+    let server = rt.block_on(async {
+        let server = MockServer::start().await;
+        // Resumed stream: only served when the client reports the last seen id.
+        Mock::given(method("GET"))
+            .and(path("/events"))
+            .and(header("last-event-id", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string("id: 3\ndata: {\"n\":3}\n\n"),
+            )
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/events"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(
+                        "retry: 10\nid: 1\ndata: {\"n\":1}\n\nid: 2\nevent: ping\ndata: {}\n\n",
+                    ),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(GlobalHttpClient::default());
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(rt.handle().clone()));
+    let store = rt
+        .block_on(PersistentStore::new("sqlite::memory:"))
+        .expect("Failed to init DB");
+    world.insert_resource(DatabaseSecretStore::new(store, vec![0; 32]));
+
+    let node = world
+        .spawn((
+            SseConfig {
+                url: format!("{}/events", server.uri()),
+                // `ping` events still advance the resume point but are not emitted.
+                events: vec!["message".to_string()],
+                max_backoff_seconds: 1,
+                connection_slug: None,
+            },
+            SseState::default(),
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Stream".to_string(),
+                node_type: "SSE".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(sse_worker);
+    for _ in 0..100 {
+        schedule.run(&mut world);
+        if world.get::<Outbox>(node).unwrap().queue.len() >= 2 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let store = world.resource::<BlobStore>().clone();
+    let payloads: Vec<Value> = world
+        .get::<Outbox>(node)
+        .unwrap()
+        .queue
+        .iter()
+        .map(|(_, t)| serde_json::from_slice(&store.claim(t).unwrap()).unwrap())
+        .collect();
+    assert_eq!(payloads.len(), 2, "payloads: {:?}", payloads);
+    assert_eq!(payloads[0]["data"]["n"], 1);
+    assert_eq!(payloads[1]["data"]["n"], 3);
+    assert_eq!(payloads[1]["id"], "3");
+    assert_eq!(
+        world
+            .get::<SseState>(node)
+            .unwrap()
+            .last_event_id
+            .as_deref(),
+        Some("3")
+    );
+}
@@ -14,7 +14,7 @@ async fn setup_world_async() -> World {
     world.insert_resource(blob_store);
 
     let (tx, _) = broadcast::channel(100); // Increased buffer
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
 
     world
 }
@@ -26,7 +26,7 @@ async fn test_stats_zscore_outlier() {
     schedule.add_systems(stats_worker);
 
     let store = world.resource::<BlobStore>().clone();
-    let mut event_rx = world.resource::<SystemEventBus>().0.subscribe();
+    let mut event_rx = world.resource::<SystemEventBus>().subscribe();
 
     // 1. Create Input Data - Large Dataset (20,000 items)
     // Baseline: Value = 100.0, with minor noise +/- 5.0
@@ -66,6 +66,7 @@ async fn test_stats_zscore_outlier() {
             node_type: "Test".to_string(),
             workflow_id: None,
             tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+            version: None,
         },
         config,
         inbox,
@@ -0,0 +1,181 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::{Edge, NodeConfig};
+use ferroflux_core::graph_loader::{GraphLibrary, LibraryGraph, load_graph_from_str};
+use ferroflux_core::nodes::register_core_nodes;
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_iam::TenantId;
+use uuid::Uuid;
+
+const NOTIFY_V1: &str = r#"
+id: notify_error
+version: 1
+ports:
+  in:
+    node: "00000000-0000-0000-0000-00000000000a"
+nodes:
+  - id: "00000000-0000-0000-0000-00000000000a"
+    name: "Format Error"
+    type: "Transform"
+    config: {}
+edges: []
+"#;
+
+const NOTIFY_V2: &str = r#"
+id: notify_error
+version: 2
+ports:
+  in:
+    node: "00000000-0000-0000-0000-00000000000a"
+  failed:
+    node: "00000000-0000-0000-0000-00000000000b"
+    handle: "error"
+nodes:
+  - id: "00000000-0000-0000-0000-00000000000a"
+    name: "Format Error"
+    type: "Transform"
+    config: {}
+  - id: "00000000-0000-0000-0000-00000000000b"
+    name: "Send Email"
+    type: "Email"
+    config: {}
+edges:
+  - source_id: "00000000-0000-0000-0000-00000000000a"
+    target_id: "00000000-0000-0000-0000-00000000000b"
+    label: null
+    source_handle: null
+    target_handle: null
+"#;
+
+fn setup_world() -> World {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    let mut registry = NodeRegistry::default();
+    register_core_nodes(&mut registry);
+    world.insert_resource(registry);
+
+    let mut library = GraphLibrary::default();
+    for yaml in [NOTIFY_V1, NOTIFY_V2] {
+        library.register(serde_yaml::from_str::<LibraryGraph>(yaml).unwrap());
+    }
+    world.insert_resource(library);
+    world
+}
+
+fn workflow(id: &str, trigger: Uuid, instance: Uuid, version: Option<u32>) -> String {
+    let version = version
+        .map(|v| format!("version: {}", v))
+        .unwrap_or_default();
+    format!(
+        r#"
+id: {id}
+nodes:
+  - id: "{trigger}"
+    name: "Webhook"
+    type: "Webhook"
+    config: {{}}
+subgraphs:
+  - id: "{instance}"
+    graph: notify_error
+    {version}
+edges:
+  - source_id: "{trigger}"
+    target_id: "{instance}"
+    label: null
+    source_handle: null
+    target_handle: "in"
+"#
+    )
+}
+
+fn nodes_of(world: &mut World, workflow_id: &str) -> Vec<NodeConfig> {
+    world
+        .query::<&NodeConfig>()
+        .iter(world)
+        .filter(|n| n.workflow_id.as_deref() == Some(workflow_id))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_subgraphs_are_namespaced_per_instance() {
+    let mut world = setup_world();
+    let tenant = TenantId::from("default_tenant");
+    let (billing_trigger, billing_instance) = (Uuid::new_v4(), Uuid::new_v4());
+    let (signup_trigger, signup_instance) = (Uuid::new_v4(), Uuid::new_v4());
+
+    load_graph_from_str(
+        &mut world,
+        tenant.clone(),
+        &workflow("billing", billing_trigger, billing_instance, None),
+    )
+    .unwrap();
+    load_graph_from_str(
+        &mut world,
+        tenant,
+        &workflow("signup", signup_trigger, signup_instance, Some(1)),
+    )
+    .unwrap();
+
+    // Latest version (2 nodes) vs. pinned version 1 (1 node), each plus the trigger.
+    let billing = nodes_of(&mut world, "billing");
+    let signup = nodes_of(&mut world, "signup");
+    assert_eq!(billing.len(), 3);
+    assert_eq!(signup.len(), 2);
+
+    let library_node = Uuid::parse_str("00000000-0000-0000-0000-00000000000a").unwrap();
+    let billing_entry = Uuid::new_v5(&billing_instance, library_node.as_bytes());
+    let signup_entry = Uuid::new_v5(&signup_instance, library_node.as_bytes());
+    assert_ne!(billing_entry, signup_entry);
+    assert!(billing.iter().any(|n| n.id == billing_entry));
+    assert!(signup.iter().any(|n| n.id == signup_entry));
+
+    // The workflow edge lands on the library's entry node.
+    let router = world.resource::<NodeRouter>().0.clone();
+    let edges: Vec<(Entity, Entity)> = world
+        .query::<&Edge>()
+        .iter(&world)
+        .map(|e| (e.source, e.target))
+        .collect();
+    assert!(edges.contains(&(router[&billing_trigger], router[&billing_entry])));
+    assert!(edges.contains(&(router[&signup_trigger], router[&signup_entry])));
+    assert_eq!(edges.len(), 3);
+}
+
+#[test]
+fn test_subgraph_errors() {
+    let mut world = setup_world();
+    let tenant = TenantId::from("default_tenant");
+
+    let missing_version = workflow("a", Uuid::new_v4(), Uuid::new_v4(), Some(9));
+    assert!(load_graph_from_str(&mut world, tenant.clone(), &missing_version).is_err());
+
+    let unknown_port =
+        workflow("b", Uuid::new_v4(), Uuid::new_v4(), None).replace("\"in\"", "\"nope\"");
+    assert!(load_graph_from_str(&mut world, tenant.clone(), &unknown_port).is_err());
+
+    // A library that includes itself.
+    let mut library = GraphLibrary::default();
+    let looping: LibraryGraph = serde_yaml::from_str(
+        r#"
+id: loop
+version: 1
+nodes: []
+edges: []
+subgraphs:
+  - id: "00000000-0000-0000-0000-0000000000ff"
+    graph: loop
+"#,
+    )
+    .unwrap();
+    library.register(looping);
+    world.insert_resource(library);
+    let yaml = r#"
+nodes: []
+edges: []
+subgraphs:
+  - id: "00000000-0000-0000-0000-000000000001"
+    graph: loop
+"#;
+    assert!(load_graph_from_str(&mut world, tenant, yaml).is_err());
+}
@@ -0,0 +1,191 @@
+use ferroflux_core::api::events::SystemEvent;
+use ferroflux_core::api::{ApiCommand, ApiRequest};
+use ferroflux_core::app::AppBuilder;
+use ferroflux_core::secrets::{DatabaseSecretStore, SecretStore, TenantKeyring};
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_iam::TenantId;
+use ferroflux_security::encryption::{decrypt, encrypt};
+use serde_json::json;
+
+const MASTER_KEY: [u8; 32] = [9; 32];
+
+#[tokio::test]
+async fn test_each_tenant_gets_its_own_key() {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let secrets = DatabaseSecretStore::new(store.clone(), MASTER_KEY.to_vec());
+    let keyring = secrets.keyring();
+    let (acme, other) = (TenantId::from("acme"), TenantId::from("other"));
+
+    for tenant in [&acme, &other] {
+        keyring
+            .save_connection(
+                tenant,
+                "crm",
+                "CRM",
+                "http",
+                &json!({ "api_key": format!("sk-{}", tenant) }),
+                "active",
+            )
+            .await
+            .unwrap();
+    }
+
+    let (_, secret) = store
+        .get_connection_secret(&acme, "crm")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(secret.key_version, Some(1));
+    // The master key alone can't read it, and neither can another tenant's key.
+    assert!(decrypt(&secret.ciphertext, &MASTER_KEY, &secret.nonce).is_err());
+    let other_key = store.get_tenant_key(&other, None).await.unwrap().unwrap();
+    let other_key = decrypt(&other_key.wrapped_key, &MASTER_KEY, &other_key.nonce).unwrap();
+    assert!(decrypt(&secret.ciphertext, &other_key, &secret.nonce).is_err());
+
+    for tenant in [&acme, &other] {
+        let credentials = secrets.resolve_connection(tenant, "crm").await.unwrap();
+        assert_eq!(credentials["api_key"], format!("sk-{}", tenant));
+    }
+}
+
+#[tokio::test]
+async fn test_rotation_re_encrypts_the_tenants_connections() {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let secrets = DatabaseSecretStore::new(store.clone(), MASTER_KEY.to_vec());
+    let keyring = TenantKeyring::new(store.clone(), MASTER_KEY.to_vec());
+    let (acme, other) = (TenantId::from("acme"), TenantId::from("other"));
+
+    keyring
+        .save_connection(
+            &acme,
+            "crm",
+            "CRM",
+            "http",
+            &json!({ "api_key": "a" }),
+            "active",
+        )
+        .await
+        .unwrap();
+    keyring
+        .save_connection(
+            &other,
+            "crm",
+            "CRM",
+            "http",
+            &json!({ "api_key": "b" }),
+            "active",
+        )
+        .await
+        .unwrap();
+    // Saved before tenant keys existed: encrypted with the master key.
+    let (ciphertext, nonce) = encrypt(br#"{"api_key": "legacy"}"#, &MASTER_KEY).unwrap();
+    store
+        .save_connection(&acme, "old", "Old", "http", &ciphertext, &nonce, "active")
+        .await
+        .unwrap();
+    assert_eq!(
+        secrets.resolve_connection(&acme, "old").await.unwrap()["api_key"],
+        "legacy"
+    );
+
+    assert_eq!(keyring.rotate_tenant_key(&acme).await.unwrap(), 2);
+
+    let versions: Vec<_> = store
+        .list_connection_secrets(&acme)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(slug, secret)| (slug, secret.key_version))
+        .collect();
+    assert_eq!(
+        versions,
+        [("crm".to_string(), Some(2)), ("old".to_string(), Some(2))]
+    );
+    assert!(
+        store
+            .get_tenant_key(&acme, Some(1))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        secrets.resolve_connection(&acme, "crm").await.unwrap()["api_key"],
+        "a"
+    );
+    assert_eq!(
+        secrets.resolve_connection(&acme, "old").await.unwrap()["api_key"],
+        "legacy"
+    );
+
+    // Other tenants keep their key.
+    let (_, secret) = store
+        .get_connection_secret(&other, "crm")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(secret.key_version, Some(1));
+    assert_eq!(
+        secrets.resolve_connection(&other, "crm").await.unwrap()["api_key"],
+        "b"
+    );
+
+    // A secret sealed with the retired key can no longer be saved.
+    let mut stale = keyring.seal(&acme, b"{}").await.unwrap();
+    stale.key_version = Some(1);
+    assert!(
+        store
+            .save_sealed_connection(&acme, "new", "New", "http", &stale, "active")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rotate_tenant_key_command_reports_the_new_version() {
+    let store = PersistentStore::new("sqlite::memory:").await.unwrap();
+    let (mut app, api_tx, event_tx, ..) = AppBuilder::new()
+        .with_store(store.clone())
+        .with_master_key(MASTER_KEY.to_vec())
+        .build()
+        .await
+        .unwrap();
+    let mut events = event_tx.subscribe();
+    let acme = TenantId::from("acme");
+    TenantKeyring::new(store.clone(), MASTER_KEY.to_vec())
+        .save_connection(
+            &acme,
+            "crm",
+            "CRM",
+            "http",
+            &json!({ "api_key": "a" }),
+            "active",
+        )
+        .await
+        .unwrap();
+
+    api_tx
+        .send(ApiRequest::system(ApiCommand::RotateTenantKey(
+            acme.clone(),
+        )))
+        .await
+        .unwrap();
+    app.update();
+
+    let rotated = std::iter::from_fn(|| events.try_recv().ok()).find_map(|event| match event {
+        SystemEvent::TenantKeyRotated {
+            tenant_id, version, ..
+        } => Some((tenant_id, version)),
+        _ => None,
+    });
+    assert_eq!(rotated, Some(("acme".to_string(), 2)));
+    assert_eq!(
+        store
+            .get_connection_secret(&acme, "crm")
+            .await
+            .unwrap()
+            .unwrap()
+            .1
+            .key_version,
+        Some(2)
+    );
+}
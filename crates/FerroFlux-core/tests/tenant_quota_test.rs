@@ -0,0 +1,256 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::trigger::{handle_trigger_and_wait, handle_trigger_workflow};
+use ferroflux_core::api::{RunError, RunReply};
+use ferroflux_core::components::pipeline::{ExecutionContext, ExecutionResult, ReadyToExecute};
+use ferroflux_core::components::{NodeConfig, Outbox};
+use ferroflux_core::graph_loader::load_graph_from_str;
+use ferroflux_core::resources::quotas::{Quota, QuotaError, TenantQuotas};
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::resources::{
+    GlobalHttpClient, NodeRouter, PipelineResultChannel, TokioRuntime, WorkDone,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::{PersistentStore, TenantLimits, TokenUsage};
+use ferroflux_core::systems::agent::agent_exec;
+use ferroflux_core::systems::gateway::{WEBHOOK_QUEUE, ingest_webhooks};
+use ferroflux_iam::{Principal, TenantId};
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+fn workflow(id: &str) -> String {
+    format!(
+        r#"
+id: "{id}"
+nodes:
+  - id: "{node}"
+    name: "Start"
+    type: "Webhook"
+    config: {{}}
+edges: []
+"#,
+        node = uuid::Uuid::new_v4()
+    )
+}
+
+fn setup(limits: TenantLimits) -> (World, broadcast::Receiver<SystemEvent>) {
+    let mut world = World::new();
+    let (tx, events) = broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(NodeRegistry::default());
+    world.insert_resource(NodeRouter::default());
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    let quotas = TenantQuotas::default();
+    quotas.set_limits(&TenantId::from("acme"), limits);
+    world.insert_resource(quotas);
+    (world, events)
+}
+
+fn exceeded(events: &mut broadcast::Receiver<SystemEvent>) -> Vec<(Quota, u64, u64)> {
+    std::iter::from_fn(|| events.try_recv().ok())
+        .filter_map(|event| match event {
+            SystemEvent::QuotaExceeded {
+                quota, limit, used, ..
+            } => Some((quota, limit, used)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_workflow_quota_rejects_new_workflows_but_not_reloads() {
+    let acme = TenantId::from("acme");
+    let (mut world, mut events) = setup(TenantLimits {
+        max_workflows: Some(1),
+        ..Default::default()
+    });
+
+    load_graph_from_str(&mut world, acme.clone(), &workflow("orders")).unwrap();
+    load_graph_from_str(&mut world, acme.clone(), &workflow("orders")).unwrap();
+    let err = load_graph_from_str(&mut world, acme.clone(), &workflow("invoices")).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<QuotaError>().map(|e| e.quota),
+        Some(Quota::Workflows)
+    );
+    assert_eq!(exceeded(&mut events), [(Quota::Workflows, 1, 1)]);
+
+    // Other tenants are unaffected.
+    load_graph_from_str(&mut world, TenantId::from("globex"), &workflow("invoices")).unwrap();
+}
+
+#[test]
+fn test_runs_and_payload_sizes_are_capped() {
+    let acme = TenantId::from("acme");
+    let (mut world, mut events) = setup(TenantLimits {
+        max_runs_per_day: Some(2),
+        max_payload_mb: Some(1),
+        ..Default::default()
+    });
+    load_graph_from_str(&mut world, acme.clone(), &workflow("orders")).unwrap();
+
+    let large = json!({ "data": "x".repeat(2 * 1024 * 1024) });
+    assert!(
+        handle_trigger_workflow(
+            &mut world,
+            &Principal::system(),
+            acme.clone(),
+            "orders".into(),
+            large
+        )
+        .is_err()
+    );
+    for _ in 0..2 {
+        handle_trigger_workflow(
+            &mut world,
+            &Principal::system(),
+            acme.clone(),
+            "orders".into(),
+            json!({}),
+        )
+        .unwrap();
+    }
+
+    let (reply, mut rx) = RunReply::channel();
+    assert!(
+        handle_trigger_and_wait(
+            &mut world,
+            &Principal::system(),
+            acme.clone(),
+            "orders".into(),
+            json!({}),
+            Duration::from_secs(1),
+            reply,
+        )
+        .is_err()
+    );
+    assert!(matches!(
+        rx.try_recv().unwrap(),
+        Err(RunError::QuotaExceeded(QuotaError {
+            quota: Quota::RunsPerDay,
+            ..
+        }))
+    ));
+    assert_eq!(
+        exceeded(&mut events),
+        [(Quota::PayloadMb, 1, 3), (Quota::RunsPerDay, 2, 2)]
+    );
+    assert_eq!(world.resource::<TenantQuotas>().runs_today(&acme), 2);
+}
+
+#[test]
+fn test_webhooks_are_refused_once_daily_runs_are_used_up() {
+    let acme = TenantId::from("acme");
+    let (mut world, mut events) = setup(TenantLimits {
+        max_runs_per_day: Some(1),
+        ..Default::default()
+    });
+    load_graph_from_str(&mut world, acme.clone(), &workflow("orders")).unwrap();
+    let (webhook, node_id) = world
+        .query::<(Entity, &NodeConfig)>()
+        .iter(&world)
+        .map(|(e, conf)| (e, conf.id))
+        .next()
+        .unwrap();
+
+    let (tx, _) = WEBHOOK_QUEUE.get_or_init(async_channel::unbounded);
+    let store = world.resource::<BlobStore>().clone();
+    let tickets: Vec<_> = (0..2).map(|_| store.check_in(b"{}").unwrap()).collect();
+    for ticket in &tickets {
+        tx.try_send((node_id, ticket.clone())).unwrap();
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(ingest_webhooks);
+    schedule.run(&mut world);
+
+    assert_eq!(world.get::<Outbox>(webhook).unwrap().queue.len(), 1);
+    assert_eq!(world.resource::<TenantQuotas>().runs_today(&acme), 1);
+    assert_eq!(exceeded(&mut events), [(Quota::RunsPerDay, 1, 1)]);
+    // The refused request's payload is not left behind in the store.
+    assert!(store.size(&tickets[1]).is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_agent_requests_fail_once_monthly_tokens_are_used_up() {
+    let acme = TenantId::from("acme");
+    let db = PersistentStore::new("sqlite::memory:").await.unwrap();
+    db.save_tenant_limits(
+        &acme,
+        &TenantLimits {
+            max_agent_tokens_per_month: Some(100),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    db.record_usage(
+        &acme,
+        Some("orders"),
+        "gpt-4o",
+        &TokenUsage {
+            prompt_tokens: 80,
+            completion_tokens: 20,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Limits and this month's usage survive a restart.
+    let quotas = TenantQuotas::load(&db).await.unwrap();
+    assert_eq!(quotas.limits(&acme).max_agent_tokens_per_month, Some(100));
+    assert_eq!(quotas.agent_tokens_this_month(&acme), 100);
+
+    let (mut world, mut events) = setup(TenantLimits::default());
+    world.insert_resource(quotas);
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(PipelineResultChannel::default());
+    let agent = world
+        .spawn((
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Agent".to_string(),
+                node_type: "Agent".to_string(),
+                workflow_id: Some("orders".to_string()),
+                tenant_id: Some(acme.clone()),
+                version: None,
+            },
+            ReadyToExecute {
+                // Never contacted: the request is refused before it is sent.
+                url: "http://127.0.0.1:9/v1/chat/completions".to_string(),
+                method: "POST".to_string(),
+                headers: Default::default(),
+                body: "{}".to_string(),
+                trace_id: "t1".to_string(),
+                context: ExecutionContext {
+                    provider_name: "openai".to_string(),
+                    model_name: "gpt-4o".to_string(),
+                    node_id: uuid::Uuid::nil(),
+                    result_key: None,
+                    output_transform: None,
+                    input_json: json!({}),
+                    start_time: 0,
+                    fallback_from: vec![],
+                    repair_attempt: 0,
+                },
+                stream: None,
+                timeout_ms: None,
+                fallbacks: vec![],
+            },
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(agent_exec);
+    schedule.run(&mut world);
+
+    let result = world.get::<ExecutionResult>(agent).unwrap();
+    assert_eq!(result.status, 429);
+    assert!(world.get::<ReadyToExecute>(agent).is_none());
+    assert_eq!(
+        exceeded(&mut events),
+        [(Quota::AgentTokensPerMonth, 100, 100)]
+    );
+}
@@ -13,7 +13,7 @@ fn test_transport_optimization_dynamic_edge() {
     world.insert_resource(GraphTopology::default());
     world.insert_resource(WorkDone::default());
     let (tx, _) = tokio::sync::broadcast::channel(10);
-    world.insert_resource(ferroflux_core::api::events::SystemEventBus(tx));
+    world.insert_resource(ferroflux_core::api::events::SystemEventBus::new(tx));
 
     // 2. Schedule
     let mut schedule = Schedule::default();
@@ -31,6 +31,7 @@ fn test_transport_optimization_dynamic_edge() {
                 node_type: "Generic".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             Outbox {
                 queue: VecDeque::new(),
@@ -49,6 +50,7 @@ fn test_transport_optimization_dynamic_edge() {
                 node_type: "Target".to_string(),
                 workflow_id: None,
                 tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+                version: None,
             },
             Outbox {
                 queue: VecDeque::new(),
@@ -106,3 +108,65 @@ fn test_transport_optimization_dynamic_edge() {
         "Target should receive ticket after edge addition"
     );
 }
+
+#[test]
+fn test_topology_applies_only_changes() {
+    let mut world = World::new();
+    world.insert_resource(GraphTopology::default());
+    let mut schedule = Schedule::default();
+    schedule.add_systems(update_graph_topology);
+
+    let node = |world: &mut World| {
+        world
+            .spawn(NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Node".to_string(),
+                node_type: "Generic".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            })
+            .id()
+    };
+    let (a, b, c) = (node(&mut world), node(&mut world), node(&mut world));
+    let edge = |source, target, port: &str| Edge {
+        source,
+        source_handle: None,
+        target,
+        target_handle: Some(port.to_string()),
+    };
+    let ab = world.spawn(edge(a, b, "left")).id();
+    world.spawn(edge(b, c, "in"));
+    schedule.run(&mut world);
+
+    let topology = world.resource::<GraphTopology>();
+    assert_eq!(topology.edge_count(), 2);
+    assert_eq!(topology.adjacency[&a], vec![(None, b)]);
+    let revision = topology.revision;
+
+    // Idle ticks leave the cache alone.
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<GraphTopology>().revision, revision);
+
+    // A rewired edge moves, keeping its input port.
+    world.get_mut::<Edge>(ab).unwrap().target = c;
+    schedule.run(&mut world);
+    let topology = world.resource::<GraphTopology>();
+    assert_eq!(topology.adjacency[&a], vec![(None, c)]);
+    assert_eq!(topology.target_ports[&(a, None, c)], "left");
+    assert!(!topology.target_ports.contains_key(&(a, None, b)));
+
+    world.despawn(ab);
+    schedule.run(&mut world);
+    let topology = world.resource::<GraphTopology>();
+    assert!(!topology.adjacency.contains_key(&a));
+    assert_eq!(topology.edge_count(), 1);
+
+    // Despawning a node drops the edges that still point at it.
+    world.despawn(c);
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    let topology = world.resource::<GraphTopology>();
+    assert!(topology.adjacency.is_empty());
+    assert!(topology.target_ports.is_empty());
+}
@@ -0,0 +1,241 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::handlers::run::handle_cancel_run;
+use ferroflux_core::api::handlers::trigger::handle_trigger_and_wait;
+use ferroflux_core::api::{ApiCommand, ApiReceiver, ApiRequest, RunError, RunReply};
+use ferroflux_core::components::{Edge, Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::{GraphTopology, PendingRuns, WorkDone};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::api_worker::api_command_worker;
+use ferroflux_core::systems::observability::run_waiter_worker;
+use ferroflux_core::systems::transport::{transport_worker, update_graph_topology};
+use ferroflux_iam::{AuthzError, Principal, Role, TenantId};
+use serde_json::json;
+use std::time::Duration;
+
+fn node(node_type: &str) -> NodeConfig {
+    NodeConfig {
+        id: uuid::Uuid::new_v4(),
+        name: node_type.to_string(),
+        node_type: node_type.to_string(),
+        workflow_id: Some("orders".to_string()),
+        tenant_id: Some(TenantId::from("acme")),
+        version: None,
+    }
+}
+
+/// Webhook -> Step, where the test plays the part of Step's worker.
+fn setup() -> (World, Schedule, Entity) {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(GraphTopology::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(PendingRuns::default());
+    world.insert_resource(BlobStore::default());
+
+    let webhook = world.spawn((node("Webhook"), Outbox::default())).id();
+    let step = world
+        .spawn((node("Step"), Inbox::default(), Outbox::default()))
+        .id();
+    world.spawn(Edge {
+        source: webhook,
+        source_handle: None,
+        target: step,
+        target_handle: None,
+    });
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((update_graph_topology, run_waiter_worker, transport_worker).chain());
+    (world, schedule, step)
+}
+
+/// Moves every ticket waiting at `step` to its outbox on `port`.
+fn process(world: &mut World, step: Entity, port: Option<&str>) {
+    let tickets: Vec<_> = world
+        .get_mut::<Inbox>(step)
+        .unwrap()
+        .queue
+        .drain(..)
+        .collect();
+    let mut outbox = world.get_mut::<Outbox>(step).unwrap();
+    for ticket in tickets {
+        outbox.queue.push_back((port.map(str::to_string), ticket));
+    }
+}
+
+type RunReceiver = tokio::sync::oneshot::Receiver<ferroflux_core::api::RunOutcome>;
+
+fn trigger(world: &mut World, workflow_id: &str, timeout: Duration) -> RunReceiver {
+    let (reply, rx) = RunReply::channel();
+    let _ = handle_trigger_and_wait(
+        world,
+        &Principal::system(),
+        TenantId::from("acme"),
+        workflow_id.to_string(),
+        json!({ "order": 42 }),
+        timeout,
+        reply,
+    );
+    rx
+}
+
+#[test]
+fn test_trigger_and_wait_returns_terminal_payload() {
+    let (mut world, mut schedule, step) = setup();
+    let mut rx = trigger(&mut world, "orders", Duration::from_secs(30));
+
+    // Delivered to Step, which is still working on it.
+    schedule.run(&mut world);
+    assert_eq!(world.get::<Inbox>(step).unwrap().queue.len(), 1);
+    assert!(rx.try_recv().is_err());
+
+    process(&mut world, step, None);
+    schedule.run(&mut world);
+    assert_eq!(
+        rx.try_recv().unwrap().unwrap(),
+        vec![json!({ "order": 42 })]
+    );
+    assert!(world.resource::<PendingRuns>().0.is_empty());
+}
+
+#[test]
+fn test_trigger_and_wait_reports_failures() {
+    let (mut world, mut schedule, step) = setup();
+    let mut rx = trigger(&mut world, "orders", Duration::from_secs(30));
+    schedule.run(&mut world);
+    process(&mut world, step, Some("error"));
+    schedule.run(&mut world);
+    assert!(matches!(rx.try_recv().unwrap(), Err(RunError::Failed(_))));
+
+    let mut rx = trigger(&mut world, "orders", Duration::ZERO);
+    schedule.run(&mut world);
+    assert!(matches!(rx.try_recv().unwrap(), Err(RunError::TimedOut)));
+
+    let mut rx = trigger(&mut world, "missing", Duration::from_secs(30));
+    assert!(matches!(rx.try_recv().unwrap(), Err(RunError::NotFound(_))));
+}
+
+#[test]
+fn test_trigger_and_wait_reports_cancellation() {
+    let (mut world, mut schedule, step) = setup();
+    let mut rx = trigger(&mut world, "orders", Duration::from_secs(30));
+    schedule.run(&mut world);
+    let trace_id = world
+        .resource::<PendingRuns>()
+        .0
+        .keys()
+        .next()
+        .cloned()
+        .unwrap();
+
+    // Another tenant naming the trace doesn't end the wait.
+    handle_cancel_run(
+        &mut world,
+        &Principal::system(),
+        TenantId::from("globex"),
+        trace_id.clone(),
+    )
+    .unwrap();
+    schedule.run(&mut world);
+    assert!(rx.try_recv().is_err());
+    assert_eq!(world.get::<Inbox>(step).unwrap().queue.len(), 1);
+
+    handle_cancel_run(
+        &mut world,
+        &Principal::system(),
+        TenantId::from("acme"),
+        trace_id,
+    )
+    .unwrap();
+    schedule.run(&mut world);
+    assert!(matches!(rx.try_recv().unwrap(), Err(RunError::Cancelled)));
+    assert!(world.resource::<PendingRuns>().0.is_empty());
+}
+
+#[test]
+fn test_trigger_and_wait_reports_permission_errors() {
+    let (mut world, _, _) = setup();
+    let (api_tx, api_rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(api_rx));
+    let mut events = world.resource::<SystemEventBus>().subscribe();
+
+    let acme = TenantId::from("acme");
+    let viewer = Principal::new("vera").with_membership(acme.clone(), Role::Viewer);
+    let (reply, mut rx) = RunReply::channel();
+    api_tx
+        .try_send(ApiRequest::new(
+            viewer,
+            ApiCommand::TriggerAndWait {
+                tenant_id: acme.clone(),
+                workflow_id: "orders".to_string(),
+                payload: json!({}),
+                timeout_ms: 30_000,
+                reply,
+            },
+        ))
+        .unwrap();
+    api_command_worker(&mut world);
+
+    let expected = AuthzError::InsufficientRole {
+        user_id: "vera".to_string(),
+        tenant: acme,
+        role: Role::Viewer,
+        required: Role::Editor,
+    };
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Err(RunError::Forbidden(expected.clone()))
+    );
+    assert!(world.resource::<PendingRuns>().0.is_empty());
+    match events.try_recv().unwrap() {
+        SystemEvent::AccessDenied {
+            permission, error, ..
+        } => {
+            assert_eq!(permission.as_deref(), Some("deploy"));
+            assert_eq!(error, expected);
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+#[test]
+fn test_untenanted_workflows_are_only_reachable_by_the_system() {
+    let (mut world, _, _) = setup();
+    let (api_tx, api_rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(api_rx));
+    world.spawn((
+        NodeConfig {
+            workflow_id: Some("shared".to_string()),
+            tenant_id: None,
+            ..node("Webhook")
+        },
+        Outbox::default(),
+    ));
+
+    let acme = TenantId::from("acme");
+    let mut send = |principal: Principal| {
+        let (reply, rx) = RunReply::channel();
+        let command = ApiCommand::TriggerAndWait {
+            tenant_id: acme.clone(),
+            workflow_id: "shared".to_string(),
+            payload: json!({}),
+            timeout_ms: 30_000,
+            reply,
+        };
+        api_tx
+            .try_send(ApiRequest::new(principal, command))
+            .unwrap();
+        api_command_worker(&mut world);
+        rx
+    };
+
+    let editor = Principal::new("ed").with_membership(acme.clone(), Role::Editor);
+    let mut refused = send(editor);
+    assert!(matches!(
+        refused.try_recv().unwrap(),
+        Err(RunError::NotFound(_))
+    ));
+    let _started = send(Principal::system());
+    assert_eq!(world.resource::<PendingRuns>().0.len(), 1);
+}
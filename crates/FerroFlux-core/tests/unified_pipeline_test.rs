@@ -61,6 +61,7 @@ fn test_unified_pipeline_execution() {
             node_type: "Action".to_string(),
             category: "Test".to_string(),
             version: Some("1.0".to_string()),
+            deprecated: None,
             description: None,
             platform: None,
             data_strategy: None,
@@ -0,0 +1,336 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::SystemEventBus;
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::components::io::{EmbeddingConfig, VectorSearchConfig};
+use ferroflux_core::integrations::{IntegrationDef, IntegrationRegistry};
+use ferroflux_core::resources::{
+    EmbeddingResultChannel, GlobalHttpClient, TokioRuntime, VectorIndex, VectorSearchResultChannel,
+    WorkDone,
+};
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::vector::{SqliteVectorStore, VectorRecord, VectorStore};
+use ferroflux_core::systems::io::{embedding_worker, vector_search_worker};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn sqlite_store() -> SqliteVectorStore {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    SqliteVectorStore::new(pool).await.unwrap()
+}
+
+fn record(id: &str, vector: [f32; 3]) -> VectorRecord {
+    VectorRecord {
+        id: id.to_string(),
+        vector: vector.to_vec(),
+        metadata: json!({ "title": id }),
+    }
+}
+
+#[tokio::test]
+async fn test_sqlite_store_ranks_by_cosine_similarity_per_tenant() {
+    let store = sqlite_store().await;
+    let acme = TenantId::from("acme");
+    let other = TenantId::from("other");
+    store
+        .upsert(
+            &acme,
+            "docs",
+            vec![
+                record("x", [1.0, 0.0, 0.0]),
+                record("xy", [1.0, 1.0, 0.0]),
+                record("z", [0.0, 0.0, 1.0]),
+            ],
+        )
+        .await
+        .unwrap();
+    store
+        .upsert(&other, "docs", vec![record("theirs", [1.0, 0.0, 0.0])])
+        .await
+        .unwrap();
+
+    let matches = store
+        .search(&acme, "docs", &[1.0, 0.1, 0.0], 2)
+        .await
+        .unwrap();
+    let ids: Vec<&str> = matches.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["x", "xy"]);
+    assert!(matches[0].score > 0.99);
+    assert_eq!(matches[0].metadata["title"], "x");
+
+    // Upserting an id replaces it; deleting removes it.
+    store
+        .upsert(&acme, "docs", vec![record("x", [0.0, 0.0, 1.0])])
+        .await
+        .unwrap();
+    store
+        .delete(&acme, "docs", &["xy".to_string()])
+        .await
+        .unwrap();
+    let matches = store
+        .search(&acme, "docs", &[0.0, 0.0, 1.0], 5)
+        .await
+        .unwrap();
+    let mut ids: Vec<&str> = matches.iter().map(|m| m.id.as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, ["x", "z"]);
+
+    assert!(
+        store
+            .search(&acme, "missing", &[1.0, 0.0, 0.0], 5)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+/// An OpenAI-style `embeddings` action.
+fn registry(base_url: &str) -> IntegrationRegistry {
+    let def: IntegrationDef = serde_yaml::from_str(&format!(
+        r#"
+name: embedder
+base_url: "{base_url}/v1"
+auth: {{ type: bearer }}
+verify_params: {{ api_key: VECTOR_TEST_EMBEDDER_KEY }}
+actions:
+  embeddings:
+    implementation:
+      type: http
+      config:
+        method: POST
+        path: /embeddings
+        headers: {{ Content-Type: application/json }}
+        body_template: '{{ "model": {{{{json model}}}}, "input": {{{{json text}}}} }}'
+"#
+    ))
+    .unwrap();
+    IntegrationRegistry {
+        definitions: HashMap::from([("embedder".to_string(), def)]),
+        ..Default::default()
+    }
+}
+
+fn embedding_config(collection: Option<&str>) -> EmbeddingConfig {
+    EmbeddingConfig {
+        provider: "embedder".to_string(),
+        model: Some("embed-small".to_string()),
+        input_field: "text".to_string(),
+        vector_pointer: "/data/0/embedding".to_string(),
+        collection: collection.map(str::to_string),
+        id_field: Some("id".to_string()),
+        connection_slug: None,
+        timeout_ms: 2_000,
+        result_key: "embedding".to_string(),
+    }
+}
+
+fn world(server: &MockServer, store: &BlobStore, index: Option<Arc<dyn VectorStore>>) -> World {
+    let mut world = World::new();
+    let (tx, _) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(TokioRuntime(tokio::runtime::Handle::current()));
+    world.insert_resource(GlobalHttpClient::default());
+    world.insert_resource(EmbeddingResultChannel::default());
+    world.insert_resource(VectorSearchResultChannel::default());
+    world.insert_resource(registry(&server.uri()));
+    world.insert_resource(store.clone());
+    if let Some(index) = index {
+        world.insert_resource(VectorIndex(index));
+    }
+    world
+}
+
+async fn run(
+    world: &mut World,
+    store: &BlobStore,
+    config: impl Component,
+    payload: Value,
+) -> (Option<String>, Value) {
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(
+        store
+            .check_in(&serde_json::to_vec(&payload).unwrap())
+            .unwrap(),
+    );
+    let node = world
+        .spawn((
+            config,
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Knowledge Base".to_string(),
+                node_type: "Embedding".to_string(),
+                workflow_id: None,
+                tenant_id: Some(TenantId::from("acme")),
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+    let mut schedule = Schedule::default();
+    schedule.add_systems((embedding_worker, vector_search_worker));
+
+    for _ in 0..100 {
+        schedule.run(world);
+        if let Some((port, ticket)) = world.get_mut::<Outbox>(node).unwrap().queue.pop_front() {
+            let payload = serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap();
+            world.despawn(node);
+            return (port, payload);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Vector worker timed out");
+}
+
+fn embedding(vector: [f32; 3]) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "data": [{ "index": 0, "embedding": vector }],
+        "model": "embed-small",
+    }))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_embedding_nodes_index_documents_and_vector_search_retrieves_them() {
+    let server = MockServer::start().await;
+    for (text, vector) in [
+        ("Refunds are issued within 14 days", [0.9, 0.1, 0.0]),
+        ("Shipping takes 3-5 business days", [0.0, 0.9, 0.1]),
+        ("How long until I get my money back?", [0.8, 0.2, 0.0]),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(header("authorization", "Bearer sk-embed"))
+            .and(body_string_contains(text))
+            .respond_with(embedding(vector))
+            .mount(&server)
+            .await;
+    }
+    unsafe {
+        std::env::set_var("VECTOR_TEST_EMBEDDER_KEY", "sk-embed");
+    }
+
+    let blobs = BlobStore::default();
+    let index: Arc<dyn VectorStore> = Arc::new(sqlite_store().await);
+    let mut world = world(&server, &blobs, Some(index.clone()));
+
+    for (id, text) in [
+        ("refunds", "Refunds are issued within 14 days"),
+        ("shipping", "Shipping takes 3-5 business days"),
+    ] {
+        let (port, payload) = run(
+            &mut world,
+            &blobs,
+            embedding_config(Some("faq")),
+            json!({ "id": id, "text": text }),
+        )
+        .await;
+        assert_eq!(port, None);
+        assert_eq!(payload["embedding"].as_array().unwrap().len(), 3);
+    }
+
+    let (port, question) = run(
+        &mut world,
+        &blobs,
+        embedding_config(None),
+        json!({ "text": "How long until I get my money back?" }),
+    )
+    .await;
+    assert_eq!(port, None);
+
+    let search = VectorSearchConfig {
+        collection: "faq".to_string(),
+        vector_field: "embedding".to_string(),
+        top_k: 1,
+        min_score: Some(0.5),
+        result_key: "matches".to_string(),
+    };
+    let (port, payload) = run(&mut world, &blobs, search, question).await;
+    assert_eq!(port, None);
+    let matches = payload["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["id"], "refunds");
+    assert_eq!(
+        matches[0]["metadata"]["text"],
+        "Refunds are issued within 14 days"
+    );
+
+    // The question was not indexed, and other tenants see nothing.
+    assert_eq!(
+        index
+            .search(&TenantId::from("acme"), "faq", &[1.0, 0.0, 0.0], 10)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(
+        index
+            .search(
+                &TenantId::from("default_tenant"),
+                "faq",
+                &[1.0, 0.0, 0.0],
+                10
+            )
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failures_route_to_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })))
+        .mount(&server)
+        .await;
+    let blobs = BlobStore::default();
+    let mut world = world(&server, &blobs, None);
+
+    // No vector at the pointer.
+    let (port, _) = run(
+        &mut world,
+        &blobs,
+        embedding_config(None),
+        json!({ "text": "hello" }),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+
+    // Indexing and searching need a vector store.
+    let (port, payload) = run(
+        &mut world,
+        &blobs,
+        embedding_config(Some("faq")),
+        json!({ "text": "hello" }),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(payload, json!({ "text": "hello" }));
+
+    let search = VectorSearchConfig {
+        collection: "faq".to_string(),
+        vector_field: "embedding".to_string(),
+        top_k: 3,
+        min_score: None,
+        result_key: "matches".to_string(),
+    };
+    let (port, _) = run(
+        &mut world,
+        &blobs,
+        search,
+        json!({ "embedding": [1.0, 0.0] }),
+    )
+    .await;
+    assert_eq!(port.as_deref(), Some("error"));
+}
@@ -0,0 +1,335 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::api::{ApiCommand, ApiReceiver, ApiRequest, Reply};
+use ferroflux_core::components::{Inbox, NodeConfig, Outbox, WorkDone};
+use ferroflux_core::graph_loader::load_graph_from_str;
+use ferroflux_core::nodes::plugin::{self, PluginNode};
+use ferroflux_core::nodes::register_core_nodes;
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::systems::api_worker::api_command_worker;
+use ferroflux_core::systems::compute::{WasmRuntime, plugin_worker};
+use ferroflux_core::traits::node_factory::NodeMetadata;
+use ferroflux_iam::{Principal, Role, TenantId};
+use serde_json::{Value, json};
+
+/// A plugin for node type `id` whose `execute` is `execute_body` (the default echoes its
+/// argument back), plus any `extra` exports.
+fn plugin_wat(id: &str, execute_body: &str, extra: &str) -> String {
+    let metadata = json!({
+        "id": id,
+        "name": "Echo",
+        "category": "Plugins",
+        "inputs": [{ "name": "Exec", "data_type": "flow" }],
+        "outputs": [{ "name": "Success", "data_type": "flow" }],
+        "settings": [],
+    })
+    .to_string();
+    format!(
+        r#"(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 4096))
+  (data (i32.const 0) "{data}")
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    global.get $heap
+    local.set $ptr
+    global.get $heap
+    local.get $len
+    i32.add
+    global.set $heap
+    local.get $ptr)
+  (func (export "metadata") (result i64)
+    i64.const {len})
+  (func (export "execute") (param $ptr i32) (param $len i32) (result i64)
+    {execute_body})
+  {extra})"#,
+        data = metadata.replace('"', "\\\""),
+        len = metadata.len(),
+    )
+}
+
+const ECHO: &str = "local.get $ptr
+    i64.extend_i32_u
+    i64.const 32
+    i64.shl
+    local.get $len
+    i64.extend_i32_u
+    i64.or";
+
+fn world() -> World {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    world.insert_resource(WasmRuntime::default());
+    world.insert_resource(BlobStore::default());
+    world.insert_resource(WorkDone::default());
+    world.insert_resource(SystemEventBus::new(tokio::sync::broadcast::channel(16).0));
+    let mut registry = NodeRegistry::default();
+    register_core_nodes(&mut registry);
+    world.insert_resource(registry);
+    world
+}
+
+fn graph(node_id: &str, node_type: &str) -> String {
+    format!(
+        r#"
+nodes:
+  - id: "{node_id}"
+    name: "Plugin"
+    type: "{node_type}"
+    config:
+      greeting: "hi"
+edges: []
+"#
+    )
+}
+
+fn plugin_node(world: &mut World, tenant: &str) -> Option<Entity> {
+    world
+        .query_filtered::<(Entity, &NodeConfig), With<PluginNode>>()
+        .iter(world)
+        .find(|(_, node)| node.tenant_id == Some(TenantId::from(tenant)))
+        .map(|(entity, _)| entity)
+}
+
+/// Feeds `input` to the node and returns the port and payload it emitted.
+fn run(world: &mut World, node: Entity, input: Value) -> (Option<String>, Value) {
+    let store = world.resource::<BlobStore>().clone();
+    let ticket = store.check_in(input.to_string().as_bytes()).unwrap();
+    world
+        .get_mut::<Inbox>(node)
+        .unwrap()
+        .queue
+        .push_back(ticket);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(plugin_worker);
+    schedule.run(world);
+
+    let (port, ticket) = world
+        .get_mut::<Outbox>(node)
+        .unwrap()
+        .queue
+        .pop_front()
+        .unwrap();
+    (
+        port,
+        serde_json::from_slice(&store.claim(&ticket).unwrap()).unwrap(),
+    )
+}
+
+#[test]
+fn test_plugin_nodes_run_only_in_workflows_of_their_tenant() {
+    let mut world = world();
+    let acme = TenantId::from("acme");
+    let wat = plugin_wat("plugin.echo", ECHO, "");
+    let metadata = plugin::install(&mut world, Some(acme.clone()), wat.as_bytes()).unwrap();
+    assert_eq!(metadata.name, "Echo");
+    let registry = world.resource::<NodeRegistry>();
+    assert!(registry.get_for(Some(&acme), "plugin.echo").is_some());
+    assert!(registry.get("plugin.echo").is_none());
+    assert!(
+        registry
+            .get_for(Some(&TenantId::from("globex")), "plugin.echo")
+            .is_none()
+    );
+
+    load_graph_from_str(
+        &mut world,
+        acme,
+        &graph("55555555-5555-5555-5555-555555555555", "plugin.echo"),
+    )
+    .unwrap();
+    load_graph_from_str(
+        &mut world,
+        TenantId::from("globex"),
+        &graph("66666666-6666-6666-6666-666666666666", "plugin.echo"),
+    )
+    .unwrap();
+    assert!(plugin_node(&mut world, "globex").is_none());
+
+    let node = plugin_node(&mut world, "acme").expect("plugin node should be built");
+    let (port, output) = run(&mut world, node, json!({ "name": "Ada" }));
+    assert_eq!(port, None);
+    assert_eq!(
+        output,
+        json!({ "settings": { "greeting": "hi" }, "input": { "name": "Ada" } })
+    );
+}
+
+#[test]
+fn test_tenant_plugins_are_listed_only_for_their_tenant_and_keep_global_metadata() {
+    let mut world = world();
+    let (api, rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(rx));
+    let (events_tx, mut events) = tokio::sync::broadcast::channel(16);
+    world.insert_resource(SystemEventBus::new(events_tx));
+    plugin::install(
+        &mut world,
+        None,
+        plugin_wat("plugin.echo", ECHO, "").as_bytes(),
+    )
+    .unwrap();
+    let acme = TenantId::from("acme");
+    // Same length, so the packed metadata length still holds.
+    let custom = plugin_wat("plugin.echo", ECHO, "").replace("Echo", "Acme");
+    plugin::install(&mut world, Some(acme.clone()), custom.as_bytes()).unwrap();
+
+    let registry = world.resource::<NodeRegistry>();
+    assert_eq!(registry.get("plugin.echo").unwrap().metadata().name, "Echo");
+    assert_eq!(
+        registry
+            .get_for(Some(&acme), "plugin.echo")
+            .unwrap()
+            .metadata()
+            .name,
+        "Acme"
+    );
+
+    let list = |tenant: &str| {
+        let (reply, reply_rx) = Reply::channel();
+        let tenant = TenantId::from(tenant);
+        api.try_send(ApiRequest::new(
+            Principal::new("dev").with_membership(tenant.clone(), Role::Viewer),
+            ApiCommand::ListNodeTypes {
+                tenant_id: tenant,
+                query: "echo".to_string(),
+                category: None,
+                reply,
+            },
+        ))
+        .unwrap();
+        reply_rx
+    };
+    let mut acme_rx = list("acme");
+    let mut globex_rx = list("globex");
+    api_command_worker(&mut world);
+    let names = |rx: &mut tokio::sync::oneshot::Receiver<Result<Vec<NodeMetadata>, String>>| {
+        let types = rx.try_recv().unwrap().unwrap();
+        types.into_iter().map(|meta| meta.name).collect::<Vec<_>>()
+    };
+    assert_eq!(names(&mut acme_rx), ["Acme"]);
+    assert_eq!(names(&mut globex_rx), ["Echo"]);
+
+    // Only the tenant install is announced to the tenant.
+    let tenants: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter_map(|event| match event {
+            SystemEvent::RegistryChanged { tenant_id, .. } => Some(tenant_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(tenants, [None, Some("acme".to_string())]);
+}
+
+#[test]
+fn test_tenant_plugins_are_saved_and_loaded_back_at_startup() {
+    let dir = std::env::temp_dir().join(format!("plugins_{}", uuid::Uuid::new_v4()));
+    let acme = TenantId::from("acme/../corp");
+    let mut first = world();
+    first.insert_resource(plugin::PluginDir(dir.clone()));
+    plugin::install(
+        &mut first,
+        Some(acme.clone()),
+        plugin_wat("plugin.echo", ECHO, "").as_bytes(),
+    )
+    .unwrap();
+    let tenant_dirs: Vec<_> = std::fs::read_dir(dir.join("tenants"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(tenant_dirs.len(), 1);
+
+    let mut restarted = world();
+    let loaded = plugin::load_dir(&mut restarted, &dir);
+    assert_eq!(loaded.len(), 1);
+    let registry = restarted.resource::<NodeRegistry>();
+    assert!(registry.get_for(Some(&acme), "plugin.echo").is_some());
+    assert!(registry.get("plugin.echo").is_none());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_plugins_are_sandboxed_and_validated() {
+    let mut world = world();
+
+    // Runaway code runs out of fuel and the input goes to the error port.
+    let spin = plugin_wat(
+        "plugin.spin",
+        "(loop $forever (br $forever)) i64.const 0",
+        "",
+    );
+    plugin::install(&mut world, None, spin.as_bytes()).unwrap();
+    load_graph_from_str(
+        &mut world,
+        TenantId::from("acme"),
+        &graph("77777777-7777-7777-7777-777777777777", "plugin.spin"),
+    )
+    .unwrap();
+    let node = plugin_node(&mut world, "acme").expect("global plugins serve every tenant");
+    let (port, output) = run(&mut world, node, json!({ "n": 1 }));
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(output, json!({ "n": 1 }));
+
+    // A returned length beyond the plugin's memory is refused, not allocated.
+    let oversized = plugin_wat("plugin.oversized", "i64.const 0x00000000ffffffff", "");
+    plugin::install(&mut world, None, oversized.as_bytes()).unwrap();
+    load_graph_from_str(
+        &mut world,
+        TenantId::from("initech"),
+        &graph("99999999-9999-9999-9999-999999999999", "plugin.oversized"),
+    )
+    .unwrap();
+    let node = plugin_node(&mut world, "initech").expect("plugin node should be built");
+    let (port, output) = run(&mut world, node, json!({ "n": 2 }));
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(output, json!({ "n": 2 }));
+
+    // `build` can reject a node's settings.
+    let reject = plugin_wat(
+        "plugin.strict",
+        ECHO,
+        r#"(data (i32.const 2048) "{\"error\":\"bad settings\"}")
+  (func (export "build") (param i32 i32) (result i64)
+    i64.const 0x0000080000000018)"#,
+    );
+    plugin::install(&mut world, None, reject.as_bytes()).unwrap();
+    load_graph_from_str(
+        &mut world,
+        TenantId::from("globex"),
+        &graph("88888888-8888-8888-8888-888888888888", "plugin.strict"),
+    )
+    .unwrap();
+    assert!(plugin_node(&mut world, "globex").is_none());
+
+    // Plugins can't take over built-in node types, nor load garbage.
+    let note = plugin_wat("note", ECHO, "");
+    assert!(plugin::install(&mut world, Some(TenantId::from("acme")), note.as_bytes()).is_err());
+    assert!(plugin::install(&mut world, None, b"not wasm").is_err());
+}
+
+#[test]
+fn test_install_plugin_reads_the_module_from_the_blob_store() {
+    let mut world = world();
+    let (api, rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(rx));
+    let ticket = world
+        .resource::<BlobStore>()
+        .check_in(plugin_wat("plugin.echo", ECHO, "").as_bytes())
+        .unwrap();
+
+    let acme = TenantId::from("acme");
+    let (reply, mut reply_rx) = Reply::channel();
+    api.try_send(ApiRequest::new(
+        Principal::new("dev").with_membership(acme.clone(), Role::Editor),
+        ApiCommand::InstallPlugin {
+            tenant_id: acme,
+            ticket: ticket.id,
+            reply,
+        },
+    ))
+    .unwrap();
+    api_command_worker(&mut world);
+
+    assert_eq!(reply_rx.try_recv().unwrap().unwrap().id, "plugin.echo");
+}
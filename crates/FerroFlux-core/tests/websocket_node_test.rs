@@ -0,0 +1,75 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::events::{SystemEvent, SystemEventBus};
+use ferroflux_core::components::connectors::{WebSocketConfig, WebSocketState};
+use ferroflux_core::components::core::{Inbox, NodeConfig, Outbox};
+use ferroflux_core::resources::TokioRuntime;
+use ferroflux_core::secrets::DatabaseSecretStore;
+use ferroflux_core::store::BlobStore;
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_core::systems::connectors::websocket_worker;
+use tokio::runtime::Runtime;
+
+#[test]
+fn test_websocket_blocked_host_reports_status_and_errors_sends() {
+    let rt = Runtime::new().unwrap();
+    let mut world = World::new();
+    world.insert_resource(BlobStore::default());
+    let (tx, mut rx) = tokio::sync::broadcast::channel(100);
+    world.insert_resource(SystemEventBus::new(tx));
+    world.insert_resource(TokioRuntime(rt.handle().clone()));
+    let store = rt
+        .block_on(PersistentStore::new("sqlite::memory:"))
+        .expect("Failed to init DB");
+    world.insert_resource(DatabaseSecretStore::new(store, vec![0; 32]));
+
+    let ticket = world
+        .resource::<BlobStore>()
+        .check_in(br#"{"subscribe": "prices"}"#)
+        .unwrap();
+    let mut inbox = Inbox::default();
+    inbox.queue.push_back(ticket.clone());
+
+    let node = world
+        .spawn((
+            WebSocketConfig {
+                // Internal addresses are rejected unless explicitly allowed.
+                url: "ws://169.254.169.254/stream".to_string(),
+                max_backoff_seconds: 60,
+                connection_slug: None,
+            },
+            WebSocketState::default(),
+            NodeConfig {
+                id: uuid::Uuid::new_v4(),
+                name: "Feed".to_string(),
+                node_type: "WebSocket".to_string(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            },
+            inbox,
+            Outbox::default(),
+        ))
+        .id();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(websocket_worker);
+    schedule.run(&mut world);
+
+    let state = world.get::<WebSocketState>(node).unwrap();
+    assert!(state.events.is_none());
+
+    let outbox = world.get::<Outbox>(node).unwrap();
+    assert_eq!(outbox.queue.len(), 1);
+    let (port, routed) = outbox.queue.front().unwrap();
+    assert_eq!(port.as_deref(), Some("error"));
+    assert_eq!(routed.id, ticket.id);
+
+    let Ok(SystemEvent::NodeTelemetry {
+        success, details, ..
+    }) = rx.try_recv()
+    else {
+        panic!("expected a status event");
+    };
+    assert!(!success);
+    assert_eq!(details["status"], "disconnected");
+}
@@ -14,7 +14,7 @@ fn test_window_rolling_mean() {
 
     // 1. Setup Resources
     let (tx, _) = tokio::sync::broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
     let store = BlobStore::default();
     world.insert_resource(store.clone());
 
@@ -36,6 +36,7 @@ fn test_window_rolling_mean() {
         node_type: "Window".to_string(),
         workflow_id: None,
         tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+        version: None,
     };
 
     let mut inbox = Inbox::default();
@@ -93,7 +94,7 @@ fn test_window_rolling_variance() {
     let mut schedule = Schedule::default();
 
     let (tx, _) = tokio::sync::broadcast::channel(10);
-    world.insert_resource(SystemEventBus(tx));
+    world.insert_resource(SystemEventBus::new(tx));
     let store = BlobStore::default();
     world.insert_resource(store.clone());
 
@@ -113,6 +114,7 @@ fn test_window_rolling_variance() {
         node_type: "Window".to_string(),
         workflow_id: None,
         tenant_id: Some(ferroflux_iam::TenantId::from("default_tenant")),
+        version: None,
     };
 
     let mut inbox = Inbox::default();
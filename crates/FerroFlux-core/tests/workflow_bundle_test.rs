@@ -0,0 +1,194 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::components::{Edge, NodeConfig};
+use ferroflux_core::graph_loader::{
+    BUNDLE_FORMAT_VERSION, ImportOptions, WorkflowBundle, export_workflow, import_workflow,
+    load_graph_from_str,
+};
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::prompts::{PromptLibrary, PromptRef};
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::store::database::PromptTemplate;
+use ferroflux_core::traits::node_factory::{NodeFactory, NodeMetadata};
+use ferroflux_iam::TenantId;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+#[derive(Component)]
+struct StoredConfig(Value);
+
+/// Keeps the config as given, like the YAML-defined nodes do.
+struct ConfigFactory;
+
+impl NodeFactory for ConfigFactory {
+    fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
+        entity.insert(StoredConfig(config.clone()));
+        Ok(())
+    }
+
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Value> {
+        world.get::<StoredConfig>(entity).map(|c| c.0.clone())
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            id: "Agent".to_string(),
+            name: "Agent".to_string(),
+            category: "AI".to_string(),
+            platform: None,
+            description: None,
+            inputs: vec![],
+            outputs: vec![],
+            settings: vec![],
+            version: None,
+            deprecated: None,
+        }
+    }
+}
+
+const WORKFLOW: &str = r#"
+id: "support"
+nodes:
+  - id: "11111111-1111-1111-1111-111111111111"
+    name: "Start"
+    type: "Agent"
+    config: {}
+  - id: "22222222-2222-2222-2222-222222222222"
+    name: "Triage"
+    type: "Agent"
+    config:
+      system_prompt_ref: "triage@1"
+      user_prompt_ref: "triage"
+      connection_slug: "acme-openai"
+      tools:
+        - connection_slug: "acme-crm"
+edges:
+  - source_id: "11111111-1111-1111-1111-111111111111"
+    target_id: "22222222-2222-2222-2222-222222222222"
+    label: null
+    source_handle: null
+    target_handle: null
+"#;
+
+fn template(name: &str, version: u32, text: &str) -> PromptTemplate {
+    PromptTemplate {
+        name: name.to_string(),
+        version,
+        template: text.to_string(),
+        variables: vec![],
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+    }
+}
+
+fn world(tenant: &TenantId, templates: Vec<PromptTemplate>) -> World {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    let mut registry = NodeRegistry::default();
+    registry.register("Agent", Box::new(ConfigFactory));
+    world.insert_resource(registry);
+    let mut library = PromptLibrary::default();
+    for t in templates {
+        library.insert(tenant, t);
+    }
+    world.insert_resource(library);
+    world
+}
+
+fn node_config(world: &mut World, id: uuid::Uuid) -> (NodeConfig, Value) {
+    world
+        .query::<(&NodeConfig, &StoredConfig)>()
+        .iter(world)
+        .find(|(conf, _)| conf.id == id)
+        .map(|(conf, config)| (conf.clone(), config.0.clone()))
+        .unwrap()
+}
+
+#[test]
+fn test_bundles_carry_prompts_and_remap_ids_slugs_and_pinned_versions() {
+    let acme = TenantId::from("acme");
+    let mut source = world(
+        &acme,
+        vec![
+            template("triage", 1, "Sort {{ticket}}"),
+            template("triage", 2, "Sort {{ticket}} by urgency"),
+            template("unused", 1, "Not exported"),
+        ],
+    );
+    load_graph_from_str(&mut source, acme.clone(), WORKFLOW).unwrap();
+
+    let bundle = export_workflow(&mut source, &acme, "support").unwrap();
+    assert_eq!(bundle.format_version, BUNDLE_FORMAT_VERSION);
+    assert_eq!(bundle.workflow.nodes.len(), 2);
+    assert_eq!(bundle.workflow.edges.len(), 1);
+    assert_eq!(bundle.connections, ["acme-crm", "acme-openai"]);
+    let prompts: Vec<_> = bundle.prompts.iter().map(|t| t.version).collect();
+    assert_eq!(prompts, [1, 2]);
+    assert!(export_workflow(&mut source, &TenantId::from("globex"), "support").is_err());
+
+    // Both formats round-trip.
+    let yaml = serde_yaml::to_string(&bundle).unwrap();
+    let json = serde_json::to_string(&bundle).unwrap();
+    let bundle: WorkflowBundle = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(serde_json::to_string(&bundle).unwrap(), json);
+
+    let globex = TenantId::from("globex");
+    let mut target = world(&globex, vec![template("triage", 1, "Something else")]);
+    let options = ImportOptions {
+        connections: HashMap::from([("acme-openai".to_string(), "globex-openai".to_string())]),
+        ..Default::default()
+    };
+    let imported = import_workflow(&mut target, globex.clone(), bundle.clone(), &options).unwrap();
+    assert_eq!(imported.workflow_id, "support");
+
+    let triage = "22222222-2222-2222-2222-222222222222".parse().unwrap();
+    let new_id = imported.node_ids[&triage];
+    assert_ne!(new_id, triage);
+    let (conf, config) = node_config(&mut target, new_id);
+    assert_eq!(conf.tenant_id, Some(globex.clone()));
+    assert_eq!(conf.workflow_id.as_deref(), Some("support"));
+    assert_eq!(
+        config,
+        json!({
+            "system_prompt_ref": "triage@2",
+            "user_prompt_ref": "triage",
+            "connection_slug": "globex-openai",
+            "tools": [{ "connection_slug": "acme-crm" }],
+        })
+    );
+    let library = target.resource::<PromptLibrary>();
+    let pinned = library
+        .resolve(&globex, &"triage@2".parse::<PromptRef>().unwrap())
+        .unwrap();
+    assert_eq!(pinned.template, "Sort {{ticket}}");
+    let latest = library
+        .resolve(&globex, &"triage".parse::<PromptRef>().unwrap())
+        .unwrap();
+    assert_eq!(
+        (latest.version, latest.template.as_str()),
+        (3, "Sort {{ticket}} by urgency")
+    );
+    assert_eq!(target.query::<&Edge>().iter(&target).count(), 1);
+
+    // Importing again replaces the workflow and reuses the imported templates.
+    let again = import_workflow(&mut target, globex.clone(), bundle, &options).unwrap();
+    assert_eq!(again.node_ids, imported.node_ids);
+    assert_eq!(target.query::<&NodeConfig>().iter(&target).count(), 2);
+    assert_eq!(target.resource::<PromptLibrary>().list(&globex).len(), 3);
+}
+
+#[test]
+fn test_bundles_from_newer_engines_are_rejected() {
+    let acme = TenantId::from("acme");
+    let mut source = world(&acme, vec![template("triage", 1, "Sort")]);
+    load_graph_from_str(&mut source, acme.clone(), WORKFLOW).unwrap();
+    let mut bundle = export_workflow(&mut source, &acme, "support").unwrap();
+    bundle.format_version = BUNDLE_FORMAT_VERSION + 1;
+
+    let mut target = world(&acme, vec![]);
+    let options = ImportOptions {
+        workflow_id: Some("support-copy".to_string()),
+        ..Default::default()
+    };
+    let err = import_workflow(&mut target, acme, bundle, &options).unwrap_err();
+    assert!(err.to_string().contains("format version"));
+    assert_eq!(target.query::<&NodeConfig>().iter(&target).count(), 0);
+}
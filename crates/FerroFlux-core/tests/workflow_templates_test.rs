@@ -0,0 +1,176 @@
+use bevy_ecs::prelude::*;
+use ferroflux_core::api::{ApiCommand, ApiReceiver, ApiRequest, Reply};
+use ferroflux_core::components::NodeConfig;
+use ferroflux_core::resources::NodeRouter;
+use ferroflux_core::resources::registry::NodeRegistry;
+use ferroflux_core::systems::api_worker::api_command_worker;
+use ferroflux_core::templates::TemplateLibrary;
+use ferroflux_core::traits::node_factory::{NodeFactory, NodeMetadata};
+use ferroflux_iam::{Principal, Role, TenantId};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+#[derive(Component)]
+struct StoredConfig(Value);
+
+struct ConfigFactory;
+
+impl NodeFactory for ConfigFactory {
+    fn build(&self, entity: &mut EntityWorldMut, config: &Value) -> anyhow::Result<()> {
+        entity.insert(StoredConfig(config.clone()));
+        Ok(())
+    }
+
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Value> {
+        world.get::<StoredConfig>(entity).map(|c| c.0.clone())
+    }
+
+    fn metadata(&self) -> NodeMetadata {
+        NodeMetadata {
+            id: "Agent".to_string(),
+            name: "Agent".to_string(),
+            category: "AI".to_string(),
+            platform: None,
+            description: None,
+            inputs: vec![],
+            outputs: vec![],
+            settings: vec![],
+            version: None,
+            deprecated: None,
+        }
+    }
+}
+
+const TRIAGE: &str = r#"
+id: "support-triage"
+name: "Support triage"
+category: "Customer Support"
+description: "Classifies incoming tickets."
+connections:
+  - slug: "llm"
+    platform: "openai"
+nodes:
+  - id: "11111111-1111-1111-1111-111111111111"
+    name: "Classify"
+    type: "Agent"
+    config:
+      connection_slug: "llm"
+edges: []
+"#;
+
+const FOLLOW_UP: &str = r#"
+id: "lead-follow-up"
+name: "Lead follow-up"
+category: "Sales"
+nodes:
+  - id: "22222222-2222-2222-2222-222222222222"
+    name: "Draft"
+    type: "Agent"
+    config: {}
+edges: []
+"#;
+
+fn setup() -> (World, async_channel::Sender<ApiRequest>) {
+    let mut world = World::new();
+    world.insert_resource(NodeRouter::default());
+    let mut registry = NodeRegistry::default();
+    registry.register("Agent", Box::new(ConfigFactory));
+    world.insert_resource(registry);
+
+    let mut templates = TemplateLibrary::default();
+    templates.register_yaml(TRIAGE).unwrap();
+    templates.register_yaml(FOLLOW_UP).unwrap();
+    world.insert_resource(templates);
+
+    let (tx, rx) = async_channel::unbounded();
+    world.insert_resource(ApiReceiver(rx));
+    (world, tx)
+}
+
+fn instantiate(
+    world: &mut World,
+    api: &async_channel::Sender<ApiRequest>,
+    principal: Principal,
+    connections: &[(&str, &str)],
+) -> Option<Result<String, String>> {
+    let (reply, mut rx) = Reply::channel();
+    api.try_send(ApiRequest::new(
+        principal,
+        ApiCommand::InstantiateTemplate {
+            tenant_id: TenantId::from("acme"),
+            template_id: "support-triage".to_string(),
+            workflow_id: None,
+            connections: connections
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            reply,
+        },
+    ))
+    .unwrap();
+    api_command_worker(world);
+    rx.try_recv().ok()
+}
+
+#[test]
+fn test_templates_are_listed_by_category() {
+    let (mut world, api) = setup();
+    let acme = TenantId::from("acme");
+    let viewer = Principal::new("vera").with_membership(acme.clone(), Role::Viewer);
+
+    let (reply, mut rx) = Reply::channel();
+    api.try_send(ApiRequest::new(
+        viewer,
+        ApiCommand::ListTemplates {
+            tenant_id: acme,
+            reply,
+        },
+    ))
+    .unwrap();
+    api_command_worker(&mut world);
+
+    let catalog = rx.try_recv().unwrap().unwrap();
+    let categories: Vec<&str> = catalog.keys().map(String::as_str).collect();
+    assert_eq!(categories, ["Customer Support", "Sales"]);
+    let triage = &catalog["Customer Support"][0];
+    assert_eq!(triage.id, "support-triage");
+    assert_eq!(triage.node_count, 1);
+    assert_eq!(triage.connections[0].platform.as_deref(), Some("openai"));
+}
+
+#[test]
+fn test_instances_bind_connections_and_get_fresh_node_ids() {
+    let (mut world, api) = setup();
+    let acme = TenantId::from("acme");
+    let editor = Principal::new("eddie").with_membership(acme.clone(), Role::Editor);
+
+    let missing = instantiate(&mut world, &api, editor.clone(), &[]).unwrap();
+    assert!(missing.unwrap_err().contains("llm"));
+    let viewer = Principal::new("vera").with_membership(acme.clone(), Role::Viewer);
+    let denied = instantiate(&mut world, &api, viewer, &[("llm", "acme-openai")]).unwrap();
+    assert!(denied.is_err());
+    assert_eq!(world.query::<&NodeConfig>().iter(&world).count(), 0);
+
+    let bindings = [("llm", "acme-openai")];
+    let first = instantiate(&mut world, &api, editor.clone(), &bindings)
+        .unwrap()
+        .unwrap();
+    let second = instantiate(&mut world, &api, editor, &bindings)
+        .unwrap()
+        .unwrap();
+    assert!(first.starts_with("support-triage-"));
+    assert_ne!(first, second);
+
+    let nodes: Vec<(NodeConfig, Value)> = world
+        .query::<(&NodeConfig, &StoredConfig)>()
+        .iter(&world)
+        .map(|(conf, config)| (conf.clone(), config.0.clone()))
+        .collect();
+    assert_eq!(nodes.len(), 2);
+    assert_ne!(nodes[0].0.id, nodes[1].0.id);
+    for (conf, config) in &nodes {
+        assert_ne!(conf.id.to_string(), "11111111-1111-1111-1111-111111111111");
+        assert_eq!(conf.tenant_id, Some(acme.clone()));
+        assert_eq!(config, &json!({ "connection_slug": "acme-openai" }));
+    }
+}
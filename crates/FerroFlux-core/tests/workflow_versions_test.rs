@@ -0,0 +1,89 @@
+use ferroflux_core::store::database::PersistentStore;
+use ferroflux_iam::{IamStore, TenantId};
+
+fn blueprint(node_name: &str) -> String {
+    format!(
+        r#"{{"id":"orders","nodes":[{{"id":"11111111-1111-1111-1111-111111111111","name":"{node_name}","type":"Webhook","config":{{}}}}],"edges":[]}}"#
+    )
+}
+
+/// A store sharing its database with IAM, which owns the `tenants` table, and two tenants.
+async fn setup() -> (PersistentStore, TenantId, TenantId) {
+    let path = std::env::temp_dir().join(format!("versions_{}.db", uuid::Uuid::new_v4()));
+    let url = format!("sqlite://{}", path.display());
+    let iam = IamStore::new(&url).await.unwrap();
+    let (_, user) = iam.create_magic_link("ada@example.com").await.unwrap();
+    let acme = iam.create_organization(&user, "Acme").await.unwrap();
+    let globex = iam.create_organization(&user, "Globex").await.unwrap();
+    (PersistentStore::new(&url).await.unwrap(), acme, globex)
+}
+
+#[tokio::test]
+async fn test_every_save_is_a_version_and_rollbacks_restore_them() {
+    let (store, acme, globex) = setup().await;
+
+    for (i, name) in ["Start", "Broken"].into_iter().enumerate() {
+        let version = store
+            .save_workflow(&acme, "orders", "Orders", None, &blueprint(name), "active")
+            .await
+            .unwrap();
+        assert_eq!(version, i as u32 + 1);
+    }
+    store
+        .save_workflow(&globex, "invoices", "Invoices", None, "{}", "active")
+        .await
+        .unwrap();
+
+    let versions = store.list_versions(&acme, "orders").await.unwrap();
+    let numbers: Vec<u32> = versions.iter().map(|v| v.version).collect();
+    assert_eq!(numbers, [2, 1]);
+    assert_eq!(versions[1].blueprint_json, blueprint("Start"));
+    assert!(
+        store
+            .get_version(&acme, "orders", 3)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        store
+            .get_version(&globex, "orders", 1)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let restored = store.rollback_to_version(&acme, "orders", 1).await.unwrap();
+    assert_eq!(restored.version, 3);
+    assert_eq!(restored.blueprint_json, blueprint("Start"));
+    let (.., json, status) = store.get_workflow(&acme, "orders").await.unwrap().unwrap();
+    assert_eq!((json, status.as_str()), (blueprint("Start"), "active"));
+    assert!(store.rollback_to_version(&acme, "orders", 9).await.is_err());
+}
+
+#[tokio::test]
+async fn test_deleted_workflows_can_be_restored() {
+    let (store, acme, _) = setup().await;
+    store
+        .save_workflow(
+            &acme,
+            "orders",
+            "Orders",
+            Some("Order intake"),
+            &blueprint("Start"),
+            "inactive",
+        )
+        .await
+        .unwrap();
+    store.delete_workflow(&acme, "orders").await.unwrap();
+    assert!(store.get_workflow(&acme, "orders").await.unwrap().is_none());
+
+    let restored = store.rollback_to_version(&acme, "orders", 1).await.unwrap();
+    assert_eq!(restored.description.as_deref(), Some("Order intake"));
+    let (_, name, description, _, status) =
+        store.get_workflow(&acme, "orders").await.unwrap().unwrap();
+    assert_eq!(name, "Orders");
+    assert_eq!(description.as_deref(), Some("Order intake"));
+    // A deleted workflow's status is gone; it comes back active.
+    assert_eq!(status, "active");
+}
@@ -0,0 +1,68 @@
+//! Frame cost of `Canvas::update` on synthetic graphs.
+//!
+//! Nodes sit on a grid, each wired to its right-hand neighbour. `viewport` renders at 100%
+//! zoom, so most of the graph is off screen; `overview` zooms out until all of it is visible.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use flow_canvas::input::InputState;
+use flow_canvas::model::{GraphState, Node, NodeFlags, NodeId, Uuid};
+use flow_canvas::{Canvas, CanvasConfig};
+use glam::Vec2;
+use std::hint::black_box;
+
+const COLUMNS: usize = 100;
+
+fn graph(nodes: usize) -> GraphState<String> {
+    let mut graph = GraphState::default();
+    let mut previous = None;
+    for i in 0..nodes {
+        let id = graph.insert_node(Node {
+            id: NodeId::default(),
+            uuid: Uuid::new_v4(),
+            position: Vec2::new((i % COLUMNS) as f32 * 180.0, (i / COLUMNS) as f32 * 120.0),
+            size: Vec2::new(140.0, 80.0),
+            inputs: vec![],
+            outputs: vec![],
+            data: format!("Node {}", i),
+            flags: NodeFlags::default(),
+            style: None,
+        });
+        let input = graph.add_port(id, true);
+        let output = graph.add_port(id, false);
+        if i % COLUMNS != 0
+            && let Some(previous) = previous
+        {
+            graph.connect(previous, input);
+        }
+        previous = Some(output);
+    }
+    graph
+}
+
+fn canvas(zoom: f32) -> Canvas {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    canvas.update_viewport_size(Vec2::new(1920.0, 1080.0));
+    canvas.view.transform.zoom = zoom;
+    canvas
+}
+
+fn bench_update(c: &mut Criterion) {
+    let input = InputState {
+        screen_size: Vec2::new(1920.0, 1080.0),
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("canvas_update");
+    for nodes in [1_000, 10_000] {
+        let mut graph = graph(nodes);
+        for (view, zoom) in [("viewport", 1.0), ("overview", 0.1)] {
+            let mut canvas = canvas(zoom);
+            group.bench_with_input(BenchmarkId::new(view, nodes), &nodes, |b, _| {
+                b.iter(|| black_box(canvas.update(&input, 0.016, &mut graph)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_update);
+criterion_main!(benches);
@@ -125,6 +125,9 @@ fn main() {
             pressed_keys: Vec::new(),
             screen_size: Vec2::new(1280.0, 720.0),
             event_consumed_by_content: false,
+            touches: Vec::new(),
+            pan_delta: Vec2::ZERO,
+            pinch_delta: 0.0,
         };
 
         // Update Canvas
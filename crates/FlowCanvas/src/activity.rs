@@ -0,0 +1,89 @@
+//! # Live Activity
+//!
+//! Execution feedback while a graph runs. Nodes that reported recently carry
+//! `NodeFlags::RUNNING` or `NodeFlags::ERROR`, and connections a ticket just crossed carry a
+//! pulse whose progress runs from 0.0 to 1.0 as the painter moves it along the wire.
+//!
+//! The host reports engine activity through the `GraphState` methods below; `Canvas::update`
+//! ages it by the frame time, and flags and pulses expire after
+//! `CanvasConfig::activity_decay` seconds.
+
+use crate::model::{ConnectionId, GraphState, NodeFlags, NodeId};
+use std::collections::HashMap;
+
+/// Transient execution state of a graph. Never persisted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LiveActivity {
+    /// Seconds since each flagged node last reported.
+    nodes: HashMap<NodeId, f32>,
+    /// Progress (0.0 - 1.0) of the pulse on each traversed connection.
+    pub pulses: HashMap<ConnectionId, f32>,
+}
+
+impl LiveActivity {
+    /// Progress of the pulse on a connection, if one is travelling along it.
+    pub fn pulse(&self, id: ConnectionId) -> Option<f32> {
+        self.pulses.get(&id).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.pulses.is_empty()
+    }
+
+    pub(crate) fn forget_node(&mut self, id: NodeId) {
+        self.nodes.remove(&id);
+    }
+}
+
+impl<T> GraphState<T> {
+    /// Flags a node as running, clearing an earlier error.
+    pub fn mark_node_running(&mut self, id: NodeId) {
+        self.set_live_flag(id, NodeFlags::RUNNING);
+    }
+
+    /// Flags a node as failed.
+    pub fn mark_node_failed(&mut self, id: NodeId) {
+        self.set_live_flag(id, NodeFlags::ERROR);
+    }
+
+    fn set_live_flag(&mut self, id: NodeId, flag: NodeFlags) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.flags.remove(NodeFlags::LIVE);
+            node.flags.insert(flag);
+            self.activity.nodes.insert(id, 0.0);
+        }
+    }
+
+    /// Starts a pulse on a connection, restarting one already underway.
+    pub fn pulse_connection(&mut self, id: ConnectionId) {
+        if self.connections.contains_key(id) {
+            self.activity.pulses.insert(id, 0.0);
+        }
+    }
+
+    /// Ages live activity by `dt` seconds: pulses advance, and flags and pulses older than
+    /// `decay` seconds are cleared.
+    pub fn advance_activity(&mut self, dt: f32, decay: f32) {
+        if self.activity.is_empty() {
+            return;
+        }
+        let decay = decay.max(f32::EPSILON);
+
+        self.activity.pulses.retain(|_, progress| {
+            *progress += dt / decay;
+            *progress < 1.0
+        });
+
+        let nodes = &mut self.nodes;
+        self.activity.nodes.retain(|id, age| {
+            *age += dt;
+            if *age < decay {
+                return true;
+            }
+            if let Some(node) = nodes.get_mut(*id) {
+                node.flags.remove(NodeFlags::LIVE);
+            }
+            false
+        });
+    }
+}
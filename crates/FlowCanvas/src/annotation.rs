@@ -0,0 +1,210 @@
+//! # Annotations
+//!
+//! Non-executable elements that document a graph: comment boxes (sticky notes) holding free
+//! text, and group frames that visually contain nodes. Annotations live in their own arena on
+//! `GraphState`, are drawn behind wires and nodes, and never reach the engine.
+//!
+//! Dragging a group frame by its header moves every node (and annotation) fully inside it.
+//! Both kinds are resized from their bottom-right corner.
+
+use crate::math::Rect;
+use crate::model::{AnnotationId, GraphState, NodeId};
+use glam::{Vec2, Vec4};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Height of a group frame's title bar in world units. Groups are dragged by their header so
+/// clicks on the empty space inside them still start a box selection.
+pub const GROUP_HEADER_HEIGHT: f32 = 24.0;
+
+/// Side of the square resize handle in the bottom-right corner, in world units.
+pub const RESIZE_HANDLE_SIZE: f32 = 12.0;
+
+/// Smallest size an annotation can be resized to, in world units.
+pub const MIN_ANNOTATION_SIZE: Vec2 = Vec2::new(60.0, 40.0);
+
+/// What an annotation shows.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AnnotationKind {
+    /// A sticky note with free text.
+    Comment { text: String },
+    /// A titled frame around a set of nodes.
+    Group { title: String },
+}
+
+/// A comment box or group frame.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Self-reference ID.
+    pub id: AnnotationId,
+    /// Stable UUID for persistence.
+    pub uuid: Uuid,
+    /// World-space position of the top-left corner.
+    pub position: Vec2,
+    /// Size of the box or frame.
+    pub size: Vec2,
+    pub kind: AnnotationKind,
+    /// Optional fill color override.
+    pub color: Option<Vec4>,
+}
+
+impl Annotation {
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.position, self.size)
+    }
+
+    pub fn is_group(&self) -> bool {
+        matches!(self.kind, AnnotationKind::Group { .. })
+    }
+
+    /// Which part of the annotation, if any, is under `point`.
+    pub fn hit(&self, point: Vec2) -> Option<AnnotationHit> {
+        let rect = self.rect();
+        if !rect.contains(point) {
+            return None;
+        }
+        let handle = Rect::new(
+            rect.max - Vec2::splat(RESIZE_HANDLE_SIZE),
+            Vec2::splat(RESIZE_HANDLE_SIZE),
+        );
+        if handle.contains(point) {
+            Some(AnnotationHit::ResizeHandle)
+        } else if !self.is_group() || point.y <= self.position.y + GROUP_HEADER_HEIGHT {
+            Some(AnnotationHit::Body)
+        } else {
+            None
+        }
+    }
+}
+
+/// The part of an annotation a pointer hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotationHit {
+    /// The draggable area: the whole box for comments, the header for groups.
+    Body,
+    /// The bottom-right resize handle.
+    ResizeHandle,
+}
+
+impl<T> GraphState<T> {
+    /// Adds a comment box with `text`.
+    pub fn add_comment(&mut self, position: Vec2, size: Vec2, text: &str) -> AnnotationId {
+        self.add_annotation(
+            position,
+            size,
+            AnnotationKind::Comment {
+                text: text.to_string(),
+            },
+        )
+    }
+
+    /// Adds a group frame titled `title`.
+    pub fn add_group(&mut self, position: Vec2, size: Vec2, title: &str) -> AnnotationId {
+        self.add_annotation(
+            position,
+            size,
+            AnnotationKind::Group {
+                title: title.to_string(),
+            },
+        )
+    }
+
+    /// Adds a group frame around `nodes`, leaving `padding` on each side and room for the header.
+    /// Returns `None` if none of the nodes exist.
+    pub fn group_nodes(
+        &mut self,
+        nodes: &[NodeId],
+        title: &str,
+        padding: f32,
+    ) -> Option<AnnotationId> {
+        let (min, max) = nodes
+            .iter()
+            .filter_map(|id| self.nodes.get(*id))
+            .map(|node| (node.position, node.position + node.visible_size()))
+            .reduce(|(min, max), (lo, hi)| (min.min(lo), max.max(hi)))?;
+        let min = min - Vec2::new(padding, padding + GROUP_HEADER_HEIGHT);
+        let max = max + Vec2::splat(padding);
+        Some(self.add_group(min, max - min, title))
+    }
+
+    pub fn add_annotation(
+        &mut self,
+        position: Vec2,
+        size: Vec2,
+        kind: AnnotationKind,
+    ) -> AnnotationId {
+        self.mark_changed();
+        self.annotations.insert_with_key(|id| Annotation {
+            id,
+            uuid: Uuid::new_v4(),
+            position,
+            size: size.max(MIN_ANNOTATION_SIZE),
+            kind,
+            color: None,
+        })
+    }
+
+    pub fn remove_annotation(&mut self, id: AnnotationId) -> Option<Annotation> {
+        let removed = self.annotations.remove(id);
+        if removed.is_some() {
+            self.mark_changed();
+        }
+        removed
+    }
+
+    /// The topmost annotation under `point`. Comments sit above groups, and smaller groups above
+    /// the larger ones they are nested in.
+    pub fn annotation_at(&self, point: Vec2) -> Option<(AnnotationId, AnnotationHit)> {
+        self.annotations
+            .iter()
+            .filter_map(|(id, annotation)| Some((id, annotation, annotation.hit(point)?)))
+            .max_by(|(_, a, _), (_, b, _)| stacking(a).total_cmp(&stacking(b)))
+            .map(|(id, _, hit)| (id, hit))
+    }
+
+    /// Annotations in painting order, back to front.
+    pub fn annotation_draw_order(&self) -> Vec<AnnotationId> {
+        let mut order: Vec<_> = self.annotations.keys().collect();
+        order.sort_by(|a, b| {
+            stacking(&self.annotations[*a]).total_cmp(&stacking(&self.annotations[*b]))
+        });
+        order
+    }
+
+    /// Nodes lying entirely inside an annotation's frame.
+    pub fn nodes_inside(&self, id: AnnotationId) -> Vec<NodeId> {
+        let Some(frame) = self.annotations.get(id).map(Annotation::rect) else {
+            return Vec::new();
+        };
+        self.nodes
+            .iter()
+            .filter(|(_, node)| contains_rect(&frame, &node.rect()))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Other annotations lying entirely inside an annotation's frame.
+    pub fn annotations_inside(&self, id: AnnotationId) -> Vec<AnnotationId> {
+        let Some(frame) = self.annotations.get(id).map(Annotation::rect) else {
+            return Vec::new();
+        };
+        self.annotations
+            .iter()
+            .filter(|(other, annotation)| *other != id && contains_rect(&frame, &annotation.rect()))
+            .map(|(other, _)| other)
+            .collect()
+    }
+}
+
+/// Paint height of an annotation: groups below comments, larger groups below smaller ones.
+fn stacking(annotation: &Annotation) -> f32 {
+    if annotation.is_group() {
+        -(annotation.size.x * annotation.size.y)
+    } else {
+        0.0
+    }
+}
+
+fn contains_rect(outer: &Rect, inner: &Rect) -> bool {
+    outer.contains(inner.min) && outer.contains(inner.max)
+}
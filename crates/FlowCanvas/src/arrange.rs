@@ -0,0 +1,159 @@
+//! # Arranging
+//!
+//! Alignment, distribution and nudging of the selected nodes, for tidying a diagram. Locked nodes
+//! neither move nor serve as a reference. Each operation returns the `LogicEvent::NodesMoved`
+//! events for the nodes it moved, one per distinct offset, so hosts can sync positions to the
+//! engine and record history the same way they do for drags.
+
+use crate::interaction::LogicEvent;
+use crate::model::{GraphState, NodeFlags, NodeId};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Edge or center line to line the selection up on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alignment {
+    /// Left edges on the leftmost edge.
+    Left,
+    /// Right edges on the rightmost edge.
+    Right,
+    /// Top edges on the topmost edge.
+    Top,
+    /// Bottom edges on the bottommost edge.
+    Bottom,
+    /// Horizontal centers on the center of the selection's bounds.
+    CenterX,
+    /// Vertical centers on the center of the selection's bounds.
+    CenterY,
+}
+
+/// Axis along which to space the selection evenly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Distribution {
+    Horizontal,
+    Vertical,
+}
+
+impl<T> GraphState<T> {
+    /// Aligns the selected, unlocked nodes. Needs at least two of them.
+    pub fn align_selected(&mut self, alignment: Alignment) -> Vec<LogicEvent> {
+        let selection = self.arrangeable();
+        if selection.len() < 2 {
+            return Vec::new();
+        }
+        let (min, max) = self.selection_bounds(&selection);
+        let center = (min + max) * 0.5;
+
+        let targets = selection
+            .iter()
+            .map(|id| {
+                let node = &self.nodes[*id];
+                let (pos, size) = (node.position, node.visible_size());
+                let target = match alignment {
+                    Alignment::Left => Vec2::new(min.x, pos.y),
+                    Alignment::Right => Vec2::new(max.x - size.x, pos.y),
+                    Alignment::Top => Vec2::new(pos.x, min.y),
+                    Alignment::Bottom => Vec2::new(pos.x, max.y - size.y),
+                    Alignment::CenterX => Vec2::new(center.x - size.x * 0.5, pos.y),
+                    Alignment::CenterY => Vec2::new(pos.x, center.y - size.y * 0.5),
+                };
+                (*id, target)
+            })
+            .collect();
+        self.move_nodes_to(targets)
+    }
+
+    /// Spaces the selected, unlocked nodes so the gaps between them are equal, keeping the
+    /// first and last in place. Needs at least three of them.
+    pub fn distribute_selected(&mut self, distribution: Distribution) -> Vec<LogicEvent> {
+        let mut selection = self.arrangeable();
+        if selection.len() < 3 {
+            return Vec::new();
+        }
+        let axis = match distribution {
+            Distribution::Horizontal => Vec2::X,
+            Distribution::Vertical => Vec2::Y,
+        };
+        let along = |v: Vec2| v.dot(axis);
+        selection.sort_by(|a, b| {
+            along(self.nodes[*a].position).total_cmp(&along(self.nodes[*b].position))
+        });
+
+        let (first, last) = (
+            &self.nodes[selection[0]],
+            &self.nodes[selection[selection.len() - 1]],
+        );
+        let span = along(last.position + last.visible_size()) - along(first.position);
+        let occupied: f32 = selection
+            .iter()
+            .map(|id| along(self.nodes[*id].visible_size()))
+            .sum();
+        let gap = (span - occupied) / (selection.len() - 1) as f32;
+
+        let mut cursor = along(first.position);
+        let mut targets = Vec::with_capacity(selection.len());
+        for id in selection {
+            let node = &self.nodes[id];
+            // Replace the coordinate along the axis, keep the other one
+            let target = node.position - axis * along(node.position) + axis * cursor;
+            targets.push((id, target));
+            cursor += along(node.visible_size()) + gap;
+        }
+        self.move_nodes_to(targets)
+    }
+
+    /// Moves the selected, unlocked nodes by `delta`, as the arrow keys do.
+    pub fn nudge_selected(&mut self, delta: Vec2) -> Vec<LogicEvent> {
+        let targets = self
+            .arrangeable()
+            .into_iter()
+            .map(|id| (id, self.nodes[id].position + delta))
+            .collect();
+        self.move_nodes_to(targets)
+    }
+
+    /// Selected nodes that may be moved.
+    fn arrangeable(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| {
+                node.flags.contains(NodeFlags::SELECTED) && !node.flags.contains(NodeFlags::LOCKED)
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    fn selection_bounds(&self, nodes: &[NodeId]) -> (Vec2, Vec2) {
+        nodes
+            .iter()
+            .map(|id| &self.nodes[*id])
+            .map(|node| (node.position, node.position + node.visible_size()))
+            .reduce(|(min, max), (lo, hi)| (min.min(lo), max.max(hi)))
+            .unwrap_or_default()
+    }
+
+    /// Moves nodes to their targets, grouping those that moved by the same offset into one
+    /// `NodesMoved` event.
+    fn move_nodes_to(&mut self, targets: Vec<(NodeId, Vec2)>) -> Vec<LogicEvent> {
+        let mut moves: Vec<(Vec2, Vec<NodeId>)> = Vec::new();
+        for (id, target) in targets {
+            let node = &mut self.nodes[id];
+            let delta = target - node.position;
+            if delta == Vec2::ZERO {
+                continue;
+            }
+            node.position = target;
+            match moves.iter_mut().find(|(d, _)| *d == delta) {
+                Some((_, ids)) => ids.push(id),
+                None => moves.push((delta, vec![id])),
+            }
+        }
+        if !moves.is_empty() {
+            self.mark_changed();
+        }
+        moves
+            .into_iter()
+            .map(|(delta, ids)| LogicEvent::NodesMoved { ids, delta })
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+//! # Clipboard
+//!
+//! Copy, cut and paste of node selections. A [`ClipboardGraph`] is a snippet of the selected
+//! nodes and the connections between them; pasting it creates fresh nodes, ports and UUIDs, so
+//! the same clipboard can be pasted any number of times.
+//!
+//! The canvas turns Ctrl/Cmd+C, X, V and D into [`LogicEvent::CopySelection`],
+//! [`LogicEvent::CutSelection`], [`LogicEvent::Paste`] and [`LogicEvent::DuplicateSelection`].
+//! The host owns the clipboard (it may serialize it to the system clipboard) and answers those
+//! requests with the methods below.
+
+use crate::interaction::LogicEvent;
+use crate::model::{GraphState, NodeData, NodeFlags, NodeId};
+use crate::persistence::SavedGraph;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A copied selection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipboardGraph<T> {
+    /// The copied nodes and their internal connections, relative to `origin`.
+    pub snippet: SavedGraph<T>,
+    /// World-space top-left corner of the selection when it was copied.
+    pub origin: Vec2,
+}
+
+impl<T> ClipboardGraph<T> {
+    pub fn is_empty(&self) -> bool {
+        self.snippet.nodes.is_empty()
+    }
+}
+
+impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
+    /// Selected nodes, in arena order.
+    pub fn selected_nodes(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| node.flags.contains(NodeFlags::SELECTED))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Copies the selected nodes. Connections are kept only when both ends are selected.
+    pub fn copy_selection(&self) -> ClipboardGraph<T> {
+        let selection = self.selected_nodes();
+        let origin = selection
+            .iter()
+            .map(|id| self.nodes[*id].position)
+            .reduce(Vec2::min)
+            .unwrap_or(Vec2::ZERO);
+        ClipboardGraph {
+            snippet: self.save_selection(&selection),
+            origin,
+        }
+    }
+
+    /// Copies the selected nodes, then deletes them. Locked nodes are copied but stay in place.
+    pub fn cut_selection(&mut self) -> ClipboardGraph<T> {
+        let clipboard = self.copy_selection();
+        for id in self.selected_nodes() {
+            if !self.nodes[id].flags.contains(NodeFlags::LOCKED) {
+                self.delete_node(id);
+            }
+        }
+        clipboard
+    }
+
+    /// Pastes a clipboard `offset` away from where it was copied, selecting the new nodes in
+    /// place of the current selection. Returns a [`LogicEvent::Pasted`] listing them.
+    pub fn paste(&mut self, clipboard: &ClipboardGraph<T>, offset: Vec2) -> LogicEvent {
+        for node in self.nodes.values_mut() {
+            node.flags.remove(NodeFlags::SELECTED);
+        }
+        let ids = self.instantiate(clipboard.snippet.clone(), clipboard.origin + offset);
+        for id in &ids {
+            self.nodes[*id].flags.insert(NodeFlags::SELECTED);
+        }
+        LogicEvent::Pasted { ids }
+    }
+
+    /// Pastes a copy of the selection `offset` away from it without touching the clipboard,
+    /// selecting the copies. Returns a [`LogicEvent::Pasted`] listing them.
+    pub fn duplicate_selection(&mut self, offset: Vec2) -> LogicEvent {
+        let copy = self.copy_selection();
+        self.paste(&copy, offset)
+    }
+}
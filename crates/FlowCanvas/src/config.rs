@@ -2,7 +2,10 @@
 //!
 //! This module defines the configuration struct for the Canvas.
 
+use crate::keymap::Keymap;
+use crate::theme::Theme;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Configuration parameters for the Canvas.
 ///
@@ -17,6 +20,41 @@ pub struct CanvasConfig {
     pub snap_threshold: f32,
     /// Max time in ms to register a double-click. Default: 300ms.
     pub double_click_time_ms: u64,
+    /// Snap dragged nodes to the grid. Default: false.
+    #[serde(default)]
+    pub snap_to_grid: bool,
+    /// Spacing of the snapping grid in world units. Default: 20.0.
+    #[serde(default = "default_grid_size")]
+    pub grid_size: f32,
+    /// Seconds before a node's running/error flag and a connection's pulse fade. Default: 1.0.
+    #[serde(default = "default_activity_decay")]
+    pub activity_decay: f32,
+    /// Time in ms a finger must rest in place to open a context menu. Default: 500ms.
+    #[serde(default = "default_long_press_ms")]
+    pub long_press_ms: u64,
+    /// Distance in world units an arrow key moves the selection. Default: 1.0.
+    /// Shift+arrow moves it by `grid_size`.
+    #[serde(default = "default_nudge_step")]
+    pub nudge_step: f32,
+    /// Seconds the view takes to glide to a node, the selection or the whole graph.
+    /// 0 jumps straight there. Default: 0.25.
+    #[serde(default = "default_focus_duration")]
+    pub focus_duration: f32,
+    /// Below this zoom nodes are drawn as flat rects and wires as straight lines, without
+    /// ports or text. Default: 0.4.
+    #[serde(default = "default_lod_zoom")]
+    pub lod_zoom: f32,
+    /// Below this zoom the background grid is not drawn. Default: 0.2.
+    #[serde(default = "default_grid_min_zoom")]
+    pub grid_min_zoom: f32,
+    /// Reuse the previous frame's draw list while the graph and view are unchanged. Hosts that
+    /// edit `GraphState` fields directly must call `GraphState::mark_changed`, or turn this off.
+    /// Default: true.
+    #[serde(default = "default_true")]
+    pub retain_render_list: bool,
+    /// Keyboard shortcuts.
+    #[serde(default)]
+    pub keymap: Keymap,
     /// Visual styling configuration.
     #[serde(default)]
     pub style: CanvasStyle,
@@ -29,11 +67,63 @@ impl Default for CanvasConfig {
             zoom_speed: 0.1,
             snap_threshold: 10.0,
             double_click_time_ms: 300,
+            snap_to_grid: false,
+            grid_size: default_grid_size(),
+            activity_decay: default_activity_decay(),
+            long_press_ms: default_long_press_ms(),
+            nudge_step: default_nudge_step(),
+            focus_duration: default_focus_duration(),
+            lod_zoom: default_lod_zoom(),
+            grid_min_zoom: default_grid_min_zoom(),
+            retain_render_list: true,
+            keymap: Keymap::default(),
             style: CanvasStyle::default(),
         }
     }
 }
 
+impl CanvasConfig {
+    /// The default configuration painted with `theme`.
+    pub fn with_theme(theme: &Theme) -> Self {
+        Self {
+            style: theme.style(),
+            ..Default::default()
+        }
+    }
+}
+
+fn default_activity_decay() -> f32 {
+    1.0
+}
+
+fn default_grid_size() -> f32 {
+    20.0
+}
+
+fn default_focus_duration() -> f32 {
+    0.25
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_lod_zoom() -> f32 {
+    0.4
+}
+
+fn default_grid_min_zoom() -> f32 {
+    0.2
+}
+
+fn default_long_press_ms() -> u64 {
+    500
+}
+
+fn default_nudge_step() -> f32 {
+    1.0
+}
+
 /// Visual styling configuration for the Canvas.
 ///
 /// This struct defines the colors used for rendering the graph.
@@ -52,28 +142,104 @@ pub struct CanvasStyle {
     pub edge_default: EdgeStyle,
     /// Color of the ports.
     pub port_color: glam::Vec4,
+    /// Outline drawn around every port.
+    #[serde(default = "default_port_outline_color")]
+    pub port_outline_color: glam::Vec4,
+    /// Port colors by data type. Untyped ports, and types missing here, use `port_color`.
+    #[serde(default)]
+    pub port_type_colors: HashMap<String, glam::Vec4>,
+    /// Font size of port labels in world units.
+    #[serde(default = "default_port_label_size")]
+    pub port_label_size: f32,
+    /// Wire colors by the data type of the source port. Types missing here fall back to
+    /// `port_type_colors`, then to `edge_default`.
+    #[serde(default)]
+    pub edge_type_colors: HashMap<String, glam::Vec4>,
+    /// Font size of wire labels in world units.
+    #[serde(default = "default_port_label_size")]
+    pub edge_label_size: f32,
     /// Color of the selection box (fill).
     pub selection_box_color: glam::Vec4,
     /// Color of the selection box (border).
     pub selection_box_border_color: glam::Vec4,
+    /// Color of the selected wire.
+    #[serde(default = "default_selected_color")]
+    pub selected_edge_color: glam::Vec4,
+    /// Border color of selected nodes. Their fill is tinted towards it.
+    #[serde(default = "default_selected_color")]
+    pub selected_border_color: glam::Vec4,
+    /// Color of the wire being dragged from a port.
+    #[serde(default = "default_active_link_color")]
+    pub active_link_color: glam::Vec4,
+    /// Node styles by `NodeData::node_type`, for nodes without a style of their own.
+    /// Types missing here use `node_default`.
+    #[serde(default)]
+    pub category_styles: HashMap<String, NodeStyle>,
+    /// Styling of an execution trail overlay.
+    #[serde(default)]
+    pub trail: TrailStyle,
+    /// Styling of live execution activity.
+    #[serde(default)]
+    pub activity: ActivityStyle,
+    /// Styling of comment boxes and group frames.
+    #[serde(default)]
+    pub annotation: AnnotationStyle,
 }
 
 impl Default for CanvasStyle {
+    /// The dark theme.
     fn default() -> Self {
-        Self {
-            background_color: glam::Vec4::new(0.1, 0.1, 0.1, 1.0),
-            grid_color: glam::Vec4::new(0.2, 0.2, 0.2, 1.0),
-            node_default: NodeStyle::default(),
-            edge_default: EdgeStyle::default(),
-            port_color: glam::Vec4::new(0.7, 0.7, 0.7, 1.0),
-            selection_box_color: glam::Vec4::new(0.3, 0.3, 0.6, 0.2),
-            selection_box_border_color: glam::Vec4::new(0.4, 0.4, 0.8, 0.5),
-        }
+        Theme::dark().style()
     }
 }
 
+impl CanvasStyle {
+    /// The color of a port carrying `data_type`.
+    pub fn port_color_for(&self, data_type: Option<&str>) -> glam::Vec4 {
+        data_type
+            .and_then(|ty| self.port_type_colors.get(ty))
+            .copied()
+            .unwrap_or(self.port_color)
+    }
+
+    /// The style of a node of `node_type` that has no style of its own.
+    pub fn node_style_for(&self, node_type: &str) -> &NodeStyle {
+        self.category_styles
+            .get(node_type)
+            .unwrap_or(&self.node_default)
+    }
+
+    /// The color of a wire carrying `data_type`, unless the wire overrides it.
+    pub fn edge_color_for(&self, data_type: Option<&str>) -> glam::Vec4 {
+        data_type
+            .and_then(|ty| {
+                self.edge_type_colors
+                    .get(ty)
+                    .or_else(|| self.port_type_colors.get(ty))
+            })
+            .copied()
+            .unwrap_or(self.edge_default.color)
+    }
+}
+
+fn default_selected_color() -> glam::Vec4 {
+    Theme::dark().selected
+}
+
+fn default_port_outline_color() -> glam::Vec4 {
+    Theme::dark().background
+}
+
+fn default_active_link_color() -> glam::Vec4 {
+    Theme::dark().text
+}
+
+pub(crate) fn default_port_label_size() -> f32 {
+    11.0
+}
+
 /// Visual style for a Node.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct NodeStyle {
     /// Fill color of the node.
     pub color: glam::Vec4,
@@ -85,11 +251,7 @@ pub struct NodeStyle {
 
 impl Default for NodeStyle {
     fn default() -> Self {
-        Self {
-            color: glam::Vec4::new(0.15, 0.15, 0.15, 1.0),
-            border_color: glam::Vec4::new(0.5, 0.5, 0.5, 1.0),
-            text_color: glam::Vec4::new(1.0, 1.0, 1.0, 1.0),
-        }
+        CanvasStyle::default().node_default
     }
 }
 
@@ -103,10 +265,82 @@ pub struct EdgeStyle {
 }
 
 impl Default for EdgeStyle {
+    fn default() -> Self {
+        CanvasStyle::default().edge_default
+    }
+}
+
+/// Visual style for an execution trail overlay.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrailStyle {
+    /// Alpha multiplier applied to nodes and wires the run skipped.
+    pub skipped_opacity: f32,
+    /// Color of the wires the run traversed.
+    pub traversed_edge_color: glam::Vec4,
+    /// Border color of nodes that failed during the run.
+    pub failed_border_color: glam::Vec4,
+}
+
+impl Default for TrailStyle {
     fn default() -> Self {
         Self {
-            color: glam::Vec4::new(0.8, 0.8, 0.8, 1.0),
-            width: 2.0,
+            skipped_opacity: 0.25,
+            traversed_edge_color: glam::Vec4::new(0.3, 0.8, 0.4, 1.0),
+            failed_border_color: glam::Vec4::new(0.9, 0.25, 0.25, 1.0),
+        }
+    }
+}
+
+/// Visual style for live execution activity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActivityStyle {
+    /// Border color of nodes flagged `RUNNING`.
+    pub running_border_color: glam::Vec4,
+    /// Border color of nodes flagged `ERROR`.
+    pub error_border_color: glam::Vec4,
+    /// Color of the pulse travelling along a traversed wire.
+    pub pulse_color: glam::Vec4,
+    /// Radius of the pulse in world units.
+    pub pulse_radius: f32,
+}
+
+impl Default for ActivityStyle {
+    fn default() -> Self {
+        Self {
+            running_border_color: glam::Vec4::new(0.35, 0.65, 1.0, 1.0),
+            error_border_color: glam::Vec4::new(0.9, 0.25, 0.25, 1.0),
+            pulse_color: glam::Vec4::new(0.6, 0.85, 1.0, 1.0),
+            pulse_radius: 5.0,
+        }
+    }
+}
+
+/// Visual style for comment boxes and group frames.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnnotationStyle {
+    /// Fill color of comment boxes without a color override.
+    pub comment_color: glam::Vec4,
+    /// Fill color of group frames without a color override.
+    pub group_color: glam::Vec4,
+    /// Color of a group frame's title bar.
+    pub group_header_color: glam::Vec4,
+    /// Border color of comments and groups.
+    pub border_color: glam::Vec4,
+    /// Color of comment text and group titles.
+    pub text_color: glam::Vec4,
+    /// Font size in world units.
+    pub text_size: f32,
+}
+
+impl Default for AnnotationStyle {
+    fn default() -> Self {
+        Self {
+            comment_color: glam::Vec4::new(0.55, 0.48, 0.2, 0.9),
+            group_color: glam::Vec4::new(0.3, 0.4, 0.6, 0.15),
+            group_header_color: glam::Vec4::new(0.3, 0.4, 0.6, 0.5),
+            border_color: glam::Vec4::new(0.5, 0.55, 0.65, 0.8),
+            text_color: glam::Vec4::new(1.0, 1.0, 1.0, 1.0),
+            text_size: 14.0,
         }
     }
 }
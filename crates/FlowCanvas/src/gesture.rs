@@ -0,0 +1,221 @@
+//! # Gestures
+//!
+//! Turns touch and trackpad input into what the interaction state machine understands, so the
+//! canvas works on tablets and trackpads as well as with a mouse.
+//!
+//! - **One finger** is fed to `handle_interactions` as the left mouse button at the finger's
+//!   position, so tapping selects, and dragging moves nodes, draws wires or box-selects.
+//! - **Two fingers** pan by the movement of their midpoint and zoom by the change in their
+//!   spread. Whatever the first finger had started is cancelled, and the remaining finger is
+//!   ignored until every finger has lifted.
+//! - **Long press**: a finger held still for `CanvasConfig::long_press_ms` cancels what it
+//!   started and emits `LogicEvent::ContextMenu`, like pressing the right mouse button does.
+//! - **Trackpad** scroll (`InputState::pan_delta`) and pinch (`InputState::pinch_delta`) move
+//!   the view directly.
+
+use crate::config::CanvasConfig;
+use crate::input::{InputState, TouchPoint};
+use crate::interaction::{self, InteractionMode, LogicEvent};
+use crate::model::{GraphState, NodeData};
+use crate::view::View;
+use glam::Vec2;
+use std::borrow::Cow;
+
+/// Distance in pixels a finger may wander before it no longer counts as a long press.
+pub const LONG_PRESS_SLOP: f32 = 10.0;
+
+/// Touch state carried between frames.
+#[derive(Clone, Debug, Default)]
+pub struct GestureState {
+    phase: GesturePhase,
+    /// Whether the right mouse button was down last frame.
+    right_was_down: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+enum GesturePhase {
+    #[default]
+    None,
+    /// One finger acting as the left mouse button.
+    Press {
+        id: u64,
+        start: Vec2,
+        last: Vec2,
+        /// Seconds the finger has been down.
+        held: f32,
+        /// Moved further than `LONG_PRESS_SLOP`, or already opened a context menu.
+        long_press_done: bool,
+    },
+    /// Two fingers pinching and panning.
+    Pinch {
+        ids: [u64; 2],
+        centroid: Vec2,
+        distance: f32,
+    },
+    /// A gesture ended while fingers are still down; ignore them until they lift.
+    Lifting,
+}
+
+impl GestureState {
+    /// Applies this frame's gestures to the view and returns the input `handle_interactions`
+    /// should see, with a single touch standing in for the left mouse button.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process<'a, T: NodeData>(
+        &mut self,
+        input: &'a InputState,
+        dt: f32,
+        mode: &mut InteractionMode,
+        view: &mut View,
+        config: &CanvasConfig,
+        graph: &mut GraphState<T>,
+        events: &mut Vec<LogicEvent>,
+    ) -> Cow<'a, InputState> {
+        if input.mouse_buttons.right && !self.right_was_down {
+            events.push(context_menu(input.mouse_pos, view, graph));
+        }
+        self.right_was_down = input.mouse_buttons.right;
+
+        // Trackpad
+        if input.pan_delta != Vec2::ZERO {
+            view.transform.pan += input.pan_delta * config.pan_speed;
+            events.push(LogicEvent::RepaintNeeded);
+        }
+        if input.pinch_delta != 0.0
+            && view.zoom_at(
+                input.mouse_pos,
+                view.transform.zoom * (1.0 + input.pinch_delta),
+            )
+        {
+            events.push(LogicEvent::RepaintNeeded);
+        }
+
+        match input.touches.as_slice() {
+            [] => {
+                let phase = std::mem::take(&mut self.phase);
+                match phase {
+                    // Release where the finger was last seen.
+                    GesturePhase::Press { last, .. } => Cow::Owned(pointer(input, last, false)),
+                    _ => Cow::Borrowed(input),
+                }
+            }
+            [touch] => self.single_touch(*touch, input, dt, mode, view, config, graph, events),
+            [a, b, ..] => {
+                self.pinch(*a, *b, input, mode, view, graph, events);
+                Cow::Owned(pointer(input, input.mouse_pos, false))
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn single_touch<'a, T: NodeData>(
+        &mut self,
+        touch: TouchPoint,
+        input: &'a InputState,
+        dt: f32,
+        mode: &mut InteractionMode,
+        view: &mut View,
+        config: &CanvasConfig,
+        graph: &mut GraphState<T>,
+        events: &mut Vec<LogicEvent>,
+    ) -> Cow<'a, InputState> {
+        match &mut self.phase {
+            GesturePhase::None => {
+                self.phase = GesturePhase::Press {
+                    id: touch.id,
+                    start: touch.pos,
+                    last: touch.pos,
+                    held: 0.0,
+                    long_press_done: false,
+                };
+            }
+            GesturePhase::Press {
+                id,
+                start,
+                last,
+                held,
+                long_press_done,
+            } if *id == touch.id => {
+                *last = touch.pos;
+                *held += dt;
+                if touch.pos.distance(*start) > LONG_PRESS_SLOP {
+                    *long_press_done = true;
+                }
+                if !*long_press_done && *held * 1000.0 >= config.long_press_ms as f32 {
+                    *long_press_done = true;
+                    let effective = pointer(input, touch.pos, true);
+                    if !matches!(mode, InteractionMode::Idle) {
+                        interaction::cancel_interaction(mode, view, &effective, graph, events);
+                    }
+                    *mode = InteractionMode::WaitingForRelease;
+                    events.push(context_menu(touch.pos, view, graph));
+                    return Cow::Owned(effective);
+                }
+            }
+            // The finger that started the press lifted and another is still down.
+            _ => {
+                self.phase = GesturePhase::Lifting;
+                return Cow::Owned(pointer(input, input.mouse_pos, false));
+            }
+        }
+        Cow::Owned(pointer(input, touch.pos, true))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn pinch<T: NodeData>(
+        &mut self,
+        a: TouchPoint,
+        b: TouchPoint,
+        input: &InputState,
+        mode: &mut InteractionMode,
+        view: &mut View,
+        graph: &mut GraphState<T>,
+        events: &mut Vec<LogicEvent>,
+    ) {
+        let centroid = (a.pos + b.pos) * 0.5;
+        let distance = a.pos.distance(b.pos);
+
+        if let GesturePhase::Pinch {
+            ids,
+            centroid: last_centroid,
+            distance: last_distance,
+        } = &mut self.phase
+            && *ids == [a.id, b.id]
+        {
+            view.transform.pan += centroid - *last_centroid;
+            if *last_distance > f32::EPSILON {
+                view.zoom_at(centroid, view.transform.zoom * distance / *last_distance);
+            }
+            *last_centroid = centroid;
+            *last_distance = distance;
+            events.push(LogicEvent::RepaintNeeded);
+            return;
+        }
+
+        // A second finger landed: undo whatever the first one started.
+        if !matches!(mode, InteractionMode::Idle) {
+            let released = pointer(input, input.mouse_pos, false);
+            interaction::cancel_interaction(mode, view, &released, graph, events);
+        }
+        self.phase = GesturePhase::Pinch {
+            ids: [a.id, b.id],
+            centroid,
+            distance,
+        };
+    }
+}
+
+/// `input` with the left mouse button replaced by a pointer at `pos`.
+fn pointer(input: &InputState, pos: Vec2, pressed: bool) -> InputState {
+    let mut effective = input.clone();
+    effective.mouse_pos = pos;
+    effective.mouse_buttons.left = pressed;
+    effective
+}
+
+fn context_menu<T: NodeData>(position: Vec2, view: &View, graph: &mut GraphState<T>) -> LogicEvent {
+    graph.sync_spatial_index();
+    LogicEvent::ContextMenu {
+        position,
+        node: graph.node_at(view.screen_to_world(position)),
+    }
+}
@@ -1,7 +1,8 @@
 //! # Input Protocol
 //!
 //! This module defines the input state that the host application must pass to the Canvas every frame.
-//! It includes mouse position, buttons, scroll delta, and keyboard modifiers.
+//! It includes mouse position, buttons, scroll delta, keyboard modifiers, active touches and
+//! trackpad gestures.
 
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
@@ -31,14 +32,36 @@ pub struct MouseButtons {
 }
 
 /// Standard keyboard keys that the Canvas cares about.
+///
+/// What each key does is decided by the `Keymap` in `CanvasConfig`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Key {
     Delete,
     Backspace,
+    Escape,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Slash,
     A,
+    C,
+    D,
+    F,
+    V,
+    X,
     // Add more as needed
 }
 
+/// A finger currently on a touch screen.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TouchPoint {
+    /// Identifier that stays the same for as long as the finger is down.
+    pub id: u64,
+    /// Position in Screen Space (pixels).
+    pub pos: Vec2,
+}
+
 /// The input state for a single frame.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InputState {
@@ -60,6 +83,21 @@ pub struct InputState {
     /// If true, the canvas will ignore Click/Drag events (but still track mouse pos).
     /// This is used when the mouse interaction was consumed by UI content inside a node.
     pub event_consumed_by_content: bool,
+    /// Fingers currently on the screen, in the order they touched down.
+    ///
+    /// One finger acts like the left mouse button, two pinch-zoom and pan, and holding one
+    /// still opens a context menu. While any touches are reported the Canvas ignores
+    /// `mouse_pos` and `mouse_buttons.left`, so hosts should not also report the browser's
+    /// emulated mouse events for them.
+    #[serde(default)]
+    pub touches: Vec<TouchPoint>,
+    /// Two-finger trackpad scroll this frame in Screen Space (pixels). Pans the canvas.
+    #[serde(default)]
+    pub pan_delta: Vec2,
+    /// Trackpad pinch this frame, as a relative change in zoom (0.1 = zoom in by 10%).
+    /// Zooms around `mouse_pos`.
+    #[serde(default)]
+    pub pinch_delta: f32,
 }
 
 impl Default for InputState {
@@ -72,6 +110,9 @@ impl Default for InputState {
             pressed_keys: Vec::new(),
             screen_size: Vec2::new(800.0, 600.0), // Sound default
             event_consumed_by_content: false,
+            touches: Vec::new(),
+            pan_delta: Vec2::ZERO,
+            pinch_delta: 0.0,
         }
     }
 }
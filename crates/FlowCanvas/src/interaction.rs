@@ -1,11 +1,18 @@
 use glam::Vec2;
 use std::collections::HashMap;
 
+use crate::annotation::{AnnotationHit, MIN_ANNOTATION_SIZE};
 use crate::config::CanvasConfig;
-use crate::input::{self, InputState};
-use crate::model::{self, GraphState, NodeFlags, NodeId};
+use crate::input::InputState;
+use crate::keymap::CanvasCommand;
+use crate::math::{self, Rect};
+use crate::model::{self, AnnotationId, GraphState, NodeFlags, NodeId};
+use crate::painter;
 use crate::view::{Transform, View};
 
+/// Margin in pixels left around the graph by `CanvasCommand::ZoomToFit`.
+pub const ZOOM_TO_FIT_PADDING: f32 = 40.0;
+
 /// Events emitted by the Canvas logic to the host application.
 #[derive(Clone, Debug, PartialEq)]
 pub enum LogicEvent {
@@ -16,6 +23,28 @@ pub enum LogicEvent {
     },
     /// Request to delete selected nodes.
     DeleteSelection,
+    /// Request to remove a single connection (a selected wire was deleted, or a wire was
+    /// unplugged from its input and dropped elsewhere).
+    Disconnect { id: model::ConnectionId },
+    /// Request to copy selected nodes (Ctrl/Cmd+C). See `GraphState::copy_selection`.
+    CopySelection,
+    /// Request to cut selected nodes (Ctrl/Cmd+X). See `GraphState::cut_selection`.
+    CutSelection,
+    /// Request to paste the host's clipboard (Ctrl/Cmd+V). See `GraphState::paste`.
+    Paste,
+    /// Request to duplicate selected nodes (Ctrl/Cmd+D). See `GraphState::duplicate_selection`.
+    DuplicateSelection,
+    /// The user asked for the host's node search (Ctrl/Cmd+F or /).
+    FocusSearch,
+    /// The user asked for a context menu, with a right click or a long press.
+    ContextMenu {
+        /// Where to open the menu (Screen Space).
+        position: Vec2,
+        /// The node under the pointer, if any.
+        node: Option<model::NodeId>,
+    },
+    /// Nodes were pasted into the graph.
+    Pasted { ids: Vec<model::NodeId> },
     /// A selection of nodes was moved.
     NodesMoved {
         ids: Vec<model::NodeId>,
@@ -48,13 +77,45 @@ pub enum InteractionMode {
         /// Mouse position when drag started (World Space).
         start_mouse_world: Vec2,
     },
+    /// User is moving a comment or group frame, together with what the frame contains.
+    DraggingAnnotation {
+        /// Initial positions of the dragged annotation and the annotations inside it (World Space).
+        annotations: HashMap<AnnotationId, Vec2>,
+        /// Initial positions of the nodes inside the frame (World Space).
+        nodes: HashMap<NodeId, Vec2>,
+        /// Mouse position when drag started (World Space).
+        start_mouse_world: Vec2,
+    },
+    /// User is resizing a comment or group frame from its bottom-right corner.
+    ResizingAnnotation {
+        id: AnnotationId,
+        /// Size of the annotation when the resize started.
+        initial_size: Vec2,
+        /// Mouse position when the resize started (World Space).
+        start_mouse_world: Vec2,
+    },
     /// User is creating a connection.
     Linking {
         /// The port where the wire started.
         source: model::PortId,
         /// Current temporary endpoint of the wire (World Space).
         curr_pos_world: Vec2,
+        /// The existing connection this wire was unplugged from, if any. It is hidden while
+        /// dragging and disconnected on release unless it is plugged back in.
+        detached: Option<model::ConnectionId>,
     },
+    /// User is moving a waypoint on a wire.
+    DraggingWaypoint {
+        connection: model::ConnectionId,
+        /// Index into `Connection::waypoints`.
+        index: usize,
+        /// Position of the waypoint when the drag started (World Space).
+        initial_position: Vec2,
+        /// Mouse position when the drag started (World Space).
+        start_mouse_world: Vec2,
+    },
+    /// A click was fully handled on press; input is ignored until the button is released.
+    WaitingForRelease,
     /// User is box selecting.
     BoxSelecting {
         /// Start of the selection box (World Space).
@@ -85,24 +146,13 @@ pub fn handle_interactions<T: model::NodeData>(
     graph: &mut GraphState<T>,
     _events: &mut Vec<LogicEvent>,
 ) {
-    // Zooming via Scroll
-    // Zooming via Scroll
+    // Hit tests below query the spatial index
+    graph.sync_spatial_index();
+
+    // Zooming via Scroll, around the mouse
     if input.scroll_delta != 0.0 {
-        let old_zoom = view.transform.zoom;
         let zoom_factor = 1.0 + (input.scroll_delta * config.zoom_speed);
-        let new_zoom = (old_zoom * zoom_factor).clamp(0.1, 10.0);
-
-        if (new_zoom - old_zoom).abs() > f32::EPSILON {
-            // 1. Calculate world position under mouse BEFORE zoom
-            let world_mouse = view.screen_to_world(input.mouse_pos);
-
-            // 2. Apply new zoom
-            view.transform.zoom = new_zoom;
-
-            // 3. Adjust Pan so that world_mouse remains under input.mouse_pos
-            // Screen = World * Zoom + Pan  =>  Pan = Screen - (World * Zoom)
-            view.transform.pan = input.mouse_pos - (world_mouse * new_zoom);
-
+        if view.zoom_at(input.mouse_pos, view.transform.zoom * zoom_factor) {
             _events.push(LogicEvent::RepaintNeeded);
         }
     }
@@ -110,20 +160,8 @@ pub fn handle_interactions<T: model::NodeData>(
     // Keyboard Shortcuts
     if !input.event_consumed_by_content {
         for key in &input.pressed_keys {
-            match key {
-                input::Key::Delete | input::Key::Backspace => {
-                    _events.push(LogicEvent::DeleteSelection);
-                    _events.push(LogicEvent::RepaintNeeded);
-                }
-                input::Key::A => {
-                    if input.modifiers.ctrl || input.modifiers.meta {
-                        // Select All
-                        for (_, node) in &mut graph.nodes {
-                            node.flags.insert(NodeFlags::SELECTED);
-                        }
-                        _events.push(LogicEvent::RepaintNeeded);
-                    }
-                }
+            if let Some(command) = config.keymap.command_for(*key, &input.modifiers) {
+                run_command(command, mode, view, config, input, graph, _events);
             }
         }
     }
@@ -140,6 +178,7 @@ pub fn handle_interactions<T: model::NodeData>(
             start_mouse_world,
         } => handle_dragging_nodes(
             view,
+            config,
             input,
             graph,
             nodes,
@@ -147,10 +186,65 @@ pub fn handle_interactions<T: model::NodeData>(
             *start_mouse_world,
             _events,
         ),
+        InteractionMode::DraggingAnnotation {
+            annotations,
+            nodes,
+            start_mouse_world,
+        } => handle_dragging_annotation(
+            view,
+            input,
+            graph,
+            annotations,
+            nodes,
+            *start_mouse_world,
+            _events,
+        ),
+        InteractionMode::ResizingAnnotation {
+            id,
+            initial_size,
+            start_mouse_world,
+        } => handle_resizing_annotation(
+            view,
+            input,
+            graph,
+            *id,
+            *initial_size,
+            *start_mouse_world,
+            _events,
+        ),
         InteractionMode::Linking {
             source,
             curr_pos_world,
-        } => handle_linking(view, config, input, graph, *source, curr_pos_world, _events),
+            detached,
+        } => handle_linking(
+            view,
+            config,
+            input,
+            graph,
+            *source,
+            curr_pos_world,
+            *detached,
+            _events,
+        ),
+        InteractionMode::DraggingWaypoint {
+            connection,
+            index,
+            initial_position,
+            start_mouse_world,
+        } => handle_dragging_waypoint(
+            view,
+            config,
+            input,
+            graph,
+            *connection,
+            *index,
+            *initial_position,
+            *start_mouse_world,
+            _events,
+        ),
+        InteractionMode::WaitingForRelease => (!input.mouse_buttons.left
+            && !input.mouse_buttons.middle)
+            .then_some(InteractionMode::Idle),
         InteractionMode::BoxSelecting {
             start_pos_world,
             current_pos_world,
@@ -169,12 +263,154 @@ pub fn handle_interactions<T: model::NodeData>(
     }
 }
 
+/// Carries out a command bound in `CanvasConfig::keymap`.
+fn run_command<T: model::NodeData>(
+    command: CanvasCommand,
+    mode: &mut InteractionMode,
+    view: &mut View,
+    config: &CanvasConfig,
+    input: &InputState,
+    graph: &mut GraphState<T>,
+    _events: &mut Vec<LogicEvent>,
+) {
+    match command {
+        CanvasCommand::Delete => {
+            match graph.selected_connection.take() {
+                Some(id) if graph.connections.contains_key(id) => {
+                    _events.push(LogicEvent::Disconnect { id })
+                }
+                _ => _events.push(LogicEvent::DeleteSelection),
+            }
+            _events.push(LogicEvent::RepaintNeeded);
+        }
+        CanvasCommand::SelectAll => {
+            for (_, node) in &mut graph.nodes {
+                node.flags.insert(NodeFlags::SELECTED);
+            }
+            _events.push(LogicEvent::RepaintNeeded);
+        }
+        // Clipboard requests; the host owns the clipboard
+        CanvasCommand::Copy => _events.push(LogicEvent::CopySelection),
+        CanvasCommand::Cut => _events.push(LogicEvent::CutSelection),
+        CanvasCommand::Paste => _events.push(LogicEvent::Paste),
+        CanvasCommand::Duplicate => _events.push(LogicEvent::DuplicateSelection),
+        CanvasCommand::Nudge(direction) | CanvasCommand::NudgeLarge(direction) => {
+            // Nodes already following the mouse are left alone
+            if !matches!(mode, InteractionMode::Idle) {
+                return;
+            }
+            let step = match command {
+                CanvasCommand::Nudge(_) => config.nudge_step,
+                _ => config.grid_size,
+            };
+            let moved = graph.nudge_selected(direction.vector() * step);
+            if !moved.is_empty() {
+                _events.extend(moved);
+                _events.push(LogicEvent::RepaintNeeded);
+            }
+        }
+        CanvasCommand::ZoomToFit => {
+            if let Some(bounds) = graph.content_bounds() {
+                view.viewport_size = input.screen_size;
+                view.animate_to(
+                    view.fitted_transform(bounds, ZOOM_TO_FIT_PADDING),
+                    config.focus_duration,
+                );
+                _events.push(LogicEvent::RepaintNeeded);
+            }
+        }
+        CanvasCommand::FocusSearch => _events.push(LogicEvent::FocusSearch),
+        CanvasCommand::Cancel => cancel_interaction(mode, view, input, graph, _events),
+    }
+}
+
+/// Aborts the current interaction, undoing whatever it changed so far. When idle, clears the
+/// selection instead. A button still held afterwards is ignored until released.
+pub(crate) fn cancel_interaction<T: model::NodeData>(
+    mode: &mut InteractionMode,
+    view: &mut View,
+    input: &InputState,
+    graph: &mut GraphState<T>,
+    _events: &mut Vec<LogicEvent>,
+) {
+    match mode {
+        InteractionMode::Idle => {
+            for (_, node) in &mut graph.nodes {
+                node.flags.remove(NodeFlags::SELECTED);
+            }
+            graph.selected_connection = None;
+        }
+        InteractionMode::Panning {
+            initial_transform, ..
+        } => view.transform = *initial_transform,
+        InteractionMode::DraggingNodes {
+            initial_positions, ..
+        } => {
+            for (id, position) in initial_positions.iter() {
+                if let Some(node) = graph.nodes.get_mut(*id) {
+                    node.position = *position;
+                }
+            }
+        }
+        InteractionMode::DraggingAnnotation {
+            annotations, nodes, ..
+        } => {
+            for (id, position) in annotations.iter() {
+                if let Some(annotation) = graph.annotations.get_mut(*id) {
+                    annotation.position = *position;
+                }
+            }
+            for (id, position) in nodes.iter() {
+                if let Some(node) = graph.nodes.get_mut(*id) {
+                    node.position = *position;
+                }
+            }
+        }
+        InteractionMode::ResizingAnnotation {
+            id, initial_size, ..
+        } => {
+            if let Some(annotation) = graph.annotations.get_mut(*id) {
+                annotation.size = *initial_size;
+            }
+        }
+        InteractionMode::DraggingWaypoint {
+            connection,
+            index,
+            initial_position,
+            ..
+        } => {
+            if let Some(point) = graph
+                .connections
+                .get_mut(*connection)
+                .and_then(|conn| conn.waypoints.get_mut(*index))
+            {
+                *point = *initial_position;
+            }
+        }
+        // A dragged or unplugged wire only changes the graph on release
+        InteractionMode::Linking { .. }
+        | InteractionMode::BoxSelecting { .. }
+        | InteractionMode::WaitingForRelease => {}
+    }
+
+    *mode = if input.mouse_buttons.left || input.mouse_buttons.middle {
+        InteractionMode::WaitingForRelease
+    } else {
+        InteractionMode::Idle
+    };
+    _events.push(LogicEvent::RepaintNeeded);
+}
+
 /// Handles the `Idle` state interactions.
 ///
 /// This checks for inputs to transition into:
 /// - `Panning` (middle click)
-/// - `Linking` (clicking a port)
+/// - `Linking` (clicking a port; pressing a connected input unplugs its wire)
+/// - `DraggingWaypoint` (clicking a waypoint, or Alt+clicking a wire to add one)
 /// - `DraggingNodes` (clicking a node)
+/// - `WaitingForRelease` (selecting a wire, or Alt+clicking a waypoint to remove it)
+/// - `DraggingAnnotation` / `ResizingAnnotation` (clicking a comment, a group header or a
+///   resize handle)
 /// - `BoxSelecting` (clicking empty space)
 fn handle_idle<T: model::NodeData>(
     view: &View,
@@ -190,30 +426,31 @@ fn handle_idle<T: model::NodeData>(
         });
     } else if input.mouse_buttons.left && !input.event_consumed_by_content {
         let world_mouse = view.screen_to_world(input.mouse_pos);
+        // Any click drops the wire selection; clicking a wire selects it again below
+        graph.selected_connection = None;
         let mut hit_port = None;
-        let mut hit_node = None;
 
         // Hit Test Ports FIRST (Priority)
-        // Iterate front to back
+        // Only nodes within reach of the cursor can own the port; iterate them front to back
+        let port_radius = (10.0 / view.transform.zoom).max(5.0);
+        let nearby = graph.nodes_in_rect(Rect::new(world_mouse, Vec2::ZERO).expand(port_radius));
         'port_search: for &node_id in graph.draw_order.iter().rev() {
-            if let Some(node) = graph.nodes.get(node_id) {
+            if nearby.binary_search(&node_id).is_err() {
+                continue;
+            }
+            // A collapsed node's ports are folded into its header; expand it to wire them
+            if let Some(node) = graph.nodes.get(node_id).filter(|n| !n.is_collapsed()) {
                 // Check inputs
-                let spacing_in = node.size.y / (node.inputs.len() as f32 + 1.0);
                 for (i, &port_id) in node.inputs.iter().enumerate() {
-                    let local_y = spacing_in * (i as f32 + 1.0);
-                    let port_pos = node.position + Vec2::new(0.0, local_y);
-                    if port_pos.distance(world_mouse) <= (10.0 / view.transform.zoom).max(5.0) {
+                    if node.port_position(true, i).distance(world_mouse) <= port_radius {
                         hit_port = Some(port_id);
                         break 'port_search;
                     }
                 }
 
                 // Check outputs
-                let spacing_out = node.size.y / (node.outputs.len() as f32 + 1.0);
                 for (i, &port_id) in node.outputs.iter().enumerate() {
-                    let local_y = spacing_out * (i as f32 + 1.0);
-                    let port_pos = node.position + Vec2::new(node.size.x, local_y);
-                    if port_pos.distance(world_mouse) <= (10.0 / view.transform.zoom).max(5.0) {
+                    if node.port_position(false, i).distance(world_mouse) <= port_radius {
                         hit_port = Some(port_id);
                         break 'port_search;
                     }
@@ -222,28 +459,48 @@ fn handle_idle<T: model::NodeData>(
         }
 
         if let Some(port_id) = hit_port {
+            // Pressing a connected input picks its wire up by the loose end, so it can be
+            // re-plugged elsewhere; any other port starts a new wire.
+            let plugged = graph
+                .connections
+                .iter()
+                .filter(|(_, conn)| conn.to == port_id)
+                .map(|(id, conn)| (id, conn.from))
+                .last();
+            if let Some((id, from)) = plugged {
+                _events.push(LogicEvent::RepaintNeeded);
+                return Some(InteractionMode::Linking {
+                    source: from,
+                    curr_pos_world: world_mouse,
+                    detached: Some(id),
+                });
+            }
+
             // Start Linking
             return Some(InteractionMode::Linking {
                 source: port_id,
                 curr_pos_world: world_mouse,
+                detached: None,
             });
         }
 
-        // Hit test Nodes interaction
-        // Iterate in reverse draw order (front to back)
-        for &node_id in graph.draw_order.iter().rev() {
-            if let Some(node) = graph.nodes.get(node_id)
-                && world_mouse.x >= node.position.x
-                && world_mouse.x <= node.position.x + node.size.x
-                && world_mouse.y >= node.position.y
-                && world_mouse.y <= node.position.y + node.size.y
-            {
-                hit_node = Some(node_id);
-                break;
+        // Waypoints sit on top of wires and may overlap nodes; Alt+click removes one
+        if let Some((connection, index)) = hit_waypoint(view, graph, input.mouse_pos) {
+            _events.push(LogicEvent::RepaintNeeded);
+            if input.modifiers.alt {
+                graph.remove_waypoint(connection, index);
+                return Some(InteractionMode::WaitingForRelease);
             }
+            return Some(InteractionMode::DraggingWaypoint {
+                connection,
+                index,
+                initial_position: graph.connections[connection].waypoints[index],
+                start_mouse_world: world_mouse,
+            });
         }
 
-        if let Some(node_id) = hit_node {
+        // Hit test Nodes interaction (topmost in draw order)
+        if let Some(node_id) = graph.node_at(world_mouse) {
             // Selection Logic
             if !input.modifiers.shift {
                 // Deselect others
@@ -277,6 +534,54 @@ fn handle_idle<T: model::NodeData>(
                 initial_positions,
                 start_mouse_world: world_mouse,
             });
+        } else if let Some(id) = hit_connection(view, graph, input.mouse_pos) {
+            _events.push(LogicEvent::RepaintNeeded);
+
+            // Alt+click drops a new waypoint on the wire and picks it up
+            if input.modifiers.alt
+                && let Some(index) = graph.insert_waypoint(id, world_mouse)
+            {
+                return Some(InteractionMode::DraggingWaypoint {
+                    connection: id,
+                    index,
+                    initial_position: world_mouse,
+                    start_mouse_world: world_mouse,
+                });
+            }
+
+            // Select the wire (nodes and wires are selected exclusively)
+            for (_, node) in &mut graph.nodes {
+                node.flags.remove(NodeFlags::SELECTED);
+            }
+            graph.selected_connection = Some(id);
+            return Some(InteractionMode::WaitingForRelease);
+        } else if let Some((id, hit)) = graph.annotation_at(world_mouse) {
+            _events.push(LogicEvent::RepaintNeeded);
+            if hit == AnnotationHit::ResizeHandle {
+                return Some(InteractionMode::ResizingAnnotation {
+                    id,
+                    initial_size: graph.annotations[id].size,
+                    start_mouse_world: world_mouse,
+                });
+            }
+
+            // A group frame carries everything lying inside it
+            let mut annotations = HashMap::from([(id, graph.annotations[id].position)]);
+            let mut nodes = HashMap::new();
+            if graph.annotations[id].is_group() {
+                for inner in graph.annotations_inside(id) {
+                    annotations.insert(inner, graph.annotations[inner].position);
+                }
+                for node_id in graph.nodes_inside(id) {
+                    nodes.insert(node_id, graph.nodes[node_id].position);
+                }
+            }
+
+            return Some(InteractionMode::DraggingAnnotation {
+                annotations,
+                nodes,
+                start_mouse_world: world_mouse,
+            });
         } else {
             // Clicked on empty space -> Deselect all (unless shift?)
             if !input.modifiers.shift {
@@ -318,11 +623,14 @@ fn handle_panning(
 
 /// Handles the `DraggingNodes` state interactions.
 ///
-/// Updates the position of all selected nodes based on mouse delta.
+/// Updates the position of all selected nodes based on mouse delta. With
+/// `CanvasConfig::snap_to_grid`, the delta is adjusted so the selection's top-left corner lands
+/// on the grid, keeping the nodes' relative layout.
 /// Returns to `Idle` on mouse release.
 #[allow(clippy::too_many_arguments)]
 fn handle_dragging_nodes<T: model::NodeData>(
     view: &View,
+    config: &CanvasConfig,
     input: &InputState,
     graph: &mut GraphState<T>,
     nodes: &[NodeId],
@@ -335,7 +643,15 @@ fn handle_dragging_nodes<T: model::NodeData>(
         Some(InteractionMode::Idle)
     } else {
         let current_mouse_world = view.screen_to_world(input.mouse_pos);
-        let delta = current_mouse_world - start_mouse_world;
+        let mut delta = current_mouse_world - start_mouse_world;
+
+        if config.snap_to_grid
+            && config.grid_size > 0.0
+            && let Some(corner) = initial_positions.values().copied().reduce(Vec2::min)
+        {
+            let snapped = ((corner + delta) / config.grid_size).round() * config.grid_size;
+            delta = snapped - corner;
+        }
 
         for node_id in nodes.iter() {
             if let Some(initial_pos) = initial_positions.get(node_id)
@@ -349,6 +665,64 @@ fn handle_dragging_nodes<T: model::NodeData>(
     }
 }
 
+/// Handles the `DraggingAnnotation` state interactions.
+///
+/// Moves the annotation and its contents by the mouse delta.
+/// Returns to `Idle` on mouse release.
+#[allow(clippy::too_many_arguments)]
+fn handle_dragging_annotation<T: model::NodeData>(
+    view: &View,
+    input: &InputState,
+    graph: &mut GraphState<T>,
+    annotations: &HashMap<AnnotationId, Vec2>,
+    nodes: &HashMap<NodeId, Vec2>,
+    start_mouse_world: Vec2,
+    _events: &mut Vec<LogicEvent>,
+) -> Option<InteractionMode> {
+    if !input.mouse_buttons.left {
+        return Some(InteractionMode::Idle);
+    }
+    let delta = view.screen_to_world(input.mouse_pos) - start_mouse_world;
+
+    for (id, initial_pos) in annotations {
+        if let Some(annotation) = graph.annotations.get_mut(*id) {
+            annotation.position = *initial_pos + delta;
+        }
+    }
+    for (id, initial_pos) in nodes {
+        if let Some(node) = graph.nodes.get_mut(*id) {
+            node.position = *initial_pos + delta;
+        }
+    }
+    _events.push(LogicEvent::RepaintNeeded);
+    None
+}
+
+/// Handles the `ResizingAnnotation` state interactions.
+///
+/// Grows or shrinks the annotation with the mouse, down to `MIN_ANNOTATION_SIZE`.
+/// Returns to `Idle` on mouse release.
+fn handle_resizing_annotation<T: model::NodeData>(
+    view: &View,
+    input: &InputState,
+    graph: &mut GraphState<T>,
+    id: AnnotationId,
+    initial_size: Vec2,
+    start_mouse_world: Vec2,
+    _events: &mut Vec<LogicEvent>,
+) -> Option<InteractionMode> {
+    if !input.mouse_buttons.left {
+        return Some(InteractionMode::Idle);
+    }
+    let delta = view.screen_to_world(input.mouse_pos) - start_mouse_world;
+
+    if let Some(annotation) = graph.annotations.get_mut(id) {
+        annotation.size = (initial_size + delta).max(MIN_ANNOTATION_SIZE);
+    }
+    _events.push(LogicEvent::RepaintNeeded);
+    None
+}
+
 /// Handles the `Linking` state interactions.
 ///
 /// Updates the temporary wire position and handles snapping to valid ports.
@@ -362,6 +736,7 @@ fn handle_linking<T: model::NodeData>(
     graph: &GraphState<T>,
     source: model::PortId,
     curr_pos_world: &mut Vec2,
+    detached: Option<model::ConnectionId>,
     _events: &mut Vec<LogicEvent>,
 ) -> Option<InteractionMode> {
     let world_mouse = view.screen_to_world(input.mouse_pos);
@@ -373,17 +748,24 @@ fn handle_linking<T: model::NodeData>(
     let mut closest_dist = config.snap_threshold / view.transform.zoom; // Logic threshold
     let mut snap_target = None;
 
-    // This is O(Ports), naive but fine for V1
-    for (port_id, _port) in &graph.ports {
-        if port_id == source {
+    // Only ports of nodes within the threshold of the cursor are candidates
+    let nearby = graph.nodes_in_rect(Rect::new(world_mouse, Vec2::ZERO).expand(closest_dist));
+    for node_id in nearby {
+        let node = &graph.nodes[node_id];
+        if node.is_collapsed() {
             continue;
-        } // Don't snap to self
-
-        if let Some(pos) = graph.find_port_position(port_id) {
-            let dist = pos.distance(world_mouse);
-            if dist < closest_dist {
-                closest_dist = dist;
-                snap_target = Some(port_id);
+        }
+        for &port_id in node.inputs.iter().chain(&node.outputs) {
+            if port_id == source {
+                continue;
+            } // Don't snap to self
+
+            if let Some(pos) = graph.find_port_position(port_id) {
+                let dist = pos.distance(world_mouse);
+                if dist < closest_dist {
+                    closest_dist = dist;
+                    snap_target = Some(port_id);
+                }
             }
         }
     }
@@ -396,18 +778,115 @@ fn handle_linking<T: model::NodeData>(
 
     if !input.mouse_buttons.left {
         // Release
-        if let Some(target) = snap_target {
-            _events.push(LogicEvent::Connect {
-                from: source,
-                to: target,
-            });
-            _events.push(LogicEvent::RepaintNeeded);
+        let replugged = detached
+            .and_then(|id| graph.connections.get(id))
+            .is_some_and(|conn| conn.from == source && Some(conn.to) == snap_target);
+        if !replugged {
+            if let Some(id) = detached {
+                _events.push(LogicEvent::Disconnect { id });
+            }
+            if let Some(target) = snap_target {
+                _events.push(LogicEvent::Connect {
+                    from: source,
+                    to: target,
+                });
+            }
+            if detached.is_some() || snap_target.is_some() {
+                _events.push(LogicEvent::RepaintNeeded);
+            }
         }
         return Some(InteractionMode::Idle);
     }
     None
 }
 
+/// The wire closest to `screen_pos`, if one passes within a few pixels of it. Wires are
+/// tested in screen space, where the painter lays out their curves.
+fn hit_connection<T: model::NodeData>(
+    view: &View,
+    graph: &GraphState<T>,
+    screen_pos: Vec2,
+) -> Option<model::ConnectionId> {
+    const TOLERANCE: f32 = 6.0;
+    let mut closest = None;
+    let mut closest_dist = TOLERANCE;
+
+    for (id, conn) in &graph.connections {
+        let (Some(from), Some(to)) = (
+            graph.find_port_position(conn.from),
+            graph.find_port_position(conn.to),
+        ) else {
+            continue;
+        };
+        for [start, cp1, cp2, end] in painter::wire_segments(view, from, &conn.waypoints, to) {
+            // Cheap reject: the curve lies within the hull of its control points
+            let hull = painter::segment_hull(&[start, cp1, cp2, end]);
+            if !hull.expand(TOLERANCE).contains(screen_pos) {
+                continue;
+            }
+
+            let dist = math::distance_to_bezier(screen_pos, start, cp1, cp2, end, 24);
+            if dist < closest_dist {
+                closest_dist = dist;
+                closest = Some(id);
+            }
+        }
+    }
+    closest
+}
+
+/// The waypoint handle under `screen_pos`, as its connection and index. Handles stay at least
+/// a few pixels wide when zoomed out so they remain grabbable.
+fn hit_waypoint<T: model::NodeData>(
+    view: &View,
+    graph: &GraphState<T>,
+    screen_pos: Vec2,
+) -> Option<(model::ConnectionId, usize)> {
+    let radius = (painter::WAYPOINT_RADIUS * view.transform.zoom).max(6.0);
+    graph.connections.iter().find_map(|(id, conn)| {
+        conn.waypoints
+            .iter()
+            .position(|p| view.world_to_screen(*p).distance(screen_pos) <= radius)
+            .map(|index| (id, index))
+    })
+}
+
+/// Handles the `DraggingWaypoint` state interactions.
+///
+/// Moves the waypoint with the mouse, snapping it to the grid when
+/// `CanvasConfig::snap_to_grid` is set.
+/// Returns to `Idle` on mouse release.
+#[allow(clippy::too_many_arguments)]
+fn handle_dragging_waypoint<T: model::NodeData>(
+    view: &View,
+    config: &CanvasConfig,
+    input: &InputState,
+    graph: &mut GraphState<T>,
+    connection: model::ConnectionId,
+    index: usize,
+    initial_position: Vec2,
+    start_mouse_world: Vec2,
+    _events: &mut Vec<LogicEvent>,
+) -> Option<InteractionMode> {
+    if !input.mouse_buttons.left {
+        return Some(InteractionMode::Idle);
+    }
+    let mut position = initial_position + view.screen_to_world(input.mouse_pos) - start_mouse_world;
+    if config.snap_to_grid && config.grid_size > 0.0 {
+        position = (position / config.grid_size).round() * config.grid_size;
+    }
+
+    if let Some(waypoint) = graph
+        .connections
+        .get_mut(connection)
+        .and_then(|conn| conn.waypoints.get_mut(index))
+    {
+        *waypoint = position;
+    }
+    _events.push(LogicEvent::RepaintNeeded);
+    None
+}
+
 /// Handles the `BoxSelecting` state interactions.
 ///
 /// Updates the selection box and selects nodes overlapping with it.
@@ -438,12 +917,18 @@ fn handle_box_selecting<T: model::NodeData>(
             }
         }
 
-        for (_, node) in &mut graph.nodes {
+        let candidates = graph.nodes_in_rect(Rect {
+            min: Vec2::new(min_x, min_y),
+            max: Vec2::new(max_x, max_y),
+        });
+        for node_id in candidates {
+            let node = &mut graph.nodes[node_id];
+            let bounds = node.rect();
             // Check overlap
-            if node.position.x < max_x
-                && node.position.x + node.size.x > min_x
-                && node.position.y < max_y
-                && node.position.y + node.size.y > min_y
+            if bounds.min.x < max_x
+                && bounds.max.x > min_x
+                && bounds.min.y < max_y
+                && bounds.max.y > min_y
             {
                 node.flags.insert(NodeFlags::SELECTED);
             }
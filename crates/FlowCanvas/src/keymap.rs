@@ -0,0 +1,189 @@
+//! # Keymap
+//!
+//! Keyboard shortcuts are resolved through a [`Keymap`] in `CanvasConfig` rather than being
+//! hard-coded in the interaction state machine. Each [`KeyBinding`] maps a [`KeyChord`] (a key
+//! plus modifiers) to a [`CanvasCommand`], so a host can offer remappable shortcuts by editing
+//! the bindings, and can list the chords bound to a command to display them in its menus.
+//!
+//! The `command` modifier of a chord matches Ctrl or Meta, so the same binding serves
+//! Ctrl+C on Windows/Linux and Cmd+C on macOS.
+
+use crate::input::{Key, ModifiersState};
+use serde::{Deserialize, Serialize};
+
+/// A direction to nudge the selection in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Unit vector in world space (y grows downwards).
+    pub fn vector(self) -> glam::Vec2 {
+        match self {
+            Direction::Up => glam::Vec2::NEG_Y,
+            Direction::Down => glam::Vec2::Y,
+            Direction::Left => glam::Vec2::NEG_X,
+            Direction::Right => glam::Vec2::X,
+        }
+    }
+}
+
+/// An action a keyboard shortcut can trigger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CanvasCommand {
+    /// Delete the selected wire, or request deleting the selected nodes.
+    Delete,
+    /// Select every node.
+    SelectAll,
+    /// Request copying the selection. See `GraphState::copy_selection`.
+    Copy,
+    /// Request cutting the selection. See `GraphState::cut_selection`.
+    Cut,
+    /// Request pasting the host's clipboard. See `GraphState::paste`.
+    Paste,
+    /// Request duplicating the selection. See `GraphState::duplicate_selection`.
+    Duplicate,
+    /// Move the selected nodes by `CanvasConfig::nudge_step`.
+    Nudge(Direction),
+    /// Move the selected nodes by one grid cell.
+    NudgeLarge(Direction),
+    /// Pan and zoom so the whole graph is in view.
+    ZoomToFit,
+    /// Ask the host to focus its node search.
+    FocusSearch,
+    /// Abort the current interaction: drop a wire being dragged, end a box selection, put
+    /// dragged nodes back. When idle, clears the selection.
+    Cancel,
+}
+
+/// A key together with the modifiers that must be held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct KeyChord {
+    pub key: Key,
+    /// Ctrl, or Meta (Cmd) on macOS.
+    #[serde(default)]
+    pub command: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl KeyChord {
+    /// The key on its own.
+    pub fn key(key: Key) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    /// Ctrl/Cmd + `key`.
+    pub fn command(key: Key) -> Self {
+        Self {
+            command: true,
+            ..Self::key(key)
+        }
+    }
+
+    /// Shift + `key`.
+    pub fn shift(key: Key) -> Self {
+        Self {
+            shift: true,
+            ..Self::key(key)
+        }
+    }
+
+    /// Whether this chord is `key` pressed with exactly these modifiers.
+    pub fn matches(&self, key: Key, modifiers: &ModifiersState) -> bool {
+        self.key == key
+            && self.command == (modifiers.ctrl || modifiers.meta)
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+    }
+}
+
+/// One shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub chord: KeyChord,
+    pub command: CanvasCommand,
+}
+
+/// The set of keyboard shortcuts the canvas responds to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keymap {
+    pub bindings: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use CanvasCommand::*;
+        let mut keymap = Self::empty();
+        keymap.bind(KeyChord::key(Key::Delete), Delete);
+        keymap.bind(KeyChord::key(Key::Backspace), Delete);
+        keymap.bind(KeyChord::command(Key::A), SelectAll);
+        keymap.bind(KeyChord::command(Key::C), Copy);
+        keymap.bind(KeyChord::command(Key::X), Cut);
+        keymap.bind(KeyChord::command(Key::V), Paste);
+        keymap.bind(KeyChord::command(Key::D), Duplicate);
+        for (key, direction) in [
+            (Key::ArrowUp, Direction::Up),
+            (Key::ArrowDown, Direction::Down),
+            (Key::ArrowLeft, Direction::Left),
+            (Key::ArrowRight, Direction::Right),
+        ] {
+            keymap.bind(KeyChord::key(key), Nudge(direction));
+            keymap.bind(KeyChord::shift(key), NudgeLarge(direction));
+        }
+        keymap.bind(KeyChord::key(Key::F), ZoomToFit);
+        keymap.bind(KeyChord::command(Key::F), FocusSearch);
+        keymap.bind(KeyChord::key(Key::Slash), FocusSearch);
+        keymap.bind(KeyChord::key(Key::Escape), Cancel);
+        keymap
+    }
+}
+
+impl Keymap {
+    /// A keymap with no shortcuts.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds `chord` to `command`, replacing whatever it was bound to.
+    pub fn bind(&mut self, chord: KeyChord, command: CanvasCommand) {
+        self.unbind(chord);
+        self.bindings.push(KeyBinding { chord, command });
+    }
+
+    /// Removes the binding for `chord`, returning the command it triggered.
+    pub fn unbind(&mut self, chord: KeyChord) -> Option<CanvasCommand> {
+        let index = self.bindings.iter().position(|b| b.chord == chord)?;
+        Some(self.bindings.remove(index).command)
+    }
+
+    /// The command `key` triggers with the given modifiers held, if any.
+    pub fn command_for(&self, key: Key, modifiers: &ModifiersState) -> Option<CanvasCommand> {
+        self.bindings
+            .iter()
+            .find(|b| b.chord.matches(key, modifiers))
+            .map(|b| b.command)
+    }
+
+    /// Every chord bound to `command`, in binding order.
+    pub fn chords_for(&self, command: CanvasCommand) -> Vec<KeyChord> {
+        self.bindings
+            .iter()
+            .filter(|b| b.command == command)
+            .map(|b| b.chord)
+            .collect()
+    }
+}
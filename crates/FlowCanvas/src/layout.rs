@@ -0,0 +1,104 @@
+//! # Node Layout
+//!
+//! Where a node's body and ports sit in world space. Painting, port hit-testing, wire routing
+//! and the spatial index all go through these helpers so they agree on the geometry.
+//!
+//! Ports are spaced evenly down each side of an expanded node, so a node can carry any number
+//! of them; `fit_node_to_ports` grows a node until each port has a row tall enough for its
+//! label. A collapsed node shrinks to its header bar and every port on a side shares a single
+//! summary position in the middle of the header, keeping wires attached while it is folded.
+
+use crate::math::Rect;
+use crate::model::{GraphState, Node, NodeFlags, NodeId};
+use glam::Vec2;
+
+/// Height of a node's header bar in world units. A collapsed node is only this tall.
+pub const NODE_HEADER_HEIGHT: f32 = 24.0;
+
+/// Smallest vertical distance between two ports on the same side, in world units.
+pub const PORT_ROW_HEIGHT: f32 = 20.0;
+
+impl<T> Node<T> {
+    pub fn is_collapsed(&self) -> bool {
+        self.flags.contains(NodeFlags::COLLAPSED)
+    }
+
+    /// The size the node occupies on the canvas: `size`, or just the header when collapsed.
+    pub fn visible_size(&self) -> Vec2 {
+        if self.is_collapsed() {
+            Vec2::new(self.size.x, NODE_HEADER_HEIGHT.min(self.size.y))
+        } else {
+            self.size
+        }
+    }
+
+    /// World-space bounds of the visible node.
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.position, self.visible_size())
+    }
+
+    /// World position of the `index`th input or output port.
+    pub fn port_position(&self, is_input: bool, index: usize) -> Vec2 {
+        if self.is_collapsed() {
+            return self.summary_port_position(is_input);
+        }
+        let count = if is_input {
+            self.inputs.len()
+        } else {
+            self.outputs.len()
+        };
+        let spacing = self.size.y / (count as f32 + 1.0);
+        self.side_top(is_input) + Vec2::new(0.0, spacing * (index as f32 + 1.0))
+    }
+
+    /// Where every input (or output) port meets its wires while the node is collapsed.
+    pub fn summary_port_position(&self, is_input: bool) -> Vec2 {
+        self.side_top(is_input) + Vec2::new(0.0, self.visible_size().y * 0.5)
+    }
+
+    /// Smallest height that gives each port on the busier side a row of `PORT_ROW_HEIGHT`.
+    pub fn min_height_for_ports(&self) -> f32 {
+        (self.inputs.len().max(self.outputs.len()) as f32 + 1.0) * PORT_ROW_HEIGHT
+    }
+
+    /// Top end of the edge the input (left) or output (right) ports sit on.
+    fn side_top(&self, is_input: bool) -> Vec2 {
+        if is_input {
+            self.position
+        } else {
+            Vec2::new(self.position.x + self.size.x, self.position.y)
+        }
+    }
+}
+
+impl<T> GraphState<T> {
+    /// Folds a node down to its header bar, or unfolds it. Returns whether the flag changed.
+    pub fn set_collapsed(&mut self, id: NodeId, collapsed: bool) -> bool {
+        let Some(node) = self.nodes.get_mut(id) else {
+            return false;
+        };
+        if node.is_collapsed() == collapsed {
+            return false;
+        }
+        node.flags.set(NodeFlags::COLLAPSED, collapsed);
+        self.mark_changed();
+        true
+    }
+
+    /// Flips a node between collapsed and expanded. Returns whether it is now collapsed.
+    pub fn toggle_collapsed(&mut self, id: NodeId) -> Option<bool> {
+        let node = self.nodes.get_mut(id)?;
+        node.flags.toggle(NodeFlags::COLLAPSED);
+        let collapsed = node.is_collapsed();
+        self.mark_changed();
+        Some(collapsed)
+    }
+
+    /// Grows a node's height so its ports are at least `PORT_ROW_HEIGHT` apart. Never shrinks it.
+    pub fn fit_node_to_ports(&mut self, id: NodeId) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.size.y = node.size.y.max(node.min_height_for_ports());
+            self.mark_changed();
+        }
+    }
+}
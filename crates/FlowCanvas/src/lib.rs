@@ -9,15 +9,25 @@
 //! - **View (`src/view.rs`)**: Handles coordinate transformation (World <-> Screen).
 //! - **Render (`src/render.rs`)**: Outputs a list of `DrawCommand`s for the host to render.
 
+pub mod activity;
+pub mod annotation;
+pub mod arrange;
+pub mod clipboard;
 pub mod config;
+pub mod gesture;
 pub mod history;
 pub mod input;
 pub mod interaction;
+pub mod keymap;
+pub mod layout;
 pub mod math;
 pub mod model;
 pub mod painter;
 pub mod persistence;
 pub mod render;
+pub mod spatial;
+pub mod theme;
+pub mod trail;
 pub mod view;
 
 use glam::Vec2;
@@ -41,6 +51,28 @@ pub struct Canvas {
     pub view: View,
     /// Current interaction mode.
     pub interaction_mode: InteractionMode,
+    /// Touch gesture tracking.
+    pub gestures: gesture::GestureState,
+    /// The last frame painted, reused while nothing it depends on changes.
+    render_cache: Option<RenderCache>,
+    /// Whether the previous frame emitted events the host may have answered by editing the graph.
+    events_pending: bool,
+    /// Whether the last `update` returned the cached draw list.
+    last_frame_cached: bool,
+}
+
+/// Everything a painted frame depends on, besides `CanvasConfig`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct FrameKey {
+    generation: u64,
+    pan: Vec2,
+    zoom: f32,
+    screen_size: Vec2,
+}
+
+struct RenderCache {
+    key: FrameKey,
+    draw_list: RenderList,
 }
 
 impl Canvas {
@@ -50,9 +82,30 @@ impl Canvas {
             config,
             view: View::new(Transform::default(), Vec2::new(800.0, 600.0)), // Default 800x600, user should update
             interaction_mode: InteractionMode::Idle,
+            gestures: gesture::GestureState::default(),
+            render_cache: None,
+            events_pending: false,
+            last_frame_cached: false,
         }
     }
 
+    /// Whether the last `update` skipped painting and returned the previous frame's draw list
+    /// unchanged, so a host can skip re-uploading it.
+    pub fn last_frame_cached(&self) -> bool {
+        self.last_frame_cached
+    }
+
+    /// Forces the next `update` to repaint, e.g. after changing `config`.
+    pub fn invalidate(&mut self) {
+        self.render_cache = None;
+    }
+
+    /// Repaints with `theme`, replacing `config.style`.
+    pub fn set_theme(&mut self, theme: &theme::Theme) {
+        self.config.style = theme.style();
+        self.invalidate();
+    }
+
     /// Updates the viewport size (e.g., on window resize).
     ///
     /// This should be called whenever the host application's window or panel size changes.
@@ -60,29 +113,132 @@ impl Canvas {
         self.view.viewport_size = size;
     }
 
+    /// Glides the view to the whole graph. Returns `false` for an empty graph.
+    pub fn zoom_to_fit<T: model::NodeData>(&mut self, graph: &GraphState<T>) -> bool {
+        let Some(bounds) = graph.content_bounds() else {
+            return false;
+        };
+        let target = self
+            .view
+            .fitted_transform(bounds, interaction::ZOOM_TO_FIT_PADDING);
+        self.view.animate_to(target, self.config.focus_duration);
+        true
+    }
+
+    /// Glides the view to the selected nodes. Returns `false` when nothing is selected.
+    pub fn focus_selection<T: model::NodeData>(&mut self, graph: &GraphState<T>) -> bool {
+        match graph.selected_bounds() {
+            Some(bounds) => {
+                self.focus_rect(bounds);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Glides the view to a single node, e.g. to jump to the node that reported an error.
+    /// Returns `false` if the node does not exist.
+    pub fn focus_node<T: model::NodeData>(
+        &mut self,
+        graph: &GraphState<T>,
+        id: model::NodeId,
+    ) -> bool {
+        match graph.nodes.get(id) {
+            Some(node) => {
+                self.focus_rect(node.rect());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Frames `rect`, zooming out if it does not fit but never zooming in past 100%, so a
+    /// small selection is not blown up to fill the screen.
+    fn focus_rect(&mut self, rect: math::Rect) {
+        let mut target = self
+            .view
+            .fitted_transform(rect, interaction::ZOOM_TO_FIT_PADDING);
+        if target.zoom > 1.0 {
+            target = self
+                .view
+                .centered_transform((rect.min + rect.max) * 0.5, 1.0);
+        }
+        self.view.animate_to(target, self.config.focus_duration);
+    }
+
     /// The core update loop.
     ///
     /// This function should be called every frame (or on event). It processes the `GraphState`
     /// and returns a list of drawing commands (`RenderList`) that the host application should render.
+    /// `dt` is the time since the previous call in seconds; it ages live execution activity.
+    ///
+    /// When `CanvasConfig::retain_render_list` is set, a frame in which nothing happened (no
+    /// events, no interaction or live activity, the same view, and the same
+    /// `GraphState::generation`) returns a copy of the previous draw list instead of painting.
     pub fn update<T: model::NodeData>(
         &mut self,
         input: &InputState,
-        _dt: f32,
+        dt: f32,
         graph: &mut GraphState<T>,
     ) -> (RenderList, Vec<LogicEvent>) {
         let mut logic_events = Vec::new();
+        let was_idle = matches!(self.interaction_mode, InteractionMode::Idle);
+
+        let live = !graph.activity.is_empty();
+        graph.advance_activity(dt, self.config.activity_decay);
+
+        // 0. View transitions. Panning or zooming by hand takes over from them.
+        if takes_over_view(input) {
+            self.view.animation = None;
+        }
+        if self.view.advance_animation(dt) {
+            logic_events.push(LogicEvent::RepaintNeeded);
+        }
 
-        // 1. Handle Interactions (Pan, Zoom, Select, Drag)
+        // 1. Touch & trackpad gestures (Pinch, Two-finger pan, Long press)
+        let input = self.gestures.process(
+            input,
+            dt,
+            &mut self.interaction_mode,
+            &mut self.view,
+            &self.config,
+            graph,
+            &mut logic_events,
+        );
+
+        // 2. Handle Interactions (Pan, Zoom, Select, Drag)
         interaction::handle_interactions(
             &mut self.interaction_mode,
             &mut self.view,
             &self.config,
-            input,
+            &input,
             graph,
             &mut logic_events,
         );
 
-        // 2. Render
+        // 3. Render, unless nothing changed since the last frame. Interactions edit the
+        // graph's fields directly, and the host answers events between frames, so both count
+        // as changes.
+        let busy = !was_idle || !matches!(self.interaction_mode, InteractionMode::Idle);
+        if live || busy || self.events_pending || !logic_events.is_empty() {
+            graph.mark_changed();
+        }
+        self.events_pending = !logic_events.is_empty();
+
+        let key = FrameKey {
+            generation: graph.generation(),
+            pan: self.view.transform.pan,
+            zoom: self.view.transform.zoom,
+            screen_size: input.screen_size,
+        };
+        self.last_frame_cached = false;
+        if let Some(cache) = &self.render_cache
+            && cache.key == key
+        {
+            self.last_frame_cached = true;
+            return (cache.draw_list.clone(), logic_events);
+        }
+
         let draw_list = painter::Painter::draw_graph(
             &self.view,
             &self.config,
@@ -90,7 +246,20 @@ impl Canvas {
             &self.interaction_mode,
             input.screen_size,
         );
+        self.render_cache = self.config.retain_render_list.then(|| RenderCache {
+            key,
+            draw_list: draw_list.clone(),
+        });
 
         (draw_list, logic_events)
     }
 }
+
+/// Whether `input` pans or zooms the view directly.
+fn takes_over_view(input: &InputState) -> bool {
+    input.scroll_delta != 0.0
+        || input.pinch_delta != 0.0
+        || input.pan_delta != Vec2::ZERO
+        || input.mouse_buttons.middle
+        || input.touches.len() >= 2
+}
@@ -1,7 +1,7 @@
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub min: Vec2,
     pub max: Vec2,
@@ -47,6 +47,44 @@ pub fn calculate_bezier_points(start: Vec2, end: Vec2) -> (Vec2, Vec2) {
     (cp1, cp2)
 }
 
+/// The point at `t` (0.0 - 1.0) along a cubic Bezier curve.
+pub fn bezier_point(start: Vec2, cp1: Vec2, cp2: Vec2, end: Vec2, t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    start * (u * u * u) + cp1 * (3.0 * u * u * t) + cp2 * (3.0 * u * t * t) + end * (t * t * t)
+}
+
+/// Approximate distance from `point` to a cubic Bezier curve, measured against a polyline of
+/// `segments` straight pieces.
+pub fn distance_to_bezier(
+    point: Vec2,
+    start: Vec2,
+    cp1: Vec2,
+    cp2: Vec2,
+    end: Vec2,
+    segments: usize,
+) -> f32 {
+    let segments = segments.max(1);
+    let mut previous = start;
+    let mut closest = f32::INFINITY;
+    for i in 1..=segments {
+        let next = bezier_point(start, cp1, cp2, end, i as f32 / segments as f32);
+        closest = closest.min(distance_to_segment(point, previous, next));
+        previous = next;
+    }
+    closest
+}
+
+/// Distance from `point` to the segment `a`-`b`.
+pub fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_squared() > 0.0 {
+        ((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + ab * t)
+}
+
 /// Calculates a smart orthogonal path avoiding obstacles.
 pub fn calculate_smart_orthogonal(
     start: Vec2,
@@ -342,3 +380,55 @@ pub fn calculate_orthogonal_points(start: Vec2, end: Vec2) -> Vec<Vec2> {
 pub fn calculate_linear_points(start: Vec2, end: Vec2) -> Vec<Vec2> {
     vec![start, end]
 }
+
+/// Cubic Bezier segments `[start, cp1, cp2, end]` for a wire passing through `waypoints` in
+/// order. Every segment leaves and enters horizontally, so the joins at the waypoints are smooth.
+pub fn calculate_bezier_segments(start: Vec2, waypoints: &[Vec2], end: Vec2) -> Vec<[Vec2; 4]> {
+    calculate_linear_path(start, waypoints, end)
+        .windows(2)
+        .map(|pair| {
+            let (cp1, cp2) = calculate_bezier_points(pair[0], pair[1]);
+            [pair[0], cp1, cp2, pair[1]]
+        })
+        .collect()
+}
+
+/// The point at `t` (0.0 - 1.0) along a chain of Bezier segments, giving each segment an
+/// equal share of `t`.
+pub fn bezier_path_point(segments: &[[Vec2; 4]], t: f32) -> Option<Vec2> {
+    let count = segments.len();
+    if count == 0 {
+        return None;
+    }
+    let scaled = t.clamp(0.0, 1.0) * count as f32;
+    let index = (scaled as usize).min(count - 1);
+    let [start, cp1, cp2, end] = segments[index];
+    Some(bezier_point(start, cp1, cp2, end, scaled - index as f32))
+}
+
+/// Like [`calculate_smart_orthogonal`], but routed through `waypoints` in order: each leg
+/// between consecutive points avoids the obstacles on its own, and the legs are joined.
+pub fn calculate_smart_orthogonal_through(
+    start: Vec2,
+    waypoints: &[Vec2],
+    end: Vec2,
+    obstacles: &[Rect],
+    buffer: f32,
+) -> Vec<Vec2> {
+    let mut path: Vec<Vec2> = Vec::new();
+    for pair in calculate_linear_path(start, waypoints, end).windows(2) {
+        let leg = calculate_smart_orthogonal(pair[0], pair[1], obstacles, buffer);
+        // Each leg starts where the previous one ended
+        let skip = usize::from(path.last() == leg.first());
+        path.extend(leg.into_iter().skip(skip));
+    }
+    path
+}
+
+/// Straight-line path through `waypoints`.
+pub fn calculate_linear_path(start: Vec2, waypoints: &[Vec2], end: Vec2) -> Vec<Vec2> {
+    std::iter::once(start)
+        .chain(waypoints.iter().copied())
+        .chain(std::iter::once(end))
+        .collect()
+}
@@ -9,6 +9,7 @@ use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use slotmap::SlotMap;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Trait that user data must implement to be stored in the graph.
 pub trait NodeData: Clone + std::fmt::Debug {
@@ -100,6 +101,7 @@ macro_rules! define_id_type {
 define_id_type!(NodeId);
 define_id_type!(PortId);
 define_id_type!(ConnectionId);
+define_id_type!(AnnotationId);
 
 /// Bitflags representing various boolean states of a Node.
 use bitflags::bitflags;
@@ -114,6 +116,14 @@ bitflags! {
         const HIDDEN = 1 << 1;
         /// The node is currently selected by the user.
         const SELECTED = 1 << 2;
+        /// The node executed recently. Set from engine activity and cleared as it decays.
+        const RUNNING = 1 << 3;
+        /// The node failed recently. Set from engine activity and cleared as it decays.
+        const ERROR = 1 << 4;
+        /// The node is folded down to its header bar, with its wires meeting at one port per side.
+        const COLLAPSED = 1 << 5;
+        /// The transient execution flags, which are never persisted.
+        const LIVE = Self::RUNNING.bits() | Self::ERROR.bits();
     }
 }
 
@@ -174,7 +184,12 @@ pub struct Port {
     pub id: PortId,
     /// ID of the Node this port belongs to.
     pub node: NodeId,
-    // Add other relevant port info if needed, e.g., name, type
+    /// Name shown next to the port, if any.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Type of the data the port carries, used to color it.
+    #[serde(default)]
+    pub data_type: Option<String>,
 }
 
 /// Visual style of the connection wire.
@@ -199,6 +214,12 @@ pub struct Connection {
     pub style: WireStyle,
     /// Optional visual style override (color/width).
     pub visual_style: Option<crate::config::EdgeStyle>,
+    /// World-space points the wire is routed through, in order from source to target.
+    #[serde(default)]
+    pub waypoints: Vec<Vec2>,
+    /// Text drawn at the middle of the wire, if any.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 /// The entire state of the Graph.
@@ -213,6 +234,9 @@ pub struct GraphState<T> {
     pub ports: SlotMap<PortId, Port>,
     /// Arena for Connections.
     pub connections: SlotMap<ConnectionId, Connection>,
+    /// Arena for non-executable annotations (comments and group frames).
+    #[serde(default)]
+    pub annotations: SlotMap<AnnotationId, crate::annotation::Annotation>,
     /// Draw order cache.
     /// Lower index = Background/Bottom.
     /// Higher index = Foreground/Top.
@@ -220,6 +244,30 @@ pub struct GraphState<T> {
     /// Index for O(1) UUID to NodeId lookup.
     #[serde(default, skip)]
     pub uuid_index: HashMap<Uuid, NodeId>,
+    /// Execution trail to overlay on the graph, if any.
+    #[serde(default, skip)]
+    pub trail: Option<crate::trail::ExecutionTrail>,
+    /// Live execution flags and connection pulses.
+    #[serde(default, skip)]
+    pub activity: crate::activity::LiveActivity,
+    /// The wire the user clicked, if any. Delete unplugs it.
+    #[serde(default, skip)]
+    pub selected_connection: Option<ConnectionId>,
+    /// Grid index of node bounds for hit-testing and culling. Rebuilt on load.
+    #[serde(default, skip)]
+    pub spatial: crate::spatial::SpatialIndex,
+    /// Identifies the current content. See `GraphState::generation`.
+    #[serde(skip, default = "next_generation")]
+    generation: u64,
+}
+
+/// Generations are drawn from one counter shared by every graph, so a fresh graph, or one
+/// whose contents were swapped for an undo snapshot, never reuses a generation that was
+/// painted for different content.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+fn next_generation() -> u64 {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
 }
 
 impl<T> Default for GraphState<T> {
@@ -228,12 +276,35 @@ impl<T> Default for GraphState<T> {
             nodes: SlotMap::with_key(),
             ports: SlotMap::with_key(),
             connections: SlotMap::with_key(),
+            annotations: SlotMap::with_key(),
             draw_order: Vec::new(),
             uuid_index: HashMap::new(),
+            trail: None,
+            activity: Default::default(),
+            selected_connection: None,
+            spatial: Default::default(),
+            generation: next_generation(),
         }
     }
 }
 
+impl<T> GraphState<T> {
+    /// Changes whenever the graph is edited through its methods or by an interaction, and
+    /// stays the same otherwise. `Canvas::update` reuses the previous frame's draw list while
+    /// the generation and the view are unchanged.
+    ///
+    /// Writing the public fields directly (moving a node, setting `trail`, selecting from
+    /// the host) does not change it; call `mark_changed` afterwards.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Records that the graph changed, so the next frame is painted afresh.
+    pub fn mark_changed(&mut self) {
+        self.generation = next_generation();
+    }
+}
+
 impl<T: NodeData> GraphState<T> {
     /// Helper to find the world position of a port.
     pub fn find_port_position(&self, port_id: PortId) -> Option<Vec2> {
@@ -241,17 +312,11 @@ impl<T: NodeData> GraphState<T> {
         let node = self.nodes.get(port.node)?;
 
         if let Some(idx) = node.inputs.iter().position(|&id| id == port_id) {
-            let spacing = node.size.y / (node.inputs.len() as f32 + 1.0);
-            let y = node.position.y + spacing * (idx as f32 + 1.0);
-            let x = node.position.x;
-            return Some(Vec2::new(x, y));
+            return Some(node.port_position(true, idx));
         }
 
         if let Some(idx) = node.outputs.iter().position(|&id| id == port_id) {
-            let spacing = node.size.y / (node.outputs.len() as f32 + 1.0);
-            let y = node.position.y + spacing * (idx as f32 + 1.0);
-            let x = node.position.x + node.size.x;
-            return Some(Vec2::new(x, y));
+            return Some(node.port_position(false, idx));
         }
 
         None
@@ -259,6 +324,7 @@ impl<T: NodeData> GraphState<T> {
 
     /// Inserts a node and updates the UUID index.
     pub fn insert_node(&mut self, mut node: Node<T>) -> NodeId {
+        self.mark_changed();
         let id = self.nodes.insert_with_key(|key| {
             node.id = key;
             node
@@ -272,18 +338,37 @@ impl<T: NodeData> GraphState<T> {
     /// Removes a node and updates the UUID index.
     pub fn remove_node(&mut self, id: NodeId) -> Option<Node<T>> {
         if let Some(node) = self.nodes.remove(id) {
+            self.mark_changed();
             self.uuid_index.remove(&node.uuid);
+            self.activity.forget_node(id);
+            self.spatial.remove(id);
             Some(node)
         } else {
             None
         }
     }
 
+    /// Removes a node together with its ports, its connections and its draw order entry.
+    pub fn delete_node(&mut self, id: NodeId) -> Option<Node<T>> {
+        let node = self.remove_node(id)?;
+        for port in node.inputs.iter().chain(&node.outputs) {
+            self.ports.remove(*port);
+        }
+        self.connections.retain(|_, conn| {
+            self.ports.contains_key(conn.from) && self.ports.contains_key(conn.to)
+        });
+        self.draw_order.retain(|n| *n != id);
+        Some(node)
+    }
+
     /// Adds a port to a node.
     pub fn add_port(&mut self, node_id: NodeId, is_input: bool) -> PortId {
+        self.mark_changed();
         let port_id = self.ports.insert_with_key(|key| Port {
             id: key,
             node: node_id,
+            label: None,
+            data_type: None,
         });
 
         if let Some(node) = self.nodes.get_mut(node_id) {
@@ -296,6 +381,23 @@ impl<T: NodeData> GraphState<T> {
         port_id
     }
 
+    /// Adds a named, typed port to a node, growing the node if needed so every port gets a
+    /// row of its own.
+    pub fn add_labeled_port(
+        &mut self,
+        node_id: NodeId,
+        is_input: bool,
+        label: impl Into<String>,
+        data_type: Option<String>,
+    ) -> PortId {
+        let port_id = self.add_port(node_id, is_input);
+        let port = &mut self.ports[port_id];
+        port.label = Some(label.into());
+        port.data_type = data_type;
+        self.fit_node_to_ports(node_id);
+        port_id
+    }
+
     /// Connects two ports with a specific style.
     pub fn connect_with_style(
         &mut self,
@@ -303,11 +405,14 @@ impl<T: NodeData> GraphState<T> {
         to: PortId,
         style: WireStyle,
     ) -> ConnectionId {
+        self.mark_changed();
         self.connections.insert(Connection {
             from,
             to,
             style,
             visual_style: None,
+            waypoints: Vec::new(),
+            label: None,
         })
     }
 
@@ -317,15 +422,82 @@ impl<T: NodeData> GraphState<T> {
     }
 
     pub fn set_connection_style(&mut self, id: ConnectionId, style: WireStyle) {
+        self.mark_changed();
         if let Some(conn) = self.connections.get_mut(id) {
             conn.style = style;
         }
     }
 
-    pub fn get_node_rects(&self) -> Vec<crate::math::Rect> {
+    /// Sets or clears the text drawn along a connection.
+    pub fn set_connection_label(&mut self, id: ConnectionId, label: Option<String>) {
+        self.mark_changed();
+        if let Some(conn) = self.connections.get_mut(id) {
+            conn.label = label;
+        }
+    }
+
+    /// The type of data flowing over a connection: its source port's type, or the target's
+    /// when the source is untyped.
+    pub fn connection_data_type(&self, id: ConnectionId) -> Option<&str> {
+        let conn = self.connections.get(id)?;
+        [conn.from, conn.to]
+            .into_iter()
+            .find_map(|port| self.ports.get(port)?.data_type.as_deref())
+    }
+
+    /// Adds a waypoint to a connection at `point`, on the leg of the wire it is closest to.
+    /// Returns its index in `Connection::waypoints`.
+    pub fn insert_waypoint(&mut self, id: ConnectionId, point: Vec2) -> Option<usize> {
+        let conn = self.connections.get(id)?;
+        let start = self.find_port_position(conn.from)?;
+        let end = self.find_port_position(conn.to)?;
+        let path = crate::math::calculate_linear_path(start, &conn.waypoints, end);
+        let index = path
+            .windows(2)
+            .map(|leg| crate::math::distance_to_segment(point, leg[0], leg[1]))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)?;
+        self.connections[id].waypoints.insert(index, point);
+        self.mark_changed();
+        Some(index)
+    }
+
+    /// Removes a connection's waypoint, returning its position.
+    pub fn remove_waypoint(&mut self, id: ConnectionId, index: usize) -> Option<Vec2> {
+        let waypoints = &mut self.connections.get_mut(id)?.waypoints;
+        let removed = (index < waypoints.len()).then(|| waypoints.remove(index));
+        if removed.is_some() {
+            self.mark_changed();
+        }
+        removed
+    }
+
+    /// World-space bounds of every node and annotation, or `None` for an empty graph.
+    pub fn content_bounds(&self) -> Option<crate::math::Rect> {
         self.nodes
             .values()
-            .map(|node| crate::math::Rect::new(node.position, node.size))
-            .collect()
+            .map(|node| node.rect())
+            .chain(self.annotations.values().map(|a| a.rect()))
+            .reduce(|a, b| crate::math::Rect {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
+    }
+
+    /// World-space bounds of the selected nodes, or `None` when nothing is selected.
+    pub fn selected_bounds(&self) -> Option<crate::math::Rect> {
+        self.nodes
+            .values()
+            .filter(|node| node.flags.contains(NodeFlags::SELECTED))
+            .map(|node| node.rect())
+            .reduce(|a, b| crate::math::Rect {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
+    }
+
+    pub fn get_node_rects(&self) -> Vec<crate::math::Rect> {
+        self.nodes.values().map(|node| node.rect()).collect()
     }
 }
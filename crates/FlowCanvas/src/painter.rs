@@ -1,5 +1,6 @@
 use glam::Vec2;
 
+use crate::annotation::{self, AnnotationKind};
 use crate::config::CanvasConfig;
 use crate::interaction::InteractionMode;
 use crate::math;
@@ -7,16 +8,71 @@ use crate::model::{self, GraphState, NodeFlags};
 use crate::render::{DrawCommand, RenderList};
 use crate::view::View;
 
+/// Radius of a waypoint handle on a wire, in world units.
+pub const WAYPOINT_RADIUS: f32 = 4.0;
+
+/// Gap between a port and its label, in world units.
+pub const PORT_LABEL_INSET: f32 = 8.0;
+
+/// How far a selected node's fill is tinted towards `CanvasStyle::selected_border_color`.
+pub const SELECTED_TINT: f32 = 0.2;
+
+/// Gap between a wire and the bottom of its label, in world units.
+pub const EDGE_LABEL_GAP: f32 = 4.0;
+
+/// The screen-space Bezier segments of a wire from `start` to `end` through `waypoints` (all in
+/// world space). Hit-testing uses the same layout as painting.
+pub fn wire_segments(view: &View, start: Vec2, waypoints: &[Vec2], end: Vec2) -> Vec<[Vec2; 4]> {
+    let waypoints: Vec<Vec2> = waypoints.iter().map(|p| view.world_to_screen(*p)).collect();
+    math::calculate_bezier_segments(
+        view.world_to_screen(start),
+        &waypoints,
+        view.world_to_screen(end),
+    )
+}
+
+/// Bounding box of a Bezier segment's control polygon, which contains the curve.
+pub(crate) fn segment_hull(segment: &[Vec2; 4]) -> math::Rect {
+    let [start, cp1, cp2, end] = *segment;
+    math::Rect {
+        min: start.min(end).min(cp1).min(cp2),
+        max: start.max(end).max(cp1).max(cp2),
+    }
+}
+
+/// How much of the graph the painter draws, chosen from the zoom level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetailLevel {
+    /// Everything: rounded, outlined nodes, ports and labels, curved wires.
+    Full,
+    /// A zoomed-out overview: flat node rects and straight wires, no ports, handles or text.
+    Simplified,
+}
+
+impl DetailLevel {
+    /// The level for the current zoom, using the thresholds in `config`.
+    pub fn for_view(view: &View, config: &CanvasConfig) -> Self {
+        if view.transform.zoom < config.lod_zoom {
+            DetailLevel::Simplified
+        } else {
+            DetailLevel::Full
+        }
+    }
+}
+
 /// High-level renderer for the FlowCanvas graph.
 ///
 /// The `Painter` is responsible for converting the abstract graph state (Nodes, Ports, Connections)
 /// into concrete drawing commands (`RenderList`) that the host application can render.
 /// It handles:
 /// - Grid rendering
+/// - Comment boxes and group frames (behind everything else)
 /// - Node shape and style (including selection highlights)
-/// - Port positioning and rendering
+/// - Port positioning and rendering (labels, data-type colors, collapsed summary ports)
 /// - Wire rendering (Bezier curves)
 /// - Z-ordering (painters algorithm)
+/// - Viewport culling (off-screen nodes and wires are skipped)
+/// - Level of detail (see [`DetailLevel`]; the grid is dropped below `grid_min_zoom`)
 pub struct Painter;
 
 impl Painter {
@@ -24,7 +80,7 @@ impl Painter {
     ///
     /// # Arguments
     /// * `view` - The current viewport transform (pan/zoom).
-    /// * `_config` - Canvas configuration (unused for now).
+    /// * `config` - Canvas configuration (style and level-of-detail thresholds).
     /// * `graph` - The graph state to render.
     /// * `interaction_mode` - Current interaction state (used for rendering active wires/selection boxes).
     /// * `screen_size` - dimensions of the viewport in pixels (used for culling/grid).
@@ -37,43 +93,175 @@ impl Painter {
     ) -> RenderList {
         let mut draw_list = Vec::new();
         let style = &config.style;
+        let simplified = DetailLevel::for_view(view, config) == DetailLevel::Simplified;
+
+        // Visible area, in screen space for wires and world space for the node index.
+        // Half a port's width of slack keeps ports on a node just off screen.
+        graph.sync_spatial_index();
+        let screen_rect = math::Rect::new(Vec2::ZERO, screen_size);
+        let visible_nodes = graph.nodes_in_rect(
+            math::Rect {
+                min: view.screen_to_world(Vec2::ZERO),
+                max: view.screen_to_world(screen_size),
+            }
+            .expand(5.0),
+        );
 
         // 1. Background grid
-        Self::draw_grid(view, style, screen_size, &mut draw_list);
+        if view.transform.zoom >= config.grid_min_zoom {
+            Self::draw_grid(view, style, screen_size, &mut draw_list);
+        }
+
+        // 2. Annotations (groups behind comments)
+        Self::draw_annotations(view, style, graph, simplified, &mut draw_list);
 
-        // 2. Render Connections (Behind nodes)
-        for (_id, connection) in &graph.connections {
+        // A wire unplugged by the user is drawn as the active link instead
+        let detached = match interaction_mode {
+            InteractionMode::Linking { detached, .. } => *detached,
+            _ => None,
+        };
+
+        // 3. Render Connections (Behind nodes)
+        for (id, connection) in &graph.connections {
+            if Some(id) == detached {
+                continue;
+            }
             let start_pos = graph.find_port_position(connection.from);
             let end_pos = graph.find_port_position(connection.to);
 
             if let (Some(start_world), Some(end_world)) = (start_pos, end_pos) {
-                let screen_start = view.world_to_screen(start_world);
-                let screen_end = view.world_to_screen(end_world);
+                let segments = if simplified {
+                    Vec::new()
+                } else {
+                    wire_segments(view, start_world, &connection.waypoints, end_world)
+                };
+                let points: Vec<Vec2> = if simplified {
+                    std::iter::once(start_world)
+                        .chain(connection.waypoints.iter().copied())
+                        .chain(std::iter::once(end_world))
+                        .map(|p| view.world_to_screen(p))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
 
-                let (cp1, cp2) = math::calculate_bezier_points(screen_start, screen_end);
+                // The curve lies within the hull of its control points
+                let on_screen = if simplified {
+                    points.windows(2).any(|pair| {
+                        math::Rect {
+                            min: pair[0].min(pair[1]),
+                            max: pair[0].max(pair[1]),
+                        }
+                        .intersects(&screen_rect)
+                    })
+                } else {
+                    segments
+                        .iter()
+                        .any(|segment| segment_hull(segment).intersects(&screen_rect))
+                };
+                if !on_screen {
+                    continue;
+                }
 
-                // Use overrides if present, otherwise default
-                let (color, width) = if let Some(override_style) = &connection.visual_style {
+                // Use overrides if present, otherwise color by the data flowing over the wire
+                let (mut color, width) = if let Some(override_style) = &connection.visual_style {
                     (override_style.color, override_style.width)
                 } else {
-                    (style.edge_default.color, style.edge_default.width)
+                    (
+                        style.edge_color_for(graph.connection_data_type(id)),
+                        style.edge_default.width,
+                    )
                 };
+                let mut label_color = style.node_default.text_color;
 
-                draw_list.push(DrawCommand::Bezier {
-                    start: screen_start,
-                    end: screen_end,
-                    cp1,
-                    cp2,
-                    color,
-                    width,
-                });
+                if graph.selected_connection == Some(id) {
+                    color = style.selected_edge_color;
+                }
+
+                // Trail overlay: highlight the path taken, ghost the rest
+                if let Some(trail) = &graph.trail {
+                    if trail.visited_connection(id) {
+                        color = style.trail.traversed_edge_color;
+                    } else {
+                        color.w *= style.trail.skipped_opacity;
+                        label_color.w *= style.trail.skipped_opacity;
+                    }
+                }
+
+                if simplified {
+                    for pair in points.windows(2) {
+                        draw_list.push(DrawCommand::Line {
+                            start: pair[0],
+                            end: pair[1],
+                            color,
+                            width,
+                        });
+                    }
+                    continue;
+                }
+
+                for &[start, cp1, cp2, end] in &segments {
+                    draw_list.push(DrawCommand::Bezier {
+                        start,
+                        end,
+                        cp1,
+                        cp2,
+                        color,
+                        width,
+                    });
+                }
+
+                // Waypoint handles
+                let handle = WAYPOINT_RADIUS * view.transform.zoom;
+                for &[start, ..] in &segments[1..] {
+                    draw_list.push(DrawCommand::Rect {
+                        pos: start - Vec2::splat(handle),
+                        size: Vec2::splat(handle * 2.0),
+                        color,
+                        corner_radius: handle,
+                        stroke_width: 0.0,
+                        stroke_color: None,
+                    });
+                }
+
+                // Live activity: a dot travelling along a wire a ticket just crossed
+                if let Some(progress) = graph.activity.pulse(id)
+                    && let Some(center) = math::bezier_path_point(&segments, progress)
+                {
+                    let radius = style.activity.pulse_radius * view.transform.zoom;
+                    draw_list.push(DrawCommand::Rect {
+                        pos: center - Vec2::splat(radius),
+                        size: Vec2::splat(radius * 2.0),
+                        color: style.activity.pulse_color,
+                        corner_radius: radius,
+                        stroke_width: 0.0,
+                        stroke_color: None,
+                    });
+                }
+
+                // Label: centered just above the middle of the curve, against an estimated
+                // width since text is laid out by the host
+                if let Some(label) = &connection.label
+                    && let Some(center) = math::bezier_path_point(&segments, 0.5)
+                {
+                    let size = style.edge_label_size * view.transform.zoom;
+                    let width = label.chars().count() as f32 * size * 0.5;
+                    draw_list.push(DrawCommand::Text {
+                        pos: center
+                            - Vec2::new(width * 0.5, size + EDGE_LABEL_GAP * view.transform.zoom),
+                        text: label.clone(),
+                        color: label_color,
+                        size,
+                    });
+                }
             }
         }
 
-        // 3. Render Active Link (Dragging)
+        // 4. Render Active Link (Dragging)
         if let InteractionMode::Linking {
             source,
             curr_pos_world,
+            ..
         } = interaction_mode
         {
             // Calculate start pos
@@ -88,13 +276,13 @@ impl Painter {
                     end: screen_end,
                     cp1,
                     cp2,
-                    color: glam::Vec4::new(1.0, 1.0, 1.0, 1.0), // Active link is white
+                    color: style.active_link_color,
                     width: 2.0,
                 });
             }
         }
 
-        // 4. Draw nodes based on Z-Order
+        // 5. Draw nodes based on Z-Order
         // Lazy populate draw order if empty
         if graph.draw_order.is_empty() && !graph.nodes.is_empty() {
             for (id, _) in &graph.nodes {
@@ -103,31 +291,81 @@ impl Painter {
         }
 
         for &node_id in &graph.draw_order {
+            if visible_nodes.binary_search(&node_id).is_err() {
+                continue;
+            }
             if let Some(node) = graph.nodes.get(node_id) {
                 // Project world pos to screen pos
                 let screen_pos = view.world_to_screen(node.position);
-                let scaled_size = node.size * view.transform.zoom;
+                let scaled_size = node.visible_size() * view.transform.zoom;
 
-                // Resolve style: Override > Default
-                let node_style = node.style.as_ref().unwrap_or(&style.node_default);
+                // Resolve style: Override > Category > Default
+                let node_style = match &node.style {
+                    Some(node_style) => node_style,
+                    None if style.category_styles.is_empty() => &style.node_default,
+                    None => style.node_style_for(&node.data.node_type()),
+                };
 
-                let color = if node.flags.contains(NodeFlags::SELECTED) {
-                    node_style.color * 1.2 // Highlight
+                let selected = node.flags.contains(NodeFlags::SELECTED);
+                let mut color = if selected {
+                    // Highlight, keeping the fill's opacity
+                    node_style
+                        .color
+                        .lerp(style.selected_border_color, SELECTED_TINT)
+                        .with_w(node_style.color.w)
                 } else {
                     node_style.color
                 };
 
-                let stroke_color = if node.flags.contains(NodeFlags::SELECTED) {
-                    Some(node_style.border_color * 1.5) // Highlight border
+                let mut stroke_color = if selected {
+                    Some(style.selected_border_color)
                 } else {
                     Some(node_style.border_color)
                 };
 
-                let stroke_width = if node.flags.contains(NodeFlags::SELECTED) {
-                    2.0
-                } else {
-                    1.0
-                };
+                let mut stroke_width = if selected { 2.0 } else { 1.0 };
+
+                // Live activity: outline nodes that just ran or failed
+                let mut accent = None;
+                if node.flags.contains(NodeFlags::ERROR) {
+                    accent = Some(style.activity.error_border_color);
+                    stroke_width = 2.0;
+                } else if node.flags.contains(NodeFlags::RUNNING) {
+                    accent = Some(style.activity.running_border_color);
+                    stroke_width = 2.0;
+                }
+
+                // Trail overlay: ghost skipped nodes, outline failed ones
+                let mut port_opacity = 1.0;
+                if let Some(trail) = &graph.trail {
+                    if !trail.visited_node(node_id) {
+                        let opacity = style.trail.skipped_opacity;
+                        color.w *= opacity;
+                        port_opacity = opacity;
+                        stroke_color = stroke_color.map(|mut c| {
+                            c.w *= opacity;
+                            c
+                        });
+                    } else if trail.failed.contains(&node_id) {
+                        accent = Some(style.trail.failed_border_color);
+                    }
+                }
+
+                // Overview: a flat rect, filled with the outline color when it carries news
+                if simplified {
+                    draw_list.push(DrawCommand::Rect {
+                        pos: screen_pos,
+                        size: scaled_size,
+                        color: accent.unwrap_or(color),
+                        corner_radius: 0.0,
+                        stroke_width: 0.0,
+                        stroke_color: None,
+                    });
+                    continue;
+                }
+                if accent.is_some() {
+                    stroke_color = accent;
+                }
 
                 draw_list.push(DrawCommand::Rect {
                     pos: screen_pos,
@@ -139,40 +377,49 @@ impl Painter {
                 });
 
                 // Render Ports
-                // Inputs
-                let spacing_in = node.size.y / (node.inputs.len() as f32 + 1.0);
-                for (i, _) in node.inputs.iter().enumerate() {
-                    let local_y = spacing_in * (i as f32 + 1.0);
-                    let world_pos = node.position + Vec2::new(0.0, local_y);
-                    let screen_port_pos = view.world_to_screen(world_pos);
-                    let port_size = Vec2::new(10.0, 10.0) * view.transform.zoom; // 10px ports
-
-                    draw_list.push(DrawCommand::Rect {
-                        pos: screen_port_pos - (port_size * 0.5), // Center it
-                        size: port_size,
-                        color: style.port_color,
-                        corner_radius: 5.0 * view.transform.zoom, // Circle
-                        stroke_width: 1.0,
-                        stroke_color: Some(glam::Vec4::new(0.0, 0.0, 0.0, 1.0)),
-                    });
+                if node.is_collapsed() {
+                    // One summary port per side stands in for all of them
+                    let mut port_color = style.port_color;
+                    port_color.w *= port_opacity;
+                    for (is_input, ports) in [(true, &node.inputs), (false, &node.outputs)] {
+                        if !ports.is_empty() {
+                            let world_pos = node.summary_port_position(is_input);
+                            Self::draw_port(view, style, world_pos, port_color, &mut draw_list);
+                        }
+                    }
+                    continue;
                 }
 
-                // Outputs
-                let spacing_out = node.size.y / (node.outputs.len() as f32 + 1.0);
-                for (i, _) in node.outputs.iter().enumerate() {
-                    let local_y = spacing_out * (i as f32 + 1.0);
-                    let world_pos = node.position + Vec2::new(node.size.x, local_y);
-                    let screen_port_pos = view.world_to_screen(world_pos);
-                    let port_size = Vec2::new(10.0, 10.0) * view.transform.zoom; // 10px ports
-
-                    draw_list.push(DrawCommand::Rect {
-                        pos: screen_port_pos - (port_size * 0.5), // Center it
-                        size: port_size,
-                        color: style.port_color,
-                        corner_radius: 5.0 * view.transform.zoom, // Circle
-                        stroke_width: 1.0,
-                        stroke_color: Some(glam::Vec4::new(0.0, 0.0, 0.0, 1.0)),
-                    });
+                let mut label_color = node_style.text_color;
+                label_color.w *= port_opacity;
+                for (is_input, ports) in [(true, &node.inputs), (false, &node.outputs)] {
+                    for (i, port_id) in ports.iter().enumerate() {
+                        let port = graph.ports.get(*port_id);
+                        let mut port_color =
+                            style.port_color_for(port.and_then(|p| p.data_type.as_deref()));
+                        port_color.w *= port_opacity;
+
+                        let world_pos = node.port_position(is_input, i);
+                        Self::draw_port(view, style, world_pos, port_color, &mut draw_list);
+
+                        if let Some(label) = port.and_then(|p| p.label.as_ref()) {
+                            let size = style.port_label_size;
+                            // Text is laid out by the host, so output labels are right-aligned
+                            // against an estimated width
+                            let offset = if is_input {
+                                Vec2::new(PORT_LABEL_INSET, -size * 0.5)
+                            } else {
+                                let width = label.chars().count() as f32 * size * 0.5;
+                                Vec2::new(-PORT_LABEL_INSET - width, -size * 0.5)
+                            };
+                            draw_list.push(DrawCommand::Text {
+                                pos: view.world_to_screen(world_pos + offset),
+                                text: label.clone(),
+                                color: label_color,
+                                size: size * view.transform.zoom,
+                            });
+                        }
+                    }
                 }
             }
         }
@@ -207,6 +454,91 @@ impl Painter {
         draw_list
     }
 
+    /// Renders a port as a small circle centered on `world_pos`.
+    fn draw_port(
+        view: &View,
+        style: &crate::config::CanvasStyle,
+        world_pos: Vec2,
+        color: glam::Vec4,
+        draw_list: &mut RenderList,
+    ) {
+        let port_size = Vec2::new(10.0, 10.0) * view.transform.zoom; // 10px ports
+        draw_list.push(DrawCommand::Rect {
+            pos: view.world_to_screen(world_pos) - (port_size * 0.5), // Center it
+            size: port_size,
+            color,
+            corner_radius: 5.0 * view.transform.zoom, // Circle
+            stroke_width: 1.0,
+            stroke_color: Some(style.port_outline_color),
+        });
+    }
+
+    /// Renders comment boxes and group frames, each with its text and a resize handle. A
+    /// `simplified` overview leaves out the text and handles.
+    fn draw_annotations<T: model::NodeData>(
+        view: &View,
+        style: &crate::config::CanvasStyle,
+        graph: &GraphState<T>,
+        simplified: bool,
+        draw_list: &mut RenderList,
+    ) {
+        let zoom = view.transform.zoom;
+        let annotation_style = &style.annotation;
+        let padding = 6.0 * zoom;
+
+        for id in graph.annotation_draw_order() {
+            let item = &graph.annotations[id];
+            let screen_pos = view.world_to_screen(item.position);
+            let scaled_size = item.size * zoom;
+
+            let (fill, text) = match &item.kind {
+                AnnotationKind::Comment { text } => (annotation_style.comment_color, text),
+                AnnotationKind::Group { title } => (annotation_style.group_color, title),
+            };
+
+            draw_list.push(DrawCommand::Rect {
+                pos: screen_pos,
+                size: scaled_size,
+                color: item.color.unwrap_or(fill),
+                corner_radius: 3.0 * zoom,
+                stroke_width: 1.0,
+                stroke_color: Some(annotation_style.border_color),
+            });
+
+            if item.is_group() {
+                draw_list.push(DrawCommand::Rect {
+                    pos: screen_pos,
+                    size: Vec2::new(scaled_size.x, annotation::GROUP_HEADER_HEIGHT * zoom),
+                    color: annotation_style.group_header_color,
+                    corner_radius: 3.0 * zoom,
+                    stroke_width: 0.0,
+                    stroke_color: None,
+                });
+            }
+
+            if simplified {
+                continue;
+            }
+
+            draw_list.push(DrawCommand::Text {
+                pos: screen_pos + Vec2::splat(padding),
+                text: text.clone(),
+                color: annotation_style.text_color,
+                size: annotation_style.text_size * zoom,
+            });
+
+            let handle = Vec2::splat(annotation::RESIZE_HANDLE_SIZE * zoom);
+            draw_list.push(DrawCommand::Rect {
+                pos: screen_pos + scaled_size - handle,
+                size: handle,
+                color: annotation_style.border_color,
+                corner_radius: 0.0,
+                stroke_width: 0.0,
+                stroke_color: None,
+            });
+        }
+    }
+
     /// Renders an infinite background grid.
     ///
     /// This helper calculates the visible world bounds based on the viewport and
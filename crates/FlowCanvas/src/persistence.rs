@@ -1,4 +1,20 @@
-use crate::model::{self, Connection, GraphState, Node, NodeData, Port, PortId, WireStyle};
+//! # Persistence
+//!
+//! `GraphState` is saved as a `SavedGraph`: nodes keyed by stable UUID, ports by index, so a
+//! snapshot does not depend on the arena keys of the process that wrote it.
+//!
+//! `save_to_json` wraps the snapshot in a [`GraphDocument`], which adds a format version, the
+//! back-to-front draw order and the camera transform. Documents stay loadable in both
+//! directions: fields added since a document was written fall back to their defaults (a bare
+//! `SavedGraph` with no version at all loads as version 0), and fields this build does not know
+//! about are ignored, so a document written by a newer build loads as far as it can.
+
+use crate::annotation::{Annotation, AnnotationKind};
+use crate::model::{
+    self, AnnotationId, Connection, ConnectionId, GraphState, Node, NodeData, NodeId, Port, PortId,
+    WireStyle,
+};
+use crate::view::Transform;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,6 +31,10 @@ pub struct SavedConnection {
     pub to_port_index: usize,
     pub style: WireStyle,
     pub visual_style: Option<crate::config::EdgeStyle>,
+    #[serde(default)]
+    pub waypoints: Vec<Vec2>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 /// A serializable representation of a Node.
@@ -27,10 +47,31 @@ pub struct SavedNode<T> {
     pub flags: model::NodeFlags,
     pub style: Option<crate::config::NodeStyle>,
     /// We save the number of ports to recreate them.
-    /// If ports had distinct data (names, types), we would save a `SavedPort` struct here or in a list.
-    /// For now, ports are structural.
     pub input_count: usize,
     pub output_count: usize,
+    /// Labels and data types of the input ports, by index. Empty when none carry any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<SavedPort>,
+    /// Labels and data types of the output ports, by index. Empty when none carry any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<SavedPort>,
+}
+
+/// The descriptive part of a Port. Its identity is its index on the node.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SavedPort {
+    pub label: Option<String>,
+    pub data_type: Option<String>,
+}
+
+/// A serializable representation of a comment box or group frame.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedAnnotation {
+    pub uuid: Uuid,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub kind: AnnotationKind,
+    pub color: Option<glam::Vec4>,
 }
 
 /// A serializable snapshot of the Graph.
@@ -38,30 +79,125 @@ pub struct SavedNode<T> {
 pub struct SavedGraph<T> {
     pub nodes: Vec<SavedNode<T>>,
     pub connections: Vec<SavedConnection>,
+    /// Comments and group frames. Missing from snapshots written before annotations existed.
+    #[serde(default)]
+    pub annotations: Vec<SavedAnnotation>,
+}
+
+/// Version of the [`GraphDocument`] format written by this build.
+pub const GRAPH_FORMAT_VERSION: u32 = 1;
+
+/// A complete saved canvas: the graph, its draw order and where the user was looking.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphDocument<T> {
+    /// `GRAPH_FORMAT_VERSION` of the build that wrote the document; 0 if it predates versioning.
+    #[serde(default)]
+    pub version: u32,
+    #[serde(flatten)]
+    pub graph: SavedGraph<T>,
+    /// Node UUIDs from back to front.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub draw_order: Vec<Uuid>,
+    /// The camera transform when the document was saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<Transform>,
 }
 
 impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
+    /// Wraps a full snapshot in a versioned [`GraphDocument`].
+    pub fn document(&self, view: Option<Transform>) -> GraphDocument<T> {
+        GraphDocument {
+            version: GRAPH_FORMAT_VERSION,
+            graph: self.save(),
+            draw_order: self
+                .draw_order
+                .iter()
+                .filter_map(|id| self.nodes.get(*id))
+                .map(|node| node.uuid)
+                .collect(),
+            view,
+        }
+    }
+
+    /// Loads a [`GraphDocument`], REPLACING the current state. Returns the saved camera
+    /// transform, if any, for the host to apply to its `View`.
+    pub fn load_document(&mut self, document: GraphDocument<T>) -> Option<Transform> {
+        self.load(document.graph);
+
+        // Nodes named in the saved order go first, in that order; any others keep their place.
+        let rank: HashMap<Uuid, usize> = document
+            .draw_order
+            .iter()
+            .enumerate()
+            .map(|(rank, uuid)| (*uuid, rank))
+            .collect();
+        let nodes = &self.nodes;
+        self.draw_order.sort_by_key(|id| {
+            nodes
+                .get(*id)
+                .and_then(|node| rank.get(&node.uuid))
+                .copied()
+                .unwrap_or(usize::MAX)
+        });
+
+        document.view
+    }
+
+    /// Serializes the graph, its draw order and the optional camera transform as a versioned
+    /// JSON document.
+    pub fn save_to_json(&self, view: Option<Transform>) -> serde_json::Result<String> {
+        serde_json::to_string(&self.document(view))
+    }
+
+    /// Loads a document written by `save_to_json`, or a bare `SavedGraph`, REPLACING the
+    /// current state. Returns the saved camera transform, if any.
+    ///
+    /// On error the graph is left untouched.
+    pub fn load_from_json(&mut self, json: &str) -> serde_json::Result<Option<Transform>> {
+        let document: GraphDocument<T> = serde_json::from_str(json)?;
+        Ok(self.load_document(document))
+    }
+
     /// Serializes the graph state into a `SavedGraph` payload.
     pub fn save(&self) -> SavedGraph<T> {
+        let mut saved = self.save_filtered(|_| true, Vec2::ZERO);
+        saved.annotations = self
+            .annotations
+            .keys()
+            .filter_map(|id| self.saved_annotation(id))
+            .collect();
+        saved
+    }
+
+    /// Serializes a subset of nodes as a reusable snippet.
+    ///
+    /// Only connections between selected nodes are kept, and positions are made relative to
+    /// the top-left corner of the selection so the snippet can be placed anywhere.
+    pub fn save_selection(&self, selection: &[NodeId]) -> SavedGraph<T> {
+        let origin = selection
+            .iter()
+            .filter_map(|id| self.nodes.get(*id))
+            .map(|n| n.position)
+            .reduce(Vec2::min)
+            .unwrap_or(Vec2::ZERO);
+        self.save_filtered(|id| selection.contains(&id), origin)
+    }
+
+    fn save_filtered(&self, include: impl Fn(NodeId) -> bool, origin: Vec2) -> SavedGraph<T> {
         let mut saved_nodes = Vec::new();
-        let mut node_id_to_uuid = HashMap::new();
-
-        for (id, node) in &self.nodes {
-            saved_nodes.push(SavedNode {
-                uuid: node.uuid,
-                position: node.position,
-                size: node.size,
-                data: node.data.clone(),
-                flags: node.flags,
-                style: node.style.clone(),
-                input_count: node.inputs.len(),
-                output_count: node.outputs.len(),
-            });
-            node_id_to_uuid.insert(id, node.uuid);
+
+        for id in self.nodes.keys() {
+            if !include(id) {
+                continue;
+            }
+            if let Some(mut saved) = self.saved_node(id) {
+                saved.position -= origin;
+                saved_nodes.push(saved);
+            }
         }
 
         let mut saved_connections = Vec::new();
-        for (_id, conn) in &self.connections {
+        for (id, conn) in &self.connections {
             // Resolve Ports to Nodes
             let from_port = self
                 .ports
@@ -72,41 +208,104 @@ impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
                 .get(conn.to)
                 .expect("Connection with invalid To Port");
 
-            let from_node = self
-                .nodes
-                .get(from_port.node)
-                .expect("Port with invalid Node");
-            let to_node = self
-                .nodes
-                .get(to_port.node)
-                .expect("Port with invalid Node");
+            if !include(from_port.node) || !include(to_port.node) {
+                continue;
+            }
 
-            // Find index of port in node's list
-            let from_idx = from_node
-                .outputs
-                .iter()
-                .position(|&p| p == conn.from)
-                .unwrap_or(0);
-            let to_idx = to_node
-                .inputs
-                .iter()
-                .position(|&p| p == conn.to)
-                .unwrap_or(0);
-
-            saved_connections.push(SavedConnection {
-                from_node: from_node.uuid,
-                from_port_index: from_idx,
-                to_node: to_node.uuid,
-                to_port_index: to_idx,
-                style: conn.style.clone(),
-                visual_style: conn.visual_style.clone(),
-            });
+            let mut saved = self.saved_connection(id).expect("Port with invalid Node");
+            for point in &mut saved.waypoints {
+                *point -= origin;
+            }
+            saved_connections.push(saved);
         }
 
         SavedGraph {
             nodes: saved_nodes,
             connections: saved_connections,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// The persisted form of a single node, at its absolute position.
+    pub fn saved_node(&self, id: NodeId) -> Option<SavedNode<T>> {
+        let node = self.nodes.get(id)?;
+        Some(SavedNode {
+            uuid: node.uuid,
+            position: node.position,
+            size: node.size,
+            data: node.data.clone(),
+            flags: node.flags.difference(model::NodeFlags::LIVE),
+            style: node.style.clone(),
+            input_count: node.inputs.len(),
+            output_count: node.outputs.len(),
+            inputs: self.port_metadata(&node.inputs),
+            outputs: self.port_metadata(&node.outputs),
+        })
+    }
+
+    /// Port metadata for `ports`, or nothing if they are all anonymous.
+    fn port_metadata(&self, ports: &[PortId]) -> Vec<SavedPort> {
+        let saved: Vec<SavedPort> = ports
+            .iter()
+            .map(|id| {
+                self.ports
+                    .get(*id)
+                    .map(|port| SavedPort {
+                        label: port.label.clone(),
+                        data_type: port.data_type.clone(),
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        if saved
+            .iter()
+            .all(|port| port.label.is_none() && port.data_type.is_none())
+        {
+            return Vec::new();
         }
+        saved
+    }
+
+    /// The persisted form of a single annotation, at its absolute position.
+    pub fn saved_annotation(&self, id: AnnotationId) -> Option<SavedAnnotation> {
+        let annotation = self.annotations.get(id)?;
+        Some(SavedAnnotation {
+            uuid: annotation.uuid,
+            position: annotation.position,
+            size: annotation.size,
+            kind: annotation.kind.clone(),
+            color: annotation.color,
+        })
+    }
+
+    /// The persisted form of a single connection, or `None` if its ports are dangling.
+    pub fn saved_connection(&self, id: ConnectionId) -> Option<SavedConnection> {
+        let conn = self.connections.get(id)?;
+        let from_node = self.nodes.get(self.ports.get(conn.from)?.node)?;
+        let to_node = self.nodes.get(self.ports.get(conn.to)?.node)?;
+
+        // Find index of port in node's list
+        let from_idx = from_node
+            .outputs
+            .iter()
+            .position(|&p| p == conn.from)
+            .unwrap_or(0);
+        let to_idx = to_node
+            .inputs
+            .iter()
+            .position(|&p| p == conn.to)
+            .unwrap_or(0);
+
+        Some(SavedConnection {
+            from_node: from_node.uuid,
+            from_port_index: from_idx,
+            to_node: to_node.uuid,
+            to_port_index: to_idx,
+            style: conn.style.clone(),
+            visual_style: conn.visual_style.clone(),
+            waypoints: conn.waypoints.clone(),
+            label: conn.label.clone(),
+        })
     }
 
     /// Loads a `SavedGraph` payload, REPLACING the current state.
@@ -115,20 +314,39 @@ impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
         self.nodes.clear();
         self.ports.clear();
         self.connections.clear();
+        self.annotations.clear();
         self.draw_order.clear();
+        self.uuid_index.clear();
+
+        self.restore(saved, Vec2::ZERO, false);
+    }
+
+    /// Adds a snippet to the graph at `position`, with fresh UUIDs so it can be
+    /// instantiated several times. Returns the new node IDs.
+    pub fn instantiate(&mut self, snippet: SavedGraph<T>, position: Vec2) -> Vec<NodeId> {
+        self.restore(snippet, position, true)
+    }
 
+    fn restore(&mut self, saved: SavedGraph<T>, offset: Vec2, fresh_uuids: bool) -> Vec<NodeId> {
+        self.mark_changed();
         let mut uuid_to_new_id = HashMap::new();
+        let mut created = Vec::new();
 
         // 1. Restore Nodes
         for saved_node in saved.nodes {
+            let uuid = if fresh_uuids {
+                Uuid::new_v4()
+            } else {
+                saved_node.uuid
+            };
             let node_id = self.nodes.insert_with_key(|key| {
                 // We need to create Ports first to put in struct,
                 // BUT ports need NodeId. Circular dep with SlotMap insert?
                 // SlotMap allows `insert_with_key`.
                 Node {
                     id: key,
-                    uuid: saved_node.uuid,
-                    position: saved_node.position,
+                    uuid,
+                    position: saved_node.position + offset,
                     size: saved_node.size,
                     inputs: Vec::new(), // Will fill momentarily
                     outputs: Vec::new(),
@@ -140,17 +358,23 @@ impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
 
             // Create Ports
             let mut inputs = Vec::new();
-            for _ in 0..saved_node.input_count {
+            for i in 0..saved_node.input_count {
+                let meta = saved_node.inputs.get(i).cloned().unwrap_or_default();
                 inputs.push(self.ports.insert(Port {
                     id: PortId::default(), // overwritten
                     node: node_id,
+                    label: meta.label,
+                    data_type: meta.data_type,
                 }));
             }
             let mut outputs = Vec::new();
-            for _ in 0..saved_node.output_count {
+            for i in 0..saved_node.output_count {
+                let meta = saved_node.outputs.get(i).cloned().unwrap_or_default();
                 outputs.push(self.ports.insert(Port {
                     id: PortId::default(), // overwritten
                     node: node_id,
+                    label: meta.label,
+                    data_type: meta.data_type,
                 }));
             }
 
@@ -174,7 +398,9 @@ impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
             }
 
             self.draw_order.push(node_id);
+            self.uuid_index.insert(uuid, node_id);
             uuid_to_new_id.insert(saved_node.uuid, node_id);
+            created.push(node_id);
         }
 
         // 2. Restore Connections
@@ -182,28 +408,304 @@ impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
             if let (Some(&from_node_id), Some(&to_node_id)) = (
                 uuid_to_new_id.get(&saved_conn.from_node),
                 uuid_to_new_id.get(&saved_conn.to_node),
-            ) {
-                // Find ports
-                let from_port = self
-                    .nodes
-                    .get(from_node_id)
-                    .and_then(|n| n.outputs.get(saved_conn.from_port_index).copied());
-                let to_port = self
-                    .nodes
-                    .get(to_node_id)
-                    .and_then(|n| n.inputs.get(saved_conn.to_port_index).copied());
-
-                if let (Some(from), Some(to)) = (from_port, to_port) {
+            ) && let Some((from, to)) = self.resolve_ports(from_node_id, to_node_id, &saved_conn)
+            {
+                self.connections.insert(Connection {
+                    from,
+                    to,
+                    style: saved_conn.style,
+                    visual_style: saved_conn.visual_style,
+                    waypoints: saved_conn
+                        .waypoints
+                        .iter()
+                        .map(|point| *point + offset)
+                        .collect(),
+                    label: saved_conn.label,
+                });
+            }
+        }
+
+        // 3. Restore Annotations
+        for saved_annotation in saved.annotations {
+            let uuid = if fresh_uuids {
+                Uuid::new_v4()
+            } else {
+                saved_annotation.uuid
+            };
+            self.insert_annotation(saved_annotation, uuid, offset);
+        }
+
+        created
+    }
+
+    fn insert_annotation(
+        &mut self,
+        saved: SavedAnnotation,
+        uuid: Uuid,
+        offset: Vec2,
+    ) -> AnnotationId {
+        self.annotations.insert_with_key(|id| Annotation {
+            id,
+            uuid,
+            position: saved.position + offset,
+            size: saved.size,
+            kind: saved.kind,
+            color: saved.color,
+        })
+    }
+
+    /// Finds the ports a saved connection refers to on the given nodes.
+    fn resolve_ports(
+        &self,
+        from_node: NodeId,
+        to_node: NodeId,
+        saved: &SavedConnection,
+    ) -> Option<(PortId, PortId)> {
+        let from = self
+            .nodes
+            .get(from_node)?
+            .outputs
+            .get(saved.from_port_index)
+            .copied()?;
+        let to = self
+            .nodes
+            .get(to_node)?
+            .inputs
+            .get(saved.to_port_index)
+            .copied()?;
+        Some((from, to))
+    }
+}
+
+/// A single edit to the graph, keyed by stable UUIDs so it can be replayed onto a loaded
+/// snapshot. Port references use the same indices as [`SavedConnection`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum GraphOp<T> {
+    AddNode(SavedNode<T>),
+    /// Removes the node together with its ports and connections.
+    RemoveNode {
+        uuid: Uuid,
+    },
+    MoveNode {
+        uuid: Uuid,
+        position: Vec2,
+    },
+    UpdateNode {
+        uuid: Uuid,
+        data: T,
+    },
+    Connect(SavedConnection),
+    /// Removes every connection between the two ports.
+    Disconnect(SavedConnection),
+    /// Adds an annotation, or replaces the one with the same UUID.
+    SetAnnotation(SavedAnnotation),
+    RemoveAnnotation {
+        uuid: Uuid,
+    },
+}
+
+impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> GraphState<T> {
+    /// Applies a recorded edit. Returns false if it refers to nodes or ports that don't exist.
+    pub fn apply(&mut self, op: GraphOp<T>) -> bool {
+        self.mark_changed();
+        match op {
+            GraphOp::AddNode(saved) => {
+                if self.node_by_uuid(saved.uuid).is_some() {
+                    return false;
+                }
+                self.restore(
+                    SavedGraph {
+                        nodes: vec![saved],
+                        connections: Vec::new(),
+                        annotations: Vec::new(),
+                    },
+                    Vec2::ZERO,
+                    false,
+                );
+                true
+            }
+            GraphOp::RemoveNode { uuid } => {
+                let Some(id) = self.node_by_uuid(uuid) else {
+                    return false;
+                };
+                self.delete_node(id).is_some()
+            }
+            GraphOp::MoveNode { uuid, position } => self
+                .node_by_uuid(uuid)
+                .and_then(|id| self.nodes.get_mut(id))
+                .map(|node| node.position = position)
+                .is_some(),
+            GraphOp::UpdateNode { uuid, data } => self
+                .node_by_uuid(uuid)
+                .and_then(|id| self.nodes.get_mut(id))
+                .map(|node| node.data = data)
+                .is_some(),
+            GraphOp::Connect(saved) => match self.saved_ports(&saved) {
+                Some((from, to)) => {
                     self.connections.insert(Connection {
                         from,
                         to,
-                        style: saved_conn.style,
-                        visual_style: saved_conn.visual_style,
+                        style: saved.style,
+                        visual_style: saved.visual_style,
+                        waypoints: saved.waypoints,
+                        label: saved.label,
                     });
+                    true
                 }
+                None => false,
+            },
+            GraphOp::Disconnect(saved) => {
+                let Some((from, to)) = self.saved_ports(&saved) else {
+                    return false;
+                };
+                let before = self.connections.len();
+                self.connections
+                    .retain(|_, conn| conn.from != from || conn.to != to);
+                self.connections.len() < before
             }
+            GraphOp::SetAnnotation(saved) => {
+                match self.annotation_by_uuid(saved.uuid) {
+                    Some(id) => {
+                        let annotation = &mut self.annotations[id];
+                        annotation.position = saved.position;
+                        annotation.size = saved.size;
+                        annotation.kind = saved.kind;
+                        annotation.color = saved.color;
+                    }
+                    None => {
+                        let uuid = saved.uuid;
+                        self.insert_annotation(saved, uuid, Vec2::ZERO);
+                    }
+                }
+                true
+            }
+            GraphOp::RemoveAnnotation { uuid } => self
+                .annotation_by_uuid(uuid)
+                .and_then(|id| self.annotations.remove(id))
+                .is_some(),
+        }
+    }
+
+    /// Applies a sequence of edits in order, skipping any that no longer apply. Returns how
+    /// many were applied.
+    pub fn replay(&mut self, ops: impl IntoIterator<Item = GraphOp<T>>) -> usize {
+        let mut applied = 0;
+        for op in ops {
+            if self.apply(op) {
+                applied += 1;
+            }
+        }
+        applied
+    }
+
+    /// Loads a snapshot and replays the operation log written after it.
+    pub fn load_with_log(
+        &mut self,
+        snapshot: SavedGraph<T>,
+        ops: impl IntoIterator<Item = GraphOp<T>>,
+    ) -> usize {
+        self.load(snapshot);
+        self.replay(ops)
+    }
+
+    /// Looks a node up by UUID, falling back to a scan for nodes inserted without the index.
+    fn node_by_uuid(&self, uuid: Uuid) -> Option<NodeId> {
+        match self.uuid_index.get(&uuid) {
+            Some(id) if self.nodes.contains_key(*id) => Some(*id),
+            _ => self
+                .nodes
+                .iter()
+                .find(|(_, node)| node.uuid == uuid)
+                .map(|(id, _)| id),
         }
+    }
+
+    fn annotation_by_uuid(&self, uuid: Uuid) -> Option<AnnotationId> {
+        self.annotations
+            .iter()
+            .find(|(_, annotation)| annotation.uuid == uuid)
+            .map(|(id, _)| id)
+    }
+
+    fn saved_ports(&self, saved: &SavedConnection) -> Option<(PortId, PortId)> {
+        let from_node = self.node_by_uuid(saved.from_node)?;
+        let to_node = self.node_by_uuid(saved.to_node)?;
+        self.resolve_ports(from_node, to_node, saved)
+    }
+}
+
+/// What an autosave should write, as decided by [`SaveJournal::flush`].
+#[derive(Clone, Debug)]
+pub enum Autosave<T> {
+    /// Append these operations to the log written since the last snapshot.
+    Append(Vec<GraphOp<T>>),
+    /// The log has grown past the compaction threshold: replace the stored snapshot with this
+    /// one and truncate the log.
+    Snapshot(SavedGraph<T>),
+}
+
+/// Tracks edits between autosaves so large graphs can be persisted as an append-only
+/// operation log instead of a full snapshot on every change.
+///
+/// Record a [`GraphOp`] for each edit, then call [`flush`](Self::flush) whenever the host
+/// autosaves. Restore with [`GraphState::load_with_log`].
+#[derive(Clone, Debug)]
+pub struct SaveJournal<T> {
+    pending: Vec<GraphOp<T>>,
+    /// Operations already flushed to the log since the last snapshot.
+    logged: usize,
+    /// Log length at which the next flush writes a fresh snapshot instead.
+    pub compact_after: usize,
+}
+
+impl<T> Default for SaveJournal<T> {
+    fn default() -> Self {
+        Self::new(500)
+    }
+}
+
+impl<T> SaveJournal<T> {
+    pub fn new(compact_after: usize) -> Self {
+        Self {
+            pending: Vec::new(),
+            logged: 0,
+            compact_after,
+        }
+    }
+
+    /// Records an edit that has already been applied to the graph.
+    pub fn record(&mut self, op: GraphOp<T>) {
+        self.pending.push(op);
+    }
+
+    /// True if there are edits that haven't been flushed yet.
+    pub fn is_dirty(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Number of operations in the log since the last snapshot, including unflushed ones.
+    pub fn log_len(&self) -> usize {
+        self.logged + self.pending.len()
+    }
+}
+
+impl<T: NodeData + Serialize + for<'de> Deserialize<'de>> SaveJournal<T> {
+    /// Returns what the next autosave should write, or `None` if nothing changed.
+    pub fn flush(&mut self, graph: &GraphState<T>) -> Option<Autosave<T>> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        if self.log_len() >= self.compact_after {
+            return Some(Autosave::Snapshot(self.compact(graph)));
+        }
+        self.logged += self.pending.len();
+        Some(Autosave::Append(std::mem::take(&mut self.pending)))
+    }
 
-        self.uuid_index = uuid_to_new_id;
+    /// Takes a full snapshot and starts a new, empty log.
+    pub fn compact(&mut self, graph: &GraphState<T>) -> SavedGraph<T> {
+        self.pending.clear();
+        self.logged = 0;
+        graph.save()
     }
 }
@@ -0,0 +1,152 @@
+//! # Spatial Index
+//!
+//! A uniform grid over world space that buckets nodes by their bounds, so hit-testing, port
+//! snapping and viewport culling only look at the nodes near a point or inside a rectangle
+//! instead of the whole graph.
+//!
+//! Node positions are plain fields that the host and the interaction logic write directly, so
+//! the index is not updated on every write. Instead `GraphState::sync_spatial_index` compares
+//! each node against the bounds it was indexed at and re-buckets only the ones that moved,
+//! resized, appeared or disappeared. `handle_interactions` and `Painter::draw_graph` sync before
+//! they query, so the index is never more than one call out of date.
+
+use crate::math::Rect;
+use crate::model::{GraphState, NodeId};
+use glam::Vec2;
+use slotmap::SecondaryMap;
+use std::collections::HashMap;
+
+/// Side of a grid cell in world units. A few node widths, so a typical node spans 1-4 cells.
+pub const CELL_SIZE: f32 = 256.0;
+
+type Cell = (i32, i32);
+
+/// Grid of node IDs keyed by cell.
+#[derive(Clone, Debug, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<Cell, Vec<NodeId>>,
+    /// The bounds each node was indexed at.
+    bounds: SecondaryMap<NodeId, Rect>,
+}
+
+impl SpatialIndex {
+    /// Number of indexed nodes.
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Indexes a node at `rect`, moving it if it was indexed elsewhere.
+    pub fn insert(&mut self, id: NodeId, rect: Rect) {
+        if self.bounds.get(id) == Some(&rect) {
+            return;
+        }
+        self.remove(id);
+        for cell in cells(&rect) {
+            self.cells.entry(cell).or_default().push(id);
+        }
+        self.bounds.insert(id, rect);
+    }
+
+    pub fn remove(&mut self, id: NodeId) {
+        let Some(rect) = self.bounds.remove(id) else {
+            return;
+        };
+        for cell in cells(&rect) {
+            if let Some(ids) = self.cells.get_mut(&cell) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// Nodes whose indexed bounds intersect `rect` (edges included), sorted and deduplicated.
+    pub fn query(&self, rect: Rect) -> Vec<NodeId> {
+        let (min, max) = (cell_of(rect.min), cell_of(rect.max));
+        let span = ((max.0 - min.0 + 1) as usize).saturating_mul((max.1 - min.1 + 1) as usize);
+
+        let mut found: Vec<NodeId> = if span > self.cells.len() {
+            // Zoomed far out: walking the occupied cells is cheaper than the covered ones.
+            self.cells.values().flatten().copied().collect()
+        } else {
+            (min.0..=max.0)
+                .flat_map(|x| (min.1..=max.1).map(move |y| (x, y)))
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .copied()
+                .collect()
+        };
+        found.sort_unstable();
+        found.dedup();
+        // Slots reused before a sync can leave an old key behind in a cell.
+        found.retain(|id| self.bounds.get(*id).is_some_and(|b| touches(b, &rect)));
+        found
+    }
+
+    /// Nodes whose indexed bounds contain `point`.
+    pub fn query_point(&self, point: Vec2) -> Vec<NodeId> {
+        self.query(Rect::new(point, Vec2::ZERO))
+    }
+}
+
+impl<T> GraphState<T> {
+    /// Brings the spatial index up to date with node positions and sizes, re-bucketing only the
+    /// nodes that changed.
+    pub fn sync_spatial_index(&mut self) {
+        for (id, node) in &self.nodes {
+            self.spatial.insert(id, node.rect());
+        }
+        if self.spatial.len() > self.nodes.len() {
+            let gone: Vec<NodeId> = self
+                .spatial
+                .bounds
+                .keys()
+                .filter(|id| !self.nodes.contains_key(*id))
+                .collect();
+            for id in gone {
+                self.spatial.remove(id);
+            }
+        }
+    }
+
+    /// Nodes intersecting `rect` in world space, according to the last sync.
+    pub fn nodes_in_rect(&self, rect: Rect) -> Vec<NodeId> {
+        self.spatial.query(rect)
+    }
+
+    /// The topmost node in draw order whose bounds contain `point`, according to the last sync.
+    pub fn node_at(&self, point: Vec2) -> Option<NodeId> {
+        let hits = self.spatial.query_point(point);
+        if hits.is_empty() {
+            return None;
+        }
+        self.draw_order
+            .iter()
+            .rev()
+            .find(|id| hits.binary_search(id).is_ok())
+            .copied()
+    }
+}
+
+fn cell_of(point: Vec2) -> Cell {
+    (
+        (point.x / CELL_SIZE).floor() as i32,
+        (point.y / CELL_SIZE).floor() as i32,
+    )
+}
+
+fn cells(rect: &Rect) -> impl Iterator<Item = Cell> {
+    let (min, max) = (cell_of(rect.min), cell_of(rect.max));
+    (min.0..=max.0).flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+}
+
+/// Like `Rect::intersects`, but rectangles that only share an edge count, so a point on a
+/// node's border still hits it.
+fn touches(a: &Rect, b: &Rect) -> bool {
+    a.min.x <= b.max.x && a.max.x >= b.min.x && a.min.y <= b.max.y && a.max.y >= b.min.y
+}
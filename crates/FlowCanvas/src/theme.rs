@@ -0,0 +1,163 @@
+//! # Theming
+//!
+//! A [`Theme`] is the small palette a canvas is painted with: background, grid, node fill and
+//! border, selection, ports, wires and text. [`Theme::style`] expands it into the full
+//! `CanvasStyle` the painter reads, so a host can switch between the built-in presets, or ship
+//! its own palette, without setting every color in the style by hand.
+//!
+//! Nodes can be colored by kind: `Theme::categories` maps a `NodeData::node_type` to the
+//! `NodeStyle` its nodes are drawn with, unless a node carries its own style.
+
+use crate::config::{
+    self, ActivityStyle, AnnotationStyle, CanvasStyle, EdgeStyle, NodeStyle, TrailStyle,
+};
+use glam::Vec4;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The built-in themes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// The colors a canvas is painted with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Theme {
+    /// Canvas background, for the host to clear to.
+    pub background: Vec4,
+    /// Background grid lines.
+    pub grid: Vec4,
+    /// Fill of nodes.
+    pub node_fill: Vec4,
+    /// Border of nodes.
+    pub node_border: Vec4,
+    /// Border of selected nodes, selected wires and the selection box.
+    pub selected: Vec4,
+    /// Untyped ports.
+    pub port: Vec4,
+    /// Untyped wires.
+    pub wire: Vec4,
+    /// Node titles, port and wire labels, and annotation text.
+    pub text: Vec4,
+    /// Width of wires in screen pixels.
+    #[serde(default = "default_wire_width")]
+    pub wire_width: f32,
+    /// Node styles by `NodeData::node_type`.
+    #[serde(default)]
+    pub categories: HashMap<String, NodeStyle>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The theme for `preset`.
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self::dark(),
+            ThemePreset::Light => Self::light(),
+            ThemePreset::HighContrast => Self::high_contrast(),
+        }
+    }
+
+    /// Light lines on a near-black background. The default.
+    pub fn dark() -> Self {
+        Self {
+            background: Vec4::new(0.1, 0.1, 0.1, 1.0),
+            grid: Vec4::new(0.2, 0.2, 0.2, 1.0),
+            node_fill: Vec4::new(0.15, 0.15, 0.15, 1.0),
+            node_border: Vec4::new(0.5, 0.5, 0.5, 1.0),
+            selected: Vec4::new(1.0, 0.75, 0.3, 1.0),
+            port: Vec4::new(0.7, 0.7, 0.7, 1.0),
+            wire: Vec4::new(0.8, 0.8, 0.8, 1.0),
+            text: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            wire_width: default_wire_width(),
+            categories: HashMap::new(),
+        }
+    }
+
+    /// Dark lines on white nodes over a pale background.
+    pub fn light() -> Self {
+        Self {
+            background: Vec4::new(0.95, 0.95, 0.96, 1.0),
+            grid: Vec4::new(0.86, 0.86, 0.88, 1.0),
+            node_fill: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            node_border: Vec4::new(0.7, 0.7, 0.74, 1.0),
+            selected: Vec4::new(0.15, 0.45, 0.95, 1.0),
+            port: Vec4::new(0.45, 0.45, 0.5, 1.0),
+            wire: Vec4::new(0.4, 0.4, 0.45, 1.0),
+            text: Vec4::new(0.1, 0.1, 0.12, 1.0),
+            wire_width: default_wire_width(),
+            categories: HashMap::new(),
+        }
+    }
+
+    /// Pure black and white with a yellow selection and thicker wires.
+    pub fn high_contrast() -> Self {
+        Self {
+            background: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            grid: Vec4::new(0.3, 0.3, 0.3, 1.0),
+            node_fill: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            node_border: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            selected: Vec4::new(1.0, 1.0, 0.0, 1.0),
+            port: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            wire: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            text: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            wire_width: 3.0,
+            categories: HashMap::new(),
+        }
+    }
+
+    /// Colors nodes of `node_type` with `style`.
+    pub fn with_category(mut self, node_type: impl Into<String>, style: NodeStyle) -> Self {
+        self.categories.insert(node_type.into(), style);
+        self
+    }
+
+    /// The canvas style painting with this theme. Sizes, and the trail, activity and
+    /// annotation colors, keep their defaults.
+    pub fn style(&self) -> CanvasStyle {
+        CanvasStyle {
+            background_color: self.background,
+            grid_color: self.grid,
+            node_default: NodeStyle {
+                color: self.node_fill,
+                border_color: self.node_border,
+                text_color: self.text,
+            },
+            edge_default: EdgeStyle {
+                color: self.wire,
+                width: self.wire_width,
+            },
+            port_color: self.port,
+            port_outline_color: self.background,
+            port_type_colors: HashMap::new(),
+            port_label_size: config::default_port_label_size(),
+            edge_type_colors: HashMap::new(),
+            edge_label_size: config::default_port_label_size(),
+            selection_box_color: self.selected.with_w(0.15),
+            selection_box_border_color: self.selected.with_w(0.6),
+            selected_edge_color: self.selected,
+            selected_border_color: self.selected,
+            active_link_color: self.text,
+            category_styles: self.categories.clone(),
+            trail: TrailStyle::default(),
+            activity: ActivityStyle::default(),
+            annotation: AnnotationStyle {
+                text_color: self.text,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+fn default_wire_width() -> f32 {
+    2.0
+}
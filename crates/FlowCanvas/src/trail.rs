@@ -0,0 +1,41 @@
+//! # Execution Trails
+//!
+//! An `ExecutionTrail` records which nodes and connections a single run passed through.
+//! While one is set on the `GraphState`, the painter draws the traversed path normally
+//! (failed nodes outlined) and ghosts everything the run skipped, such as untaken branches.
+
+use crate::model::{ConnectionId, NodeId};
+use std::collections::HashSet;
+
+/// The path a run took through the graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutionTrail {
+    /// Identifier of the run, as reported by the engine.
+    pub trace_id: String,
+    /// Nodes that executed.
+    pub nodes: HashSet<NodeId>,
+    /// Executed nodes that reported a failure.
+    pub failed: HashSet<NodeId>,
+    /// Connections that carried data.
+    pub connections: HashSet<ConnectionId>,
+}
+
+impl ExecutionTrail {
+    /// Creates an empty trail for `trace_id`.
+    pub fn new(trace_id: impl Into<String>) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns true if the node executed during the run.
+    pub fn visited_node(&self, id: NodeId) -> bool {
+        self.nodes.contains(&id)
+    }
+
+    /// Returns true if the connection carried data during the run.
+    pub fn visited_connection(&self, id: ConnectionId) -> bool {
+        self.connections.contains(&id)
+    }
+}
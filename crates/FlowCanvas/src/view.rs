@@ -3,9 +3,16 @@
 //! This module handles the "infinite canvas" mathematics.
 //! It provides utilities to transform between World Space (the infinite grid) and Screen Space (the pixels on the monitor).
 
+use crate::math::Rect;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
+/// Smallest zoom factor the canvas allows.
+pub const MIN_ZOOM: f32 = 0.1;
+
+/// Largest zoom factor the canvas allows.
+pub const MAX_ZOOM: f32 = 10.0;
+
 /// Represents the current camera state: where we are looking (Pan) and how close (Zoom).
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct Transform {
@@ -28,6 +35,17 @@ impl Default for Transform {
     }
 }
 
+/// A transition from one camera transform to another, advanced by `View::advance_animation`.
+#[derive(Clone, Copy, Debug)]
+pub struct ViewAnimation {
+    pub from: Transform,
+    pub to: Transform,
+    /// Seconds since the transition started.
+    pub elapsed: f32,
+    /// Length of the transition in seconds.
+    pub duration: f32,
+}
+
 /// The View struct combines the Transform with the actual Viewport size (window size).
 /// It serves as the single source of truth for coordinate conversions.
 pub struct View {
@@ -35,6 +53,8 @@ pub struct View {
     pub transform: Transform,
     /// The size of the visible area in pixels.
     pub viewport_size: Vec2,
+    /// The transition in progress, if any.
+    pub animation: Option<ViewAnimation>,
 }
 
 impl View {
@@ -43,6 +63,7 @@ impl View {
         Self {
             transform,
             viewport_size,
+            animation: None,
         }
     }
 
@@ -59,4 +80,92 @@ impl View {
     pub fn screen_to_world(&self, screen_pos: Vec2) -> Vec2 {
         (screen_pos - self.transform.pan) / self.transform.zoom
     }
+
+    /// Sets the zoom (clamped to `MIN_ZOOM..=MAX_ZOOM`), keeping the world point under `anchor`
+    /// (Screen Space) in place. Returns whether the zoom changed.
+    pub fn zoom_at(&mut self, anchor: Vec2, zoom: f32) -> bool {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        if (zoom - self.transform.zoom).abs() <= f32::EPSILON {
+            return false;
+        }
+        // Screen = World * Zoom + Pan  =>  Pan = Screen - (World * Zoom)
+        let world_anchor = self.screen_to_world(anchor);
+        self.transform.zoom = zoom;
+        self.transform.pan = anchor - world_anchor * zoom;
+        true
+    }
+
+    /// Pans and zooms so `rect` (World Space) is centered in the viewport with at least
+    /// `padding` pixels around it. Zoom stays within `MIN_ZOOM..=MAX_ZOOM`.
+    pub fn fit_rect(&mut self, rect: Rect, padding: f32) {
+        self.animation = None;
+        self.transform = self.fitted_transform(rect, padding);
+    }
+
+    /// The transform `fit_rect` would move to.
+    pub fn fitted_transform(&self, rect: Rect, padding: f32) -> Transform {
+        let available = (self.viewport_size - Vec2::splat(padding * 2.0)).max(Vec2::ONE);
+        let size = (rect.max - rect.min).max(Vec2::splat(f32::EPSILON));
+        let zoom = (available / size).min_element();
+        self.centered_transform((rect.min + rect.max) * 0.5, zoom)
+    }
+
+    /// The transform that puts `center` (World Space) in the middle of the viewport at `zoom`
+    /// (clamped to `MIN_ZOOM..=MAX_ZOOM`).
+    pub fn centered_transform(&self, center: Vec2, zoom: f32) -> Transform {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        Transform {
+            pan: self.viewport_size * 0.5 - center * zoom,
+            zoom,
+        }
+    }
+
+    /// Moves to `target` over `duration` seconds, or at once if `duration` is not positive.
+    /// Replaces any transition already in progress.
+    pub fn animate_to(&mut self, target: Transform, duration: f32) {
+        if duration <= 0.0 {
+            self.transform = target;
+            self.animation = None;
+            return;
+        }
+        self.animation = Some(ViewAnimation {
+            from: self.transform,
+            to: target,
+            elapsed: 0.0,
+            duration,
+        });
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Steps the transition in progress by `dt` seconds. Returns whether the transform changed.
+    ///
+    /// The point in the middle of the viewport travels in a straight line while the zoom changes
+    /// geometrically, so zooming out and back in feels even. Both ease in and out.
+    pub fn advance_animation(&mut self, dt: f32) -> bool {
+        let Some(animation) = self.animation.as_mut() else {
+            return false;
+        };
+        animation.elapsed += dt;
+        let t = (animation.elapsed / animation.duration).min(1.0);
+        let animation = *animation;
+        if t >= 1.0 {
+            self.transform = animation.to;
+            self.animation = None;
+            return true;
+        }
+
+        let eased = t * t * (3.0 - 2.0 * t);
+        let middle = self.viewport_size * 0.5;
+        let from_center = (middle - animation.from.pan) / animation.from.zoom;
+        let to_center = (middle - animation.to.pan) / animation.to.zoom;
+        let zoom = animation.from.zoom * (animation.to.zoom / animation.from.zoom).powf(eased);
+        self.transform = Transform {
+            pan: middle - from_center.lerp(to_center, eased) * zoom,
+            zoom,
+        };
+        true
+    }
 }
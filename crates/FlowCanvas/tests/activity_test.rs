@@ -0,0 +1,93 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::config::CanvasConfig;
+use flow_canvas::interaction::InteractionMode;
+use flow_canvas::model::{GraphState, NodeFlags};
+use flow_canvas::painter::Painter;
+use flow_canvas::render::DrawCommand;
+use flow_canvas::view::{Transform, View};
+use glam::Vec2;
+
+fn draw(graph: &mut GraphState<String>, config: &CanvasConfig) -> Vec<DrawCommand> {
+    let view = View::new(Transform::default(), Vec2::new(800.0, 600.0));
+    Painter::draw_graph(
+        &view,
+        config,
+        graph,
+        &InteractionMode::Idle,
+        Vec2::new(800.0, 600.0),
+    )
+}
+
+/// Border color of the node rect drawn at `x`.
+fn border(commands: &[DrawCommand], x: f32) -> Option<glam::Vec4> {
+    commands.iter().find_map(|cmd| match cmd {
+        DrawCommand::Rect {
+            pos, stroke_color, ..
+        } if *pos == Vec2::new(x, 0.0) => *stroke_color,
+        _ => None,
+    })
+}
+
+fn pulses(commands: &[DrawCommand], config: &CanvasConfig) -> Vec<Vec2> {
+    commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Rect { pos, color, .. } if *color == config.style.activity.pulse_color => {
+                Some(*pos)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_flags_and_pulses_are_drawn_and_decay() {
+    let config = CanvasConfig::default();
+    let mut graph: GraphState<String> = GraphState::default();
+    let source = add_node(&mut graph, Vec2::new(0.0, 0.0));
+    let target = add_node(&mut graph, Vec2::new(300.0, 0.0));
+    let out = graph.add_port(source, false);
+    let input = graph.add_port(target, true);
+    let wire = graph.connect(out, input);
+
+    graph.mark_node_running(source);
+    graph.mark_node_failed(target);
+    graph.pulse_connection(wire);
+    assert!(graph.nodes[source].flags.contains(NodeFlags::RUNNING));
+
+    let commands = draw(&mut graph, &config);
+    let activity = &config.style.activity;
+    assert_eq!(border(&commands, 0.0), Some(activity.running_border_color));
+    assert_eq!(border(&commands, 300.0), Some(activity.error_border_color));
+    let start = pulses(&commands, &config);
+    assert_eq!(start.len(), 1);
+
+    // Half way through the decay the pulse has moved along the wire.
+    graph.advance_activity(0.5, config.activity_decay);
+    assert_eq!(graph.activity.pulse(wire), Some(0.5));
+    let halfway = pulses(&draw(&mut graph, &config), &config);
+    assert!(halfway[0].x > start[0].x);
+
+    graph.advance_activity(0.6, config.activity_decay);
+    assert!(graph.activity.is_empty());
+    assert!(!graph.nodes[target].flags.intersects(NodeFlags::LIVE));
+    let commands = draw(&mut graph, &config);
+    assert!(pulses(&commands, &config).is_empty());
+    assert_eq!(
+        border(&commands, 0.0),
+        Some(config.style.node_default.border_color)
+    );
+}
+
+#[test]
+fn test_live_flags_are_not_saved() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let node = add_node(&mut graph, Vec2::new(0.0, 0.0));
+    graph.nodes[node].flags.insert(NodeFlags::LOCKED);
+    graph.mark_node_failed(node);
+
+    let saved = graph.save();
+    assert_eq!(saved.nodes[0].flags, NodeFlags::LOCKED);
+}
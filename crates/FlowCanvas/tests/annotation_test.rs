@@ -0,0 +1,129 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::annotation::{AnnotationHit, AnnotationKind, GROUP_HEADER_HEIGHT};
+use flow_canvas::input::{InputState, MouseButtons};
+use flow_canvas::model::GraphState;
+use flow_canvas::persistence::GraphOp;
+use flow_canvas::render::DrawCommand;
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode};
+use glam::Vec2;
+
+fn press(pos: Vec2) -> InputState {
+    InputState {
+        mouse_pos: pos,
+        mouse_buttons: MouseButtons {
+            left: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_dragging_a_group_header_moves_its_nodes() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph: GraphState<String> = GraphState::default();
+    let inside = add_node(&mut graph, Vec2::new(100.0, 100.0));
+    let outside = add_node(&mut graph, Vec2::new(500.0, 100.0));
+    let group = graph.group_nodes(&[inside], "Ingest", 20.0).unwrap();
+    let note = graph.add_comment(Vec2::new(230.0, 100.0), Vec2::new(80.0, 60.0), "todo");
+    let header = graph.annotations[group].position + Vec2::new(10.0, 10.0);
+
+    // Only the header grabs the frame; its body is left for box selection.
+    assert_eq!(
+        graph.annotation_at(header),
+        Some((group, AnnotationHit::Body))
+    );
+    assert_eq!(
+        graph.annotation_at(header + Vec2::new(0.0, GROUP_HEADER_HEIGHT + 5.0)),
+        None
+    );
+
+    canvas.update(&press(header), 0.016, &mut graph);
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::DraggingAnnotation { .. }
+    ));
+    canvas.update(&press(header + Vec2::new(50.0, 30.0)), 0.016, &mut graph);
+    canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+
+    assert_eq!(graph.nodes[inside].position, Vec2::new(150.0, 130.0));
+    assert_eq!(graph.nodes[outside].position, Vec2::new(500.0, 100.0));
+    assert_eq!(graph.annotations[note].position, Vec2::new(230.0, 100.0));
+}
+
+#[test]
+fn test_resizing_a_comment_and_drawing_it() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph: GraphState<String> = GraphState::default();
+    let note = graph.add_comment(Vec2::ZERO, Vec2::new(200.0, 100.0), "Remember the API key");
+
+    let corner = Vec2::new(195.0, 95.0);
+    assert_eq!(
+        graph.annotation_at(corner),
+        Some((note, AnnotationHit::ResizeHandle))
+    );
+    canvas.update(&press(corner), 0.016, &mut graph);
+    canvas.update(&press(corner + Vec2::new(40.0, -500.0)), 0.016, &mut graph);
+    let (commands, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+
+    // Shrinking stops at the minimum size.
+    assert_eq!(graph.annotations[note].size.x, 240.0);
+    assert_eq!(
+        graph.annotations[note].size.y,
+        flow_canvas::annotation::MIN_ANNOTATION_SIZE.y
+    );
+    assert!(commands.iter().any(|cmd| matches!(
+        cmd,
+        DrawCommand::Text { text, .. } if text == "Remember the API key"
+    )));
+}
+
+#[test]
+fn test_annotations_round_trip_and_replay() {
+    let mut graph: GraphState<String> = GraphState::default();
+    add_node(&mut graph, Vec2::ZERO);
+    let group = graph.add_group(Vec2::new(-20.0, -40.0), Vec2::new(300.0, 200.0), "Core");
+    graph.annotations[group].color = Some(glam::Vec4::ONE);
+
+    let mut restored: GraphState<String> = GraphState::default();
+    restored.add_comment(Vec2::ZERO, Vec2::ONE, "stale");
+    restored.load(graph.save());
+    let (_, loaded) = restored.annotations.iter().next().unwrap();
+    assert_eq!(restored.annotations.len(), 1);
+    assert_eq!(loaded.uuid, graph.annotations[group].uuid);
+    assert_eq!(
+        loaded.kind,
+        AnnotationKind::Group {
+            title: "Core".into()
+        }
+    );
+    assert_eq!(loaded.color, Some(glam::Vec4::ONE));
+
+    // Pasting a snapshot places its annotations relative to the drop point.
+    let original = graph.annotations[group].uuid;
+    let pasted = restored.instantiate(graph.save(), Vec2::new(100.0, 100.0));
+    assert_eq!(pasted.len(), 1);
+    assert_eq!(restored.annotations.len(), 2);
+    assert!(
+        restored
+            .annotations
+            .values()
+            .any(|a| a.position == Vec2::new(80.0, 60.0) && a.uuid != original)
+    );
+
+    restored.load(graph.save_selection(&[]));
+    assert!(restored.annotations.is_empty());
+
+    let mut saved = graph.saved_annotation(group).unwrap();
+    assert!(restored.apply(GraphOp::SetAnnotation(saved.clone())));
+    saved.kind = AnnotationKind::Group {
+        title: "Renamed".into(),
+    };
+    assert!(restored.apply(GraphOp::SetAnnotation(saved.clone())));
+    assert_eq!(restored.annotations.len(), 1);
+    assert!(restored.apply(GraphOp::RemoveAnnotation { uuid: saved.uuid }));
+    assert!(!restored.apply(GraphOp::RemoveAnnotation { uuid: saved.uuid }));
+}
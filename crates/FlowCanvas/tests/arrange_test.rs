@@ -0,0 +1,105 @@
+mod common;
+
+use common::add_node_with;
+use flow_canvas::arrange::{Alignment, Distribution};
+use flow_canvas::input::{InputState, ModifiersState, MouseButtons};
+use flow_canvas::model::{GraphState, NodeFlags, NodeId};
+use flow_canvas::{Canvas, CanvasConfig, LogicEvent};
+use glam::Vec2;
+
+/// A selected node, as the arrange commands act on the selection.
+fn add_selected(graph: &mut GraphState<String>, pos: Vec2, size: Vec2) -> NodeId {
+    let id = add_node_with(graph, pos, size, "Node".to_string());
+    graph.nodes[id].flags = NodeFlags::SELECTED;
+    id
+}
+
+#[test]
+fn test_drag_snaps_selection_corner_to_grid() {
+    let mut canvas = Canvas::new(CanvasConfig {
+        snap_to_grid: true,
+        grid_size: 20.0,
+        ..Default::default()
+    });
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_selected(&mut graph, Vec2::new(3.0, 7.0), Vec2::new(100.0, 50.0));
+    let b = add_selected(&mut graph, Vec2::new(203.0, 7.0), Vec2::new(100.0, 50.0));
+
+    let mut input = InputState {
+        mouse_pos: Vec2::new(50.0, 30.0),
+        mouse_buttons: MouseButtons {
+            left: true,
+            ..Default::default()
+        },
+        // Keep both nodes selected when grabbing one
+        modifiers: ModifiersState {
+            shift: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    canvas.update(&input, 0.016, &mut graph);
+    input.mouse_pos += Vec2::new(44.0, 12.0);
+    canvas.update(&input, 0.016, &mut graph);
+
+    // The corner at (47, 19) snaps to (40, 20); both nodes keep their spacing.
+    assert_eq!(graph.nodes[a].position, Vec2::new(40.0, 20.0));
+    assert_eq!(graph.nodes[b].position, Vec2::new(240.0, 20.0));
+}
+
+#[test]
+fn test_align_moves_unlocked_selection_and_reports_moves() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_selected(&mut graph, Vec2::new(10.0, 0.0), Vec2::new(100.0, 50.0));
+    let b = add_selected(&mut graph, Vec2::new(40.0, 100.0), Vec2::new(60.0, 50.0));
+    let c = add_selected(&mut graph, Vec2::new(70.0, 200.0), Vec2::new(60.0, 50.0));
+    let locked = add_selected(&mut graph, Vec2::new(500.0, 0.0), Vec2::new(60.0, 50.0));
+    graph.nodes[locked].flags.insert(NodeFlags::LOCKED);
+
+    let events = graph.align_selected(Alignment::Left);
+    assert_eq!(graph.nodes[b].position.x, 10.0);
+    assert_eq!(graph.nodes[c].position.x, 10.0);
+    assert_eq!(graph.nodes[locked].position.x, 500.0);
+    assert_eq!(events.len(), 2);
+    assert!(events.contains(&LogicEvent::NodesMoved {
+        ids: vec![b],
+        delta: Vec2::new(-30.0, 0.0),
+    }));
+
+    graph.align_selected(Alignment::Right);
+    assert_eq!(graph.nodes[a].position.x, 10.0);
+    assert_eq!(graph.nodes[b].position.x, 50.0);
+
+    graph.align_selected(Alignment::CenterX);
+    assert_eq!(
+        graph.nodes[b].position.x + 30.0,
+        graph.nodes[a].position.x + 50.0
+    );
+
+    // Already aligned: nothing moves, nothing is reported.
+    assert!(graph.align_selected(Alignment::CenterX).is_empty());
+}
+
+#[test]
+fn test_distribute_evens_out_gaps() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_selected(&mut graph, Vec2::new(0.0, 0.0), Vec2::new(100.0, 50.0));
+    let b = add_selected(&mut graph, Vec2::new(110.0, 30.0), Vec2::new(50.0, 50.0));
+    let c = add_selected(&mut graph, Vec2::new(400.0, 60.0), Vec2::new(100.0, 50.0));
+
+    let events = graph.distribute_selected(Distribution::Horizontal);
+    // Span 500, widths 250: two gaps of 125.
+    assert_eq!(graph.nodes[a].position, Vec2::new(0.0, 0.0));
+    assert_eq!(graph.nodes[b].position, Vec2::new(225.0, 30.0));
+    assert_eq!(graph.nodes[c].position, Vec2::new(400.0, 60.0));
+    assert_eq!(
+        events,
+        vec![LogicEvent::NodesMoved {
+            ids: vec![b],
+            delta: Vec2::new(115.0, 0.0),
+        }]
+    );
+
+    graph.nodes[c].flags.remove(NodeFlags::SELECTED);
+    assert!(graph.distribute_selected(Distribution::Vertical).is_empty());
+}
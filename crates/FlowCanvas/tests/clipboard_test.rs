@@ -0,0 +1,123 @@
+mod common;
+
+use common::{NODE_SIZE, add_node_with};
+use flow_canvas::input::{InputState, Key, ModifiersState};
+use flow_canvas::model::{GraphState, NodeFlags, NodeId};
+use flow_canvas::{Canvas, CanvasConfig, LogicEvent};
+use glam::Vec2;
+
+/// Three nodes in a chain `a -> b -> c`, with `a` and `b` selected.
+fn chain() -> (GraphState<String>, [NodeId; 3]) {
+    let mut graph = GraphState::default();
+    let a = add_node_with(&mut graph, Vec2::new(0.0, 0.0), NODE_SIZE, "A".to_string());
+    let b = add_node_with(
+        &mut graph,
+        Vec2::new(200.0, 50.0),
+        NODE_SIZE,
+        "B".to_string(),
+    );
+    let c = add_node_with(
+        &mut graph,
+        Vec2::new(400.0, 0.0),
+        NODE_SIZE,
+        "C".to_string(),
+    );
+    let a_out = graph.add_port(a, false);
+    let b_in = graph.add_port(b, true);
+    let b_out = graph.add_port(b, false);
+    let c_in = graph.add_port(c, true);
+    graph.connect(a_out, b_in);
+    graph.connect(b_out, c_in);
+    graph.nodes[a].flags.insert(NodeFlags::SELECTED);
+    graph.nodes[b].flags.insert(NodeFlags::SELECTED);
+    (graph, [a, b, c])
+}
+
+#[test]
+fn test_paste_duplicates_selection_with_internal_connections() {
+    let (mut graph, [a, b, _]) = chain();
+    let clipboard = graph.copy_selection();
+    assert_eq!(clipboard.snippet.nodes.len(), 2);
+    assert_eq!(clipboard.snippet.connections.len(), 1);
+
+    let LogicEvent::Pasted { ids } = graph.paste(&clipboard, Vec2::new(20.0, 20.0)) else {
+        panic!("paste should report the new nodes");
+    };
+    assert_eq!(ids.len(), 2);
+    assert_eq!(graph.nodes.len(), 5);
+    assert_eq!(graph.connections.len(), 3);
+
+    // The copies are new nodes with new ports, offset from the originals and selected instead
+    // of them.
+    assert_eq!(graph.selected_nodes().len(), 2);
+    for id in &ids {
+        let copy = &graph.nodes[*id];
+        let original = [a, b]
+            .into_iter()
+            .find(|o| graph.nodes[*o].data == copy.data)
+            .unwrap();
+        assert_ne!(copy.uuid, graph.nodes[original].uuid);
+        assert_eq!(
+            copy.position,
+            graph.nodes[original].position + Vec2::new(20.0, 20.0)
+        );
+        assert!(copy.flags.contains(NodeFlags::SELECTED));
+        assert!(!graph.nodes[original].flags.contains(NodeFlags::SELECTED));
+    }
+    let new_ports: Vec<_> = ids
+        .iter()
+        .flat_map(|id| graph.nodes[*id].outputs.clone())
+        .collect();
+    assert!(
+        graph
+            .connections
+            .values()
+            .any(|conn| new_ports.contains(&conn.from))
+    );
+
+    // The same clipboard can be pasted again.
+    graph.paste(&clipboard, Vec2::new(40.0, 40.0));
+    assert_eq!(graph.nodes.len(), 7);
+}
+
+#[test]
+fn test_cut_removes_selection_but_keeps_locked_nodes() {
+    let (mut graph, [a, b, c]) = chain();
+    graph.nodes[a].flags.insert(NodeFlags::LOCKED);
+
+    let clipboard = graph.cut_selection();
+    assert_eq!(clipboard.snippet.nodes.len(), 2);
+    assert!(graph.nodes.contains_key(a));
+    assert!(!graph.nodes.contains_key(b));
+    assert!(graph.nodes.contains_key(c));
+    assert!(graph.connections.is_empty());
+    assert_eq!(graph.ports.len(), 2);
+    assert!(!graph.draw_order.contains(&b));
+}
+
+#[test]
+fn test_shortcuts_request_clipboard_actions() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph: GraphState<String> = GraphState::default();
+    let mut input = InputState {
+        pressed_keys: vec![Key::C, Key::X, Key::V],
+        ..Default::default()
+    };
+
+    let (_, events) = canvas.update(&input, 0.016, &mut graph);
+    assert!(events.is_empty());
+
+    input.modifiers = ModifiersState {
+        ctrl: true,
+        ..Default::default()
+    };
+    let (_, events) = canvas.update(&input, 0.016, &mut graph);
+    assert_eq!(
+        events,
+        vec![
+            LogicEvent::CopySelection,
+            LogicEvent::CutSelection,
+            LogicEvent::Paste
+        ]
+    );
+}
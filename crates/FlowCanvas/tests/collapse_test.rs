@@ -0,0 +1,198 @@
+mod common;
+
+use common::{add_node, mouse};
+use flow_canvas::input::InputState;
+use flow_canvas::layout::{NODE_HEADER_HEIGHT, PORT_ROW_HEIGHT};
+use flow_canvas::model::GraphState;
+use flow_canvas::render::DrawCommand;
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode};
+use glam::{Vec2, Vec4};
+
+#[test]
+fn test_collapsed_ports_share_a_summary_position() {
+    let mut graph = GraphState::default();
+    let node = add_node(&mut graph, Vec2::ZERO);
+    let inputs = [graph.add_port(node, true), graph.add_port(node, true)];
+    let output = graph.add_port(node, false);
+
+    // Expanded: ports are evenly spaced down each side.
+    assert_eq!(
+        graph.find_port_position(inputs[0]),
+        Some(Vec2::new(0.0, 100.0 / 3.0))
+    );
+    assert_eq!(
+        graph.find_port_position(inputs[1]),
+        Some(Vec2::new(0.0, 200.0 / 3.0))
+    );
+    assert_eq!(
+        graph.find_port_position(output),
+        Some(Vec2::new(100.0, 50.0))
+    );
+
+    assert!(graph.set_collapsed(node, true));
+    assert!(!graph.set_collapsed(node, true));
+    let header_mid = NODE_HEADER_HEIGHT * 0.5;
+    for input in inputs {
+        assert_eq!(
+            graph.find_port_position(input),
+            Some(Vec2::new(0.0, header_mid))
+        );
+    }
+    assert_eq!(
+        graph.find_port_position(output),
+        Some(Vec2::new(100.0, header_mid))
+    );
+    assert_eq!(graph.nodes[node].size, Vec2::new(100.0, 100.0));
+
+    assert_eq!(graph.toggle_collapsed(node), Some(false));
+    assert_eq!(
+        graph.find_port_position(output),
+        Some(Vec2::new(100.0, 50.0))
+    );
+}
+
+#[test]
+fn test_collapsed_node_only_occupies_its_header() {
+    let mut graph = GraphState::default();
+    let node = add_node(&mut graph, Vec2::ZERO);
+    graph.set_collapsed(node, true);
+    graph.sync_spatial_index();
+
+    assert_eq!(graph.node_at(Vec2::new(50.0, 10.0)), Some(node));
+    assert_eq!(graph.node_at(Vec2::new(50.0, 80.0)), None);
+
+    graph.set_collapsed(node, false);
+    graph.sync_spatial_index();
+    assert_eq!(graph.node_at(Vec2::new(50.0, 80.0)), Some(node));
+}
+
+#[test]
+fn test_labeled_ports_grow_the_node() {
+    let mut graph = GraphState::default();
+    let node = add_node(&mut graph, Vec2::ZERO);
+    for i in 0..7 {
+        graph.add_labeled_port(node, true, format!("in{i}"), Some("Number".to_string()));
+    }
+
+    assert_eq!(graph.nodes[node].size.y, 8.0 * PORT_ROW_HEIGHT);
+    let first = graph
+        .find_port_position(graph.nodes[node].inputs[0])
+        .unwrap();
+    let second = graph
+        .find_port_position(graph.nodes[node].inputs[1])
+        .unwrap();
+    assert_eq!(second.y - first.y, PORT_ROW_HEIGHT);
+
+    // A node already tall enough keeps its size.
+    let roomy = add_node(&mut graph, Vec2::new(300.0, 0.0));
+    graph.add_labeled_port(roomy, false, "out", None);
+    assert_eq!(graph.nodes[roomy].size.y, 100.0);
+}
+
+#[test]
+fn test_painter_draws_labels_type_colors_and_summary_ports() {
+    let mut config = CanvasConfig::default();
+    let number_color = Vec4::new(0.2, 0.6, 1.0, 1.0);
+    config
+        .style
+        .port_type_colors
+        .insert("Number".to_string(), number_color);
+    let mut canvas = Canvas::new(config);
+
+    let mut graph = GraphState::default();
+    let node = add_node(&mut graph, Vec2::new(100.0, 100.0));
+    graph.add_labeled_port(node, true, "value", Some("Number".to_string()));
+    graph.add_labeled_port(node, true, "flag", Some("Bool".to_string()));
+    graph.add_port(node, false);
+
+    let (draw_list, _) = canvas.update(&InputState::default(), 0.0, &mut graph);
+    let labels: Vec<&str> = draw_list
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(labels, ["value", "flag"]);
+    let port_colors: Vec<Vec4> = draw_list
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Rect { size, color, .. } if *size == Vec2::splat(10.0) => Some(*color),
+            _ => None,
+        })
+        .collect();
+    let default_color = canvas.config.style.port_color;
+    assert_eq!(port_colors, [number_color, default_color, default_color]);
+
+    // Collapsed: a header-sized body, one port per side and no labels.
+    graph.set_collapsed(node, true);
+    let (draw_list, _) = canvas.update(&InputState::default(), 0.0, &mut graph);
+    assert!(draw_list.iter().any(|cmd| matches!(
+        cmd,
+        DrawCommand::Rect { size, .. } if *size == Vec2::new(100.0, NODE_HEADER_HEIGHT)
+    )));
+    let ports = draw_list
+        .iter()
+        .filter(|cmd| matches!(cmd, DrawCommand::Rect { size, .. } if *size == Vec2::splat(10.0)))
+        .count();
+    assert_eq!(ports, 2);
+    assert!(
+        !draw_list
+            .iter()
+            .any(|cmd| matches!(cmd, DrawCommand::Text { .. }))
+    );
+}
+
+#[test]
+fn test_collapsed_ports_cannot_be_grabbed() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let node = add_node(&mut graph, Vec2::ZERO);
+    graph.add_port(node, false);
+    graph.set_collapsed(node, true);
+
+    // Pressing on the summary port picks the node up instead of starting a wire.
+    canvas.update(
+        &mouse(Vec2::new(100.0, NODE_HEADER_HEIGHT * 0.5), true),
+        0.0,
+        &mut graph,
+    );
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::DraggingNodes { .. }
+    ));
+}
+
+#[test]
+fn test_port_labels_and_collapse_survive_save_and_load() {
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(300.0, 0.0));
+    let out = graph.add_labeled_port(a, false, "result", Some("Text".to_string()));
+    let input = graph.add_port(b, true);
+    graph.connect(out, input);
+    graph.set_collapsed(a, true);
+
+    let saved = graph.save();
+    let saved_b = saved.nodes.iter().find(|n| n.position.x == 300.0).unwrap();
+    assert!(saved_b.inputs.is_empty(), "anonymous ports are not written");
+
+    let mut restored: GraphState<String> = GraphState::default();
+    restored.load(saved);
+
+    let a = restored
+        .nodes
+        .values()
+        .find(|n| n.position == Vec2::ZERO)
+        .unwrap();
+    assert!(a.is_collapsed());
+    let port = &restored.ports[a.outputs[0]];
+    assert_eq!(port.label.as_deref(), Some("result"));
+    assert_eq!(port.data_type.as_deref(), Some("Text"));
+    let b = restored
+        .nodes
+        .values()
+        .find(|n| n.position.x == 300.0)
+        .unwrap();
+    assert_eq!(restored.ports[b.inputs[0]].label, None);
+}
@@ -0,0 +1,56 @@
+//! Graph fixtures and input builders shared by the integration tests.
+
+// Each test crate uses only some of these.
+#![allow(dead_code)]
+
+use flow_canvas::input::{InputState, ModifiersState, MouseButtons};
+use flow_canvas::model::{GraphState, Node, NodeData, NodeFlags, NodeId, Uuid};
+use glam::Vec2;
+
+/// Size of the nodes [`add_node`] inserts.
+pub const NODE_SIZE: Vec2 = Vec2::new(100.0, 100.0);
+
+/// Inserts a port-less, unselected node and draws it on top.
+pub fn add_node_with<T: NodeData>(
+    graph: &mut GraphState<T>,
+    pos: Vec2,
+    size: Vec2,
+    data: T,
+) -> NodeId {
+    let id = graph.insert_node(Node {
+        id: NodeId::default(),
+        uuid: Uuid::new_v4(),
+        position: pos,
+        size,
+        inputs: vec![],
+        outputs: vec![],
+        data,
+        flags: NodeFlags::default(),
+        style: None,
+    });
+    graph.draw_order.push(id);
+    id
+}
+
+/// Inserts a `"Node"` of [`NODE_SIZE`] at `pos`.
+pub fn add_node(graph: &mut GraphState<String>, pos: Vec2) -> NodeId {
+    add_node_with(graph, pos, NODE_SIZE, "Node".to_string())
+}
+
+/// The mouse at `pos`, with the left button held down or released.
+pub fn mouse(pos: Vec2, left: bool) -> InputState {
+    mouse_with(pos, left, ModifiersState::default())
+}
+
+/// [`mouse`] while `modifiers` are held.
+pub fn mouse_with(pos: Vec2, left: bool, modifiers: ModifiersState) -> InputState {
+    InputState {
+        mouse_pos: pos,
+        mouse_buttons: MouseButtons {
+            left,
+            ..Default::default()
+        },
+        modifiers,
+        ..Default::default()
+    }
+}
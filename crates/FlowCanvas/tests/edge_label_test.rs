@@ -0,0 +1,123 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::config::{CanvasStyle, EdgeStyle};
+use flow_canvas::input::InputState;
+use flow_canvas::model::{ConnectionId, GraphState};
+use flow_canvas::painter::Painter;
+use flow_canvas::render::{DrawCommand, RenderList};
+use flow_canvas::view::{Transform, View};
+use flow_canvas::{CanvasConfig, InteractionMode};
+use glam::{Vec2, Vec4};
+
+/// Two nodes wired from (100, 50) to (300, 50) through ports of `data_type`.
+fn pair(data_type: Option<&str>) -> (GraphState<String>, ConnectionId) {
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(300.0, 0.0));
+    let output = graph.add_labeled_port(a, false, "", data_type.map(str::to_string));
+    let input = graph.add_labeled_port(b, true, "", data_type.map(str::to_string));
+    let conn = graph.connect(output, input);
+    (graph, conn)
+}
+
+fn paint(graph: &mut GraphState<String>, config: &CanvasConfig) -> RenderList {
+    let screen_size = InputState::default().screen_size;
+    let view = View::new(
+        Transform {
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+        },
+        screen_size,
+    );
+    Painter::draw_graph(&view, config, graph, &InteractionMode::Idle, screen_size)
+}
+
+fn wire_colors(list: &RenderList) -> Vec<Vec4> {
+    list.iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Bezier { color, .. } => Some(*color),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Text commands other than node titles and (empty) port labels.
+fn labels(list: &RenderList) -> Vec<(Vec2, String)> {
+    list.iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Text { pos, text, .. } if !text.is_empty() && text != "Node" => {
+                Some((*pos, text.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_wires_are_colored_by_data_type() {
+    let number = Vec4::new(0.2, 0.6, 1.0, 1.0);
+    let flow = Vec4::new(1.0, 1.0, 1.0, 1.0);
+    let mut style = CanvasStyle::default();
+    style.port_type_colors.insert("number".to_string(), number);
+    style.port_type_colors.insert("flow".to_string(), number);
+    style.edge_type_colors.insert("flow".to_string(), flow);
+    let config = CanvasConfig {
+        style,
+        ..Default::default()
+    };
+
+    // Port colors carry over to wires unless the wire palette has its own entry.
+    let (mut graph, _) = pair(Some("number"));
+    assert_eq!(wire_colors(&paint(&mut graph, &config)), [number]);
+    let (mut graph, _) = pair(Some("flow"));
+    assert_eq!(wire_colors(&paint(&mut graph, &config)), [flow]);
+    let (mut graph, _) = pair(None);
+    assert_eq!(
+        wire_colors(&paint(&mut graph, &config)),
+        [config.style.edge_default.color]
+    );
+
+    // An explicit override wins.
+    let (mut graph, conn) = pair(Some("number"));
+    let red = Vec4::new(1.0, 0.0, 0.0, 1.0);
+    graph.connections[conn].visual_style = Some(EdgeStyle {
+        color: red,
+        width: 2.0,
+    });
+    assert_eq!(wire_colors(&paint(&mut graph, &config)), [red]);
+}
+
+#[test]
+fn test_label_is_drawn_above_the_wire_midpoint() {
+    let config = CanvasConfig::default();
+    let (mut graph, conn) = pair(None);
+    assert!(labels(&paint(&mut graph, &config)).is_empty());
+
+    let generation = graph.generation();
+    graph.set_connection_label(conn, Some("rows".to_string()));
+    assert_ne!(graph.generation(), generation);
+
+    let drawn = labels(&paint(&mut graph, &config));
+    assert_eq!(drawn.len(), 1);
+    let (pos, text) = &drawn[0];
+    assert_eq!(text, "rows");
+    // Centered on the midpoint at (200, 50), sitting on top of the wire.
+    let size = config.style.edge_label_size;
+    let width = 4.0 * size * 0.5;
+    assert!((pos.x + width * 0.5 - 200.0).abs() < 1e-3);
+    assert!(pos.y + size < 50.0);
+}
+
+#[test]
+fn test_labels_survive_save_and_load() {
+    let (mut graph, conn) = pair(Some("flow"));
+    graph.set_connection_label(conn, Some("on success".to_string()));
+
+    let mut restored: GraphState<String> = GraphState::default();
+    restored.load(graph.save());
+    let conn = restored.connections.values().next().unwrap();
+    assert_eq!(conn.label.as_deref(), Some("on success"));
+    let id = restored.connections.keys().next().unwrap();
+    assert_eq!(restored.connection_data_type(id), Some("flow"));
+}
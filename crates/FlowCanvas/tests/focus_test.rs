@@ -0,0 +1,95 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::input::InputState;
+use flow_canvas::model::{GraphState, NodeFlags};
+use flow_canvas::view::Transform;
+use flow_canvas::{Canvas, CanvasConfig, LogicEvent};
+use glam::Vec2;
+
+#[test]
+fn test_focus_node_glides_there_and_repaints_each_frame() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node(&mut graph, Vec2::ZERO);
+    let far = add_node(&mut graph, Vec2::new(3000.0, 2000.0));
+
+    assert!(canvas.focus_node(&graph, far));
+    let (_, events) = canvas.update(&InputState::default(), 0.1, &mut graph);
+    assert!(events.contains(&LogicEvent::RepaintNeeded));
+    // Partway there: the node is not centered yet.
+    let center = canvas.view.world_to_screen(Vec2::new(3050.0, 2050.0));
+    assert!(center.distance(Vec2::new(400.0, 300.0)) > 1.0);
+
+    canvas.update(&InputState::default(), 0.2, &mut graph);
+    assert!(!canvas.view.is_animating());
+    let center = canvas.view.world_to_screen(Vec2::new(3050.0, 2050.0));
+    assert!(center.distance(Vec2::new(400.0, 300.0)) < 1e-3);
+    // A small target is not zoomed in past 100%.
+    assert_eq!(canvas.view.transform.zoom, 1.0);
+
+    // Idle frames no longer repaint.
+    let (_, events) = canvas.update(&InputState::default(), 0.1, &mut graph);
+    assert!(!events.contains(&LogicEvent::RepaintNeeded));
+}
+
+#[test]
+fn test_focus_selection_frames_the_selected_nodes() {
+    let mut canvas = Canvas::new(CanvasConfig {
+        focus_duration: 0.0,
+        ..Default::default()
+    });
+    let mut graph = GraphState::default();
+    assert!(!canvas.focus_selection(&graph));
+
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(1900.0, 0.0));
+    add_node(&mut graph, Vec2::new(-5000.0, 0.0));
+    for id in [a, b] {
+        graph.nodes[id].flags.insert(NodeFlags::SELECTED);
+    }
+
+    // With no duration the view jumps straight there.
+    assert!(canvas.focus_selection(&graph));
+    assert!(!canvas.view.is_animating());
+    let view = &canvas.view;
+    assert!((view.transform.zoom - 0.36).abs() < 1e-4);
+    assert!((view.world_to_screen(Vec2::ZERO).x - 40.0).abs() < 1e-3);
+    assert!((view.world_to_screen(Vec2::new(2000.0, 0.0)).x - 760.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_manual_zoom_interrupts_a_transition() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node(&mut graph, Vec2::new(5000.0, 5000.0));
+
+    assert!(canvas.zoom_to_fit(&graph));
+    canvas.update(&InputState::default(), 0.05, &mut graph);
+    assert!(canvas.view.is_animating());
+
+    let scroll = InputState {
+        scroll_delta: 1.0,
+        ..Default::default()
+    };
+    canvas.update(&scroll, 0.05, &mut graph);
+    assert!(!canvas.view.is_animating());
+}
+
+#[test]
+fn test_transition_eases_zoom_geometrically() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    canvas.view.animate_to(
+        Transform {
+            pan: Vec2::ZERO,
+            zoom: 4.0,
+        },
+        1.0,
+    );
+    canvas.view.advance_animation(0.5);
+    // Halfway in time is halfway on a log scale: sqrt(1 * 4).
+    assert!((canvas.view.transform.zoom - 2.0).abs() < 1e-5);
+    canvas.view.advance_animation(0.5);
+    assert_eq!(canvas.view.transform.zoom, 4.0);
+    assert!(!canvas.view.is_animating());
+}
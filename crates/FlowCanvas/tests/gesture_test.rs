@@ -0,0 +1,171 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::input::{InputState, MouseButtons, TouchPoint};
+use flow_canvas::model::{GraphState, NodeFlags, NodeId};
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode, LogicEvent};
+use glam::Vec2;
+
+fn touch(points: &[(u64, Vec2)]) -> InputState {
+    InputState {
+        touches: points
+            .iter()
+            .map(|&(id, pos)| TouchPoint { id, pos })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn context_menus(events: &[LogicEvent]) -> Vec<(Vec2, Option<NodeId>)> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            LogicEvent::ContextMenu { position, node } => Some((*position, *node)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_single_touch_drags_like_the_mouse() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+
+    canvas.update(&touch(&[(1, Vec2::new(50.0, 50.0))]), 0.016, &mut graph);
+    canvas.update(&touch(&[(1, Vec2::new(80.0, 70.0))]), 0.016, &mut graph);
+    assert_eq!(graph.nodes[a].position, Vec2::new(30.0, 20.0));
+
+    // Lifting the finger releases where it was last seen.
+    canvas.update(&touch(&[]), 0.016, &mut graph);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+    assert_eq!(graph.nodes[a].position, Vec2::new(30.0, 20.0));
+    assert!(graph.nodes[a].flags.contains(NodeFlags::SELECTED));
+}
+
+#[test]
+fn test_two_fingers_pinch_zoom_and_pan() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+
+    // The first finger lands on the node and starts dragging it.
+    canvas.update(&touch(&[(1, Vec2::new(50.0, 50.0))]), 0.016, &mut graph);
+    canvas.update(&touch(&[(1, Vec2::new(60.0, 50.0))]), 0.016, &mut graph);
+    assert_eq!(graph.nodes[a].position, Vec2::new(10.0, 0.0));
+
+    // A second finger turns it into a pinch and puts the node back.
+    canvas.update(
+        &touch(&[(1, Vec2::new(300.0, 300.0)), (2, Vec2::new(500.0, 300.0))]),
+        0.016,
+        &mut graph,
+    );
+    assert_eq!(graph.nodes[a].position, Vec2::ZERO);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+
+    // Spreading the fingers to twice the distance doubles the zoom around their midpoint.
+    let anchor = canvas.view.screen_to_world(Vec2::new(400.0, 300.0));
+    canvas.update(
+        &touch(&[(1, Vec2::new(200.0, 300.0)), (2, Vec2::new(600.0, 300.0))]),
+        0.016,
+        &mut graph,
+    );
+    assert!((canvas.view.transform.zoom - 2.0).abs() < 1e-5);
+    assert!(
+        canvas
+            .view
+            .world_to_screen(anchor)
+            .distance(Vec2::new(400.0, 300.0))
+            < 1e-3
+    );
+
+    // Moving both fingers together pans.
+    let pan = canvas.view.transform.pan;
+    canvas.update(
+        &touch(&[(1, Vec2::new(220.0, 330.0)), (2, Vec2::new(620.0, 330.0))]),
+        0.016,
+        &mut graph,
+    );
+    assert_eq!(canvas.view.transform.pan - pan, Vec2::new(20.0, 30.0));
+
+    // The finger left behind does nothing until every finger has lifted.
+    canvas.update(&touch(&[(2, Vec2::new(50.0, 50.0))]), 0.016, &mut graph);
+    canvas.update(&touch(&[(2, Vec2::new(90.0, 90.0))]), 0.016, &mut graph);
+    assert_eq!(graph.nodes[a].position, Vec2::ZERO);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+}
+
+#[test]
+fn test_long_press_opens_a_context_menu() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let on_node = Vec2::new(50.0, 50.0);
+
+    let (_, events) = canvas.update(&touch(&[(1, on_node)]), 0.016, &mut graph);
+    assert!(context_menus(&events).is_empty());
+    let (_, events) = canvas.update(&touch(&[(1, on_node)]), 0.3, &mut graph);
+    assert!(context_menus(&events).is_empty());
+    let (_, events) = canvas.update(&touch(&[(1, on_node)]), 0.3, &mut graph);
+    assert_eq!(context_menus(&events), [(on_node, Some(a))]);
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::WaitingForRelease
+    ));
+
+    // Only once per press, and moving the finger afterwards does not drag.
+    let (_, events) = canvas.update(&touch(&[(1, Vec2::new(90.0, 90.0))]), 0.3, &mut graph);
+    assert!(context_menus(&events).is_empty());
+    assert_eq!(graph.nodes[a].position, Vec2::ZERO);
+    canvas.update(&touch(&[]), 0.016, &mut graph);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+
+    // A finger that wanders off is a drag, not a long press.
+    canvas.update(&touch(&[(2, Vec2::new(300.0, 300.0))]), 0.016, &mut graph);
+    let (_, events) = canvas.update(&touch(&[(2, Vec2::new(340.0, 300.0))]), 1.0, &mut graph);
+    assert!(context_menus(&events).is_empty());
+}
+
+#[test]
+fn test_right_click_opens_a_context_menu_once() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node(&mut graph, Vec2::ZERO);
+
+    let right = InputState {
+        mouse_pos: Vec2::new(300.0, 300.0),
+        mouse_buttons: MouseButtons {
+            right: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (_, events) = canvas.update(&right, 0.016, &mut graph);
+    assert_eq!(context_menus(&events), [(Vec2::new(300.0, 300.0), None)]);
+    let (_, events) = canvas.update(&right, 0.016, &mut graph);
+    assert!(context_menus(&events).is_empty());
+}
+
+#[test]
+fn test_trackpad_scroll_pans_and_pinch_zooms_around_the_cursor() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph: GraphState<String> = GraphState::default();
+
+    let input = InputState {
+        pan_delta: Vec2::new(-15.0, 40.0),
+        ..Default::default()
+    };
+    canvas.update(&input, 0.016, &mut graph);
+    assert_eq!(canvas.view.transform.pan, Vec2::new(-15.0, 40.0));
+
+    let cursor = Vec2::new(200.0, 100.0);
+    let anchor = canvas.view.screen_to_world(cursor);
+    let input = InputState {
+        mouse_pos: cursor,
+        pinch_delta: 0.5,
+        ..Default::default()
+    };
+    canvas.update(&input, 0.016, &mut graph);
+    assert!((canvas.view.transform.zoom - 1.5).abs() < 1e-5);
+    assert!(canvas.view.world_to_screen(anchor).distance(cursor) < 1e-3);
+}
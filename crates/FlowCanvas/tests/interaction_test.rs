@@ -173,6 +173,8 @@ fn test_linking() {
     let port_out = graph.ports.insert(flow_canvas::model::Port {
         id: flow_canvas::model::PortId::default(),
         node: node_a,
+        label: None,
+        data_type: None,
     });
     graph.ports[port_out].id = port_out;
     graph.nodes[node_a].outputs.push(port_out);
@@ -194,6 +196,8 @@ fn test_linking() {
     let port_in = graph.ports.insert(flow_canvas::model::Port {
         id: flow_canvas::model::PortId::default(),
         node: node_b,
+        label: None,
+        data_type: None,
     });
     graph.ports[port_in].id = port_in;
     graph.nodes[node_b].inputs.push(port_in);
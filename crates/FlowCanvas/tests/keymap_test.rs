@@ -0,0 +1,205 @@
+mod common;
+
+use common::{add_node, mouse};
+use flow_canvas::input::{InputState, Key, ModifiersState};
+use flow_canvas::keymap::{CanvasCommand, Direction, KeyChord, Keymap};
+use flow_canvas::model::{GraphState, NodeFlags};
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode, LogicEvent};
+use glam::Vec2;
+
+fn press(key: Key, modifiers: ModifiersState) -> InputState {
+    InputState {
+        pressed_keys: vec![key],
+        modifiers,
+        ..Default::default()
+    }
+}
+
+fn ctrl() -> ModifiersState {
+    ModifiersState {
+        ctrl: true,
+        ..Default::default()
+    }
+}
+
+fn shift() -> ModifiersState {
+    ModifiersState {
+        shift: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_keymap_resolves_and_rebinds_chords() {
+    let mut keymap = Keymap::default();
+    let meta = ModifiersState {
+        meta: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        keymap.command_for(Key::D, &meta),
+        Some(CanvasCommand::Duplicate)
+    );
+    assert_eq!(
+        keymap.command_for(Key::ArrowLeft, &shift()),
+        Some(CanvasCommand::NudgeLarge(Direction::Left))
+    );
+    // Modifiers must match exactly.
+    assert_eq!(keymap.command_for(Key::F, &shift()), None);
+    assert_eq!(
+        keymap.chords_for(CanvasCommand::FocusSearch),
+        [KeyChord::command(Key::F), KeyChord::key(Key::Slash)]
+    );
+
+    // Rebinding a chord replaces its command.
+    keymap.bind(KeyChord::key(Key::F), CanvasCommand::FocusSearch);
+    assert_eq!(
+        keymap.command_for(Key::F, &ModifiersState::default()),
+        Some(CanvasCommand::FocusSearch)
+    );
+    assert!(keymap.chords_for(CanvasCommand::ZoomToFit).is_empty());
+    assert_eq!(
+        keymap.unbind(KeyChord::key(Key::Slash)),
+        Some(CanvasCommand::FocusSearch)
+    );
+}
+
+#[test]
+fn test_remapped_shortcuts_drive_the_canvas() {
+    let mut keymap = Keymap::empty();
+    keymap.bind(KeyChord::key(Key::X), CanvasCommand::Duplicate);
+    let mut canvas = Canvas::new(CanvasConfig {
+        keymap,
+        ..Default::default()
+    });
+    let mut graph: GraphState<String> = GraphState::default();
+
+    // The default Ctrl+X no longer cuts.
+    let (_, events) = canvas.update(&press(Key::X, ctrl()), 0.016, &mut graph);
+    assert!(events.is_empty());
+
+    let (_, events) = canvas.update(&press(Key::X, ModifiersState::default()), 0.016, &mut graph);
+    assert_eq!(events, [LogicEvent::DuplicateSelection]);
+}
+
+#[test]
+fn test_arrows_nudge_the_unlocked_selection() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(200.0, 0.0));
+    let locked = add_node(&mut graph, Vec2::new(400.0, 0.0));
+    for id in [a, b, locked] {
+        graph.nodes[id].flags.insert(NodeFlags::SELECTED);
+    }
+    graph.nodes[locked].flags.insert(NodeFlags::LOCKED);
+
+    let (_, events) = canvas.update(
+        &press(Key::ArrowRight, ModifiersState::default()),
+        0.016,
+        &mut graph,
+    );
+    assert_eq!(graph.nodes[a].position, Vec2::new(1.0, 0.0));
+    assert_eq!(graph.nodes[locked].position, Vec2::new(400.0, 0.0));
+    match &events[0] {
+        LogicEvent::NodesMoved { ids, delta } => {
+            assert_eq!(ids.len(), 2);
+            assert_eq!(*delta, Vec2::new(1.0, 0.0));
+        }
+        other => panic!("expected NodesMoved, got {other:?}"),
+    }
+
+    // Shift moves a whole grid cell.
+    canvas.update(&press(Key::ArrowUp, shift()), 0.016, &mut graph);
+    assert_eq!(graph.nodes[b].position, Vec2::new(201.0, -20.0));
+}
+
+#[test]
+fn test_escape_cancels_linking_and_dragging() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    graph.add_port(a, false);
+
+    // Start a wire from the output at (100, 50) and drag it away.
+    canvas.update(&mouse(Vec2::new(100.0, 50.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(300.0, 50.0), true), 0.016, &mut graph);
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::Linking { .. }
+    ));
+
+    let mut escape = mouse(Vec2::new(300.0, 50.0), true);
+    escape.pressed_keys = vec![Key::Escape];
+    canvas.update(&escape, 0.016, &mut graph);
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::WaitingForRelease
+    ));
+    let (_, events) = canvas.update(&mouse(Vec2::new(300.0, 50.0), false), 0.016, &mut graph);
+    assert!(
+        !events
+            .iter()
+            .any(|e| matches!(e, LogicEvent::Connect { .. }))
+    );
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+
+    // A drag cancelled midway puts the nodes back.
+    canvas.update(&mouse(Vec2::new(50.0, 50.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(90.0, 80.0), true), 0.016, &mut graph);
+    assert_eq!(graph.nodes[a].position, Vec2::new(40.0, 30.0));
+    let mut escape = mouse(Vec2::new(90.0, 80.0), true);
+    escape.pressed_keys = vec![Key::Escape];
+    canvas.update(&escape, 0.016, &mut graph);
+    assert_eq!(graph.nodes[a].position, Vec2::ZERO);
+
+    // When idle, Escape clears the selection.
+    canvas.update(&mouse(Vec2::new(90.0, 80.0), false), 0.016, &mut graph);
+    assert!(graph.nodes[a].flags.contains(NodeFlags::SELECTED));
+    canvas.update(
+        &press(Key::Escape, ModifiersState::default()),
+        0.016,
+        &mut graph,
+    );
+    assert!(!graph.nodes[a].flags.contains(NodeFlags::SELECTED));
+}
+
+#[test]
+fn test_zoom_to_fit_frames_the_graph() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node(&mut graph, Vec2::new(1000.0, 1000.0));
+    add_node(&mut graph, Vec2::new(2900.0, 1400.0));
+
+    let mut input = press(Key::F, ModifiersState::default());
+    input.screen_size = Vec2::new(800.0, 600.0);
+    canvas.update(&input, 0.016, &mut graph);
+    assert!(canvas.view.is_animating());
+    // Let the transition finish.
+    input.pressed_keys.clear();
+    canvas.update(&input, 1.0, &mut graph);
+
+    let view = &canvas.view;
+    let top_left = view.world_to_screen(Vec2::new(1000.0, 1000.0));
+    let bottom_right = view.world_to_screen(Vec2::new(3000.0, 1500.0));
+    // Width is the tighter fit: 2000 world units across 720 pixels.
+    assert!((view.transform.zoom - 0.36).abs() < 1e-4);
+    assert!((top_left.x - 40.0).abs() < 1e-3);
+    assert!((bottom_right.x - 760.0).abs() < 1e-3);
+    assert!(((top_left.y + bottom_right.y) * 0.5 - 300.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_duplicate_selection_offsets_copies() {
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::new(10.0, 10.0));
+    graph.nodes[a].flags.insert(NodeFlags::SELECTED);
+
+    let LogicEvent::Pasted { ids } = graph.duplicate_selection(Vec2::splat(20.0)) else {
+        panic!("expected Pasted");
+    };
+    assert_eq!(ids.len(), 1);
+    assert_eq!(graph.nodes[ids[0]].position, Vec2::splat(30.0));
+    assert!(graph.nodes[ids[0]].flags.contains(NodeFlags::SELECTED));
+    assert!(!graph.nodes[a].flags.contains(NodeFlags::SELECTED));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::input::InputState;
+use flow_canvas::model::{GraphState, NodeFlags};
+use flow_canvas::painter::{DetailLevel, Painter};
+use flow_canvas::render::{DrawCommand, RenderList};
+use flow_canvas::view::{Transform, View};
+use flow_canvas::{CanvasConfig, InteractionMode};
+use glam::Vec2;
+
+/// A row of labeled nodes chained together.
+fn chain(count: usize) -> GraphState<String> {
+    let mut graph = GraphState::default();
+    let mut previous = None;
+    for i in 0..count {
+        let id = add_node(&mut graph, Vec2::new(i as f32 * 150.0, 0.0));
+        let input = graph.add_labeled_port(id, true, "in", Some("Number".to_string()));
+        let output = graph.add_labeled_port(id, false, "out", Some("Number".to_string()));
+        if let Some(previous) = previous {
+            graph.connect(previous, input);
+        }
+        previous = Some(output);
+    }
+    graph
+}
+
+fn paint(graph: &mut GraphState<String>, config: &CanvasConfig, zoom: f32) -> RenderList {
+    let screen_size = InputState::default().screen_size;
+    let view = View::new(
+        Transform {
+            pan: Vec2::ZERO,
+            zoom,
+        },
+        screen_size,
+    );
+    Painter::draw_graph(&view, config, graph, &InteractionMode::Idle, screen_size)
+}
+
+fn count(list: &RenderList, pred: impl Fn(&DrawCommand) -> bool) -> usize {
+    list.iter().filter(|cmd| pred(cmd)).count()
+}
+
+#[test]
+fn test_zoomed_out_overview_uses_simplified_commands() {
+    // No grid, so every Line is a wire.
+    let config = CanvasConfig {
+        grid_min_zoom: f32::INFINITY,
+        ..Default::default()
+    };
+    let mut graph = chain(8);
+    let full = paint(&mut graph, &config, 0.5);
+    let overview = paint(&mut graph, &config, 0.3);
+
+    assert_eq!(count(&full, |c| matches!(c, DrawCommand::Bezier { .. })), 7);
+    assert_eq!(count(&full, |c| matches!(c, DrawCommand::Text { .. })), 16);
+
+    // One straight line per wire, one flat rect per node, no ports and no text.
+    assert_eq!(
+        count(&overview, |c| matches!(c, DrawCommand::Bezier { .. })),
+        0
+    );
+    assert_eq!(
+        count(&overview, |c| matches!(c, DrawCommand::Text { .. })),
+        0
+    );
+    assert_eq!(
+        count(&overview, |c| matches!(c, DrawCommand::Line { .. })),
+        7
+    );
+    let rects: Vec<&DrawCommand> = overview
+        .iter()
+        .filter(|c| matches!(c, DrawCommand::Rect { .. }))
+        .collect();
+    assert_eq!(rects.len(), 8);
+    assert!(rects.iter().all(|c| matches!(
+        c,
+        DrawCommand::Rect {
+            corner_radius: 0.0,
+            stroke_color: None,
+            ..
+        }
+    )));
+    assert!(overview.len() * 3 < full.len());
+}
+
+#[test]
+fn test_thresholds_come_from_config() {
+    let mut config = CanvasConfig::default();
+    let view = View::new(
+        Transform {
+            pan: Vec2::ZERO,
+            zoom: 0.3,
+        },
+        Vec2::new(800.0, 600.0),
+    );
+    assert_eq!(
+        DetailLevel::for_view(&view, &config),
+        DetailLevel::Simplified
+    );
+    config.lod_zoom = 0.25;
+    assert_eq!(DetailLevel::for_view(&view, &config), DetailLevel::Full);
+
+    // The grid disappears below its own threshold.
+    let mut empty: GraphState<String> = GraphState::default();
+    let lines = |list: &RenderList| count(list, |c| matches!(c, DrawCommand::Line { .. }));
+    assert!(lines(&paint(&mut empty, &config, 0.25)) > 0);
+    assert_eq!(lines(&paint(&mut empty, &config, 0.15)), 0);
+}
+
+#[test]
+fn test_overview_fills_failed_nodes_with_the_error_color() {
+    let config = CanvasConfig::default();
+    let mut graph = chain(2);
+    let failed = graph.draw_order[1];
+    graph.nodes[failed].flags.insert(NodeFlags::ERROR);
+
+    let overview = paint(&mut graph, &config, 0.3);
+    let fills: Vec<_> = overview
+        .iter()
+        .filter_map(|c| match c {
+            DrawCommand::Rect { color, .. } => Some(*color),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        fills,
+        [
+            config.style.node_default.color,
+            config.style.activity.error_border_color
+        ]
+    );
+}
@@ -0,0 +1,96 @@
+//! Coarse frame-time budgets, loose enough for shared CI runners but tight enough to catch an
+//! accidental O(n²). Debug builds get 10x the budget; set `FLOW_CANVAS_BUDGET_SCALE` to
+//! override the factor (e.g. on very slow hardware). `cargo bench` has the precise numbers.
+
+use flow_canvas::input::InputState;
+use flow_canvas::model::{GraphState, Node, NodeFlags, NodeId, Uuid};
+use flow_canvas::{Canvas, CanvasConfig};
+use glam::Vec2;
+use std::time::{Duration, Instant};
+
+fn budget(release_ms: u64) -> Duration {
+    let scale = std::env::var("FLOW_CANVAS_BUDGET_SCALE")
+        .ok()
+        .and_then(|scale| scale.parse::<f64>().ok())
+        .unwrap_or(if cfg!(debug_assertions) { 10.0 } else { 1.0 });
+    Duration::from_secs_f64(release_ms as f64 / 1000.0 * scale)
+}
+
+/// Best of `runs`, which filters out scheduler noise better than the mean.
+fn fastest(runs: usize, mut f: impl FnMut()) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn graph(nodes: usize) -> GraphState<String> {
+    let mut graph = GraphState::default();
+    let mut previous = None;
+    for i in 0..nodes {
+        let id = graph.insert_node(Node {
+            id: NodeId::default(),
+            uuid: Uuid::new_v4(),
+            position: Vec2::new((i % 100) as f32 * 180.0, (i / 100) as f32 * 120.0),
+            size: Vec2::new(140.0, 80.0),
+            inputs: vec![],
+            outputs: vec![],
+            data: format!("Node {}", i),
+            flags: NodeFlags::default(),
+            style: None,
+        });
+        let input = graph.add_port(id, true);
+        let output = graph.add_port(id, false);
+        if let Some(previous) = previous {
+            graph.connect(previous, input);
+        }
+        previous = Some(output);
+    }
+    graph
+}
+
+#[test]
+fn test_frame_budget_10k_nodes() {
+    let mut graph = graph(10_000);
+    let input = InputState {
+        screen_size: Vec2::new(1920.0, 1080.0),
+        ..Default::default()
+    };
+    for zoom in [1.0, 0.1] {
+        let mut canvas = Canvas::new(CanvasConfig::default());
+        canvas.update_viewport_size(input.screen_size);
+        canvas.view.transform.zoom = zoom;
+
+        let frame = fastest(5, || {
+            canvas.update(&input, 0.016, &mut graph);
+        });
+        // One frame at 60 fps.
+        assert!(
+            frame < budget(16),
+            "10k-node frame at zoom {} took {:?} (budget {:?})",
+            zoom,
+            frame,
+            budget(16)
+        );
+    }
+}
+
+#[test]
+fn test_snapshot_budget_10k_nodes() {
+    let graph = graph(10_000);
+    let saved = fastest(3, || {
+        std::hint::black_box(graph.save());
+    });
+    assert!(saved < budget(20), "save took {:?}", saved);
+
+    let snapshot = graph.save();
+    let loaded = fastest(3, || {
+        let mut restored: GraphState<String> = GraphState::default();
+        restored.load(snapshot.clone());
+    });
+    assert!(loaded < budget(40), "load took {:?}", loaded);
+}
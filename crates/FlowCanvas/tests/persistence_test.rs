@@ -1,5 +1,9 @@
-use flow_canvas::model::{GraphState, Node, NodeFlags, Port, PortId, Uuid, WireStyle};
-use flow_canvas::persistence::SavedGraph;
+mod common;
+
+use common::{NODE_SIZE, add_node_with};
+use flow_canvas::model::{GraphState, Node, NodeFlags, NodeId, Port, PortId, Uuid, WireStyle};
+use flow_canvas::persistence::{GRAPH_FORMAT_VERSION, SavedGraph};
+use flow_canvas::view::Transform;
 use glam::Vec2;
 
 #[test]
@@ -24,6 +28,8 @@ fn test_roundtrip_persistence() {
     let port_out = graph.ports.insert(Port {
         id: PortId::default(),
         node: node_a,
+        label: None,
+        data_type: None,
     });
     graph.nodes[node_a].outputs.push(port_out);
 
@@ -44,6 +50,8 @@ fn test_roundtrip_persistence() {
     let port_in = graph.ports.insert(Port {
         id: PortId::default(),
         node: node_b,
+        label: None,
+        data_type: None,
     });
     graph.nodes[node_b].inputs.push(port_in);
 
@@ -53,6 +61,8 @@ fn test_roundtrip_persistence() {
         to: port_in,
         style: WireStyle::Cubic,
         visual_style: None,
+        waypoints: vec![],
+        label: None,
     });
 
     // 2. Save
@@ -109,3 +119,225 @@ fn test_roundtrip_persistence() {
     assert_eq!(new_from_port.node, new_node_a.id); // Not stable ID, but correct relationship
     assert_eq!(new_to_port.node, new_node_b.id);
 }
+
+#[test]
+fn test_selection_snippet_instantiation() {
+    fn add(graph: &mut GraphState<String>, x: f32) -> (NodeId, PortId, PortId) {
+        let id = graph.insert_node(Node {
+            id: NodeId::default(),
+            uuid: Uuid::new_v4(),
+            position: Vec2::new(x, 50.0),
+            size: Vec2::new(100.0, 100.0),
+            inputs: vec![],
+            outputs: vec![],
+            data: format!("Node {}", x),
+            flags: NodeFlags::default(),
+            style: None,
+        });
+        let input = graph.add_port(id, true);
+        let output = graph.add_port(id, false);
+        (id, input, output)
+    }
+
+    let mut graph: GraphState<String> = GraphState::default();
+    let (a, _, a_out) = add(&mut graph, 100.0);
+    let (b, b_in, b_out) = add(&mut graph, 300.0);
+    let (_c, c_in, _) = add(&mut graph, 500.0);
+    graph.connect(a_out, b_in);
+    graph.connect(b_out, c_in);
+
+    // Only A -> B is internal to the selection; B -> C is cut.
+    let snippet = graph.save_selection(&[a, b]);
+    assert_eq!(snippet.nodes.len(), 2);
+    assert_eq!(snippet.connections.len(), 1);
+    assert_eq!(snippet.nodes[0].position, Vec2::new(0.0, 0.0));
+    assert_eq!(snippet.nodes[1].position, Vec2::new(200.0, 0.0));
+
+    let created = graph.instantiate(snippet.clone(), Vec2::new(1000.0, 1000.0));
+    assert_eq!(created.len(), 2);
+    assert_eq!(graph.nodes.len(), 5);
+    assert_eq!(graph.connections.len(), 3);
+    assert_eq!(graph.nodes[created[0]].position, Vec2::new(1000.0, 1000.0));
+    for id in &created {
+        let uuid = graph.nodes[*id].uuid;
+        assert!(snippet.nodes.iter().all(|n| n.uuid != uuid));
+        assert_eq!(graph.uuid_index.get(&uuid), Some(id));
+    }
+}
+
+#[test]
+fn test_operation_log_replay_and_compaction() {
+    use flow_canvas::persistence::{Autosave, GraphOp, SaveJournal};
+
+    fn add(graph: &mut GraphState<String>, name: &str) -> NodeId {
+        let id = graph.insert_node(Node {
+            id: NodeId::default(),
+            uuid: Uuid::new_v4(),
+            position: Vec2::ZERO,
+            size: Vec2::new(100.0, 100.0),
+            inputs: vec![],
+            outputs: vec![],
+            data: name.to_string(),
+            flags: NodeFlags::default(),
+            style: None,
+        });
+        graph.add_port(id, true);
+        graph.add_port(id, false);
+        id
+    }
+
+    let mut graph: GraphState<String> = GraphState::default();
+    for i in 0..1000 {
+        add(&mut graph, &format!("Node {}", i));
+    }
+    let mut journal = SaveJournal::new(4);
+    let snapshot = journal.compact(&graph);
+    assert!(journal.flush(&graph).is_none());
+
+    // Add a node, wire it up, move it and rename it.
+    let a = graph.nodes.keys().next().unwrap();
+    let b = add(&mut graph, "New");
+    journal.record(GraphOp::AddNode(graph.saved_node(b).unwrap()));
+    let conn = graph.connect(graph.nodes[a].outputs[0], graph.nodes[b].inputs[0]);
+    journal.record(GraphOp::Connect(graph.saved_connection(conn).unwrap()));
+    let Some(Autosave::Append(first)) = journal.flush(&graph) else {
+        panic!("expected an append");
+    };
+    assert_eq!(first.len(), 2);
+
+    graph.nodes[b].position = Vec2::new(40.0, 80.0);
+    journal.record(GraphOp::MoveNode {
+        uuid: graph.nodes[b].uuid,
+        position: graph.nodes[b].position,
+    });
+    let Some(Autosave::Append(second)) = journal.flush(&graph) else {
+        panic!("expected an append");
+    };
+    assert_eq!(journal.log_len(), 3);
+
+    // Snapshot + log reproduces the graph.
+    let mut restored: GraphState<String> = GraphState::default();
+    let applied = restored.load_with_log(snapshot.clone(), first.into_iter().chain(second));
+    assert_eq!(applied, 3);
+    assert_eq!(restored.nodes.len(), 1001);
+    assert_eq!(restored.connections.len(), 1);
+    let b_uuid = graph.nodes[b].uuid;
+    let new_b = restored.uuid_index[&b_uuid];
+    assert_eq!(restored.nodes[new_b].position, Vec2::new(40.0, 80.0));
+    assert_eq!(restored.nodes[new_b].data, "New");
+
+    // Edits against missing nodes are skipped.
+    assert!(!restored.apply(GraphOp::MoveNode {
+        uuid: Uuid::new_v4(),
+        position: Vec2::ONE,
+    }));
+
+    let wire = graph.saved_connection(conn).unwrap();
+    assert!(restored.apply(GraphOp::Disconnect(wire.clone())));
+    assert!(restored.connections.is_empty());
+    assert!(restored.apply(GraphOp::Connect(wire)));
+
+    // Removing a node drops its connections; the fourth op triggers compaction.
+    let a_uuid = graph.nodes[a].uuid;
+    assert!(restored.apply(GraphOp::RemoveNode { uuid: b_uuid }));
+    assert!(restored.connections.is_empty());
+    assert!(!restored.draw_order.contains(&new_b));
+    graph.remove_node(b);
+    graph.connections.clear();
+    journal.record(GraphOp::RemoveNode { uuid: b_uuid });
+    let Some(Autosave::Snapshot(compacted)) = journal.flush(&graph) else {
+        panic!("expected a snapshot");
+    };
+    assert_eq!(journal.log_len(), 0);
+    assert_eq!(compacted.nodes.len(), 1000);
+    assert!(compacted.nodes.iter().any(|n| n.uuid == a_uuid));
+}
+
+#[test]
+fn test_json_document_roundtrip_keeps_draw_order_and_view() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_node_with(&mut graph, Vec2::ZERO, NODE_SIZE, "A".to_string());
+    let b = add_node_with(
+        &mut graph,
+        Vec2::new(200.0, 0.0),
+        NODE_SIZE,
+        "B".to_string(),
+    );
+    let out = graph.add_labeled_port(a, false, "out", Some("Number".to_string()));
+    let input = graph.add_port(b, true);
+    graph.connect(out, input);
+    // Bring A to the front.
+    graph.draw_order.retain(|id| *id != a);
+    graph.draw_order.push(a);
+
+    let view = Transform {
+        pan: Vec2::new(-30.0, 12.5),
+        zoom: 1.75,
+    };
+    let json = graph.save_to_json(Some(view)).unwrap();
+    assert!(json.contains(&format!("\"version\":{GRAPH_FORMAT_VERSION}")));
+
+    let mut restored: GraphState<String> = GraphState::default();
+    let restored_view = restored.load_from_json(&json).unwrap().unwrap();
+    assert_eq!(restored_view.pan, view.pan);
+    assert_eq!(restored_view.zoom, view.zoom);
+
+    let order: Vec<&str> = restored
+        .draw_order
+        .iter()
+        .map(|id| restored.nodes[*id].data.as_str())
+        .collect();
+    assert_eq!(order, ["B", "A"]);
+    assert_eq!(restored.connections.len(), 1);
+    let a = restored.draw_order[1];
+    let port = &restored.ports[restored.nodes[a].outputs[0]];
+    assert_eq!(port.data_type.as_deref(), Some("Number"));
+}
+
+#[test]
+fn test_json_loading_is_tolerant_of_old_and_new_documents() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_node_with(
+        &mut graph,
+        Vec2::new(10.0, 20.0),
+        NODE_SIZE,
+        "A".to_string(),
+    );
+    graph.add_port(a, true);
+
+    // A bare SavedGraph, as written before documents were versioned.
+    let legacy = r#"{
+        "nodes": [{
+            "uuid": "6f1c1c38-3c1e-4c55-9b0e-3f8c1b1d2a11",
+            "position": [5.0, 6.0],
+            "size": [100.0, 80.0],
+            "data": "Old",
+            "flags": 0,
+            "style": null,
+            "input_count": 1,
+            "output_count": 0
+        }],
+        "connections": []
+    }"#;
+    let mut restored: GraphState<String> = GraphState::default();
+    assert!(restored.load_from_json(legacy).unwrap().is_none());
+    let node = restored.nodes.values().next().unwrap();
+    assert_eq!(node.data, "Old");
+    assert_eq!(node.position, Vec2::new(5.0, 6.0));
+    assert_eq!(node.inputs.len(), 1);
+
+    // A newer document with fields this build does not know about.
+    let mut document: serde_json::Value =
+        serde_json::from_str(&graph.save_to_json(None).unwrap()).unwrap();
+    document["version"] = (GRAPH_FORMAT_VERSION + 1).into();
+    document["minimap"] = serde_json::json!({ "visible": true });
+    document["nodes"][0]["badge"] = "new".into();
+    restored.load_from_json(&document.to_string()).unwrap();
+    let node = restored.nodes.values().next().unwrap();
+    assert_eq!(node.data, "A");
+    assert_eq!(node.position, Vec2::new(10.0, 20.0));
+
+    // Malformed input leaves the graph alone.
+    assert!(restored.load_from_json("{ \"nodes\": 3 }").is_err());
+    assert_eq!(restored.nodes.len(), 1);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::{add_node, mouse};
+use flow_canvas::history::HistoryManager;
+use flow_canvas::input::{InputState, Key};
+use flow_canvas::model::{GraphState, NodeFlags};
+use flow_canvas::render::DrawCommand;
+use flow_canvas::{Canvas, CanvasConfig, LogicEvent};
+use glam::Vec2;
+
+fn node_rects(list: &[DrawCommand]) -> usize {
+    list.iter()
+        .filter(|c| matches!(c, DrawCommand::Rect { size, .. } if *size == Vec2::splat(100.0)))
+        .count()
+}
+
+#[test]
+fn test_idle_frames_reuse_the_draw_list() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node(&mut graph, Vec2::ZERO);
+
+    let idle = InputState::default();
+    let (first, _) = canvas.update(&idle, 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    let (second, events) = canvas.update(&idle, 0.016, &mut graph);
+    assert!(canvas.last_frame_cached());
+    assert!(events.is_empty());
+    assert_eq!(first.len(), second.len());
+
+    // Editing through GraphState methods repaints.
+    add_node(&mut graph, Vec2::new(200.0, 0.0));
+    let (list, _) = canvas.update(&idle, 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert_eq!(node_rects(&list), 2);
+
+    // So does moving the view.
+    canvas.view.transform.pan.x += 10.0;
+    canvas.update(&idle, 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    canvas.update(&idle, 0.016, &mut graph);
+    assert!(canvas.last_frame_cached());
+
+    // Direct field writes need mark_changed.
+    let node = graph.draw_order[0];
+    graph.nodes[node].flags.insert(NodeFlags::HIDDEN);
+    graph.mark_changed();
+    canvas.update(&idle, 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+}
+
+#[test]
+fn test_interactions_and_host_responses_repaint() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+
+    canvas.update(&InputState::default(), 0.016, &mut graph);
+
+    // Dragging a node repaints every frame, including the release.
+    canvas.update(&mouse(Vec2::new(50.0, 50.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(80.0, 50.0), true), 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    canvas.update(&mouse(Vec2::new(80.0, 50.0), false), 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert_eq!(graph.nodes[a].position, Vec2::new(30.0, 0.0));
+
+    // A request event is answered between frames, so the frame after it repaints too.
+    graph.nodes[a].flags.insert(NodeFlags::SELECTED);
+    let delete = InputState {
+        pressed_keys: vec![Key::Delete],
+        ..Default::default()
+    };
+    let (_, events) = canvas.update(&delete, 0.016, &mut graph);
+    assert!(events.contains(&LogicEvent::DeleteSelection));
+    graph.nodes.remove(a);
+    graph.draw_order.clear();
+    let (list, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert_eq!(node_rects(&list), 0);
+}
+
+#[test]
+fn test_live_activity_and_undo_repaint() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let idle = InputState::default();
+    canvas.update(&idle, 0.016, &mut graph);
+
+    // Repaints while the flag is live and once more as it expires, then settles.
+    graph.mark_node_running(a);
+    canvas.update(&idle, 0.5, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    canvas.update(&idle, 1.0, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert!(!graph.nodes[a].flags.contains(NodeFlags::RUNNING));
+    canvas.update(&idle, 0.016, &mut graph);
+    assert!(canvas.last_frame_cached());
+
+    let mut history = HistoryManager::new(10);
+    history.commit(&graph);
+    add_node(&mut graph, Vec2::new(200.0, 0.0));
+    canvas.update(&idle, 0.016, &mut graph);
+    assert!(history.undo(&mut graph));
+    let (list, _) = canvas.update(&idle, 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert_eq!(node_rects(&list), 1);
+}
+
+#[test]
+fn test_retaining_can_be_turned_off() {
+    let mut canvas = Canvas::new(CanvasConfig {
+        retain_render_list: false,
+        ..Default::default()
+    });
+    let mut graph: GraphState<String> = GraphState::default();
+    canvas.update(&InputState::default(), 0.016, &mut graph);
+    canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::{add_node, mouse};
+use flow_canvas::input::{InputState, Key};
+use flow_canvas::model::{ConnectionId, GraphState, PortId};
+use flow_canvas::render::DrawCommand;
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode, LogicEvent};
+use glam::Vec2;
+
+/// `a -> b`, plus an unconnected `c` below `b`. Ports sit at the middle of the node sides:
+/// a's output at (100, 50), b's input at (300, 50) and c's input at (300, 250).
+fn wired() -> (GraphState<String>, ConnectionId, PortId, PortId) {
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(300.0, 0.0));
+    let c = add_node(&mut graph, Vec2::new(300.0, 200.0));
+    let out = graph.add_port(a, false);
+    let b_in = graph.add_port(b, true);
+    let c_in = graph.add_port(c, true);
+    let wire = graph.connect(out, b_in);
+    (graph, wire, out, c_in)
+}
+
+fn wire_count(commands: &[DrawCommand]) -> usize {
+    commands
+        .iter()
+        .filter(|cmd| matches!(cmd, DrawCommand::Bezier { .. }))
+        .count()
+}
+
+#[test]
+fn test_click_selects_wire_and_delete_disconnects_it() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let (mut graph, wire, _, _) = wired();
+
+    canvas.update(&mouse(Vec2::new(200.0, 53.0), true), 0.016, &mut graph);
+    let (commands, _) = canvas.update(&mouse(Vec2::new(200.0, 53.0), false), 0.016, &mut graph);
+    assert_eq!(graph.selected_connection, Some(wire));
+    assert!(commands.iter().any(|cmd| matches!(
+        cmd,
+        DrawCommand::Bezier { color, .. } if *color == canvas.config.style.selected_edge_color
+    )));
+
+    let delete = InputState {
+        pressed_keys: vec![Key::Delete],
+        ..Default::default()
+    };
+    let (_, events) = canvas.update(&delete, 0.016, &mut graph);
+    assert_eq!(events[0], LogicEvent::Disconnect { id: wire });
+    assert_eq!(graph.selected_connection, None);
+
+    // Clicking elsewhere drops the wire selection.
+    canvas.update(&mouse(Vec2::new(200.0, 53.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(200.0, 53.0), false), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(600.0, 500.0), true), 0.016, &mut graph);
+    assert_eq!(graph.selected_connection, None);
+}
+
+#[test]
+fn test_dragging_off_an_input_replugs_the_wire() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let (mut graph, wire, out, c_in) = wired();
+
+    let (commands, _) = canvas.update(&mouse(Vec2::new(300.0, 50.0), true), 0.016, &mut graph);
+    match canvas.interaction_mode {
+        InteractionMode::Linking {
+            source, detached, ..
+        } => {
+            assert_eq!(source, out);
+            assert_eq!(detached, Some(wire));
+        }
+        _ => panic!("Should be relinking"),
+    }
+    // Only the wire being dragged is drawn, not the original.
+    assert_eq!(wire_count(&commands), 1);
+
+    canvas.update(&mouse(Vec2::new(300.0, 250.0), true), 0.016, &mut graph);
+    let (_, events) = canvas.update(&mouse(Vec2::new(300.0, 250.0), false), 0.016, &mut graph);
+    let requests: Vec<_> = events
+        .into_iter()
+        .filter(|e| *e != LogicEvent::RepaintNeeded)
+        .collect();
+    assert_eq!(
+        requests,
+        vec![
+            LogicEvent::Disconnect { id: wire },
+            LogicEvent::Connect {
+                from: out,
+                to: c_in
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_plugging_a_wire_back_in_is_a_no_op() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let (mut graph, _, _, _) = wired();
+
+    canvas.update(&mouse(Vec2::new(300.0, 50.0), true), 0.016, &mut graph);
+    let (_, events) = canvas.update(&mouse(Vec2::new(302.0, 50.0), false), 0.016, &mut graph);
+    assert!(!events.iter().any(|e| matches!(
+        e,
+        LogicEvent::Disconnect { .. } | LogicEvent::Connect { .. }
+    )));
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::add_node_with;
+use flow_canvas::config::CanvasConfig;
+use flow_canvas::interaction::InteractionMode;
+use flow_canvas::math::Rect;
+use flow_canvas::model::GraphState;
+use flow_canvas::painter::Painter;
+use flow_canvas::render::DrawCommand;
+use flow_canvas::view::{Transform, View};
+use glam::Vec2;
+
+/// The culling bounds below are worked out for nodes of this size.
+const NODE_SIZE: Vec2 = Vec2::new(100.0, 50.0);
+
+#[test]
+fn test_index_follows_moves_and_removals() {
+    let mut graph: GraphState<String> = GraphState::default();
+    let a = add_node_with(&mut graph, Vec2::ZERO, NODE_SIZE, "Node".to_string());
+    let b = add_node_with(
+        &mut graph,
+        Vec2::new(50.0, 0.0),
+        NODE_SIZE,
+        "Node".to_string(),
+    );
+    graph.sync_spatial_index();
+
+    // Overlapping nodes: the one drawn last wins, borders included.
+    assert_eq!(graph.node_at(Vec2::new(75.0, 25.0)), Some(b));
+    assert_eq!(graph.node_at(Vec2::new(0.0, 50.0)), Some(a));
+    assert_eq!(graph.node_at(Vec2::new(500.0, 25.0)), None);
+
+    // Positions are written directly; the next sync picks the move up.
+    graph.nodes[b].position = Vec2::new(1000.0, 1000.0);
+    assert_eq!(graph.node_at(Vec2::new(75.0, 25.0)), Some(b));
+    graph.sync_spatial_index();
+    assert_eq!(graph.node_at(Vec2::new(75.0, 25.0)), Some(a));
+    assert_eq!(
+        graph.nodes_in_rect(Rect::new(Vec2::new(900.0, 900.0), Vec2::splat(200.0))),
+        vec![b]
+    );
+
+    // Nodes removed behind the index's back are dropped on sync.
+    graph.nodes.remove(a);
+    graph.sync_spatial_index();
+    assert_eq!(graph.spatial.len(), 1);
+    assert_eq!(graph.node_at(Vec2::new(25.0, 25.0)), None);
+}
+
+#[test]
+fn test_painter_culls_off_screen_nodes_and_wires() {
+    let config = CanvasConfig::default();
+    let mut graph: GraphState<String> = GraphState::default();
+    let visible = add_node_with(
+        &mut graph,
+        Vec2::new(100.0, 100.0),
+        NODE_SIZE,
+        "Node".to_string(),
+    );
+    let far = add_node_with(
+        &mut graph,
+        Vec2::new(5000.0, 5000.0),
+        NODE_SIZE,
+        "Node".to_string(),
+    );
+    let farther = add_node_with(
+        &mut graph,
+        Vec2::new(5300.0, 5000.0),
+        NODE_SIZE,
+        "Node".to_string(),
+    );
+    let out = graph.add_port(far, false);
+    let input = graph.add_port(farther, true);
+    graph.connect(out, input);
+    let out = graph.add_port(visible, false);
+    let input = graph.add_port(far, true);
+    graph.connect(out, input);
+
+    let view = View::new(Transform::default(), Vec2::new(800.0, 600.0));
+    let commands = Painter::draw_graph(
+        &view,
+        &config,
+        &mut graph,
+        &InteractionMode::Idle,
+        Vec2::new(800.0, 600.0),
+    );
+
+    let node_rects: Vec<Vec2> = commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Rect { pos, size, .. } if *size == Vec2::new(100.0, 50.0) => Some(*pos),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(node_rects, vec![graph.nodes[visible].position]);
+
+    // The wire leaving the screen is kept; the one entirely off screen is not.
+    let wires = commands
+        .iter()
+        .filter(|cmd| matches!(cmd, DrawCommand::Bezier { .. }))
+        .count();
+    assert_eq!(wires, 1);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{NODE_SIZE, add_node_with};
+use flow_canvas::config::NodeStyle;
+use flow_canvas::input::InputState;
+use flow_canvas::model::{GraphState, NodeData, NodeFlags};
+use flow_canvas::render::{DrawCommand, RenderList};
+use flow_canvas::theme::{Theme, ThemePreset};
+use flow_canvas::{Canvas, CanvasConfig};
+use glam::{Vec2, Vec4};
+
+#[derive(Clone, Debug)]
+struct Typed(&'static str);
+
+impl NodeData for Typed {
+    fn node_type(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Fill and border of the node rect drawn at screen `x`.
+fn node_colors(list: &RenderList, x: f32) -> (Vec4, Option<Vec4>) {
+    list.iter()
+        .find_map(|cmd| match cmd {
+            DrawCommand::Rect {
+                pos,
+                size,
+                color,
+                stroke_color,
+                ..
+            } if pos.x == x && size.x == 100.0 => Some((*color, *stroke_color)),
+            _ => None,
+        })
+        .expect("node rect")
+}
+
+#[test]
+fn test_presets_expand_into_distinct_styles() {
+    let dark = CanvasConfig::default().style;
+    assert_eq!(dark.background_color, Theme::dark().background);
+    assert_eq!(dark.node_default.color, Theme::dark().node_fill);
+
+    let light = Theme::preset(ThemePreset::Light).style();
+    let contrast = Theme::preset(ThemePreset::HighContrast).style();
+    assert_ne!(light.background_color, dark.background_color);
+    assert_eq!(light.node_default.text_color, Theme::light().text);
+    assert_eq!(light.active_link_color, Theme::light().text);
+    assert_eq!(
+        contrast.selected_edge_color,
+        Theme::high_contrast().selected
+    );
+    assert!(contrast.edge_default.width > dark.edge_default.width);
+}
+
+#[test]
+fn test_nodes_are_colored_by_category_and_selection() {
+    let http = NodeStyle {
+        color: Vec4::new(0.1, 0.2, 0.4, 1.0),
+        border_color: Vec4::new(0.3, 0.5, 0.9, 1.0),
+        text_color: Vec4::ONE,
+    };
+    let theme = Theme::light().with_category("http", http.clone());
+    let mut canvas = Canvas::new(CanvasConfig::with_theme(&theme));
+    let mut graph = GraphState::default();
+    add_node_with(&mut graph, Vec2::ZERO, NODE_SIZE, Typed("http"));
+    let plain = add_node_with(&mut graph, Vec2::new(200.0, 0.0), NODE_SIZE, Typed("log"));
+    let custom = add_node_with(&mut graph, Vec2::new(400.0, 0.0), NODE_SIZE, Typed("http"));
+    graph.nodes[custom].style = Some(NodeStyle {
+        color: Vec4::new(0.5, 0.0, 0.0, 1.0),
+        ..http.clone()
+    });
+
+    let (list, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert_eq!(
+        node_colors(&list, 0.0),
+        (http.color, Some(http.border_color))
+    );
+    assert_eq!(
+        node_colors(&list, 200.0),
+        (theme.node_fill, Some(theme.node_border))
+    );
+    assert_eq!(node_colors(&list, 400.0).0, Vec4::new(0.5, 0.0, 0.0, 1.0));
+
+    // Selection outlines in the theme's color and tints the fill towards it.
+    graph.nodes[plain].flags.insert(NodeFlags::SELECTED);
+    graph.mark_changed();
+    let (list, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+    let (fill, border) = node_colors(&list, 200.0);
+    assert_eq!(border, Some(theme.selected));
+    assert_ne!(fill, theme.node_fill);
+    assert_eq!(fill.w, theme.node_fill.w);
+}
+
+#[test]
+fn test_switching_themes_repaints() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let mut graph = GraphState::default();
+    add_node_with(&mut graph, Vec2::ZERO, NODE_SIZE, Typed("log"));
+
+    let (list, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert_eq!(node_colors(&list, 0.0).0, Theme::dark().node_fill);
+
+    canvas.set_theme(&Theme::high_contrast());
+    let (list, _) = canvas.update(&InputState::default(), 0.016, &mut graph);
+    assert!(!canvas.last_frame_cached());
+    assert_eq!(node_colors(&list, 0.0).0, Theme::high_contrast().node_fill);
+}
+
+#[test]
+fn test_theme_round_trips_through_json() {
+    let theme = Theme::dark().with_category("http", NodeStyle::default());
+    let json = serde_json::to_string(&theme).unwrap();
+    let loaded: Theme = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, theme);
+}
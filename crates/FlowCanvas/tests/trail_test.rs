@@ -0,0 +1,81 @@
+mod common;
+
+use common::add_node;
+use flow_canvas::config::CanvasConfig;
+use flow_canvas::interaction::InteractionMode;
+use flow_canvas::model::GraphState;
+use flow_canvas::painter::Painter;
+use flow_canvas::render::DrawCommand;
+use flow_canvas::trail::ExecutionTrail;
+use flow_canvas::view::{Transform, View};
+use glam::Vec2;
+
+fn draw(graph: &mut GraphState<String>, config: &CanvasConfig) -> Vec<DrawCommand> {
+    let view = View::new(Transform::default(), Vec2::new(800.0, 600.0));
+    Painter::draw_graph(
+        &view,
+        config,
+        graph,
+        &InteractionMode::Idle,
+        Vec2::new(800.0, 600.0),
+    )
+}
+
+/// Fill alpha of the node rect drawn at `x`, and the alpha of each wire in draw order.
+fn alphas(commands: &[DrawCommand], x: f32) -> (f32, Vec<f32>) {
+    let node = commands
+        .iter()
+        .find_map(|cmd| match cmd {
+            DrawCommand::Rect { pos, color, .. } if *pos == Vec2::new(x, 0.0) => Some(color.w),
+            _ => None,
+        })
+        .unwrap();
+    let wires = commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Bezier { color, .. } => Some(color.w),
+            _ => None,
+        })
+        .collect();
+    (node, wires)
+}
+
+#[test]
+fn test_trail_dims_skipped_branch() {
+    let config = CanvasConfig::default();
+    let mut graph: GraphState<String> = GraphState::default();
+    let switch = add_node(&mut graph, Vec2::new(0.0, 0.0));
+    let taken = add_node(&mut graph, Vec2::new(200.0, 0.0));
+    let skipped = add_node(&mut graph, Vec2::new(400.0, 0.0));
+    let out_a = graph.add_port(switch, false);
+    let out_b = graph.add_port(switch, false);
+    let in_taken = graph.add_port(taken, true);
+    let in_skipped = graph.add_port(skipped, true);
+    let taken_wire = graph.connect(out_a, in_taken);
+    graph.connect(out_b, in_skipped);
+
+    let commands = draw(&mut graph, &config);
+    assert_eq!(alphas(&commands, 400.0), (1.0, vec![1.0, 1.0]));
+
+    let mut trail = ExecutionTrail::new("trace-1");
+    trail.nodes.extend([switch, taken]);
+    trail.failed.insert(taken);
+    trail.connections.insert(taken_wire);
+    graph.trail = Some(trail);
+
+    let commands = draw(&mut graph, &config);
+    let opacity = config.style.trail.skipped_opacity;
+    let (skipped_alpha, mut wires) = alphas(&commands, 400.0);
+    assert_eq!(skipped_alpha, opacity);
+    assert_eq!(alphas(&commands, 200.0).0, 1.0);
+    wires.sort_by(f32::total_cmp);
+    assert_eq!(wires, vec![opacity, 1.0]);
+
+    let failed_border = commands.iter().find_map(|cmd| match cmd {
+        DrawCommand::Rect {
+            pos, stroke_color, ..
+        } if *pos == Vec2::new(200.0, 0.0) => *stroke_color,
+        _ => None,
+    });
+    assert_eq!(failed_border, Some(config.style.trail.failed_border_color));
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::{add_node, mouse, mouse_with};
+use flow_canvas::input::ModifiersState;
+use flow_canvas::math::{self, Rect};
+use flow_canvas::model::{ConnectionId, GraphState, NodeId};
+use flow_canvas::{Canvas, CanvasConfig, InteractionMode};
+use glam::Vec2;
+
+/// `a -> b` with a straight wire from (100, 50) to (400, 50).
+fn wired() -> (GraphState<String>, ConnectionId, [NodeId; 2]) {
+    let mut graph = GraphState::default();
+    let a = add_node(&mut graph, Vec2::ZERO);
+    let b = add_node(&mut graph, Vec2::new(400.0, 0.0));
+    let out = graph.add_port(a, false);
+    let input = graph.add_port(b, true);
+    let wire = graph.connect(out, input);
+    (graph, wire, [a, b])
+}
+
+fn alt() -> ModifiersState {
+    ModifiersState {
+        alt: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_paths_pass_through_waypoints() {
+    let start = Vec2::new(0.0, 0.0);
+    let end = Vec2::new(400.0, 0.0);
+    let waypoints = [Vec2::new(100.0, 200.0), Vec2::new(300.0, -100.0)];
+
+    let segments = math::calculate_bezier_segments(start, &waypoints, end);
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[0][0], start);
+    assert_eq!(segments[0][3], waypoints[0]);
+    assert_eq!(segments[1][0], waypoints[0]);
+    assert_eq!(segments[1][3], waypoints[1]);
+    assert_eq!(segments[2][3], end);
+    assert_eq!(
+        math::bezier_path_point(&segments, 1.0 / 3.0),
+        Some(waypoints[0])
+    );
+
+    let obstacles = [Rect::new(Vec2::new(150.0, 0.0), Vec2::new(50.0, 50.0))];
+    let path = math::calculate_smart_orthogonal_through(start, &waypoints, end, &obstacles, 10.0);
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&end));
+    for waypoint in waypoints {
+        assert!(path.contains(&waypoint));
+    }
+    // Every leg is axis-aligned.
+    for pair in path.windows(2) {
+        assert!(pair[0].x == pair[1].x || pair[0].y == pair[1].y);
+    }
+}
+
+#[test]
+fn test_alt_click_adds_drags_and_removes_waypoints() {
+    let mut canvas = Canvas::new(CanvasConfig::default());
+    let (mut graph, wire, _) = wired();
+
+    // Alt+click on the wire drops a waypoint and picks it up
+    canvas.update(
+        &mouse_with(Vec2::new(250.0, 50.0), true, alt()),
+        0.016,
+        &mut graph,
+    );
+    assert_eq!(
+        graph.connections[wire].waypoints,
+        vec![Vec2::new(250.0, 50.0)]
+    );
+    assert!(matches!(
+        canvas.interaction_mode,
+        InteractionMode::DraggingWaypoint { index: 0, .. }
+    ));
+
+    canvas.update(
+        &mouse_with(Vec2::new(250.0, 150.0), true, alt()),
+        0.016,
+        &mut graph,
+    );
+    canvas.update(&mouse(Vec2::new(250.0, 150.0), false), 0.016, &mut graph);
+    assert_eq!(
+        graph.connections[wire].waypoints,
+        vec![Vec2::new(250.0, 150.0)]
+    );
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+
+    // A plain click grabs it again; the wire is not selected
+    canvas.update(&mouse(Vec2::new(252.0, 151.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(262.0, 161.0), true), 0.016, &mut graph);
+    canvas.update(&mouse(Vec2::new(262.0, 161.0), false), 0.016, &mut graph);
+    assert_eq!(
+        graph.connections[wire].waypoints,
+        vec![Vec2::new(260.0, 160.0)]
+    );
+    assert_eq!(graph.selected_connection, None);
+
+    // A second waypoint lands on the leg it was placed on
+    let segments = math::calculate_bezier_segments(
+        Vec2::new(100.0, 50.0),
+        &graph.connections[wire].waypoints,
+        Vec2::new(400.0, 50.0),
+    );
+    let on_first_leg = math::bezier_path_point(&segments, 0.25).unwrap();
+    canvas.update(&mouse_with(on_first_leg, true, alt()), 0.016, &mut graph);
+    canvas.update(&mouse(on_first_leg, false), 0.016, &mut graph);
+    assert_eq!(
+        graph.connections[wire].waypoints,
+        vec![on_first_leg, Vec2::new(260.0, 160.0)]
+    );
+
+    // Alt+click on a waypoint removes it
+    canvas.update(
+        &mouse_with(Vec2::new(260.0, 160.0), true, alt()),
+        0.016,
+        &mut graph,
+    );
+    canvas.update(
+        &mouse_with(Vec2::new(300.0, 300.0), true, alt()),
+        0.016,
+        &mut graph,
+    );
+    assert_eq!(graph.connections[wire].waypoints, vec![on_first_leg]);
+    canvas.update(&mouse(Vec2::new(300.0, 300.0), false), 0.016, &mut graph);
+    assert!(matches!(canvas.interaction_mode, InteractionMode::Idle));
+}
+
+#[test]
+fn test_waypoints_follow_snippets() {
+    let (mut graph, wire, nodes) = wired();
+    graph.connections[wire].waypoints = vec![Vec2::new(250.0, 150.0)];
+
+    let mut restored: GraphState<String> = GraphState::default();
+    restored.load(graph.save());
+    let (_, conn) = restored.connections.iter().next().unwrap();
+    assert_eq!(conn.waypoints, vec![Vec2::new(250.0, 150.0)]);
+
+    // Snippets store waypoints relative to the selection and offset them when placed
+    let mut pasted: GraphState<String> = GraphState::default();
+    pasted.instantiate(graph.save_selection(&nodes), Vec2::new(1000.0, 1000.0));
+    let (_, conn) = pasted.connections.iter().next().unwrap();
+    assert_eq!(conn.waypoints, vec![Vec2::new(1250.0, 1150.0)]);
+}
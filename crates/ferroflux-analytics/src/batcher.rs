@@ -6,6 +6,9 @@ use tokio::sync::broadcast;
 use tokio::time::{self, Duration};
 use uuid::Uuid;
 
+/// `event_type` of the rows recorded for ticket movements between nodes.
+pub const EDGE_TRAVERSAL_EVENT: &str = "edge_traversal";
+
 pub struct AnalyticsBatcher<B: AnalyticsBackend> {
     backend: Arc<B>,
     bus: broadcast::Sender<SystemEvent>,
@@ -110,6 +113,29 @@ impl<B: AnalyticsBackend + 'static> AnalyticsBatcher<B> {
                 duration_ms: 0,
                 status: "error".to_string(),
             }),
+            SystemEvent::EdgeTraversal {
+                trace_id,
+                source_id,
+                source_handle,
+                target_id,
+                bytes,
+                ..
+            } => Some(AnalyticsEvent {
+                id: Uuid::new_v4(),
+                timestamp: Utc::now(),
+                tenant_id: "default".to_string(),
+                node_id: source_id.to_string(),
+                workflow_id: "".to_string(),
+                event_type: EDGE_TRAVERSAL_EVENT.to_string(),
+                payload: serde_json::json!({
+                    "trace_id": trace_id,
+                    "source_handle": source_handle,
+                    "target_id": target_id.to_string(),
+                    "bytes": bytes,
+                }),
+                duration_ms: 0,
+                status: "success".to_string(),
+            }),
             _ => None,
         }
     }
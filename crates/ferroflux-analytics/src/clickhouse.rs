@@ -1,7 +1,10 @@
+use crate::batcher::EDGE_TRAVERSAL_EVENT;
 use anyhow::Result;
 use async_trait::async_trait;
 use clickhouse::{Client, Row};
-use ferroflux_core::store::analytics::{AnalyticsBackend, AnalyticsEvent, PerformanceMetric};
+use ferroflux_core::store::analytics::{
+    AnalyticsBackend, AnalyticsEvent, EdgeTrafficMetric, PerformanceMetric,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -118,6 +121,31 @@ impl AnalyticsBackend for ClickHouseStore {
         let cursor = query.fetch_all::<ClickHouseMetric>().await?;
         Ok(cursor.into_iter().map(|m| m.into()).collect())
     }
+
+    async fn get_edge_traffic(&self, tenant_id: &str) -> Result<Vec<EdgeTrafficMetric>> {
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT
+                    node_id as source_id,
+                    JSONExtract(payload, 'source_handle', 'Nullable(String)') as source_handle,
+                    JSONExtractString(payload, 'target_id') as target_id,
+                    count(*) as messages,
+                    sum(JSONExtractUInt(payload, 'bytes')) as total_bytes,
+                    avg(JSONExtractUInt(payload, 'bytes')) as avg_bytes,
+                    max(JSONExtractUInt(payload, 'bytes')) as max_bytes
+                FROM analytics_events
+                WHERE tenant_id = ? AND event_type = ?
+                GROUP BY source_id, source_handle, target_id
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(EDGE_TRAVERSAL_EVENT)
+            .fetch_all::<ClickHouseEdgeTraffic>()
+            .await?;
+        Ok(rows.into_iter().map(|m| m.into()).collect())
+    }
 }
 
 #[derive(Row, Deserialize)]
@@ -138,3 +166,28 @@ impl From<ClickHouseMetric> for PerformanceMetric {
         }
     }
 }
+
+#[derive(Row, Deserialize)]
+struct ClickHouseEdgeTraffic {
+    source_id: String,
+    source_handle: Option<String>,
+    target_id: String,
+    messages: u64,
+    total_bytes: u64,
+    avg_bytes: f64,
+    max_bytes: u64,
+}
+
+impl From<ClickHouseEdgeTraffic> for EdgeTrafficMetric {
+    fn from(m: ClickHouseEdgeTraffic) -> Self {
+        Self {
+            source_id: m.source_id,
+            source_handle: m.source_handle,
+            target_id: m.target_id,
+            messages: m.messages as i64,
+            total_bytes: m.total_bytes as i64,
+            avg_bytes: m.avg_bytes,
+            max_bytes: m.max_bytes as i64,
+        }
+    }
+}
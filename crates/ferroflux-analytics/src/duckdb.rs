@@ -1,7 +1,10 @@
+use crate::batcher::EDGE_TRAVERSAL_EVENT;
 use anyhow::Result;
 use async_trait::async_trait;
 use duckdb::Connection;
-use ferroflux_core::store::analytics::{AnalyticsBackend, AnalyticsEvent, PerformanceMetric};
+use ferroflux_core::store::analytics::{
+    AnalyticsBackend, AnalyticsEvent, EdgeTrafficMetric, PerformanceMetric,
+};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
@@ -180,4 +183,43 @@ impl AnalyticsBackend for DuckDbStore {
 
         Ok(events)
     }
+
+    async fn get_edge_traffic(&self, tenant_id: &str) -> Result<Vec<EdgeTrafficMetric>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                node_id,
+                json_extract_string(payload, '$.source_handle') as source_handle,
+                json_extract_string(payload, '$.target_id') as target_id,
+                COUNT(*) as messages,
+                SUM(CAST(json_extract(payload, '$.bytes') AS BIGINT)) as total_bytes,
+                AVG(CAST(json_extract(payload, '$.bytes') AS DOUBLE)) as avg_bytes,
+                MAX(CAST(json_extract(payload, '$.bytes') AS BIGINT)) as max_bytes
+            FROM analytics_events
+            WHERE tenant_id = ?
+            AND event_type = ?
+            GROUP BY 1, 2, 3
+            "#,
+        )?;
+
+        let rows = stmt.query_map(duckdb::params![tenant_id, EDGE_TRAVERSAL_EVENT], |row| {
+            Ok(EdgeTrafficMetric {
+                source_id: row.get(0)?,
+                source_handle: row.get(1)?,
+                target_id: row.get(2)?,
+                messages: row.get(3)?,
+                total_bytes: row.get(4)?,
+                avg_bytes: row.get(5)?,
+                max_bytes: row.get(6)?,
+            })
+        })?;
+
+        let mut metrics = Vec::new();
+        for row in rows {
+            metrics.push(row?);
+        }
+
+        Ok(metrics)
+    }
 }
@@ -0,0 +1,319 @@
+//! Tenant-scoped API keys for machine clients (CI, external triggers).
+//!
+//! A key is shown once, when it is created; only its SHA-256 hash is stored. Keys are long
+//! random strings, so a plain digest is enough to make a leaked table useless. The short
+//! `prefix` is kept in clear so keys can be told apart in listings.
+
+use crate::{IamStore, Permission, Principal, Role, TenantId};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+use uuid::Uuid;
+
+/// Every key starts with this, so secret scanners can recognise leaked keys.
+pub const API_KEY_PREFIX: &str = "ffk_";
+
+/// Prefix of the user id of principals authenticated with an API key.
+pub const API_KEY_USER_PREFIX: &str = "api_key:";
+
+/// A stored API key, without its secret.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub tenant_id: TenantId,
+    pub name: String,
+    /// The first characters of the key, e.g. `ffk_1a2b3c4d`.
+    pub prefix: String,
+    /// Role the key acts with inside its tenant.
+    pub role: Role,
+    /// User id of whoever created the key.
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Whether the key can still authenticate at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| now < expires)
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Self> {
+        let role: String = row.get("role");
+        Ok(Self {
+            id: row.get("id"),
+            tenant_id: TenantId(row.get("tenant_id")),
+            name: row.get("name"),
+            prefix: row.get("prefix"),
+            role: role.parse()?,
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            last_used_at: row.get("last_used_at"),
+            revoked_at: row.get("revoked_at"),
+        })
+    }
+}
+
+/// A freshly created key together with its secret, which cannot be retrieved again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewApiKey {
+    pub key: ApiKey,
+    pub secret: String,
+}
+
+fn hash_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", API_KEY_PREFIX, hex::encode(bytes))
+}
+
+const COLUMNS: &str = "id, tenant_id, name, prefix, role, created_by, created_at, expires_at, \
+                       last_used_at, revoked_at";
+
+impl IamStore {
+    /// Creates a key for `tenant` acting with `role`.
+    ///
+    /// The creator needs `Permission::ManageConnections` in the tenant and can't hand out a
+    /// role above their own.
+    pub async fn create_api_key(
+        &self,
+        creator: &Principal,
+        tenant: &TenantId,
+        name: &str,
+        role: Role,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<NewApiKey> {
+        creator.require(tenant, Permission::ManageConnections)?;
+        creator.authorize(tenant, role)?;
+        if name.trim().is_empty() {
+            anyhow::bail!("API key name must not be empty");
+        }
+
+        let secret = generate_secret();
+        let key = ApiKey {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: tenant.clone(),
+            name: name.to_string(),
+            prefix: secret[..API_KEY_PREFIX.len() + 8].to_string(),
+            role,
+            created_by: creator.user_id.clone(),
+            created_at: Utc::now(),
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, tenant_id, name, prefix, key_hash, role, created_by, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&key.id)
+        .bind(&tenant.0)
+        .bind(&key.name)
+        .bind(&key.prefix)
+        .bind(hash_key(&secret))
+        .bind(role.as_str())
+        .bind(&key.created_by)
+        .bind(key.created_at)
+        .bind(key.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!(tenant = %tenant, key_id = %key.id, %role, "API key created");
+        Ok(NewApiKey { key, secret })
+    }
+
+    /// The tenant's keys, revoked and expired ones included, newest first.
+    ///
+    /// The caller needs `Permission::ManageConnections` in the tenant.
+    pub async fn list_api_keys(
+        &self,
+        principal: &Principal,
+        tenant: &TenantId,
+    ) -> Result<Vec<ApiKey>> {
+        principal.require(tenant, Permission::ManageConnections)?;
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE tenant_id = ? ORDER BY created_at DESC",
+            COLUMNS
+        ))
+        .bind(&tenant.0)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(ApiKey::from_row).collect()
+    }
+
+    /// Revokes a key. Returns false if the tenant has no such active key.
+    ///
+    /// The caller needs `Permission::ManageConnections` in the tenant.
+    pub async fn revoke_api_key(
+        &self,
+        principal: &Principal,
+        tenant: &TenantId,
+        key_id: &str,
+    ) -> Result<bool> {
+        principal.require(tenant, Permission::ManageConnections)?;
+        let result = sqlx::query(
+            "UPDATE api_keys SET revoked_at = ? WHERE id = ? AND tenant_id = ? AND revoked_at IS NULL",
+        )
+        .bind(Utc::now())
+        .bind(key_id)
+        .bind(&tenant.0)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolves a presented key to a principal holding the key's role in its tenant, and
+    /// records the use. Unknown, revoked and expired keys yield `None`.
+    pub async fn authenticate_api_key(&self, secret: &str) -> Result<Option<Principal>> {
+        if !secret.starts_with(API_KEY_PREFIX) {
+            return Ok(None);
+        }
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM api_keys WHERE key_hash = ?",
+            COLUMNS
+        ))
+        .bind(hash_key(secret))
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(key) = row.as_ref().map(ApiKey::from_row).transpose()? else {
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        if !key.is_active(now) {
+            return Ok(None);
+        }
+        sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(&key.id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(Some(
+            Principal::new(format!("{}{}", API_KEY_USER_PREFIX, key.id))
+                .with_membership(key.tenant_id, key.role),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh in-memory store with an `acme` organization.
+    async fn store() -> IamStore {
+        let iam = IamStore::new("sqlite::memory:").await.unwrap();
+        sqlx::query("INSERT INTO tenants (id, name, type) VALUES ('acme', 'Acme', 'organization')")
+            .execute(&iam.pool)
+            .await
+            .unwrap();
+        iam
+    }
+
+    #[tokio::test]
+    async fn test_api_key_lifecycle() {
+        let iam = store().await;
+        let acme = TenantId::from("acme");
+        let admin = Principal::new("alice").with_membership(acme.clone(), Role::Admin);
+
+        let created = iam
+            .create_api_key(&admin, &acme, "CI", Role::Editor, None)
+            .await
+            .unwrap();
+        assert!(created.secret.starts_with(&created.key.prefix));
+
+        let principal = iam
+            .authenticate_api_key(&created.secret)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(principal.role_in(&acme), Some(Role::Editor));
+        assert!(principal.require(&acme, Permission::Deploy).is_ok());
+
+        let listed = iam.list_api_keys(&admin, &acme).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_used_at.is_some());
+
+        // Listing and revoking take the same permission as creating.
+        let editor = Principal::new("bob").with_membership(acme.clone(), Role::Editor);
+        assert!(iam.list_api_keys(&editor, &acme).await.is_err());
+        assert!(
+            iam.revoke_api_key(&editor, &acme, &created.key.id)
+                .await
+                .is_err()
+        );
+
+        assert!(
+            iam.revoke_api_key(&admin, &acme, &created.key.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !iam.revoke_api_key(&admin, &acme, &created.key.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            iam.authenticate_api_key(&created.secret)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_cannot_escalate_or_outlive_expiry() {
+        let iam = store().await;
+        let acme = TenantId::from("acme");
+        let admin = Principal::new("alice").with_membership(acme.clone(), Role::Admin);
+        let editor = Principal::new("bob").with_membership(acme.clone(), Role::Editor);
+
+        assert!(
+            iam.create_api_key(&admin, &acme, "root", Role::Owner, None)
+                .await
+                .is_err()
+        );
+        assert!(
+            iam.create_api_key(&editor, &acme, "ci", Role::Viewer, None)
+                .await
+                .is_err()
+        );
+
+        let expired = iam
+            .create_api_key(
+                &admin,
+                &acme,
+                "old",
+                Role::Viewer,
+                Some(Utc::now() - chrono::Duration::minutes(1)),
+            )
+            .await
+            .unwrap();
+        assert!(
+            iam.authenticate_api_key(&expired.secret)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            iam.authenticate_api_key("ffk_not-a-key")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
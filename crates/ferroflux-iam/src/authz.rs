@@ -0,0 +1,208 @@
+use crate::TenantId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Membership role of a user inside a tenant.
+///
+/// Roles are ordered by privilege so that `role >= Role::Editor` reads naturally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+    Owner,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Admin => "admin",
+            Role::Owner => "owner",
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(Role::Viewer),
+            "editor" => Ok(Role::Editor),
+            "admin" => Ok(Role::Admin),
+            "owner" => Ok(Role::Owner),
+            other => Err(anyhow::anyhow!("Unknown role '{}'", other)),
+        }
+    }
+}
+
+/// What an operation needs to be allowed to do inside a tenant.
+///
+/// Each permission is granted by one role and every role above it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Inspect workflows, runs and settings.
+    Read,
+    /// Deploy, trigger and operate workflows.
+    Deploy,
+    /// Manage connections, secrets and encryption keys.
+    ManageConnections,
+    /// Invite, remove and change the roles of members.
+    ManageMembers,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::Read => "read",
+            Permission::Deploy => "deploy",
+            Permission::ManageConnections => "manage_connections",
+            Permission::ManageMembers => "manage_members",
+        }
+    }
+
+    /// The least privileged role holding this permission.
+    pub fn required_role(&self) -> Role {
+        match self {
+            Permission::Read => Role::Viewer,
+            Permission::Deploy => Role::Editor,
+            Permission::ManageConnections => Role::Admin,
+            Permission::ManageMembers => Role::Owner,
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The authenticated caller of an API operation.
+///
+/// A principal carries a snapshot of its tenant memberships so that authorization
+/// can be evaluated synchronously (e.g. inside the ECS tick) without a database round-trip.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Principal {
+    /// The user ID (or service name for system principals).
+    pub user_id: String,
+    /// Tenant -> Role memberships, resolved at authentication time.
+    #[serde(default)]
+    pub memberships: HashMap<TenantId, Role>,
+    /// Internal callers (engine bootstrap, embedded SDK) bypass tenant checks.
+    #[serde(default)]
+    pub is_system: bool,
+}
+
+impl Principal {
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            memberships: HashMap::new(),
+            is_system: false,
+        }
+    }
+
+    /// The trusted in-process principal used by the engine itself.
+    pub fn system() -> Self {
+        Self {
+            user_id: "system".to_string(),
+            memberships: HashMap::new(),
+            is_system: true,
+        }
+    }
+
+    pub fn with_membership(mut self, tenant: TenantId, role: Role) -> Self {
+        self.memberships.insert(tenant, role);
+        self
+    }
+
+    pub fn role_in(&self, tenant: &TenantId) -> Option<Role> {
+        self.memberships.get(tenant).copied()
+    }
+
+    /// Checks that this principal may act on `tenant` with at least `required` privileges.
+    pub fn authorize(&self, tenant: &TenantId, required: Role) -> Result<(), AuthzError> {
+        if self.is_system {
+            return Ok(());
+        }
+        match self.role_in(tenant) {
+            None => Err(AuthzError::NotAMember {
+                user_id: self.user_id.clone(),
+                tenant: tenant.clone(),
+            }),
+            Some(role) if role < required => Err(AuthzError::InsufficientRole {
+                user_id: self.user_id.clone(),
+                tenant: tenant.clone(),
+                role,
+                required,
+            }),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Checks that this principal holds `permission` in `tenant`.
+    pub fn require(&self, tenant: &TenantId, permission: Permission) -> Result<(), AuthzError> {
+        self.authorize(tenant, permission.required_role())
+    }
+}
+
+/// Reasons an authorization check can fail.
+///
+/// Serialized with a `kind` tag (`not_a_member`, `insufficient_role`, `system_only`) so API
+/// clients can tell the cases apart without parsing the message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuthzError {
+    /// The principal has no membership in the tenant.
+    NotAMember { user_id: String, tenant: TenantId },
+    /// The principal is a member, but its role is below the required level.
+    InsufficientRole {
+        user_id: String,
+        tenant: TenantId,
+        role: Role,
+        required: Role,
+    },
+    /// The operation is engine-global and reserved for system principals.
+    SystemOnly { user_id: String },
+}
+
+impl std::fmt::Display for AuthzError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthzError::NotAMember { user_id, tenant } => {
+                write!(
+                    f,
+                    "User '{}' is not a member of tenant '{}'",
+                    user_id, tenant
+                )
+            }
+            AuthzError::InsufficientRole {
+                user_id,
+                tenant,
+                role,
+                required,
+            } => write!(
+                f,
+                "User '{}' has role '{}' in tenant '{}' but '{}' is required",
+                user_id, role, tenant, required
+            ),
+            AuthzError::SystemOnly { user_id } => {
+                write!(f, "User '{}' attempted a system-only operation", user_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthzError {}
@@ -5,6 +5,15 @@ use sqlx::{Pool, Row, Sqlite};
 use std::str::FromStr;
 use uuid::Uuid;
 
+pub mod api_keys;
+pub mod authz;
+pub mod members;
+pub mod session;
+pub use api_keys::{ApiKey, NewApiKey};
+pub use authz::{AuthzError, Permission, Principal, Role};
+pub use members::{Invitation, Member};
+pub use session::{SessionError, SessionKeys, SessionTokens};
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TenantId(pub String);
 
@@ -35,6 +44,7 @@ impl From<String> for TenantId {
 #[derive(Clone, Debug)]
 pub struct IamStore {
     pool: Pool<Sqlite>,
+    session_keys: Option<SessionKeys>,
 }
 
 impl IamStore {
@@ -78,12 +88,46 @@ impl IamStore {
                 expires_at DATETIME NOT NULL,
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP
             );
+            CREATE TABLE IF NOT EXISTS api_keys (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                prefix TEXT NOT NULL,
+                key_hash TEXT UNIQUE NOT NULL, -- SHA-256 of the key; the key itself is never stored
+                role TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                created_at DATETIME NOT NULL,
+                expires_at DATETIME,
+                last_used_at DATETIME,
+                revoked_at DATETIME,
+                FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+            );
+            CREATE TABLE IF NOT EXISTS revoked_sessions (
+                jti TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                expires_at DATETIME NOT NULL, -- the row can be dropped once the token has expired
+                revoked_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE IF NOT EXISTS invitations (
+                token TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                email TEXT NOT NULL, -- lowercased
+                role TEXT NOT NULL,
+                invited_by TEXT NOT NULL,
+                expires_at DATETIME NOT NULL,
+                accepted_at DATETIME,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY(tenant_id) REFERENCES tenants(id)
+            );
             "#,
         )
         .execute(&pool)
         .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            session_keys: None,
+        })
     }
 
     pub async fn create_magic_link(&self, email: &str) -> Result<(String, String)> {
@@ -230,4 +274,35 @@ impl IamStore {
             .await?;
         Ok(row.is_some())
     }
+
+    pub async fn get_user_role(&self, user_id: &str, tenant_id: &str) -> Result<Option<Role>> {
+        let row = sqlx::query("SELECT role FROM user_tenants WHERE user_id = ? AND tenant_id = ?")
+            .bind(user_id)
+            .bind(tenant_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        match row {
+            Some(row) => {
+                let role: String = row.get("role");
+                Ok(Some(role.parse()?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Loads every tenant membership of a user into a `Principal` for authorization.
+    pub async fn resolve_principal(&self, user_id: &str) -> Result<Principal> {
+        let rows = sqlx::query("SELECT tenant_id, role FROM user_tenants WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut principal = Principal::new(user_id);
+        for row in rows {
+            let tenant_id: String = row.get("tenant_id");
+            let role: String = row.get("role");
+            principal = principal.with_membership(TenantId(tenant_id), role.parse()?);
+        }
+        Ok(principal)
+    }
 }
@@ -0,0 +1,379 @@
+//! Organizations and their members.
+//!
+//! Besides the personal workspace created at first sign-in, a user can create organization
+//! tenants and invite others by email. An invitation is a single-use token for one email
+//! address and role; accepting it (signed in as a user with that email) adds the membership.
+//! Member management requires `Permission::ManageMembers`, and a tenant always keeps at least
+//! one owner.
+
+use crate::{AuthzError, IamStore, Permission, Principal, Role, TenantId};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use uuid::Uuid;
+
+/// How long an invitation can be accepted.
+pub const INVITATION_TTL: Duration = Duration::days(7);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Invitation {
+    pub token: String,
+    pub tenant_id: TenantId,
+    pub email: String,
+    pub role: Role,
+    /// User id of the inviter.
+    pub invited_by: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub user_id: String,
+    pub email: String,
+    pub role: Role,
+}
+
+impl IamStore {
+    /// Creates an organization tenant owned by `owner_id` and returns its id.
+    pub async fn create_organization(&self, owner_id: &str, name: &str) -> Result<TenantId> {
+        if name.trim().is_empty() {
+            anyhow::bail!("Organization name must not be empty");
+        }
+        let tenant_id = Uuid::new_v4().to_string();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO tenants (id, name, type) VALUES (?, ?, 'organization')")
+            .bind(&tenant_id)
+            .bind(name.trim())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO user_tenants (user_id, tenant_id, role) VALUES (?, ?, 'owner')")
+            .bind(owner_id)
+            .bind(&tenant_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        tracing::info!(tenant = %tenant_id, owner = %owner_id, "Organization created");
+        Ok(TenantId(tenant_id))
+    }
+
+    /// Invites `email` to join `tenant` as `role`. The inviter can't hand out a role above
+    /// their own, and personal workspaces can't be shared.
+    pub async fn invite_member(
+        &self,
+        inviter: &Principal,
+        tenant: &TenantId,
+        email: &str,
+        role: Role,
+    ) -> Result<Invitation> {
+        inviter.require(tenant, Permission::ManageMembers)?;
+        inviter.authorize(tenant, role)?;
+        let email = email.trim().to_lowercase();
+        if !email.contains('@') {
+            anyhow::bail!("Invalid email address '{}'", email);
+        }
+        self.ensure_organization(tenant).await?;
+
+        let invitation = Invitation {
+            token: Uuid::new_v4().to_string(),
+            tenant_id: tenant.clone(),
+            email,
+            role,
+            invited_by: inviter.user_id.clone(),
+            expires_at: Utc::now() + INVITATION_TTL,
+        };
+        sqlx::query(
+            "INSERT INTO invitations (token, tenant_id, email, role, invited_by, expires_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&invitation.token)
+        .bind(&tenant.0)
+        .bind(&invitation.email)
+        .bind(role.as_str())
+        .bind(&invitation.invited_by)
+        .bind(invitation.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!(tenant = %tenant, %role, "Member invited");
+        Ok(invitation)
+    }
+
+    /// Accepts an invitation as `user_id`, whose email must be the invited one. A user who is
+    /// already a member keeps their current role. Returns the tenant joined.
+    pub async fn accept_invite(&self, token: &str, user_id: &str) -> Result<TenantId> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "SELECT tenant_id, email, role, expires_at FROM invitations WHERE token = ? AND accepted_at IS NULL",
+        )
+        .bind(token)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Invitation not found or already used"))?;
+
+        let expires_at: DateTime<Utc> = row.get("expires_at");
+        if Utc::now() > expires_at {
+            anyhow::bail!("Invitation expired");
+        }
+        let invited: String = row.get("email");
+        let email: Option<String> = sqlx::query("SELECT email FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|r| r.get("email"));
+        if email.map(|e| e.to_lowercase()).as_deref() != Some(invited.as_str()) {
+            anyhow::bail!("Invitation was sent to a different email address");
+        }
+
+        let tenant_id: String = row.get("tenant_id");
+        let role: String = row.get("role");
+        sqlx::query(
+            "INSERT INTO user_tenants (user_id, tenant_id, role) VALUES (?, ?, ?) ON CONFLICT (user_id, tenant_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(&tenant_id)
+        .bind(&role)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE invitations SET accepted_at = ? WHERE token = ?")
+            .bind(Utc::now())
+            .bind(token)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        tracing::info!(tenant = %tenant_id, user_id = %user_id, "Invitation accepted");
+        Ok(TenantId(tenant_id))
+    }
+
+    /// Removes a member. Members may also remove themselves (leave the organization).
+    pub async fn remove_member(
+        &self,
+        actor: &Principal,
+        tenant: &TenantId,
+        user_id: &str,
+    ) -> Result<()> {
+        if actor.user_id != user_id {
+            actor.require(tenant, Permission::ManageMembers)?;
+        }
+        self.ensure_organization(tenant).await?;
+
+        let mut tx = self.pool.begin().await?;
+        Self::ensure_other_owner(&mut tx, tenant, user_id).await?;
+        let removed = sqlx::query("DELETE FROM user_tenants WHERE user_id = ? AND tenant_id = ?")
+            .bind(user_id)
+            .bind(&tenant.0)
+            .execute(&mut *tx)
+            .await?;
+        if removed.rows_affected() == 0 {
+            return Err(AuthzError::NotAMember {
+                user_id: user_id.to_string(),
+                tenant: tenant.clone(),
+            }
+            .into());
+        }
+        tx.commit().await?;
+
+        tracing::info!(tenant = %tenant, user_id = %user_id, "Member removed");
+        Ok(())
+    }
+
+    /// Changes a member's role.
+    pub async fn change_role(
+        &self,
+        actor: &Principal,
+        tenant: &TenantId,
+        user_id: &str,
+        role: Role,
+    ) -> Result<()> {
+        actor.require(tenant, Permission::ManageMembers)?;
+
+        let mut tx = self.pool.begin().await?;
+        if role != Role::Owner {
+            Self::ensure_other_owner(&mut tx, tenant, user_id).await?;
+        }
+        let updated =
+            sqlx::query("UPDATE user_tenants SET role = ? WHERE user_id = ? AND tenant_id = ?")
+                .bind(role.as_str())
+                .bind(user_id)
+                .bind(&tenant.0)
+                .execute(&mut *tx)
+                .await?;
+        if updated.rows_affected() == 0 {
+            return Err(AuthzError::NotAMember {
+                user_id: user_id.to_string(),
+                tenant: tenant.clone(),
+            }
+            .into());
+        }
+        tx.commit().await?;
+
+        tracing::info!(tenant = %tenant, user_id = %user_id, %role, "Member role changed");
+        Ok(())
+    }
+
+    /// The tenant's members, by email.
+    pub async fn list_members(&self, tenant: &TenantId) -> Result<Vec<Member>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT u.id, u.email, ut.role
+            FROM user_tenants ut
+            JOIN users u ON u.id = ut.user_id
+            WHERE ut.tenant_id = ?
+            ORDER BY u.email COLLATE NOCASE
+            "#,
+        )
+        .bind(&tenant.0)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                let role: String = row.get("role");
+                Ok(Member {
+                    user_id: row.get("id"),
+                    email: row.get("email"),
+                    role: role.parse()?,
+                })
+            })
+            .collect()
+    }
+
+    async fn ensure_organization(&self, tenant: &TenantId) -> Result<()> {
+        let kind: Option<String> = sqlx::query("SELECT type FROM tenants WHERE id = ?")
+            .bind(&tenant.0)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|row| row.get("type"));
+        match kind.as_deref() {
+            Some("organization") => Ok(()),
+            Some(_) => anyhow::bail!("Members of a personal workspace can't be changed"),
+            None => anyhow::bail!("Tenant '{}' not found", tenant),
+        }
+    }
+
+    /// Fails if `user_id` is the tenant's only owner.
+    async fn ensure_other_owner(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        tenant: &TenantId,
+        user_id: &str,
+    ) -> Result<()> {
+        let others: i64 = sqlx::query(
+            "SELECT COUNT(*) AS owners FROM user_tenants WHERE tenant_id = ? AND role = 'owner' AND user_id != ?",
+        )
+        .bind(&tenant.0)
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?
+        .get("owners");
+        let is_owner = sqlx::query(
+            "SELECT 1 FROM user_tenants WHERE tenant_id = ? AND user_id = ? AND role = 'owner'",
+        )
+        .bind(&tenant.0)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .is_some();
+        if is_owner && others == 0 {
+            anyhow::bail!("Tenant '{}' must keep at least one owner", tenant);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn store() -> IamStore {
+        let path = std::env::temp_dir().join(format!("iam_{}.db", Uuid::new_v4()));
+        let iam = IamStore::new(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap();
+        for (id, email) in [("ada", "ada@example.com"), ("bob", "Bob@Example.com")] {
+            sqlx::query("INSERT INTO users (id, email) VALUES (?, ?)")
+                .bind(id)
+                .bind(email)
+                .execute(&iam.pool)
+                .await
+                .unwrap();
+        }
+        iam
+    }
+
+    #[tokio::test]
+    async fn test_invitation_flow() {
+        let iam = store().await;
+        let org = iam.create_organization("ada", "Acme").await.unwrap();
+        let ada = iam.resolve_principal("ada").await.unwrap();
+        assert_eq!(ada.role_in(&org), Some(Role::Owner));
+
+        let invitation = iam
+            .invite_member(&ada, &org, "bob@example.com", Role::Editor)
+            .await
+            .unwrap();
+        // Only the invited address can accept, and only once.
+        assert!(iam.accept_invite(&invitation.token, "ada").await.is_err());
+        assert_eq!(
+            iam.accept_invite(&invitation.token, "bob").await.unwrap(),
+            org
+        );
+        assert!(iam.accept_invite(&invitation.token, "bob").await.is_err());
+
+        let bob = iam.resolve_principal("bob").await.unwrap();
+        assert_eq!(bob.role_in(&org), Some(Role::Editor));
+        assert!(
+            iam.invite_member(&bob, &org, "eve@example.com", Role::Viewer)
+                .await
+                .is_err()
+        );
+
+        let members: Vec<_> = iam
+            .list_members(&org)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.user_id, m.role))
+            .collect();
+        assert_eq!(
+            members,
+            [
+                ("ada".to_string(), Role::Owner),
+                ("bob".to_string(), Role::Editor)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenants_keep_an_owner() {
+        let iam = store().await;
+        let org = iam.create_organization("ada", "Acme").await.unwrap();
+        let ada = iam.resolve_principal("ada").await.unwrap();
+        let invitation = iam
+            .invite_member(&ada, &org, "bob@example.com", Role::Viewer)
+            .await
+            .unwrap();
+        iam.accept_invite(&invitation.token, "bob").await.unwrap();
+
+        assert!(
+            iam.change_role(&ada, &org, "ada", Role::Admin)
+                .await
+                .is_err()
+        );
+        assert!(iam.remove_member(&ada, &org, "ada").await.is_err());
+
+        iam.change_role(&ada, &org, "bob", Role::Owner)
+            .await
+            .unwrap();
+        iam.remove_member(&ada, &org, "ada").await.unwrap();
+        let bob = iam.resolve_principal("bob").await.unwrap();
+        assert!(matches!(
+            iam.remove_member(&bob, &org, "ada")
+                .await
+                .unwrap_err()
+                .downcast::<AuthzError>(),
+            Ok(AuthzError::NotAMember { .. })
+        ));
+        assert_eq!(iam.list_members(&org).await.unwrap().len(), 1);
+    }
+}
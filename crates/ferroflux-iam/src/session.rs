@@ -0,0 +1,470 @@
+//! Signed session tokens (JWTs) issued after a magic link sign-in.
+//!
+//! A session is a pair of tokens: a short-lived access token carrying the user's tenant
+//! memberships, so it can be turned into a [`Principal`] without a database round trip, and a
+//! long-lived refresh token exchanged for a new pair with up-to-date memberships. Refreshing
+//! retires the old refresh token; revoked token ids (`jti`) are kept in `revoked_sessions`
+//! until the token would have expired anyway.
+//!
+//! Tokens are signed with HS256 (a shared secret) or EdDSA (Ed25519), depending on the
+//! [`SessionKeys`] the store was given.
+
+use crate::{IamStore, Principal, Role, TenantId};
+use anyhow::{Context, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Duration, Utc};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Lifetime of an access token.
+pub const ACCESS_TOKEN_TTL: Duration = Duration::minutes(15);
+/// Lifetime of a refresh token.
+pub const REFRESH_TOKEN_TTL: Duration = Duration::days(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionAlgorithm {
+    #[serde(rename = "HS256")]
+    Hs256,
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+#[derive(Clone)]
+enum KeyMaterial {
+    Hs256(ring::hmac::Key),
+    EdDsa {
+        /// `None` for a verify-only key.
+        signer: Option<Arc<Ed25519KeyPair>>,
+        public_key: Vec<u8>,
+    },
+}
+
+/// The keys sessions are signed and verified with.
+#[derive(Clone)]
+pub struct SessionKeys(KeyMaterial);
+
+impl SessionKeys {
+    /// HS256 with a shared secret of at least 32 bytes.
+    pub fn hs256(secret: &[u8]) -> Result<Self> {
+        if secret.len() < 32 {
+            anyhow::bail!("HS256 session secret must be at least 32 bytes");
+        }
+        Ok(Self(KeyMaterial::Hs256(ring::hmac::Key::new(
+            ring::hmac::HMAC_SHA256,
+            secret,
+        ))))
+    }
+
+    /// EdDSA with an Ed25519 key pair in PKCS#8 form.
+    pub fn ed25519(pkcs8: &[u8]) -> Result<Self> {
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|e| anyhow::anyhow!("Invalid Ed25519 key: {}", e))?;
+        let public_key = pair.public_key().as_ref().to_vec();
+        Ok(Self(KeyMaterial::EdDsa {
+            signer: Some(Arc::new(pair)),
+            public_key,
+        }))
+    }
+
+    /// Verifies EdDSA sessions issued elsewhere; issuing with it fails.
+    pub fn ed25519_verifier(public_key: &[u8]) -> Self {
+        Self(KeyMaterial::EdDsa {
+            signer: None,
+            public_key: public_key.to_vec(),
+        })
+    }
+
+    /// A new Ed25519 key pair as PKCS#8, for [`ed25519`](Self::ed25519).
+    pub fn generate_ed25519() -> Result<Vec<u8>> {
+        let document = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate Ed25519 key"))?;
+        Ok(document.as_ref().to_vec())
+    }
+
+    /// The public half of an EdDSA key, for verify-only services.
+    pub fn public_key(&self) -> Option<&[u8]> {
+        match &self.0 {
+            KeyMaterial::Hs256(_) => None,
+            KeyMaterial::EdDsa { public_key, .. } => Some(public_key),
+        }
+    }
+
+    pub fn algorithm(&self) -> SessionAlgorithm {
+        match self.0 {
+            KeyMaterial::Hs256(_) => SessionAlgorithm::Hs256,
+            KeyMaterial::EdDsa { .. } => SessionAlgorithm::EdDsa,
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        match &self.0 {
+            KeyMaterial::Hs256(key) => Ok(ring::hmac::sign(key, message).as_ref().to_vec()),
+            KeyMaterial::EdDsa {
+                signer: Some(pair), ..
+            } => Ok(pair.sign(message).as_ref().to_vec()),
+            KeyMaterial::EdDsa { signer: None, .. } => {
+                anyhow::bail!("Session keys are verify-only")
+            }
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match &self.0 {
+            KeyMaterial::Hs256(key) => ring::hmac::verify(key, message, signature).is_ok(),
+            KeyMaterial::EdDsa { public_key, .. } => UnparsedPublicKey::new(&ED25519, public_key)
+                .verify(message, signature)
+                .is_ok(),
+        }
+    }
+}
+
+impl std::fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SessionKeys({:?})", self.algorithm())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+/// The payload of a session token.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionClaims {
+    /// User id.
+    pub sub: String,
+    /// Token id, used for revocation.
+    pub jti: String,
+    /// Issued at, seconds since the epoch.
+    pub iat: i64,
+    /// Expires at, seconds since the epoch.
+    pub exp: i64,
+    pub typ: TokenType,
+    /// Tenant memberships at the time the token was issued.
+    #[serde(default)]
+    pub memberships: HashMap<TenantId, Role>,
+}
+
+impl SessionClaims {
+    pub fn principal(&self) -> Principal {
+        Principal {
+            user_id: self.sub.clone(),
+            memberships: self.memberships.clone(),
+            is_system: false,
+        }
+    }
+
+    fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp, 0).unwrap_or_default()
+    }
+}
+
+/// An access/refresh token pair.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub access_expires_at: DateTime<Utc>,
+    pub refresh_expires_at: DateTime<Utc>,
+}
+
+/// Why a session token was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    /// Not a well-formed token, or signed with another algorithm.
+    Malformed,
+    /// The signature does not match.
+    BadSignature,
+    Expired,
+    Revoked,
+    /// A refresh token was presented as an access token or vice versa.
+    WrongType,
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SessionError::Malformed => "Malformed session token",
+            SessionError::BadSignature => "Invalid session token signature",
+            SessionError::Expired => "Session token expired",
+            SessionError::Revoked => "Session token revoked",
+            SessionError::WrongType => "Wrong session token type",
+        })
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    alg: SessionAlgorithm,
+    typ: String,
+}
+
+fn encode(keys: &SessionKeys, claims: &SessionClaims) -> Result<String> {
+    let header = Header {
+        alg: keys.algorithm(),
+        typ: "JWT".to_string(),
+    };
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?)
+    );
+    let signature = keys.sign(signing_input.as_bytes())?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Checks the signature and expiry of `token` and returns its claims.
+pub fn decode(keys: &SessionKeys, token: &str) -> Result<SessionClaims, SessionError> {
+    let (signing_input, signature) = token.rsplit_once('.').ok_or(SessionError::Malformed)?;
+    let (header, claims) = signing_input
+        .split_once('.')
+        .ok_or(SessionError::Malformed)?;
+    let part = |text: &str| {
+        URL_SAFE_NO_PAD
+            .decode(text)
+            .map_err(|_| SessionError::Malformed)
+    };
+
+    let header: Header =
+        serde_json::from_slice(&part(header)?).map_err(|_| SessionError::Malformed)?;
+    // Only the configured algorithm is accepted, whatever the token claims.
+    if header.alg != keys.algorithm() {
+        return Err(SessionError::Malformed);
+    }
+    if !keys.verify(signing_input.as_bytes(), &part(signature)?) {
+        return Err(SessionError::BadSignature);
+    }
+    let claims: SessionClaims =
+        serde_json::from_slice(&part(claims)?).map_err(|_| SessionError::Malformed)?;
+    if claims.exp <= Utc::now().timestamp() {
+        return Err(SessionError::Expired);
+    }
+    Ok(claims)
+}
+
+impl IamStore {
+    /// Signs sessions with `keys`; required by the session methods.
+    pub fn with_session_keys(mut self, keys: SessionKeys) -> Self {
+        self.session_keys = Some(keys);
+        self
+    }
+
+    fn session_keys(&self) -> Result<&SessionKeys> {
+        self.session_keys
+            .as_ref()
+            .context("IamStore has no session keys")
+    }
+
+    /// Issues a session for `user_id`, typically right after `verify_magic_link_token`.
+    pub async fn issue_session(&self, user_id: &str) -> Result<SessionTokens> {
+        let keys = self.session_keys()?;
+        let principal = self.resolve_principal(user_id).await?;
+        let now = Utc::now();
+        let claims = |typ, ttl: Duration| SessionClaims {
+            sub: principal.user_id.clone(),
+            jti: Uuid::new_v4().to_string(),
+            iat: now.timestamp(),
+            exp: (now + ttl).timestamp(),
+            typ,
+            memberships: principal.memberships.clone(),
+        };
+        let access = claims(TokenType::Access, ACCESS_TOKEN_TTL);
+        let refresh = claims(TokenType::Refresh, REFRESH_TOKEN_TTL);
+
+        Ok(SessionTokens {
+            access_token: encode(keys, &access)?,
+            refresh_token: encode(keys, &refresh)?,
+            access_expires_at: access.expires_at(),
+            refresh_expires_at: refresh.expires_at(),
+        })
+    }
+
+    /// Verifies an access token and returns the principal it was issued to.
+    ///
+    /// Fails with a [`SessionError`] for tokens that are invalid, expired or revoked.
+    pub async fn verify_session(&self, token: &str) -> Result<Principal> {
+        let claims = self.verified_claims(token, TokenType::Access).await?;
+        Ok(claims.principal())
+    }
+
+    /// Exchanges a refresh token for a new session with current memberships. The presented
+    /// refresh token is revoked, so it can only be used once.
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<SessionTokens> {
+        let claims = self
+            .verified_claims(refresh_token, TokenType::Refresh)
+            .await?;
+        if !self.revoke_claims(&claims).await? {
+            // Another refresh with the same token won the race.
+            return Err(SessionError::Revoked.into());
+        }
+        self.issue_session(&claims.sub).await
+    }
+
+    /// Revokes an access or refresh token until it expires. Expired tokens need no
+    /// revocation and are accepted silently.
+    pub async fn revoke_session(&self, token: &str) -> Result<()> {
+        match decode(self.session_keys()?, token) {
+            Ok(claims) => {
+                self.revoke_claims(&claims).await?;
+            }
+            Err(SessionError::Expired) => {}
+            Err(e) => return Err(e.into()),
+        }
+        // Rows for tokens that have expired since are no longer needed.
+        sqlx::query("DELETE FROM revoked_sessions WHERE expires_at <= ?")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn verified_claims(&self, token: &str, typ: TokenType) -> Result<SessionClaims> {
+        let claims = decode(self.session_keys()?, token)?;
+        if claims.typ != typ {
+            return Err(SessionError::WrongType.into());
+        }
+        let revoked = sqlx::query("SELECT 1 FROM revoked_sessions WHERE jti = ?")
+            .bind(&claims.jti)
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+        if revoked {
+            return Err(SessionError::Revoked.into());
+        }
+        Ok(claims)
+    }
+
+    /// Returns false if the token was already revoked.
+    async fn revoke_claims(&self, claims: &SessionClaims) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO revoked_sessions (jti, user_id, expires_at) VALUES (?, ?, ?)",
+        )
+        .bind(&claims.jti)
+        .bind(&claims.sub)
+        .bind(claims.expires_at())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn store(keys: SessionKeys) -> IamStore {
+        let path = std::env::temp_dir().join(format!("iam_{}.db", Uuid::new_v4()));
+        let iam = IamStore::new(&format!("sqlite://{}", path.display()))
+            .await
+            .unwrap()
+            .with_session_keys(keys);
+        sqlx::query("INSERT INTO tenants (id, name, type) VALUES ('acme', 'Acme', 'organization')")
+            .execute(&iam.pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO users (id, email) VALUES ('u1', 'ada@example.com')")
+            .execute(&iam.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_tenants (user_id, tenant_id, role) VALUES ('u1', 'acme', 'editor')",
+        )
+        .execute(&iam.pool)
+        .await
+        .unwrap();
+        iam
+    }
+
+    fn session_error(error: anyhow::Error) -> SessionError {
+        error.downcast().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_carries_memberships_and_refreshes_once() {
+        let iam = store(SessionKeys::hs256(&[7; 32]).unwrap()).await;
+        let acme = TenantId::from("acme");
+
+        let session = iam.issue_session("u1").await.unwrap();
+        let principal = iam.verify_session(&session.access_token).await.unwrap();
+        assert_eq!(principal.user_id, "u1");
+        assert_eq!(principal.role_in(&acme), Some(Role::Editor));
+        assert_eq!(
+            session_error(
+                iam.verify_session(&session.refresh_token)
+                    .await
+                    .unwrap_err()
+            ),
+            SessionError::WrongType
+        );
+
+        // Refreshing picks up the promotion and retires the refresh token.
+        sqlx::query("UPDATE user_tenants SET role = 'admin' WHERE user_id = 'u1'")
+            .execute(&iam.pool)
+            .await
+            .unwrap();
+        let refreshed = iam.refresh_session(&session.refresh_token).await.unwrap();
+        let principal = iam.verify_session(&refreshed.access_token).await.unwrap();
+        assert_eq!(principal.role_in(&acme), Some(Role::Admin));
+        assert_eq!(
+            session_error(
+                iam.refresh_session(&session.refresh_token)
+                    .await
+                    .unwrap_err()
+            ),
+            SessionError::Revoked
+        );
+
+        iam.revoke_session(&refreshed.access_token).await.unwrap();
+        assert_eq!(
+            session_error(
+                iam.verify_session(&refreshed.access_token)
+                    .await
+                    .unwrap_err()
+            ),
+            SessionError::Revoked
+        );
+    }
+
+    #[tokio::test]
+    async fn test_eddsa_tokens_verify_with_the_public_key_only() {
+        let keys = SessionKeys::ed25519(&SessionKeys::generate_ed25519().unwrap()).unwrap();
+        let verifier = SessionKeys::ed25519_verifier(keys.public_key().unwrap());
+        let iam = store(keys).await;
+        let session = iam.issue_session("u1").await.unwrap();
+
+        let claims = decode(&verifier, &session.access_token).unwrap();
+        assert_eq!(claims.sub, "u1");
+
+        // A token signed with a different key, or re-labelled as HS256, is rejected.
+        let other = SessionKeys::ed25519(&SessionKeys::generate_ed25519().unwrap()).unwrap();
+        assert_eq!(
+            decode(&other, &session.access_token),
+            Err(SessionError::BadSignature)
+        );
+        let hs256 = SessionKeys::hs256(&[7; 32]).unwrap();
+        assert_eq!(
+            decode(&hs256, &session.access_token),
+            Err(SessionError::Malformed)
+        );
+
+        let expired = SessionClaims {
+            exp: Utc::now().timestamp() - 1,
+            ..claims
+        };
+        let token = encode(&SessionKeys::hs256(&[7; 32]).unwrap(), &expired).unwrap();
+        assert_eq!(decode(&hs256, &token), Err(SessionError::Expired));
+    }
+}
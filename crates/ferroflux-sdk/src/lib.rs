@@ -1,26 +1,119 @@
+pub mod stepping;
+pub mod subscription;
+pub mod traffic;
+pub mod trail;
+
+use crate::stepping::{IDLE_TICKS, StepSummary};
+use crate::subscription::EventFilter;
+use crate::traffic::{EdgeVolume, TrafficRecorder};
+use crate::trail::{TraceTrail, TrailRecorder};
 use anyhow::Result;
 use bevy_ecs::prelude::*;
 use ferroflux_core::api::events::SystemEvent;
 use ferroflux_core::app::App;
 use ferroflux_core::app::AppBuilder;
 use ferroflux_core::components::core::{Edge, NodeConfig};
-use flow_canvas::model::{GraphState, NodeData};
+use ferroflux_core::graph_analysis::{AnalysisEdge, AnalysisNode, GraphWarning};
+use ferroflux_core::graph_loader::{self, ImportOptions, ImportedWorkflow, WorkflowBundle};
+use ferroflux_core::graph_validation::{GraphDiagnostic, GraphValidator, Severity, ValidationEdge};
+use ferroflux_core::resources::WorkDone;
+use ferroflux_core::resources::prompts::PromptLibrary;
+use ferroflux_core::schedule_calendar::{ScheduledRun, upcoming_runs};
+use ferroflux_core::store::database::{PersistentStore, PromptTemplate, WorkflowVersion};
+use ferroflux_core::traits::node_factory::{NodeCatalog, NodeUsage};
+use ferroflux_iam::TenantId;
+use flow_canvas::model::{ConnectionId, GraphState, NodeData, NodeId};
+use flow_canvas::persistence::SavedGraph;
+use futures::Stream;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{Mutex, broadcast};
 
+/// A graph analysis warning mapped back to a canvas node.
+#[derive(Debug, Clone)]
+pub struct CanvasWarning {
+    pub node: NodeId,
+    pub warning: GraphWarning,
+}
+
+/// A graph validation diagnostic mapped back to the canvas nodes it concerns.
+#[derive(Debug, Clone)]
+pub struct CanvasDiagnostic {
+    pub nodes: Vec<NodeId>,
+    pub diagnostic: GraphDiagnostic,
+}
+
+/// A canvas node deployed to the engine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeployedNode {
+    pub node: NodeId,
+    pub uuid: uuid::Uuid,
+    pub entity: Entity,
+}
+
+/// A canvas node that was not deployed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedNode {
+    pub node: NodeId,
+    pub reason: String,
+}
+
+/// What [`FerroFluxClient::compile_and_deploy`] did, for reconciling the canvas with the
+/// engine and showing deployment feedback.
+#[derive(Debug, Clone, Default)]
+pub struct DeployReport {
+    /// Every deployed canvas node with its engine UUID and entity.
+    pub nodes: Vec<DeployedNode>,
+    /// Node entities created for nodes the engine didn't have yet.
+    pub spawned: usize,
+    /// Node entities kept and updated in place.
+    pub updated: usize,
+    /// Node entities whose node is no longer on the canvas (or changed type).
+    pub removed: usize,
+    /// Connections deployed as edges.
+    pub edges: usize,
+    pub skipped: Vec<SkippedNode>,
+    /// Validation warnings. Graphs with validation errors are not deployed.
+    pub diagnostics: Vec<CanvasDiagnostic>,
+    /// Pre-deploy analysis warnings.
+    pub warnings: Vec<CanvasWarning>,
+}
+
+impl DeployReport {
+    /// The entity a canvas node was deployed as.
+    pub fn entity(&self, node: NodeId) -> Option<Entity> {
+        self.nodes.iter().find(|d| d.node == node).map(|d| d.entity)
+    }
+
+    /// The canvas node deployed under an engine UUID.
+    pub fn node(&self, uuid: uuid::Uuid) -> Option<NodeId> {
+        self.nodes.iter().find(|d| d.uuid == uuid).map(|d| d.node)
+    }
+}
+
 /// The SDK Client for interacting with the FerroFlux Engine.
 ///
 /// This client manages the lifecycle of the engine, graph deployment,
 /// and event synchronization, serving as the primary interface for
 /// Desktop, Web, and CLI applications.
+///
+/// The engine either runs in-process ([`init`](Self::init), [`new`](Self::new)) or on a
+/// headless server ([`connect`](Self::connect)). Commands and events work the same way in
+/// both cases; methods that read the engine's world or database directly need an embedded
+/// engine and fail on a remote one.
 pub struct FerroFluxClient<T: NodeData> {
-    /// Handle to the underlying FerroFlux engine.
-    pub engine: Arc<Mutex<App>>,
+    /// Handle to the underlying FerroFlux engine; `None` when connected to a remote one.
+    pub engine: Option<Arc<Mutex<App>>>,
     /// Channel for sending commands to the engine.
-    api_tx: async_channel::Sender<ferroflux_core::api::ApiCommand>,
+    api_tx: async_channel::Sender<ferroflux_core::api::ApiRequest>,
     /// Subscriber to the engine's event bus.
     event_rx: broadcast::Receiver<SystemEvent>,
+    /// Paths taken by recent traces, built from the event stream.
+    trails: TrailRecorder,
+    /// Message and byte totals per edge, built from the event stream.
+    traffic: TrafficRecorder,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -45,52 +138,173 @@ impl<T: NodeData> FerroFluxClient<T> {
     /// Creates a new SDK client for a given engine instance.
     pub fn new(
         engine: App,
-        api_tx: async_channel::Sender<ferroflux_core::api::ApiCommand>,
+        api_tx: async_channel::Sender<ferroflux_core::api::ApiRequest>,
         event_bus: broadcast::Sender<SystemEvent>,
     ) -> Self {
         Self {
-            engine: Arc::new(Mutex::new(engine)),
+            engine: Some(Arc::new(Mutex::new(engine))),
             api_tx,
             event_rx: event_bus.subscribe(),
+            trails: TrailRecorder::default(),
+            traffic: TrafficRecorder::default(),
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Connects to an engine served by `ferroflux_core::api::remote::RemoteServer`, e.g.
+    /// `ws://engine.internal:7070`, authenticating with an API key or session access token.
+    ///
+    /// Commands run as the principal the token belongs to, and only the events of its
+    /// tenants are streamed back.
+    pub async fn connect(url: &str, token: &str) -> Result<Self> {
+        let (api_tx, event_bus) = ferroflux_core::api::remote::connect(url, token).await?;
+        Ok(Self {
+            engine: None,
+            api_tx,
+            event_rx: event_bus.subscribe(),
+            trails: TrailRecorder::default(),
+            traffic: TrafficRecorder::default(),
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    /// The in-process engine, for methods that can't go through commands.
+    fn embedded(&self) -> Result<&Arc<Mutex<App>>> {
+        self.engine
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Not available when connected to a remote engine"))
+    }
+
+    /// Waits for the answer to a command sent with a reply slot. An embedded engine is ticked
+    /// once to process it; a remote one answers over the connection.
+    async fn answer<R>(
+        &self,
+        mut rx: tokio::sync::oneshot::Receiver<R>,
+        command: &str,
+    ) -> Result<R> {
+        match &self.engine {
+            Some(engine) => {
+                engine.lock().await.update();
+                rx.try_recv().ok()
+            }
+            None => rx.await.ok(),
+        }
+        .ok_or_else(|| anyhow::anyhow!("{} was not answered", command))
+    }
+
     /// Compiles and deploys the current Canvas state to the Engine.
     ///
     /// This process "lowers" the high-level visual graph into a set of optimized
     /// ECS entities and components ready for execution. It strips away layout
     /// information (position, size) as the engine operates purely on logic.
-    pub async fn compile_and_deploy(&mut self, graph: &GraphState<T>) -> Result<()> {
-        let mut engine = self.engine.lock().await;
+    ///
+    /// The graph is validated first (see [`validate_graph`](Self::validate_graph)); a graph with
+    /// validation errors is not deployed.
+    ///
+    /// Nodes the engine already runs keep their entity as long as their type is unchanged;
+    /// notes and nodes whose UUID repeats another's are skipped.
+    pub async fn compile_and_deploy(&mut self, graph: &GraphState<T>) -> Result<DeployReport> {
+        let mut engine = self.embedded()?.lock().await;
         let world = &mut engine.world;
 
-        // 1. Clear existing nodes/edges from the engine (simplified for V1)
-        // In a real app, we might want incremental updates.
-        let mut query = world.query_filtered::<Entity, With<NodeConfig>>();
-        let entities: Vec<Entity> = query.iter(world).collect();
-        for entity in entities {
+        let diagnostics = validate_canvas(world, graph);
+        for d in &diagnostics {
+            match d.diagnostic.severity {
+                Severity::Error => tracing::error!(nodes = ?d.nodes, "{}", d.diagnostic.message),
+                Severity::Warning => tracing::warn!(nodes = ?d.nodes, "{}", d.diagnostic.message),
+            }
+        }
+        let errors: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.diagnostic.severity == Severity::Error)
+            .map(|d| d.diagnostic.message.as_str())
+            .collect();
+        if !errors.is_empty() {
+            anyhow::bail!("Graph failed validation: {}", errors.join("; "));
+        }
+
+        let warnings = analyze_canvas(world, graph);
+        for w in &warnings {
+            tracing::warn!(node = ?w.node, "{}", w.warning.message);
+        }
+        let mut report = DeployReport {
+            diagnostics,
+            warnings,
+            ..Default::default()
+        };
+
+        // 1. Edges point at entities; they are rebuilt from the canvas below.
+        let mut query = world.query_filtered::<Entity, With<Edge>>();
+        let edges: Vec<Entity> = query.iter(world).collect();
+        for entity in edges {
             world.despawn(entity);
         }
 
+        let mut existing: HashMap<uuid::Uuid, (Entity, String)> = world
+            .query::<(Entity, &NodeConfig)>()
+            .iter(world)
+            .map(|(entity, config)| (config.id, (entity, config.node_type.clone())))
+            .collect();
         let mut canvas_to_entity = HashMap::new();
+        let mut deployed = std::collections::HashSet::new();
 
-        // 2. Spawn Nodes
+        // 2. Spawn or update nodes
         for (id, node) in &graph.nodes {
-            let entity = world
-                .spawn(NodeConfig {
-                    id: node.uuid,
-                    name: format!("{:?}", node.id), // Placeholder name
-                    node_type: node.data.node_type(), // This now comes from node.data
-                    workflow_id: None,
-                    tenant_id: None,
-                })
-                .id();
+            let node_type = node.data.node_type();
+            if ferroflux_core::nodes::is_annotation_type(&node_type) {
+                report.skipped.push(SkippedNode {
+                    node: id,
+                    reason: "annotations are not executed".to_string(),
+                });
+                continue;
+            }
+            if !deployed.insert(node.uuid) {
+                report.skipped.push(SkippedNode {
+                    node: id,
+                    reason: format!("UUID {} is already used by another node", node.uuid),
+                });
+                continue;
+            }
+
+            let config = NodeConfig {
+                id: node.uuid,
+                name: format!("{:?}", node.id), // Placeholder name
+                node_type: node_type.clone(),
+                workflow_id: None,
+                tenant_id: None,
+                version: None,
+            };
+            let entity = match existing.remove(&node.uuid) {
+                Some((entity, previous_type)) if previous_type == node_type => {
+                    world.entity_mut(entity).insert(config);
+                    report.updated += 1;
+                    entity
+                }
+                previous => {
+                    if let Some((entity, _)) = previous {
+                        world.despawn(entity);
+                        report.removed += 1;
+                    }
+                    report.spawned += 1;
+                    world.spawn(config).id()
+                }
+            };
 
             canvas_to_entity.insert(id, entity);
+            report.nodes.push(DeployedNode {
+                node: id,
+                uuid: node.uuid,
+                entity,
+            });
+        }
+
+        // 3. Remove nodes that left the canvas
+        for (entity, _) in existing.into_values() {
+            world.despawn(entity);
+            report.removed += 1;
         }
 
-        // 3. Spawn Edges
+        // 4. Spawn Edges
         for (_, conn) in &graph.connections {
             let from_node_id = graph.ports.get(conn.from).map(|p| p.node);
             let to_node_id = graph.ports.get(conn.to).map(|p| p.node);
@@ -107,64 +321,558 @@ impl<T: NodeData> FerroFluxClient<T> {
                         source_handle: Some("Exec".to_string()),
                         target_handle: Some("Exec".to_string()),
                     });
+                    report.edges += 1;
                 }
             }
         }
 
-        Ok(())
+        tracing::info!(
+            spawned = report.spawned,
+            updated = report.updated,
+            removed = report.removed,
+            edges = report.edges,
+            skipped = report.skipped.len(),
+            "Canvas deployed"
+        );
+        Ok(report)
+    }
+
+    /// Checks a canvas graph for cycles, dangling connections, unknown ports, port type
+    /// mismatches and a missing trigger. `compile_and_deploy` refuses graphs with errors.
+    ///
+    /// A remote engine's node registry isn't available, so port checks are skipped there.
+    pub async fn validate_graph(&self, graph: &GraphState<T>) -> Vec<CanvasDiagnostic> {
+        match &self.engine {
+            Some(engine) => validate_canvas(&engine.lock().await.world, graph),
+            None => validate_canvas(&World::new(), graph),
+        }
+    }
+
+    /// Runs the pre-deploy analysis (unreachable nodes, dangling outputs) on a canvas graph.
+    pub async fn analyze_graph(&self, graph: &GraphState<T>) -> Vec<CanvasWarning> {
+        match &self.engine {
+            Some(engine) => analyze_canvas(&engine.lock().await.world, graph),
+            None => analyze_canvas(&World::new(), graph),
+        }
     }
 
     /// Processes pending events from the engine and updates the visual state.
     ///
-    /// This is where the visualization of execution flow happens.
+    /// Nodes that report telemetry are flagged `RUNNING` (or `ERROR` when they fail) and the
+    /// connections tickets cross get a pulse; `Canvas::update` fades both out.
     pub fn sync_events(&mut self, graph: &mut GraphState<T>) {
         while let Ok(event) = self.event_rx.try_recv() {
             match event {
                 SystemEvent::NodeTelemetry {
-                    node_id, success, ..
+                    trace_id,
+                    node_id,
+                    success,
+                    ..
                 } => {
-                    #[allow(clippy::collapsible_if)]
+                    self.trails.record_node(&trace_id, node_id, success);
                     if let Some(&canvas_id) = graph.uuid_index.get(&node_id) {
-                        if let Some(_node) = graph.nodes.get_mut(canvas_id) {
-                            // Here we could trigger a "Pulse" animation or change style
-                            // For now, let's just log it.
-                            tracing::info!(node_id = ?canvas_id, success, "Node execution visualization triggered");
+                        if success {
+                            graph.mark_node_running(canvas_id);
+                        } else {
+                            graph.mark_node_failed(canvas_id);
                         }
                     }
                 }
+                SystemEvent::NodeError {
+                    trace_id, node_id, ..
+                } => {
+                    self.trails.record_node(&trace_id, node_id, false);
+                    if let Some(&canvas_id) = graph.uuid_index.get(&node_id) {
+                        graph.mark_node_failed(canvas_id);
+                    }
+                }
                 SystemEvent::EdgeTraversal {
+                    trace_id,
                     source_id,
                     target_id,
+                    bytes,
                     ..
                 } => {
-                    // We could find the connection between these nodes and animate the transition
-                    tracing::info!(from = ?source_id, to = ?target_id, "Edge traversal visualization triggered");
+                    self.trails.record_edge(&trace_id, source_id, target_id);
+                    self.traffic.record(source_id, target_id, bytes);
+                    let endpoint = |port| {
+                        let port = graph.ports.get(port)?;
+                        graph.nodes.get(port.node).map(|n| n.uuid)
+                    };
+                    let crossed: Vec<ConnectionId> = graph
+                        .connections
+                        .iter()
+                        .filter(|(_, conn)| {
+                            endpoint(conn.from) == Some(source_id)
+                                && endpoint(conn.to) == Some(target_id)
+                        })
+                        .map(|(id, _)| id)
+                        .collect();
+                    for id in crossed {
+                        graph.pulse_connection(id);
+                    }
                 }
                 _ => {}
             }
         }
     }
 
-    /// Runs one tick of the backend engine.
+    /// Streams the engine events that pass `filter`, independently of
+    /// [`sync_events`](Self::sync_events) and of other subscriptions. Events are produced while
+    /// the engine is ticked; a subscriber that falls too far behind misses the oldest ones.
+    ///
+    /// The owner of a node isn't known on a remote engine, so there a tenant filter only
+    /// passes events that name their tenant.
+    pub fn subscribe(
+        &self,
+        filter: EventFilter,
+    ) -> impl Stream<Item = SystemEvent> + Send + 'static {
+        let engine = self.engine.clone();
+        futures::stream::unfold(self.event_rx.resubscribe(), move |mut rx| {
+            let filter = filter.clone();
+            let engine = engine.clone();
+            async move {
+                loop {
+                    let event = match rx.recv().await {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!(skipped, "Event subscriber lagged behind");
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    };
+                    let tenant = match event.tenant_id() {
+                        Some(tenant) => Some(tenant.to_string()),
+                        None if filter.filters_tenants() => match &engine {
+                            Some(engine) => node_tenant(engine, &event).await,
+                            None => None,
+                        },
+                        None => None,
+                    };
+                    if filter.matches(&event, tenant.as_deref()) {
+                        return Some((event, rx));
+                    }
+                }
+            }
+        })
+    }
+
+    /// The path a trace took, as seen by [`sync_events`](Self::sync_events).
+    pub fn execution_trail(&self, trace_id: &str) -> Option<&TraceTrail> {
+        self.trails.get(trace_id)
+    }
+
+    /// Trace IDs with a recorded trail, oldest first.
+    pub fn recent_traces(&self) -> Vec<String> {
+        self.trails.trace_ids().map(str::to_string).collect()
+    }
+
+    /// Overlays the trace's path on the canvas: traversed nodes and connections stay lit,
+    /// skipped branches are dimmed. Returns false if no trail was recorded for `trace_id`.
+    pub fn show_execution_trail(&self, graph: &mut GraphState<T>, trace_id: &str) -> bool {
+        match self.trails.get(trace_id) {
+            Some(trail) => {
+                graph.trail = Some(trail.to_canvas(trace_id, graph));
+                graph.mark_changed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Messages and bytes seen per connection of `graph`, for sizing wires by volume.
+    ///
+    /// Counts everything [`sync_events`](Self::sync_events) has observed since the client
+    /// started or [`reset_edge_traffic`](Self::reset_edge_traffic) was called.
+    pub fn edge_traffic(&self, graph: &GraphState<T>) -> HashMap<ConnectionId, EdgeVolume> {
+        self.traffic.to_canvas(graph)
+    }
+
+    /// Forgets the per-edge totals, e.g. before profiling a fresh run.
+    pub fn reset_edge_traffic(&mut self) {
+        self.traffic.clear();
+    }
+
+    /// Runs one tick of the backend engine. A remote engine ticks itself, so this does
+    /// nothing there.
     pub async fn tick(&mut self) -> Result<()> {
-        let mut engine = self.engine.lock().await;
-        engine.update();
+        if let Some(engine) = &self.engine {
+            engine.lock().await.update();
+        }
         Ok(())
     }
 
+    /// Runs exactly one tick of the embedded engine and reports the node executions and ticket
+    /// moves it produced. Events are still delivered to [`sync_events`](Self::sync_events) and
+    /// subscriptions.
+    pub async fn step(&self) -> Result<StepSummary> {
+        let engine = self.embedded()?;
+        let mut events = self.event_rx.resubscribe();
+        let work_done = {
+            let mut engine = engine.lock().await;
+            engine.update();
+            engine.world.resource::<WorkDone>().is_set()
+        };
+
+        let mut summary = StepSummary::new(work_done);
+        loop {
+            match events.try_recv() {
+                Ok(event) => summary.record(&event),
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Step summary missed events");
+                }
+                Err(_) => break,
+            }
+        }
+        tracing::debug!(
+            work_done,
+            executed = summary.executed.len(),
+            traversals = summary.traversals.len(),
+            "Engine stepped"
+        );
+        Ok(summary)
+    }
+
+    /// Ticks the embedded engine until no system reports progress for [`IDLE_TICKS`] ticks in a
+    /// row, yielding to the runtime in between so I/O can complete. Returns the number of ticks
+    /// run, or an error if the engine is still busy after `timeout`.
+    ///
+    /// A call still awaiting its response doesn't count as progress; use
+    /// [`trigger_and_wait`](Self::trigger_and_wait) to wait for a run that makes slow calls.
+    pub async fn run_until_idle(&self, timeout: std::time::Duration) -> Result<u64> {
+        let engine = self.embedded()?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut ticks = 0;
+        let mut idle = 0;
+        while idle < IDLE_TICKS {
+            if tokio::time::Instant::now() >= deadline {
+                anyhow::bail!("Engine still busy after {} ticks", ticks);
+            }
+            let work_done = {
+                let mut engine = engine.lock().await;
+                engine.update();
+                engine.world.resource::<WorkDone>().is_set()
+            };
+            ticks += 1;
+            idle = if work_done { 0 } else { idle + 1 };
+            tokio::task::yield_now().await;
+        }
+        Ok(ticks)
+    }
+
     /// Triggers a reload of all YAML node definitions.
     pub async fn reload_definitions(&self) -> Result<()> {
         self.api_tx
-            .send(ferroflux_core::api::ApiCommand::ReloadDefinitions)
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::ReloadDefinitions,
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Triggers a workflow and ticks the engine until the run reaches its terminal nodes,
+    /// returning their payloads. Fails if the workflow does not exist, a node emits on an
+    /// unconnected `error` port, or `timeout` elapses first.
+    pub async fn trigger_and_wait(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        payload: serde_json::Value,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<serde_json::Value>> {
+        let (reply, mut rx) = ferroflux_core::api::RunReply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::TriggerAndWait {
+                    tenant_id: tenant.clone(),
+                    workflow_id: workflow_id.to_string(),
+                    payload,
+                    timeout_ms: timeout.as_millis() as u64,
+                    reply,
+                },
+            ))
+            .await?;
+        loop {
+            if let Some(engine) = &self.engine {
+                engine.lock().await.update();
+            }
+            match rx.try_recv() {
+                Ok(outcome) => return outcome.map_err(anyhow::Error::from),
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                    return Err(anyhow::anyhow!("Run was dropped before completing"));
+                }
+            }
+        }
+    }
+
+    /// Dry-runs a workflow: HTTP, agent, connector and file nodes answer with `mocks` (keyed by
+    /// node id or node type) or pass their input through, while telemetry is emitted as usual.
+    /// The run's trace id arrives in `SystemEvent::ShadowRunStarted`.
+    pub async fn shadow_run(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        payload: serde_json::Value,
+        mocks: HashMap<String, ferroflux_core::components::shadow::MockConfig>,
+    ) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::ShadowRun {
+                    tenant_id: tenant.clone(),
+                    workflow_id: workflow_id.to_string(),
+                    payload,
+                    mock_config: mocks,
+                },
+            ))
             .await?;
         Ok(())
     }
 
+    /// Replays a run recorded with `capture.io_results`, feeding back its recorded results
+    /// instead of calling out. Call [`Self::tick`] to step through it; the replay's trace id
+    /// arrives in `SystemEvent::ReplayStarted`.
+    pub async fn replay_run(&self, tenant: &TenantId, trace_id: &str) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::ReplayRun {
+                    tenant_id: tenant.clone(),
+                    trace_id: trace_id.to_string(),
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Pins `output` as a deployed node's result: the node emits it instead of executing
+    /// until [`Self::unpin_node`]. Editors learn of the change through
+    /// `SystemEvent::NodePinChanged`.
+    pub async fn pin_node_output(
+        &self,
+        tenant: &TenantId,
+        node_uuid: uuid::Uuid,
+        output: serde_json::Value,
+    ) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::PinNodeOutput(tenant.clone(), node_uuid, output),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Lets a pinned node execute again.
+    pub async fn unpin_node(&self, tenant: &TenantId, node_uuid: uuid::Uuid) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::UnpinNode(tenant.clone(), node_uuid),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Saves `template` as the next version of a prompt library template. Agents that
+    /// reference it by name alone use the new version from their next ticket.
+    pub async fn save_prompt_template(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        template: &str,
+        variables: Vec<String>,
+    ) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::SavePromptTemplate {
+                    tenant_id: tenant.clone(),
+                    name: name.to_string(),
+                    template: template.to_string(),
+                    variables,
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes one version of a prompt template, or all of them when `version` is `None`.
+    pub async fn delete_prompt_template(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        version: Option<u32>,
+    ) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::DeletePromptTemplate {
+                    tenant_id: tenant.clone(),
+                    name: name.to_string(),
+                    version,
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Lists every version of the tenant's prompt templates, ordered by name then version.
+    pub async fn list_prompt_templates(&self, tenant: &TenantId) -> Result<Vec<PromptTemplate>> {
+        let engine = self.embedded()?.lock().await;
+        Ok(engine
+            .world
+            .get_resource::<PromptLibrary>()
+            .map(|library| library.list(tenant).into_iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Replaces the tenant's connection encryption key and re-encrypts its connections. The
+    /// new key version arrives in `SystemEvent::TenantKeyRotated`.
+    pub async fn rotate_tenant_key(&self, tenant: &TenantId) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::RotateTenantKey(tenant.clone()),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Tests a stored connection's credentials against its integration's `verify_endpoint`.
+    /// The connection's status becomes `active` or `error` accordingly.
+    pub async fn verify_connection(
+        &self,
+        tenant: &TenantId,
+        slug: &str,
+    ) -> Result<ferroflux_core::integrations::ConnectionVerification> {
+        let (reply, rx) = ferroflux_core::api::Reply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::VerifyConnection {
+                    tenant_id: tenant.clone(),
+                    slug: slug.to_string(),
+                    reply,
+                },
+            ))
+            .await?;
+        self.answer(rx, "VerifyConnection")
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Replaces the tenant's quotas. Work refused under them is reported as
+    /// `SystemEvent::QuotaExceeded`.
+    pub async fn set_tenant_limits(
+        &self,
+        tenant: &TenantId,
+        limits: ferroflux_core::store::database::TenantLimits,
+    ) -> Result<()> {
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::SetTenantLimits {
+                    tenant_id: tenant.clone(),
+                    limits,
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Lists the starter workflow templates, by category.
+    pub async fn list_templates(
+        &self,
+        tenant: &TenantId,
+    ) -> Result<ferroflux_core::templates::TemplatesByCategory> {
+        let (reply, rx) = ferroflux_core::api::Reply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::ListTemplates {
+                    tenant_id: tenant.clone(),
+                    reply,
+                },
+            ))
+            .await?;
+        self.answer(rx, "ListTemplates")
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Lists the engine's node types matching `query`, best match first, optionally within one
+    /// `category`. An empty query lists them all, ordered by category and name, so an editor
+    /// can build its "add node" palette from the engine.
+    pub async fn list_node_types(
+        &self,
+        tenant: &TenantId,
+        query: &str,
+        category: Option<&str>,
+    ) -> Result<Vec<ferroflux_core::traits::node_factory::NodeMetadata>> {
+        let (reply, rx) = ferroflux_core::api::Reply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::ListNodeTypes {
+                    tenant_id: tenant.clone(),
+                    query: query.to_string(),
+                    category: category.map(str::to_string),
+                    reply,
+                },
+            ))
+            .await?;
+        self.answer(rx, "ListNodeTypes")
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Instantiates a starter template as a new workflow of the tenant. `connections` maps the
+    /// template's connection placeholders to the tenant's connection slugs. Returns the new
+    /// workflow id.
+    pub async fn instantiate_template(
+        &self,
+        tenant: &TenantId,
+        template_id: &str,
+        workflow_id: Option<String>,
+        connections: HashMap<String, String>,
+    ) -> Result<String> {
+        let (reply, rx) = ferroflux_core::api::Reply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::InstantiateTemplate {
+                    tenant_id: tenant.clone(),
+                    template_id: template_id.to_string(),
+                    workflow_id,
+                    connections,
+                    reply,
+                },
+            ))
+            .await?;
+        self.answer(rx, "InstantiateTemplate")
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Installs the WASM plugin module checked into the blob store under `ticket` as a node
+    /// type of the tenant, returning its metadata. Only the tenant's workflows can use it.
+    pub async fn install_plugin(
+        &self,
+        tenant: &TenantId,
+        ticket: uuid::Uuid,
+    ) -> Result<ferroflux_core::traits::node_factory::NodeMetadata> {
+        let (reply, rx) = ferroflux_core::api::Reply::channel();
+        self.api_tx
+            .send(ferroflux_core::api::ApiRequest::system(
+                ferroflux_core::api::ApiCommand::InstallPlugin {
+                    tenant_id: tenant.clone(),
+                    ticket,
+                    reply,
+                },
+            ))
+            .await?;
+        self.answer(rx, "InstallPlugin")
+            .await?
+            .map_err(anyhow::Error::msg)
+    }
+
     /// Fetches all available node templates from the engine registry.
     pub async fn get_node_templates(
         &self,
     ) -> Result<Vec<ferroflux_core::traits::node_factory::NodeMetadata>> {
-        let engine = self.engine.lock().await;
+        let engine = self.embedded()?.lock().await;
         // Access NodeRegistry
         let mut templates = Vec::new();
 
@@ -233,6 +941,8 @@ impl<T: NodeData> FerroFluxClient<T> {
                         inputs,
                         outputs,
                         settings: vec![], // For now
+                        version: None,
+                        deprecated: None,
                     });
                 }
             }
@@ -240,4 +950,338 @@ impl<T: NodeData> FerroFluxClient<T> {
 
         Ok(templates)
     }
+
+    /// Fetches the node catalog with the user's "Recently used" and "Favorites" sections.
+    pub async fn get_node_catalog(&self, tenant: &TenantId, user_id: &str) -> Result<NodeCatalog> {
+        let templates = self.get_node_templates().await?;
+        let usage = self
+            .persistent_store()
+            .await?
+            .list_node_usage(tenant, user_id)
+            .await?
+            .into_iter()
+            .map(|(node_type, use_count, last_used_at, favorite)| NodeUsage {
+                node_type,
+                use_count,
+                last_used_at,
+                favorite,
+            })
+            .collect();
+
+        Ok(NodeCatalog::build(templates, usage))
+    }
+
+    /// Lists the tenant's scheduled (Cron) runs in `[from, to)` for a schedule calendar.
+    pub async fn get_schedule(
+        &self,
+        tenant: &TenantId,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ScheduledRun>> {
+        let mut engine = self.embedded()?.lock().await;
+        Ok(upcoming_runs(&mut engine.world, tenant, from, to))
+    }
+
+    /// Records that the user placed a node from the palette.
+    pub async fn record_node_usage(
+        &self,
+        tenant: &TenantId,
+        user_id: &str,
+        template_id: &str,
+    ) -> Result<()> {
+        self.persistent_store()
+            .await?
+            .record_node_usage(tenant, user_id, template_id)
+            .await
+    }
+
+    /// Adds or removes a node template from the user's favorites.
+    pub async fn set_node_favorite(
+        &self,
+        tenant: &TenantId,
+        user_id: &str,
+        template_id: &str,
+        favorite: bool,
+    ) -> Result<()> {
+        self.persistent_store()
+            .await?
+            .set_node_favorite(tenant, user_id, template_id, favorite)
+            .await
+    }
+
+    /// Saved versions of a workflow, newest first.
+    pub async fn list_workflow_versions(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+    ) -> Result<Vec<WorkflowVersion>> {
+        self.persistent_store()
+            .await?
+            .list_versions(tenant, workflow_id)
+            .await
+    }
+
+    /// Restores an earlier version of a workflow, and redeploys it unless the workflow is
+    /// inactive. Returns the snapshot of the restore.
+    pub async fn rollback_workflow(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        version: u32,
+    ) -> Result<WorkflowVersion> {
+        let store = self.persistent_store().await?;
+        let restored = store
+            .rollback_to_version(tenant, workflow_id, version)
+            .await?;
+        let active = store
+            .get_workflow(tenant, workflow_id)
+            .await?
+            .is_some_and(|(.., status)| status == "active");
+        if active {
+            self.api_tx
+                .send(ferroflux_core::api::ApiRequest::system(
+                    ferroflux_core::api::ApiCommand::LoadGraph(
+                        tenant.clone(),
+                        restored.blueprint_json.clone(),
+                    ),
+                ))
+                .await?;
+        }
+        Ok(restored)
+    }
+
+    async fn persistent_store(&self) -> Result<PersistentStore> {
+        let engine = self.embedded()?.lock().await;
+        engine
+            .world
+            .get_resource::<PersistentStore>()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("PersistentStore resource not found"))
+    }
+}
+
+impl<T: NodeData + Serialize + DeserializeOwned> FerroFluxClient<T> {
+    /// Exports the selected nodes (and the connections between them) as a named snippet.
+    /// Returns the snippet ID.
+    pub async fn save_snippet(
+        &self,
+        tenant: &TenantId,
+        name: &str,
+        graph: &GraphState<T>,
+        selection: &[NodeId],
+    ) -> Result<String> {
+        let snippet = serde_json::to_string(&graph.save_selection(selection))?;
+        self.persistent_store()
+            .await?
+            .save_snippet(tenant, name, &snippet)
+            .await
+    }
+
+    /// Instantiates a stored snippet into `graph` with its top-left corner at `position`.
+    /// Returns the IDs of the new nodes.
+    pub async fn insert_snippet(
+        &self,
+        tenant: &TenantId,
+        snippet_id: &str,
+        graph: &mut GraphState<T>,
+        position: glam::Vec2,
+    ) -> Result<Vec<NodeId>> {
+        let json = self
+            .persistent_store()
+            .await?
+            .get_snippet(tenant, snippet_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Snippet '{}' not found", snippet_id))?;
+        let snippet: SavedGraph<T> = serde_json::from_str(&json)?;
+        Ok(graph.instantiate(snippet, position))
+    }
+
+    /// Packages a loaded workflow as a portable bundle, with `layout` as its canvas layout.
+    pub async fn export_workflow(
+        &self,
+        tenant: &TenantId,
+        workflow_id: &str,
+        layout: Option<&GraphState<T>>,
+    ) -> Result<WorkflowBundle> {
+        let mut engine = self.embedded()?.lock().await;
+        let mut bundle = graph_loader::export_workflow(&mut engine.world, tenant, workflow_id)?;
+        bundle.layout = layout
+            .map(|graph| serde_json::to_value(graph.save()))
+            .transpose()?;
+        Ok(bundle)
+    }
+
+    /// Loads a bundle into `tenant` and returns the bundle's layout, if any, with its nodes
+    /// pointing at the imported node UUIDs, ready for `GraphState::load`.
+    pub async fn import_workflow(
+        &self,
+        tenant: &TenantId,
+        bundle: WorkflowBundle,
+        options: &ImportOptions,
+    ) -> Result<(ImportedWorkflow, Option<SavedGraph<T>>)> {
+        let mut layout: Option<SavedGraph<T>> = bundle
+            .layout
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()?;
+
+        let mut engine = self.embedded()?.lock().await;
+        let imported =
+            graph_loader::import_workflow(&mut engine.world, tenant.clone(), bundle, options)?;
+
+        for node in layout.iter_mut().flat_map(|layout| layout.nodes.iter_mut()) {
+            if let Some(id) = imported.node_ids.get(&node.uuid) {
+                node.uuid = *id;
+            }
+        }
+        Ok((imported, layout))
+    }
+}
+
+/// The tenant owning the (first) node an event is about.
+async fn node_tenant(engine: &Mutex<App>, event: &SystemEvent) -> Option<String> {
+    let node_id = *event.node_ids().first()?;
+    let engine = engine.lock().await;
+    let world = &engine.world;
+    let entity = *world
+        .get_resource::<ferroflux_core::resources::NodeRouter>()?
+        .0
+        .get(&node_id)?;
+    let tenant = world.get::<NodeConfig>(entity)?.tenant_id.as_ref()?;
+    Some(tenant.as_ref().to_string())
+}
+
+/// Lowers the canvas into the analysis model. Output ports are named after the
+/// template's declared outputs (in port order), falling back to their index.
+fn analyze_canvas<T: NodeData>(world: &World, graph: &GraphState<T>) -> Vec<CanvasWarning> {
+    let registry = world.get_resource::<ferroflux_core::resources::registry::NodeRegistry>();
+
+    let mut port_names = HashMap::new();
+    let mut nodes = Vec::new();
+    for (_, node) in &graph.nodes {
+        let node_type = node.data.node_type();
+        if ferroflux_core::nodes::is_annotation_type(&node_type) {
+            continue;
+        }
+        let metadata = registry
+            .and_then(|r| r.get(&node_type))
+            .map(|f| f.metadata());
+
+        let outputs: Vec<String> = node
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, port_id)| {
+                let name = metadata
+                    .as_ref()
+                    .and_then(|m| m.outputs.get(i))
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| i.to_string());
+                port_names.insert(*port_id, name.clone());
+                name
+            })
+            .collect();
+
+        nodes.push(AnalysisNode {
+            id: node.uuid,
+            name: metadata
+                .as_ref()
+                .map(|m| m.name.clone())
+                .unwrap_or(node_type.clone()),
+            is_trigger: ferroflux_core::graph_analysis::is_trigger_type(
+                &node_type,
+                metadata.as_ref(),
+            ),
+            outputs,
+        });
+    }
+
+    let edges: Vec<AnalysisEdge> = graph
+        .connections
+        .values()
+        .filter_map(|conn| {
+            let source = graph.nodes.get(graph.ports.get(conn.from)?.node)?;
+            let target = graph.nodes.get(graph.ports.get(conn.to)?.node)?;
+            Some(AnalysisEdge {
+                source: source.uuid,
+                source_handle: port_names.get(&conn.from).cloned(),
+                target: target.uuid,
+            })
+        })
+        .collect();
+
+    let uuid_to_node: HashMap<_, _> = graph.nodes.iter().map(|(id, n)| (n.uuid, id)).collect();
+    ferroflux_core::graph_analysis::analyze(&nodes, &edges)
+        .into_iter()
+        .filter_map(|warning| {
+            Some(CanvasWarning {
+                node: *uuid_to_node.get(&warning.node_id)?,
+                warning,
+            })
+        })
+        .collect()
+}
+
+fn validate_canvas<T: NodeData>(world: &World, graph: &GraphState<T>) -> Vec<CanvasDiagnostic> {
+    let registry = world.get_resource::<ferroflux_core::resources::registry::NodeRegistry>();
+    let validator = GraphValidator::new(registry);
+
+    // Canvas ports are unnamed; they line up with the declared ports by position.
+    let mut port_names = HashMap::new();
+    let mut nodes = Vec::new();
+    for (_, node) in &graph.nodes {
+        let node_type = node.data.node_type();
+        if ferroflux_core::nodes::is_annotation_type(&node_type) {
+            continue;
+        }
+        let described = validator.node(node.uuid, &node_type, &node_type);
+        for (ports, declared) in [
+            (&node.inputs, &described.inputs),
+            (&node.outputs, &described.outputs),
+        ] {
+            for (i, port_id) in ports.iter().enumerate() {
+                let name = declared
+                    .as_ref()
+                    .and_then(|declared| declared.get(i))
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| i.to_string());
+                port_names.insert(*port_id, name);
+            }
+        }
+        nodes.push(described);
+    }
+
+    // A connection to a port or node that no longer exists gets a nil end, which the
+    // validator reports as dangling.
+    let node_uuid = |port| {
+        graph
+            .ports
+            .get(port)
+            .and_then(|p| graph.nodes.get(p.node))
+            .map_or(uuid::Uuid::nil(), |n| n.uuid)
+    };
+    let edges: Vec<ValidationEdge> = graph
+        .connections
+        .values()
+        .map(|conn| ValidationEdge {
+            source: node_uuid(conn.from),
+            source_port: port_names.get(&conn.from).cloned(),
+            target: node_uuid(conn.to),
+            target_port: port_names.get(&conn.to).cloned(),
+        })
+        .collect();
+
+    let uuid_to_node: HashMap<_, _> = graph.nodes.iter().map(|(id, n)| (n.uuid, id)).collect();
+    validator
+        .validate(&nodes, &edges)
+        .into_iter()
+        .map(|diagnostic| CanvasDiagnostic {
+            nodes: diagnostic
+                .node_ids
+                .iter()
+                .filter_map(|id| uuid_to_node.get(id).copied())
+                .collect(),
+            diagnostic,
+        })
+        .collect()
 }
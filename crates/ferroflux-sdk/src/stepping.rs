@@ -0,0 +1,96 @@
+//! Tick-level execution control.
+//!
+//! Tests and the editor's step debugger drive an embedded engine through
+//! [`FerroFluxClient::step`](crate::FerroFluxClient::step), which runs one tick and reports the
+//! node executions and ticket moves it produced, and
+//! [`FerroFluxClient::run_until_idle`](crate::FerroFluxClient::run_until_idle).
+
+use ferroflux_core::api::events::SystemEvent;
+use uuid::Uuid;
+
+/// Consecutive ticks without progress after which `run_until_idle` considers the engine idle.
+pub const IDLE_TICKS: u32 = 3;
+
+/// One node execution reported during a tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStep {
+    pub node_id: Uuid,
+    pub trace_id: String,
+    /// The node type, when the node reported telemetry (e.g. "Http").
+    pub node_type: Option<String>,
+    pub success: bool,
+    pub execution_ms: u64,
+    /// The error the node reported, if any.
+    pub error: Option<String>,
+}
+
+/// What a single engine tick did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepSummary {
+    /// Whether any system reported progress.
+    pub work_done: bool,
+    /// Node executions, in the order they were reported.
+    pub executed: Vec<NodeStep>,
+    /// `(source, target)` node pairs a ticket moved along.
+    pub traversals: Vec<(Uuid, Uuid)>,
+}
+
+impl StepSummary {
+    pub(crate) fn new(work_done: bool) -> Self {
+        Self {
+            work_done,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: &SystemEvent) {
+        match event {
+            SystemEvent::NodeTelemetry {
+                trace_id,
+                node_id,
+                node_type,
+                execution_ms,
+                success,
+                ..
+            } => self.executed.push(NodeStep {
+                node_id: *node_id,
+                trace_id: trace_id.clone(),
+                node_type: Some(node_type.clone()),
+                success: *success,
+                execution_ms: *execution_ms,
+                error: None,
+            }),
+            // A node usually reports telemetry alongside its error; attach it to that step.
+            SystemEvent::NodeError {
+                trace_id,
+                node_id,
+                error,
+                ..
+            } => match self
+                .executed
+                .iter_mut()
+                .rev()
+                .find(|step| step.node_id == *node_id && step.trace_id == *trace_id)
+            {
+                Some(step) => {
+                    step.success = false;
+                    step.error = Some(error.clone());
+                }
+                None => self.executed.push(NodeStep {
+                    node_id: *node_id,
+                    trace_id: trace_id.clone(),
+                    node_type: None,
+                    success: false,
+                    execution_ms: 0,
+                    error: Some(error.clone()),
+                }),
+            },
+            SystemEvent::EdgeTraversal {
+                source_id,
+                target_id,
+                ..
+            } => self.traversals.push((*source_id, *target_id)),
+            _ => {}
+        }
+    }
+}
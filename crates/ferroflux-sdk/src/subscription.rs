@@ -0,0 +1,64 @@
+//! Filters for live event subscriptions.
+//!
+//! Hosts (the desktop shell, the playground) forward engine telemetry to their UIs through
+//! [`FerroFluxClient::subscribe`](crate::FerroFluxClient::subscribe) instead of polling
+//! [`sync_events`](crate::FerroFluxClient::sync_events), which only feeds the canvas overlays.
+
+use ferroflux_core::api::events::SystemEvent;
+use ferroflux_iam::TenantId;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Selects the events a subscription yields. A criterion with no values matches every event;
+/// an event must meet all the others, and any one value of each.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    kinds: HashSet<String>,
+    nodes: HashSet<Uuid>,
+    traces: HashSet<String>,
+    tenants: HashSet<String>,
+}
+
+impl EventFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events of a kind as named by `SystemEvent::kind`, e.g. `"NodeTelemetry"`.
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kinds.insert(kind.into());
+        self
+    }
+
+    /// Events about a node. An `EdgeTraversal` matches on either end.
+    pub fn node(mut self, node_id: Uuid) -> Self {
+        self.nodes.insert(node_id);
+        self
+    }
+
+    pub fn trace(mut self, trace_id: impl Into<String>) -> Self {
+        self.traces.insert(trace_id.into());
+        self
+    }
+
+    /// Events of a tenant. Node events count for the tenant owning the node; events tied to
+    /// no tenant, such as `EngineStats`, never match.
+    pub fn tenant(mut self, tenant: &TenantId) -> Self {
+        self.tenants.insert(tenant.as_ref().to_string());
+        self
+    }
+
+    /// Whether matching needs the tenant of events that don't name one.
+    pub(crate) fn filters_tenants(&self) -> bool {
+        !self.tenants.is_empty()
+    }
+
+    /// Whether the event passes. `tenant` is the event's tenant, when known.
+    pub fn matches(&self, event: &SystemEvent, tenant: Option<&str>) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(event.kind()))
+            && (self.nodes.is_empty() || event.node_ids().iter().any(|id| self.nodes.contains(id)))
+            && (self.traces.is_empty()
+                || event.trace_id().is_some_and(|id| self.traces.contains(id)))
+            && (self.tenants.is_empty() || tenant.is_some_and(|t| self.tenants.contains(t)))
+    }
+}
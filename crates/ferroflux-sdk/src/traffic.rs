@@ -0,0 +1,60 @@
+//! Per-edge traffic totals.
+//!
+//! `EdgeTraversal` events carry the payload size of every delivery; the client sums them per
+//! `(source, target)` pair so a canvas can draw busy wires thicker than idle ones.
+
+use flow_canvas::model::{ConnectionId, GraphState, NodeData};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Messages and payload bytes that crossed one edge since the client started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EdgeVolume {
+    pub messages: u64,
+    pub bytes: u64,
+}
+
+impl EdgeVolume {
+    /// Average payload size, 0 when nothing crossed yet.
+    pub fn avg_bytes(&self) -> u64 {
+        self.bytes.checked_div(self.messages).unwrap_or(0)
+    }
+}
+
+/// Running totals keyed by engine node UUIDs.
+#[derive(Debug, Default)]
+pub(crate) struct TrafficRecorder {
+    edges: HashMap<(Uuid, Uuid), EdgeVolume>,
+}
+
+impl TrafficRecorder {
+    pub(crate) fn record(&mut self, source: Uuid, target: Uuid, bytes: u64) {
+        let volume = self.edges.entry((source, target)).or_default();
+        volume.messages += 1;
+        volume.bytes += bytes;
+    }
+
+    /// Totals for the connections of `graph`; connections nothing crossed are left out.
+    pub(crate) fn to_canvas<T: NodeData>(
+        &self,
+        graph: &GraphState<T>,
+    ) -> HashMap<ConnectionId, EdgeVolume> {
+        let endpoint = |port| {
+            let port = graph.ports.get(port)?;
+            graph.nodes.get(port.node).map(|n| n.uuid)
+        };
+        graph
+            .connections
+            .iter()
+            .filter_map(|(id, conn)| {
+                let key = (endpoint(conn.from)?, endpoint(conn.to)?);
+                self.edges.get(&key).map(|volume| (id, *volume))
+            })
+            .collect()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.edges.clear();
+    }
+}
@@ -0,0 +1,111 @@
+//! Per-trace execution trails.
+//!
+//! The client folds `NodeTelemetry`, `NodeError` and `EdgeTraversal` events into a
+//! [`TraceTrail`] per `trace_id`, so the path a finished run took can be laid over the
+//! canvas afterwards as a [`flow_canvas::trail::ExecutionTrail`].
+
+use flow_canvas::model::{GraphState, NodeData};
+use flow_canvas::trail::ExecutionTrail;
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Number of traces kept before the oldest is forgotten.
+const DEFAULT_CAPACITY: usize = 100;
+
+/// The nodes and edges one trace passed through, by engine node UUID.
+#[derive(Debug, Clone, Default)]
+pub struct TraceTrail {
+    /// Nodes that received or processed the trace's tickets.
+    pub nodes: HashSet<Uuid>,
+    /// Nodes that reported a failure.
+    pub failed: HashSet<Uuid>,
+    /// `(source, target)` pairs a ticket moved along.
+    pub edges: HashSet<(Uuid, Uuid)>,
+}
+
+impl TraceTrail {
+    /// Maps the trail onto `graph`; nodes and connections that aren't on the canvas are dropped.
+    pub fn to_canvas<T: NodeData>(&self, trace_id: &str, graph: &GraphState<T>) -> ExecutionTrail {
+        let mut trail = ExecutionTrail::new(trace_id);
+        for (id, node) in &graph.nodes {
+            if self.nodes.contains(&node.uuid) {
+                trail.nodes.insert(id);
+            }
+            if self.failed.contains(&node.uuid) {
+                trail.failed.insert(id);
+            }
+        }
+        for (id, conn) in &graph.connections {
+            let endpoint = |port| {
+                let port = graph.ports.get(port)?;
+                graph.nodes.get(port.node).map(|n| n.uuid)
+            };
+            if let (Some(source), Some(target)) = (endpoint(conn.from), endpoint(conn.to))
+                && self.edges.contains(&(source, target))
+            {
+                trail.connections.insert(id);
+            }
+        }
+        trail
+    }
+}
+
+/// The most recent trails, oldest first.
+#[derive(Debug)]
+pub(crate) struct TrailRecorder {
+    trails: HashMap<String, TraceTrail>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Default for TrailRecorder {
+    fn default() -> Self {
+        Self {
+            trails: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+}
+
+impl TrailRecorder {
+    fn entry(&mut self, trace_id: &str) -> Option<&mut TraceTrail> {
+        if trace_id.is_empty() {
+            return None;
+        }
+        if !self.trails.contains_key(trace_id) {
+            if self.order.len() >= self.capacity
+                && let Some(oldest) = self.order.pop_front()
+            {
+                self.trails.remove(&oldest);
+            }
+            self.order.push_back(trace_id.to_string());
+        }
+        Some(self.trails.entry(trace_id.to_string()).or_default())
+    }
+
+    pub(crate) fn record_node(&mut self, trace_id: &str, node: Uuid, success: bool) {
+        if let Some(trail) = self.entry(trace_id) {
+            trail.nodes.insert(node);
+            if !success {
+                trail.failed.insert(node);
+            }
+        }
+    }
+
+    pub(crate) fn record_edge(&mut self, trace_id: &str, source: Uuid, target: Uuid) {
+        if let Some(trail) = self.entry(trace_id) {
+            trail.nodes.insert(source);
+            trail.nodes.insert(target);
+            trail.edges.insert((source, target));
+        }
+    }
+
+    pub(crate) fn get(&self, trace_id: &str) -> Option<&TraceTrail> {
+        self.trails.get(trace_id)
+    }
+
+    pub(crate) fn trace_ids(&self) -> impl Iterator<Item = &str> {
+        self.order.iter().map(String::as_str)
+    }
+}
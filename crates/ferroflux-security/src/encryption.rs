@@ -54,6 +54,13 @@ pub fn decrypt(ciphertext: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
     Ok(plaintext)
 }
 
+/// A random 32-byte key for [`encrypt`].
+pub fn generate_key() -> Vec<u8> {
+    let mut key = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
 /// Retrieves the master key.
 ///
 /// Priority:
@@ -91,13 +98,12 @@ pub fn get_or_create_master_key() -> Result<Vec<u8>> {
 
     // 3. Auto-generate
     tracing::info!("Generating new master key -> 'ferroflux.key'");
-    let mut key = [0u8; 32];
-    rand::thread_rng().fill_bytes(&mut key);
-    let hex_key = hex::encode(key);
+    let key = generate_key();
+    let hex_key = hex::encode(&key);
 
     fs::write(key_path, hex_key).context("Failed to write ferroflux.key")?;
 
-    Ok(key.to_vec())
+    Ok(key)
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
 pub mod api_key;
 pub mod encryption;
 pub mod network;
+pub mod sandbox;
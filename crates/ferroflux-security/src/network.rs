@@ -38,7 +38,8 @@ pub fn validate_host_port(host: &str, port: u16) -> Result<(), String> {
     Ok(())
 }
 
-fn is_blocked_ip(ip: IpAddr) -> bool {
+/// Returns true for loopback, private, link-local and other non-routable addresses.
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
     // Block Loopback (127.0.0.0/8)
     if ip.is_loopback() {
         return true;
@@ -0,0 +1,134 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Root used when `FERROFLUX_FILE_ROOT` is not set.
+pub const DEFAULT_SANDBOX_ROOT: &str = "data/files";
+
+/// Returns the directory under which all workflow file access happens.
+pub fn sandbox_root() -> PathBuf {
+    std::env::var("FERROFLUX_FILE_ROOT")
+        .ok()
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SANDBOX_ROOT))
+}
+
+/// Returns the sandbox directory of a single tenant.
+///
+/// Tenant IDs become a single directory name, so anything that could act as a path
+/// separator or traversal is rejected.
+pub fn tenant_root(root: &Path, tenant_id: &str) -> Result<PathBuf, String> {
+    let valid = !tenant_id.is_empty()
+        && tenant_id != "."
+        && tenant_id != ".."
+        && tenant_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "Invalid tenant id for file sandbox: '{}'",
+            tenant_id
+        ));
+    }
+    Ok(root.join(tenant_id))
+}
+
+/// Resolves `relative` inside `root`, refusing anything that would end up outside of it.
+///
+/// Absolute paths and `..` segments that climb above `root` are rejected up front. Symlinks
+/// that already exist inside `root` are followed and must also stay within it, so a link
+/// can't be used to reach the rest of the filesystem.
+pub fn resolve_path(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let mut parts: Vec<&std::ffi::OsStr> = Vec::new();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(part) => parts.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if parts.pop().is_none() {
+                    return Err(format!("Path '{}' escapes the sandbox", relative));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!("Absolute path '{}' is not allowed", relative));
+            }
+        }
+    }
+    if parts.is_empty() {
+        return Err("File path is empty".to_string());
+    }
+
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| format!("Sandbox root {:?} is not accessible: {}", root, e))?;
+    let mut path = root.to_path_buf();
+    for part in parts {
+        path.push(part);
+        let is_symlink = std::fs::symlink_metadata(&path)
+            .map(|meta| meta.file_type().is_symlink())
+            .unwrap_or(false);
+        if is_symlink {
+            let target = path
+                .canonicalize()
+                .map_err(|_| format!("Path '{}' contains a dangling symlink", relative))?;
+            if !target.starts_with(&canonical_root) {
+                return Err(format!("Path '{}' escapes the sandbox", relative));
+            }
+        }
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("sandbox_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let root = temp_root();
+        assert_eq!(
+            resolve_path(&root, "reports/./2024/out.json").unwrap(),
+            root.join("reports/2024/out.json")
+        );
+        assert_eq!(
+            resolve_path(&root, "a/../b.txt").unwrap(),
+            root.join("b.txt")
+        );
+
+        assert!(resolve_path(&root, "../secrets.txt").is_err());
+        assert!(resolve_path(&root, "a/../../secrets.txt").is_err());
+        assert!(resolve_path(&root, "/etc/passwd").is_err());
+        assert!(resolve_path(&root, "").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_path_rejects_escaping_symlinks() {
+        let root = temp_root();
+        std::os::unix::fs::symlink("/etc", root.join("etc")).unwrap();
+        std::fs::create_dir_all(root.join("real")).unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("alias")).unwrap();
+
+        assert!(resolve_path(&root, "etc/passwd").is_err());
+        assert!(resolve_path(&root, "alias/file.txt").is_ok());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_tenant_root() {
+        let root = Path::new("/data");
+        assert_eq!(
+            tenant_root(root, "default_tenant").unwrap(),
+            root.join("default_tenant")
+        );
+        assert!(tenant_root(root, "..").is_err());
+        assert!(tenant_root(root, "a/b").is_err());
+        assert!(tenant_root(root, "").is_err());
+    }
+}